@@ -0,0 +1,278 @@
+use crate::report::Summary;
+use crate::series::Series;
+use anyhow::{anyhow, bail, Result};
+use defi_tools::fees::accrue;
+use defi_tools::planner::{max_deviation_bps, target_values, RebalancePlan, BPS_DENOMINATOR};
+use defi_tools::validation::validate_strategy_params;
+use defi_tools::{Allocation, FeeConfig, RebalanceCondition};
+use serde::Serialize;
+
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+// 回测的策略参数，与 create_strategy 的参数一致
+#[derive(Clone)]
+pub struct Params {
+    pub allocations: Vec<Allocation>,
+    pub rebalance_condition: RebalanceCondition,
+    pub max_slippage_bps: u16,
+    // 每笔调整的交易手续费(基点)
+    pub fee_bps: u16,
+    // 管理费和业绩费费率，与链上全局配置一致
+    pub fees: FeeConfig,
+    pub initial_value: u64,
+}
+
+impl Params {
+    // 与链上 create_strategy 相同的参数校验
+    pub fn validate(&self) -> Result<()> {
+        validate_strategy_params(&self.allocations, self.max_slippage_bps)
+            .map_err(|e| anyhow!("策略参数无效: {e}"))?;
+        self.fees.validate().map_err(|e| anyhow!("费率无效: {e}"))?;
+        if self.initial_value == 0 {
+            bail!("初始价值必须大于0");
+        }
+        Ok(())
+    }
+}
+
+// 每个时刻结束时的状态
+#[derive(Clone, Debug, Serialize)]
+pub struct Step {
+    pub timestamp: i64,
+    pub total_value: u64,
+    // 再平衡前的最大权重偏差(基点)
+    pub deviation_bps: u16,
+    pub rebalanced: bool,
+    // 本时刻再平衡的成交额
+    pub turnover: u64,
+    // 本时刻支付的手续费和滑点
+    pub costs: u64,
+    // 本时刻计提的管理费和业绩费
+    pub fees: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Backtest {
+    pub summary: Summary,
+    pub steps: Vec<Step>,
+}
+
+// 在历史行情上回放策略。第一个时刻按目标权重建仓，之后每个时刻先按价格变化和
+// 上一区间的年化收益率更新各头寸价值，再用链上相同的条件判断是否再平衡。
+// 再平衡成交额按 (手续费 + 最大滑点) 计入成本，即按最坏情况估计滑点。
+// 管理费与链上一样在每次再平衡时计提；业绩费按高水位计提，链上没有对应的收益来源，只在回测中估算
+pub fn run(params: &Params, series: &Series) -> Result<Backtest> {
+    params.validate()?;
+    let allocations = &params.allocations;
+    let cost_bps = params.fee_bps as u64 + params.max_slippage_bps as u64;
+
+    let mut values = target_values(params.initial_value, allocations)?;
+    let mut last_executed_at = series.timestamps[0];
+    let mut high_water_mark = params.initial_value;
+    let mut fees_accrued_at = series.timestamps[0];
+    let mut steps = vec![Step {
+        timestamp: last_executed_at,
+        total_value: values.iter().sum(),
+        deviation_bps: 0,
+        rebalanced: false,
+        turnover: 0,
+        costs: 0,
+        fees: 0,
+    }];
+
+    for i in 1..series.timestamps.len() {
+        let now = series.timestamps[i];
+        let elapsed = (now - series.timestamps[i - 1]) as f64;
+        for (j, value) in values.iter_mut().enumerate() {
+            let previous = series.quotes[i - 1][j];
+            let current = series.quotes[i][j];
+            let growth = current.price / previous.price
+                * (1.0
+                    + previous.apy_bps as f64 / BPS_DENOMINATOR as f64 * elapsed
+                        / SECONDS_PER_YEAR);
+            *value = (*value as f64 * growth).round() as u64;
+        }
+
+        let deviation_bps = max_deviation_bps(allocations, &values);
+        let mut step = Step {
+            timestamp: now,
+            total_value: values.iter().sum(),
+            deviation_bps,
+            rebalanced: false,
+            turnover: 0,
+            costs: 0,
+            fees: 0,
+        };
+        if params
+            .rebalance_condition
+            .is_due(last_executed_at, now, deviation_bps)
+        {
+            let accrual = accrue(
+                &params.fees,
+                step.total_value,
+                high_water_mark,
+                fees_accrued_at,
+                now,
+            )
+            .map_err(|e| anyhow!("计提费用失败: {e}"))?;
+            high_water_mark = accrual.high_water_mark;
+            fees_accrued_at = accrual.accrued_at;
+
+            let plan = RebalancePlan::new(allocations, &values)?;
+            let turnover = plan.turnover();
+            let costs = (turnover as u128 * cost_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+            values = target_values(accrual.value.saturating_sub(costs), allocations)?;
+            last_executed_at = now;
+            step = Step {
+                total_value: values.iter().sum(),
+                rebalanced: true,
+                turnover,
+                costs,
+                fees: accrual.total(),
+                ..step
+            };
+        }
+        steps.push(step);
+    }
+
+    Ok(Backtest {
+        summary: Summary::from_steps(params.initial_value, &steps),
+        steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::series::Quote;
+
+    fn params(interval: u64, max_deviation_bps: u16) -> Params {
+        Params {
+            allocations: vec![
+                Allocation {
+                    protocol: 0,
+                    asset: 0,
+                    target_weight_bps: 5000,
+                },
+                Allocation {
+                    protocol: 1,
+                    asset: 1,
+                    target_weight_bps: 5000,
+                },
+            ],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: interval,
+                max_deviation_bps,
+                auto_rebalance: true,
+            },
+            max_slippage_bps: 50,
+            fee_bps: 50,
+            fees: FeeConfig::default(),
+            initial_value: 1_000_000,
+        }
+    }
+
+    // 第一个资产价格依次为 prices，第二个资产价格不变，均无收益率
+    fn series(prices: &[f64]) -> Series {
+        Series {
+            timestamps: (0..prices.len() as i64).map(|i| i * 3600).collect(),
+            quotes: prices
+                .iter()
+                .map(|price| {
+                    vec![
+                        Quote {
+                            price: *price,
+                            apy_bps: 0,
+                        },
+                        Quote {
+                            price: 1.0,
+                            apy_bps: 0,
+                        },
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    // 测试1: 按时间间隔再平衡并扣除成本
+    #[test]
+    fn test_periodic_rebalance() {
+        let result = run(&params(3600, 0), &series(&[1.0, 2.0, 2.0])).unwrap();
+        let step = &result.steps[1];
+        // 500000 -> 1000000，总价值 1500000，调整成交额 250000，成本 1%
+        assert!(step.rebalanced);
+        assert_eq!(step.deviation_bps, 1667);
+        assert_eq!(step.turnover, 250_000);
+        assert_eq!(step.costs, 2_500);
+        assert_eq!(step.total_value, 1_497_500);
+        // 价格不变，已在目标权重，仍按间隔再平衡但没有成交
+        assert!(result.steps[2].rebalanced);
+        assert_eq!(result.steps[2].turnover, 0);
+        assert_eq!(result.summary.rebalances, 2);
+    }
+
+    // 测试2: 偏差未达到阈值时不再平衡
+    #[test]
+    fn test_drift_threshold() {
+        let result = run(&params(0, 1000), &series(&[1.0, 1.2, 2.0])).unwrap();
+        // 500000 / 1100000 = 4545基点，偏离455基点
+        assert!(!result.steps[1].rebalanced);
+        assert_eq!(result.steps[1].deviation_bps, 455);
+        assert!(result.steps[2].rebalanced);
+        assert_eq!(result.summary.rebalances, 1);
+    }
+
+    // 测试3: 年化收益率按持有时间累计
+    #[test]
+    fn test_apy_accrual() {
+        let mut s = series(&[1.0, 1.0]);
+        s.timestamps[1] = SECONDS_PER_YEAR as i64;
+        s.quotes[0][0].apy_bps = 1000;
+        let mut p = params(0, 0);
+        p.rebalance_condition.auto_rebalance = false;
+        let result = run(&p, &s).unwrap();
+        assert_eq!(result.steps[1].total_value, 1_050_000);
+        assert_eq!(result.summary.rebalances, 0);
+    }
+
+    // 测试4: 参数校验与链上一致
+    #[test]
+    fn test_invalid_params() {
+        let mut p = params(0, 0);
+        p.allocations[0].target_weight_bps = 4000;
+        assert!(run(&p, &series(&[1.0, 1.0])).is_err());
+
+        let mut p = params(0, 0);
+        p.max_slippage_bps = 1001;
+        assert!(run(&p, &series(&[1.0, 1.0])).is_err());
+
+        let mut p = params(0, 0);
+        p.fees.management_fee_bps = 501;
+        assert!(run(&p, &series(&[1.0, 1.0])).is_err());
+    }
+
+    // 测试5: 再平衡时计提管理费和超过高水位部分的业绩费
+    #[test]
+    fn test_fees() {
+        let mut s = series(&[1.0, 1.2, 1.0]);
+        let year = SECONDS_PER_YEAR as i64;
+        s.timestamps = vec![1, 1 + year, 1 + year * 2];
+        let mut p = params(1, 0);
+        p.fee_bps = 0;
+        p.max_slippage_bps = 0;
+        p.fees = FeeConfig {
+            management_fee_bps: 100,
+            performance_fee_bps: 1000,
+        };
+        let result = run(&p, &s).unwrap();
+
+        // 1_100_000 的 1% 管理费为 11_000，剩余 1_089_000 超过高水位 89_000，业绩费 8_900
+        let step = &result.steps[1];
+        assert_eq!(step.fees, 19_900);
+        assert_eq!(step.total_value, 1_080_100);
+        // 价格回落后低于高水位，只有管理费
+        let step = &result.steps[2];
+        assert!(step.fees > 0 && step.fees < 11_000);
+        assert_eq!(result.summary.fees, 19_900 + step.fees);
+    }
+}
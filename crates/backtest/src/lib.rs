@@ -0,0 +1,7 @@
+pub mod engine;
+pub mod report;
+pub mod series;
+
+pub use engine::{run, Backtest, Params, Step};
+pub use report::Summary;
+pub use series::{Quote, Series};
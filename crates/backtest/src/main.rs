@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use defi_tools::{Allocation, FeeConfig, RebalanceCondition};
+use defi_tools_backtest::{run, Params, Series};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "defi-tools-backtest",
+    about = "在历史价格和收益率数据上回测策略的分配和再平衡规则"
+)]
+struct Cli {
+    /// 行情 CSV，列为 timestamp,protocol,asset,price,apy_bps
+    #[arg(long)]
+    prices: PathBuf,
+
+    /// 分配项，格式为 协议ID:资产ID:权重基点，可重复指定
+    #[arg(long = "alloc", required = true, value_parser = parse_allocation)]
+    allocations: Vec<Allocation>,
+
+    /// 最小再平衡间隔(秒)
+    #[arg(long, default_value_t = 86400)]
+    interval: u64,
+
+    /// 触发再平衡的权重偏差(基点)，0 表示只按时间间隔
+    #[arg(long, default_value_t = 500)]
+    max_deviation_bps: u16,
+
+    /// 不自动再平衡，只持有初始仓位
+    #[arg(long)]
+    no_auto_rebalance: bool,
+
+    /// 最大滑点(基点)，按最坏情况计入再平衡成本
+    #[arg(long, default_value_t = 100)]
+    max_slippage_bps: u16,
+
+    /// 交易手续费(基点)
+    #[arg(long, default_value_t = 30)]
+    fee_bps: u16,
+
+    /// 年化管理费(基点)
+    #[arg(long, default_value_t = 0)]
+    management_fee_bps: u16,
+
+    /// 超过高水位收益的业绩费(基点)
+    #[arg(long, default_value_t = 0)]
+    performance_fee_bps: u16,
+
+    /// 初始价值(lamports)
+    #[arg(long, default_value_t = 1_000_000_000_000)]
+    initial_value: u64,
+
+    /// json 输出汇总和逐时刻结果，csv 只输出逐时刻结果
+    #[arg(long, value_enum, default_value = "json")]
+    format: Format,
+
+    /// 输出文件，默认写到标准输出
+    #[arg(long, short = 'o')]
+    output: Option<PathBuf>,
+}
+
+// 解析 `协议ID:资产ID:权重基点` 形式的分配项，与行情 CSV 中的编号一致
+fn parse_allocation(value: &str) -> Result<Allocation, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [protocol, asset, weight] = parts.as_slice() else {
+        return Err(format!(
+            "分配项格式应为 协议ID:资产ID:权重基点，实际为 {value}"
+        ));
+    };
+    let number = |field: &str| {
+        field
+            .parse::<u16>()
+            .map_err(|_| format!("无效的数字: {field}"))
+    };
+    let byte = |field: &str| {
+        field
+            .parse::<u8>()
+            .map_err(|_| format!("无效的ID: {field}"))
+    };
+    Ok(Allocation {
+        protocol: byte(protocol)?,
+        asset: byte(asset)?,
+        target_weight_bps: number(weight)?,
+    })
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let params = Params {
+        allocations: cli.allocations,
+        rebalance_condition: RebalanceCondition {
+            time_interval_seconds: cli.interval,
+            max_deviation_bps: cli.max_deviation_bps,
+            auto_rebalance: !cli.no_auto_rebalance,
+        },
+        max_slippage_bps: cli.max_slippage_bps,
+        fee_bps: cli.fee_bps,
+        fees: FeeConfig {
+            management_fee_bps: cli.management_fee_bps,
+            performance_fee_bps: cli.performance_fee_bps,
+        },
+        initial_value: cli.initial_value,
+    };
+
+    let series = Series::from_path(&cli.prices, &params.allocations)?;
+    let backtest = run(&params, &series)?;
+
+    let mut out: Box<dyn Write> = match &cli.output {
+        Some(path) => Box::new(
+            File::create(path).with_context(|| format!("无法创建输出文件: {}", path.display()))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    match cli.format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, &backtest)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for step in &backtest.steps {
+                writer.serialize(step)?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1: 解析分配项
+    #[test]
+    fn test_parse_allocation() {
+        let allocation = parse_allocation("1:2:2500").unwrap();
+        assert_eq!(allocation.protocol, 1);
+        assert_eq!(allocation.asset, 2);
+        assert_eq!(allocation.target_weight_bps, 2500);
+        assert!(parse_allocation("1:2").is_err());
+        assert!(parse_allocation("1:256:100").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::engine::{Step, SECONDS_PER_YEAR};
+use serde::Serialize;
+
+// 回测汇总指标。收益率、波动率和回撤都是比例(0.1 表示 10%)
+#[derive(Clone, Debug, Serialize)]
+pub struct Summary {
+    pub start: i64,
+    pub end: i64,
+    pub initial_value: u64,
+    pub final_value: u64,
+    pub total_return: f64,
+    pub annualized_return: f64,
+    // 按平均采样间隔年化的收益率标准差
+    pub volatility: f64,
+    pub max_drawdown: f64,
+    // 各次再平衡成交额占当时总价值的比例之和
+    pub turnover: f64,
+    pub rebalances: u32,
+    // 累计支付的手续费和滑点
+    pub costs: u64,
+    // 累计计提的管理费和业绩费
+    pub fees: u64,
+}
+
+impl Summary {
+    pub fn from_steps(initial_value: u64, steps: &[Step]) -> Self {
+        let start = steps.first().map_or(0, |s| s.timestamp);
+        let end = steps.last().map_or(0, |s| s.timestamp);
+        let final_value = steps.last().map_or(initial_value, |s| s.total_value);
+        let total_return = ratio(final_value, initial_value) - 1.0;
+
+        let duration = (end - start) as f64;
+        let annualized_return = if duration > 0.0 && total_return > -1.0 {
+            (1.0 + total_return).powf(SECONDS_PER_YEAR / duration) - 1.0
+        } else {
+            0.0
+        };
+
+        let returns: Vec<f64> = steps
+            .windows(2)
+            .map(|w| ratio(w[1].total_value, w[0].total_value) - 1.0)
+            .collect();
+        let volatility = if returns.len() > 1 {
+            let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+            let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+                / (returns.len() - 1) as f64;
+            let periods_per_year = SECONDS_PER_YEAR / (duration / returns.len() as f64);
+            (variance * periods_per_year).sqrt()
+        } else {
+            0.0
+        };
+
+        let mut peak = 0u64;
+        let mut max_drawdown = 0.0f64;
+        for step in steps {
+            peak = peak.max(step.total_value);
+            max_drawdown = max_drawdown.max(1.0 - ratio(step.total_value, peak));
+        }
+
+        let rebalanced = steps.iter().filter(|s| s.rebalanced);
+        Self {
+            start,
+            end,
+            initial_value,
+            final_value,
+            total_return,
+            annualized_return,
+            volatility,
+            max_drawdown,
+            turnover: rebalanced
+                .clone()
+                .map(|s| ratio(s.turnover, s.total_value + s.costs + s.fees))
+                .fold(0.0, |sum, r| sum + r),
+            rebalances: rebalanced.clone().count() as u32,
+            costs: rebalanced.clone().map(|s| s.costs).sum(),
+            fees: rebalanced.map(|s| s.fees).sum(),
+        }
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(timestamp: i64, total_value: u64) -> Step {
+        Step {
+            timestamp,
+            total_value,
+            deviation_bps: 0,
+            rebalanced: false,
+            turnover: 0,
+            costs: 0,
+            fees: 0,
+        }
+    }
+
+    // 测试1: 收益率、回撤和成交额
+    #[test]
+    fn test_summary() {
+        let year = SECONDS_PER_YEAR as i64;
+        let mut steps = vec![
+            step(0, 100),
+            step(year / 2, 120),
+            step(year, 90),
+            step(year * 2, 121),
+        ];
+        steps[2].rebalanced = true;
+        steps[2].turnover = 45;
+        steps[2].costs = 10;
+
+        let summary = Summary::from_steps(100, &steps);
+        assert!((summary.total_return - 0.21).abs() < 1e-9);
+        assert!((summary.annualized_return - 0.1).abs() < 1e-9);
+        assert!((summary.max_drawdown - 0.25).abs() < 1e-9);
+        assert!((summary.turnover - 0.45).abs() < 1e-9);
+        assert_eq!(summary.rebalances, 1);
+        assert_eq!(summary.costs, 10);
+        assert!(summary.volatility > 0.0);
+    }
+
+    // 测试2: 价值不变时没有波动和回撤
+    #[test]
+    fn test_summary_flat() {
+        let steps = vec![step(0, 100), step(10, 100), step(20, 100)];
+        let summary = Summary::from_steps(100, &steps);
+        assert_eq!(summary.total_return, 0.0);
+        assert_eq!(summary.volatility, 0.0);
+        assert_eq!(summary.max_drawdown, 0.0);
+    }
+}
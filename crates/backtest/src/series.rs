@@ -0,0 +1,136 @@
+use anyhow::{anyhow, bail, Context, Result};
+use defi_tools::Allocation;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::Path;
+
+// CSV 中的一行: 某一时刻某个(协议, 资产)头寸的资产价格和年化收益率
+#[derive(Clone, Debug, Deserialize)]
+struct Row {
+    timestamp: i64,
+    protocol: u8,
+    asset: u8,
+    price: f64,
+    #[serde(default)]
+    apy_bps: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quote {
+    pub price: f64,
+    pub apy_bps: u32,
+}
+
+// 按分配项顺序对齐的历史行情，quotes[i][j] 是第 i 个时刻第 j 个分配项的行情
+#[derive(Clone, Debug)]
+pub struct Series {
+    pub timestamps: Vec<i64>,
+    pub quotes: Vec<Vec<Quote>>,
+}
+
+impl Series {
+    pub fn from_path(path: &Path, allocations: &[Allocation]) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("无法打开行情文件: {}", path.display()))?;
+        Self::from_reader(file, allocations)
+    }
+
+    // 读取 timestamp,protocol,asset,price,apy_bps 格式的 CSV。
+    // 第一个时刻必须包含全部分配项，之后缺失的行情沿用上一时刻的值
+    pub fn from_reader<R: Read>(reader: R, allocations: &[Allocation]) -> Result<Self> {
+        let mut rows: BTreeMap<i64, HashMap<(u8, u8), Quote>> = BTreeMap::new();
+        for (line, row) in csv::Reader::from_reader(reader)
+            .deserialize::<Row>()
+            .enumerate()
+        {
+            let row = row.with_context(|| format!("第 {} 行格式错误", line + 2))?;
+            if !row.price.is_finite() || row.price <= 0.0 {
+                bail!("第 {} 行价格无效: {}", line + 2, row.price);
+            }
+            rows.entry(row.timestamp).or_default().insert(
+                (row.protocol, row.asset),
+                Quote {
+                    price: row.price,
+                    apy_bps: row.apy_bps,
+                },
+            );
+        }
+        if rows.len() < 2 {
+            bail!("行情数据至少需要两个时刻");
+        }
+
+        let mut timestamps = Vec::with_capacity(rows.len());
+        let mut quotes: Vec<Vec<Quote>> = Vec::with_capacity(rows.len());
+        for (timestamp, row) in rows {
+            let step = allocations
+                .iter()
+                .enumerate()
+                .map(|(j, allocation)| {
+                    row.get(&(allocation.protocol, allocation.asset))
+                        .copied()
+                        .or_else(|| quotes.last().map(|previous| previous[j]))
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "时刻 {timestamp} 缺少协议 {} 资产 {} 的行情",
+                                allocation.protocol,
+                                allocation.asset
+                            )
+                        })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            timestamps.push(timestamp);
+            quotes.push(step);
+        }
+        Ok(Self { timestamps, quotes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocations() -> Vec<Allocation> {
+        vec![
+            Allocation {
+                protocol: 0,
+                asset: 0,
+                target_weight_bps: 5000,
+            },
+            Allocation {
+                protocol: 1,
+                asset: 1,
+                target_weight_bps: 5000,
+            },
+        ]
+    }
+
+    // 测试1: 按时间排序并沿用缺失的行情
+    #[test]
+    fn test_load_series() {
+        let csv = "timestamp,protocol,asset,price,apy_bps\n\
+                   200,0,0,110,500\n\
+                   100,0,0,100,500\n\
+                   100,1,1,1,800\n";
+        let series = Series::from_reader(csv.as_bytes(), &allocations()).unwrap();
+        assert_eq!(series.timestamps, vec![100, 200]);
+        assert_eq!(series.quotes[1][0].price, 110.0);
+        assert_eq!(
+            series.quotes[1][1],
+            Quote {
+                price: 1.0,
+                apy_bps: 800
+            }
+        );
+    }
+
+    // 测试2: 第一个时刻缺少分配项或价格无效
+    #[test]
+    fn test_load_series_invalid() {
+        let missing = "timestamp,protocol,asset,price,apy_bps\n100,0,0,100,0\n200,1,1,1,0\n";
+        assert!(Series::from_reader(missing.as_bytes(), &allocations()).is_err());
+
+        let zero = "timestamp,protocol,asset,price,apy_bps\n100,0,0,0,0\n100,1,1,1,0\n";
+        assert!(Series::from_reader(zero.as_bytes(), &allocations()).is_err());
+    }
+}
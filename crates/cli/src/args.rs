@@ -0,0 +1,111 @@
+use defi_tools::{Allocation, Protocol};
+
+// 资产注册表中的默认资产ID，与 sdk/src/types.ts 的 AssetType 一致
+const ASSET_SYMBOLS: &[(&str, u8)] = &[("sol", 0), ("usdc", 1), ("usdt", 2)];
+
+const PROTOCOL_NAMES: &[(&str, Protocol)] = &[
+    ("raydium", Protocol::Raydium),
+    ("solend", Protocol::Solend),
+    ("orca", Protocol::Orca),
+    ("mango", Protocol::Mango),
+];
+
+pub fn parse_protocol(value: &str) -> Result<u8, String> {
+    let value = value.to_lowercase();
+    if let Some((_, protocol)) = PROTOCOL_NAMES.iter().find(|(name, _)| *name == value) {
+        return Ok(*protocol as u8);
+    }
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|id| Protocol::try_from(*id).is_ok())
+        .ok_or_else(|| format!("未知的协议: {value}"))
+}
+
+pub fn parse_asset(value: &str) -> Result<u8, String> {
+    let value = value.to_lowercase();
+    if let Some((_, id)) = ASSET_SYMBOLS.iter().find(|(symbol, _)| *symbol == value) {
+        return Ok(*id);
+    }
+    value
+        .parse::<u8>()
+        .map_err(|_| format!("未知的资产: {value}，请使用资产符号或资产ID"))
+}
+
+// 解析 `协议:资产:权重基点` 形式的分配项，例如 raydium:sol:5000
+pub fn parse_allocation(value: &str) -> Result<Allocation, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [protocol, asset, weight] = parts.as_slice() else {
+        return Err(format!("分配项格式应为 协议:资产:权重基点，实际为 {value}"));
+    };
+    let target_weight_bps = weight
+        .parse::<u16>()
+        .map_err(|_| format!("无效的权重: {weight}"))?;
+
+    Ok(Allocation {
+        protocol: parse_protocol(protocol)?,
+        asset: parse_asset(asset)?,
+        target_weight_bps,
+    })
+}
+
+// 解析 `协议:资产` 形式的头寸，例如 orca:usdc，返回 (协议, 资产)
+pub fn parse_position(value: &str) -> Result<(u8, u8), String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [protocol, asset] = parts.as_slice() else {
+        return Err(format!("头寸格式应为 协议:资产，实际为 {value}"));
+    };
+    Ok((parse_protocol(protocol)?, parse_asset(asset)?))
+}
+
+// 协议的显示名称
+pub fn protocol_name(protocol: u8) -> String {
+    match Protocol::try_from(protocol) {
+        Ok(protocol) => format!("{protocol:?}").to_lowercase(),
+        Err(_) => protocol.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1: 按名称和数字解析分配项
+    #[test]
+    fn test_parse_allocation() {
+        let allocation = parse_allocation("raydium:sol:5000").unwrap();
+        assert_eq!(allocation.protocol, Protocol::Raydium as u8);
+        assert_eq!(allocation.asset, 0);
+        assert_eq!(allocation.target_weight_bps, 5000);
+
+        let allocation = parse_allocation("Solend:7:2500").unwrap();
+        assert_eq!(allocation.protocol, Protocol::Solend as u8);
+        assert_eq!(allocation.asset, 7);
+    }
+
+    // 测试2: 格式错误、未知协议和无效权重被拒绝
+    #[test]
+    fn test_parse_allocation_invalid() {
+        assert!(parse_allocation("raydium:sol").is_err());
+        assert!(parse_allocation("uniswap:sol:5000").is_err());
+        assert!(parse_allocation("9:sol:5000").is_err());
+        assert!(parse_allocation("orca:btc:5000").is_err());
+        assert!(parse_allocation("orca:sol:70000").is_err());
+    }
+
+    // 测试3: 协议显示名称
+    #[test]
+    fn test_protocol_name() {
+        assert_eq!(protocol_name(2), "orca");
+        assert_eq!(protocol_name(42), "42");
+    }
+
+    // 测试4: 解析不带权重的头寸
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(parse_position("orca:usdc").unwrap(), (2, 1));
+        assert_eq!(parse_position("1:5").unwrap(), (1, 5));
+        assert!(parse_position("orca:usdc:5000").is_err());
+        assert!(parse_position("uniswap:sol").is_err());
+    }
+}
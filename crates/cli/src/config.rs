@@ -0,0 +1,62 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+
+// Solana CLI 配置文件(~/.config/solana/cli/config.yml)中用到的字段
+#[derive(Default, Deserialize)]
+struct SolanaCliConfig {
+    json_rpc_url: Option<String>,
+    keypair_path: Option<String>,
+}
+
+fn solana_config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config/solana"))
+}
+
+fn load_solana_cli_config() -> SolanaCliConfig {
+    solana_config_dir()
+        .map(|dir| dir.join("cli/config.yml"))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_yaml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// 命令行参数 > 环境变量 > Solana CLI 配置 > 默认值
+pub struct Config {
+    pub rpc_url: String,
+    pub keypair_path: PathBuf,
+}
+
+impl Config {
+    pub fn resolve(rpc_url: Option<String>, keypair_path: Option<PathBuf>) -> Result<Self> {
+        let solana = load_solana_cli_config();
+
+        let rpc_url = rpc_url
+            .or(solana.json_rpc_url)
+            .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+        let keypair_path = keypair_path
+            .or(solana.keypair_path.map(PathBuf::from))
+            .or_else(|| solana_config_dir().map(|dir| dir.join("id.json")))
+            .ok_or_else(|| anyhow!("无法确定密钥文件路径，请使用 --keypair 指定"))?;
+
+        Ok(Self {
+            rpc_url,
+            keypair_path,
+        })
+    }
+
+    pub fn keypair(&self) -> Result<Keypair> {
+        read_keypair(&self.keypair_path)
+    }
+}
+
+// 读取命令行另外指定的签名密钥，例如联署的监护人
+pub fn read_keypair(path: &Path) -> Result<Keypair> {
+    read_keypair_file(path)
+        .map_err(|e| anyhow!("{e}"))
+        .with_context(|| format!("读取密钥文件失败: {}", path.display()))
+}
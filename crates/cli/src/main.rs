@@ -0,0 +1,1862 @@
+mod args;
+mod config;
+mod output;
+
+use anyhow::Result;
+use args::{parse_allocation, parse_asset, parse_position, parse_protocol};
+use clap::{Parser, Subcommand};
+use config::{read_keypair, Config};
+use defi_tools::{
+    Allocation, DcaParams, DelegateScope, ProtectionParams, RebalanceCondition, StrategyConfig,
+    YieldParams,
+};
+use defi_tools_client::instructions::Payout;
+use defi_tools_client::pda::{
+    find_strategy_pda, find_strategy_template_pda, find_user_profile_pda,
+};
+use defi_tools_client::{instructions, Client};
+use output::{
+    ApyView, DcaView, DelegateList, DelegateView, DestinationList, DestinationView, ErrorReport,
+    FollowList, FollowView, OutputFormat, ProtectionView, StrategyList, StrategyView, TemplateList,
+    TemplateView, TicketList, TicketView, TxReport, UserView, YieldView,
+};
+use solana_sdk::hash::hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Parser)]
+#[command(name = "defi-tools", about = "defi-tools 策略运维命令行工具")]
+struct Cli {
+    /// RPC 节点地址，默认读取 Solana CLI 配置
+    #[arg(long, short = 'u', global = true, env = "DEFI_TOOLS_RPC_URL")]
+    url: Option<String>,
+
+    /// 签名密钥文件，默认读取 Solana CLI 配置
+    #[arg(long, short = 'k', global = true, env = "DEFI_TOOLS_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    /// 只模拟交易，不发送
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// 输出格式
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 初始化用户配置
+    InitUser {
+        /// 风险等级(1-5)
+        #[arg(long)]
+        risk_level: u8,
+    },
+    /// 策略管理
+    #[command(subcommand)]
+    Strategy(StrategyCommand),
+    /// 向策略存入资金
+    Deposit {
+        strategy_id: u64,
+        #[arg(long)]
+        lamports: u64,
+    },
+    /// 从策略提取资金
+    Withdraw {
+        strategy_id: u64,
+        #[arg(long)]
+        lamports: u64,
+        /// 接收地址，默认为当前密钥；启用白名单时必须是已生效的白名单地址
+        #[arg(long)]
+        to: Option<Pubkey>,
+        /// 监护人密钥文件，联署的提款不受周期提款限额限制
+        #[arg(long)]
+        guardian: Option<PathBuf>,
+    },
+    /// 排队提款
+    #[command(subcommand)]
+    Withdrawal(WithdrawalCommand),
+    /// 提款地址白名单管理
+    #[command(subcommand)]
+    Allowlist(AllowlistCommand),
+    /// 周期提款限额管理
+    #[command(subcommand)]
+    SpendingLimit(SpendingLimitCommand),
+    /// 策略代理人管理
+    #[command(subcommand)]
+    Delegate(DelegateCommand),
+    /// 所有权转移
+    #[command(subcommand)]
+    Owner(OwnerCommand),
+    /// 策略模板管理
+    #[command(subcommand)]
+    Template(TemplateCommand),
+    /// 跟投管理
+    #[command(subcommand)]
+    Follow(FollowCommand),
+    /// 定投管理
+    #[command(subcommand)]
+    Dca(DcaCommand),
+    /// 止损止盈保护单管理
+    #[command(subcommand)]
+    Protect(ProtectCommand),
+    /// 收益率轮动管理
+    #[command(subcommand)]
+    Yield(YieldCommand),
+    /// 借贷协议利率报告(管理员)
+    #[command(subcommand)]
+    Apy(ApyCommand),
+    /// 执行策略
+    Execute {
+        strategy_id: u64,
+        /// 以代理人身份操作该所有者的策略
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 按预言机价格再平衡策略头寸
+    Rebalance {
+        strategy_id: u64,
+        /// 以代理人身份操作该所有者的策略
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 显示策略；不指定策略ID时显示用户配置
+    Show {
+        strategy_id: Option<u64>,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 列出策略
+    List {
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+        /// 列出所有用户的策略
+        #[arg(long, conflicts_with = "owner")]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum StrategyCommand {
+    /// 创建策略
+    Create {
+        /// 分配项，格式为 协议:资产:权重基点，例如 raydium:sol:5000
+        #[arg(long = "alloc", value_parser = parse_allocation, required = true)]
+        allocations: Vec<Allocation>,
+        /// 再平衡最小时间间隔(秒)
+        #[arg(long, default_value_t = 86400)]
+        interval: u64,
+        /// 触发再平衡的最大偏差(基点)
+        #[arg(long, default_value_t = 500)]
+        max_deviation_bps: u16,
+        /// 是否自动再平衡
+        #[arg(long)]
+        auto_rebalance: bool,
+        /// 最大滑点(基点)
+        #[arg(long, default_value_t = 100)]
+        max_slippage_bps: u16,
+    },
+    /// 按模板创建策略，用户的风险等级不能低于模板的风险等级
+    FromTemplate { publisher: Pubkey, template_id: u64 },
+    /// 更新策略参数，未指定的参数保持不变
+    Update {
+        strategy_id: u64,
+        /// 替换全部分配项，格式同 create
+        #[arg(long = "alloc", value_parser = parse_allocation)]
+        allocations: Vec<Allocation>,
+        /// 再平衡最小时间间隔(秒)
+        #[arg(long)]
+        interval: Option<u64>,
+        /// 触发再平衡的最大偏差(基点)
+        #[arg(long)]
+        max_deviation_bps: Option<u16>,
+        /// 是否自动再平衡
+        #[arg(long)]
+        auto_rebalance: Option<bool>,
+        /// 最大滑点(基点)
+        #[arg(long)]
+        max_slippage_bps: Option<u16>,
+        /// 以代理人身份操作该所有者的策略
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WithdrawalCommand {
+    /// 申请排队提款，金额锁定到头寸解除或锁定时间到期
+    Request {
+        strategy_id: u64,
+        #[arg(long)]
+        lamports: u64,
+    },
+    /// 领取提款工单，之前的工单必须已领取或取消
+    Claim {
+        strategy_id: u64,
+        ticket_id: u64,
+        /// 接收地址，默认为当前密钥
+        #[arg(long)]
+        to: Option<Pubkey>,
+        /// 监护人密钥文件，联署的领取不受周期提款限额限制
+        #[arg(long)]
+        guardian: Option<PathBuf>,
+    },
+    /// 取消提款工单，金额退回策略
+    Cancel { strategy_id: u64, ticket_id: u64 },
+    /// 列出尚未领取的提款工单
+    List {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AllowlistCommand {
+    /// 添加白名单地址，在生效延迟之后才能接收提款
+    Add { destination: Pubkey },
+    /// 移除白名单地址，立即生效
+    Remove { destination: Pubkey },
+    /// 启用白名单，立即生效
+    Enable,
+    /// 关闭白名单，在生效延迟之后生效
+    Disable,
+    /// 列出白名单地址
+    List {
+        /// 白名单所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SpendingLimitCommand {
+    /// 设置周期提款限额，必须已有或指定监护人；放宽限额或更换监护人需要当前监护人联署
+    Set {
+        /// 每个窗口最多转出的 lamports
+        #[arg(long)]
+        max_lamports: u64,
+        /// 窗口长度(秒)
+        #[arg(long)]
+        window: u32,
+        /// 新的监护人，默认保持不变
+        #[arg(long)]
+        new_guardian: Option<Pubkey>,
+        /// 当前监护人的密钥文件
+        #[arg(long)]
+        guardian: Option<PathBuf>,
+    },
+    /// 取消周期提款限额，设置了监护人时需要其联署
+    Clear {
+        /// 同时移除监护人
+        #[arg(long)]
+        remove_guardian: bool,
+        /// 当前监护人的密钥文件
+        #[arg(long)]
+        guardian: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DelegateCommand {
+    /// 授权代理人操作策略，至少指定一项权限
+    Add {
+        strategy_id: u64,
+        delegate: Pubkey,
+        /// 允许执行策略
+        #[arg(long)]
+        execute: bool,
+        /// 允许再平衡头寸
+        #[arg(long)]
+        rebalance: bool,
+        /// 允许在限定范围内更新策略
+        #[arg(long)]
+        update: bool,
+        /// 更新时每个分配项权重的最大变化(基点)
+        #[arg(long, default_value_t = 0)]
+        max_weight_change_bps: u16,
+        /// 更新时可设置的最大滑点(基点)
+        #[arg(long, default_value_t = 0)]
+        max_slippage_bps: u16,
+        /// 授权有效期(秒)
+        #[arg(long)]
+        expires_in: u64,
+    },
+    /// 撤销代理人，关闭代理人账户并退还租金
+    Revoke { strategy_id: u64, delegate: Pubkey },
+    /// 列出策略的代理人
+    List {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum OwnerCommand {
+    /// 提名新所有者；白名单生效时需等待生效延迟后才能接受
+    Propose {
+        new_owner: Pubkey,
+        /// 监护人密钥文件，设置了监护人时必须联署
+        #[arg(long)]
+        guardian: Option<PathBuf>,
+    },
+    /// 取消尚未接受的提名
+    Cancel,
+    /// 以当前密钥接受提名，创建继承原设置的用户配置
+    Accept { previous_owner: Pubkey },
+    /// 将原所有者的策略和资金迁移到当前密钥名下，未领取的提款工单需先取消
+    Migrate {
+        previous_owner: Pubkey,
+        /// 只迁移该策略，默认迁移全部策略
+        #[arg(long)]
+        strategy_id: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateCommand {
+    /// 以当前密钥发布策略模板
+    Publish {
+        /// 发布者自选的模板ID，不能与已发布的模板重复
+        template_id: u64,
+        /// 分配项，格式同 strategy create
+        #[arg(long = "alloc", value_parser = parse_allocation, required = true)]
+        allocations: Vec<Allocation>,
+        /// 再平衡最小时间间隔(秒)
+        #[arg(long, default_value_t = 86400)]
+        interval: u64,
+        /// 触发再平衡的最大偏差(基点)
+        #[arg(long, default_value_t = 500)]
+        max_deviation_bps: u16,
+        /// 是否自动再平衡
+        #[arg(long)]
+        auto_rebalance: bool,
+        /// 最大滑点(基点)
+        #[arg(long, default_value_t = 100)]
+        max_slippage_bps: u16,
+        /// 模板风险等级(1-5)，低于按分配项计算的等级时以计算结果为准
+        #[arg(long)]
+        risk_level: u8,
+        /// 链下策略说明，链上只保存其哈希
+        #[arg(long, default_value = "")]
+        description: String,
+    },
+    /// 停用并关闭模板，已创建的策略不受影响
+    Retire { template_id: u64 },
+    /// 显示模板
+    Show { publisher: Pubkey, template_id: u64 },
+    /// 列出模板
+    List {
+        /// 只列出该发布者的模板
+        #[arg(long)]
+        publisher: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum FollowCommand {
+    /// 让策略跟随领投策略，之后由同步操作复制领投策略的分配项
+    Add {
+        strategy_id: u64,
+        /// 领投策略所有者
+        leader_owner: Pubkey,
+        /// 领投策略ID
+        leader_id: u64,
+        /// 同步时单个分配项的最大权重(基点)，领投策略超出时拒绝同步
+        #[arg(long, default_value_t = 10000)]
+        max_weight_bps: u16,
+        /// 领投策略调整分配项后需等待的时长(秒)
+        #[arg(long, default_value_t = 0)]
+        delay: u32,
+        /// 每次同步按策略价值支付给领投者的费用(基点)
+        #[arg(long, default_value_t = 0)]
+        leader_fee_bps: u16,
+    },
+    /// 停止跟随，关闭跟投关系并退还租金
+    Remove { strategy_id: u64 },
+    /// 将领投策略的分配项同步到跟随策略，任何人都可以发起
+    Sync {
+        strategy_id: u64,
+        /// 跟随策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 显示策略的跟投关系
+    Show {
+        strategy_id: u64,
+        /// 跟随策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 列出领投策略的跟随者
+    List {
+        leader_owner: Pubkey,
+        leader_id: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum DcaCommand {
+    /// 创建定投计划，每个再平衡间隔把固定价值从报价分配项转移到目标分配项
+    Create {
+        strategy_id: u64,
+        /// 出资的报价分配项，格式为 协议:资产，例如 solend:usdc
+        #[arg(value_parser = parse_position)]
+        quote: (u8, u8),
+        /// 定投的目标头寸，格式为 协议:资产，例如 orca:sol
+        #[arg(value_parser = parse_position)]
+        target: (u8, u8),
+        /// 每次转移的价值(lamports)
+        #[arg(long)]
+        increment_lamports: u64,
+        /// 目标资产价格上限(报价精度)，高于上限时不执行，0表示不限制
+        #[arg(long, default_value_t = 0)]
+        max_price: u64,
+    },
+    /// 取消定投计划，已转移的分配项保持不变
+    Cancel { strategy_id: u64 },
+    /// 执行一次到期的定投，任何人都可以发起
+    Execute {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 显示策略的定投计划
+    Show {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProtectCommand {
+    /// 设置保护单，估值满足任一触发条件时全部转入安全头寸并暂停策略
+    Set {
+        strategy_id: u64,
+        /// 触发后转入的安全头寸，格式为 协议:资产，例如 solend:usdc
+        #[arg(value_parser = parse_position)]
+        safe: (u8, u8),
+        /// 估值低于该值时止损(lamports)，0表示不启用
+        #[arg(long, default_value_t = 0)]
+        stop_loss: u64,
+        /// 估值从峰值回撤超过该比例时止损(基点)，0表示不启用
+        #[arg(long, default_value_t = 0)]
+        max_drawdown_bps: u16,
+        /// 估值达到该值时止盈(lamports)，0表示不启用
+        #[arg(long, default_value_t = 0)]
+        take_profit: u64,
+    },
+    /// 取消保护单
+    Cancel { strategy_id: u64 },
+    /// 按预言机价格重新估值，任何人都可以发起
+    Check {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 恢复被保护单暂停的策略
+    Resume { strategy_id: u64 },
+    /// 显示策略的保护单
+    Show {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum YieldCommand {
+    /// 启用收益率轮动，资产的轮动头寸移动到APY最高的候选借贷协议
+    Enable {
+        strategy_id: u64,
+        /// 轮动头寸的资产，策略中该资产在候选协议上的分配项必须恰好一个
+        #[arg(value_parser = parse_asset)]
+        asset: u8,
+        /// 候选借贷协议，以逗号分隔，例如 solend,mango
+        #[arg(long, value_delimiter = ',', value_parser = parse_protocol, required = true)]
+        protocols: Vec<u8>,
+        /// 新协议的APY至少比当前协议高出的基点数
+        #[arg(long, default_value_t = 0)]
+        min_advantage_bps: u32,
+        /// 在当前协议的最短持有时间(秒)
+        #[arg(long, default_value_t = 0)]
+        min_hold_seconds: u32,
+    },
+    /// 停用收益率轮动，轮动头寸保留在当前协议
+    Disable { strategy_id: u64 },
+    /// 将轮动头寸移到APY最高的候选协议，任何人都可以发起
+    Rotate {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// 显示策略的收益率轮动计划
+    Show {
+        strategy_id: u64,
+        /// 策略所有者，默认为当前密钥
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ApyCommand {
+    /// 创建利率追踪账户，格式为 协议:资产，例如 solend:usdc
+    Register {
+        #[arg(value_parser = parse_position)]
+        position: (u8, u8),
+        apy_bps: u32,
+    },
+    /// 报告最新APY
+    Report {
+        #[arg(value_parser = parse_position)]
+        position: (u8, u8),
+        apy_bps: u32,
+    },
+    /// 显示协议和资产的APY
+    Show {
+        #[arg(value_parser = parse_position)]
+        position: (u8, u8),
+    },
+}
+
+struct Context {
+    config: Config,
+    client: Client,
+    dry_run: bool,
+    output: OutputFormat,
+}
+
+impl Context {
+    // 读取命令指定的所有者，未指定时使用当前密钥
+    fn owner(&self, owner: Option<Pubkey>) -> Result<Pubkey> {
+        match owner {
+            Some(owner) => Ok(owner),
+            None => Ok(self.config.keypair()?.pubkey()),
+        }
+    }
+
+    // 提款的转出方式。接收地址已加入所有者的白名单时附加白名单账户
+    fn payout(
+        &self,
+        owner: &Pubkey,
+        to: Option<Pubkey>,
+        guardian: Option<&Keypair>,
+    ) -> Result<Payout> {
+        let destination = to.unwrap_or(*owner);
+        let listed = destination != *owner
+            && match self.client.withdrawal_destination(owner, &destination) {
+                Ok(_) => true,
+                Err(defi_tools_client::Error::AccountNotFound(_)) => false,
+                Err(err) => return Err(err.into()),
+            };
+        Ok(Payout {
+            destination,
+            listed,
+            guardian: guardian.map(|guardian| guardian.pubkey()),
+        })
+    }
+
+    fn submit(
+        &self,
+        action: &'static str,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        strategy_id: Option<u64>,
+    ) -> Result<()> {
+        self.submit_signed(action, payer, &[], instructions, strategy_id)
+    }
+
+    // 发送需要其他密钥联署的交易，例如监护人
+    fn submit_signed(
+        &self,
+        action: &'static str,
+        payer: &Keypair,
+        signers: &[&Keypair],
+        instructions: &[Instruction],
+        strategy_id: Option<u64>,
+    ) -> Result<()> {
+        let owner = payer.pubkey();
+        self.submit_as(action, payer, signers, &owner, instructions, strategy_id)
+    }
+
+    // 发送操作 owner 的策略的交易，签名者可以是所有者的代理人
+    fn submit_for(
+        &self,
+        action: &'static str,
+        payer: &Keypair,
+        owner: &Pubkey,
+        instructions: &[Instruction],
+        strategy_id: Option<u64>,
+    ) -> Result<()> {
+        self.submit_as(action, payer, &[], owner, instructions, strategy_id)
+    }
+
+    fn submit_as(
+        &self,
+        action: &'static str,
+        payer: &Keypair,
+        signers: &[&Keypair],
+        owner: &Pubkey,
+        instructions: &[Instruction],
+        strategy_id: Option<u64>,
+    ) -> Result<()> {
+        let signers: Vec<&dyn Signer> = signers.iter().map(|s| *s as &dyn Signer).collect();
+        let strategy = strategy_id.map(|id| {
+            let user_profile = find_user_profile_pda(owner).0;
+            find_strategy_pda(&user_profile, id).0.to_string()
+        });
+
+        let report = if self.dry_run {
+            let result = self.client.simulate(instructions, payer, &signers)?;
+            if let Some(err) = result.err {
+                anyhow::bail!("模拟失败: {err}");
+            }
+            TxReport {
+                action,
+                simulated: true,
+                signature: None,
+                units_consumed: result.units_consumed,
+                logs: result.logs.unwrap_or_default(),
+                strategy_id,
+                strategy,
+            }
+        } else {
+            let signature = self.client.send(instructions, payer, &signers)?;
+            TxReport {
+                action,
+                simulated: false,
+                signature: Some(signature.to_string()),
+                units_consumed: None,
+                logs: Vec::new(),
+                strategy_id,
+                strategy,
+            }
+        };
+        self.output.print(&report)
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let config = Config::resolve(cli.url, cli.keypair)?;
+    let ctx = Context {
+        client: Client::new(config.rpc_url.clone()),
+        config,
+        dry_run: cli.dry_run,
+        output: cli.output,
+    };
+
+    match cli.command {
+        Command::InitUser { risk_level } => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::initialize_user(&payer.pubkey(), risk_level);
+            ctx.submit("init-user", &payer, &[ix], None)
+        }
+        Command::Strategy(StrategyCommand::Create {
+            allocations,
+            interval,
+            max_deviation_bps,
+            auto_rebalance,
+            max_slippage_bps,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let strategy_id = ctx.client.user_profile(&payer.pubkey())?.strategy_counter;
+            let condition = RebalanceCondition {
+                time_interval_seconds: interval,
+                max_deviation_bps,
+                auto_rebalance,
+            };
+            let ix = instructions::create_strategy(
+                &payer.pubkey(),
+                strategy_id,
+                allocations,
+                condition,
+                max_slippage_bps,
+            );
+            ctx.submit("strategy create", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Strategy(StrategyCommand::FromTemplate {
+            publisher,
+            template_id,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let strategy_id = ctx.client.user_profile(&payer.pubkey())?.strategy_counter;
+            let template = ctx.client.strategy_template(&publisher, template_id)?;
+            let ix = instructions::create_strategy_from_template(
+                &payer.pubkey(),
+                strategy_id,
+                &template,
+            );
+            ctx.submit("strategy from-template", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Strategy(StrategyCommand::Update {
+            strategy_id,
+            allocations,
+            interval,
+            max_deviation_bps,
+            auto_rebalance,
+            max_slippage_bps,
+            owner,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let owner = owner.unwrap_or(payer.pubkey());
+            let current = ctx.client.strategy(&owner, strategy_id)?;
+            let allocations = if allocations.is_empty() {
+                current.allocations
+            } else {
+                allocations
+            };
+            let current_condition = current.rebalance_condition;
+            let condition = RebalanceCondition {
+                time_interval_seconds: interval.unwrap_or(current_condition.time_interval_seconds),
+                max_deviation_bps: max_deviation_bps.unwrap_or(current_condition.max_deviation_bps),
+                auto_rebalance: auto_rebalance.unwrap_or(current_condition.auto_rebalance),
+            };
+            let ix = instructions::update_strategy_as(
+                &payer.pubkey(),
+                &owner,
+                strategy_id,
+                allocations,
+                condition,
+                max_slippage_bps.unwrap_or(current.max_slippage_bps),
+            );
+            ctx.submit_for("strategy update", &payer, &owner, &[ix], Some(strategy_id))
+        }
+        Command::Deposit {
+            strategy_id,
+            lamports,
+        } => {
+            let payer = ctx.config.keypair()?;
+            let strategy = ctx.client.strategy(&payer.pubkey(), strategy_id)?;
+            let treasury = ctx.client.config()?.treasury;
+            let ix = instructions::deposit_funds(
+                &payer.pubkey(),
+                strategy_id,
+                lamports,
+                &treasury,
+                &strategy.allocations,
+            );
+            ctx.submit("deposit", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Withdraw {
+            strategy_id,
+            lamports,
+            to,
+            guardian,
+        } => {
+            let payer = ctx.config.keypair()?;
+            let owner = payer.pubkey();
+            let guardian = guardian.as_deref().map(read_keypair).transpose()?;
+            let strategy = ctx.client.strategy(&owner, strategy_id)?;
+            let treasury = ctx.client.config()?.treasury;
+            let ix = instructions::withdraw_funds_to(
+                &owner,
+                strategy_id,
+                lamports,
+                &treasury,
+                &strategy.asset_deposits,
+                &ctx.payout(&owner, to, guardian.as_ref())?,
+            );
+            let signers: Vec<&Keypair> = guardian.iter().collect();
+            ctx.submit_signed("withdraw", &payer, &signers, &[ix], Some(strategy_id))
+        }
+        Command::Withdrawal(WithdrawalCommand::Request {
+            strategy_id,
+            lamports,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let strategy = ctx.client.strategy(&payer.pubkey(), strategy_id)?;
+            let treasury = ctx.client.config()?.treasury;
+            let ix = instructions::request_withdrawal(
+                &payer.pubkey(),
+                strategy_id,
+                strategy.withdrawal_queue.next_ticket_id,
+                lamports,
+                &treasury,
+            );
+            ctx.submit("withdrawal request", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Withdrawal(WithdrawalCommand::Claim {
+            strategy_id,
+            ticket_id,
+            to,
+            guardian,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let owner = payer.pubkey();
+            let guardian = guardian.as_deref().map(read_keypair).transpose()?;
+            let strategy = ctx.client.strategy(&owner, strategy_id)?;
+            // 队首到该工单之间的工单都应已取消，由程序校验
+            let head = strategy.withdrawal_queue.head_ticket_id;
+            let skipped: Vec<u64> = (head..ticket_id).collect();
+            let ix = instructions::claim_withdrawal_to(
+                &owner,
+                strategy_id,
+                ticket_id,
+                &strategy.asset_deposits,
+                &skipped,
+                &ctx.payout(&owner, to, guardian.as_ref())?,
+            );
+            let signers: Vec<&Keypair> = guardian.iter().collect();
+            ctx.submit_signed(
+                "withdrawal claim",
+                &payer,
+                &signers,
+                &[ix],
+                Some(strategy_id),
+            )
+        }
+        Command::Withdrawal(WithdrawalCommand::Cancel {
+            strategy_id,
+            ticket_id,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let treasury = ctx.client.config()?.treasury;
+            let ix =
+                instructions::cancel_withdrawal(&payer.pubkey(), strategy_id, ticket_id, &treasury);
+            ctx.submit("withdrawal cancel", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Withdrawal(WithdrawalCommand::List { strategy_id, owner }) => {
+            let owner = ctx.owner(owner)?;
+            let queue = ctx.client.strategy(&owner, strategy_id)?.withdrawal_queue;
+            let tickets = ctx.client.withdrawal_tickets(&owner, strategy_id)?;
+            let list = tickets
+                .iter()
+                .map(|ticket| TicketView::new(ticket, &queue))
+                .collect();
+            ctx.output.print(&TicketList(list))
+        }
+        Command::Allowlist(AllowlistCommand::Add { destination }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::add_withdrawal_destination(&payer.pubkey(), &destination);
+            ctx.submit("allowlist add", &payer, &[ix], None)
+        }
+        Command::Allowlist(AllowlistCommand::Remove { destination }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::remove_withdrawal_destination(&payer.pubkey(), &destination);
+            ctx.submit("allowlist remove", &payer, &[ix], None)
+        }
+        Command::Allowlist(AllowlistCommand::Enable) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::set_withdrawal_allowlist(&payer.pubkey(), true);
+            ctx.submit("allowlist enable", &payer, &[ix], None)
+        }
+        Command::Allowlist(AllowlistCommand::Disable) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::set_withdrawal_allowlist(&payer.pubkey(), false);
+            ctx.submit("allowlist disable", &payer, &[ix], None)
+        }
+        Command::Allowlist(AllowlistCommand::List { owner }) => {
+            let owner = ctx.owner(owner)?;
+            let mut entries = ctx.client.withdrawal_destinations(&owner)?;
+            entries.sort_by_key(|entry| (entry.activates_at, entry.destination.to_string()));
+            let list = entries.iter().map(DestinationView::new).collect();
+            ctx.output.print(&DestinationList(list))
+        }
+        Command::SpendingLimit(command) => {
+            let payer = ctx.config.keypair()?;
+            let owner = payer.pubkey();
+            let current = ctx.client.user_profile(&owner)?.spending_limit.guardian;
+            let (action, max_lamports, window, new_guardian, guardian) = match command {
+                SpendingLimitCommand::Set {
+                    max_lamports,
+                    window,
+                    new_guardian,
+                    guardian,
+                } => (
+                    "spending-limit set",
+                    max_lamports,
+                    window,
+                    new_guardian.unwrap_or(current),
+                    guardian,
+                ),
+                SpendingLimitCommand::Clear {
+                    remove_guardian,
+                    guardian,
+                } => {
+                    let new_guardian = if remove_guardian {
+                        Pubkey::default()
+                    } else {
+                        current
+                    };
+                    ("spending-limit clear", 0, 0, new_guardian, guardian)
+                }
+            };
+            let guardian = guardian.as_deref().map(read_keypair).transpose()?;
+            let ix = instructions::set_spending_limit(
+                &owner,
+                max_lamports,
+                window,
+                &new_guardian,
+                guardian.as_ref().map(|guardian| guardian.pubkey()).as_ref(),
+            );
+            let signers: Vec<&Keypair> = guardian.iter().collect();
+            ctx.submit_signed(action, &payer, &signers, &[ix], None)
+        }
+        Command::Delegate(DelegateCommand::Add {
+            strategy_id,
+            delegate,
+            execute,
+            rebalance,
+            update,
+            max_weight_change_bps,
+            max_slippage_bps,
+            expires_in,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let scope = DelegateScope {
+                execute,
+                rebalance,
+                update,
+                max_weight_change_bps,
+                max_slippage_bps,
+            };
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let expires_at = i64::try_from(now.saturating_add(expires_in))?;
+            let ix = instructions::add_delegate(
+                &payer.pubkey(),
+                strategy_id,
+                &delegate,
+                scope,
+                expires_at,
+            );
+            ctx.submit("delegate add", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Delegate(DelegateCommand::Revoke {
+            strategy_id,
+            delegate,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::revoke_delegate(&payer.pubkey(), strategy_id, &delegate);
+            ctx.submit("delegate revoke", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Delegate(DelegateCommand::List { strategy_id, owner }) => {
+            let owner = ctx.owner(owner)?;
+            let mut delegates = ctx.client.strategy_delegates(&owner, strategy_id)?;
+            delegates.sort_by_key(|delegate| delegate.delegate.to_string());
+            let list = delegates.iter().map(DelegateView::new).collect();
+            ctx.output.print(&DelegateList(list))
+        }
+        Command::Owner(OwnerCommand::Propose {
+            new_owner,
+            guardian,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let guardian = guardian.as_deref().map(read_keypair).transpose()?;
+            let ix = instructions::propose_owner(
+                &payer.pubkey(),
+                &new_owner,
+                guardian.as_ref().map(|guardian| guardian.pubkey()).as_ref(),
+            );
+            let signers: Vec<&Keypair> = guardian.iter().collect();
+            ctx.submit_signed("owner propose", &payer, &signers, &[ix], None)
+        }
+        Command::Owner(OwnerCommand::Cancel) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::propose_owner(&payer.pubkey(), &Pubkey::default(), None);
+            ctx.submit("owner cancel", &payer, &[ix], None)
+        }
+        Command::Owner(OwnerCommand::Accept { previous_owner }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::accept_owner(&payer.pubkey(), &previous_owner);
+            ctx.submit("owner accept", &payer, &[ix], None)
+        }
+        Command::Owner(OwnerCommand::Migrate {
+            previous_owner,
+            strategy_id,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let ids = match strategy_id {
+                Some(strategy_id) => vec![strategy_id],
+                None => {
+                    let mut ids: Vec<u64> = ctx
+                        .client
+                        .strategies(Some(&previous_owner))?
+                        .iter()
+                        .map(|(_, strategy)| strategy.strategy_id)
+                        .collect();
+                    ids.sort_unstable();
+                    ids
+                }
+            };
+            // 每个策略单独一笔交易，失败时已迁移的策略不受影响
+            for strategy_id in ids {
+                let ix =
+                    instructions::migrate_strategy(&payer.pubkey(), &previous_owner, strategy_id);
+                ctx.submit("owner migrate", &payer, &[ix], Some(strategy_id))?;
+            }
+            Ok(())
+        }
+        Command::Template(TemplateCommand::Publish {
+            template_id,
+            allocations,
+            interval,
+            max_deviation_bps,
+            auto_rebalance,
+            max_slippage_bps,
+            risk_level,
+            description,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let condition = RebalanceCondition {
+                time_interval_seconds: interval,
+                max_deviation_bps,
+                auto_rebalance,
+            };
+            let ix = instructions::publish_template(
+                &payer.pubkey(),
+                template_id,
+                allocations,
+                condition,
+                max_slippage_bps,
+                risk_level,
+                hash(description.as_bytes()).to_bytes(),
+            );
+            ctx.submit("template publish", &payer, &[ix], None)
+        }
+        Command::Template(TemplateCommand::Retire { template_id }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::retire_template(&payer.pubkey(), template_id);
+            ctx.submit("template retire", &payer, &[ix], None)
+        }
+        Command::Template(TemplateCommand::Show {
+            publisher,
+            template_id,
+        }) => {
+            let template = ctx.client.strategy_template(&publisher, template_id)?;
+            let address = find_strategy_template_pda(&publisher, template_id).0;
+            ctx.output.print(&TemplateView::new(&address, &template))
+        }
+        Command::Template(TemplateCommand::List { publisher }) => {
+            let mut templates = ctx.client.strategy_templates(publisher.as_ref())?;
+            templates.sort_by_key(|(_, t)| (t.publisher.to_string(), t.template_id));
+            let list = templates
+                .iter()
+                .map(|(address, template)| TemplateView::new(address, template))
+                .collect();
+            ctx.output.print(&TemplateList(list))
+        }
+        Command::Follow(FollowCommand::Add {
+            strategy_id,
+            leader_owner,
+            leader_id,
+            max_weight_bps,
+            delay,
+            leader_fee_bps,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let leader = find_strategy_pda(&find_user_profile_pda(&leader_owner).0, leader_id).0;
+            let ix = instructions::follow_strategy(
+                &payer.pubkey(),
+                strategy_id,
+                &leader,
+                max_weight_bps,
+                delay,
+                leader_fee_bps,
+            );
+            ctx.submit("follow add", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Follow(FollowCommand::Remove { strategy_id }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::unfollow_strategy(&payer.pubkey(), strategy_id);
+            ctx.submit("follow remove", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Follow(FollowCommand::Sync { strategy_id, owner }) => {
+            let payer = ctx.config.keypair()?;
+            let owner = owner.unwrap_or(payer.pubkey());
+            let follow = ctx.client.strategy_follow(&owner, strategy_id)?;
+            let (leader_strategy, leader) = ctx.client.leader_strategy(&follow)?;
+            let treasury = ctx.client.config()?.treasury;
+            // 领投者已加入跟随者的白名单时附加白名单账户
+            let leader_listed = ctx.payout(&owner, Some(leader.owner), None)?.listed;
+            let ix = instructions::sync_follower(
+                &payer.pubkey(),
+                &follow,
+                &leader_strategy,
+                &leader,
+                &treasury,
+                leader_listed,
+            );
+            ctx.submit_for("follow sync", &payer, &owner, &[ix], Some(strategy_id))
+        }
+        Command::Follow(FollowCommand::Show { strategy_id, owner }) => {
+            let owner = ctx.owner(owner)?;
+            let follow = ctx.client.strategy_follow(&owner, strategy_id)?;
+            ctx.output.print(&FollowView::new(&follow))
+        }
+        Command::Follow(FollowCommand::List {
+            leader_owner,
+            leader_id,
+        }) => {
+            let leader = find_strategy_pda(&find_user_profile_pda(&leader_owner).0, leader_id).0;
+            // 迁移得到的策略按原始地址查找跟随者
+            let origin = ctx
+                .client
+                .account::<StrategyConfig>(&leader)?
+                .origin_or(leader);
+            let mut follows = ctx.client.followers(&origin)?;
+            follows.sort_by_key(|(_, follow)| follow.followed_at);
+            let list = follows
+                .iter()
+                .map(|(_, follow)| FollowView::new(follow))
+                .collect();
+            ctx.output.print(&FollowList(list))
+        }
+        Command::Dca(DcaCommand::Create {
+            strategy_id,
+            quote,
+            target,
+            increment_lamports,
+            max_price,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let params = DcaParams {
+                quote_protocol: quote.0,
+                quote_asset: quote.1,
+                target_protocol: target.0,
+                target_asset: target.1,
+                increment_lamports,
+                max_price_quote: max_price,
+            };
+            let ix = instructions::create_dca(&payer.pubkey(), strategy_id, params);
+            ctx.submit("dca create", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Dca(DcaCommand::Cancel { strategy_id }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::cancel_dca(&payer.pubkey(), strategy_id);
+            ctx.submit("dca cancel", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Dca(DcaCommand::Execute { strategy_id, owner }) => {
+            let payer = ctx.config.keypair()?;
+            let owner = owner.unwrap_or(payer.pubkey());
+            let schedule = ctx.client.dca_schedule(&owner, strategy_id)?;
+            let prices = ctx.client.dca_price_accounts(&schedule)?;
+            let ix = instructions::execute_dca(&payer.pubkey(), &schedule, &prices);
+            ctx.submit_for("dca execute", &payer, &owner, &[ix], Some(strategy_id))
+        }
+        Command::Dca(DcaCommand::Show { strategy_id, owner }) => {
+            let owner = ctx.owner(owner)?;
+            let schedule = ctx.client.dca_schedule(&owner, strategy_id)?;
+            ctx.output.print(&DcaView::new(&schedule))
+        }
+        Command::Protect(ProtectCommand::Set {
+            strategy_id,
+            safe,
+            stop_loss,
+            max_drawdown_bps,
+            take_profit,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let params = ProtectionParams {
+                stop_loss_lamports: stop_loss,
+                max_drawdown_bps,
+                take_profit_lamports: take_profit,
+                safe_protocol: safe.0,
+                safe_asset: safe.1,
+            };
+            let strategy = ctx.client.strategy(&payer.pubkey(), strategy_id)?;
+            let prices = ctx.client.price_accounts(&strategy)?;
+            let ix = instructions::set_protection(&payer.pubkey(), strategy_id, params, &prices);
+            ctx.submit("protect set", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Protect(ProtectCommand::Cancel { strategy_id }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::cancel_protection(&payer.pubkey(), strategy_id);
+            ctx.submit("protect cancel", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Protect(ProtectCommand::Check { strategy_id, owner }) => {
+            let payer = ctx.config.keypair()?;
+            let owner = owner.unwrap_or(payer.pubkey());
+            let order = ctx.client.protective_order(&owner, strategy_id)?;
+            let strategy = ctx.client.strategy(&owner, strategy_id)?;
+            let prices = ctx.client.price_accounts(&strategy)?;
+            let ix = instructions::check_protection(&payer.pubkey(), &order, &prices);
+            ctx.submit_for("protect check", &payer, &owner, &[ix], Some(strategy_id))
+        }
+        Command::Protect(ProtectCommand::Resume { strategy_id }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::resume_strategy(&payer.pubkey(), strategy_id);
+            ctx.submit("protect resume", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Protect(ProtectCommand::Show { strategy_id, owner }) => {
+            let owner = ctx.owner(owner)?;
+            let order = ctx.client.protective_order(&owner, strategy_id)?;
+            let strategy = ctx.client.strategy(&owner, strategy_id)?;
+            ctx.output.print(&ProtectionView::new(&order, &strategy))
+        }
+        Command::Yield(YieldCommand::Enable {
+            strategy_id,
+            asset,
+            protocols,
+            min_advantage_bps,
+            min_hold_seconds,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let params = YieldParams {
+                asset,
+                candidate_protocols: protocols
+                    .iter()
+                    .fold(0, |mask, protocol| mask | 1 << protocol),
+                min_apy_advantage_bps: min_advantage_bps,
+                min_hold_seconds,
+            };
+            let ix = instructions::enable_yield_rotation(&payer.pubkey(), strategy_id, params);
+            ctx.submit("yield enable", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Yield(YieldCommand::Disable { strategy_id }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::disable_yield_rotation(&payer.pubkey(), strategy_id);
+            ctx.submit("yield disable", &payer, &[ix], Some(strategy_id))
+        }
+        Command::Yield(YieldCommand::Rotate { strategy_id, owner }) => {
+            let payer = ctx.config.keypair()?;
+            let owner = owner.unwrap_or(payer.pubkey());
+            let rotation = ctx.client.yield_rotation(&owner, strategy_id)?;
+            let ix = instructions::rotate_yield(&payer.pubkey(), &rotation);
+            ctx.submit_for("yield rotate", &payer, &owner, &[ix], Some(strategy_id))
+        }
+        Command::Yield(YieldCommand::Show { strategy_id, owner }) => {
+            let owner = ctx.owner(owner)?;
+            let rotation = ctx.client.yield_rotation(&owner, strategy_id)?;
+            let strategy = ctx.client.strategy(&owner, strategy_id)?;
+            ctx.output.print(&YieldView::new(&rotation, &strategy))
+        }
+        Command::Apy(ApyCommand::Register {
+            position: (protocol, asset),
+            apy_bps,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::register_apy_tracker(&payer.pubkey(), protocol, asset, apy_bps);
+            ctx.submit("apy register", &payer, &[ix], None)
+        }
+        Command::Apy(ApyCommand::Report {
+            position: (protocol, asset),
+            apy_bps,
+        }) => {
+            let payer = ctx.config.keypair()?;
+            let ix = instructions::report_apy(&payer.pubkey(), protocol, asset, apy_bps);
+            ctx.submit("apy report", &payer, &[ix], None)
+        }
+        Command::Apy(ApyCommand::Show {
+            position: (protocol, asset),
+        }) => {
+            let tracker = ctx.client.apy_tracker(protocol, asset)?;
+            ctx.output.print(&ApyView::new(protocol, asset, &tracker))
+        }
+        Command::Execute { strategy_id, owner } => {
+            let payer = ctx.config.keypair()?;
+            let owner = owner.unwrap_or(payer.pubkey());
+            let ix = instructions::execute_strategy_as(&payer.pubkey(), &owner, strategy_id);
+            ctx.submit_for("execute", &payer, &owner, &[ix], Some(strategy_id))
+        }
+        Command::Rebalance { strategy_id, owner } => {
+            let payer = ctx.config.keypair()?;
+            let owner = owner.unwrap_or(payer.pubkey());
+            let strategy = ctx.client.strategy(&owner, strategy_id)?;
+            let price_accounts = ctx.client.price_accounts(&strategy)?;
+            let treasury = ctx.client.config()?.treasury;
+            let ix = instructions::rebalance_positions_as(
+                &payer.pubkey(),
+                &owner,
+                strategy_id,
+                &price_accounts,
+                &treasury,
+            );
+            ctx.submit_for("rebalance", &payer, &owner, &[ix], Some(strategy_id))
+        }
+        Command::Show { strategy_id, owner } => {
+            let owner = ctx.owner(owner)?;
+            let user_profile = find_user_profile_pda(&owner).0;
+            match strategy_id {
+                Some(strategy_id) => {
+                    let strategy = ctx.client.strategy(&owner, strategy_id)?;
+                    let address = find_strategy_pda(&user_profile, strategy_id).0;
+                    ctx.output.print(&StrategyView::new(&address, &strategy))
+                }
+                None => {
+                    let profile = ctx.client.user_profile(&owner)?;
+                    ctx.output.print(&UserView::new(&user_profile, &profile))
+                }
+            }
+        }
+        Command::List { owner, all } => {
+            let owner = if all { None } else { Some(ctx.owner(owner)?) };
+            let mut strategies = ctx.client.strategies(owner.as_ref())?;
+            strategies.sort_by_key(|(_, s)| (s.owner.to_string(), s.strategy_id));
+            let list = strategies
+                .iter()
+                .map(|(address, strategy)| StrategyView::new(address, strategy))
+                .collect();
+            ctx.output.print(&StrategyList(list))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let output = cli.output;
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            // 客户端错误自身已包含完整的错误链
+            let client_error = err.downcast_ref::<defi_tools_client::Error>();
+            let code = client_error.and_then(defi_tools_client::Error::error_code);
+            let report = ErrorReport {
+                error: match client_error {
+                    Some(client_error) => client_error.to_string(),
+                    None => format!("{err:#}"),
+                },
+                code: code.map(u32::from),
+                name: code.map(|code| code.name()),
+            };
+            match output {
+                OutputFormat::Json => {
+                    let _ = output.print(&report);
+                }
+                OutputFormat::Text => eprint!("{report}"),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    // 测试1: 命令行定义有效
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    // 测试2: 解析创建策略命令
+    #[test]
+    fn test_parse_strategy_create() {
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "--output",
+            "json",
+            "--dry-run",
+            "strategy",
+            "create",
+            "--alloc",
+            "raydium:sol:6000",
+            "--alloc",
+            "solend:usdc:4000",
+            "--auto-rebalance",
+        ])
+        .unwrap();
+
+        assert!(cli.dry_run);
+        assert_eq!(cli.output, OutputFormat::Json);
+        match cli.command {
+            Command::Strategy(StrategyCommand::Create {
+                allocations,
+                auto_rebalance,
+                interval,
+                ..
+            }) => {
+                assert_eq!(allocations.len(), 2);
+                assert_eq!(allocations[1].asset, 1);
+                assert!(auto_rebalance);
+                assert_eq!(interval, 86400);
+            }
+            _ => panic!("应解析为 strategy create"),
+        }
+    }
+
+    // 测试3: 更新策略时未指定的参数为空
+    #[test]
+    fn test_parse_strategy_update() {
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "strategy",
+            "update",
+            "2",
+            "--auto-rebalance",
+            "false",
+            "--max-slippage-bps",
+            "50",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Strategy(StrategyCommand::Update {
+                strategy_id,
+                allocations,
+                interval,
+                auto_rebalance,
+                max_slippage_bps,
+                ..
+            }) => {
+                assert_eq!(strategy_id, 2);
+                assert!(allocations.is_empty());
+                assert_eq!(interval, None);
+                assert_eq!(auto_rebalance, Some(false));
+                assert_eq!(max_slippage_bps, Some(50));
+            }
+            _ => panic!("应解析为 strategy update"),
+        }
+    }
+
+    // 测试4: --all 与 --owner 互斥
+    #[test]
+    fn test_list_all_conflicts_with_owner() {
+        let owner = Pubkey::new_unique().to_string();
+        assert!(Cli::try_parse_from(["defi-tools", "list", "--all", "--owner", &owner]).is_err());
+    }
+
+    // 测试5: 解析添加代理人命令，未指定的权限为 false
+    #[test]
+    fn test_parse_delegate_add() {
+        let delegate = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "delegate",
+            "add",
+            "1",
+            &delegate.to_string(),
+            "--rebalance",
+            "--expires-in",
+            "86400",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Delegate(DelegateCommand::Add {
+                strategy_id,
+                delegate: parsed,
+                execute,
+                rebalance,
+                update,
+                expires_in,
+                ..
+            }) => {
+                assert_eq!(strategy_id, 1);
+                assert_eq!(parsed, delegate);
+                assert!(!execute && rebalance && !update);
+                assert_eq!(expires_in, 86400);
+            }
+            _ => panic!("应解析为 delegate add"),
+        }
+
+        // 有效期是必填项
+        let result = Cli::try_parse_from([
+            "defi-tools",
+            "delegate",
+            "add",
+            "1",
+            "11111111111111111111111111111111",
+        ]);
+        assert!(result.is_err());
+    }
+
+    // 测试6: 提款和领取工单的接收地址默认为空
+    #[test]
+    fn test_parse_withdraw_destination() {
+        let cold = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "withdraw",
+            "0",
+            "--lamports",
+            "1000",
+            "--to",
+            &cold.to_string(),
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Withdraw { to, .. } => assert_eq!(to, Some(cold)),
+            _ => panic!("应解析为 withdraw"),
+        }
+
+        let cli = Cli::try_parse_from(["defi-tools", "withdrawal", "claim", "0", "3"]).unwrap();
+        match cli.command {
+            Command::Withdrawal(WithdrawalCommand::Claim { ticket_id, to, .. }) => {
+                assert_eq!(ticket_id, 3);
+                assert_eq!(to, None);
+            }
+            _ => panic!("应解析为 withdrawal claim"),
+        }
+
+        let cli = Cli::try_parse_from(["defi-tools", "allowlist", "disable"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Allowlist(AllowlistCommand::Disable)
+        ));
+    }
+
+    // 测试7: 解析周期提款限额命令和提款联署的监护人
+    #[test]
+    fn test_parse_spending_limit() {
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "spending-limit",
+            "set",
+            "--max-lamports",
+            "1000000000",
+            "--window",
+            "86400",
+            "--guardian",
+            "guardian.json",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::SpendingLimit(SpendingLimitCommand::Set {
+                max_lamports,
+                window,
+                new_guardian,
+                guardian,
+            }) => {
+                assert_eq!(max_lamports, 1_000_000_000);
+                assert_eq!(window, 86400);
+                assert_eq!(new_guardian, None);
+                assert_eq!(guardian, Some(PathBuf::from("guardian.json")));
+            }
+            _ => panic!("应解析为 spending-limit set"),
+        }
+
+        let cli = Cli::try_parse_from(["defi-tools", "spending-limit", "clear"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::SpendingLimit(SpendingLimitCommand::Clear {
+                remove_guardian: false,
+                guardian: None
+            })
+        ));
+
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "withdraw",
+            "0",
+            "--lamports",
+            "1000",
+            "--guardian",
+            "guardian.json",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Withdraw { to, guardian, .. } => {
+                assert_eq!(to, None);
+                assert_eq!(guardian, Some(PathBuf::from("guardian.json")));
+            }
+            _ => panic!("应解析为 withdraw"),
+        }
+    }
+
+    // 测试8: 解析所有权转移命令，迁移默认不指定策略
+    #[test]
+    fn test_parse_owner() {
+        let new_owner = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "owner",
+            "propose",
+            &new_owner.to_string(),
+            "--guardian",
+            "guardian.json",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Owner(OwnerCommand::Propose {
+                new_owner: parsed,
+                guardian,
+            }) => {
+                assert_eq!(parsed, new_owner);
+                assert_eq!(guardian, Some(PathBuf::from("guardian.json")));
+            }
+            _ => panic!("应解析为 owner propose"),
+        }
+
+        let cli = Cli::try_parse_from(["defi-tools", "owner", "migrate", &new_owner.to_string()])
+            .unwrap();
+        match cli.command {
+            Command::Owner(OwnerCommand::Migrate {
+                previous_owner,
+                strategy_id,
+            }) => {
+                assert_eq!(previous_owner, new_owner);
+                assert_eq!(strategy_id, None);
+            }
+            _ => panic!("应解析为 owner migrate"),
+        }
+
+        assert!(Cli::try_parse_from(["defi-tools", "owner", "accept"]).is_err());
+    }
+
+    // 测试9: 解析模板命令，说明默认为空
+    #[test]
+    fn test_parse_template() {
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "template",
+            "publish",
+            "3",
+            "--alloc",
+            "orca:sol:10000",
+            "--risk-level",
+            "2",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Template(TemplateCommand::Publish {
+                template_id,
+                allocations,
+                risk_level,
+                description,
+                ..
+            }) => {
+                assert_eq!(template_id, 3);
+                assert_eq!(allocations.len(), 1);
+                assert_eq!(risk_level, 2);
+                assert!(description.is_empty());
+            }
+            _ => panic!("应解析为 template publish"),
+        }
+
+        let publisher = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "strategy",
+            "from-template",
+            &publisher.to_string(),
+            "3",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Strategy(StrategyCommand::FromTemplate {
+                publisher: parsed,
+                template_id,
+            }) => {
+                assert_eq!(parsed, publisher);
+                assert_eq!(template_id, 3);
+            }
+            _ => panic!("应解析为 strategy from-template"),
+        }
+
+        assert!(Cli::try_parse_from(["defi-tools", "template", "publish", "3"]).is_err());
+    }
+
+    // 测试10: 解析跟投命令，未指定时不限制权重、不收费、不延迟
+    #[test]
+    fn test_parse_follow() {
+        let leader_owner = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "follow",
+            "add",
+            "1",
+            &leader_owner.to_string(),
+            "4",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Follow(FollowCommand::Add {
+                strategy_id,
+                leader_owner: parsed,
+                leader_id,
+                max_weight_bps,
+                delay,
+                leader_fee_bps,
+            }) => {
+                assert_eq!(strategy_id, 1);
+                assert_eq!(parsed, leader_owner);
+                assert_eq!(leader_id, 4);
+                assert_eq!((max_weight_bps, delay, leader_fee_bps), (10000, 0, 0));
+            }
+            _ => panic!("应解析为 follow add"),
+        }
+
+        let owner = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "follow",
+            "sync",
+            "1",
+            "--owner",
+            &owner.to_string(),
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Follow(FollowCommand::Sync {
+                strategy_id,
+                owner: parsed,
+            }) => {
+                assert_eq!(strategy_id, 1);
+                assert_eq!(parsed, Some(owner));
+            }
+            _ => panic!("应解析为 follow sync"),
+        }
+
+        assert!(Cli::try_parse_from(["defi-tools", "follow", "add", "1"]).is_err());
+    }
+
+    // 测试11: 解析定投命令，未指定时不限制价格
+    #[test]
+    fn test_parse_dca() {
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "dca",
+            "create",
+            "2",
+            "solend:usdc",
+            "orca:sol",
+            "--increment-lamports",
+            "1000000",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Dca(DcaCommand::Create {
+                strategy_id,
+                quote,
+                target,
+                increment_lamports,
+                max_price,
+            }) => {
+                assert_eq!(strategy_id, 2);
+                assert_eq!(quote, (1, 1));
+                assert_eq!(target, (2, 0));
+                assert_eq!(increment_lamports, 1_000_000);
+                assert_eq!(max_price, 0);
+            }
+            _ => panic!("应解析为 dca create"),
+        }
+
+        assert!(Cli::try_parse_from([
+            "defi-tools",
+            "dca",
+            "create",
+            "2",
+            "solend:usdc:5000",
+            "orca:sol",
+            "--increment-lamports",
+            "1",
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from([
+            "defi-tools",
+            "dca",
+            "create",
+            "2",
+            "solend:usdc",
+            "orca:sol"
+        ])
+        .is_err());
+    }
+
+    // 测试12: 解析保护单命令，至少需要指定安全头寸
+    #[test]
+    fn test_parse_protect() {
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "protect",
+            "set",
+            "3",
+            "solend:usdc",
+            "--max-drawdown-bps",
+            "1500",
+            "--take-profit",
+            "2000000000",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Protect(ProtectCommand::Set {
+                strategy_id,
+                safe,
+                stop_loss,
+                max_drawdown_bps,
+                take_profit,
+            }) => {
+                assert_eq!(strategy_id, 3);
+                assert_eq!(safe, (1, 1));
+                assert_eq!(stop_loss, 0);
+                assert_eq!(max_drawdown_bps, 1500);
+                assert_eq!(take_profit, 2_000_000_000);
+            }
+            _ => panic!("应解析为 protect set"),
+        }
+
+        assert!(Cli::try_parse_from(["defi-tools", "protect", "set", "3"]).is_err());
+        assert!(matches!(
+            Cli::try_parse_from(["defi-tools", "protect", "resume", "3"])
+                .unwrap()
+                .command,
+            Command::Protect(ProtectCommand::Resume { strategy_id: 3 })
+        ));
+    }
+
+    // 测试13: 解析收益率轮动命令，候选协议以逗号分隔且至少指定一个
+    #[test]
+    fn test_parse_yield() {
+        let cli = Cli::try_parse_from([
+            "defi-tools",
+            "yield",
+            "enable",
+            "2",
+            "usdc",
+            "--protocols",
+            "solend,mango",
+            "--min-advantage-bps",
+            "50",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Yield(YieldCommand::Enable {
+                strategy_id,
+                asset,
+                protocols,
+                min_advantage_bps,
+                min_hold_seconds,
+            }) => {
+                assert_eq!(strategy_id, 2);
+                assert_eq!(asset, 1);
+                assert_eq!(protocols, vec![1, 3]);
+                assert_eq!(min_advantage_bps, 50);
+                assert_eq!(min_hold_seconds, 0);
+            }
+            _ => panic!("应解析为 yield enable"),
+        }
+
+        assert!(Cli::try_parse_from(["defi-tools", "yield", "enable", "2", "usdc"]).is_err());
+        assert!(Cli::try_parse_from([
+            "defi-tools",
+            "yield",
+            "enable",
+            "2",
+            "usdc",
+            "--protocols",
+            "solend,aave",
+        ])
+        .is_err());
+        assert!(matches!(
+            Cli::try_parse_from(["defi-tools", "apy", "report", "mango:usdc", "640"])
+                .unwrap()
+                .command,
+            Command::Apy(ApyCommand::Report {
+                position: (3, 1),
+                apy_bps: 640,
+            })
+        ));
+    }
+}
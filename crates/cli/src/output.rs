@@ -0,0 +1,771 @@
+use crate::args::protocol_name;
+use clap::ValueEnum;
+use defi_tools::apy::yield_position;
+use defi_tools::{
+    Allocation, DcaSchedule, PositionTracker, ProtectiveOrder, StrategyConfig, StrategyDelegate,
+    StrategyFollow, StrategyTemplate, UserProfile, WithdrawalDestination, WithdrawalQueue,
+    WithdrawalTicket, YieldRotation,
+};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn print<T: Serialize + fmt::Display>(self, value: &T) -> anyhow::Result<()> {
+        match self {
+            OutputFormat::Text => print!("{value}"),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        }
+        Ok(())
+    }
+}
+
+// 发送或模拟交易的结果
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxReport {
+    pub action: &'static str,
+    pub simulated: bool,
+    pub signature: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    pub strategy_id: Option<u64>,
+    pub strategy: Option<String>,
+}
+
+impl fmt::Display for TxReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.simulated {
+            writeln!(f, "{}: 模拟成功", self.action)?;
+        } else {
+            writeln!(f, "{}: 交易已确认", self.action)?;
+        }
+        if let Some(signature) = &self.signature {
+            writeln!(f, "  签名: {signature}")?;
+        }
+        if let Some(strategy_id) = self.strategy_id {
+            writeln!(f, "  策略ID: {strategy_id}")?;
+        }
+        if let Some(strategy) = &self.strategy {
+            writeln!(f, "  策略地址: {strategy}")?;
+        }
+        if let Some(units) = self.units_consumed {
+            writeln!(f, "  计算单元: {units}")?;
+        }
+        for log in &self.logs {
+            writeln!(f, "  {log}")?;
+        }
+        Ok(())
+    }
+}
+
+// 默认公钥表示未设置
+fn optional_key(key: &Pubkey) -> Option<String> {
+    (*key != Pubkey::default()).then(|| key.to_string())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserView {
+    pub address: String,
+    pub owner: String,
+    pub risk_level: u8,
+    pub strategy_counter: u64,
+    pub total_value_lamports: u64,
+    pub is_paused: bool,
+    pub allowlist_enabled: bool,
+    pub allowlist_disable_at: i64,
+    pub spending_max_lamports: u64,
+    pub spending_window_seconds: u32,
+    pub spending_window_start: i64,
+    pub spending_spent_lamports: u64,
+    pub spending_guardian: Option<String>,
+    pub pending_owner: Option<String>,
+    pub pending_owner_at: i64,
+    pub migrated_to: Option<String>,
+    pub migrated_from: Option<String>,
+    pub last_activity: i64,
+}
+
+impl UserView {
+    pub fn new(address: &Pubkey, profile: &UserProfile) -> Self {
+        let limit = &profile.spending_limit;
+        Self {
+            address: address.to_string(),
+            owner: profile.owner.to_string(),
+            risk_level: profile.risk_level,
+            strategy_counter: profile.strategy_counter,
+            total_value_lamports: profile.total_value_lamports,
+            is_paused: profile.is_paused,
+            allowlist_enabled: profile.allowlist_enabled,
+            allowlist_disable_at: profile.allowlist_disable_at,
+            spending_max_lamports: limit.max_lamports,
+            spending_window_seconds: limit.window_seconds,
+            spending_window_start: limit.window_start,
+            spending_spent_lamports: limit.spent_lamports,
+            spending_guardian: limit.has_guardian().then(|| limit.guardian.to_string()),
+            pending_owner: optional_key(&profile.pending_owner),
+            pending_owner_at: profile.pending_owner_at,
+            migrated_to: optional_key(&profile.migrated_to),
+            migrated_from: optional_key(&profile.migrated_from),
+            last_activity: profile.last_activity,
+        }
+    }
+}
+
+impl fmt::Display for UserView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "用户配置 {}", self.address)?;
+        writeln!(f, "  所有者: {}", self.owner)?;
+        writeln!(f, "  风险等级: {}", self.risk_level)?;
+        writeln!(f, "  策略数量: {}", self.strategy_counter)?;
+        writeln!(f, "  总价值(lamports): {}", self.total_value_lamports)?;
+        writeln!(f, "  已暂停: {}", self.is_paused)?;
+        match (self.allowlist_enabled, self.allowlist_disable_at) {
+            (false, _) => writeln!(f, "  提款白名单: 未启用")?,
+            (true, 0) => writeln!(f, "  提款白名单: 已启用")?,
+            (true, at) => writeln!(f, "  提款白名单: 已启用，{at} 起关闭")?,
+        }
+        match self.spending_max_lamports {
+            0 => writeln!(f, "  提款限额: 未设置")?,
+            max => writeln!(
+                f,
+                "  提款限额: 每 {} 秒 {max} lamports，{} 起的窗口已转出 {}",
+                self.spending_window_seconds,
+                self.spending_window_start,
+                self.spending_spent_lamports
+            )?,
+        }
+        if let Some(guardian) = &self.spending_guardian {
+            writeln!(f, "  监护人: {guardian}")?;
+        }
+        if let Some(pending) = &self.pending_owner {
+            writeln!(
+                f,
+                "  待接受的新所有者: {pending}，{} 起可接受",
+                self.pending_owner_at
+            )?;
+        }
+        if let Some(migrated_from) = &self.migrated_from {
+            writeln!(f, "  转移自: {migrated_from}")?;
+        }
+        if let Some(migrated_to) = &self.migrated_to {
+            writeln!(f, "  已转移给: {migrated_to}")?;
+        }
+        writeln!(f, "  最后活动时间: {}", self.last_activity)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationView {
+    pub protocol: String,
+    pub asset: u8,
+    pub target_weight_bps: u16,
+}
+
+fn allocation_views(allocations: &[Allocation]) -> Vec<AllocationView> {
+    allocations
+        .iter()
+        .map(|a| AllocationView {
+            protocol: protocol_name(a.protocol),
+            asset: a.asset,
+            target_weight_bps: a.target_weight_bps,
+        })
+        .collect()
+}
+
+fn write_allocations(f: &mut fmt::Formatter<'_>, allocations: &[AllocationView]) -> fmt::Result {
+    for a in allocations {
+        writeln!(
+            f,
+            "  分配: {} / 资产{} / {} bps",
+            a.protocol, a.asset, a.target_weight_bps
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyView {
+    pub address: String,
+    pub owner: String,
+    pub strategy_id: u64,
+    pub allocations: Vec<AllocationView>,
+    pub time_interval_seconds: u64,
+    pub max_deviation_bps: u16,
+    pub auto_rebalance: bool,
+    pub max_slippage_bps: u16,
+    pub total_value_lamports: u64,
+    pub locked_lamports: u64,
+    pub created_at: i64,
+    pub last_executed_at: i64,
+    pub paused_at: i64,
+}
+
+impl StrategyView {
+    pub fn new(address: &Pubkey, strategy: &StrategyConfig) -> Self {
+        let condition = &strategy.rebalance_condition;
+        Self {
+            address: address.to_string(),
+            owner: strategy.owner.to_string(),
+            strategy_id: strategy.strategy_id,
+            allocations: allocation_views(&strategy.allocations),
+            time_interval_seconds: condition.time_interval_seconds,
+            max_deviation_bps: condition.max_deviation_bps,
+            auto_rebalance: condition.auto_rebalance,
+            max_slippage_bps: strategy.max_slippage_bps,
+            total_value_lamports: strategy.total_value_lamports,
+            locked_lamports: strategy.withdrawal_queue.locked_lamports,
+            created_at: strategy.created_at,
+            last_executed_at: strategy.last_executed_at,
+            paused_at: strategy.paused_at,
+        }
+    }
+}
+
+impl fmt::Display for StrategyView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "策略 #{} {}", self.strategy_id, self.address)?;
+        writeln!(f, "  所有者: {}", self.owner)?;
+        write_allocations(f, &self.allocations)?;
+        writeln!(
+            f,
+            "  再平衡: 每 {} 秒 / 偏差 {} bps / 自动 {}",
+            self.time_interval_seconds, self.max_deviation_bps, self.auto_rebalance
+        )?;
+        writeln!(f, "  最大滑点: {} bps", self.max_slippage_bps)?;
+        writeln!(f, "  总价值(lamports): {}", self.total_value_lamports)?;
+        writeln!(f, "  排队提款锁定(lamports): {}", self.locked_lamports)?;
+        writeln!(f, "  创建时间: {}", self.created_at)?;
+        writeln!(f, "  最后执行时间: {}", self.last_executed_at)?;
+        if self.paused_at != 0 {
+            writeln!(f, "  保护单触发，已暂停: {}", self.paused_at)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct StrategyList(pub Vec<StrategyView>);
+
+impl fmt::Display for StrategyList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "没有找到策略");
+        }
+        for strategy in &self.0 {
+            write!(f, "{strategy}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateView {
+    pub address: String,
+    pub publisher: String,
+    pub template_id: u64,
+    pub allocations: Vec<AllocationView>,
+    pub time_interval_seconds: u64,
+    pub max_deviation_bps: u16,
+    pub auto_rebalance: bool,
+    pub max_slippage_bps: u16,
+    pub risk_level: u8,
+    pub description_hash: String,
+    pub created_at: i64,
+    pub strategies_created: u64,
+}
+
+impl TemplateView {
+    pub fn new(address: &Pubkey, template: &StrategyTemplate) -> Self {
+        let condition = &template.rebalance_condition;
+        Self {
+            address: address.to_string(),
+            publisher: template.publisher.to_string(),
+            template_id: template.template_id,
+            allocations: allocation_views(&template.allocations),
+            time_interval_seconds: condition.time_interval_seconds,
+            max_deviation_bps: condition.max_deviation_bps,
+            auto_rebalance: condition.auto_rebalance,
+            max_slippage_bps: template.max_slippage_bps,
+            risk_level: template.risk_level,
+            description_hash: template
+                .description_hash
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            created_at: template.created_at,
+            strategies_created: template.strategies_created,
+        }
+    }
+}
+
+impl fmt::Display for TemplateView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "模板 #{} {}", self.template_id, self.address)?;
+        writeln!(f, "  发布者: {}", self.publisher)?;
+        write_allocations(f, &self.allocations)?;
+        writeln!(
+            f,
+            "  再平衡: 每 {} 秒 / 偏差 {} bps / 自动 {}",
+            self.time_interval_seconds, self.max_deviation_bps, self.auto_rebalance
+        )?;
+        writeln!(f, "  最大滑点: {} bps", self.max_slippage_bps)?;
+        writeln!(f, "  风险等级: {}", self.risk_level)?;
+        writeln!(f, "  说明哈希: {}", self.description_hash)?;
+        writeln!(f, "  已创建策略: {}", self.strategies_created)?;
+        writeln!(f, "  发布时间: {}", self.created_at)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct TemplateList(pub Vec<TemplateView>);
+
+impl fmt::Display for TemplateList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "没有找到模板");
+        }
+        for template in &self.0 {
+            write!(f, "{template}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketView {
+    pub ticket_id: u64,
+    pub amount_lamports: u64,
+    pub requested_at: i64,
+    pub unlocks_at: i64,
+    // 头寸已在再平衡中解除，不必等待锁定到期
+    pub unwound: bool,
+}
+
+impl TicketView {
+    pub fn new(ticket: &WithdrawalTicket, queue: &WithdrawalQueue) -> Self {
+        Self {
+            ticket_id: ticket.ticket_id,
+            amount_lamports: ticket.amount_lamports,
+            requested_at: ticket.requested_at,
+            unlocks_at: ticket.unlocks_at,
+            unwound: queue.is_unwound(ticket.ticket_id),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct TicketList(pub Vec<TicketView>);
+
+impl fmt::Display for TicketList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "没有待领取的提款工单");
+        }
+        for ticket in &self.0 {
+            writeln!(
+                f,
+                "工单 #{}: {} lamports / 申请于 {} / 到期 {} / 头寸已解除 {}",
+                ticket.ticket_id,
+                ticket.amount_lamports,
+                ticket.requested_at,
+                ticket.unlocks_at,
+                ticket.unwound
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegateView {
+    pub delegate: String,
+    pub execute: bool,
+    pub rebalance: bool,
+    pub update: bool,
+    pub max_weight_change_bps: u16,
+    pub max_slippage_bps: u16,
+    pub expires_at: i64,
+}
+
+impl DelegateView {
+    pub fn new(delegate: &StrategyDelegate) -> Self {
+        let scope = delegate.scope;
+        Self {
+            delegate: delegate.delegate.to_string(),
+            execute: scope.execute,
+            rebalance: scope.rebalance,
+            update: scope.update,
+            max_weight_change_bps: scope.max_weight_change_bps,
+            max_slippage_bps: scope.max_slippage_bps,
+            expires_at: delegate.expires_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct DelegateList(pub Vec<DelegateView>);
+
+impl fmt::Display for DelegateList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "没有代理人");
+        }
+        for delegate in &self.0 {
+            writeln!(
+                f,
+                "{}: 执行 {} / 再平衡 {} / 更新 {} (权重变化 {} bps, 滑点 {} bps) / 到期 {}",
+                delegate.delegate,
+                delegate.execute,
+                delegate.rebalance,
+                delegate.update,
+                delegate.max_weight_change_bps,
+                delegate.max_slippage_bps,
+                delegate.expires_at
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowView {
+    pub strategy: String,
+    pub owner: String,
+    pub leader: String,
+    pub max_weight_bps: u16,
+    pub delay_seconds: u32,
+    pub leader_fee_bps: u16,
+    pub followed_at: i64,
+    pub synced_at: i64,
+    pub syncs: u64,
+}
+
+impl FollowView {
+    pub fn new(follow: &StrategyFollow) -> Self {
+        Self {
+            strategy: follow.strategy.to_string(),
+            owner: follow.owner.to_string(),
+            leader: follow.leader.to_string(),
+            max_weight_bps: follow.max_weight_bps,
+            delay_seconds: follow.delay_seconds,
+            leader_fee_bps: follow.leader_fee_bps,
+            followed_at: follow.followed_at,
+            synced_at: follow.synced_at,
+            syncs: follow.syncs,
+        }
+    }
+}
+
+impl fmt::Display for FollowView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "跟随策略 {}", self.strategy)?;
+        writeln!(f, "  所有者: {}", self.owner)?;
+        writeln!(f, "  领投策略: {}", self.leader)?;
+        writeln!(
+            f,
+            "  单项权重上限: {} bps / 同步延迟: {} 秒 / 跟投费用: {} bps",
+            self.max_weight_bps, self.delay_seconds, self.leader_fee_bps
+        )?;
+        writeln!(f, "  跟随时间: {}", self.followed_at)?;
+        writeln!(f, "  已同步: {} 次 (最近 {})", self.syncs, self.synced_at)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct FollowList(pub Vec<FollowView>);
+
+impl fmt::Display for FollowList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "没有跟随者");
+        }
+        for follow in &self.0 {
+            write!(f, "{follow}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DcaView {
+    pub strategy: String,
+    pub owner: String,
+    pub quote_protocol: String,
+    pub quote_asset: u8,
+    pub target_protocol: String,
+    pub target_asset: u8,
+    pub increment_lamports: u64,
+    pub max_price_quote: u64,
+    pub created_at: i64,
+    pub purchased_lamports: u64,
+    pub purchases: u64,
+}
+
+impl DcaView {
+    pub fn new(schedule: &DcaSchedule) -> Self {
+        let params = &schedule.params;
+        Self {
+            strategy: schedule.strategy.to_string(),
+            owner: schedule.owner.to_string(),
+            quote_protocol: protocol_name(params.quote_protocol),
+            quote_asset: params.quote_asset,
+            target_protocol: protocol_name(params.target_protocol),
+            target_asset: params.target_asset,
+            increment_lamports: params.increment_lamports,
+            max_price_quote: params.max_price_quote,
+            created_at: schedule.created_at,
+            purchased_lamports: schedule.purchased_lamports,
+            purchases: schedule.purchases,
+        }
+    }
+}
+
+impl fmt::Display for DcaView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "定投策略 {}", self.strategy)?;
+        writeln!(f, "  所有者: {}", self.owner)?;
+        writeln!(
+            f,
+            "  {} / 资产{} -> {} / 资产{}",
+            self.quote_protocol, self.quote_asset, self.target_protocol, self.target_asset
+        )?;
+        writeln!(f, "  每次: {} lamports", self.increment_lamports)?;
+        if self.max_price_quote > 0 {
+            writeln!(f, "  价格上限: {}", self.max_price_quote)?;
+        }
+        writeln!(f, "  创建时间: {}", self.created_at)?;
+        writeln!(
+            f,
+            "  已定投: {} 次 / {} lamports",
+            self.purchases, self.purchased_lamports
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectionView {
+    pub strategy: String,
+    pub owner: String,
+    pub stop_loss_lamports: u64,
+    pub max_drawdown_bps: u16,
+    pub take_profit_lamports: u64,
+    pub safe_protocol: String,
+    pub safe_asset: u8,
+    pub value_lamports: u64,
+    pub peak_value_lamports: u64,
+    pub marked_at: i64,
+    pub created_at: i64,
+}
+
+impl ProtectionView {
+    pub fn new(order: &ProtectiveOrder, strategy: &StrategyConfig) -> Self {
+        let params = &order.params;
+        Self {
+            strategy: order.strategy.to_string(),
+            owner: order.owner.to_string(),
+            stop_loss_lamports: params.stop_loss_lamports,
+            max_drawdown_bps: params.max_drawdown_bps,
+            take_profit_lamports: params.take_profit_lamports,
+            safe_protocol: protocol_name(params.safe_protocol),
+            safe_asset: params.safe_asset,
+            value_lamports: order.marked_value_lamports,
+            peak_value_lamports: strategy.peak_value_lamports,
+            marked_at: order.marked_at,
+            created_at: order.created_at,
+        }
+    }
+}
+
+impl fmt::Display for ProtectionView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "保护单 {}", self.strategy)?;
+        writeln!(f, "  所有者: {}", self.owner)?;
+        if self.stop_loss_lamports > 0 {
+            writeln!(f, "  止损: {} lamports", self.stop_loss_lamports)?;
+        }
+        if self.max_drawdown_bps > 0 {
+            writeln!(f, "  最大回撤: {} bps", self.max_drawdown_bps)?;
+        }
+        if self.take_profit_lamports > 0 {
+            writeln!(f, "  止盈: {} lamports", self.take_profit_lamports)?;
+        }
+        writeln!(
+            f,
+            "  安全头寸: {} / 资产{}",
+            self.safe_protocol, self.safe_asset
+        )?;
+        writeln!(
+            f,
+            "  估值: {} lamports / 峰值 {} lamports (于 {})",
+            self.value_lamports, self.peak_value_lamports, self.marked_at
+        )?;
+        writeln!(f, "  创建时间: {}", self.created_at)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YieldView {
+    pub strategy: String,
+    pub owner: String,
+    pub asset: u8,
+    pub candidates: Vec<String>,
+    // 轮动头寸当前所在的协议，分配项被修改后可能不存在
+    pub protocol: Option<String>,
+    pub min_apy_advantage_bps: u32,
+    pub min_hold_seconds: u32,
+    pub held_since: i64,
+    pub rotations: u64,
+    pub created_at: i64,
+}
+
+impl YieldView {
+    pub fn new(rotation: &YieldRotation, strategy: &StrategyConfig) -> Self {
+        let params = &rotation.params;
+        Self {
+            strategy: rotation.strategy.to_string(),
+            owner: rotation.owner.to_string(),
+            asset: params.asset,
+            candidates: params.candidates().into_iter().map(protocol_name).collect(),
+            protocol: yield_position(params, &strategy.allocations)
+                .ok()
+                .map(|index| protocol_name(strategy.allocations[index].protocol)),
+            min_apy_advantage_bps: params.min_apy_advantage_bps,
+            min_hold_seconds: params.min_hold_seconds,
+            held_since: rotation.held_since,
+            rotations: rotation.rotations,
+            created_at: rotation.created_at,
+        }
+    }
+}
+
+impl fmt::Display for YieldView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "收益率轮动 {}", self.strategy)?;
+        writeln!(f, "  所有者: {}", self.owner)?;
+        writeln!(
+            f,
+            "  资产{}: {} 之间轮动",
+            self.asset,
+            self.candidates.join(", ")
+        )?;
+        match &self.protocol {
+            Some(protocol) => writeln!(f, "  当前协议: {} (自 {})", protocol, self.held_since)?,
+            None => writeln!(f, "  当前协议: 无，策略中没有唯一的轮动头寸")?,
+        }
+        writeln!(
+            f,
+            "  最小APY优势: {} bps / 最短持有: {} 秒",
+            self.min_apy_advantage_bps, self.min_hold_seconds
+        )?;
+        writeln!(f, "  已轮动: {} 次", self.rotations)?;
+        writeln!(f, "  创建时间: {}", self.created_at)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApyView {
+    pub protocol: String,
+    pub asset: u8,
+    pub initial_apy_bps: u32,
+    pub current_apy_bps: u32,
+    pub last_updated_at: i64,
+}
+
+impl ApyView {
+    pub fn new(protocol: u8, asset: u8, tracker: &PositionTracker) -> Self {
+        Self {
+            protocol: protocol_name(protocol),
+            asset,
+            initial_apy_bps: tracker.initial_apy_bps,
+            current_apy_bps: tracker.current_apy_bps,
+            last_updated_at: tracker.last_updated_at,
+        }
+    }
+}
+
+impl fmt::Display for ApyView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "利率 {} / 资产{}", self.protocol, self.asset)?;
+        writeln!(
+            f,
+            "  APY: {} bps (初始 {} bps)",
+            self.current_apy_bps, self.initial_apy_bps
+        )?;
+        writeln!(f, "  更新时间: {}", self.last_updated_at)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DestinationView {
+    pub destination: String,
+    pub added_at: i64,
+    pub activates_at: i64,
+}
+
+impl DestinationView {
+    pub fn new(entry: &WithdrawalDestination) -> Self {
+        Self {
+            destination: entry.destination.to_string(),
+            added_at: entry.added_at,
+            activates_at: entry.activates_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct DestinationList(pub Vec<DestinationView>);
+
+impl fmt::Display for DestinationList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "没有白名单地址");
+        }
+        for entry in &self.0 {
+            writeln!(
+                f,
+                "{}: 添加于 {} / 生效于 {}",
+                entry.destination, entry.added_at, entry.activates_at
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// 命令失败时的输出
+#[derive(Serialize)]
+pub struct ErrorReport {
+    pub error: String,
+    pub code: Option<u32>,
+    pub name: Option<String>,
+}
+
+impl fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "错误: {}", self.error)
+    }
+}
@@ -0,0 +1,366 @@
+use crate::error::{program_error, Error, Result};
+use crate::pda::{
+    find_apy_tracker_pda, find_asset_registry_pda, find_config_pda, find_dca_schedule_pda,
+    find_protective_order_pda, find_strategy_delegate_pda, find_strategy_follow_pda,
+    find_strategy_pda, find_strategy_template_pda, find_user_profile_pda,
+    find_withdrawal_destination_pda, find_withdrawal_ticket_pda, find_yield_rotation_pda,
+};
+use anchor_lang::{AccountDeserialize, Discriminator};
+use defi_tools::{
+    AssetRegistry, DcaSchedule, GlobalConfig, PositionTracker, ProtectiveOrder, StrategyConfig,
+    StrategyDelegate, StrategyFollow, StrategyTemplate, UserProfile, WithdrawalDestination,
+    WithdrawalTicket, YieldRotation,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+use solana_rpc_client_api::response::RpcSimulateTransactionResult;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+// 账户数据中 owner 字段的偏移(紧跟8字节判别符)，模板的 publisher 字段位于相同位置
+const OWNER_OFFSET: usize = 8;
+
+// 代理人账户中 strategy 字段的偏移(判别符和 owner 之后)
+const DELEGATE_STRATEGY_OFFSET: usize = 8 + 32;
+
+// 跟投关系账户中 leader 字段的偏移(判别符、owner 和 strategy 之后)
+const FOLLOW_LEADER_OFFSET: usize = 8 + 32 + 32;
+
+// defi-tools 程序的 RPC 客户端，负责账户读取、交易发送和模拟
+pub struct Client {
+    rpc: RpcClient,
+}
+
+impl Client {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_rpc(RpcClient::new_with_commitment(
+            url.into(),
+            CommitmentConfig::confirmed(),
+        ))
+    }
+
+    pub fn with_rpc(rpc: RpcClient) -> Self {
+        Self { rpc }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    // 读取并解码程序拥有的账户
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .ok_or(Error::AccountNotFound(*address))?;
+        if account.owner != defi_tools::ID {
+            return Err(Error::Decode {
+                address: *address,
+                message: format!("账户所有者为 {}", account.owner),
+            });
+        }
+        T::try_deserialize(&mut account.data.as_slice()).map_err(|e| Error::Decode {
+            address: *address,
+            message: e.to_string(),
+        })
+    }
+
+    pub fn config(&self) -> Result<GlobalConfig> {
+        self.account(&find_config_pda().0)
+    }
+
+    pub fn asset_registry(&self, asset_id: u8) -> Result<AssetRegistry> {
+        self.account(&find_asset_registry_pda(asset_id).0)
+    }
+
+    pub fn user_profile(&self, owner: &Pubkey) -> Result<UserProfile> {
+        self.account(&find_user_profile_pda(owner).0)
+    }
+
+    pub fn strategy(&self, owner: &Pubkey, strategy_id: u64) -> Result<StrategyConfig> {
+        let user_profile = find_user_profile_pda(owner).0;
+        self.account(&find_strategy_pda(&user_profile, strategy_id).0)
+    }
+
+    // 返回策略提款队列中尚未领取或取消的工单，按ID排列
+    pub fn withdrawal_tickets(
+        &self,
+        owner: &Pubkey,
+        strategy_id: u64,
+    ) -> Result<Vec<WithdrawalTicket>> {
+        let strategy_config = find_strategy_pda(&find_user_profile_pda(owner).0, strategy_id).0;
+        let queue = self
+            .account::<StrategyConfig>(&strategy_config)?
+            .withdrawal_queue;
+        let mut tickets = Vec::new();
+        for ticket_id in queue.head_ticket_id..queue.next_ticket_id {
+            match self.account(&find_withdrawal_ticket_pda(&strategy_config, ticket_id).0) {
+                Ok(ticket) => tickets.push(ticket),
+                Err(Error::AccountNotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(tickets)
+    }
+
+    pub fn strategy_delegate(
+        &self,
+        strategy: &Pubkey,
+        delegate: &Pubkey,
+    ) -> Result<StrategyDelegate> {
+        self.account(&find_strategy_delegate_pda(strategy, delegate).0)
+    }
+
+    // 返回策略的全部代理人，包括已过期但尚未撤销的
+    pub fn strategy_delegates(
+        &self,
+        owner: &Pubkey,
+        strategy_id: u64,
+    ) -> Result<Vec<StrategyDelegate>> {
+        let strategy_config = find_strategy_pda(&find_user_profile_pda(owner).0, strategy_id).0;
+        let filters = vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &StrategyDelegate::DISCRIMINATOR,
+            )),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                DELEGATE_STRATEGY_OFFSET,
+                strategy_config.as_ref(),
+            )),
+        ];
+        Ok(self
+            .program_accounts(filters)?
+            .into_iter()
+            .map(|(_, delegate)| delegate)
+            .collect())
+    }
+
+    pub fn withdrawal_destination(
+        &self,
+        owner: &Pubkey,
+        destination: &Pubkey,
+    ) -> Result<WithdrawalDestination> {
+        self.account(&find_withdrawal_destination_pda(owner, destination).0)
+    }
+
+    // 返回用户的全部提款白名单地址，包括尚未生效的
+    pub fn withdrawal_destinations(&self, owner: &Pubkey) -> Result<Vec<WithdrawalDestination>> {
+        let filters = vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &WithdrawalDestination::DISCRIMINATOR,
+            )),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(OWNER_OFFSET, owner.as_ref())),
+        ];
+        Ok(self
+            .program_accounts(filters)?
+            .into_iter()
+            .map(|(_, destination)| destination)
+            .collect())
+    }
+
+    pub fn strategy_template(
+        &self,
+        publisher: &Pubkey,
+        template_id: u64,
+    ) -> Result<StrategyTemplate> {
+        self.account(&find_strategy_template_pda(publisher, template_id).0)
+    }
+
+    // 返回指定发布者的全部模板；publisher 为 None 时返回所有模板
+    pub fn strategy_templates(
+        &self,
+        publisher: Option<&Pubkey>,
+    ) -> Result<Vec<(Pubkey, StrategyTemplate)>> {
+        let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &StrategyTemplate::DISCRIMINATOR,
+        ))];
+        if let Some(publisher) = publisher {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                OWNER_OFFSET,
+                publisher.as_ref(),
+            )));
+        }
+        self.program_accounts(filters)
+    }
+
+    pub fn strategy_follow(&self, owner: &Pubkey, strategy_id: u64) -> Result<StrategyFollow> {
+        let user_profile = find_user_profile_pda(owner).0;
+        self.account(&find_strategy_follow_pda(&find_strategy_pda(&user_profile, strategy_id).0).0)
+    }
+
+    // 按跟投关系记录的原始地址查找领投策略，返回当前地址和数据。
+    // 领投策略迁移后原账户已关闭，需在全部策略中查找原始地址相同的策略
+    pub fn leader_strategy(&self, follow: &StrategyFollow) -> Result<(Pubkey, StrategyConfig)> {
+        match self.account(&follow.leader) {
+            Ok(leader) => Ok((follow.leader, leader)),
+            Err(Error::AccountNotFound(_)) => self
+                .strategies(None)?
+                .into_iter()
+                .find(|(_, strategy)| strategy.origin == follow.leader)
+                .ok_or(Error::AccountNotFound(follow.leader)),
+            Err(e) => Err(e),
+        }
+    }
+
+    // 返回跟随指定领投策略的全部跟投关系，leader 为领投策略的原始地址
+    pub fn followers(&self, leader: &Pubkey) -> Result<Vec<(Pubkey, StrategyFollow)>> {
+        let filters = vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &StrategyFollow::DISCRIMINATOR,
+            )),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                FOLLOW_LEADER_OFFSET,
+                leader.as_ref(),
+            )),
+        ];
+        self.program_accounts(filters)
+    }
+
+    pub fn dca_schedule(&self, owner: &Pubkey, strategy_id: u64) -> Result<DcaSchedule> {
+        let user_profile = find_user_profile_pda(owner).0;
+        self.account(&find_dca_schedule_pda(&find_strategy_pda(&user_profile, strategy_id).0).0)
+    }
+
+    pub fn protective_order(&self, owner: &Pubkey, strategy_id: u64) -> Result<ProtectiveOrder> {
+        let user_profile = find_user_profile_pda(owner).0;
+        self.account(&find_protective_order_pda(&find_strategy_pda(&user_profile, strategy_id).0).0)
+    }
+
+    pub fn yield_rotation(&self, owner: &Pubkey, strategy_id: u64) -> Result<YieldRotation> {
+        let user_profile = find_user_profile_pda(owner).0;
+        self.account(&find_yield_rotation_pda(&find_strategy_pda(&user_profile, strategy_id).0).0)
+    }
+
+    pub fn position_tracker(&self, address: &Pubkey) -> Result<PositionTracker> {
+        self.account(address)
+    }
+
+    // 管理员报告的协议和资产的市场利率
+    pub fn apy_tracker(&self, protocol: u8, asset: u8) -> Result<PositionTracker> {
+        self.account(&find_apy_tracker_pda(protocol, asset).0)
+    }
+
+    // 返回指定用户的全部策略；owner 为 None 时返回所有用户的策略
+    pub fn strategies(&self, owner: Option<&Pubkey>) -> Result<Vec<(Pubkey, StrategyConfig)>> {
+        let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &StrategyConfig::DISCRIMINATOR,
+        ))];
+        if let Some(owner) = owner {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                OWNER_OFFSET,
+                owner.as_ref(),
+            )));
+        }
+        self.program_accounts(filters)
+    }
+
+    // 按过滤条件读取并解码程序拥有的账户
+    fn program_accounts<T: AccountDeserialize>(
+        &self,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, T)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                commitment: Some(self.rpc.commitment()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        self.rpc
+            .get_program_accounts_with_config(&defi_tools::ID, config)?
+            .into_iter()
+            .map(|(address, account)| {
+                T::try_deserialize(&mut account.data.as_slice())
+                    .map(|value| (address, value))
+                    .map_err(|e| Error::Decode {
+                        address,
+                        message: e.to_string(),
+                    })
+            })
+            .collect()
+    }
+
+    // 按分配项顺序组装再平衡所需的 [资产注册表, 主价格源, (备用价格源)] 账户
+    pub fn price_accounts(&self, strategy: &StrategyConfig) -> Result<Vec<Pubkey>> {
+        self.asset_price_accounts(
+            strategy
+                .allocations
+                .iter()
+                .map(|allocation| allocation.asset),
+        )
+    }
+
+    // 定投依次需要报价资产和目标资产的价格账户
+    pub fn dca_price_accounts(&self, schedule: &DcaSchedule) -> Result<Vec<Pubkey>> {
+        self.asset_price_accounts([schedule.params.quote_asset, schedule.params.target_asset])
+    }
+
+    fn asset_price_accounts(&self, assets: impl IntoIterator<Item = u8>) -> Result<Vec<Pubkey>> {
+        let cross_check = self.config()?.oracle_guard.requires_secondary();
+
+        let mut accounts = Vec::new();
+        for asset_id in assets {
+            let asset = self.asset_registry(asset_id)?;
+            accounts.push(find_asset_registry_pda(asset_id).0);
+            accounts.push(asset.oracle);
+            if cross_check {
+                accounts.push(asset.secondary_oracle);
+            }
+        }
+        Ok(accounts)
+    }
+
+    fn transaction(
+        &self,
+        instructions: &[Instruction],
+        payer: &dyn Signer,
+        signers: &[&dyn Signer],
+    ) -> Result<Transaction> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        ))
+    }
+
+    // 签名并发送交易，等待确认。程序返回的自定义错误会被解码为 `Error::Program`
+    pub fn send(
+        &self,
+        instructions: &[Instruction],
+        payer: &dyn Signer,
+        signers: &[&dyn Signer],
+    ) -> Result<Signature> {
+        let tx = self.transaction(instructions, payer, signers)?;
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+
+    // 模拟交易。模拟失败且为程序错误时返回 `Error::Program`，其余结果原样返回
+    pub fn simulate(
+        &self,
+        instructions: &[Instruction],
+        payer: &dyn Signer,
+        signers: &[&dyn Signer],
+    ) -> Result<RpcSimulateTransactionResult> {
+        let tx = self.transaction(instructions, payer, signers)?;
+        let result = self.rpc.simulate_transaction(&tx)?.value;
+        if let Some(code) = result.err.as_ref().and_then(program_error) {
+            return Err(Error::Program(code));
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,175 @@
+use defi_tools::ErrorCode;
+use solana_rpc_client_api::client_error::Error as RpcError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+
+// Anchor 自定义错误码的起始值
+pub const ERROR_CODE_OFFSET: u32 = 6000;
+
+// 按声明顺序排列的全部程序错误，下标加 ERROR_CODE_OFFSET 即为错误码
+pub const ERROR_CODES: &[ErrorCode] = &[
+    ErrorCode::Unauthorized,
+    ErrorCode::InvalidRiskLevel,
+    ErrorCode::InvalidStrategyId,
+    ErrorCode::InvalidAllocation,
+    ErrorCode::InvalidSlippage,
+    ErrorCode::StrategyPaused,
+    ErrorCode::InsufficientFunds,
+    ErrorCode::SlippageExceeded,
+    ErrorCode::IncompatibleProtocol,
+    ErrorCode::ProtocolNotRegistered,
+    ErrorCode::OperationTimeout,
+    ErrorCode::MathError,
+    ErrorCode::PositionNotFound,
+    ErrorCode::RebalanceConditionNotMet,
+    ErrorCode::EmergencyModeActive,
+    ErrorCode::StalePrice,
+    ErrorCode::PriceConfidenceTooWide,
+    ErrorCode::PriceSourceDivergence,
+    ErrorCode::InvalidPrice,
+    ErrorCode::InvalidOracleAccount,
+    ErrorCode::InvalidOracleGuardConfig,
+    ErrorCode::AssetNotRegistered,
+    ErrorCode::AssetDisabled,
+    ErrorCode::AssetAllocationCapExceeded,
+    ErrorCode::InvalidAssetConfig,
+    ErrorCode::UnknownProtocol,
+    ErrorCode::DuplicateAllocation,
+    ErrorCode::ZeroWeightAllocation,
+    ErrorCode::TooManyAllocations,
+    ErrorCode::AllocationWeightOutOfRange,
+    ErrorCode::InvalidFeeConfig,
+    ErrorCode::InvalidTreasury,
+    ErrorCode::DepositTooSmall,
+    ErrorCode::StrategyDepositCapExceeded,
+    ErrorCode::UserDepositCapExceeded,
+    ErrorCode::AssetDepositCapExceeded,
+    ErrorCode::InvalidDepositLimits,
+    ErrorCode::InvalidWithdrawalDelay,
+    ErrorCode::WithdrawalNotReady,
+    ErrorCode::WithdrawalOutOfOrder,
+    ErrorCode::InvalidDelegate,
+    ErrorCode::DelegateExpired,
+    ErrorCode::DelegatePermissionDenied,
+    ErrorCode::DelegateBoundsExceeded,
+    ErrorCode::InvalidWithdrawalDestination,
+    ErrorCode::WithdrawalDestinationNotAllowed,
+    ErrorCode::WithdrawalDestinationPending,
+    ErrorCode::InvalidSpendingLimit,
+    ErrorCode::SpendingLimitExceeded,
+    ErrorCode::GuardianApprovalRequired,
+    ErrorCode::InvalidNewOwner,
+    ErrorCode::OwnerTransferPending,
+    ErrorCode::ProfileMigrated,
+    ErrorCode::WithdrawalsPending,
+    ErrorCode::TemplateRiskTooHigh,
+    ErrorCode::InvalidFollowParams,
+    ErrorCode::FollowerInSync,
+    ErrorCode::FollowDelayActive,
+    ErrorCode::FollowerRiskExceeded,
+    ErrorCode::InvalidDcaParams,
+    ErrorCode::DcaPriceAboveLimit,
+    ErrorCode::DcaCompleted,
+    ErrorCode::InvalidProtectionParams,
+    ErrorCode::StrategyNotPaused,
+    ErrorCode::InvalidYieldParams,
+    ErrorCode::InvalidApyTracker,
+    ErrorCode::StaleApy,
+    ErrorCode::YieldHoldActive,
+    ErrorCode::YieldAdvantageTooSmall,
+    ErrorCode::TooManyAssetDeposits,
+    ErrorCode::StrategyModeConflict,
+    ErrorCode::DcaIntervalRequired,
+    ErrorCode::StrategyLinksActive,
+];
+
+// 将自定义错误码转换为程序错误
+pub fn error_code(code: u32) -> Option<ErrorCode> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    ERROR_CODES.get(index as usize).copied()
+}
+
+// 从交易错误中提取程序错误
+pub fn program_error(err: &TransactionError) -> Option<ErrorCode> {
+    match err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => error_code(*code),
+        _ => None,
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("程序错误 {}: {0}", u32::from(*.0))]
+    Program(ErrorCode),
+
+    #[error("账户不存在: {0}")]
+    AccountNotFound(Pubkey),
+
+    #[error("账户 {address} 解码失败: {message}")]
+    Decode { address: Pubkey, message: String },
+
+    #[error(transparent)]
+    Rpc(Box<RpcError>),
+}
+
+impl Error {
+    // 若错误来自程序自定义错误，返回对应的错误码
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Error::Program(code) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl From<RpcError> for Error {
+    fn from(err: RpcError) -> Self {
+        match err.get_transaction_error().as_ref().and_then(program_error) {
+            Some(code) => Error::Program(code),
+            None => Error::Rpc(Box::new(err)),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    // 测试1: 错误码列表与IDL中的错误定义一致
+    #[test]
+    fn test_error_codes_match_idl() {
+        let idl: Value =
+            serde_json::from_str(include_str!("../../../sdk/src/idl/defi_tools.json")).unwrap();
+        let errors = idl["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), ERROR_CODES.len());
+
+        for (index, error) in errors.iter().enumerate() {
+            let code = error["code"].as_u64().unwrap() as u32;
+            let decoded = error_code(code).unwrap();
+            assert_eq!(u32::from(decoded), code);
+            assert_eq!(
+                decoded.name(),
+                error["name"].as_str().unwrap(),
+                "第 {index} 个错误名称不一致"
+            );
+        }
+    }
+
+    // 测试2: 从交易错误中解码程序错误
+    #[test]
+    fn test_program_error() {
+        let err = TransactionError::InstructionError(0, InstructionError::Custom(6006));
+        assert!(matches!(
+            program_error(&err),
+            Some(ErrorCode::InsufficientFunds)
+        ));
+
+        let err = TransactionError::InstructionError(0, InstructionError::Custom(42));
+        assert!(program_error(&err).is_none());
+        assert!(program_error(&TransactionError::AccountNotFound).is_none());
+    }
+}
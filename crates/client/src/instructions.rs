@@ -0,0 +1,1531 @@
+use crate::pda::{
+    find_apy_tracker_pda, find_asset_registry_pda, find_config_pda, find_dca_schedule_pda,
+    find_program_data_pda, find_protective_order_pda, find_strategy_delegate_pda,
+    find_strategy_follow_pda, find_strategy_pda, find_strategy_template_pda, find_user_profile_pda,
+    find_vault_pda, find_withdrawal_destination_pda, find_withdrawal_ticket_pda,
+    find_yield_rotation_pda,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use defi_tools::{
+    accounts, instruction, Allocation, AssetDeposit, AssetParams, DcaParams, DcaSchedule,
+    DelegateScope, DepositLimits, FeeConfig, OracleGuardConfig, ProtectionParams, ProtectiveOrder,
+    RebalanceCondition, StrategyConfig, StrategyFollow, StrategyTemplate, YieldParams,
+    YieldRotation,
+};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: defi_tools::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn with_remaining(mut ix: Instruction, remaining: impl IntoIterator<Item = Pubkey>) -> Instruction {
+    ix.accounts.extend(
+        remaining
+            .into_iter()
+            .map(|pubkey| AccountMeta::new_readonly(pubkey, false)),
+    );
+    ix
+}
+
+// 按分配项顺序附加可写的资产注册表，存取款用它们检查和累计资产存款上限
+fn with_asset_registries(mut ix: Instruction, allocations: &[Allocation]) -> Instruction {
+    ix.accounts.extend(
+        allocations
+            .iter()
+            .map(|allocation| AccountMeta::new(find_asset_registry_pda(allocation.asset).0, false)),
+    );
+    ix
+}
+
+// 按策略的存款记录顺序附加可写的资产注册表，提款和领取工单按比例扣回
+fn with_deposit_registries(mut ix: Instruction, deposits: &[AssetDeposit]) -> Instruction {
+    ix.accounts.extend(
+        deposits
+            .iter()
+            .map(|deposit| AccountMeta::new(find_asset_registry_pda(deposit.asset).0, false)),
+    );
+    ix
+}
+
+// 签名者不是所有者时附加其代理人账户，所有者本人签名时省略
+fn delegate_account(authority: &Pubkey, owner: &Pubkey, strategy: &Pubkey) -> Option<Pubkey> {
+    (authority != owner).then(|| find_strategy_delegate_pda(strategy, authority).0)
+}
+
+// 提款和领取工单的转出方式
+#[derive(Clone, Copy, Debug)]
+pub struct Payout {
+    // 接收地址
+    pub destination: Pubkey,
+    // 接收地址已加入白名单，启用白名单时向所有者以外的地址转出必须为 true
+    pub listed: bool,
+    // 联署的监护人，提供时不受周期提款限额限制，监护人也必须签名交易
+    pub guardian: Option<Pubkey>,
+}
+
+impl Payout {
+    // 转入所有者本人钱包，不需要白名单账户
+    pub fn to_owner(owner: &Pubkey) -> Self {
+        Self {
+            destination: *owner,
+            listed: false,
+            guardian: None,
+        }
+    }
+
+    // 接收地址为所有者本人或未登记的地址时省略白名单账户
+    fn destination_account(&self, owner: &Pubkey) -> Option<Pubkey> {
+        (self.listed && self.destination != *owner)
+            .then(|| find_withdrawal_destination_pda(owner, &self.destination).0)
+    }
+}
+
+// 初始化全局配置，调用者必须是程序的升级权限，成为管理员
+pub fn initialize_config(admin: &Pubkey, oracle_guard: OracleGuardConfig) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            admin: *admin,
+            program_data: find_program_data_pda().0,
+            config: find_config_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig { oracle_guard },
+    )
+}
+
+// 更新预言机校验参数(仅管理员)
+pub fn update_oracle_guard(admin: &Pubkey, oracle_guard: OracleGuardConfig) -> Instruction {
+    build(
+        accounts::UpdateOracleGuard {
+            admin: *admin,
+            config: find_config_pda().0,
+        },
+        instruction::UpdateOracleGuard { oracle_guard },
+    )
+}
+
+// 更新费率和费用金库(仅管理员)，费用金库必须是已有余额的系统账户
+pub fn update_fee_config(admin: &Pubkey, fees: FeeConfig, treasury: &Pubkey) -> Instruction {
+    build(
+        accounts::UpdateFeeConfig {
+            admin: *admin,
+            config: find_config_pda().0,
+            treasury: *treasury,
+        },
+        instruction::UpdateFeeConfig { fees },
+    )
+}
+
+// 更新最小存款金额以及策略和用户的存款上限(仅管理员)
+pub fn update_deposit_limits(admin: &Pubkey, limits: DepositLimits) -> Instruction {
+    build(
+        accounts::UpdateDepositLimits {
+            admin: *admin,
+            config: find_config_pda().0,
+        },
+        instruction::UpdateDepositLimits { limits },
+    )
+}
+
+// 更新提款工单的锁定时间(仅管理员)，只影响之后申请的工单
+pub fn update_withdrawal_delay(admin: &Pubkey, delay_seconds: u32) -> Instruction {
+    build(
+        accounts::UpdateWithdrawalDelay {
+            admin: *admin,
+            config: find_config_pda().0,
+        },
+        instruction::UpdateWithdrawalDelay { delay_seconds },
+    )
+}
+
+// 更新提款白名单的生效延迟(仅管理员)
+pub fn update_allowlist_delay(admin: &Pubkey, delay_seconds: u32) -> Instruction {
+    build(
+        accounts::UpdateAllowlistDelay {
+            admin: *admin,
+            config: find_config_pda().0,
+        },
+        instruction::UpdateAllowlistDelay { delay_seconds },
+    )
+}
+
+// 注册资产(仅管理员)
+pub fn register_asset(admin: &Pubkey, asset_id: u8, params: AssetParams) -> Instruction {
+    build(
+        accounts::RegisterAsset {
+            admin: *admin,
+            config: find_config_pda().0,
+            asset_registry: find_asset_registry_pda(asset_id).0,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterAsset { asset_id, params },
+    )
+}
+
+// 更新资产配置(仅管理员)
+pub fn update_asset(admin: &Pubkey, asset_id: u8, params: AssetParams) -> Instruction {
+    build(
+        accounts::UpdateAsset {
+            admin: *admin,
+            config: find_config_pda().0,
+            asset_registry: find_asset_registry_pda(asset_id).0,
+        },
+        instruction::UpdateAsset { asset_id, params },
+    )
+}
+
+// 初始化用户配置
+pub fn initialize_user(owner: &Pubkey, risk_level: u8) -> Instruction {
+    build(
+        accounts::InitializeUser {
+            owner: *owner,
+            user_profile: find_user_profile_pda(owner).0,
+            vault: find_vault_pda(owner).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeUser { risk_level },
+    )
+}
+
+// 创建策略。strategy_id 必须等于用户当前的策略计数器，
+// 每个分配项对应的资产注册表账户会按顺序附加在账户列表末尾
+pub fn create_strategy(
+    owner: &Pubkey,
+    strategy_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let registries: Vec<Pubkey> = allocations
+        .iter()
+        .map(|allocation| find_asset_registry_pda(allocation.asset).0)
+        .collect();
+
+    let ix = build(
+        accounts::CreateStrategy {
+            owner: *owner,
+            user_profile,
+            strategy_config: find_strategy_pda(&user_profile, strategy_id).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateStrategy {
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+        },
+    );
+    with_remaining(ix, registries)
+}
+
+// 发布策略模板，资产注册表账户的附加方式与创建策略时相同
+pub fn publish_template(
+    publisher: &Pubkey,
+    template_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+    risk_level: u8,
+    description_hash: [u8; 32],
+) -> Instruction {
+    let registries: Vec<Pubkey> = allocations
+        .iter()
+        .map(|allocation| find_asset_registry_pda(allocation.asset).0)
+        .collect();
+
+    let ix = build(
+        accounts::PublishTemplate {
+            publisher: *publisher,
+            strategy_template: find_strategy_template_pda(publisher, template_id).0,
+            system_program: system_program::ID,
+        },
+        instruction::PublishTemplate {
+            template_id,
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+            risk_level,
+            description_hash,
+        },
+    );
+    with_remaining(ix, registries)
+}
+
+// 停用并关闭模板，租金退回发布者
+pub fn retire_template(publisher: &Pubkey, template_id: u64) -> Instruction {
+    build(
+        accounts::RetireTemplate {
+            publisher: *publisher,
+            strategy_template: find_strategy_template_pda(publisher, template_id).0,
+        },
+        instruction::RetireTemplate { template_id },
+    )
+}
+
+// 按模板创建策略，strategy_id 为用户当前的策略计数器，按模板的分配项附加资产注册表
+pub fn create_strategy_from_template(
+    owner: &Pubkey,
+    strategy_id: u64,
+    template: &StrategyTemplate,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let registries: Vec<Pubkey> = template
+        .allocations
+        .iter()
+        .map(|allocation| find_asset_registry_pda(allocation.asset).0)
+        .collect();
+
+    let ix = build(
+        accounts::CreateStrategyFromTemplate {
+            owner: *owner,
+            user_profile,
+            strategy_template: find_strategy_template_pda(
+                &template.publisher,
+                template.template_id,
+            )
+            .0,
+            strategy_config: find_strategy_pda(&user_profile, strategy_id).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateStrategyFromTemplate {},
+    );
+    with_remaining(ix, registries)
+}
+
+// 更新策略的分配项、再平衡条件和滑点，资产注册表账户的附加方式与创建时相同
+pub fn update_strategy(
+    owner: &Pubkey,
+    strategy_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Instruction {
+    update_strategy_as(
+        owner,
+        owner,
+        strategy_id,
+        allocations,
+        rebalance_condition,
+        max_slippage_bps,
+    )
+}
+
+// 由 authority 签名更新策略。authority 不是所有者时须为有更新权限的代理人，
+// 只能调整权重、再平衡条件和滑点
+pub fn update_strategy_as(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    let registries: Vec<Pubkey> = allocations
+        .iter()
+        .map(|allocation| find_asset_registry_pda(allocation.asset).0)
+        .collect();
+
+    let ix = build(
+        accounts::UpdateStrategy {
+            authority: *authority,
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            delegate: delegate_account(authority, owner, &strategy_config),
+        },
+        instruction::UpdateStrategy {
+            strategy_id,
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+        },
+    );
+    with_remaining(ix, registries)
+}
+
+// 执行策略
+pub fn execute_strategy(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    execute_strategy_as(owner, owner, strategy_id)
+}
+
+// 由 authority 签名执行策略，authority 不是所有者时须为有执行权限的代理人
+pub fn execute_strategy_as(authority: &Pubkey, owner: &Pubkey, strategy_id: u64) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::ExecuteStrategy {
+            authority: *authority,
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            delegate: delegate_account(authority, owner, &strategy_config),
+            system_program: system_program::ID,
+        },
+        instruction::ExecuteStrategy { strategy_id },
+    )
+}
+
+// 存入资金。存取款和再平衡都会先结算费用，treasury 为全局配置中的费用金库，
+// allocations 为策略当前的分配项，用于附加对应的资产注册表
+pub fn deposit_funds(
+    owner: &Pubkey,
+    strategy_id: u64,
+    amount: u64,
+    treasury: &Pubkey,
+    allocations: &[Allocation],
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let ix = build(
+        accounts::DepositFunds {
+            owner: *owner,
+            user_profile,
+            strategy_config: find_strategy_pda(&user_profile, strategy_id).0,
+            vault: find_vault_pda(owner).0,
+            config: find_config_pda().0,
+            treasury: *treasury,
+            system_program: system_program::ID,
+        },
+        instruction::DepositFunds {
+            strategy_id,
+            amount,
+        },
+    );
+    with_asset_registries(ix, allocations)
+}
+
+// 提取资金到所有者钱包。deposits 为策略的存款记录(StrategyConfig::asset_deposits)
+pub fn withdraw_funds(
+    owner: &Pubkey,
+    strategy_id: u64,
+    amount: u64,
+    treasury: &Pubkey,
+    deposits: &[AssetDeposit],
+) -> Instruction {
+    withdraw_funds_to(
+        owner,
+        strategy_id,
+        amount,
+        treasury,
+        deposits,
+        &Payout::to_owner(owner),
+    )
+}
+
+// 按 payout 指定的接收地址和监护人提取资金
+pub fn withdraw_funds_to(
+    owner: &Pubkey,
+    strategy_id: u64,
+    amount: u64,
+    treasury: &Pubkey,
+    deposits: &[AssetDeposit],
+    payout: &Payout,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let ix = build(
+        accounts::WithdrawFunds {
+            owner: *owner,
+            user_profile,
+            strategy_config: find_strategy_pda(&user_profile, strategy_id).0,
+            vault: find_vault_pda(owner).0,
+            config: find_config_pda().0,
+            treasury: *treasury,
+            destination: payout.destination,
+            withdrawal_destination: payout.destination_account(owner),
+            guardian: payout.guardian,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawFunds {
+            strategy_id,
+            amount,
+        },
+    );
+    with_deposit_registries(ix, deposits)
+}
+
+// 申请排队提款，金额从策略价值中扣除并锁定在新工单中。
+// ticket_id 必须等于策略提款队列的 next_ticket_id
+pub fn request_withdrawal(
+    owner: &Pubkey,
+    strategy_id: u64,
+    ticket_id: u64,
+    amount: u64,
+    treasury: &Pubkey,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::RequestWithdrawal {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            withdrawal_ticket: find_withdrawal_ticket_pda(&strategy_config, ticket_id).0,
+            vault: find_vault_pda(owner).0,
+            config: find_config_pda().0,
+            treasury: *treasury,
+            system_program: system_program::ID,
+        },
+        instruction::RequestWithdrawal {
+            strategy_id,
+            amount,
+        },
+    )
+}
+
+// 领取提款工单。工单按ID先后领取，skipped 为队首到该工单之间已取消的工单ID，
+// 通常为 head_ticket_id..ticket_id
+pub fn claim_withdrawal(
+    owner: &Pubkey,
+    strategy_id: u64,
+    ticket_id: u64,
+    deposits: &[AssetDeposit],
+    skipped: &[u64],
+) -> Instruction {
+    claim_withdrawal_to(
+        owner,
+        strategy_id,
+        ticket_id,
+        deposits,
+        skipped,
+        &Payout::to_owner(owner),
+    )
+}
+
+// 按 payout 指定的接收地址和监护人领取提款工单
+pub fn claim_withdrawal_to(
+    owner: &Pubkey,
+    strategy_id: u64,
+    ticket_id: u64,
+    deposits: &[AssetDeposit],
+    skipped: &[u64],
+    payout: &Payout,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    let ix = build(
+        accounts::ClaimWithdrawal {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            withdrawal_ticket: find_withdrawal_ticket_pda(&strategy_config, ticket_id).0,
+            vault: find_vault_pda(owner).0,
+            destination: payout.destination,
+            withdrawal_destination: payout.destination_account(owner),
+            guardian: payout.guardian,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimWithdrawal {
+            strategy_id,
+            ticket_id,
+        },
+    );
+    let ix = with_deposit_registries(ix, deposits);
+    with_remaining(
+        ix,
+        skipped
+            .iter()
+            .map(|id| find_withdrawal_ticket_pda(&strategy_config, *id).0),
+    )
+}
+
+// 取消提款工单，锁定的金额退回策略
+pub fn cancel_withdrawal(
+    owner: &Pubkey,
+    strategy_id: u64,
+    ticket_id: u64,
+    treasury: &Pubkey,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::CancelWithdrawal {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            withdrawal_ticket: find_withdrawal_ticket_pda(&strategy_config, ticket_id).0,
+            vault: find_vault_pda(owner).0,
+            config: find_config_pda().0,
+            treasury: *treasury,
+            system_program: system_program::ID,
+        },
+        instruction::CancelWithdrawal {
+            strategy_id,
+            ticket_id,
+        },
+    )
+}
+
+// 再平衡头寸。price_accounts 为按分配项顺序排列的
+// [资产注册表, 主价格源, (备用价格源)]，可由 `Client::price_accounts` 获取
+pub fn rebalance_positions(
+    owner: &Pubkey,
+    strategy_id: u64,
+    price_accounts: &[Pubkey],
+    treasury: &Pubkey,
+) -> Instruction {
+    rebalance_positions_as(owner, owner, strategy_id, price_accounts, treasury)
+}
+
+// 由 authority 签名再平衡头寸，authority 不是所有者时须为有再平衡权限的代理人
+pub fn rebalance_positions_as(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    price_accounts: &[Pubkey],
+    treasury: &Pubkey,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    let ix = build(
+        accounts::RebalancePositions {
+            authority: *authority,
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            delegate: delegate_account(authority, owner, &strategy_config),
+            vault: find_vault_pda(owner).0,
+            config: find_config_pda().0,
+            treasury: *treasury,
+            system_program: system_program::ID,
+        },
+        instruction::RebalancePositions { strategy_id },
+    );
+    with_remaining(ix, price_accounts.iter().copied())
+}
+
+// 让策略跟随领投策略，leader 为领投策略账户地址
+pub fn follow_strategy(
+    owner: &Pubkey,
+    strategy_id: u64,
+    leader: &Pubkey,
+    max_weight_bps: u16,
+    delay_seconds: u32,
+    leader_fee_bps: u16,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::FollowStrategy {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            leader_strategy: *leader,
+            strategy_follow: find_strategy_follow_pda(&strategy_config).0,
+            system_program: system_program::ID,
+        },
+        instruction::FollowStrategy {
+            strategy_id,
+            max_weight_bps,
+            delay_seconds,
+            leader_fee_bps,
+        },
+    )
+}
+
+// 停止跟随并关闭跟投关系，租金退回所有者
+pub fn unfollow_strategy(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::UnfollowStrategy {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            strategy_follow: find_strategy_follow_pda(&strategy_config).0,
+        },
+        instruction::UnfollowStrategy { strategy_id },
+    )
+}
+
+// 将领投策略的分配项同步到跟随策略，任何人都可以发起。
+// 按领投策略当前的分配项附加资产注册表，跟投费用从跟随者金库支付给领投策略所有者。
+// 领投者已加入跟随者的提款白名单时 leader_listed 为 true，附加白名单账户。
+// leader_strategy 为领投策略当前的地址，领投策略迁移后与 follow.leader 记录的原始地址不同
+pub fn sync_follower(
+    authority: &Pubkey,
+    follow: &StrategyFollow,
+    leader_strategy: &Pubkey,
+    leader: &StrategyConfig,
+    treasury: &Pubkey,
+    leader_listed: bool,
+) -> Instruction {
+    let registries: Vec<Pubkey> = leader
+        .allocations
+        .iter()
+        .map(|allocation| find_asset_registry_pda(allocation.asset).0)
+        .collect();
+
+    let ix = build(
+        accounts::SyncFollower {
+            authority: *authority,
+            strategy_follow: find_strategy_follow_pda(&follow.strategy).0,
+            user_profile: find_user_profile_pda(&follow.owner).0,
+            strategy_config: follow.strategy,
+            leader_strategy: *leader_strategy,
+            leader_owner: leader.owner,
+            withdrawal_destination: leader_listed
+                .then(|| find_withdrawal_destination_pda(&follow.owner, &leader.owner).0),
+            vault: find_vault_pda(&follow.owner).0,
+            config: find_config_pda().0,
+            treasury: *treasury,
+            system_program: system_program::ID,
+        },
+        instruction::SyncFollower {},
+    );
+    with_remaining(ix, registries)
+}
+
+// 为策略创建定投计划，每个策略最多一个
+pub fn create_dca(owner: &Pubkey, strategy_id: u64, params: DcaParams) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::CreateDca {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            dca_schedule: find_dca_schedule_pda(&strategy_config).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateDca {
+            strategy_id,
+            params,
+        },
+    )
+}
+
+// 取消定投计划，已转移的分配项保持不变，租金退回所有者
+pub fn cancel_dca(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::CancelDca {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            dca_schedule: find_dca_schedule_pda(&strategy_config).0,
+        },
+        instruction::CancelDca { strategy_id },
+    )
+}
+
+// 执行一次到期的定投，任何人都可以发起。price_accounts 依次为报价资产和目标资产的
+// [资产注册表, 主价格源, (备用价格源)]，可由 `Client::dca_price_accounts` 获取
+pub fn execute_dca(
+    authority: &Pubkey,
+    schedule: &DcaSchedule,
+    price_accounts: &[Pubkey],
+) -> Instruction {
+    let ix = build(
+        accounts::ExecuteDca {
+            authority: *authority,
+            dca_schedule: find_dca_schedule_pda(&schedule.strategy).0,
+            user_profile: find_user_profile_pda(&schedule.owner).0,
+            strategy_config: schedule.strategy,
+            config: find_config_pda().0,
+        },
+        instruction::ExecuteDca {},
+    );
+    with_remaining(ix, price_accounts.iter().copied())
+}
+
+// 为策略设置止损止盈保护单，每个策略最多一个。price_accounts 按策略当前的分配项顺序提供
+// [资产注册表, 主价格源, (备用价格源)]，可由 `Client::price_accounts` 获取
+pub fn set_protection(
+    owner: &Pubkey,
+    strategy_id: u64,
+    params: ProtectionParams,
+    price_accounts: &[Pubkey],
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    let ix = build(
+        accounts::SetProtection {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            protective_order: find_protective_order_pda(&strategy_config).0,
+            safe_asset: find_asset_registry_pda(params.safe_asset).0,
+            config: find_config_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::SetProtection {
+            strategy_id,
+            params,
+        },
+    );
+    with_remaining(ix, price_accounts.iter().copied())
+}
+
+// 取消保护单，租金退回所有者
+pub fn cancel_protection(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::CancelProtection {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            protective_order: find_protective_order_pda(&strategy_config).0,
+        },
+        instruction::CancelProtection { strategy_id },
+    )
+}
+
+// 按预言机价格重新估值，任何人都可以发起。满足触发条件时策略转入安全头寸并暂停，
+// 保护单关闭。price_accounts 按策略当前的分配项顺序提供
+pub fn check_protection(
+    authority: &Pubkey,
+    order: &ProtectiveOrder,
+    price_accounts: &[Pubkey],
+) -> Instruction {
+    let ix = build(
+        accounts::CheckProtection {
+            authority: *authority,
+            protective_order: find_protective_order_pda(&order.strategy).0,
+            owner: order.owner,
+            strategy_config: order.strategy,
+            config: find_config_pda().0,
+        },
+        instruction::CheckProtection {},
+    );
+    with_remaining(ix, price_accounts.iter().copied())
+}
+
+// 恢复被保护单暂停的策略
+pub fn resume_strategy(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    build(
+        accounts::ResumeStrategy {
+            owner: *owner,
+            user_profile,
+            strategy_config: find_strategy_pda(&user_profile, strategy_id).0,
+        },
+        instruction::ResumeStrategy { strategy_id },
+    )
+}
+
+// 为已注册的资产创建借贷协议的利率追踪账户(管理员)
+pub fn register_apy_tracker(admin: &Pubkey, protocol: u8, asset: u8, apy_bps: u32) -> Instruction {
+    build(
+        accounts::RegisterApyTracker {
+            admin: *admin,
+            config: find_config_pda().0,
+            asset_registry: find_asset_registry_pda(asset).0,
+            apy_tracker: find_apy_tracker_pda(protocol, asset).0,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterApyTracker {
+            protocol,
+            asset,
+            apy_bps,
+        },
+    )
+}
+
+// 报告最新APY(管理员)，超过一天未报告的利率不能用于轮动
+pub fn report_apy(admin: &Pubkey, protocol: u8, asset: u8, apy_bps: u32) -> Instruction {
+    build(
+        accounts::ReportApy {
+            admin: *admin,
+            config: find_config_pda().0,
+            apy_tracker: find_apy_tracker_pda(protocol, asset).0,
+        },
+        instruction::ReportApy {
+            protocol,
+            asset,
+            apy_bps,
+        },
+    )
+}
+
+// 为策略启用收益率轮动，每个策略最多一个轮动计划
+pub fn enable_yield_rotation(owner: &Pubkey, strategy_id: u64, params: YieldParams) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::EnableYieldRotation {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            yield_rotation: find_yield_rotation_pda(&strategy_config).0,
+            system_program: system_program::ID,
+        },
+        instruction::EnableYieldRotation {
+            strategy_id,
+            params,
+        },
+    )
+}
+
+// 停用收益率轮动，租金退回所有者
+pub fn disable_yield_rotation(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::DisableYieldRotation {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            yield_rotation: find_yield_rotation_pda(&strategy_config).0,
+        },
+        instruction::DisableYieldRotation { strategy_id },
+    )
+}
+
+// 将轮动头寸移到APY最高的候选协议，任何人都可以发起。按协议编号顺序附加每个候选协议的利率追踪账户
+pub fn rotate_yield(authority: &Pubkey, rotation: &YieldRotation) -> Instruction {
+    let ix = build(
+        accounts::RotateYield {
+            authority: *authority,
+            yield_rotation: find_yield_rotation_pda(&rotation.strategy).0,
+            user_profile: find_user_profile_pda(&rotation.owner).0,
+            strategy_config: rotation.strategy,
+        },
+        instruction::RotateYield {},
+    );
+    let asset = rotation.params.asset;
+    with_remaining(
+        ix,
+        rotation
+            .params
+            .candidates()
+            .into_iter()
+            .map(|protocol| find_apy_tracker_pda(protocol, asset).0),
+    )
+}
+
+// 授权代理人执行、再平衡或在限定范围内更新策略，expires_at 之后授权失效。
+// 同一代理人已有授权时需先撤销
+pub fn add_delegate(
+    owner: &Pubkey,
+    strategy_id: u64,
+    delegate: &Pubkey,
+    scope: DelegateScope,
+    expires_at: i64,
+) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::AddDelegate {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            strategy_delegate: find_strategy_delegate_pda(&strategy_config, delegate).0,
+            system_program: system_program::ID,
+        },
+        instruction::AddDelegate {
+            strategy_id,
+            delegate: *delegate,
+            scope,
+            expires_at,
+        },
+    )
+}
+
+// 撤销代理人授权并取回账户租金
+pub fn revoke_delegate(owner: &Pubkey, strategy_id: u64, delegate: &Pubkey) -> Instruction {
+    let user_profile = find_user_profile_pda(owner).0;
+    let strategy_config = find_strategy_pda(&user_profile, strategy_id).0;
+    build(
+        accounts::RevokeDelegate {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            strategy_delegate: find_strategy_delegate_pda(&strategy_config, delegate).0,
+        },
+        instruction::RevokeDelegate {
+            strategy_id,
+            delegate: *delegate,
+        },
+    )
+}
+
+// 启用或关闭提款白名单，关闭在生效延迟之后生效
+pub fn set_withdrawal_allowlist(owner: &Pubkey, enabled: bool) -> Instruction {
+    build(
+        accounts::SetWithdrawalAllowlist {
+            owner: *owner,
+            user_profile: find_user_profile_pda(owner).0,
+            config: find_config_pda().0,
+        },
+        instruction::SetWithdrawalAllowlist { enabled },
+    )
+}
+
+// 设置周期提款限额。已设置监护人时，放宽限额或更换监护人需要当前监护人联署
+pub fn set_spending_limit(
+    owner: &Pubkey,
+    max_lamports: u64,
+    window_seconds: u32,
+    guardian: &Pubkey,
+    current_guardian: Option<&Pubkey>,
+) -> Instruction {
+    build(
+        accounts::SetSpendingLimit {
+            owner: *owner,
+            user_profile: find_user_profile_pda(owner).0,
+            guardian: current_guardian.copied(),
+        },
+        instruction::SetSpendingLimit {
+            max_lamports,
+            window_seconds,
+            guardian: *guardian,
+        },
+    )
+}
+
+// 提名新的所有者，new_owner 为默认值时取消提名。已设置监护人时需要其联署
+pub fn propose_owner(owner: &Pubkey, new_owner: &Pubkey, guardian: Option<&Pubkey>) -> Instruction {
+    build(
+        accounts::ProposeOwner {
+            owner: *owner,
+            user_profile: find_user_profile_pda(owner).0,
+            config: find_config_pda().0,
+            guardian: guardian.copied(),
+        },
+        instruction::ProposeOwner {
+            new_owner: *new_owner,
+        },
+    )
+}
+
+// 由被提名的 owner 签名接受转移，在其名下创建继承设置的用户配置
+pub fn accept_owner(owner: &Pubkey, previous_owner: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptOwner {
+            owner: *owner,
+            previous_profile: find_user_profile_pda(previous_owner).0,
+            user_profile: find_user_profile_pda(owner).0,
+            vault: find_vault_pda(owner).0,
+            system_program: system_program::ID,
+        },
+        instruction::AcceptOwner {
+            previous_owner: *previous_owner,
+        },
+    )
+}
+
+// 接受转移后把原所有者的策略及其资金迁移到 owner 名下，策略ID不变。
+// 策略不能有未领取的提款工单，定投、跟随、轮动、保护单和代理人授权需先由原所有者取消
+pub fn migrate_strategy(owner: &Pubkey, previous_owner: &Pubkey, strategy_id: u64) -> Instruction {
+    let previous_profile = find_user_profile_pda(previous_owner).0;
+    let user_profile = find_user_profile_pda(owner).0;
+    let previous_strategy = find_strategy_pda(&previous_profile, strategy_id).0;
+    build(
+        accounts::MigrateStrategy {
+            owner: *owner,
+            previous_profile,
+            user_profile,
+            previous_strategy,
+            previous_protection: find_protective_order_pda(&previous_strategy).0,
+            strategy_config: find_strategy_pda(&user_profile, strategy_id).0,
+            previous_vault: find_vault_pda(previous_owner).0,
+            vault: find_vault_pda(owner).0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateStrategy {
+            previous_owner: *previous_owner,
+            strategy_id,
+        },
+    )
+}
+
+// 添加提款白名单地址，在生效延迟之后才能接收提款
+pub fn add_withdrawal_destination(owner: &Pubkey, destination: &Pubkey) -> Instruction {
+    build(
+        accounts::AddWithdrawalDestination {
+            owner: *owner,
+            user_profile: find_user_profile_pda(owner).0,
+            withdrawal_destination: find_withdrawal_destination_pda(owner, destination).0,
+            vault: find_vault_pda(owner).0,
+            config: find_config_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::AddWithdrawalDestination {
+            destination: *destination,
+        },
+    )
+}
+
+// 移除提款白名单地址并取回账户租金
+pub fn remove_withdrawal_destination(owner: &Pubkey, destination: &Pubkey) -> Instruction {
+    build(
+        accounts::RemoveWithdrawalDestination {
+            owner: *owner,
+            withdrawal_destination: find_withdrawal_destination_pda(owner, destination).0,
+        },
+        instruction::RemoveWithdrawalDestination {
+            destination: *destination,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+    use defi_tools::StrategyMode;
+
+    // 测试1: 指令数据以Anchor判别符开头，参数按Borsh编码
+    #[test]
+    fn test_deposit_funds_encoding() {
+        let owner = Pubkey::new_unique();
+        let treasury = Pubkey::new_unique();
+        let allocations = [Allocation {
+            protocol: 0,
+            asset: 3,
+            target_weight_bps: 10000,
+        }];
+        let ix = deposit_funds(&owner, 2, 1_000, &treasury, &allocations);
+
+        assert_eq!(ix.program_id, defi_tools::ID);
+        assert_eq!(&ix.data[..8], instruction::DepositFunds::DISCRIMINATOR);
+        assert_eq!(&ix.data[8..16], &2u64.to_le_bytes());
+        assert_eq!(&ix.data[16..24], &1_000u64.to_le_bytes());
+
+        assert_eq!(ix.accounts.len(), 8);
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+        assert_eq!(ix.accounts[1].pubkey, find_user_profile_pda(&owner).0);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            find_strategy_pda(&ix.accounts[1].pubkey, 2).0
+        );
+        assert_eq!(ix.accounts[3].pubkey, find_vault_pda(&owner).0);
+        assert!(ix.accounts[3].is_writable);
+        assert_eq!(ix.accounts[4].pubkey, find_config_pda().0);
+        assert_eq!(ix.accounts[5].pubkey, treasury);
+        assert!(ix.accounts[5].is_writable);
+        assert_eq!(ix.accounts[7].pubkey, find_asset_registry_pda(3).0);
+        assert!(ix.accounts[7].is_writable && !ix.accounts[7].is_signer);
+    }
+
+    // 测试2: 创建策略时附加每个分配项的资产注册表
+    #[test]
+    fn test_create_strategy_remaining_accounts() {
+        let owner = Pubkey::new_unique();
+        let allocations = vec![
+            Allocation {
+                protocol: 0,
+                asset: 0,
+                target_weight_bps: 6000,
+            },
+            Allocation {
+                protocol: 1,
+                asset: 1,
+                target_weight_bps: 4000,
+            },
+        ];
+        let condition = RebalanceCondition {
+            time_interval_seconds: 3600,
+            max_deviation_bps: 500,
+            auto_rebalance: true,
+        };
+        let ix = create_strategy(&owner, 0, allocations, condition, 100);
+
+        assert_eq!(ix.accounts.len(), 6);
+        assert_eq!(ix.accounts[4].pubkey, find_asset_registry_pda(0).0);
+        assert_eq!(ix.accounts[5].pubkey, find_asset_registry_pda(1).0);
+        assert!(!ix.accounts[5].is_writable && !ix.accounts[5].is_signer);
+    }
+
+    // 测试3: 领取工单时按存款记录附加的资产注册表在前、跳过的工单在后
+    #[test]
+    fn test_claim_withdrawal_remaining_accounts() {
+        let owner = Pubkey::new_unique();
+        let deposits = [AssetDeposit {
+            asset: 1,
+            lamports: 100,
+        }];
+        let ix = claim_withdrawal(&owner, 0, 5, &deposits, &[3, 4]);
+
+        let strategy = find_strategy_pda(&find_user_profile_pda(&owner).0, 0).0;
+        assert_eq!(&ix.data[8..16], &0u64.to_le_bytes());
+        assert_eq!(&ix.data[16..24], &5u64.to_le_bytes());
+        assert_eq!(ix.accounts.len(), 12);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            find_withdrawal_ticket_pda(&strategy, 5).0
+        );
+        assert!(ix.accounts[3].is_writable);
+        assert_eq!(ix.accounts[9].pubkey, find_asset_registry_pda(1).0);
+        assert!(ix.accounts[9].is_writable);
+        assert_eq!(
+            ix.accounts[10].pubkey,
+            find_withdrawal_ticket_pda(&strategy, 3).0
+        );
+        assert!(!ix.accounts[11].is_writable);
+    }
+
+    // 测试4: 所有者签名时代理人账户为程序ID占位，代理人签名时为其代理人PDA
+    #[test]
+    fn test_delegate_account_placement() {
+        let owner = Pubkey::new_unique();
+        let bot = Pubkey::new_unique();
+        let strategy = find_strategy_pda(&find_user_profile_pda(&owner).0, 0).0;
+
+        let ix = execute_strategy(&owner, 0);
+        assert_eq!(ix.accounts[0].pubkey, owner);
+        assert_eq!(ix.accounts[1].pubkey, owner);
+        assert_eq!(ix.accounts[4].pubkey, defi_tools::ID);
+
+        let ix = execute_strategy_as(&bot, &owner, 0);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, owner);
+        assert!(!ix.accounts[1].is_signer);
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            find_strategy_delegate_pda(&strategy, &bot).0
+        );
+    }
+
+    // 测试5: 提款到白名单地址时附加白名单账户，提款到所有者本人时省略
+    #[test]
+    fn test_withdrawal_destination_account() {
+        let owner = Pubkey::new_unique();
+        let cold = Pubkey::new_unique();
+        let treasury = Pubkey::new_unique();
+
+        let ix = withdraw_funds(&owner, 0, 1, &treasury, &[]);
+        assert_eq!(ix.accounts[6].pubkey, owner);
+        assert_eq!(ix.accounts[7].pubkey, defi_tools::ID);
+
+        let payout = Payout {
+            destination: cold,
+            listed: true,
+            guardian: None,
+        };
+        let ix = withdraw_funds_to(&owner, 0, 1, &treasury, &[], &payout);
+        assert_eq!(ix.accounts[6].pubkey, cold);
+        assert!(ix.accounts[6].is_writable);
+        assert_eq!(
+            ix.accounts[7].pubkey,
+            find_withdrawal_destination_pda(&owner, &cold).0
+        );
+
+        let payout = Payout {
+            listed: false,
+            ..payout
+        };
+        let ix = claim_withdrawal_to(&owner, 0, 0, &[], &[], &payout);
+        assert_eq!(ix.accounts[5].pubkey, cold);
+        assert_eq!(ix.accounts[6].pubkey, defi_tools::ID);
+    }
+
+    // 测试6: 提供监护人时监护人账户为签名者，否则为程序ID占位
+    #[test]
+    fn test_guardian_account() {
+        let owner = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let treasury = Pubkey::new_unique();
+
+        let ix = withdraw_funds(&owner, 0, 1, &treasury, &[]);
+        assert_eq!(ix.accounts[8].pubkey, defi_tools::ID);
+        assert!(!ix.accounts[8].is_signer);
+
+        let payout = Payout {
+            guardian: Some(guardian),
+            ..Payout::to_owner(&owner)
+        };
+        let ix = withdraw_funds_to(&owner, 0, 1, &treasury, &[], &payout);
+        assert_eq!(ix.accounts[8].pubkey, guardian);
+        assert!(ix.accounts[8].is_signer);
+
+        let ix = set_spending_limit(&owner, 100, 3600, &guardian, None);
+        assert_eq!(ix.accounts[2].pubkey, defi_tools::ID);
+        let ix = set_spending_limit(&owner, 0, 0, &Pubkey::default(), Some(&guardian));
+        assert!(ix.accounts[2].is_signer);
+    }
+
+    // 测试7: 迁移策略时原策略和新策略使用同一ID、分别由新旧用户配置推导
+    #[test]
+    fn test_migrate_strategy_accounts() {
+        let previous = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let ix = migrate_strategy(&owner, &previous, 4);
+
+        assert_eq!(&ix.data[8..40], previous.as_ref());
+        assert_eq!(&ix.data[40..48], &4u64.to_le_bytes());
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+        let previous_profile = find_user_profile_pda(&previous).0;
+        let user_profile = find_user_profile_pda(&owner).0;
+        assert_eq!(ix.accounts[1].pubkey, previous_profile);
+        assert_eq!(ix.accounts[2].pubkey, user_profile);
+        let previous_strategy = find_strategy_pda(&previous_profile, 4).0;
+        assert_eq!(ix.accounts[3].pubkey, previous_strategy);
+        // 原策略的保护单账户只读，用于确认保护单已取消
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            find_protective_order_pda(&previous_strategy).0
+        );
+        assert!(!ix.accounts[4].is_writable);
+        assert_eq!(ix.accounts[5].pubkey, find_strategy_pda(&user_profile, 4).0);
+        assert_eq!(ix.accounts[6].pubkey, find_vault_pda(&previous).0);
+        assert_eq!(ix.accounts[7].pubkey, find_vault_pda(&owner).0);
+        assert!(ix.accounts[6].is_writable && ix.accounts[7].is_writable);
+
+        let ix = accept_owner(&owner, &previous);
+        assert_eq!(ix.accounts[1].pubkey, previous_profile);
+        assert_eq!(ix.accounts[2].pubkey, user_profile);
+    }
+
+    // 测试8: 按模板创建策略时模板地址由发布者和模板ID推导，资产注册表按模板分配项附加
+    #[test]
+    fn test_create_strategy_from_template_accounts() {
+        let owner = Pubkey::new_unique();
+        let template = StrategyTemplate {
+            publisher: Pubkey::new_unique(),
+            template_id: 3,
+            allocations: vec![
+                Allocation {
+                    protocol: 0,
+                    asset: 2,
+                    target_weight_bps: 5000,
+                },
+                Allocation {
+                    protocol: 1,
+                    asset: 4,
+                    target_weight_bps: 5000,
+                },
+            ],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 3600,
+                max_deviation_bps: 500,
+                auto_rebalance: true,
+            },
+            max_slippage_bps: 100,
+            risk_level: 2,
+            description_hash: [0; 32],
+            created_at: 0,
+            strategies_created: 0,
+            bump: 255,
+        };
+        let ix = create_strategy_from_template(&owner, 1, &template);
+
+        assert_eq!(
+            ix.data,
+            instruction::CreateStrategyFromTemplate::DISCRIMINATOR
+        );
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            find_strategy_template_pda(&template.publisher, 3).0
+        );
+        assert!(ix.accounts[2].is_writable);
+        let user_profile = find_user_profile_pda(&owner).0;
+        assert_eq!(ix.accounts[3].pubkey, find_strategy_pda(&user_profile, 1).0);
+        assert_eq!(ix.accounts.len(), 7);
+        assert_eq!(ix.accounts[5].pubkey, find_asset_registry_pda(2).0);
+        assert_eq!(ix.accounts[6].pubkey, find_asset_registry_pda(4).0);
+    }
+
+    // 测试9: 同步跟随策略时领投者收款地址取自领投策略，白名单账户按需附加，资产注册表按领投策略的分配项附加
+    #[test]
+    fn test_sync_follower_accounts() {
+        let owner = Pubkey::new_unique();
+        let strategy = find_strategy_pda(&find_user_profile_pda(&owner).0, 0).0;
+        let leader_owner = Pubkey::new_unique();
+        let leader_strategy = find_strategy_pda(&find_user_profile_pda(&leader_owner).0, 2).0;
+        let follow = StrategyFollow {
+            owner,
+            strategy,
+            leader: leader_strategy,
+            max_weight_bps: 6000,
+            delay_seconds: 0,
+            leader_fee_bps: 50,
+            followed_at: 0,
+            leader_synced_at: 0,
+            synced_at: 0,
+            syncs: 0,
+            bump: 255,
+        };
+        let leader = StrategyConfig {
+            owner: leader_owner,
+            strategy_id: 2,
+            allocations: vec![Allocation {
+                protocol: 0,
+                asset: 3,
+                target_weight_bps: 10_000,
+            }],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 3600,
+                max_deviation_bps: 500,
+                auto_rebalance: false,
+            },
+            created_at: 0,
+            last_executed_at: 0,
+            max_slippage_bps: 100,
+            total_value_lamports: 0,
+            fees_accrued_at: 0,
+            withdrawal_queue: Default::default(),
+            allocations_updated_at: 0,
+            peak_value_lamports: 0,
+            paused_at: 0,
+            asset_deposits: Vec::new(),
+            mode: StrategyMode::Manual,
+            delegates: 0,
+            origin: Pubkey::default(),
+            unwind_pending: false,
+        };
+        let authority = Pubkey::new_unique();
+        let treasury = Pubkey::new_unique();
+        let ix = sync_follower(
+            &authority,
+            &follow,
+            &leader_strategy,
+            &leader,
+            &treasury,
+            false,
+        );
+
+        assert_eq!(ix.data, instruction::SyncFollower::DISCRIMINATOR);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, find_strategy_follow_pda(&strategy).0);
+        assert_eq!(ix.accounts[3].pubkey, strategy);
+        assert_eq!(ix.accounts[4].pubkey, leader_strategy);
+        assert_eq!(ix.accounts[5].pubkey, leader_owner);
+        assert!(ix.accounts[5].is_writable);
+        assert_eq!(ix.accounts[6].pubkey, defi_tools::ID);
+        assert_eq!(ix.accounts[7].pubkey, find_vault_pda(&owner).0);
+        assert_eq!(ix.accounts.len(), 12);
+        assert_eq!(ix.accounts[11].pubkey, find_asset_registry_pda(3).0);
+
+        // 领投者在跟随者白名单中时附加白名单账户
+        let ix = sync_follower(
+            &authority,
+            &follow,
+            &leader_strategy,
+            &leader,
+            &treasury,
+            true,
+        );
+        assert_eq!(
+            ix.accounts[6].pubkey,
+            find_withdrawal_destination_pda(&owner, &leader_owner).0
+        );
+    }
+
+    // 测试10: 定投计划地址由策略推导，价格账户附加在固定账户之后
+    #[test]
+    fn test_execute_dca_accounts() {
+        let owner = Pubkey::new_unique();
+        let strategy = find_strategy_pda(&find_user_profile_pda(&owner).0, 1).0;
+        let schedule = DcaSchedule {
+            owner,
+            strategy,
+            params: DcaParams {
+                quote_protocol: 0,
+                quote_asset: 0,
+                target_protocol: 1,
+                target_asset: 2,
+                increment_lamports: 1_000,
+                max_price_quote: 0,
+            },
+            created_at: 0,
+            purchased_lamports: 0,
+            purchases: 0,
+            carried_lamports: 0,
+            bump: 255,
+        };
+        let prices = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let ix = execute_dca(&Pubkey::new_unique(), &schedule, &prices);
+
+        assert_eq!(ix.data, instruction::ExecuteDca::DISCRIMINATOR);
+        assert_eq!(ix.accounts[1].pubkey, find_dca_schedule_pda(&strategy).0);
+        assert!(ix.accounts[1].is_writable);
+        assert_eq!(ix.accounts[2].pubkey, find_user_profile_pda(&owner).0);
+        assert_eq!(ix.accounts[3].pubkey, strategy);
+        assert_eq!(ix.accounts.len(), 7);
+        assert_eq!(ix.accounts[5].pubkey, prices[0]);
+        assert_eq!(
+            create_dca(&owner, 1, schedule.params).accounts[3].pubkey,
+            ix.accounts[1].pubkey
+        );
+    }
+
+    // 测试11: 保护单地址由策略推导，估值时租金退还给保护单记录的所有者
+    #[test]
+    fn test_check_protection_accounts() {
+        let owner = Pubkey::new_unique();
+        let strategy = find_strategy_pda(&find_user_profile_pda(&owner).0, 1).0;
+        let params = ProtectionParams {
+            stop_loss_lamports: 1_000,
+            max_drawdown_bps: 0,
+            take_profit_lamports: 0,
+            safe_protocol: 1,
+            safe_asset: 2,
+        };
+        let order = ProtectiveOrder {
+            owner,
+            strategy,
+            params,
+            marked_value_lamports: 0,
+            marked_principal_lamports: 0,
+            marks: Vec::new(),
+            marked_at: 0,
+            created_at: 0,
+            bump: 255,
+        };
+        let prices = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let ix = check_protection(&Pubkey::new_unique(), &order, &prices);
+
+        assert_eq!(ix.data, instruction::CheckProtection::DISCRIMINATOR);
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            find_protective_order_pda(&strategy).0
+        );
+        assert_eq!(ix.accounts[2].pubkey, owner);
+        assert!(ix.accounts[2].is_writable);
+        assert_eq!(ix.accounts[3].pubkey, strategy);
+        assert_eq!(ix.accounts.len(), 7);
+        assert_eq!(ix.accounts[5].pubkey, prices[0]);
+
+        let set = set_protection(&owner, 1, params, &prices);
+        assert_eq!(set.accounts[3].pubkey, ix.accounts[1].pubkey);
+        assert_eq!(set.accounts[4].pubkey, find_asset_registry_pda(2).0);
+    }
+
+    // 测试12: 轮动时按协议编号顺序附加候选协议的利率追踪账户
+    #[test]
+    fn test_rotate_yield_accounts() {
+        let owner = Pubkey::new_unique();
+        let strategy = find_strategy_pda(&find_user_profile_pda(&owner).0, 1).0;
+        let params = YieldParams {
+            asset: 2,
+            candidate_protocols: 0b1010,
+            min_apy_advantage_bps: 50,
+            min_hold_seconds: 3600,
+        };
+        let rotation = YieldRotation {
+            owner,
+            strategy,
+            params,
+            held_since: 0,
+            rotations: 0,
+            created_at: 0,
+            bump: 255,
+        };
+        let ix = rotate_yield(&Pubkey::new_unique(), &rotation);
+
+        assert_eq!(ix.data, instruction::RotateYield::DISCRIMINATOR);
+        assert_eq!(ix.accounts[1].pubkey, find_yield_rotation_pda(&strategy).0);
+        assert_eq!(ix.accounts[3].pubkey, strategy);
+        assert!(ix.accounts[3].is_writable);
+        assert_eq!(ix.accounts.len(), 6);
+        assert_eq!(ix.accounts[4].pubkey, find_apy_tracker_pda(1, 2).0);
+        assert_eq!(ix.accounts[5].pubkey, find_apy_tracker_pda(3, 2).0);
+
+        let enable = enable_yield_rotation(&owner, 1, params);
+        assert_eq!(enable.accounts[3].pubkey, ix.accounts[1].pubkey);
+        assert_eq!(
+            register_apy_tracker(&owner, 3, 2, 500).accounts[3].pubkey,
+            ix.accounts[5].pubkey
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub mod client;
+pub mod error;
+pub mod instructions;
+pub mod pda;
+
+pub use client::Client;
+pub use error::{Error, Result};
@@ -0,0 +1,202 @@
+use solana_sdk::pubkey::Pubkey;
+
+// 与 sdk/src/utils/pda.ts 中的种子保持一致
+
+// 查找全局配置PDA
+pub fn find_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &defi_tools::ID)
+}
+
+// 查找资产注册表PDA
+pub fn find_asset_registry_pda(asset_id: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"asset", &[asset_id]], &defi_tools::ID)
+}
+
+// 查找用户配置PDA
+pub fn find_user_profile_pda(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user", owner.as_ref()], &defi_tools::ID)
+}
+
+// 查找用户资金库PDA，存款以 lamports 托管在该系统账户中
+pub fn find_vault_pda(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", owner.as_ref()], &defi_tools::ID)
+}
+
+// 查找策略配置PDA，strategy_id 为创建时用户的策略计数器
+pub fn find_strategy_pda(user_profile: &Pubkey, strategy_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"strategy",
+            user_profile.as_ref(),
+            &strategy_id.to_le_bytes(),
+        ],
+        &defi_tools::ID,
+    )
+}
+
+// 查找提款工单PDA，ticket_id 为申请时策略的下一个工单ID
+pub fn find_withdrawal_ticket_pda(strategy: &Pubkey, ticket_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"ticket", strategy.as_ref(), &ticket_id.to_le_bytes()],
+        &defi_tools::ID,
+    )
+}
+
+// 查找策略代理人PDA，每个策略和代理人地址对应一个
+pub fn find_strategy_delegate_pda(strategy: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"delegate", strategy.as_ref(), delegate.as_ref()],
+        &defi_tools::ID,
+    )
+}
+
+// 查找提款白名单地址PDA，每个用户和接收地址对应一个
+pub fn find_withdrawal_destination_pda(owner: &Pubkey, destination: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"destination", owner.as_ref(), destination.as_ref()],
+        &defi_tools::ID,
+    )
+}
+
+// 查找策略模板PDA，template_id 由发布者选择
+pub fn find_strategy_template_pda(publisher: &Pubkey, template_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"template", publisher.as_ref(), &template_id.to_le_bytes()],
+        &defi_tools::ID,
+    )
+}
+
+// 查找跟投关系PDA，每个跟随策略最多跟随一个领投策略
+pub fn find_strategy_follow_pda(strategy: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"follow", strategy.as_ref()], &defi_tools::ID)
+}
+
+// 查找策略的定投计划PDA
+pub fn find_dca_schedule_pda(strategy: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dca", strategy.as_ref()], &defi_tools::ID)
+}
+
+// 查找策略的保护单PDA
+pub fn find_protective_order_pda(strategy: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"protect", strategy.as_ref()], &defi_tools::ID)
+}
+
+// 查找收益率轮动计划PDA
+pub fn find_yield_rotation_pda(strategy: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"yield", strategy.as_ref()], &defi_tools::ID)
+}
+
+// 查找按协议和资产记录市场利率的追踪PDA，账户类型与头寸追踪相同
+pub fn find_apy_tracker_pda(protocol: u8, asset: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"apy", &[protocol], &[asset]], &defi_tools::ID)
+}
+
+// 查找头寸追踪PDA
+pub fn find_position_tracker_pda(strategy_id: u64, protocol: &str, asset: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"position",
+            &strategy_id.to_le_bytes(),
+            protocol.as_bytes(),
+            asset.as_bytes(),
+        ],
+        &defi_tools::ID,
+    )
+}
+
+// 查找程序的 ProgramData 账户，保存升级权限
+pub fn find_program_data_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[defi_tools::ID.as_ref()],
+        &solana_sdk::bpf_loader_upgradeable::ID,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    // 测试1: 程序ID与TS SDK一致
+    #[test]
+    fn test_program_id_matches_sdk() {
+        assert_eq!(
+            defi_tools::ID,
+            Pubkey::from_str("CdH2ymLMr7RyYcd1nyDZm59DRv6JgrtzuAxoH7STFvnm").unwrap()
+        );
+    }
+
+    // 测试2: 不同策略ID得到不同的策略PDA
+    #[test]
+    fn test_strategy_pda_depends_on_id() {
+        let (user_profile, _) = find_user_profile_pda(&Pubkey::new_unique());
+        let (first, _) = find_strategy_pda(&user_profile, 0);
+        let (second, _) = find_strategy_pda(&user_profile, 1);
+        assert_ne!(first, second);
+        assert_eq!(find_strategy_pda(&user_profile, 0).0, first);
+    }
+
+    // 测试3: 提款工单PDA与程序的派生方式一致
+    #[test]
+    fn test_withdrawal_ticket_pda_matches_program() {
+        let strategy = Pubkey::new_unique();
+        for ticket_id in [0, 1, u64::MAX] {
+            assert_eq!(
+                find_withdrawal_ticket_pda(&strategy, ticket_id).0,
+                defi_tools::withdrawals::ticket_address(&strategy, ticket_id)
+            );
+        }
+    }
+
+    // 测试4: 同一代理人在不同策略上的代理人PDA不同
+    #[test]
+    fn test_strategy_delegate_pda_depends_on_strategy() {
+        let delegate = Pubkey::new_unique();
+        let (first, _) = find_strategy_delegate_pda(&Pubkey::new_unique(), &delegate);
+        let (second, _) = find_strategy_delegate_pda(&Pubkey::new_unique(), &delegate);
+        assert_ne!(first, second);
+    }
+
+    // 测试5: 白名单地址PDA与程序的派生方式一致
+    #[test]
+    fn test_withdrawal_destination_pda_matches_program() {
+        let owner = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        assert_eq!(
+            find_withdrawal_destination_pda(&owner, &destination).0,
+            defi_tools::allowlist::destination_address(&owner, &destination)
+        );
+    }
+
+    // 测试6: 模板PDA与程序的派生方式一致
+    #[test]
+    fn test_strategy_template_pda_matches_program() {
+        let publisher = Pubkey::new_unique();
+        for template_id in [0, 9, u64::MAX] {
+            assert_eq!(
+                find_strategy_template_pda(&publisher, template_id).0,
+                defi_tools::templates::template_address(&publisher, template_id)
+            );
+        }
+    }
+
+    // 测试7: 同一用户不同策略的跟投关系PDA不同
+    #[test]
+    fn test_strategy_follow_pda_depends_on_strategy() {
+        let user_profile = find_user_profile_pda(&Pubkey::new_unique()).0;
+        let (first, _) = find_strategy_follow_pda(&find_strategy_pda(&user_profile, 0).0);
+        let (second, _) = find_strategy_follow_pda(&find_strategy_pda(&user_profile, 1).0);
+        assert_ne!(first, second);
+    }
+
+    // 测试8: 利率追踪PDA与程序的派生方式一致
+    #[test]
+    fn test_apy_tracker_pda_matches_program() {
+        for (protocol, asset) in [(1, 0), (3, 0), (1, 255)] {
+            assert_eq!(
+                find_apy_tracker_pda(protocol, asset).0,
+                defi_tools::apy::apy_tracker_address(protocol, asset)
+            );
+        }
+    }
+}
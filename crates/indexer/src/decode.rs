@@ -0,0 +1,320 @@
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use defi_tools::events::*;
+use defi_tools::{
+    AssetRegistry, DcaSchedule, GlobalConfig, PositionTracker, ProtectiveOrder, StrategyConfig,
+    StrategyDelegate, StrategyFollow, StrategyTemplate, UserProfile, WithdrawalDestination,
+    WithdrawalTicket, YieldRotation,
+};
+
+// defi-tools 程序输出的事件
+pub enum ProgramEvent {
+    ConfigInitialized(ConfigInitialized),
+    OracleGuardUpdated(OracleGuardUpdated),
+    FeeConfigUpdated(FeeConfigUpdated),
+    DepositLimitsUpdated(DepositLimitsUpdated),
+    WithdrawalDelayUpdated(WithdrawalDelayUpdated),
+    AllowlistDelayUpdated(AllowlistDelayUpdated),
+    AssetRegistered(AssetRegistered),
+    AssetUpdated(AssetUpdated),
+    UserInitialized(UserInitialized),
+    StrategyCreated(StrategyCreated),
+    StrategyUpdated(StrategyUpdated),
+    FundsDeposited(FundsDeposited),
+    FundsWithdrawn(FundsWithdrawn),
+    StrategyExecuted(StrategyExecuted),
+    PositionsRebalanced(PositionsRebalanced),
+    FeesCharged(FeesCharged),
+    WithdrawalRequested(WithdrawalRequested),
+    WithdrawalClaimed(WithdrawalClaimed),
+    WithdrawalCancelled(WithdrawalCancelled),
+    DelegateAdded(DelegateAdded),
+    DelegateRevoked(DelegateRevoked),
+    WithdrawalDestinationAdded(WithdrawalDestinationAdded),
+    WithdrawalDestinationRemoved(WithdrawalDestinationRemoved),
+    WithdrawalAllowlistUpdated(WithdrawalAllowlistUpdated),
+    SpendingLimitUpdated(SpendingLimitUpdated),
+    OwnerProposed(OwnerProposed),
+    OwnerAccepted(OwnerAccepted),
+    StrategyMigrated(StrategyMigrated),
+    TemplatePublished(TemplatePublished),
+    TemplateRetired(TemplateRetired),
+    StrategyCreatedFromTemplate(StrategyCreatedFromTemplate),
+    StrategyFollowed(StrategyFollowed),
+    StrategyUnfollowed(StrategyUnfollowed),
+    FollowerSynced(FollowerSynced),
+    DcaCreated(DcaCreated),
+    DcaCancelled(DcaCancelled),
+    DcaExecuted(DcaExecuted),
+    ProtectionSet(ProtectionSet),
+    ProtectionCancelled(ProtectionCancelled),
+    ProtectionChecked(ProtectionChecked),
+    ProtectionTriggered(ProtectionTriggered),
+    StrategyResumed(StrategyResumed),
+    ApyReported(ApyReported),
+    YieldRotationEnabled(YieldRotationEnabled),
+    YieldRotationDisabled(YieldRotationDisabled),
+    YieldRotated(YieldRotated),
+}
+
+impl ProgramEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgramEvent::ConfigInitialized(_) => "ConfigInitialized",
+            ProgramEvent::OracleGuardUpdated(_) => "OracleGuardUpdated",
+            ProgramEvent::FeeConfigUpdated(_) => "FeeConfigUpdated",
+            ProgramEvent::DepositLimitsUpdated(_) => "DepositLimitsUpdated",
+            ProgramEvent::WithdrawalDelayUpdated(_) => "WithdrawalDelayUpdated",
+            ProgramEvent::AllowlistDelayUpdated(_) => "AllowlistDelayUpdated",
+            ProgramEvent::AssetRegistered(_) => "AssetRegistered",
+            ProgramEvent::AssetUpdated(_) => "AssetUpdated",
+            ProgramEvent::UserInitialized(_) => "UserInitialized",
+            ProgramEvent::StrategyCreated(_) => "StrategyCreated",
+            ProgramEvent::StrategyUpdated(_) => "StrategyUpdated",
+            ProgramEvent::FundsDeposited(_) => "FundsDeposited",
+            ProgramEvent::FundsWithdrawn(_) => "FundsWithdrawn",
+            ProgramEvent::StrategyExecuted(_) => "StrategyExecuted",
+            ProgramEvent::PositionsRebalanced(_) => "PositionsRebalanced",
+            ProgramEvent::FeesCharged(_) => "FeesCharged",
+            ProgramEvent::WithdrawalRequested(_) => "WithdrawalRequested",
+            ProgramEvent::WithdrawalClaimed(_) => "WithdrawalClaimed",
+            ProgramEvent::WithdrawalCancelled(_) => "WithdrawalCancelled",
+            ProgramEvent::DelegateAdded(_) => "DelegateAdded",
+            ProgramEvent::DelegateRevoked(_) => "DelegateRevoked",
+            ProgramEvent::WithdrawalDestinationAdded(_) => "WithdrawalDestinationAdded",
+            ProgramEvent::WithdrawalDestinationRemoved(_) => "WithdrawalDestinationRemoved",
+            ProgramEvent::WithdrawalAllowlistUpdated(_) => "WithdrawalAllowlistUpdated",
+            ProgramEvent::SpendingLimitUpdated(_) => "SpendingLimitUpdated",
+            ProgramEvent::OwnerProposed(_) => "OwnerProposed",
+            ProgramEvent::OwnerAccepted(_) => "OwnerAccepted",
+            ProgramEvent::StrategyMigrated(_) => "StrategyMigrated",
+            ProgramEvent::TemplatePublished(_) => "TemplatePublished",
+            ProgramEvent::TemplateRetired(_) => "TemplateRetired",
+            ProgramEvent::StrategyCreatedFromTemplate(_) => "StrategyCreatedFromTemplate",
+            ProgramEvent::StrategyFollowed(_) => "StrategyFollowed",
+            ProgramEvent::StrategyUnfollowed(_) => "StrategyUnfollowed",
+            ProgramEvent::FollowerSynced(_) => "FollowerSynced",
+            ProgramEvent::DcaCreated(_) => "DcaCreated",
+            ProgramEvent::DcaCancelled(_) => "DcaCancelled",
+            ProgramEvent::DcaExecuted(_) => "DcaExecuted",
+            ProgramEvent::ProtectionSet(_) => "ProtectionSet",
+            ProgramEvent::ProtectionCancelled(_) => "ProtectionCancelled",
+            ProgramEvent::ProtectionChecked(_) => "ProtectionChecked",
+            ProgramEvent::ProtectionTriggered(_) => "ProtectionTriggered",
+            ProgramEvent::StrategyResumed(_) => "StrategyResumed",
+            ProgramEvent::ApyReported(_) => "ApyReported",
+            ProgramEvent::YieldRotationEnabled(_) => "YieldRotationEnabled",
+            ProgramEvent::YieldRotationDisabled(_) => "YieldRotationDisabled",
+            ProgramEvent::YieldRotated(_) => "YieldRotated",
+        }
+    }
+}
+
+fn event<T: AnchorDeserialize>(mut payload: &[u8], name: &str) -> Result<T> {
+    T::deserialize(&mut payload).with_context(|| format!("解码事件失败: {name}"))
+}
+
+// 解码一条 "Program data:" 数据。未知判别符返回 None，数据损坏返回错误
+pub fn decode_event(data: &[u8]) -> Result<Option<ProgramEvent>> {
+    if data.len() < 8 {
+        return Ok(None);
+    }
+    let (discriminator, payload) = data.split_at(8);
+
+    macro_rules! match_events {
+        ($($name:ident),* $(,)?) => {
+            $(
+                if discriminator == $name::DISCRIMINATOR {
+                    return Ok(Some(ProgramEvent::$name(event(payload, stringify!($name))?)));
+                }
+            )*
+        };
+    }
+
+    match_events!(
+        ConfigInitialized,
+        OracleGuardUpdated,
+        FeeConfigUpdated,
+        DepositLimitsUpdated,
+        WithdrawalDelayUpdated,
+        AllowlistDelayUpdated,
+        AssetRegistered,
+        AssetUpdated,
+        UserInitialized,
+        StrategyCreated,
+        StrategyUpdated,
+        FundsDeposited,
+        FundsWithdrawn,
+        StrategyExecuted,
+        PositionsRebalanced,
+        FeesCharged,
+        WithdrawalRequested,
+        WithdrawalClaimed,
+        WithdrawalCancelled,
+        DelegateAdded,
+        DelegateRevoked,
+        WithdrawalDestinationAdded,
+        WithdrawalDestinationRemoved,
+        WithdrawalAllowlistUpdated,
+        SpendingLimitUpdated,
+        OwnerProposed,
+        OwnerAccepted,
+        StrategyMigrated,
+        TemplatePublished,
+        TemplateRetired,
+        StrategyCreatedFromTemplate,
+        StrategyFollowed,
+        StrategyUnfollowed,
+        FollowerSynced,
+        DcaCreated,
+        DcaCancelled,
+        DcaExecuted,
+        ProtectionSet,
+        ProtectionCancelled,
+        ProtectionChecked,
+        ProtectionTriggered,
+        StrategyResumed,
+        ApyReported,
+        YieldRotationEnabled,
+        YieldRotationDisabled,
+        YieldRotated,
+    );
+
+    Ok(None)
+}
+
+// defi-tools 程序拥有的账户
+pub enum ProgramAccount {
+    GlobalConfig(GlobalConfig),
+    AssetRegistry(AssetRegistry),
+    UserProfile(UserProfile),
+    StrategyConfig(StrategyConfig),
+    PositionTracker(PositionTracker),
+    WithdrawalTicket(WithdrawalTicket),
+    StrategyDelegate(StrategyDelegate),
+    WithdrawalDestination(WithdrawalDestination),
+    StrategyTemplate(StrategyTemplate),
+    StrategyFollow(StrategyFollow),
+    DcaSchedule(DcaSchedule),
+    ProtectiveOrder(ProtectiveOrder),
+    YieldRotation(YieldRotation),
+}
+
+// 按账户判别符解码账户数据。未知判别符返回 None
+pub fn decode_account(data: &[u8]) -> Result<Option<ProgramAccount>> {
+    if data.len() < 8 {
+        return Ok(None);
+    }
+    let discriminator = &data[..8];
+
+    macro_rules! match_accounts {
+        ($($name:ident),* $(,)?) => {
+            $(
+                if discriminator == $name::DISCRIMINATOR {
+                    let account = $name::try_deserialize(&mut &data[..])
+                        .with_context(|| format!("解码账户失败: {}", stringify!($name)))?;
+                    return Ok(Some(ProgramAccount::$name(account)));
+                }
+            )*
+        };
+    }
+
+    match_accounts!(
+        GlobalConfig,
+        AssetRegistry,
+        UserProfile,
+        StrategyConfig,
+        PositionTracker,
+        WithdrawalTicket,
+        StrategyDelegate,
+        WithdrawalDestination,
+        StrategyTemplate,
+        StrategyFollow,
+        DcaSchedule,
+        ProtectiveOrder,
+        YieldRotation,
+    );
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::{AccountSerialize, Event};
+    use defi_tools::SpendingLimit;
+
+    // 测试1: 事件编码后可以解码回来
+    #[test]
+    fn test_decode_event_roundtrip() {
+        let strategy = Pubkey::new_unique();
+        let data = FundsDeposited {
+            owner: Pubkey::new_unique(),
+            strategy,
+            strategy_id: 3,
+            amount: 500,
+            strategy_value_lamports: 1500,
+            user_value_lamports: 2500,
+            timestamp: 1700000000,
+        }
+        .data();
+
+        match decode_event(&data).unwrap() {
+            Some(ProgramEvent::FundsDeposited(event)) => {
+                assert_eq!(event.strategy, strategy);
+                assert_eq!(event.amount, 500);
+                assert_eq!(event.strategy_value_lamports, 1500);
+            }
+            _ => panic!("应解码为 FundsDeposited"),
+        }
+    }
+
+    // 测试2: 未知判别符被忽略，数据截断则报错
+    #[test]
+    fn test_decode_event_unknown_and_truncated() {
+        assert!(decode_event(&[0u8; 16]).unwrap().is_none());
+        assert!(decode_event(&[1, 2, 3]).unwrap().is_none());
+
+        let data = StrategyExecuted {
+            owner: Pubkey::new_unique(),
+            strategy: Pubkey::new_unique(),
+            strategy_id: 1,
+            timestamp: 1,
+        }
+        .data();
+        assert!(decode_event(&data[..20]).is_err());
+    }
+
+    // 测试3: 账户按判别符解码
+    #[test]
+    fn test_decode_account_user_profile() {
+        let owner = Pubkey::new_unique();
+        let profile = UserProfile {
+            owner,
+            risk_level: 3,
+            strategy_counter: 2,
+            vault_bump: 254,
+            last_activity: 10,
+            total_value_lamports: 99,
+            is_paused: false,
+            allowlist_enabled: true,
+            allowlist_disable_at: 0,
+            spending_limit: SpendingLimit::default(),
+            pending_owner: Pubkey::default(),
+            pending_owner_at: 0,
+            migrated_to: Pubkey::default(),
+            migrated_from: Pubkey::default(),
+        };
+        let mut data = Vec::new();
+        profile.try_serialize(&mut data).unwrap();
+
+        match decode_account(&data).unwrap() {
+            Some(ProgramAccount::UserProfile(decoded)) => {
+                assert_eq!(decoded.owner, owner);
+                assert_eq!(decoded.strategy_counter, 2);
+            }
+            _ => panic!("应解码为 UserProfile"),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::decode::{decode_account, decode_event, ProgramEvent};
+use crate::logs::program_data;
+use crate::rpc::{RpcClient, TransactionSource};
+use crate::store::Store;
+use crate::transaction::{load_file, TransactionRecord};
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+// 单次运行的索引结果统计
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    pub transactions: usize,
+    pub events: usize,
+    // 同步时为留到下次同步的签名数，快照时为无法解码的账户数
+    pub skipped: usize,
+}
+
+pub struct Indexer {
+    store: Store,
+    program_id: Pubkey,
+}
+
+impl Indexer {
+    pub fn new(store: Store, program_id: Pubkey) -> Self {
+        Self { store, program_id }
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    // 解码并写入一笔交易。失败的交易只记录签名，不写入事件
+    pub fn index_transaction(&mut self, record: &TransactionRecord) -> Result<usize> {
+        let events = if record.failed {
+            Vec::new()
+        } else {
+            self.decode_events(record)?
+        };
+        self.store.record_transaction(record, &events)?;
+        Ok(events.len())
+    }
+
+    fn decode_events(&self, record: &TransactionRecord) -> Result<Vec<ProgramEvent>> {
+        let mut events = Vec::new();
+        for data in program_data(&record.log_messages, &self.program_id) {
+            if let Some(event) = decode_event(&data)
+                .with_context(|| format!("交易 {} 中的事件无法解码", record.signature))?
+            {
+                events.push(event);
+            }
+        }
+        Ok(events)
+    }
+
+    // 离线索引保存在文件中的交易，按 slot 顺序写入
+    pub fn index_files(&mut self, paths: &[impl AsRef<Path>]) -> Result<IndexStats> {
+        let mut records = Vec::new();
+        for path in paths {
+            records.extend(load_file(path.as_ref())?);
+        }
+        records.sort_by_key(|record| record.slot);
+
+        let mut stats = IndexStats::default();
+        for record in &records {
+            stats.events += self.index_transaction(record)?;
+            stats.transactions += 1;
+        }
+        Ok(stats)
+    }
+
+    // 拉取上次游标之后的全部签名，从旧到新最多写入 limit 笔交易，每笔交易后推进游标。
+    // 交易尚不可查询时停止，游标不会越过未写入的交易，其余签名留到下次同步
+    pub fn sync(&mut self, source: &impl TransactionSource, limit: usize) -> Result<IndexStats> {
+        let cursor = self.store.cursor()?;
+        let signatures = source.signatures_since(&self.program_id, cursor.as_deref())?;
+
+        let mut stats = IndexStats::default();
+        let mut pending = signatures.iter().rev();
+        for signature in pending.by_ref().take(limit) {
+            if !self.store.has_transaction(signature)? {
+                let Some(record) = source.transaction(signature)? else {
+                    stats.skipped += 1;
+                    break;
+                };
+                stats.events += self.index_transaction(&record)?;
+                stats.transactions += 1;
+            }
+            self.store.set_cursor(signature)?;
+        }
+        stats.skipped += pending.len();
+        Ok(stats)
+    }
+
+    // 读取程序账户的当前状态，写入持仓及最新的用户、策略状态
+    pub fn snapshot(&mut self, rpc: &RpcClient) -> Result<IndexStats> {
+        let mut stats = IndexStats::default();
+        for (address, data) in rpc.program_accounts(&self.program_id)? {
+            match decode_account(&data)? {
+                Some(account) => {
+                    self.store.record_account(&address, &account)?;
+                    stats.events += 1;
+                }
+                None => stats.skipped += 1,
+            }
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use defi_tools::events::StrategyExecuted;
+    use std::collections::HashMap;
+
+    // 内存中的交易来源，signatures 按时间从旧到新排列，不在 records 中的交易尚不可查询
+    struct MemorySource {
+        signatures: Vec<String>,
+        records: HashMap<String, TransactionRecord>,
+    }
+
+    impl TransactionSource for MemorySource {
+        fn signature_page(
+            &self,
+            _address: &Pubkey,
+            until: Option<&str>,
+            before: Option<&str>,
+            limit: usize,
+        ) -> Result<Vec<String>> {
+            Ok(self
+                .signatures
+                .iter()
+                .rev()
+                .skip_while(|signature| before.is_some_and(|before| *signature != before))
+                .skip(usize::from(before.is_some()))
+                .take_while(|signature| until != Some(signature.as_str()))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        fn transaction(&self, signature: &str) -> Result<Option<TransactionRecord>> {
+            Ok(self.records.get(signature).cloned())
+        }
+    }
+
+    fn executed_log(program: &Pubkey, strategy: Pubkey) -> Vec<String> {
+        let data = StrategyExecuted {
+            owner: Pubkey::new_unique(),
+            strategy,
+            strategy_id: 0,
+            timestamp: 100,
+        }
+        .data();
+        vec![
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: ExecuteStrategy".to_string(),
+            format!("Program data: {}", STANDARD.encode(data)),
+            format!("Program {program} success"),
+        ]
+    }
+
+    // 测试1: 成功交易中的事件被写入历史
+    #[test]
+    fn test_index_transaction() {
+        let program = defi_tools::ID;
+        let strategy = Pubkey::new_unique();
+        let mut indexer = Indexer::new(Store::open_in_memory().unwrap(), program);
+
+        let record = TransactionRecord {
+            signature: "sig".to_string(),
+            slot: 5,
+            block_time: Some(100),
+            failed: false,
+            log_messages: executed_log(&program, strategy),
+        };
+        assert_eq!(indexer.index_transaction(&record).unwrap(), 1);
+
+        let history = indexer.store().strategy_history(&strategy).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].kind, "execute");
+    }
+
+    // 测试2: 失败的交易不写入事件
+    #[test]
+    fn test_index_failed_transaction() {
+        let program = defi_tools::ID;
+        let strategy = Pubkey::new_unique();
+        let mut indexer = Indexer::new(Store::open_in_memory().unwrap(), program);
+
+        let record = TransactionRecord {
+            signature: "sig".to_string(),
+            slot: 5,
+            block_time: None,
+            failed: true,
+            log_messages: executed_log(&program, strategy),
+        };
+        assert_eq!(indexer.index_transaction(&record).unwrap(), 0);
+        assert!(indexer.store().has_transaction("sig").unwrap());
+        assert!(indexer
+            .store()
+            .strategy_history(&strategy)
+            .unwrap()
+            .is_empty());
+    }
+
+    // 测试3: 待同步的签名超过一页和 limit 时从最旧的开始写入，
+    // 遇到尚不可查询的交易时停止，游标不越过它
+    #[test]
+    fn test_sync_oldest_first() {
+        let program = defi_tools::ID;
+        let strategy = Pubkey::new_unique();
+        let mut indexer = Indexer::new(Store::open_in_memory().unwrap(), program);
+
+        let total = crate::rpc::SIGNATURE_PAGE_LIMIT + 5;
+        let signatures: Vec<String> = (0..total).map(|i| format!("sig{i}")).collect();
+        let mut source = MemorySource {
+            signatures: signatures.clone(),
+            records: HashMap::new(),
+        };
+        for (slot, signature) in signatures.iter().take(4).enumerate() {
+            let record = TransactionRecord {
+                signature: signature.clone(),
+                slot: slot as u64,
+                block_time: Some(100),
+                failed: false,
+                log_messages: executed_log(&program, strategy),
+            };
+            source.records.insert(signature.clone(), record);
+        }
+
+        let stats = indexer.sync(&source, 3).unwrap();
+        assert_eq!(stats.transactions, 3);
+        assert_eq!(stats.skipped, total - 3);
+        assert_eq!(indexer.store().cursor().unwrap().as_deref(), Some("sig2"));
+
+        // sig4 尚不可查询
+        let stats = indexer.sync(&source, 3).unwrap();
+        assert_eq!(stats.transactions, 1);
+        assert_eq!(stats.skipped, total - 4);
+        assert_eq!(indexer.store().cursor().unwrap().as_deref(), Some("sig3"));
+        assert!(!indexer.store().has_transaction("sig4").unwrap());
+        assert_eq!(
+            indexer.store().strategy_history(&strategy).unwrap().len(),
+            4
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod decode;
+pub mod indexer;
+pub mod logs;
+pub mod rpc;
+pub mod store;
+pub mod transaction;
+
+pub use indexer::{IndexStats, Indexer};
+pub use store::Store;
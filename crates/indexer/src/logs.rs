@@ -0,0 +1,85 @@
+use anchor_lang::prelude::Pubkey;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+// 从交易日志中提取由指定程序直接输出的 "Program data:" 数据。
+// 通过跟踪 invoke/success/failed 调用栈，忽略其他程序输出的同名日志
+pub fn program_data(logs: &[String], program_id: &Pubkey) -> Vec<Vec<u8>> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut data = Vec::new();
+
+    for line in logs {
+        if let Some(payload) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if stack.last() == Some(&program_id.as_str()) {
+                if let Ok(bytes) = STANDARD.decode(payload.trim()) {
+                    data.push(bytes);
+                }
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut parts = rest.split_whitespace();
+        let (Some(id), Some(action)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if action == "invoke" {
+            stack.push(id);
+        } else if (action == "success" || action == "failed:") && stack.last() == Some(&id) {
+            stack.pop();
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(s: &str) -> String {
+        s.to_string()
+    }
+
+    // 测试1: 只收集目标程序的数据，忽略CPI调用的其他程序
+    #[test]
+    fn test_program_data_filters_by_program() {
+        let program = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let logs = vec![
+            line(&format!("Program {program} invoke [1]")),
+            line("Program log: Instruction: DepositFunds"),
+            line(&format!("Program {other} invoke [2]")),
+            line("Program data: AQID"),
+            line(&format!("Program {other} success")),
+            line("Program data: BAUG"),
+            line(&format!(
+                "Program {program} consumed 5000 of 200000 compute units"
+            )),
+            line(&format!("Program {program} success")),
+            line("Program data: BwgJ"),
+        ];
+
+        let data = program_data(&logs, &program);
+        assert_eq!(data, vec![vec![4, 5, 6]]);
+    }
+
+    // 测试2: 无法解码的数据被跳过
+    #[test]
+    fn test_program_data_skips_invalid_base64() {
+        let program = Pubkey::new_unique();
+        let logs = vec![
+            line(&format!("Program {program} invoke [1]")),
+            line("Program data: !!!"),
+            line("Program data: AQID"),
+            line(&format!("Program {program} success")),
+        ];
+
+        assert_eq!(program_data(&logs, &program), vec![vec![1, 2, 3]]);
+    }
+}
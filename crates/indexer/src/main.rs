@@ -0,0 +1,66 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use defi_tools_indexer::rpc::RpcClient;
+use defi_tools_indexer::{IndexStats, Indexer, Store};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(about = "将 defi-tools 程序的事件和账户索引到 SQLite")]
+struct Cli {
+    /// SQLite 数据库路径
+    #[arg(long, global = true, default_value = "defi-tools.db")]
+    db: PathBuf,
+
+    /// 要索引的程序ID，默认为 defi-tools 程序
+    #[arg(long, global = true, default_value_t = defi_tools::ID)]
+    program_id: Pubkey,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 索引保存在文件中的 getTransaction 结果(离线)
+    Files { paths: Vec<PathBuf> },
+    /// 从 RPC 同步上次游标之后的交易，从最旧的开始最多写入 limit 笔
+    Sync {
+        #[arg(long)]
+        rpc_url: String,
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+    },
+    /// 读取程序账户的当前状态
+    Snapshot {
+        #[arg(long)]
+        rpc_url: String,
+    },
+    /// 以 JSON 输出策略的历史操作
+    History { strategy: Pubkey },
+}
+
+fn print_stats(stats: IndexStats) {
+    println!(
+        "已索引 {} 笔交易, {} 条记录, 跳过 {}",
+        stats.transactions, stats.events, stats.skipped
+    );
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut indexer = Indexer::new(Store::open(&cli.db)?, cli.program_id);
+
+    match cli.command {
+        Command::Files { paths } => print_stats(indexer.index_files(&paths)?),
+        Command::Sync { rpc_url, limit } => {
+            print_stats(indexer.sync(&RpcClient::new(rpc_url), limit)?)
+        }
+        Command::Snapshot { rpc_url } => print_stats(indexer.snapshot(&RpcClient::new(rpc_url))?),
+        Command::History { strategy } => {
+            let history = indexer.store().strategy_history(&strategy)?;
+            println!("{}", serde_json::to_string_pretty(&history)?);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::transaction::TransactionRecord;
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+// getSignaturesForAddress 单次请求的最大条数
+pub const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
+// 索引器同步交易时对链的访问。生产环境使用 RPC，测试中使用内存中的交易
+pub trait TransactionSource {
+    // getSignaturesForAddress 的一页: until 之后、before 之前的签名，按时间从新到旧排列
+    fn signature_page(
+        &self,
+        address: &Pubkey,
+        until: Option<&str>,
+        before: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>>;
+
+    // 交易尚不可查询时返回 None
+    fn transaction(&self, signature: &str) -> Result<Option<TransactionRecord>>;
+
+    // 用 before 向更早的方向翻页直到 until，返回 until 之后的全部签名，按时间从新到旧排列
+    fn signatures_since(&self, address: &Pubkey, until: Option<&str>) -> Result<Vec<String>> {
+        let mut signatures: Vec<String> = Vec::new();
+        loop {
+            let before = signatures.last().map(String::as_str);
+            let page = self.signature_page(address, until, before, SIGNATURE_PAGE_LIMIT)?;
+            let done = page.len() < SIGNATURE_PAGE_LIMIT;
+            signatures.extend(page);
+            if done {
+                return Ok(signatures);
+            }
+        }
+    }
+}
+
+// 最小化的 JSON-RPC 客户端，只实现索引器需要的方法
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("RPC 请求失败: {method}"))?
+            .into_json()
+            .with_context(|| format!("RPC 响应解析失败: {method}"))?;
+
+        if let Some(error) = response.get("error") {
+            bail!("RPC 返回错误 {method}: {error}");
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("RPC 响应缺少 result: {method}"))
+    }
+
+    // 返回程序拥有的全部账户地址及数据
+    pub fn program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getProgramAccounts",
+            json!([
+                program_id.to_string(),
+                { "encoding": "base64", "commitment": "confirmed" }
+            ]),
+        )?;
+
+        result
+            .as_array()
+            .ok_or_else(|| anyhow!("getProgramAccounts 返回格式错误"))?
+            .iter()
+            .map(|item| {
+                let address = item
+                    .get("pubkey")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow!("账户缺少 pubkey"))?;
+                let address = Pubkey::from_str(address)
+                    .map_err(|e| anyhow!("无效的账户地址 {address}: {e}"))?;
+                let data = item
+                    .pointer("/account/data/0")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow!("账户 {address} 缺少数据"))?;
+                let data = STANDARD
+                    .decode(data)
+                    .with_context(|| format!("账户 {address} 数据解码失败"))?;
+                Ok((address, data))
+            })
+            .collect()
+    }
+}
+
+impl TransactionSource for RpcClient {
+    fn signature_page(
+        &self,
+        address: &Pubkey,
+        until: Option<&str>,
+        before: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let mut config = json!({ "limit": limit, "commitment": "confirmed" });
+        if let Some(until) = until {
+            config["until"] = json!(until);
+        }
+        if let Some(before) = before {
+            config["before"] = json!(before);
+        }
+
+        let result = self.call(
+            "getSignaturesForAddress",
+            json!([address.to_string(), config]),
+        )?;
+        Ok(result
+            .as_array()
+            .ok_or_else(|| anyhow!("getSignaturesForAddress 返回格式错误"))?
+            .iter()
+            .filter_map(|item| item.get("signature").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn transaction(&self, signature: &str) -> Result<Option<TransactionRecord>> {
+        let result = self.call(
+            "getTransaction",
+            json!([
+                signature,
+                {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0
+                }
+            ]),
+        )?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        TransactionRecord::from_rpc_json(&result).map(Some)
+    }
+}
@@ -0,0 +1,3348 @@
+use crate::decode::{ProgramAccount, ProgramEvent};
+use crate::transaction::TransactionRecord;
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use defi_tools::apy::apy_tracker_address;
+use defi_tools::events::{FundsDeposited, FundsWithdrawn};
+use defi_tools::{
+    Allocation, AssetParams, DelegateScope, DepositLimits, FeeConfig, OracleGuardConfig,
+    ProtectionTrigger, Protocol, RebalanceCondition,
+};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+use std::path::Path;
+
+// 数据库结构。事件表以 (signature, event_index) 为主键，重复索引同一交易不会产生重复记录
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS indexer_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    failed INTEGER NOT NULL,
+    event_count INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS config (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    admin TEXT NOT NULL,
+    max_price_age_seconds INTEGER NOT NULL,
+    max_confidence_bps INTEGER NOT NULL,
+    max_divergence_bps INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS fee_config (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    management_fee_bps INTEGER NOT NULL,
+    performance_fee_bps INTEGER NOT NULL,
+    treasury TEXT NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS deposit_limits (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    min_deposit_lamports INTEGER NOT NULL,
+    max_strategy_value_lamports INTEGER NOT NULL,
+    max_user_value_lamports INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS withdrawal_config (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    delay_seconds INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS allowlist_config (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    delay_seconds INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS assets (
+    asset_id INTEGER PRIMARY KEY,
+    mint TEXT NOT NULL,
+    decimals INTEGER NOT NULL,
+    oracle TEXT NOT NULL,
+    secondary_oracle TEXT NOT NULL,
+    enabled INTEGER NOT NULL,
+    max_allocation_bps INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS users (
+    owner TEXT PRIMARY KEY,
+    user_profile TEXT NOT NULL,
+    risk_level INTEGER NOT NULL,
+    strategy_counter INTEGER NOT NULL DEFAULT 0,
+    total_value_lamports INTEGER NOT NULL DEFAULT 0,
+    is_paused INTEGER NOT NULL DEFAULT 0,
+    allowlist_enabled INTEGER NOT NULL DEFAULT 0,
+    allowlist_disable_at INTEGER NOT NULL DEFAULT 0,
+    spending_max_lamports INTEGER NOT NULL DEFAULT 0,
+    spending_window_seconds INTEGER NOT NULL DEFAULT 0,
+    spending_guardian TEXT,
+    pending_owner TEXT,
+    pending_owner_at INTEGER NOT NULL DEFAULT 0,
+    migrated_to TEXT,
+    migrated_from TEXT,
+    created_at INTEGER,
+    last_activity INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS strategies (
+    strategy TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    allocations TEXT NOT NULL,
+    time_interval_seconds INTEGER NOT NULL,
+    max_deviation_bps INTEGER NOT NULL,
+    auto_rebalance INTEGER NOT NULL,
+    max_slippage_bps INTEGER NOT NULL,
+    total_value_lamports INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    last_executed_at INTEGER NOT NULL DEFAULT 0,
+    last_rebalanced_at INTEGER,
+    -- 所有权转移时策略迁移前后的地址，历史记录仍以原地址关联
+    migrated_from TEXT,
+    migrated_to TEXT,
+    -- 按模板创建时的模板地址
+    template TEXT,
+    -- 保护单触发后暂停的时间，0表示未暂停
+    paused_at INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS strategies_owner ON strategies (owner);
+
+CREATE TABLE IF NOT EXISTS deposits (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    strategy_value_lamports INTEGER NOT NULL,
+    user_value_lamports INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS deposits_strategy ON deposits (strategy, timestamp);
+
+CREATE TABLE IF NOT EXISTS withdrawals (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    strategy_value_lamports INTEGER NOT NULL,
+    user_value_lamports INTEGER NOT NULL,
+    destination TEXT,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS withdrawals_strategy ON withdrawals (strategy, timestamp);
+
+-- 提款工单当前状态: pending / claimed / cancelled
+CREATE TABLE IF NOT EXISTS withdrawal_tickets (
+    strategy TEXT NOT NULL,
+    ticket_id INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    requested_at INTEGER NOT NULL,
+    unlocks_at INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    closed_at INTEGER,
+    PRIMARY KEY (strategy, ticket_id)
+);
+CREATE INDEX IF NOT EXISTS withdrawal_tickets_owner ON withdrawal_tickets (owner, status);
+
+CREATE TABLE IF NOT EXISTS withdrawal_requests (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    ticket_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    unlocks_at INTEGER NOT NULL,
+    strategy_value_lamports INTEGER NOT NULL,
+    user_value_lamports INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS withdrawal_claims (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    ticket_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    destination TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS withdrawal_cancellations (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    ticket_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    strategy_value_lamports INTEGER NOT NULL,
+    user_value_lamports INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- 策略代理人的当前授权，撤销后保留记录并设置 revoked_at
+CREATE TABLE IF NOT EXISTS delegates (
+    strategy TEXT NOT NULL,
+    delegate TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    can_execute INTEGER NOT NULL,
+    can_rebalance INTEGER NOT NULL,
+    can_update INTEGER NOT NULL,
+    max_weight_change_bps INTEGER NOT NULL,
+    max_slippage_bps INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    added_at INTEGER,
+    revoked_at INTEGER,
+    PRIMARY KEY (strategy, delegate)
+);
+
+-- 提款白名单地址，移除后保留记录并设置 removed_at
+CREATE TABLE IF NOT EXISTS withdrawal_destinations (
+    owner TEXT NOT NULL,
+    destination TEXT NOT NULL,
+    added_at INTEGER NOT NULL,
+    activates_at INTEGER NOT NULL,
+    removed_at INTEGER,
+    PRIMARY KEY (owner, destination)
+);
+
+-- 策略模板，停用后保留记录并设置 retired_at
+CREATE TABLE IF NOT EXISTS templates (
+    template TEXT PRIMARY KEY,
+    publisher TEXT NOT NULL,
+    template_id INTEGER NOT NULL,
+    allocations TEXT NOT NULL,
+    time_interval_seconds INTEGER NOT NULL,
+    max_deviation_bps INTEGER NOT NULL,
+    auto_rebalance INTEGER NOT NULL,
+    max_slippage_bps INTEGER NOT NULL,
+    risk_level INTEGER NOT NULL,
+    description_hash TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    strategies_created INTEGER NOT NULL DEFAULT 0,
+    retired_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS templates_publisher ON templates (publisher);
+
+-- 跟投关系，每个跟随策略一条，取消跟随后保留记录并设置 unfollowed_at
+CREATE TABLE IF NOT EXISTS follows (
+    strategy TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    leader TEXT NOT NULL,
+    max_weight_bps INTEGER NOT NULL,
+    delay_seconds INTEGER NOT NULL,
+    leader_fee_bps INTEGER NOT NULL,
+    followed_at INTEGER NOT NULL,
+    synced_at INTEGER NOT NULL DEFAULT 0,
+    syncs INTEGER NOT NULL DEFAULT 0,
+    unfollowed_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS follows_leader ON follows (leader);
+
+CREATE TABLE IF NOT EXISTS follower_syncs (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    leader TEXT NOT NULL,
+    leader_owner TEXT NOT NULL,
+    allocations TEXT NOT NULL,
+    leader_fee_lamports INTEGER NOT NULL,
+    strategy_value_lamports INTEGER NOT NULL,
+    user_value_lamports INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS follower_syncs_leader ON follower_syncs (leader, timestamp);
+
+-- 定投计划，每个策略一条，取消后保留记录并设置 cancelled_at
+CREATE TABLE IF NOT EXISTS dca_schedules (
+    strategy TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    quote_protocol INTEGER NOT NULL,
+    quote_asset INTEGER NOT NULL,
+    target_protocol INTEGER NOT NULL,
+    target_asset INTEGER NOT NULL,
+    increment_lamports INTEGER NOT NULL,
+    max_price_quote INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    purchased_lamports INTEGER NOT NULL DEFAULT 0,
+    purchases INTEGER NOT NULL DEFAULT 0,
+    completed_at INTEGER,
+    cancelled_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS dca_purchases (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    quote_asset INTEGER NOT NULL,
+    target_asset INTEGER NOT NULL,
+    amount_lamports INTEGER NOT NULL,
+    price_quote INTEGER NOT NULL,
+    max_fill_price_quote INTEGER NOT NULL,
+    allocations TEXT NOT NULL,
+    completed INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS dca_purchases_strategy ON dca_purchases (strategy, timestamp);
+
+-- 保护单，每个策略一条，取消或触发后保留记录并设置 cancelled_at 或 triggered_at
+CREATE TABLE IF NOT EXISTS protective_orders (
+    strategy TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    stop_loss_lamports INTEGER NOT NULL,
+    max_drawdown_bps INTEGER NOT NULL,
+    take_profit_lamports INTEGER NOT NULL,
+    safe_protocol INTEGER NOT NULL,
+    safe_asset INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    -- 最近一次估值
+    value_lamports INTEGER NOT NULL,
+    peak_value_lamports INTEGER NOT NULL,
+    checked_at INTEGER NOT NULL,
+    triggered_at INTEGER,
+    cancelled_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS protection_triggers (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    -- stop_loss, max_drawdown 或 take_profit
+    trigger TEXT NOT NULL,
+    value_lamports INTEGER NOT NULL,
+    peak_value_lamports INTEGER NOT NULL,
+    unwound TEXT NOT NULL,
+    allocations TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS protection_triggers_strategy
+    ON protection_triggers (strategy, timestamp);
+
+-- 收益率轮动计划，每个策略一条，停用后保留记录并设置 disabled_at
+CREATE TABLE IF NOT EXISTS yield_rotations (
+    strategy TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    asset INTEGER NOT NULL,
+    candidate_protocols INTEGER NOT NULL,
+    min_apy_advantage_bps INTEGER NOT NULL,
+    min_hold_seconds INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    held_since INTEGER NOT NULL,
+    rotations INTEGER NOT NULL DEFAULT 0,
+    disabled_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS yield_moves (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    asset INTEGER NOT NULL,
+    from_protocol INTEGER NOT NULL,
+    from_apy_bps INTEGER NOT NULL,
+    to_protocol INTEGER NOT NULL,
+    to_apy_bps INTEGER NOT NULL,
+    allocations TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS yield_moves_strategy ON yield_moves (strategy, timestamp);
+
+CREATE TABLE IF NOT EXISTS executions (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS executions_strategy ON executions (strategy, timestamp);
+
+CREATE TABLE IF NOT EXISTS rebalances (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    strategy_value_lamports INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS rebalances_strategy ON rebalances (strategy, timestamp);
+
+CREATE TABLE IF NOT EXISTS fees (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    strategy_id INTEGER NOT NULL,
+    treasury TEXT NOT NULL,
+    management_fee_lamports INTEGER NOT NULL,
+    strategy_value_lamports INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS fees_strategy ON fees (strategy, timestamp);
+
+CREATE TABLE IF NOT EXISTS rebalance_legs (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    leg_index INTEGER NOT NULL,
+    protocol INTEGER NOT NULL,
+    asset INTEGER NOT NULL,
+    target_weight_bps INTEGER NOT NULL,
+    target_value_lamports INTEGER NOT NULL,
+    price INTEGER NOT NULL,
+    expo INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index, leg_index)
+);
+
+CREATE TABLE IF NOT EXISTS positions (
+    address TEXT PRIMARY KEY,
+    strategy_id INTEGER NOT NULL,
+    protocol TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    position_value_lamports INTEGER NOT NULL,
+    initial_apy_bps INTEGER NOT NULL,
+    current_apy_bps INTEGER NOT NULL,
+    last_updated_at INTEGER NOT NULL
+);
+
+CREATE VIEW IF NOT EXISTS strategy_activity AS
+    SELECT strategy, signature, event_index, slot, timestamp, 'deposit' AS kind,
+           amount, strategy_value_lamports
+      FROM deposits
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'withdraw', amount,
+           strategy_value_lamports
+      FROM withdrawals
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'execute', NULL, NULL
+      FROM executions
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'rebalance', NULL,
+           strategy_value_lamports
+      FROM rebalances
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'fee',
+           management_fee_lamports, strategy_value_lamports
+      FROM fees
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'withdraw_request', amount,
+           strategy_value_lamports
+      FROM withdrawal_requests
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'withdraw_claim', amount, NULL
+      FROM withdrawal_claims
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'withdraw_cancel', amount,
+           strategy_value_lamports
+      FROM withdrawal_cancellations
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'follow_sync', leader_fee_lamports,
+           strategy_value_lamports
+      FROM follower_syncs
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'dca', amount_lamports, NULL
+      FROM dca_purchases
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'protection_trigger',
+           value_lamports, NULL
+      FROM protection_triggers
+    UNION ALL
+    SELECT strategy, signature, event_index, slot, timestamp, 'yield_rotate', NULL, NULL
+      FROM yield_moves;
+";
+
+const CURSOR_KEY: &str = "last_signature";
+
+// 策略历史中的一条记录
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ActivityRow {
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub kind: String,
+    pub amount: Option<u64>,
+    pub strategy_value_lamports: Option<u64>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("打开数据库失败: {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).context("初始化数据库结构失败")?;
+        Ok(Self { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    // 最近一次同步到的交易签名
+    pub fn cursor(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM indexer_state WHERE key = ?1",
+                [CURSOR_KEY],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_cursor(&self, signature: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO indexer_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            params![CURSOR_KEY, signature],
+        )?;
+        Ok(())
+    }
+
+    pub fn has_transaction(&self, signature: &str) -> Result<bool> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT 1 FROM transactions WHERE signature = ?1",
+                [signature],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    // 在同一个数据库事务中写入交易及其全部事件
+    pub fn record_transaction(
+        &mut self,
+        record: &TransactionRecord,
+        events: &[ProgramEvent],
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO transactions (signature, slot, block_time, failed, event_count)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                record.signature,
+                record.slot,
+                record.block_time,
+                record.failed,
+                events.len()
+            ],
+        )?;
+        for (index, event) in events.iter().enumerate() {
+            insert_event(&tx, record, index, event)
+                .with_context(|| format!("写入事件失败: {} #{index}", record.signature))?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // 写入从链上读取的账户快照，覆盖由事件推导出的状态
+    pub fn record_account(&mut self, address: &Pubkey, account: &ProgramAccount) -> Result<()> {
+        let conn = &self.conn;
+        match account {
+            ProgramAccount::GlobalConfig(config) => {
+                upsert_config(conn, &config.admin, &config.oracle_guard, 0)?;
+                upsert_fee_config(conn, &config.fees, &config.treasury, 0)?;
+                upsert_deposit_limits(conn, &config.deposit_limits, 0)?;
+                upsert_withdrawal_config(conn, config.withdrawal_delay_seconds, 0)?;
+                upsert_allowlist_config(conn, config.allowlist_delay_seconds, 0)?;
+            }
+            ProgramAccount::AssetRegistry(asset) => {
+                let params = AssetParams {
+                    mint: asset.mint,
+                    decimals: asset.decimals,
+                    oracle: asset.oracle,
+                    secondary_oracle: asset.secondary_oracle,
+                    enabled: asset.enabled,
+                    max_allocation_bps: asset.max_allocation_bps,
+                    deposit_cap_lamports: asset.deposit_cap_lamports,
+                };
+                upsert_asset(conn, asset.asset_id, &params, 0)?;
+            }
+            ProgramAccount::UserProfile(profile) => {
+                conn.execute(
+                    "INSERT INTO users (owner, user_profile, risk_level, strategy_counter,
+                                        total_value_lamports, is_paused, allowlist_enabled,
+                                        allowlist_disable_at, spending_max_lamports,
+                                        spending_window_seconds, spending_guardian,
+                                        pending_owner, pending_owner_at, migrated_to,
+                                        migrated_from, last_activity)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
+                             ?15, ?16)
+                     ON CONFLICT (owner) DO UPDATE SET
+                         user_profile = excluded.user_profile,
+                         risk_level = excluded.risk_level,
+                         strategy_counter = excluded.strategy_counter,
+                         total_value_lamports = excluded.total_value_lamports,
+                         is_paused = excluded.is_paused,
+                         allowlist_enabled = excluded.allowlist_enabled,
+                         allowlist_disable_at = excluded.allowlist_disable_at,
+                         spending_max_lamports = excluded.spending_max_lamports,
+                         spending_window_seconds = excluded.spending_window_seconds,
+                         spending_guardian = excluded.spending_guardian,
+                         pending_owner = excluded.pending_owner,
+                         pending_owner_at = excluded.pending_owner_at,
+                         migrated_to = excluded.migrated_to,
+                         migrated_from = excluded.migrated_from,
+                         last_activity = excluded.last_activity",
+                    params![
+                        profile.owner.to_string(),
+                        address.to_string(),
+                        profile.risk_level,
+                        profile.strategy_counter,
+                        profile.total_value_lamports,
+                        profile.is_paused,
+                        profile.allowlist_enabled,
+                        profile.allowlist_disable_at,
+                        profile.spending_limit.max_lamports,
+                        profile.spending_limit.window_seconds,
+                        optional_key(&profile.spending_limit.guardian),
+                        optional_key(&profile.pending_owner),
+                        profile.pending_owner_at,
+                        optional_key(&profile.migrated_to),
+                        optional_key(&profile.migrated_from),
+                        profile.last_activity
+                    ],
+                )?;
+            }
+            ProgramAccount::StrategyConfig(strategy) => {
+                let condition = &strategy.rebalance_condition;
+                conn.execute(
+                    "INSERT INTO strategies (strategy, owner, strategy_id, allocations,
+                                             time_interval_seconds, max_deviation_bps,
+                                             auto_rebalance, max_slippage_bps,
+                                             total_value_lamports, created_at, last_executed_at,
+                                             paused_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                     ON CONFLICT (strategy) DO UPDATE SET
+                         allocations = excluded.allocations,
+                         time_interval_seconds = excluded.time_interval_seconds,
+                         max_deviation_bps = excluded.max_deviation_bps,
+                         auto_rebalance = excluded.auto_rebalance,
+                         max_slippage_bps = excluded.max_slippage_bps,
+                         total_value_lamports = excluded.total_value_lamports,
+                         last_executed_at = excluded.last_executed_at,
+                         paused_at = excluded.paused_at",
+                    params![
+                        address.to_string(),
+                        strategy.owner.to_string(),
+                        strategy.strategy_id,
+                        allocations_json(&strategy.allocations),
+                        condition.time_interval_seconds,
+                        condition.max_deviation_bps,
+                        condition.auto_rebalance,
+                        strategy.max_slippage_bps,
+                        strategy.total_value_lamports,
+                        strategy.created_at,
+                        strategy.last_executed_at,
+                        strategy.paused_at
+                    ],
+                )?;
+            }
+            ProgramAccount::PositionTracker(position) => {
+                conn.execute(
+                    "INSERT OR REPLACE INTO positions (address, strategy_id, protocol, asset,
+                                                       position_value_lamports, initial_apy_bps,
+                                                       current_apy_bps, last_updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        address.to_string(),
+                        position.strategy_id,
+                        fixed_str(&position.protocol),
+                        fixed_str(&position.asset),
+                        position.position_value_lamports,
+                        position.initial_apy_bps,
+                        position.current_apy_bps,
+                        position.last_updated_at
+                    ],
+                )?;
+            }
+            // 链上仍存在的工单都未领取或取消
+            ProgramAccount::WithdrawalTicket(ticket) => {
+                conn.execute(
+                    "INSERT INTO withdrawal_tickets (strategy, ticket_id, owner, amount,
+                                                     requested_at, unlocks_at, status)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending')
+                     ON CONFLICT (strategy, ticket_id) DO UPDATE SET
+                         amount = excluded.amount,
+                         unlocks_at = excluded.unlocks_at,
+                         status = 'pending',
+                         closed_at = NULL",
+                    params![
+                        ticket.strategy.to_string(),
+                        ticket.ticket_id,
+                        ticket.owner.to_string(),
+                        ticket.amount_lamports,
+                        ticket.requested_at,
+                        ticket.unlocks_at
+                    ],
+                )?;
+            }
+            ProgramAccount::StrategyDelegate(delegate) => {
+                upsert_delegate(
+                    conn,
+                    &delegate.owner,
+                    &delegate.strategy,
+                    &delegate.delegate,
+                    &delegate.scope,
+                    delegate.expires_at,
+                    None,
+                )?;
+            }
+            // 链上仍存在的白名单地址都未移除
+            ProgramAccount::WithdrawalDestination(entry) => {
+                conn.execute(
+                    "INSERT INTO withdrawal_destinations (owner, destination, added_at,
+                                                          activates_at)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (owner, destination) DO UPDATE SET
+                         added_at = excluded.added_at,
+                         activates_at = excluded.activates_at,
+                         removed_at = NULL",
+                    params![
+                        entry.owner.to_string(),
+                        entry.destination.to_string(),
+                        entry.added_at,
+                        entry.activates_at
+                    ],
+                )?;
+            }
+            ProgramAccount::StrategyTemplate(template) => {
+                upsert_template(
+                    conn,
+                    address,
+                    &TemplateParams {
+                        publisher: template.publisher,
+                        template_id: template.template_id,
+                        allocations: &template.allocations,
+                        rebalance_condition: &template.rebalance_condition,
+                        max_slippage_bps: template.max_slippage_bps,
+                        risk_level: template.risk_level,
+                        description_hash: &template.description_hash,
+                        created_at: template.created_at,
+                        strategies_created: template.strategies_created,
+                    },
+                )?;
+            }
+            // 链上仍存在的跟投关系都未取消
+            ProgramAccount::StrategyFollow(follow) => {
+                conn.execute(
+                    "INSERT INTO follows (strategy, owner, leader, max_weight_bps, delay_seconds,
+                                          leader_fee_bps, followed_at, synced_at, syncs)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                     ON CONFLICT (strategy) DO UPDATE SET
+                         owner = excluded.owner,
+                         leader = excluded.leader,
+                         max_weight_bps = excluded.max_weight_bps,
+                         delay_seconds = excluded.delay_seconds,
+                         leader_fee_bps = excluded.leader_fee_bps,
+                         followed_at = excluded.followed_at,
+                         synced_at = excluded.synced_at,
+                         syncs = excluded.syncs,
+                         unfollowed_at = NULL",
+                    params![
+                        follow.strategy.to_string(),
+                        follow.owner.to_string(),
+                        follow.leader.to_string(),
+                        follow.max_weight_bps,
+                        follow.delay_seconds,
+                        follow.leader_fee_bps,
+                        follow.followed_at,
+                        follow.synced_at,
+                        follow.syncs
+                    ],
+                )?;
+            }
+            // 链上仍存在的定投计划都未取消
+            ProgramAccount::DcaSchedule(schedule) => {
+                let dca = &schedule.params;
+                conn.execute(
+                    "INSERT INTO dca_schedules (strategy, owner, quote_protocol, quote_asset,
+                                                target_protocol, target_asset,
+                                                increment_lamports, max_price_quote, created_at,
+                                                purchased_lamports, purchases)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                     ON CONFLICT (strategy) DO UPDATE SET
+                         owner = excluded.owner,
+                         quote_protocol = excluded.quote_protocol,
+                         quote_asset = excluded.quote_asset,
+                         target_protocol = excluded.target_protocol,
+                         target_asset = excluded.target_asset,
+                         increment_lamports = excluded.increment_lamports,
+                         max_price_quote = excluded.max_price_quote,
+                         created_at = excluded.created_at,
+                         purchased_lamports = excluded.purchased_lamports,
+                         purchases = excluded.purchases,
+                         cancelled_at = NULL",
+                    params![
+                        schedule.strategy.to_string(),
+                        schedule.owner.to_string(),
+                        dca.quote_protocol,
+                        dca.quote_asset,
+                        dca.target_protocol,
+                        dca.target_asset,
+                        dca.increment_lamports,
+                        dca.max_price_quote,
+                        schedule.created_at,
+                        schedule.purchased_lamports,
+                        schedule.purchases
+                    ],
+                )?;
+            }
+            // 链上仍存在的轮动计划都未停用
+            ProgramAccount::YieldRotation(rotation) => {
+                let params = &rotation.params;
+                conn.execute(
+                    "INSERT INTO yield_rotations (strategy, owner, asset, candidate_protocols,
+                                                  min_apy_advantage_bps, min_hold_seconds,
+                                                  created_at, held_since, rotations)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                     ON CONFLICT (strategy) DO UPDATE SET
+                         owner = excluded.owner,
+                         asset = excluded.asset,
+                         candidate_protocols = excluded.candidate_protocols,
+                         min_apy_advantage_bps = excluded.min_apy_advantage_bps,
+                         min_hold_seconds = excluded.min_hold_seconds,
+                         created_at = excluded.created_at,
+                         held_since = excluded.held_since,
+                         rotations = excluded.rotations,
+                         disabled_at = NULL",
+                    params![
+                        rotation.strategy.to_string(),
+                        rotation.owner.to_string(),
+                        params.asset,
+                        params.candidate_protocols,
+                        params.min_apy_advantage_bps,
+                        params.min_hold_seconds,
+                        rotation.created_at,
+                        rotation.held_since,
+                        rotation.rotations
+                    ],
+                )?;
+            }
+            // 链上仍存在的保护单都未取消或触发。账户不记录峰值，同一保护单保留已索引的峰值
+            ProgramAccount::ProtectiveOrder(order) => {
+                let protection = &order.params;
+                conn.execute(
+                    "INSERT INTO protective_orders (strategy, owner, stop_loss_lamports,
+                                                    max_drawdown_bps, take_profit_lamports,
+                                                    safe_protocol, safe_asset, created_at,
+                                                    value_lamports, peak_value_lamports,
+                                                    checked_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9, ?10)
+                     ON CONFLICT (strategy) DO UPDATE SET
+                         owner = excluded.owner,
+                         stop_loss_lamports = excluded.stop_loss_lamports,
+                         max_drawdown_bps = excluded.max_drawdown_bps,
+                         take_profit_lamports = excluded.take_profit_lamports,
+                         safe_protocol = excluded.safe_protocol,
+                         safe_asset = excluded.safe_asset,
+                         created_at = excluded.created_at,
+                         value_lamports = excluded.value_lamports,
+                         peak_value_lamports = CASE
+                             WHEN protective_orders.created_at = excluded.created_at
+                             THEN MAX(protective_orders.peak_value_lamports,
+                                      excluded.value_lamports)
+                             ELSE excluded.value_lamports
+                         END,
+                         checked_at = excluded.checked_at,
+                         triggered_at = NULL,
+                         cancelled_at = NULL",
+                    params![
+                        order.strategy.to_string(),
+                        order.owner.to_string(),
+                        protection.stop_loss_lamports,
+                        protection.max_drawdown_bps,
+                        protection.take_profit_lamports,
+                        protection.safe_protocol,
+                        protection.safe_asset,
+                        order.created_at,
+                        order.marked_value_lamports,
+                        order.marked_at
+                    ],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    // 按时间顺序返回策略的全部历史操作
+    pub fn strategy_history(&self, strategy: &Pubkey) -> Result<Vec<ActivityRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT signature, slot, timestamp, kind, amount, strategy_value_lamports
+               FROM strategy_activity
+              WHERE strategy = ?1
+              ORDER BY slot, signature, event_index",
+        )?;
+        let rows = stmt.query_map([strategy.to_string()], |row| {
+            Ok(ActivityRow {
+                signature: row.get(0)?,
+                slot: row.get(1)?,
+                timestamp: row.get(2)?,
+                kind: row.get(3)?,
+                amount: row.get(4)?,
+                strategy_value_lamports: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+fn insert_event(
+    tx: &Transaction,
+    record: &TransactionRecord,
+    index: usize,
+    event: &ProgramEvent,
+) -> Result<()> {
+    let signature = &record.signature;
+    let slot = record.slot;
+
+    match event {
+        ProgramEvent::ConfigInitialized(e) => {
+            upsert_config(tx, &e.admin, &e.oracle_guard, e.timestamp)?;
+        }
+        ProgramEvent::OracleGuardUpdated(e) => {
+            upsert_config(tx, &e.admin, &e.oracle_guard, e.timestamp)?;
+        }
+        ProgramEvent::FeeConfigUpdated(e) => {
+            upsert_fee_config(tx, &e.fees, &e.treasury, e.timestamp)?;
+        }
+        ProgramEvent::DepositLimitsUpdated(e) => {
+            upsert_deposit_limits(tx, &e.limits, e.timestamp)?;
+        }
+        ProgramEvent::WithdrawalDelayUpdated(e) => {
+            upsert_withdrawal_config(tx, e.delay_seconds, e.timestamp)?;
+        }
+        ProgramEvent::AllowlistDelayUpdated(e) => {
+            upsert_allowlist_config(tx, e.delay_seconds, e.timestamp)?;
+        }
+        ProgramEvent::AssetRegistered(e) => {
+            upsert_asset(tx, e.asset_id, &e.params, e.timestamp)?;
+        }
+        ProgramEvent::AssetUpdated(e) => {
+            upsert_asset(tx, e.asset_id, &e.params, e.timestamp)?;
+        }
+        ProgramEvent::UserInitialized(e) => {
+            tx.execute(
+                "INSERT INTO users (owner, user_profile, risk_level, created_at, last_activity)
+                 VALUES (?1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT (owner) DO UPDATE SET created_at = excluded.created_at",
+                params![
+                    e.owner.to_string(),
+                    e.user_profile.to_string(),
+                    e.risk_level,
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::StrategyCreated(e) => {
+            let condition = &e.rebalance_condition;
+            tx.execute(
+                "INSERT OR IGNORE INTO strategies (strategy, owner, strategy_id, allocations,
+                                                   time_interval_seconds, max_deviation_bps,
+                                                   auto_rebalance, max_slippage_bps, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    e.strategy.to_string(),
+                    e.owner.to_string(),
+                    e.strategy_id,
+                    allocations_json(&e.allocations),
+                    condition.time_interval_seconds,
+                    condition.max_deviation_bps,
+                    condition.auto_rebalance,
+                    e.max_slippage_bps,
+                    e.timestamp
+                ],
+            )?;
+            tx.execute(
+                "UPDATE users SET strategy_counter = MAX(strategy_counter, ?2 + 1),
+                                  last_activity = ?3
+                  WHERE owner = ?1",
+                params![e.owner.to_string(), e.strategy_id, e.timestamp],
+            )?;
+        }
+        ProgramEvent::StrategyUpdated(e) => {
+            let condition = &e.rebalance_condition;
+            tx.execute(
+                "UPDATE strategies SET allocations = ?2, time_interval_seconds = ?3,
+                                       max_deviation_bps = ?4, auto_rebalance = ?5,
+                                       max_slippage_bps = ?6
+                  WHERE strategy = ?1",
+                params![
+                    e.strategy.to_string(),
+                    allocations_json(&e.allocations),
+                    condition.time_interval_seconds,
+                    condition.max_deviation_bps,
+                    condition.auto_rebalance,
+                    e.max_slippage_bps
+                ],
+            )?;
+            tx.execute(
+                "UPDATE users SET last_activity = ?2 WHERE owner = ?1",
+                params![e.owner.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::FundsDeposited(e) => {
+            insert_funds_flow(tx, "deposits", record, index, &FundsFlow::from(e))?;
+        }
+        ProgramEvent::FundsWithdrawn(e) => {
+            insert_funds_flow(tx, "withdrawals", record, index, &FundsFlow::from(e))?;
+            // 接收地址是提款独有的字段
+            tx.execute(
+                "UPDATE withdrawals SET destination = ?3 WHERE signature = ?1 AND event_index = ?2",
+                params![signature, index, e.destination.to_string()],
+            )?;
+        }
+        ProgramEvent::StrategyExecuted(e) => {
+            tx.execute(
+                "INSERT OR IGNORE INTO executions (signature, event_index, slot, owner, strategy,
+                                                   strategy_id, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.timestamp
+                ],
+            )?;
+            tx.execute(
+                "UPDATE strategies SET last_executed_at = ?2 WHERE strategy = ?1",
+                params![e.strategy.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::PositionsRebalanced(e) => {
+            tx.execute(
+                "INSERT OR IGNORE INTO rebalances (signature, event_index, slot, owner, strategy,
+                                                   strategy_id, strategy_value_lamports, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.strategy_value_lamports,
+                    e.timestamp
+                ],
+            )?;
+            for (leg_index, leg) in e.legs.iter().enumerate() {
+                tx.execute(
+                    "INSERT OR IGNORE INTO rebalance_legs (signature, event_index, leg_index,
+                                                           protocol, asset, target_weight_bps,
+                                                           target_value_lamports, price, expo)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        signature,
+                        index,
+                        leg_index,
+                        leg.protocol,
+                        leg.asset,
+                        leg.target_weight_bps,
+                        leg.target_value_lamports,
+                        leg.price,
+                        leg.expo
+                    ],
+                )?;
+            }
+            update_strategy_value(tx, &e.strategy, e.strategy_value_lamports)?;
+            tx.execute(
+                "UPDATE strategies SET last_rebalanced_at = ?2 WHERE strategy = ?1",
+                params![e.strategy.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::FeesCharged(e) => {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO fees (signature, event_index, slot, owner, strategy,
+                                             strategy_id, treasury, management_fee_lamports,
+                                             strategy_value_lamports, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.treasury.to_string(),
+                    e.management_fee_lamports,
+                    e.strategy_value_lamports,
+                    e.timestamp
+                ],
+            )?;
+            update_strategy_value(tx, &e.strategy, e.strategy_value_lamports)?;
+            // 事件不携带用户总价值，只在首次写入时扣减，重复索引不会重复扣费
+            if inserted > 0 {
+                tx.execute(
+                    "UPDATE users SET total_value_lamports = MAX(total_value_lamports - ?2, 0)
+                      WHERE owner = ?1",
+                    params![e.owner.to_string(), e.management_fee_lamports],
+                )?;
+            }
+        }
+        ProgramEvent::WithdrawalRequested(e) => {
+            tx.execute(
+                "INSERT OR IGNORE INTO withdrawal_requests (signature, event_index, slot, owner,
+                                                            strategy, strategy_id, ticket_id,
+                                                            amount, unlocks_at,
+                                                            strategy_value_lamports,
+                                                            user_value_lamports, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.ticket_id,
+                    e.amount,
+                    e.unlocks_at,
+                    e.strategy_value_lamports,
+                    e.user_value_lamports,
+                    e.timestamp
+                ],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO withdrawal_tickets (strategy, ticket_id, owner, amount,
+                                                           requested_at, unlocks_at, status)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending')",
+                params![
+                    e.strategy.to_string(),
+                    e.ticket_id,
+                    e.owner.to_string(),
+                    e.amount,
+                    e.timestamp,
+                    e.unlocks_at
+                ],
+            )?;
+            update_balances(
+                tx,
+                &e.owner,
+                &e.strategy,
+                e.strategy_value_lamports,
+                e.user_value_lamports,
+                e.timestamp,
+            )?;
+        }
+        ProgramEvent::WithdrawalClaimed(e) => {
+            tx.execute(
+                "INSERT OR IGNORE INTO withdrawal_claims (signature, event_index, slot, owner,
+                                                          strategy, strategy_id, ticket_id,
+                                                          amount, destination, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.ticket_id,
+                    e.amount,
+                    e.destination.to_string(),
+                    e.timestamp
+                ],
+            )?;
+            close_ticket(tx, &e.strategy, e.ticket_id, "claimed", e.timestamp)?;
+            // 金额已在申请时从用户总价值中扣除
+            tx.execute(
+                "UPDATE users SET last_activity = ?2 WHERE owner = ?1",
+                params![e.owner.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::WithdrawalCancelled(e) => {
+            tx.execute(
+                "INSERT OR IGNORE INTO withdrawal_cancellations (signature, event_index, slot,
+                                                                 owner, strategy, strategy_id,
+                                                                 ticket_id, amount,
+                                                                 strategy_value_lamports,
+                                                                 user_value_lamports, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.ticket_id,
+                    e.amount,
+                    e.strategy_value_lamports,
+                    e.user_value_lamports,
+                    e.timestamp
+                ],
+            )?;
+            close_ticket(tx, &e.strategy, e.ticket_id, "cancelled", e.timestamp)?;
+            update_balances(
+                tx,
+                &e.owner,
+                &e.strategy,
+                e.strategy_value_lamports,
+                e.user_value_lamports,
+                e.timestamp,
+            )?;
+        }
+        ProgramEvent::DelegateAdded(e) => {
+            upsert_delegate(
+                tx,
+                &e.owner,
+                &e.strategy,
+                &e.delegate,
+                &e.scope,
+                e.expires_at,
+                Some(e.timestamp),
+            )?;
+        }
+        ProgramEvent::DelegateRevoked(e) => {
+            tx.execute(
+                "UPDATE delegates SET revoked_at = ?3 WHERE strategy = ?1 AND delegate = ?2",
+                params![e.strategy.to_string(), e.delegate.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::WithdrawalDestinationAdded(e) => {
+            tx.execute(
+                "INSERT INTO withdrawal_destinations (owner, destination, added_at, activates_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (owner, destination) DO UPDATE SET
+                     added_at = excluded.added_at,
+                     activates_at = excluded.activates_at,
+                     removed_at = NULL",
+                params![
+                    e.owner.to_string(),
+                    e.destination.to_string(),
+                    e.timestamp,
+                    e.activates_at
+                ],
+            )?;
+        }
+        ProgramEvent::WithdrawalDestinationRemoved(e) => {
+            tx.execute(
+                "UPDATE withdrawal_destinations SET removed_at = ?3
+                  WHERE owner = ?1 AND destination = ?2",
+                params![e.owner.to_string(), e.destination.to_string(), e.timestamp],
+            )?;
+        }
+        // 关闭立即生效时 effective_at 等于事件时间，否则在 effective_at 之前仍然启用
+        ProgramEvent::WithdrawalAllowlistUpdated(e) => {
+            let disabled = !e.enabled && e.effective_at <= e.timestamp;
+            let disable_at = if e.enabled || disabled {
+                0
+            } else {
+                e.effective_at
+            };
+            tx.execute(
+                "UPDATE users SET allowlist_enabled = ?2, allowlist_disable_at = ?3,
+                                  last_activity = ?4
+                  WHERE owner = ?1",
+                params![e.owner.to_string(), !disabled, disable_at, e.timestamp],
+            )?;
+        }
+        ProgramEvent::SpendingLimitUpdated(e) => {
+            tx.execute(
+                "UPDATE users SET spending_max_lamports = ?2, spending_window_seconds = ?3,
+                                  spending_guardian = ?4, last_activity = ?5
+                  WHERE owner = ?1",
+                params![
+                    e.owner.to_string(),
+                    e.max_lamports,
+                    e.window_seconds,
+                    optional_key(&e.guardian),
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::OwnerProposed(e) => {
+            tx.execute(
+                "UPDATE users SET pending_owner = ?2, pending_owner_at = ?3, last_activity = ?4
+                  WHERE owner = ?1",
+                params![
+                    e.owner.to_string(),
+                    optional_key(&e.new_owner),
+                    e.accept_after,
+                    e.timestamp
+                ],
+            )?;
+        }
+        // 新配置继承原配置的设置，价值随策略迁移转入
+        ProgramEvent::OwnerAccepted(e) => {
+            tx.execute(
+                "INSERT INTO users (owner, user_profile, risk_level, strategy_counter, is_paused,
+                                    allowlist_enabled, allowlist_disable_at,
+                                    spending_max_lamports, spending_window_seconds,
+                                    spending_guardian, migrated_from, created_at, last_activity)
+                 SELECT ?2, ?3, risk_level, strategy_counter, is_paused, allowlist_enabled,
+                        allowlist_disable_at, spending_max_lamports, spending_window_seconds,
+                        spending_guardian, ?1, ?4, ?4
+                   FROM users WHERE owner = ?1
+                 ON CONFLICT (owner) DO UPDATE SET migrated_from = excluded.migrated_from",
+                params![
+                    e.previous_owner.to_string(),
+                    e.owner.to_string(),
+                    e.user_profile.to_string(),
+                    e.timestamp
+                ],
+            )?;
+            tx.execute(
+                "UPDATE users SET migrated_to = ?2, pending_owner = NULL, pending_owner_at = 0,
+                                  last_activity = ?3
+                  WHERE owner = ?1",
+                params![
+                    e.previous_owner.to_string(),
+                    e.owner.to_string(),
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::StrategyMigrated(e) => {
+            tx.execute(
+                "INSERT OR IGNORE INTO strategies (strategy, owner, strategy_id, allocations,
+                                                   time_interval_seconds, max_deviation_bps,
+                                                   auto_rebalance, max_slippage_bps,
+                                                   total_value_lamports, created_at,
+                                                   last_executed_at, last_rebalanced_at,
+                                                   migrated_from, template)
+                 SELECT ?2, ?3, strategy_id, allocations, time_interval_seconds,
+                        max_deviation_bps, auto_rebalance, max_slippage_bps, ?4, created_at,
+                        last_executed_at, last_rebalanced_at, ?1, template
+                   FROM strategies WHERE strategy = ?1",
+                params![
+                    e.previous_strategy.to_string(),
+                    e.strategy.to_string(),
+                    e.owner.to_string(),
+                    e.value_lamports
+                ],
+            )?;
+            tx.execute(
+                "UPDATE strategies SET migrated_to = ?2, total_value_lamports = 0
+                  WHERE strategy = ?1",
+                params![e.previous_strategy.to_string(), e.strategy.to_string()],
+            )?;
+            tx.execute(
+                "UPDATE users SET total_value_lamports = MAX(total_value_lamports - ?2, 0)
+                  WHERE owner = ?1",
+                params![e.previous_owner.to_string(), e.value_lamports],
+            )?;
+            tx.execute(
+                "UPDATE users SET total_value_lamports = total_value_lamports + ?2,
+                                  last_activity = ?3
+                  WHERE owner = ?1",
+                params![e.owner.to_string(), e.value_lamports, e.timestamp],
+            )?;
+        }
+        ProgramEvent::TemplatePublished(e) => {
+            upsert_template(
+                tx,
+                &e.template,
+                &TemplateParams {
+                    publisher: e.publisher,
+                    template_id: e.template_id,
+                    allocations: &e.allocations,
+                    rebalance_condition: &e.rebalance_condition,
+                    max_slippage_bps: e.max_slippage_bps,
+                    risk_level: e.risk_level,
+                    description_hash: &e.description_hash,
+                    created_at: e.timestamp,
+                    strategies_created: 0,
+                },
+            )?;
+        }
+        ProgramEvent::TemplateRetired(e) => {
+            tx.execute(
+                "UPDATE templates SET retired_at = ?2, strategies_created = ?3
+                  WHERE template = ?1",
+                params![e.template.to_string(), e.timestamp, e.strategies_created],
+            )?;
+        }
+        // 策略本身由同一交易中的 StrategyCreated 写入
+        ProgramEvent::StrategyCreatedFromTemplate(e) => {
+            tx.execute(
+                "UPDATE strategies SET template = ?2 WHERE strategy = ?1",
+                params![e.strategy.to_string(), e.template.to_string()],
+            )?;
+            tx.execute(
+                "UPDATE templates SET strategies_created = strategies_created + 1
+                  WHERE template = ?1",
+                [e.template.to_string()],
+            )?;
+        }
+        // 重新跟随时覆盖之前取消的记录
+        ProgramEvent::StrategyFollowed(e) => {
+            tx.execute(
+                "INSERT INTO follows (strategy, owner, leader, max_weight_bps, delay_seconds,
+                                      leader_fee_bps, followed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (strategy) DO UPDATE SET
+                     owner = excluded.owner,
+                     leader = excluded.leader,
+                     max_weight_bps = excluded.max_weight_bps,
+                     delay_seconds = excluded.delay_seconds,
+                     leader_fee_bps = excluded.leader_fee_bps,
+                     followed_at = excluded.followed_at,
+                     synced_at = 0,
+                     syncs = 0,
+                     unfollowed_at = NULL",
+                params![
+                    e.strategy.to_string(),
+                    e.owner.to_string(),
+                    e.leader.to_string(),
+                    e.max_weight_bps,
+                    e.delay_seconds,
+                    e.leader_fee_bps,
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::StrategyUnfollowed(e) => {
+            tx.execute(
+                "UPDATE follows SET unfollowed_at = ?2, syncs = ?3 WHERE strategy = ?1",
+                params![e.strategy.to_string(), e.timestamp, e.syncs],
+            )?;
+        }
+        ProgramEvent::FollowerSynced(e) => {
+            let allocations = allocations_json(&e.allocations);
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO follower_syncs (signature, event_index, slot, owner,
+                                                       strategy, strategy_id, leader,
+                                                       leader_owner, allocations,
+                                                       leader_fee_lamports,
+                                                       strategy_value_lamports,
+                                                       user_value_lamports, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.leader.to_string(),
+                    e.leader_owner.to_string(),
+                    allocations,
+                    e.leader_fee_lamports,
+                    e.strategy_value_lamports,
+                    e.user_value_lamports,
+                    e.timestamp
+                ],
+            )?;
+            // 只在首次写入时累加同步次数，重复索引不会重复计数
+            if inserted > 0 {
+                tx.execute(
+                    "UPDATE follows SET synced_at = ?2, syncs = syncs + 1 WHERE strategy = ?1",
+                    params![e.strategy.to_string(), e.timestamp],
+                )?;
+            }
+            tx.execute(
+                "UPDATE strategies SET allocations = ?2 WHERE strategy = ?1",
+                params![e.strategy.to_string(), allocations],
+            )?;
+            update_balances(
+                tx,
+                &e.owner,
+                &e.strategy,
+                e.strategy_value_lamports,
+                e.user_value_lamports,
+                e.timestamp,
+            )?;
+        }
+        // 重新创建时覆盖之前取消的记录
+        ProgramEvent::DcaCreated(e) => {
+            let dca = &e.params;
+            tx.execute(
+                "INSERT INTO dca_schedules (strategy, owner, quote_protocol, quote_asset,
+                                            target_protocol, target_asset, increment_lamports,
+                                            max_price_quote, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT (strategy) DO UPDATE SET
+                     owner = excluded.owner,
+                     quote_protocol = excluded.quote_protocol,
+                     quote_asset = excluded.quote_asset,
+                     target_protocol = excluded.target_protocol,
+                     target_asset = excluded.target_asset,
+                     increment_lamports = excluded.increment_lamports,
+                     max_price_quote = excluded.max_price_quote,
+                     created_at = excluded.created_at,
+                     purchased_lamports = 0,
+                     purchases = 0,
+                     completed_at = NULL,
+                     cancelled_at = NULL",
+                params![
+                    e.strategy.to_string(),
+                    e.owner.to_string(),
+                    dca.quote_protocol,
+                    dca.quote_asset,
+                    dca.target_protocol,
+                    dca.target_asset,
+                    dca.increment_lamports,
+                    dca.max_price_quote,
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::DcaCancelled(e) => {
+            tx.execute(
+                "UPDATE dca_schedules SET cancelled_at = ?2, purchased_lamports = ?3,
+                                          purchases = ?4
+                  WHERE strategy = ?1",
+                params![
+                    e.strategy.to_string(),
+                    e.timestamp,
+                    e.purchased_lamports,
+                    e.purchases
+                ],
+            )?;
+        }
+        ProgramEvent::DcaExecuted(e) => {
+            let allocations = allocations_json(&e.allocations);
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO dca_purchases (signature, event_index, slot, owner,
+                                                      strategy, strategy_id, quote_asset,
+                                                      target_asset, amount_lamports, price_quote,
+                                                      max_fill_price_quote, allocations,
+                                                      completed, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.quote_asset,
+                    e.target_asset,
+                    e.amount_lamports,
+                    e.price_quote,
+                    e.max_fill_price_quote,
+                    allocations,
+                    e.completed,
+                    e.timestamp
+                ],
+            )?;
+            // 只在首次写入时累加，重复索引不会重复计数
+            if inserted > 0 {
+                tx.execute(
+                    "UPDATE dca_schedules
+                        SET purchased_lamports = purchased_lamports + ?2,
+                            purchases = purchases + 1,
+                            completed_at = CASE WHEN ?3 THEN ?4 ELSE completed_at END
+                      WHERE strategy = ?1",
+                    params![
+                        e.strategy.to_string(),
+                        e.amount_lamports,
+                        e.completed,
+                        e.timestamp
+                    ],
+                )?;
+            }
+            tx.execute(
+                "UPDATE strategies SET allocations = ?2, last_executed_at = ?3 WHERE strategy = ?1",
+                params![e.strategy.to_string(), allocations, e.timestamp],
+            )?;
+        }
+        // 重新设置时覆盖之前取消或触发的记录
+        ProgramEvent::ProtectionSet(e) => {
+            let protection = &e.params;
+            tx.execute(
+                "INSERT INTO protective_orders (strategy, owner, stop_loss_lamports,
+                                                max_drawdown_bps, take_profit_lamports,
+                                                safe_protocol, safe_asset, created_at,
+                                                value_lamports, peak_value_lamports, checked_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9, ?8)
+                 ON CONFLICT (strategy) DO UPDATE SET
+                     owner = excluded.owner,
+                     stop_loss_lamports = excluded.stop_loss_lamports,
+                     max_drawdown_bps = excluded.max_drawdown_bps,
+                     take_profit_lamports = excluded.take_profit_lamports,
+                     safe_protocol = excluded.safe_protocol,
+                     safe_asset = excluded.safe_asset,
+                     created_at = excluded.created_at,
+                     value_lamports = excluded.value_lamports,
+                     peak_value_lamports = excluded.peak_value_lamports,
+                     checked_at = excluded.checked_at,
+                     triggered_at = NULL,
+                     cancelled_at = NULL",
+                params![
+                    e.strategy.to_string(),
+                    e.owner.to_string(),
+                    protection.stop_loss_lamports,
+                    protection.max_drawdown_bps,
+                    protection.take_profit_lamports,
+                    protection.safe_protocol,
+                    protection.safe_asset,
+                    e.timestamp,
+                    e.value_lamports
+                ],
+            )?;
+        }
+        ProgramEvent::ProtectionCancelled(e) => {
+            tx.execute(
+                "UPDATE protective_orders SET cancelled_at = ?2 WHERE strategy = ?1",
+                params![e.strategy.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::ProtectionChecked(e) => {
+            tx.execute(
+                "UPDATE protective_orders
+                    SET value_lamports = ?2, peak_value_lamports = ?3, checked_at = ?4
+                  WHERE strategy = ?1 AND checked_at <= ?4",
+                params![
+                    e.strategy.to_string(),
+                    e.value_lamports,
+                    e.peak_value_lamports,
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::ProtectionTriggered(e) => {
+            let allocations = allocations_json(&e.allocations);
+            tx.execute(
+                "INSERT OR IGNORE INTO protection_triggers (signature, event_index, slot, owner,
+                                                            strategy, strategy_id, trigger,
+                                                            value_lamports,
+                                                            peak_value_lamports, unwound,
+                                                            allocations, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    trigger_name(e.trigger),
+                    e.value_lamports,
+                    e.peak_value_lamports,
+                    allocations_json(&e.unwound),
+                    allocations,
+                    e.timestamp
+                ],
+            )?;
+            tx.execute(
+                "UPDATE protective_orders
+                    SET value_lamports = ?2, peak_value_lamports = ?3, checked_at = ?4,
+                        triggered_at = ?4
+                  WHERE strategy = ?1",
+                params![
+                    e.strategy.to_string(),
+                    e.value_lamports,
+                    e.peak_value_lamports,
+                    e.timestamp
+                ],
+            )?;
+            tx.execute(
+                "UPDATE strategies SET allocations = ?2, paused_at = ?3 WHERE strategy = ?1",
+                params![e.strategy.to_string(), allocations, e.timestamp],
+            )?;
+        }
+        ProgramEvent::StrategyResumed(e) => {
+            tx.execute(
+                "UPDATE strategies SET paused_at = 0 WHERE strategy = ?1",
+                [e.strategy.to_string()],
+            )?;
+        }
+        // 利率追踪账户写入头寸表，首次报告的APY作为初始APY
+        ProgramEvent::ApyReported(e) => {
+            let protocol = Protocol::try_from(e.protocol)
+                .map(|protocol| protocol.name())
+                .unwrap_or_default();
+            tx.execute(
+                "INSERT INTO positions (address, strategy_id, protocol, asset,
+                                        position_value_lamports, initial_apy_bps,
+                                        current_apy_bps, last_updated_at)
+                 VALUES (?1, 0, ?2, ?3, 0, ?4, ?4, ?5)
+                 ON CONFLICT (address) DO UPDATE SET
+                     current_apy_bps = excluded.current_apy_bps,
+                     last_updated_at = excluded.last_updated_at
+                  WHERE positions.last_updated_at <= excluded.last_updated_at",
+                params![
+                    apy_tracker_address(e.protocol, e.asset).to_string(),
+                    protocol,
+                    e.asset.to_string(),
+                    e.apy_bps,
+                    e.timestamp
+                ],
+            )?;
+        }
+        // 重新启用时覆盖之前停用的记录
+        ProgramEvent::YieldRotationEnabled(e) => {
+            let rotation = &e.params;
+            tx.execute(
+                "INSERT INTO yield_rotations (strategy, owner, asset, candidate_protocols,
+                                              min_apy_advantage_bps, min_hold_seconds,
+                                              created_at, held_since)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+                 ON CONFLICT (strategy) DO UPDATE SET
+                     owner = excluded.owner,
+                     asset = excluded.asset,
+                     candidate_protocols = excluded.candidate_protocols,
+                     min_apy_advantage_bps = excluded.min_apy_advantage_bps,
+                     min_hold_seconds = excluded.min_hold_seconds,
+                     created_at = excluded.created_at,
+                     held_since = excluded.held_since,
+                     rotations = 0,
+                     disabled_at = NULL",
+                params![
+                    e.strategy.to_string(),
+                    e.owner.to_string(),
+                    rotation.asset,
+                    rotation.candidate_protocols,
+                    rotation.min_apy_advantage_bps,
+                    rotation.min_hold_seconds,
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::YieldRotationDisabled(e) => {
+            tx.execute(
+                "UPDATE yield_rotations SET disabled_at = ?2, rotations = ?3 WHERE strategy = ?1",
+                params![e.strategy.to_string(), e.timestamp, e.rotations],
+            )?;
+        }
+        ProgramEvent::YieldRotated(e) => {
+            let allocations = allocations_json(&e.allocations);
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO yield_moves (signature, event_index, slot, owner, strategy,
+                                                    strategy_id, asset, from_protocol,
+                                                    from_apy_bps, to_protocol, to_apy_bps,
+                                                    allocations, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    e.owner.to_string(),
+                    e.strategy.to_string(),
+                    e.strategy_id,
+                    e.asset,
+                    e.from_protocol,
+                    e.from_apy_bps,
+                    e.to_protocol,
+                    e.to_apy_bps,
+                    allocations,
+                    e.timestamp
+                ],
+            )?;
+            // 只在首次写入时累加，重复索引不会重复计数
+            if inserted > 0 {
+                tx.execute(
+                    "UPDATE yield_rotations SET held_since = ?2, rotations = rotations + 1
+                      WHERE strategy = ?1",
+                    params![e.strategy.to_string(), e.timestamp],
+                )?;
+            }
+            tx.execute(
+                "UPDATE strategies SET allocations = ?2 WHERE strategy = ?1",
+                params![e.strategy.to_string(), allocations],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn trigger_name(trigger: ProtectionTrigger) -> &'static str {
+    match trigger {
+        ProtectionTrigger::StopLoss => "stop_loss",
+        ProtectionTrigger::MaxDrawdown => "max_drawdown",
+        ProtectionTrigger::TakeProfit => "take_profit",
+    }
+}
+
+// 模板事件和模板账户共用的字段
+struct TemplateParams<'a> {
+    publisher: Pubkey,
+    template_id: u64,
+    allocations: &'a [Allocation],
+    rebalance_condition: &'a RebalanceCondition,
+    max_slippage_bps: u16,
+    risk_level: u8,
+    description_hash: &'a [u8; 32],
+    created_at: i64,
+    strategies_created: u64,
+}
+
+// 链上仍存在的模板都未停用
+fn upsert_template(conn: &Connection, template: &Pubkey, p: &TemplateParams) -> Result<()> {
+    let condition = p.rebalance_condition;
+    let description_hash: String = p
+        .description_hash
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    conn.execute(
+        "INSERT INTO templates (template, publisher, template_id, allocations,
+                                time_interval_seconds, max_deviation_bps, auto_rebalance,
+                                max_slippage_bps, risk_level, description_hash, created_at,
+                                strategies_created)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+         ON CONFLICT (template) DO UPDATE SET
+             publisher = excluded.publisher,
+             template_id = excluded.template_id,
+             allocations = excluded.allocations,
+             time_interval_seconds = excluded.time_interval_seconds,
+             max_deviation_bps = excluded.max_deviation_bps,
+             auto_rebalance = excluded.auto_rebalance,
+             max_slippage_bps = excluded.max_slippage_bps,
+             risk_level = excluded.risk_level,
+             description_hash = excluded.description_hash,
+             created_at = excluded.created_at,
+             strategies_created = excluded.strategies_created,
+             retired_at = NULL",
+        params![
+            template.to_string(),
+            p.publisher.to_string(),
+            p.template_id,
+            allocations_json(p.allocations),
+            condition.time_interval_seconds,
+            condition.max_deviation_bps,
+            condition.auto_rebalance,
+            p.max_slippage_bps,
+            p.risk_level,
+            description_hash,
+            p.created_at,
+            p.strategies_created
+        ],
+    )?;
+    Ok(())
+}
+
+// 存款和提款事件字段相同，共用同一套写入逻辑
+struct FundsFlow {
+    owner: Pubkey,
+    strategy: Pubkey,
+    strategy_id: u64,
+    amount: u64,
+    strategy_value_lamports: u64,
+    user_value_lamports: u64,
+    timestamp: i64,
+}
+
+impl From<&FundsDeposited> for FundsFlow {
+    fn from(e: &FundsDeposited) -> Self {
+        Self {
+            owner: e.owner,
+            strategy: e.strategy,
+            strategy_id: e.strategy_id,
+            amount: e.amount,
+            strategy_value_lamports: e.strategy_value_lamports,
+            user_value_lamports: e.user_value_lamports,
+            timestamp: e.timestamp,
+        }
+    }
+}
+
+impl From<&FundsWithdrawn> for FundsFlow {
+    fn from(e: &FundsWithdrawn) -> Self {
+        Self {
+            owner: e.owner,
+            strategy: e.strategy,
+            strategy_id: e.strategy_id,
+            amount: e.amount,
+            strategy_value_lamports: e.strategy_value_lamports,
+            user_value_lamports: e.user_value_lamports,
+            timestamp: e.timestamp,
+        }
+    }
+}
+
+fn insert_funds_flow(
+    tx: &Transaction,
+    table: &str,
+    record: &TransactionRecord,
+    index: usize,
+    flow: &FundsFlow,
+) -> Result<()> {
+    tx.execute(
+        &format!(
+            "INSERT OR IGNORE INTO {table} (signature, event_index, slot, owner, strategy,
+                                            strategy_id, amount, strategy_value_lamports,
+                                            user_value_lamports, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
+        ),
+        params![
+            record.signature,
+            index,
+            record.slot,
+            flow.owner.to_string(),
+            flow.strategy.to_string(),
+            flow.strategy_id,
+            flow.amount,
+            flow.strategy_value_lamports,
+            flow.user_value_lamports,
+            flow.timestamp
+        ],
+    )?;
+    update_balances(
+        tx,
+        &flow.owner,
+        &flow.strategy,
+        flow.strategy_value_lamports,
+        flow.user_value_lamports,
+        flow.timestamp,
+    )
+}
+
+// 用事件携带的余额覆盖策略价值和用户总价值
+fn update_balances(
+    tx: &Transaction,
+    owner: &Pubkey,
+    strategy: &Pubkey,
+    strategy_value: u64,
+    user_value: u64,
+    timestamp: i64,
+) -> Result<()> {
+    update_strategy_value(tx, strategy, strategy_value)?;
+    tx.execute(
+        "UPDATE users SET total_value_lamports = ?2, last_activity = ?3 WHERE owner = ?1",
+        params![owner.to_string(), user_value, timestamp],
+    )?;
+    Ok(())
+}
+
+fn close_ticket(
+    tx: &Transaction,
+    strategy: &Pubkey,
+    ticket_id: u64,
+    status: &str,
+    timestamp: i64,
+) -> Result<()> {
+    tx.execute(
+        "UPDATE withdrawal_tickets SET status = ?3, closed_at = ?4
+          WHERE strategy = ?1 AND ticket_id = ?2",
+        params![strategy.to_string(), ticket_id, status, timestamp],
+    )?;
+    Ok(())
+}
+
+// 添加代理人或读取到代理人账户时写入授权范围；重新添加已撤销的代理人会清除撤销时间
+fn upsert_delegate(
+    conn: &Connection,
+    owner: &Pubkey,
+    strategy: &Pubkey,
+    delegate: &Pubkey,
+    scope: &DelegateScope,
+    expires_at: i64,
+    added_at: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO delegates (strategy, delegate, owner, can_execute, can_rebalance, can_update,
+                                max_weight_change_bps, max_slippage_bps, expires_at, added_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT (strategy, delegate) DO UPDATE SET
+             can_execute = excluded.can_execute,
+             can_rebalance = excluded.can_rebalance,
+             can_update = excluded.can_update,
+             max_weight_change_bps = excluded.max_weight_change_bps,
+             max_slippage_bps = excluded.max_slippage_bps,
+             expires_at = excluded.expires_at,
+             added_at = COALESCE(excluded.added_at, delegates.added_at),
+             revoked_at = NULL",
+        params![
+            strategy.to_string(),
+            delegate.to_string(),
+            owner.to_string(),
+            scope.execute,
+            scope.rebalance,
+            scope.update,
+            scope.max_weight_change_bps,
+            scope.max_slippage_bps,
+            expires_at,
+            added_at
+        ],
+    )?;
+    Ok(())
+}
+
+fn upsert_asset(conn: &Connection, asset_id: u8, p: &AssetParams, timestamp: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO assets (asset_id, mint, decimals, oracle, secondary_oracle,
+                             enabled, max_allocation_bps, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT (asset_id) DO UPDATE SET
+             mint = excluded.mint, decimals = excluded.decimals,
+             oracle = excluded.oracle, secondary_oracle = excluded.secondary_oracle,
+             enabled = excluded.enabled, max_allocation_bps = excluded.max_allocation_bps,
+             updated_at = MAX(assets.updated_at, excluded.updated_at)",
+        params![
+            asset_id,
+            p.mint.to_string(),
+            p.decimals,
+            p.oracle.to_string(),
+            p.secondary_oracle.to_string(),
+            p.enabled,
+            p.max_allocation_bps,
+            timestamp
+        ],
+    )?;
+    Ok(())
+}
+
+fn upsert_config(
+    conn: &Connection,
+    admin: &Pubkey,
+    guard: &OracleGuardConfig,
+    timestamp: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO config (id, admin, max_price_age_seconds, max_confidence_bps,
+                             max_divergence_bps, updated_at)
+         VALUES (1, ?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (id) DO UPDATE SET
+             admin = excluded.admin,
+             max_price_age_seconds = excluded.max_price_age_seconds,
+             max_confidence_bps = excluded.max_confidence_bps,
+             max_divergence_bps = excluded.max_divergence_bps,
+             updated_at = MAX(config.updated_at, excluded.updated_at)",
+        params![
+            admin.to_string(),
+            guard.max_price_age_seconds,
+            guard.max_confidence_bps,
+            guard.max_divergence_bps,
+            timestamp
+        ],
+    )?;
+    Ok(())
+}
+
+fn upsert_fee_config(
+    conn: &Connection,
+    fees: &FeeConfig,
+    treasury: &Pubkey,
+    timestamp: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO fee_config (id, management_fee_bps, performance_fee_bps, treasury, updated_at)
+         VALUES (1, ?1, ?2, ?3, ?4)
+         ON CONFLICT (id) DO UPDATE SET
+             management_fee_bps = excluded.management_fee_bps,
+             performance_fee_bps = excluded.performance_fee_bps,
+             treasury = excluded.treasury,
+             updated_at = MAX(fee_config.updated_at, excluded.updated_at)",
+        params![
+            fees.management_fee_bps,
+            fees.performance_fee_bps,
+            treasury.to_string(),
+            timestamp
+        ],
+    )?;
+    Ok(())
+}
+
+fn upsert_deposit_limits(conn: &Connection, limits: &DepositLimits, timestamp: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO deposit_limits (id, min_deposit_lamports, max_strategy_value_lamports,
+                                     max_user_value_lamports, updated_at)
+         VALUES (1, ?1, ?2, ?3, ?4)
+         ON CONFLICT (id) DO UPDATE SET
+             min_deposit_lamports = excluded.min_deposit_lamports,
+             max_strategy_value_lamports = excluded.max_strategy_value_lamports,
+             max_user_value_lamports = excluded.max_user_value_lamports,
+             updated_at = MAX(deposit_limits.updated_at, excluded.updated_at)",
+        params![
+            limits.min_deposit_lamports,
+            limits.max_strategy_value_lamports,
+            limits.max_user_value_lamports,
+            timestamp
+        ],
+    )?;
+    Ok(())
+}
+
+fn upsert_withdrawal_config(conn: &Connection, delay_seconds: u32, timestamp: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO withdrawal_config (id, delay_seconds, updated_at)
+         VALUES (1, ?1, ?2)
+         ON CONFLICT (id) DO UPDATE SET
+             delay_seconds = excluded.delay_seconds,
+             updated_at = MAX(withdrawal_config.updated_at, excluded.updated_at)",
+        params![delay_seconds, timestamp],
+    )?;
+    Ok(())
+}
+
+fn upsert_allowlist_config(conn: &Connection, delay_seconds: u32, timestamp: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO allowlist_config (id, delay_seconds, updated_at)
+         VALUES (1, ?1, ?2)
+         ON CONFLICT (id) DO UPDATE SET
+             delay_seconds = excluded.delay_seconds,
+             updated_at = MAX(allowlist_config.updated_at, excluded.updated_at)",
+        params![delay_seconds, timestamp],
+    )?;
+    Ok(())
+}
+
+// 默认公钥表示未设置，存为 NULL
+fn optional_key(key: &Pubkey) -> Option<String> {
+    (*key != Pubkey::default()).then(|| key.to_string())
+}
+
+fn update_strategy_value(conn: &Connection, strategy: &Pubkey, value: u64) -> Result<()> {
+    conn.execute(
+        "UPDATE strategies SET total_value_lamports = ?2 WHERE strategy = ?1",
+        params![strategy.to_string(), value],
+    )?;
+    Ok(())
+}
+
+fn allocations_json(allocations: &[Allocation]) -> String {
+    let items: Vec<_> = allocations
+        .iter()
+        .map(|a| {
+            serde_json::json!({
+                "protocol": a.protocol,
+                "asset": a.asset,
+                "targetWeightBps": a.target_weight_bps,
+            })
+        })
+        .collect();
+    serde_json::Value::Array(items).to_string()
+}
+
+// PositionTracker 中以0填充的定长名称
+fn fixed_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use defi_tools::events::{ApyReported, YieldRotated, YieldRotationEnabled};
+    use defi_tools::events::{
+        DcaCancelled, DcaCreated, DcaExecuted, DelegateAdded, DelegateRevoked, FeesCharged,
+        FollowerSynced, OwnerAccepted, OwnerProposed, ProtectionChecked, ProtectionSet,
+        ProtectionTriggered, SpendingLimitUpdated, StrategyCreated, StrategyCreatedFromTemplate,
+        StrategyFollowed, StrategyMigrated, StrategyResumed, StrategyUnfollowed, StrategyUpdated,
+        TemplatePublished, TemplateRetired, UserInitialized, WithdrawalAllowlistUpdated,
+        WithdrawalCancelled, WithdrawalClaimed, WithdrawalDestinationAdded,
+        WithdrawalDestinationRemoved, WithdrawalRequested,
+    };
+    use defi_tools::{DcaParams, ProtectionParams, YieldParams};
+
+    fn record(signature: &str, slot: u64) -> TransactionRecord {
+        TransactionRecord {
+            signature: signature.to_string(),
+            slot,
+            block_time: Some(slot as i64),
+            failed: false,
+            log_messages: vec![],
+        }
+    }
+
+    fn created(owner: Pubkey, strategy: Pubkey) -> ProgramEvent {
+        ProgramEvent::StrategyCreated(StrategyCreated {
+            owner,
+            strategy,
+            strategy_id: 0,
+            allocations: vec![Allocation {
+                protocol: 1,
+                asset: 0,
+                target_weight_bps: 10000,
+            }],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 3600,
+                max_deviation_bps: 500,
+                auto_rebalance: true,
+            },
+            max_slippage_bps: 100,
+            timestamp: 10,
+        })
+    }
+
+    fn deposit(owner: Pubkey, strategy: Pubkey, amount: u64, value: u64) -> ProgramEvent {
+        ProgramEvent::FundsDeposited(FundsDeposited {
+            owner,
+            strategy,
+            strategy_id: 0,
+            amount,
+            strategy_value_lamports: value,
+            user_value_lamports: value,
+            timestamp: 20,
+        })
+    }
+
+    // 测试1: 事件写入历史表并更新策略状态
+    #[test]
+    fn test_record_transaction_updates_history() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+
+        store
+            .record_transaction(&record("a", 1), &[created(owner, strategy)])
+            .unwrap();
+        store
+            .record_transaction(
+                &record("b", 2),
+                &[
+                    deposit(owner, strategy, 300, 300),
+                    deposit(owner, strategy, 200, 500),
+                ],
+            )
+            .unwrap();
+
+        let history = store.strategy_history(&strategy).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].kind, "deposit");
+        assert_eq!(history[1].amount, Some(200));
+
+        let value: u64 = store
+            .connection()
+            .query_row(
+                "SELECT total_value_lamports FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(value, 500);
+    }
+
+    // 测试2: 重复索引同一交易不会产生重复记录
+    #[test]
+    fn test_record_transaction_is_idempotent() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let events = [deposit(owner, strategy, 100, 100)];
+
+        store.record_transaction(&record("a", 1), &events).unwrap();
+        store.record_transaction(&record("a", 1), &events).unwrap();
+
+        assert_eq!(store.strategy_history(&strategy).unwrap().len(), 1);
+        assert!(store.has_transaction("a").unwrap());
+    }
+
+    // 测试3: 策略更新事件覆盖分配项和再平衡条件
+    #[test]
+    fn test_strategy_updated() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let updated = ProgramEvent::StrategyUpdated(StrategyUpdated {
+            owner,
+            strategy,
+            strategy_id: 0,
+            allocations: vec![
+                Allocation {
+                    protocol: 0,
+                    asset: 0,
+                    target_weight_bps: 4000,
+                },
+                Allocation {
+                    protocol: 2,
+                    asset: 1,
+                    target_weight_bps: 6000,
+                },
+            ],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 600,
+                max_deviation_bps: 200,
+                auto_rebalance: false,
+            },
+            max_slippage_bps: 50,
+            timestamp: 30,
+        });
+
+        store
+            .record_transaction(&record("a", 1), &[created(owner, strategy)])
+            .unwrap();
+        store
+            .record_transaction(&record("b", 2), &[updated])
+            .unwrap();
+
+        let (allocations, interval, auto_rebalance, slippage): (String, u64, bool, u16) = store
+            .connection()
+            .query_row(
+                "SELECT allocations, time_interval_seconds, auto_rebalance, max_slippage_bps
+                   FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert!(allocations.contains("6000"));
+        assert_eq!(interval, 600);
+        assert!(!auto_rebalance);
+        assert_eq!(slippage, 50);
+    }
+
+    // 测试4: 费用事件写入费用表并扣减策略和用户价值，重复索引不会重复扣减
+    #[test]
+    fn test_fees_charged() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let initialized = ProgramEvent::UserInitialized(UserInitialized {
+            owner,
+            user_profile: Pubkey::new_unique(),
+            risk_level: 3,
+            timestamp: 5,
+        });
+        let charged = [ProgramEvent::FeesCharged(FeesCharged {
+            owner,
+            strategy,
+            strategy_id: 0,
+            treasury: Pubkey::new_unique(),
+            management_fee_lamports: 100,
+            strategy_value_lamports: 900,
+            timestamp: 40,
+        })];
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[
+                    initialized,
+                    created(owner, strategy),
+                    deposit(owner, strategy, 1_000, 1_000),
+                ],
+            )
+            .unwrap();
+        store.record_transaction(&record("b", 2), &charged).unwrap();
+        store.record_transaction(&record("b", 2), &charged).unwrap();
+
+        let history = store.strategy_history(&strategy).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].kind, "fee");
+        assert_eq!(history[1].amount, Some(100));
+        assert_eq!(history[1].strategy_value_lamports, Some(900));
+
+        let conn = store.connection();
+        let strategy_value: u64 = conn
+            .query_row(
+                "SELECT total_value_lamports FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let user_value: u64 = conn
+            .query_row(
+                "SELECT total_value_lamports FROM users WHERE owner = ?1",
+                [owner.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(strategy_value, 900);
+        assert_eq!(user_value, 900);
+    }
+
+    // 测试5: 提款工单的申请、领取和取消更新工单状态、策略价值和历史
+    #[test]
+    fn test_withdrawal_tickets() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let requested = |ticket_id, amount, value| {
+            ProgramEvent::WithdrawalRequested(WithdrawalRequested {
+                owner,
+                strategy,
+                strategy_id: 0,
+                ticket_id,
+                amount,
+                unlocks_at: 100,
+                strategy_value_lamports: value,
+                user_value_lamports: value,
+                timestamp: 30,
+            })
+        };
+        let claimed = ProgramEvent::WithdrawalClaimed(WithdrawalClaimed {
+            owner,
+            strategy,
+            strategy_id: 0,
+            ticket_id: 0,
+            amount: 300,
+            destination: owner,
+            timestamp: 100,
+        });
+        let cancelled = ProgramEvent::WithdrawalCancelled(WithdrawalCancelled {
+            owner,
+            strategy,
+            strategy_id: 0,
+            ticket_id: 1,
+            amount: 200,
+            strategy_value_lamports: 700,
+            user_value_lamports: 700,
+            timestamp: 40,
+        });
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[
+                    created(owner, strategy),
+                    deposit(owner, strategy, 1_000, 1_000),
+                ],
+            )
+            .unwrap();
+        store
+            .record_transaction(
+                &record("b", 2),
+                &[requested(0, 300, 700), requested(1, 200, 500)],
+            )
+            .unwrap();
+        store
+            .record_transaction(&record("c", 3), &[cancelled])
+            .unwrap();
+        store
+            .record_transaction(&record("d", 4), &[claimed])
+            .unwrap();
+
+        let conn = store.connection();
+        let status = |ticket_id: u64| -> (String, Option<i64>) {
+            conn.query_row(
+                "SELECT status, closed_at FROM withdrawal_tickets
+                  WHERE strategy = ?1 AND ticket_id = ?2",
+                params![strategy.to_string(), ticket_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+        };
+        assert_eq!(status(0), ("claimed".to_string(), Some(100)));
+        assert_eq!(status(1), ("cancelled".to_string(), Some(40)));
+
+        let value: u64 = conn
+            .query_row(
+                "SELECT total_value_lamports FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(value, 700);
+
+        let kinds: Vec<String> = store
+            .strategy_history(&strategy)
+            .unwrap()
+            .into_iter()
+            .map(|row| row.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "deposit",
+                "withdraw_request",
+                "withdraw_request",
+                "withdraw_cancel",
+                "withdraw_claim"
+            ]
+        );
+    }
+
+    // 测试6: 同步游标可以读写
+    #[test]
+    fn test_cursor() {
+        let store = Store::open_in_memory().unwrap();
+        assert_eq!(store.cursor().unwrap(), None);
+        store.set_cursor("sig1").unwrap();
+        store.set_cursor("sig2").unwrap();
+        assert_eq!(store.cursor().unwrap(), Some("sig2".to_string()));
+    }
+
+    // 测试7: 代理人的添加、撤销和重新添加
+    #[test]
+    fn test_delegates() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let added = |rebalance, timestamp| {
+            ProgramEvent::DelegateAdded(DelegateAdded {
+                owner,
+                strategy,
+                strategy_id: 0,
+                delegate,
+                scope: DelegateScope {
+                    execute: true,
+                    rebalance,
+                    ..DelegateScope::default()
+                },
+                expires_at: 1_000,
+                timestamp,
+            })
+        };
+        let revoked = ProgramEvent::DelegateRevoked(DelegateRevoked {
+            owner,
+            strategy,
+            strategy_id: 0,
+            delegate,
+            timestamp: 50,
+        });
+
+        let state = |store: &Store| -> (bool, Option<i64>, Option<i64>) {
+            store
+                .connection()
+                .query_row(
+                    "SELECT can_rebalance, added_at, revoked_at FROM delegates
+                      WHERE strategy = ?1 AND delegate = ?2",
+                    params![strategy.to_string(), delegate.to_string()],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .unwrap()
+        };
+
+        store
+            .record_transaction(&record("a", 1), &[added(false, 10)])
+            .unwrap();
+        store
+            .record_transaction(&record("b", 2), &[revoked])
+            .unwrap();
+        assert_eq!(state(&store), (false, Some(10), Some(50)));
+
+        store
+            .record_transaction(&record("c", 3), &[added(true, 60)])
+            .unwrap();
+        assert_eq!(state(&store), (true, Some(60), None));
+    }
+
+    // 测试8: 白名单地址、白名单开关和提款接收地址
+    #[test]
+    fn test_withdrawal_allowlist() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let cold = Pubkey::new_unique();
+        let initialized = ProgramEvent::UserInitialized(UserInitialized {
+            owner,
+            user_profile: Pubkey::new_unique(),
+            risk_level: 3,
+            timestamp: 1,
+        });
+        let allowlist = |enabled, effective_at, timestamp| {
+            ProgramEvent::WithdrawalAllowlistUpdated(WithdrawalAllowlistUpdated {
+                owner,
+                enabled,
+                effective_at,
+                timestamp,
+            })
+        };
+        let added = ProgramEvent::WithdrawalDestinationAdded(WithdrawalDestinationAdded {
+            owner,
+            destination: cold,
+            activates_at: 100,
+            timestamp: 10,
+        });
+        let withdrawn = ProgramEvent::FundsWithdrawn(FundsWithdrawn {
+            owner,
+            strategy,
+            strategy_id: 0,
+            amount: 5,
+            destination: cold,
+            strategy_value_lamports: 0,
+            user_value_lamports: 0,
+            timestamp: 100,
+        });
+        let removed = ProgramEvent::WithdrawalDestinationRemoved(WithdrawalDestinationRemoved {
+            owner,
+            destination: cold,
+            timestamp: 120,
+        });
+
+        let user_state = |store: &Store| -> (bool, i64) {
+            store
+                .connection()
+                .query_row(
+                    "SELECT allowlist_enabled, allowlist_disable_at FROM users WHERE owner = ?1",
+                    [owner.to_string()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap()
+        };
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[initialized, allowlist(true, 5, 5), added],
+            )
+            .unwrap();
+        assert_eq!(user_state(&store), (true, 0));
+
+        store
+            .record_transaction(&record("b", 2), &[withdrawn, removed])
+            .unwrap();
+        let destination: String = store
+            .connection()
+            .query_row("SELECT destination FROM withdrawals", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(destination, cold.to_string());
+        let entry: (i64, Option<i64>) = store
+            .connection()
+            .query_row(
+                "SELECT activates_at, removed_at FROM withdrawal_destinations
+                  WHERE owner = ?1 AND destination = ?2",
+                params![owner.to_string(), cold.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(entry, (100, Some(120)));
+
+        // 关闭在延迟之后生效，期间仍显示为启用
+        store
+            .record_transaction(&record("c", 3), &[allowlist(false, 300, 200)])
+            .unwrap();
+        assert_eq!(user_state(&store), (true, 300));
+        store
+            .record_transaction(&record("d", 4), &[allowlist(false, 400, 400)])
+            .unwrap();
+        assert_eq!(user_state(&store), (false, 0));
+    }
+
+    // 测试9: 周期提款限额和监护人
+    #[test]
+    fn test_spending_limit() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let initialized = ProgramEvent::UserInitialized(UserInitialized {
+            owner,
+            user_profile: Pubkey::new_unique(),
+            risk_level: 3,
+            timestamp: 1,
+        });
+        let limit = |max_lamports, window_seconds, guardian, timestamp| {
+            ProgramEvent::SpendingLimitUpdated(SpendingLimitUpdated {
+                owner,
+                max_lamports,
+                window_seconds,
+                guardian,
+                timestamp,
+            })
+        };
+        let state = |store: &Store| -> (u64, u32, Option<String>) {
+            store
+                .connection()
+                .query_row(
+                    "SELECT spending_max_lamports, spending_window_seconds, spending_guardian
+                       FROM users WHERE owner = ?1",
+                    [owner.to_string()],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .unwrap()
+        };
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[initialized, limit(1000, 3600, guardian, 5)],
+            )
+            .unwrap();
+        assert_eq!(state(&store), (1000, 3600, Some(guardian.to_string())));
+
+        store
+            .record_transaction(&record("b", 2), &[limit(0, 0, Pubkey::default(), 10)])
+            .unwrap();
+        assert_eq!(state(&store), (0, 0, None));
+    }
+
+    // 测试10: 所有权转移创建继承设置的新用户，迁移的策略关联原地址
+    #[test]
+    fn test_owner_transfer() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let migrated = Pubkey::new_unique();
+        let initialized = ProgramEvent::UserInitialized(UserInitialized {
+            owner,
+            user_profile: Pubkey::new_unique(),
+            risk_level: 4,
+            timestamp: 1,
+        });
+        let proposed = ProgramEvent::OwnerProposed(OwnerProposed {
+            owner,
+            new_owner,
+            accept_after: 30,
+            timestamp: 25,
+        });
+        let accepted = ProgramEvent::OwnerAccepted(OwnerAccepted {
+            previous_owner: owner,
+            owner: new_owner,
+            user_profile: Pubkey::new_unique(),
+            timestamp: 30,
+        });
+        let moved = ProgramEvent::StrategyMigrated(StrategyMigrated {
+            previous_owner: owner,
+            owner: new_owner,
+            strategy_id: 0,
+            previous_strategy: strategy,
+            strategy: migrated,
+            value_lamports: 500,
+            timestamp: 40,
+        });
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[
+                    initialized,
+                    created(owner, strategy),
+                    deposit(owner, strategy, 500, 500),
+                    proposed,
+                ],
+            )
+            .unwrap();
+        let pending: Option<String> = store
+            .connection()
+            .query_row(
+                "SELECT pending_owner FROM users WHERE owner = ?1",
+                [owner.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(pending, Some(new_owner.to_string()));
+
+        store
+            .record_transaction(&record("b", 2), &[accepted, moved])
+            .unwrap();
+        let user = |key: &Pubkey| -> (u8, u64, u64, Option<String>, Option<String>) {
+            store
+                .connection()
+                .query_row(
+                    "SELECT risk_level, strategy_counter, total_value_lamports, migrated_to,
+                            migrated_from
+                       FROM users WHERE owner = ?1",
+                    [key.to_string()],
+                    |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                        ))
+                    },
+                )
+                .unwrap()
+        };
+        assert_eq!(user(&owner), (4, 1, 0, Some(new_owner.to_string()), None));
+        assert_eq!(user(&new_owner), (4, 1, 500, None, Some(owner.to_string())));
+
+        let row: (String, i64, u64, Option<String>) = store
+            .connection()
+            .query_row(
+                "SELECT owner, created_at, total_value_lamports, migrated_from
+                   FROM strategies WHERE strategy = ?1",
+                [migrated.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (new_owner.to_string(), 10, 500, Some(strategy.to_string()))
+        );
+        let previous: Option<String> = store
+            .connection()
+            .query_row(
+                "SELECT migrated_to FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(previous, Some(migrated.to_string()));
+    }
+
+    // 测试11: 按模板创建的策略关联模板，停用后保留模板记录
+    #[test]
+    fn test_strategy_templates() {
+        let mut store = Store::open_in_memory().unwrap();
+        let publisher = Pubkey::new_unique();
+        let template = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let published = ProgramEvent::TemplatePublished(TemplatePublished {
+            publisher,
+            template,
+            template_id: 7,
+            allocations: vec![Allocation {
+                protocol: 1,
+                asset: 0,
+                target_weight_bps: 10000,
+            }],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 3600,
+                max_deviation_bps: 500,
+                auto_rebalance: true,
+            },
+            max_slippage_bps: 100,
+            risk_level: 2,
+            description_hash: [0xab; 32],
+            timestamp: 5,
+        });
+        let from_template =
+            ProgramEvent::StrategyCreatedFromTemplate(StrategyCreatedFromTemplate {
+                owner,
+                strategy,
+                strategy_id: 0,
+                template,
+                publisher,
+                template_id: 7,
+                timestamp: 10,
+            });
+        let retired = ProgramEvent::TemplateRetired(TemplateRetired {
+            publisher,
+            template,
+            template_id: 7,
+            strategies_created: 1,
+            timestamp: 30,
+        });
+
+        store
+            .record_transaction(&record("a", 1), &[published])
+            .unwrap();
+        store
+            .record_transaction(&record("b", 2), &[created(owner, strategy), from_template])
+            .unwrap();
+        let linked: Option<String> = store
+            .connection()
+            .query_row(
+                "SELECT template FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(linked, Some(template.to_string()));
+
+        store
+            .record_transaction(&record("c", 3), &[retired])
+            .unwrap();
+        let row: (u64, u8, String, u64, Option<i64>) = store
+            .connection()
+            .query_row(
+                "SELECT template_id, risk_level, description_hash, strategies_created, retired_at
+                   FROM templates WHERE template = ?1",
+                [template.to_string()],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(row, (7, 2, "ab".repeat(32), 1, Some(30)));
+    }
+
+    // 测试12: 同步跟随策略时更新分配项和余额，跟投费用计入策略历史
+    #[test]
+    fn test_follower_sync() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let leader = Pubkey::new_unique();
+        let leader_owner = Pubkey::new_unique();
+        let initialized = ProgramEvent::UserInitialized(UserInitialized {
+            owner,
+            user_profile: Pubkey::new_unique(),
+            risk_level: 3,
+            timestamp: 1,
+        });
+        let followed = ProgramEvent::StrategyFollowed(StrategyFollowed {
+            owner,
+            strategy,
+            strategy_id: 0,
+            leader,
+            leader_owner,
+            max_weight_bps: 8000,
+            delay_seconds: 60,
+            leader_fee_bps: 50,
+            timestamp: 25,
+        });
+        let synced = || {
+            ProgramEvent::FollowerSynced(FollowerSynced {
+                owner,
+                strategy,
+                strategy_id: 0,
+                leader,
+                leader_owner,
+                allocations: vec![
+                    Allocation {
+                        protocol: 0,
+                        asset: 1,
+                        target_weight_bps: 5000,
+                    },
+                    Allocation {
+                        protocol: 2,
+                        asset: 0,
+                        target_weight_bps: 5000,
+                    },
+                ],
+                leader_fee_lamports: 5,
+                strategy_value_lamports: 995,
+                user_value_lamports: 995,
+                timestamp: 100,
+            })
+        };
+        let unfollowed = ProgramEvent::StrategyUnfollowed(StrategyUnfollowed {
+            owner,
+            strategy,
+            strategy_id: 0,
+            leader,
+            syncs: 1,
+            timestamp: 200,
+        });
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[
+                    initialized,
+                    created(owner, strategy),
+                    deposit(owner, strategy, 1000, 1000),
+                    followed,
+                ],
+            )
+            .unwrap();
+        // 重复索引同一交易不会重复计数
+        store
+            .record_transaction(&record("b", 2), &[synced()])
+            .unwrap();
+        store
+            .record_transaction(&record("b", 2), &[synced()])
+            .unwrap();
+
+        let (allocations, strategy_value): (String, u64) = store
+            .connection()
+            .query_row(
+                "SELECT allocations, total_value_lamports FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(allocations.contains("\"protocol\":2"));
+        assert_eq!(strategy_value, 995);
+        let user_value: u64 = store
+            .connection()
+            .query_row(
+                "SELECT total_value_lamports FROM users WHERE owner = ?1",
+                [owner.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(user_value, 995);
+
+        let history = store.strategy_history(&strategy).unwrap();
+        let sync = history.last().unwrap();
+        assert_eq!(sync.kind, "follow_sync");
+        assert_eq!(sync.amount, Some(5));
+
+        store
+            .record_transaction(&record("c", 3), &[unfollowed])
+            .unwrap();
+        let row: (String, i64, u64, Option<i64>) = store
+            .connection()
+            .query_row(
+                "SELECT leader, synced_at, syncs, unfollowed_at FROM follows WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(row, (leader.to_string(), 100, 1, Some(200)));
+    }
+
+    // 测试13: 定投更新策略的分配项和执行时间，购买记录计入策略历史
+    #[test]
+    fn test_dca_purchases() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let dca = DcaParams {
+            quote_protocol: 1,
+            quote_asset: 0,
+            target_protocol: 0,
+            target_asset: 2,
+            increment_lamports: 600,
+            max_price_quote: 0,
+        };
+        let created_dca = ProgramEvent::DcaCreated(DcaCreated {
+            owner,
+            strategy,
+            strategy_id: 0,
+            params: dca,
+            timestamp: 30,
+        });
+        let executed = |completed: bool, timestamp: i64| {
+            let mut allocations = vec![Allocation {
+                protocol: 0,
+                asset: 2,
+                target_weight_bps: if completed { 10000 } else { 6000 },
+            }];
+            if !completed {
+                allocations.insert(
+                    0,
+                    Allocation {
+                        protocol: 1,
+                        asset: 0,
+                        target_weight_bps: 4000,
+                    },
+                );
+            }
+            ProgramEvent::DcaExecuted(DcaExecuted {
+                owner,
+                strategy,
+                strategy_id: 0,
+                quote_asset: 0,
+                target_asset: 2,
+                amount_lamports: if completed { 400 } else { 600 },
+                price_quote: 1_000_000,
+                max_fill_price_quote: 1_010_000,
+                allocations,
+                completed,
+                timestamp,
+            })
+        };
+        let cancelled = ProgramEvent::DcaCancelled(DcaCancelled {
+            owner,
+            strategy,
+            strategy_id: 0,
+            purchased_lamports: 1000,
+            purchases: 2,
+            timestamp: 9000,
+        });
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[
+                    created(owner, strategy),
+                    deposit(owner, strategy, 1000, 1000),
+                    created_dca,
+                ],
+            )
+            .unwrap();
+        // 重复索引同一交易不会重复计数
+        store
+            .record_transaction(&record("b", 2), &[executed(false, 100)])
+            .unwrap();
+        store
+            .record_transaction(&record("b", 2), &[executed(false, 100)])
+            .unwrap();
+        store
+            .record_transaction(&record("c", 3), &[executed(true, 3700)])
+            .unwrap();
+
+        let (allocations, last_executed_at): (String, i64) = store
+            .connection()
+            .query_row(
+                "SELECT allocations, last_executed_at FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(!allocations.contains("\"protocol\":1"));
+        assert_eq!(last_executed_at, 3700);
+
+        let history = store.strategy_history(&strategy).unwrap();
+        let amounts: Vec<_> = history
+            .iter()
+            .filter(|row| row.kind == "dca")
+            .map(|row| row.amount)
+            .collect();
+        assert_eq!(amounts, [Some(600), Some(400)]);
+
+        store
+            .record_transaction(&record("d", 4), &[cancelled])
+            .unwrap();
+        let row: (u64, u64, Option<i64>, Option<i64>) = store
+            .connection()
+            .query_row(
+                "SELECT purchased_lamports, purchases, completed_at, cancelled_at
+                   FROM dca_schedules WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(row, (1000, 2, Some(3700), Some(9000)));
+    }
+
+    // 测试14: 保护单记录最近估值和峰值，触发时切换策略分配项并暂停，触发记录计入策略历史
+    #[test]
+    fn test_protection_triggers() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let safe = Allocation {
+            protocol: 1,
+            asset: 1,
+            target_weight_bps: 10000,
+        };
+        let set = ProgramEvent::ProtectionSet(ProtectionSet {
+            owner,
+            strategy,
+            strategy_id: 0,
+            params: ProtectionParams {
+                stop_loss_lamports: 0,
+                max_drawdown_bps: 2000,
+                take_profit_lamports: 0,
+                safe_protocol: 1,
+                safe_asset: 1,
+            },
+            value_lamports: 1000,
+            timestamp: 30,
+        });
+        let checked = ProgramEvent::ProtectionChecked(ProtectionChecked {
+            owner,
+            strategy,
+            strategy_id: 0,
+            value_lamports: 1300,
+            peak_value_lamports: 1300,
+            timestamp: 100,
+        });
+        let triggered = ProgramEvent::ProtectionTriggered(ProtectionTriggered {
+            owner,
+            strategy,
+            strategy_id: 0,
+            trigger: ProtectionTrigger::MaxDrawdown,
+            value_lamports: 910,
+            peak_value_lamports: 1300,
+            unwound: vec![Allocation {
+                protocol: 1,
+                asset: 0,
+                target_weight_bps: 10000,
+            }],
+            allocations: vec![safe.clone()],
+            timestamp: 200,
+        });
+
+        store
+            .record_transaction(
+                &record("a", 1),
+                &[
+                    created(owner, strategy),
+                    deposit(owner, strategy, 1000, 1000),
+                    set,
+                ],
+            )
+            .unwrap();
+        store
+            .record_transaction(&record("b", 2), &[checked])
+            .unwrap();
+        let row: (u64, u64, i64) = store
+            .connection()
+            .query_row(
+                "SELECT value_lamports, peak_value_lamports, checked_at
+                   FROM protective_orders WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(row, (1300, 1300, 100));
+
+        // 重复索引同一交易不会产生重复记录
+        for _ in 0..2 {
+            store
+                .record_transaction(&record("c", 3), std::slice::from_ref(&triggered))
+                .unwrap();
+        }
+        let (allocations, paused_at): (String, i64) = store
+            .connection()
+            .query_row(
+                "SELECT allocations, paused_at FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(allocations, allocations_json(&[safe]));
+        assert_eq!(paused_at, 200);
+        let (trigger, triggered_at): (String, Option<i64>) = store
+            .connection()
+            .query_row(
+                "SELECT t.trigger, o.triggered_at
+                   FROM protection_triggers t JOIN protective_orders o USING (strategy)
+                  WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(trigger, "max_drawdown");
+        assert_eq!(triggered_at, Some(200));
+
+        let history = store.strategy_history(&strategy).unwrap();
+        let last = history.last().unwrap();
+        assert_eq!(last.kind, "protection_trigger");
+        assert_eq!(last.amount, Some(910));
+
+        let resumed = ProgramEvent::StrategyResumed(StrategyResumed {
+            owner,
+            strategy,
+            strategy_id: 0,
+            timestamp: 300,
+        });
+        store
+            .record_transaction(&record("d", 4), &[resumed])
+            .unwrap();
+        let paused_at: i64 = store
+            .connection()
+            .query_row(
+                "SELECT paused_at FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(paused_at, 0);
+    }
+
+    // 测试15: 利率报告写入头寸表，轮动记录切换策略分配项并计入策略历史
+    #[test]
+    fn test_yield_rotation() {
+        let mut store = Store::open_in_memory().unwrap();
+        let owner = Pubkey::new_unique();
+        let strategy = Pubkey::new_unique();
+        let report = |apy_bps, timestamp| {
+            ProgramEvent::ApyReported(ApyReported {
+                protocol: 3,
+                asset: 1,
+                apy_bps,
+                timestamp,
+            })
+        };
+        store
+            .record_transaction(&record("a", 1), &[report(500, 10), report(800, 20)])
+            .unwrap();
+        // 迟到的旧报告不会覆盖新的APY
+        store
+            .record_transaction(&record("b", 2), &[report(600, 15)])
+            .unwrap();
+        let row: (String, String, u32, u32, i64) = store
+            .connection()
+            .query_row(
+                "SELECT protocol, asset, initial_apy_bps, current_apy_bps, last_updated_at
+                   FROM positions WHERE address = ?1",
+                [apy_tracker_address(3, 1).to_string()],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(row, ("mango".to_string(), "1".to_string(), 500, 800, 20));
+
+        let enabled = ProgramEvent::YieldRotationEnabled(YieldRotationEnabled {
+            owner,
+            strategy,
+            strategy_id: 0,
+            params: YieldParams {
+                asset: 1,
+                candidate_protocols: 0b1010,
+                min_apy_advantage_bps: 100,
+                min_hold_seconds: 3600,
+            },
+            protocol: 1,
+            timestamp: 30,
+        });
+        let rotated_allocations = vec![Allocation {
+            protocol: 3,
+            asset: 1,
+            target_weight_bps: 10000,
+        }];
+        let rotated = ProgramEvent::YieldRotated(YieldRotated {
+            owner,
+            strategy,
+            strategy_id: 0,
+            asset: 1,
+            from_protocol: 1,
+            from_apy_bps: 500,
+            to_protocol: 3,
+            to_apy_bps: 800,
+            allocations: rotated_allocations.clone(),
+            timestamp: 4000,
+        });
+        store
+            .record_transaction(&record("c", 3), &[created(owner, strategy), enabled])
+            .unwrap();
+        // 重复索引同一交易不会重复计数
+        for _ in 0..2 {
+            store
+                .record_transaction(&record("d", 4), std::slice::from_ref(&rotated))
+                .unwrap();
+        }
+        let row: (i64, u64) = store
+            .connection()
+            .query_row(
+                "SELECT held_since, rotations FROM yield_rotations WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(row, (4000, 1));
+        let allocations: String = store
+            .connection()
+            .query_row(
+                "SELECT allocations FROM strategies WHERE strategy = ?1",
+                [strategy.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(allocations, allocations_json(&rotated_allocations));
+
+        let history = store.strategy_history(&strategy).unwrap();
+        assert_eq!(history.last().unwrap().kind, "yield_rotate");
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// 索引所需的交易信息，来自 getTransaction 的 JSON 结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub log_messages: Vec<String>,
+}
+
+impl TransactionRecord {
+    // 解析 getTransaction(encoding = "json") 返回的 result 字段
+    pub fn from_rpc_json(value: &Value) -> Result<Self> {
+        let signature = value
+            .pointer("/transaction/signatures/0")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("交易缺少签名"))?
+            .to_string();
+        let slot = value
+            .get("slot")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| anyhow!("交易 {signature} 缺少 slot"))?;
+        let block_time = value.get("blockTime").and_then(Value::as_i64);
+
+        let meta = value
+            .get("meta")
+            .ok_or_else(|| anyhow!("交易 {signature} 缺少 meta"))?;
+        let failed = !meta.get("err").map(Value::is_null).unwrap_or(true);
+        let log_messages = meta
+            .get("logMessages")
+            .and_then(Value::as_array)
+            .map(|logs| {
+                logs.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            signature,
+            slot,
+            block_time,
+            failed,
+            log_messages,
+        })
+    }
+}
+
+// 读取离线保存的交易文件。文件内容可以是单个 getTransaction 结果、
+// 结果数组，或者完整的 JSON-RPC 响应({"result": ...})
+pub fn load_file(path: &Path) -> Result<Vec<TransactionRecord>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("读取文件失败: {}", path.display()))?;
+    let value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("解析JSON失败: {}", path.display()))?;
+    let value = value.get("result").cloned().unwrap_or(value);
+
+    match value {
+        Value::Array(items) => items.iter().map(TransactionRecord::from_rpc_json).collect(),
+        item => Ok(vec![TransactionRecord::from_rpc_json(&item)?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 测试1: 解析成功的交易
+    #[test]
+    fn test_from_rpc_json() {
+        let value = json!({
+            "slot": 42,
+            "blockTime": 1700000000,
+            "meta": { "err": null, "logMessages": ["Program log: a", "Program log: b"] },
+            "transaction": { "signatures": ["sig1"], "message": {} }
+        });
+        let record = TransactionRecord::from_rpc_json(&value).unwrap();
+        assert_eq!(record.signature, "sig1");
+        assert_eq!(record.slot, 42);
+        assert_eq!(record.block_time, Some(1700000000));
+        assert!(!record.failed);
+        assert_eq!(record.log_messages.len(), 2);
+    }
+
+    // 测试2: 失败的交易被标记
+    #[test]
+    fn test_from_rpc_json_failed() {
+        let value = json!({
+            "slot": 7,
+            "blockTime": null,
+            "meta": { "err": { "InstructionError": [0, { "Custom": 6006 }] }, "logMessages": [] },
+            "transaction": { "signatures": ["sig2"] }
+        });
+        let record = TransactionRecord::from_rpc_json(&value).unwrap();
+        assert!(record.failed);
+        assert_eq!(record.block_time, None);
+    }
+
+    // 测试3: 缺少签名时报错
+    #[test]
+    fn test_from_rpc_json_missing_signature() {
+        let value = json!({ "slot": 1, "meta": {} , "transaction": { "signatures": [] } });
+        assert!(TransactionRecord::from_rpc_json(&value).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Result};
+use defi_tools::{AssetRegistry, GlobalConfig, StrategyConfig};
+use defi_tools_client::pda::find_config_pda;
+use defi_tools_client::{Client, Error as ClientError};
+use solana_sdk::account::{from_account, Account};
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::sysvar;
+
+// 交易模拟结果
+#[derive(Clone, Debug, Default)]
+pub struct Simulation {
+    // 模拟失败时的错误描述
+    pub err: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+// 发送交易失败。程序拒绝等确定性错误不重试，网络等临时错误按退避策略重试
+#[derive(Debug)]
+pub enum SendError {
+    Rejected(String),
+    Transient(String),
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Rejected(msg) => write!(f, "交易被拒绝: {msg}"),
+            SendError::Transient(msg) => write!(f, "发送失败: {msg}"),
+        }
+    }
+}
+
+// keeper 对链的全部访问。生产环境使用 RPC，测试中使用 solana-program-test
+pub trait Chain {
+    // 链上时钟的当前时间
+    fn unix_timestamp(&self) -> Result<i64>;
+
+    fn strategies(&self) -> Result<Vec<(Pubkey, StrategyConfig)>>;
+
+    fn config(&self) -> Result<GlobalConfig>;
+
+    fn asset_registry(&self, asset_id: u8) -> Result<AssetRegistry>;
+
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>>;
+
+    fn simulate(&self, instructions: &[Instruction], payer: &Keypair) -> Result<Simulation>;
+
+    fn send(&self, instructions: &[Instruction], payer: &Keypair) -> Result<Signature, SendError>;
+}
+
+pub struct RpcChain {
+    client: Client,
+}
+
+impl RpcChain {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Chain for RpcChain {
+    fn unix_timestamp(&self) -> Result<i64> {
+        let account = self.client.rpc().get_account(&sysvar::clock::ID)?;
+        let clock: Clock = from_account(&account).ok_or_else(|| anyhow!("无法解析时钟账户"))?;
+        Ok(clock.unix_timestamp)
+    }
+
+    fn strategies(&self) -> Result<Vec<(Pubkey, StrategyConfig)>> {
+        Ok(self.client.strategies(None)?)
+    }
+
+    fn config(&self) -> Result<GlobalConfig> {
+        Ok(self.client.account(&find_config_pda().0)?)
+    }
+
+    fn asset_registry(&self, asset_id: u8) -> Result<AssetRegistry> {
+        Ok(self.client.asset_registry(asset_id)?)
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        let rpc = self.client.rpc();
+        Ok(rpc
+            .get_account_with_commitment(address, rpc.commitment())?
+            .value)
+    }
+
+    fn simulate(&self, instructions: &[Instruction], payer: &Keypair) -> Result<Simulation> {
+        match self.client.simulate(instructions, payer, &[]) {
+            Ok(result) => Ok(Simulation {
+                err: result.err.map(|err| err.to_string()),
+                units_consumed: result.units_consumed,
+                logs: result.logs.unwrap_or_default(),
+            }),
+            Err(ClientError::Program(code)) => Ok(Simulation {
+                err: Some(format!("{}: {code}", code.name())),
+                ..Default::default()
+            }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn send(&self, instructions: &[Instruction], payer: &Keypair) -> Result<Signature, SendError> {
+        self.client
+            .send(instructions, payer, &[])
+            .map_err(|err| match &err {
+                ClientError::Program(_) => SendError::Rejected(err.to_string()),
+                ClientError::Rpc(rpc) if rpc.get_transaction_error().is_some() => {
+                    SendError::Rejected(err.to_string())
+                }
+                _ => SendError::Transient(err.to_string()),
+            })
+    }
+}
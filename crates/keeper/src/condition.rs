@@ -0,0 +1,210 @@
+use defi_tools::StrategyConfig;
+
+// 触发再平衡的原因
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trigger {
+    // 未设置偏差阈值，按时间间隔定期再平衡
+    Interval,
+    // 价格漂移超过偏差阈值
+    Drift(u16),
+    // 尚无上次再平衡时的参考价格，无法计算漂移
+    NoReference,
+    // 保护单已触发，按安全头寸平仓
+    Unwind,
+}
+
+// 暂不再平衡的原因
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wait {
+    AutoRebalanceDisabled,
+    // 保护单触发后策略暂停，等待所有者恢复
+    Paused,
+    // 距离最小时间间隔还剩的秒数
+    IntervalNotElapsed(i64),
+    // 当前漂移未达到阈值
+    DriftBelowThreshold(u16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Evaluation {
+    Due(Trigger),
+    Wait(Wait),
+}
+
+// 在本地评估策略的再平衡条件。时间间隔与链上 rebalance_positions 的检查一致，
+// 设置了偏差阈值时还要求价格漂移达到阈值。保护单触发后的平仓总是立即执行
+pub fn evaluate(strategy: &StrategyConfig, now: i64, drift_bps: Option<u16>) -> Evaluation {
+    if strategy.unwind_pending {
+        return Evaluation::Due(Trigger::Unwind);
+    }
+    let condition = &strategy.rebalance_condition;
+    if !condition.auto_rebalance {
+        return Evaluation::Wait(Wait::AutoRebalanceDisabled);
+    }
+    if strategy.is_paused() {
+        return Evaluation::Wait(Wait::Paused);
+    }
+
+    let elapsed = now.saturating_sub(strategy.last_executed_at);
+    let interval = i64::try_from(condition.time_interval_seconds).unwrap_or(i64::MAX);
+    if elapsed < interval {
+        return Evaluation::Wait(Wait::IntervalNotElapsed(interval - elapsed));
+    }
+
+    if condition.max_deviation_bps == 0 {
+        return Evaluation::Due(Trigger::Interval);
+    }
+    match drift_bps {
+        None => Evaluation::Due(Trigger::NoReference),
+        Some(drift) if drift >= condition.max_deviation_bps => {
+            Evaluation::Due(Trigger::Drift(drift))
+        }
+        Some(drift) => Evaluation::Wait(Wait::DriftBelowThreshold(drift)),
+    }
+}
+
+// 按上次再平衡时的参考价格和当前价格计算各分配项实际权重与目标权重的最大偏差(基点)。
+// 价格缺失或无效时返回 None
+pub fn drift_bps(weights_bps: &[u16], reference: &[f64], current: &[f64]) -> Option<u16> {
+    if weights_bps.len() != reference.len() || weights_bps.len() != current.len() {
+        return None;
+    }
+    if reference
+        .iter()
+        .chain(current)
+        .any(|p| !p.is_finite() || *p <= 0.0)
+    {
+        return None;
+    }
+
+    let values: Vec<f64> = weights_bps
+        .iter()
+        .zip(reference.iter().zip(current))
+        .map(|(weight, (reference, current))| *weight as f64 * current / reference)
+        .collect();
+    let total: f64 = values.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let drift = weights_bps
+        .iter()
+        .zip(&values)
+        .map(|(weight, value)| (value / total * 10_000.0 - *weight as f64).abs())
+        .fold(0.0, f64::max);
+    Some(drift.round().min(u16::MAX as f64) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use defi_tools::{Allocation, RebalanceCondition, StrategyMode};
+
+    fn strategy(interval: u64, max_deviation_bps: u16, last_executed_at: i64) -> StrategyConfig {
+        StrategyConfig {
+            owner: Pubkey::new_unique(),
+            strategy_id: 0,
+            allocations: vec![Allocation {
+                protocol: 0,
+                asset: 0,
+                target_weight_bps: 10000,
+            }],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: interval,
+                max_deviation_bps,
+                auto_rebalance: true,
+            },
+            created_at: 0,
+            last_executed_at,
+            max_slippage_bps: 100,
+            total_value_lamports: 0,
+            fees_accrued_at: 0,
+            withdrawal_queue: Default::default(),
+            allocations_updated_at: 0,
+            peak_value_lamports: 0,
+            paused_at: 0,
+            asset_deposits: Vec::new(),
+            mode: StrategyMode::Manual,
+            delegates: 0,
+            origin: Pubkey::default(),
+            unwind_pending: false,
+        }
+    }
+
+    // 测试1: 未到时间间隔时等待
+    #[test]
+    fn test_evaluate_interval() {
+        let s = strategy(3600, 0, 1000);
+        assert_eq!(
+            evaluate(&s, 1000 + 3599, None),
+            Evaluation::Wait(Wait::IntervalNotElapsed(1))
+        );
+        assert_eq!(
+            evaluate(&s, 1000 + 3600, None),
+            Evaluation::Due(Trigger::Interval)
+        );
+    }
+
+    // 测试2: 设置偏差阈值时按漂移触发
+    #[test]
+    fn test_evaluate_drift() {
+        let s = strategy(60, 500, 0);
+        assert_eq!(
+            evaluate(&s, 100, Some(499)),
+            Evaluation::Wait(Wait::DriftBelowThreshold(499))
+        );
+        assert_eq!(
+            evaluate(&s, 100, Some(500)),
+            Evaluation::Due(Trigger::Drift(500))
+        );
+        assert_eq!(
+            evaluate(&s, 100, None),
+            Evaluation::Due(Trigger::NoReference)
+        );
+    }
+
+    // 测试3: 关闭自动再平衡的策略不处理
+    #[test]
+    fn test_evaluate_auto_rebalance_disabled() {
+        let mut s = strategy(0, 0, 0);
+        s.rebalance_condition.auto_rebalance = false;
+        assert_eq!(
+            evaluate(&s, 100, None),
+            Evaluation::Wait(Wait::AutoRebalanceDisabled)
+        );
+    }
+
+    // 测试4: 漂移计算
+    #[test]
+    fn test_drift_bps() {
+        // 价格不变时没有漂移
+        assert_eq!(drift_bps(&[5000, 5000], &[1.0, 2.0], &[1.0, 2.0]), Some(0));
+        // 第一个资产上涨50%: 7500 / 12500 = 60%，偏离目标1000基点
+        assert_eq!(
+            drift_bps(&[5000, 5000], &[1.0, 2.0], &[1.5, 2.0]),
+            Some(1000)
+        );
+        // 价格无效
+        assert_eq!(drift_bps(&[5000, 5000], &[1.0, 0.0], &[1.5, 2.0]), None);
+        assert_eq!(drift_bps(&[10000], &[1.0, 2.0], &[1.0]), None);
+    }
+
+    // 测试5: 保护单暂停的策略不处理
+    #[test]
+    fn test_evaluate_paused() {
+        let mut s = strategy(0, 0, 0);
+        s.paused_at = 50;
+        assert_eq!(evaluate(&s, 100, None), Evaluation::Wait(Wait::Paused));
+    }
+
+    // 测试6: 保护单触发后立即平仓，不受时间间隔和自动再平衡开关限制
+    #[test]
+    fn test_evaluate_unwind() {
+        let mut s = strategy(3600, 500, 90);
+        s.rebalance_condition.auto_rebalance = false;
+        s.paused_at = 100;
+        s.unwind_pending = true;
+        assert_eq!(evaluate(&s, 100, Some(0)), Evaluation::Due(Trigger::Unwind));
+    }
+}
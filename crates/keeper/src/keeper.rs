@@ -0,0 +1,309 @@
+use crate::chain::{Chain, SendError};
+use crate::condition::{drift_bps, evaluate, Evaluation, Trigger, Wait};
+use crate::metrics::Metrics;
+use anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, Result};
+use defi_tools::delegation::DelegateAction;
+use defi_tools::oracle::OraclePrice;
+use defi_tools::{StrategyConfig, StrategyDelegate};
+use defi_tools_client::instructions::rebalance_positions_as;
+use defi_tools_client::pda::{find_asset_registry_pda, find_strategy_delegate_pda};
+use log::{info, warn};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone, Debug)]
+pub struct Settings {
+    // 计算单元价格(微lamports)，0 表示不设置优先费
+    pub priority_fee_micro_lamports: u64,
+    pub compute_unit_limit: Option<u32>,
+    // 临时错误的最大重试次数
+    pub max_retries: u32,
+    // 首次重试前的等待时间，之后每次翻倍
+    pub retry_backoff: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            priority_fee_micro_lamports: 0,
+            compute_unit_limit: None,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+// 单个策略在一轮扫描中的处理结果
+#[derive(Debug)]
+pub enum Outcome {
+    // keeper 既没有该策略所有者的密钥，也没有可再平衡的代理人密钥
+    Unmanaged,
+    Wait(Wait),
+    PriceUnavailable(String),
+    SimulationFailed(String),
+    Submitted {
+        trigger: Trigger,
+        signature: Signature,
+    },
+    SendFailed(String),
+}
+
+// 单次重试的最长等待时间
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+// 第 attempt 次重试前的等待时间
+pub fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+pub struct Keeper<C> {
+    chain: C,
+    signers: HashMap<Pubkey, Keypair>,
+    settings: Settings,
+    metrics: Arc<Metrics>,
+    // 上次由本 keeper 再平衡时各分配项的价格，用于计算漂移
+    references: HashMap<Pubkey, Vec<f64>>,
+}
+
+impl<C: Chain> Keeper<C> {
+    pub fn new(chain: C, signers: Vec<Keypair>, settings: Settings, metrics: Arc<Metrics>) -> Self {
+        Self {
+            chain,
+            signers: signers.into_iter().map(|k| (k.pubkey(), k)).collect(),
+            settings,
+            metrics,
+            references: HashMap::new(),
+        }
+    }
+
+    pub fn chain(&self) -> &C {
+        &self.chain
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    // 扫描全部策略，对满足条件的策略先模拟再提交再平衡交易
+    pub fn tick(&mut self) -> Result<Vec<(Pubkey, Outcome)>> {
+        Metrics::inc(&self.metrics.ticks);
+        let result = self.scan();
+        if result.is_err() {
+            Metrics::inc(&self.metrics.tick_errors);
+        }
+        result
+    }
+
+    fn scan(&mut self) -> Result<Vec<(Pubkey, Outcome)>> {
+        let now = self.chain.unix_timestamp()?;
+        self.metrics
+            .last_tick_timestamp
+            .store(now, std::sync::atomic::Ordering::Relaxed);
+
+        let mut outcomes = Vec::new();
+        for (address, strategy) in self.chain.strategies()? {
+            Metrics::inc(&self.metrics.strategies_scanned);
+            let outcome = self.process(&address, &strategy, now);
+            match &outcome {
+                Outcome::Submitted { trigger, signature } => {
+                    info!("策略 {address} 已再平衡({trigger:?}): {signature}")
+                }
+                Outcome::SimulationFailed(err) => warn!("策略 {address} 模拟失败: {err}"),
+                Outcome::SendFailed(err) => warn!("策略 {address} 发送失败: {err}"),
+                Outcome::PriceUnavailable(err) => warn!("策略 {address} 价格不可用: {err}"),
+                Outcome::Unmanaged | Outcome::Wait(_) => {}
+            }
+            outcomes.push((address, outcome));
+        }
+        Ok(outcomes)
+    }
+
+    fn process(&mut self, address: &Pubkey, strategy: &StrategyConfig, now: i64) -> Outcome {
+        let Some(payer) = self.signer_for(address, strategy, now) else {
+            Metrics::inc(&self.metrics.skipped_unmanaged);
+            return Outcome::Unmanaged;
+        };
+        // 保护单触发后等待平仓的策略即使暂停或关闭自动再平衡也要处理
+        if !strategy.unwind_pending {
+            if !strategy.rebalance_condition.auto_rebalance {
+                return Outcome::Wait(Wait::AutoRebalanceDisabled);
+            }
+            if strategy.is_paused() {
+                return Outcome::Wait(Wait::Paused);
+            }
+        }
+
+        let (treasury, price_accounts, prices) = match self.load_prices(strategy) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                Metrics::inc(&self.metrics.price_errors);
+                return Outcome::PriceUnavailable(format!("{err:#}"));
+            }
+        };
+        let weights: Vec<u16> = strategy
+            .allocations
+            .iter()
+            .map(|a| a.target_weight_bps)
+            .collect();
+        let drift = self
+            .references
+            .get(address)
+            .and_then(|reference| drift_bps(&weights, reference, &prices));
+
+        let trigger = match evaluate(strategy, now, drift) {
+            Evaluation::Due(trigger) => trigger,
+            Evaluation::Wait(wait) => return Outcome::Wait(wait),
+        };
+        Metrics::inc(&self.metrics.strategies_due);
+
+        let instructions = self.instructions(&payer.pubkey(), strategy, &price_accounts, &treasury);
+        match self.chain.simulate(&instructions, payer) {
+            Ok(simulation) => {
+                if let Some(err) = simulation.err {
+                    Metrics::inc(&self.metrics.simulation_failures);
+                    return Outcome::SimulationFailed(err);
+                }
+            }
+            Err(err) => {
+                Metrics::inc(&self.metrics.simulation_failures);
+                return Outcome::SimulationFailed(format!("{err:#}"));
+            }
+        }
+
+        match self.send_with_retry(&instructions, payer) {
+            Ok(signature) => {
+                Metrics::inc(&self.metrics.rebalances_submitted);
+                self.references.insert(*address, prices);
+                Outcome::Submitted { trigger, signature }
+            }
+            Err(err) => {
+                Metrics::inc(&self.metrics.rebalances_failed);
+                Outcome::SendFailed(err.to_string())
+            }
+        }
+    }
+
+    // 选择签名密钥: 优先使用所有者的密钥，否则使用未过期且有再平衡权限的代理人密钥
+    fn signer_for(
+        &self,
+        address: &Pubkey,
+        strategy: &StrategyConfig,
+        now: i64,
+    ) -> Option<&Keypair> {
+        if let Some(owner) = self.signers.get(&strategy.owner) {
+            return Some(owner);
+        }
+        self.signers.values().find(|signer| {
+            let delegate_address = find_strategy_delegate_pda(address, &signer.pubkey()).0;
+            match self.chain.account(&delegate_address) {
+                Ok(Some(account)) => {
+                    StrategyDelegate::try_deserialize(&mut account.data.as_slice())
+                        .map(|delegate| {
+                            delegate.is_active(now)
+                                && delegate.scope.allows(DelegateAction::Rebalance)
+                        })
+                        .unwrap_or(false)
+                }
+                Ok(None) => false,
+                Err(err) => {
+                    warn!("读取代理人账户 {delegate_address} 失败: {err:#}");
+                    false
+                }
+            }
+        })
+    }
+
+    fn instructions(
+        &self,
+        authority: &Pubkey,
+        strategy: &StrategyConfig,
+        price_accounts: &[Pubkey],
+        treasury: &Pubkey,
+    ) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(limit) = self.settings.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+        }
+        if self.settings.priority_fee_micro_lamports > 0 {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                self.settings.priority_fee_micro_lamports,
+            ));
+        }
+        instructions.push(rebalance_positions_as(
+            authority,
+            &strategy.owner,
+            strategy.strategy_id,
+            price_accounts,
+            treasury,
+        ));
+        instructions
+    }
+
+    fn send_with_retry(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+    ) -> Result<Signature, SendError> {
+        let mut attempt = 0;
+        loop {
+            match self.chain.send(instructions, payer) {
+                Err(SendError::Transient(err)) if attempt < self.settings.max_retries => {
+                    Metrics::inc(&self.metrics.send_retries);
+                    let delay = backoff_delay(self.settings.retry_backoff, attempt);
+                    warn!("发送失败，{delay:?} 后重试: {err}");
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // 读取费用金库，组装再平衡所需的价格账户，并读取每个分配项主价格源的当前价格
+    fn load_prices(&self, strategy: &StrategyConfig) -> Result<(Pubkey, Vec<Pubkey>, Vec<f64>)> {
+        let config = self.chain.config()?;
+        let cross_check = config.oracle_guard.requires_secondary();
+
+        let mut accounts = Vec::new();
+        let mut prices = Vec::new();
+        for allocation in &strategy.allocations {
+            let asset = self.chain.asset_registry(allocation.asset)?;
+            accounts.push(find_asset_registry_pda(allocation.asset).0);
+            accounts.push(asset.oracle);
+            if cross_check {
+                accounts.push(asset.secondary_oracle);
+            }
+
+            let oracle = self
+                .chain
+                .account(&asset.oracle)?
+                .ok_or_else(|| anyhow!("预言机账户不存在: {}", asset.oracle))?;
+            let price = OraclePrice::parse(&oracle.owner, &oracle.data)
+                .map_err(|e| anyhow!("预言机账户 {} 无效: {e}", asset.oracle))?;
+            prices.push(price.price as f64 * 10f64.powi(price.expo));
+        }
+        Ok((config.treasury, accounts, prices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1: 重试等待时间指数增长
+    #[test]
+    fn test_backoff_delay() {
+        let base = Duration::from_millis(100);
+        assert_eq!(backoff_delay(base, 0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(base, 3), Duration::from_millis(800));
+        assert_eq!(backoff_delay(base, 64), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod chain;
+pub mod condition;
+pub mod keeper;
+pub mod metrics;
+
+pub use chain::{Chain, RpcChain, SendError, Simulation};
+pub use keeper::{Keeper, Outcome, Settings};
+pub use metrics::Metrics;
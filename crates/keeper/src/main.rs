@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use defi_tools_client::Client;
+use defi_tools_keeper::{metrics, Keeper, Metrics, RpcChain, Settings};
+use log::{error, info};
+use solana_sdk::signature::read_keypair_file;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(
+    name = "defi-tools-keeper",
+    about = "定期扫描策略并按时间间隔和价格漂移提交再平衡交易"
+)]
+struct Cli {
+    /// RPC 节点地址
+    #[arg(
+        long,
+        short = 'u',
+        env = "DEFI_TOOLS_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// 策略所有者或代理人的密钥文件，可重复指定；只处理这些密钥可以再平衡的策略
+    #[arg(long = "keypair", short = 'k', required = true)]
+    keypairs: Vec<PathBuf>,
+
+    /// 两轮扫描之间的间隔(秒)
+    #[arg(long, default_value_t = 30)]
+    poll_interval: u64,
+
+    /// 计算单元价格(微lamports)
+    #[arg(long, default_value_t = 0)]
+    priority_fee: u64,
+
+    /// 计算单元上限
+    #[arg(long)]
+    compute_unit_limit: Option<u32>,
+
+    /// 临时错误的最大重试次数
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// 首次重试前的等待时间(毫秒)，之后每次翻倍
+    #[arg(long, default_value_t = 500)]
+    retry_backoff_ms: u64,
+
+    /// Prometheus 指标监听地址
+    #[arg(long, default_value = "127.0.0.1:9464")]
+    metrics_addr: String,
+
+    /// 只扫描一轮后退出
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let cli = Cli::parse();
+
+    let signers = cli
+        .keypairs
+        .iter()
+        .map(|path| {
+            read_keypair_file(path)
+                .map_err(|e| anyhow!("{e}"))
+                .with_context(|| format!("读取密钥文件失败: {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let settings = Settings {
+        priority_fee_micro_lamports: cli.priority_fee,
+        compute_unit_limit: cli.compute_unit_limit,
+        max_retries: cli.max_retries,
+        retry_backoff: Duration::from_millis(cli.retry_backoff_ms),
+    };
+
+    let metrics = Arc::new(Metrics::default());
+    metrics::serve(&cli.metrics_addr, metrics.clone())?;
+    info!("指标地址: http://{}/metrics", cli.metrics_addr);
+
+    let chain = RpcChain::new(Client::new(cli.url));
+    let mut keeper = Keeper::new(chain, signers, settings, metrics);
+
+    loop {
+        match keeper.tick() {
+            Ok(outcomes) => info!("本轮扫描 {} 个策略", outcomes.len()),
+            Err(err) => error!("扫描失败: {err:#}"),
+        }
+        if cli.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(cli.poll_interval));
+    }
+}
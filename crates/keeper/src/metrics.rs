@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Result};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+// keeper 运行指标，以 Prometheus 文本格式输出
+#[derive(Default)]
+pub struct Metrics {
+    pub ticks: AtomicU64,
+    pub tick_errors: AtomicU64,
+    pub strategies_scanned: AtomicU64,
+    pub strategies_due: AtomicU64,
+    pub skipped_unmanaged: AtomicU64,
+    pub price_errors: AtomicU64,
+    pub simulation_failures: AtomicU64,
+    pub rebalances_submitted: AtomicU64,
+    pub rebalances_failed: AtomicU64,
+    pub send_retries: AtomicU64,
+    pub last_tick_timestamp: AtomicI64,
+}
+
+impl Metrics {
+    pub fn inc(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let counters: [(&str, &str, &AtomicU64); 10] = [
+            ("keeper_ticks_total", "扫描轮数", &self.ticks),
+            (
+                "keeper_tick_errors_total",
+                "扫描失败次数",
+                &self.tick_errors,
+            ),
+            (
+                "keeper_strategies_scanned_total",
+                "扫描的策略数",
+                &self.strategies_scanned,
+            ),
+            (
+                "keeper_strategies_due_total",
+                "满足再平衡条件的策略数",
+                &self.strategies_due,
+            ),
+            (
+                "keeper_skipped_unmanaged_total",
+                "没有所有者密钥而跳过的策略数",
+                &self.skipped_unmanaged,
+            ),
+            (
+                "keeper_price_errors_total",
+                "读取价格失败次数",
+                &self.price_errors,
+            ),
+            (
+                "keeper_simulation_failures_total",
+                "模拟失败而未提交的再平衡次数",
+                &self.simulation_failures,
+            ),
+            (
+                "keeper_rebalances_submitted_total",
+                "已确认的再平衡交易数",
+                &self.rebalances_submitted,
+            ),
+            (
+                "keeper_rebalances_failed_total",
+                "发送失败的再平衡交易数",
+                &self.rebalances_failed,
+            ),
+            (
+                "keeper_send_retries_total",
+                "发送重试次数",
+                &self.send_retries,
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+        }
+        let _ = writeln!(
+            out,
+            "# HELP keeper_last_tick_timestamp 最近一轮扫描的链上时间"
+        );
+        let _ = writeln!(out, "# TYPE keeper_last_tick_timestamp gauge");
+        let _ = writeln!(
+            out,
+            "keeper_last_tick_timestamp {}",
+            self.last_tick_timestamp.load(Ordering::Relaxed)
+        );
+        out
+    }
+}
+
+// 在后台线程提供 /metrics 接口
+pub fn serve(addr: &str, metrics: Arc<Metrics>) -> Result<JoinHandle<()>> {
+    let server = tiny_http::Server::http(addr).map_err(|e| anyhow!("监听 {addr} 失败: {e}"))?;
+    Ok(thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if request.url() == "/metrics" {
+                tiny_http::Response::from_string(metrics.render())
+            } else {
+                tiny_http::Response::from_string("not found").with_status_code(404)
+            };
+            let _ = request.respond(response);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1: 指标以Prometheus文本格式输出
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        Metrics::inc(&metrics.rebalances_submitted);
+        Metrics::inc(&metrics.rebalances_submitted);
+        metrics.last_tick_timestamp.store(42, Ordering::Relaxed);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE keeper_rebalances_submitted_total counter\n"));
+        assert!(text.contains("keeper_rebalances_submitted_total 2\n"));
+        assert!(text.contains("keeper_last_tick_timestamp 42\n"));
+    }
+}
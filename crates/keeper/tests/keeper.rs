@@ -0,0 +1,508 @@
+// 在 solana-program-test(原生模式)中运行 keeper，通过修改 Clock 系统变量模拟时间流逝
+use anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, Result};
+use defi_tools::oracle::PYTH_RECEIVER_PROGRAM_ID;
+use defi_tools::{
+    Allocation, AssetParams, AssetRegistry, DelegateScope, GlobalConfig, OracleGuardConfig,
+    RebalanceCondition, StrategyConfig,
+};
+use defi_tools_client::error::program_error;
+use defi_tools_client::instructions;
+use defi_tools_client::pda::{
+    find_asset_registry_pda, find_config_pda, find_program_data_pda, find_strategy_pda,
+    find_user_profile_pda,
+};
+use defi_tools_keeper::condition::{Trigger, Wait};
+use defi_tools_keeper::{Chain, Keeper, Metrics, Outcome, SendError, Settings, Simulation};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::clock::Clock;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_sdk::{system_instruction, system_program};
+use std::cell::RefCell;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const START_TIME: i64 = 1_700_000_000;
+const MAX_PRICE_AGE: u32 = 60;
+
+// Anchor 的入口要求账户切片与 AccountInfo 具有相同的生命周期，
+// 原生测试中泄漏一份账户拷贝来满足这个约束
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    defi_tools::entry(program_id, accounts, data)
+}
+
+// 编码 Pyth PriceUpdateV2 账户数据(完全验证)
+fn price_update(price: i64, publish_time: i64) -> Vec<u8> {
+    let mut data = vec![34, 241, 35, 99, 157, 126, 244, 205];
+    data.extend([0u8; 32]);
+    data.push(1);
+    data.extend([7u8; 32]);
+    data.extend(price.to_le_bytes());
+    data.extend((price as u64 / 1000).to_le_bytes());
+    data.extend((-8i32).to_le_bytes());
+    data.extend(publish_time.to_le_bytes());
+    data.extend(publish_time.to_le_bytes());
+    data.extend(price.to_le_bytes());
+    data.extend((price as u64 / 1000).to_le_bytes());
+    data.extend(0u64.to_le_bytes());
+    data
+}
+
+fn oracle_account(price: i64, publish_time: i64) -> Account {
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: price_update(price, publish_time),
+        owner: PYTH_RECEIVER_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+// 原生模式下程序没有 ProgramData 账户，伪造一个以 authority 为升级权限的账户
+fn program_data_account(authority: &Pubkey) -> Account {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend(0u64.to_le_bytes());
+    data.push(1);
+    data.extend(authority.to_bytes());
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+struct BanksChain {
+    rt: Runtime,
+    ctx: RefCell<ProgramTestContext>,
+    strategies: Vec<Pubkey>,
+}
+
+impl BanksChain {
+    fn get<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let account = self
+            .account(address)?
+            .ok_or_else(|| anyhow!("账户不存在: {address}"))?;
+        Ok(T::try_deserialize(&mut account.data.as_slice())?)
+    }
+
+    fn transaction(&self, instructions: &[Instruction], payer: &Keypair) -> Result<Transaction> {
+        let mut ctx = self.ctx.borrow_mut();
+        let blockhash = self.rt.block_on(ctx.banks_client.get_latest_blockhash())?;
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            blockhash,
+        ))
+    }
+
+    fn process(&self, instructions: &[Instruction], payer: &Keypair) {
+        self.send(instructions, payer).unwrap();
+    }
+
+    // 推进若干秒，同时推进 slot 以获得新的 blockhash
+    fn advance(&self, seconds: i64) {
+        let mut ctx = self.ctx.borrow_mut();
+        let clock: Clock = self.rt.block_on(ctx.banks_client.get_sysvar()).unwrap();
+        ctx.warp_to_slot(clock.slot + 10).unwrap();
+        ctx.set_sysvar(&Clock {
+            slot: clock.slot + 10,
+            unix_timestamp: clock.unix_timestamp + seconds,
+            ..clock
+        });
+    }
+
+    fn set_clock(&self, unix_timestamp: i64) {
+        let mut ctx = self.ctx.borrow_mut();
+        let clock: Clock = self.rt.block_on(ctx.banks_client.get_sysvar()).unwrap();
+        ctx.set_sysvar(&Clock {
+            unix_timestamp,
+            ..clock
+        });
+    }
+
+    fn set_oracle(&self, address: &Pubkey, price: i64, publish_time: i64) {
+        let account = AccountSharedData::from(oracle_account(price, publish_time));
+        self.ctx.borrow_mut().set_account(address, &account);
+    }
+
+    fn strategy(&self, index: usize) -> StrategyConfig {
+        self.get(&self.strategies[index]).unwrap()
+    }
+}
+
+impl Chain for BanksChain {
+    fn unix_timestamp(&self) -> Result<i64> {
+        let mut ctx = self.ctx.borrow_mut();
+        let clock: Clock = self.rt.block_on(ctx.banks_client.get_sysvar())?;
+        Ok(clock.unix_timestamp)
+    }
+
+    fn strategies(&self) -> Result<Vec<(Pubkey, StrategyConfig)>> {
+        self.strategies
+            .iter()
+            .map(|address| Ok((*address, self.get(address)?)))
+            .collect()
+    }
+
+    fn config(&self) -> Result<GlobalConfig> {
+        self.get(&find_config_pda().0)
+    }
+
+    fn asset_registry(&self, asset_id: u8) -> Result<AssetRegistry> {
+        self.get(&find_asset_registry_pda(asset_id).0)
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        let mut ctx = self.ctx.borrow_mut();
+        Ok(self.rt.block_on(ctx.banks_client.get_account(*address))?)
+    }
+
+    fn simulate(&self, instructions: &[Instruction], payer: &Keypair) -> Result<Simulation> {
+        let tx = self.transaction(instructions, payer)?;
+        let mut ctx = self.ctx.borrow_mut();
+        let result = self
+            .rt
+            .block_on(ctx.banks_client.simulate_transaction(tx))?;
+        let (units_consumed, logs) = result
+            .simulation_details
+            .map(|details| (Some(details.units_consumed), details.logs))
+            .unwrap_or_default();
+        Ok(Simulation {
+            err: match result.result {
+                Some(Err(err)) => Some(match program_error(&err) {
+                    Some(code) => format!("{}: {code}", code.name()),
+                    None => err.to_string(),
+                }),
+                _ => None,
+            },
+            units_consumed,
+            logs,
+        })
+    }
+
+    fn send(&self, instructions: &[Instruction], payer: &Keypair) -> Result<Signature, SendError> {
+        let tx = self
+            .transaction(instructions, payer)
+            .map_err(|e| SendError::Transient(e.to_string()))?;
+        let signature = tx.signatures[0];
+        let mut ctx = self.ctx.borrow_mut();
+        match self.rt.block_on(ctx.banks_client.process_transaction(tx)) {
+            Ok(()) => Ok(signature),
+            Err(err @ BanksClientError::TransactionError(_))
+            | Err(err @ BanksClientError::SimulationError { .. }) => {
+                Err(SendError::Rejected(err.to_string()))
+            }
+            Err(err) => Err(SendError::Transient(err.to_string())),
+        }
+    }
+}
+
+struct Setup {
+    chain: BanksChain,
+    owner: Keypair,
+    oracles: [Pubkey; 2],
+}
+
+// 初始化配置、注册两个资产，并为 owner 创建策略
+fn setup(conditions: &[RebalanceCondition]) -> Setup {
+    let admin = Keypair::new();
+    let owner = Keypair::new();
+    let oracles = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let mut program_test = ProgramTest::new(
+        "defi_tools",
+        defi_tools::ID,
+        processor!(process_instruction),
+    );
+    program_test.prefer_bpf(false);
+    for user in [&admin, &owner] {
+        program_test.add_account(
+            user.pubkey(),
+            Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+    }
+    program_test.add_account(
+        find_program_data_pda().0,
+        program_data_account(&admin.pubkey()),
+    );
+    for oracle in &oracles {
+        program_test.add_account(*oracle, oracle_account(100_000_000, START_TIME));
+    }
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let ctx = rt.block_on(program_test.start_with_context());
+
+    let mut chain = BanksChain {
+        rt,
+        ctx: RefCell::new(ctx),
+        strategies: Vec::new(),
+    };
+    chain.set_clock(START_TIME);
+
+    let guard = OracleGuardConfig {
+        max_price_age_seconds: MAX_PRICE_AGE,
+        max_confidence_bps: 200,
+        max_divergence_bps: 0,
+    };
+    chain.process(
+        &[instructions::initialize_config(&admin.pubkey(), guard)],
+        &admin,
+    );
+    for (asset_id, oracle) in oracles.iter().enumerate() {
+        let params = AssetParams {
+            mint: Pubkey::new_unique(),
+            decimals: 9,
+            oracle: *oracle,
+            secondary_oracle: Pubkey::default(),
+            enabled: true,
+            max_allocation_bps: 10000,
+            deposit_cap_lamports: 0,
+        };
+        chain.process(
+            &[instructions::register_asset(
+                &admin.pubkey(),
+                asset_id as u8,
+                params,
+            )],
+            &admin,
+        );
+    }
+
+    chain.process(&[instructions::initialize_user(&owner.pubkey(), 3)], &owner);
+    let user_profile = find_user_profile_pda(&owner.pubkey()).0;
+    for (strategy_id, condition) in conditions.iter().enumerate() {
+        let allocations = vec![
+            Allocation {
+                protocol: 0,
+                asset: 0,
+                target_weight_bps: 6000,
+            },
+            Allocation {
+                protocol: 1,
+                asset: 1,
+                target_weight_bps: 4000,
+            },
+        ];
+        chain.process(
+            &[
+                instructions::create_strategy(
+                    &owner.pubkey(),
+                    strategy_id as u64,
+                    allocations.clone(),
+                    condition.clone(),
+                    100,
+                ),
+                // 默认的费用金库为管理员
+                instructions::deposit_funds(
+                    &owner.pubkey(),
+                    strategy_id as u64,
+                    LAMPORTS_PER_SOL,
+                    &admin.pubkey(),
+                    &allocations,
+                ),
+            ],
+            &owner,
+        );
+        chain
+            .strategies
+            .push(find_strategy_pda(&user_profile, strategy_id as u64).0);
+    }
+
+    Setup {
+        chain,
+        owner,
+        oracles,
+    }
+}
+
+fn keeper(setup: Setup, signers: Vec<Keypair>) -> (Keeper<BanksChain>, [Pubkey; 2]) {
+    let settings = Settings {
+        retry_backoff: Duration::ZERO,
+        ..Settings::default()
+    };
+    let keeper = Keeper::new(setup.chain, signers, settings, Arc::new(Metrics::default()));
+    (keeper, setup.oracles)
+}
+
+fn scheduled(interval: u64) -> RebalanceCondition {
+    RebalanceCondition {
+        time_interval_seconds: interval,
+        max_deviation_bps: 0,
+        auto_rebalance: true,
+    }
+}
+
+// 测试1: 按时间间隔定期再平衡
+#[test]
+fn test_keeper_rebalances_on_schedule() {
+    let setup = setup(&[scheduled(3600)]);
+    let owner = setup.owner.insecure_clone();
+    let (mut keeper, oracles) = keeper(setup, vec![owner]);
+
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(
+        outcomes[0].1,
+        Outcome::Submitted {
+            trigger: Trigger::Interval,
+            ..
+        }
+    ));
+    assert_eq!(keeper.chain().strategy(0).last_executed_at, START_TIME);
+
+    // 间隔未到，不提交
+    keeper.chain().advance(1800);
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(
+        outcomes[0].1,
+        Outcome::Wait(Wait::IntervalNotElapsed(1800))
+    ));
+
+    // 间隔已到且价格新鲜，再次再平衡
+    keeper.chain().advance(1800);
+    let now = START_TIME + 3600;
+    for oracle in &oracles {
+        keeper.chain().set_oracle(oracle, 100_000_000, now);
+    }
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(outcomes[0].1, Outcome::Submitted { .. }));
+    assert_eq!(keeper.chain().strategy(0).last_executed_at, now);
+
+    let metrics = keeper.metrics();
+    assert_eq!(metrics.rebalances_submitted.load(Ordering::Relaxed), 2);
+    assert_eq!(metrics.ticks.load(Ordering::Relaxed), 3);
+}
+
+// 测试2: 模拟失败(价格过期)时不提交交易
+#[test]
+fn test_keeper_skips_when_simulation_fails() {
+    let setup = setup(&[scheduled(3600)]);
+    let owner = setup.owner.insecure_clone();
+    let (mut keeper, _) = keeper(setup, vec![owner]);
+
+    keeper.chain().advance(MAX_PRICE_AGE as i64 + 1);
+    let outcomes = keeper.tick().unwrap();
+    match &outcomes[0].1 {
+        Outcome::SimulationFailed(err) => assert!(err.starts_with("StalePrice"), "{err}"),
+        other => panic!("应模拟失败，实际为 {other:?}"),
+    }
+    assert_eq!(keeper.chain().strategy(0).last_executed_at, 0);
+
+    let metrics = keeper.metrics();
+    assert_eq!(metrics.simulation_failures.load(Ordering::Relaxed), 1);
+    assert_eq!(metrics.rebalances_submitted.load(Ordering::Relaxed), 0);
+}
+
+// 测试3: 价格漂移超过阈值才再平衡
+#[test]
+fn test_keeper_rebalances_on_drift() {
+    let setup = setup(&[RebalanceCondition {
+        time_interval_seconds: 600,
+        max_deviation_bps: 500,
+        auto_rebalance: true,
+    }]);
+    let owner = setup.owner.insecure_clone();
+    let (mut keeper, oracles) = keeper(setup, vec![owner]);
+
+    // 没有参考价格时先再平衡一次
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(
+        outcomes[0].1,
+        Outcome::Submitted {
+            trigger: Trigger::NoReference,
+            ..
+        }
+    ));
+
+    // 间隔已到但价格没有变化
+    keeper.chain().advance(600);
+    let now = START_TIME + 600;
+    for oracle in &oracles {
+        keeper.chain().set_oracle(oracle, 100_000_000, now);
+    }
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(
+        outcomes[0].1,
+        Outcome::Wait(Wait::DriftBelowThreshold(0))
+    ));
+
+    // 第一个资产上涨50%: 9000 / 13000 ≈ 69.2%，偏离目标约923基点
+    keeper.chain().set_oracle(&oracles[0], 150_000_000, now);
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(
+        outcomes[0].1,
+        Outcome::Submitted {
+            trigger: Trigger::Drift(923),
+            ..
+        }
+    ));
+}
+
+// 测试4: 没有所有者密钥的策略只扫描不处理
+#[test]
+fn test_keeper_ignores_unmanaged_strategies() {
+    let setup = setup(&[scheduled(0)]);
+    let (mut keeper, _) = keeper(setup, vec![Keypair::new()]);
+
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(outcomes[0].1, Outcome::Unmanaged));
+    assert_eq!(keeper.chain().strategy(0).last_executed_at, 0);
+    assert_eq!(
+        keeper.metrics().skipped_unmanaged.load(Ordering::Relaxed),
+        1
+    );
+}
+
+// 测试5: 没有所有者密钥时使用有再平衡权限的代理人密钥，代理人过期后不再处理
+#[test]
+fn test_keeper_rebalances_as_delegate() {
+    let setup = setup(&[scheduled(3600)]);
+    let owner = setup.owner.insecure_clone();
+    let bot = Keypair::new();
+    let scope = DelegateScope {
+        rebalance: true,
+        ..DelegateScope::default()
+    };
+    setup.chain.process(
+        &[
+            system_instruction::transfer(&owner.pubkey(), &bot.pubkey(), LAMPORTS_PER_SOL),
+            instructions::add_delegate(&owner.pubkey(), 0, &bot.pubkey(), scope, START_TIME + 7200),
+        ],
+        &owner,
+    );
+    let (mut keeper, oracles) = keeper(setup, vec![bot]);
+
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(outcomes[0].1, Outcome::Submitted { .. }));
+    assert_eq!(keeper.chain().strategy(0).last_executed_at, START_TIME);
+
+    // 代理人已过期
+    keeper.chain().advance(7200);
+    let now = START_TIME + 7200;
+    for oracle in &oracles {
+        keeper.chain().set_oracle(oracle, 100_000_000, now);
+    }
+    let outcomes = keeper.tick().unwrap();
+    assert!(matches!(outcomes[0].1, Outcome::Unmanaged));
+    assert_eq!(keeper.chain().strategy(0).last_executed_at, START_TIME);
+}
@@ -0,0 +1,160 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::withdrawals::MAX_WITHDRAWAL_DELAY_SECONDS;
+use anchor_lang::prelude::*;
+
+// 提款地址白名单。启用后提款只能转入所有者本人或已生效的白名单地址；
+// 新增地址和关闭白名单都在延迟之后生效，移除地址和启用白名单立即生效
+
+// 新配置的默认生效延迟(24小时)
+pub const DEFAULT_ALLOWLIST_DELAY_SECONDS: u32 = 24 * 60 * 60;
+
+impl UserProfile {
+    // 白名单是否在当前时刻生效
+    pub fn allowlist_active(&self, now: i64) -> bool {
+        self.allowlist_enabled
+            && (self.allowlist_disable_at == 0 || now < self.allowlist_disable_at)
+    }
+}
+
+impl WithdrawalDestination {
+    pub fn is_active(&self, now: i64) -> bool {
+        now >= self.activates_at
+    }
+}
+
+// 生效延迟与提款锁定时间共用上限
+pub fn validate_allowlist_delay(delay_seconds: u32) -> Result<()> {
+    if delay_seconds > MAX_WITHDRAWAL_DELAY_SECONDS {
+        return Err(ErrorCode::InvalidWithdrawalDelay.into());
+    }
+    Ok(())
+}
+
+// 白名单地址不能是默认地址、所有者本人(始终允许)或用户的资金库
+pub fn validate_destination(owner: &Pubkey, vault: &Pubkey, destination: &Pubkey) -> Result<()> {
+    if *destination == Pubkey::default() || destination == owner || destination == vault {
+        return Err(ErrorCode::InvalidWithdrawalDestination.into());
+    }
+    Ok(())
+}
+
+// 校验提款接收地址。所有者本人或白名单未生效时直接通过，
+// 否则必须提供该地址已生效的白名单账户
+pub fn check_destination(
+    profile: &UserProfile,
+    destination: &Pubkey,
+    entry: Option<&WithdrawalDestination>,
+    now: i64,
+) -> Result<()> {
+    if *destination == profile.owner || !profile.allowlist_active(now) {
+        return Ok(());
+    }
+    let entry = entry.ok_or(ErrorCode::WithdrawalDestinationNotAllowed)?;
+    if entry.owner != profile.owner || entry.destination != *destination {
+        return Err(ErrorCode::WithdrawalDestinationNotAllowed.into());
+    }
+    if !entry.is_active(now) {
+        return Err(ErrorCode::WithdrawalDestinationPending.into());
+    }
+    Ok(())
+}
+
+// 白名单地址PDA
+pub fn destination_address(owner: &Pubkey, destination: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"destination", owner.as_ref(), destination.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(owner: Pubkey, enabled: bool, disable_at: i64) -> UserProfile {
+        UserProfile {
+            owner,
+            risk_level: 3,
+            strategy_counter: 0,
+            vault_bump: 255,
+            last_activity: 0,
+            total_value_lamports: 0,
+            is_paused: false,
+            allowlist_enabled: enabled,
+            allowlist_disable_at: disable_at,
+            spending_limit: SpendingLimit::default(),
+            pending_owner: Pubkey::default(),
+            pending_owner_at: 0,
+            migrated_to: Pubkey::default(),
+            migrated_from: Pubkey::default(),
+        }
+    }
+
+    fn entry(owner: Pubkey, destination: Pubkey, activates_at: i64) -> WithdrawalDestination {
+        WithdrawalDestination {
+            owner,
+            destination,
+            added_at: 0,
+            activates_at,
+            bump: 255,
+        }
+    }
+
+    // 测试1: 关闭在生效时间之前白名单仍然有效
+    #[test]
+    fn test_allowlist_active() {
+        let owner = Pubkey::new_unique();
+        assert!(!profile(owner, false, 0).allowlist_active(10));
+        assert!(profile(owner, true, 0).allowlist_active(10));
+        assert!(profile(owner, true, 100).allowlist_active(99));
+        assert!(!profile(owner, true, 100).allowlist_active(100));
+    }
+
+    // 测试2: 所有者本人始终允许，其他地址需要已生效的白名单账户
+    #[test]
+    fn test_check_destination() {
+        let owner = Pubkey::new_unique();
+        let cold = Pubkey::new_unique();
+        let enabled = profile(owner, true, 0);
+        let listed = entry(owner, cold, 100);
+
+        assert!(check_destination(&enabled, &owner, None, 0).is_ok());
+        assert!(check_destination(&profile(owner, false, 0), &cold, None, 0).is_ok());
+        assert!(check_destination(&enabled, &cold, Some(&listed), 100).is_ok());
+
+        let other = entry(Pubkey::new_unique(), cold, 0);
+        for (entry, now, err) in [
+            (None, 100, ErrorCode::WithdrawalDestinationNotAllowed),
+            (
+                Some(&other),
+                100,
+                ErrorCode::WithdrawalDestinationNotAllowed,
+            ),
+            (Some(&listed), 99, ErrorCode::WithdrawalDestinationPending),
+        ] {
+            assert_eq!(
+                check_destination(&enabled, &cold, entry, now).unwrap_err(),
+                err.into()
+            );
+        }
+    }
+
+    // 测试3: 白名单地址和生效延迟的校验
+    #[test]
+    fn test_validate_destination_and_delay() {
+        let owner = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        assert!(validate_destination(&owner, &vault, &Pubkey::new_unique()).is_ok());
+        for destination in [Pubkey::default(), owner, vault] {
+            assert_eq!(
+                validate_destination(&owner, &vault, &destination).unwrap_err(),
+                ErrorCode::InvalidWithdrawalDestination.into()
+            );
+        }
+
+        assert!(validate_allowlist_delay(MAX_WITHDRAWAL_DELAY_SECONDS).is_ok());
+        assert!(validate_allowlist_delay(MAX_WITHDRAWAL_DELAY_SECONDS + 1).is_err());
+    }
+}
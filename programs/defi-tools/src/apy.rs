@@ -0,0 +1,280 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 收益率轮动。策略中某个资产的一个分配项(轮动头寸)不固定协议，而是在候选借贷协议之间
+// 移动到APY最高的协议。APY由管理员按协议和资产报告到利率追踪账户，任何人都可以发起轮动。
+// 为避免频繁切换，新协议的APY须比当前协议高出最小优势，且轮动头寸已在当前协议持有最短时间
+
+// 利率数据的最长有效时间(秒)
+pub const MAX_APY_AGE_SECONDS: i64 = 24 * 60 * 60;
+// 最短持有时间的上限(秒)
+pub const MAX_YIELD_HOLD_SECONDS: u32 = 30 * 24 * 60 * 60;
+
+impl Protocol {
+    // 可以作为收益率轮动候选的借贷协议
+    pub fn is_lending(&self) -> bool {
+        matches!(self, Protocol::Solend | Protocol::Mango)
+    }
+
+    // 利率追踪账户中记录的协议名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Raydium => "raydium",
+            Protocol::Solend => "solend",
+            Protocol::Orca => "orca",
+            Protocol::Mango => "mango",
+        }
+    }
+}
+
+impl YieldParams {
+    // 按协议编号顺序返回候选协议
+    pub fn candidates(&self) -> Vec<u8> {
+        (0..8u8)
+            .filter(|protocol| self.candidate_protocols & (1 << protocol) != 0)
+            .collect()
+    }
+
+    pub fn is_candidate(&self, protocol: u8) -> bool {
+        protocol < 8 && self.candidate_protocols & (1 << protocol) != 0
+    }
+}
+
+// 协议必须是借贷协议
+pub fn check_lending(protocol: u8) -> Result<()> {
+    if !Protocol::try_from(protocol)?.is_lending() {
+        return Err(ErrorCode::IncompatibleProtocol.into());
+    }
+    Ok(())
+}
+
+// 至少两个候选协议，最短持有时间不超过上限，候选协议都是借贷协议
+pub fn validate_yield(params: &YieldParams) -> Result<()> {
+    if params.candidate_protocols.count_ones() < 2
+        || params.min_hold_seconds > MAX_YIELD_HOLD_SECONDS
+    {
+        return Err(ErrorCode::InvalidYieldParams.into());
+    }
+    for protocol in params.candidates() {
+        check_lending(protocol)?;
+    }
+    Ok(())
+}
+
+// 轮动头寸在分配项中的位置。资产匹配且协议为候选协议的分配项必须恰好一个
+pub fn yield_position(params: &YieldParams, allocations: &[Allocation]) -> Result<usize> {
+    let mut positions = allocations.iter().enumerate().filter(|(_, allocation)| {
+        allocation.asset == params.asset && params.is_candidate(allocation.protocol)
+    });
+    match (positions.next(), positions.next()) {
+        (Some((index, _)), None) => Ok(index),
+        _ => Err(ErrorCode::PositionNotFound.into()),
+    }
+}
+
+// 利率追踪账户地址
+pub fn apy_tracker_address(protocol: u8, asset: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"apy", &[protocol], &[asset]], &crate::ID).0
+}
+
+// 利率追踪账户中的定长名称，超出部分截断
+pub fn fixed_name(name: &str) -> [u8; 32] {
+    let mut fixed = [0u8; 32];
+    let len = name.len().min(fixed.len());
+    fixed[..len].copy_from_slice(&name.as_bytes()[..len]);
+    fixed
+}
+
+// 按候选协议顺序读取剩余账户中的利率追踪账户，返回 (协议, APY)
+pub fn load_apy_reports<'info>(
+    params: &YieldParams,
+    remaining: &'info [AccountInfo<'info>],
+    now: i64,
+) -> Result<Vec<(u8, u32)>> {
+    let candidates = params.candidates();
+    if remaining.len() != candidates.len() {
+        return Err(ErrorCode::InvalidApyTracker.into());
+    }
+    let mut reports = Vec::with_capacity(candidates.len());
+    for (protocol, info) in candidates.into_iter().zip(remaining) {
+        if info.key() != apy_tracker_address(protocol, params.asset) {
+            return Err(ErrorCode::InvalidApyTracker.into());
+        }
+        let tracker = Account::<PositionTracker>::try_from(info)
+            .map_err(|_| error!(ErrorCode::InvalidApyTracker))?;
+        if now.saturating_sub(tracker.last_updated_at) > MAX_APY_AGE_SECONDS {
+            return Err(ErrorCode::StaleApy.into());
+        }
+        reports.push((protocol, tracker.current_apy_bps));
+    }
+    Ok(reports)
+}
+
+// 一次轮动
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rotation {
+    pub from_protocol: u8,
+    pub from_apy_bps: u32,
+    pub to_protocol: u8,
+    pub to_apy_bps: u32,
+}
+
+// 选出APY最高的候选协议(与当前协议相同时保留当前协议)，检查最短持有时间和最小优势
+pub fn plan_rotation(
+    params: &YieldParams,
+    current_protocol: u8,
+    reports: &[(u8, u32)],
+    held_since: i64,
+    now: i64,
+) -> Result<Rotation> {
+    if now < held_since.saturating_add(params.min_hold_seconds as i64) {
+        return Err(ErrorCode::YieldHoldActive.into());
+    }
+    let from_apy_bps = reports
+        .iter()
+        .find(|(protocol, _)| *protocol == current_protocol)
+        .map(|(_, apy)| *apy)
+        .ok_or(ErrorCode::PositionNotFound)?;
+    let mut best = (current_protocol, from_apy_bps);
+    for &(protocol, apy) in reports {
+        if apy > best.1 {
+            best = (protocol, apy);
+        }
+    }
+    let advantage = best.1 - from_apy_bps;
+    if advantage == 0 || advantage < params.min_apy_advantage_bps {
+        return Err(ErrorCode::YieldAdvantageTooSmall.into());
+    }
+    Ok(Rotation {
+        from_protocol: current_protocol,
+        from_apy_bps,
+        to_protocol: best.0,
+        to_apy_bps: best.1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> YieldParams {
+        YieldParams {
+            asset: 1,
+            candidate_protocols: 0b1010,
+            min_apy_advantage_bps: 100,
+            min_hold_seconds: 3600,
+        }
+    }
+
+    fn entry(protocol: u8, asset: u8, target_weight_bps: u16) -> Allocation {
+        Allocation {
+            protocol,
+            asset,
+            target_weight_bps,
+        }
+    }
+
+    // 测试1: 轮动参数校验
+    #[test]
+    fn test_validate_yield() {
+        assert!(validate_yield(&params()).is_ok());
+        assert_eq!(params().candidates(), vec![1, 3]);
+
+        let invalid = [
+            YieldParams {
+                candidate_protocols: 0b0010,
+                ..params()
+            },
+            YieldParams {
+                min_hold_seconds: MAX_YIELD_HOLD_SECONDS + 1,
+                ..params()
+            },
+        ];
+        for params in invalid {
+            assert_eq!(
+                validate_yield(&params).unwrap_err(),
+                ErrorCode::InvalidYieldParams.into()
+            );
+        }
+        assert_eq!(
+            validate_yield(&YieldParams {
+                candidate_protocols: 0b0011,
+                ..params()
+            })
+            .unwrap_err(),
+            ErrorCode::IncompatibleProtocol.into()
+        );
+        assert_eq!(
+            validate_yield(&YieldParams {
+                candidate_protocols: 0b1000_0010,
+                ..params()
+            })
+            .unwrap_err(),
+            ErrorCode::UnknownProtocol.into()
+        );
+    }
+
+    // 测试2: 轮动头寸必须恰好一个
+    #[test]
+    fn test_yield_position() {
+        let allocations = [entry(0, 0, 6000), entry(1, 1, 4000)];
+        assert_eq!(yield_position(&params(), &allocations).unwrap(), 1);
+
+        let missing = [entry(0, 0, 6000), entry(0, 1, 4000)];
+        let duplicated = [entry(1, 1, 5000), entry(3, 1, 5000)];
+        for allocations in [&missing[..], &duplicated[..]] {
+            assert_eq!(
+                yield_position(&params(), allocations).unwrap_err(),
+                ErrorCode::PositionNotFound.into()
+            );
+        }
+    }
+
+    // 测试3: 最短持有时间和最小APY优势
+    #[test]
+    fn test_plan_rotation() {
+        let reports = [(1, 500), (3, 650)];
+        assert_eq!(
+            plan_rotation(&params(), 1, &reports, 0, 3599).unwrap_err(),
+            ErrorCode::YieldHoldActive.into()
+        );
+        assert_eq!(
+            plan_rotation(&params(), 1, &reports, 0, 3600).unwrap(),
+            Rotation {
+                from_protocol: 1,
+                from_apy_bps: 500,
+                to_protocol: 3,
+                to_apy_bps: 650,
+            }
+        );
+
+        // 优势不足或当前协议已是最高
+        for reports in [
+            [(1, 500), (3, 599)],
+            [(1, 500), (3, 500)],
+            [(1, 700), (3, 650)],
+        ] {
+            assert_eq!(
+                plan_rotation(&params(), 1, &reports, 0, 3600).unwrap_err(),
+                ErrorCode::YieldAdvantageTooSmall.into()
+            );
+        }
+
+        // 最小优势为0时APY相同也不轮动
+        let eager = YieldParams {
+            min_apy_advantage_bps: 0,
+            ..params()
+        };
+        assert_eq!(
+            plan_rotation(&eager, 3, &[(1, 650), (3, 650)], 0, 3600).unwrap_err(),
+            ErrorCode::YieldAdvantageTooSmall.into()
+        );
+        assert_eq!(
+            plan_rotation(&eager, 3, &[(1, 651), (3, 650)], 0, 3600)
+                .unwrap()
+                .to_protocol,
+            1
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use crate::errors::ErrorCode;
+use crate::oracle::GuardedPrice;
+use crate::planner::BPS_DENOMINATOR;
+use crate::state::*;
+use crate::validation::validate_allocations;
+use anchor_lang::prelude::*;
+
+// 定投。策略以报价资产的分配项出资，每个再平衡时间间隔把固定价值从报价分配项转移到目标分配项。
+// 转移只调整分配项权重，之后由常规的再平衡按新的权重调整头寸。
+// 任何人都可以在间隔到期后执行，目标资产价格高于上限时不执行
+
+// 增量为正，报价和目标是不同的资产，目标协议已知，且策略当前包含报价分配项
+pub fn validate_dca(params: &DcaParams, allocations: &[Allocation]) -> Result<()> {
+    let target = Allocation {
+        protocol: params.target_protocol,
+        asset: params.target_asset,
+        target_weight_bps: 0,
+    };
+    if params.increment_lamports == 0
+        || params.quote_asset == params.target_asset
+        || target.protocol().is_err()
+        || quote_index(params, allocations).is_none()
+    {
+        return Err(ErrorCode::InvalidDcaParams.into());
+    }
+    Ok(())
+}
+
+// 定投按再平衡时间间隔执行，间隔为0时任何人都可以在同一时刻反复执行，一次把报价分配项转完
+pub fn check_dca_interval(condition: &RebalanceCondition) -> Result<()> {
+    if condition.time_interval_seconds == 0 {
+        return Err(ErrorCode::DcaIntervalRequired.into());
+    }
+    Ok(())
+}
+
+fn quote_index(params: &DcaParams, allocations: &[Allocation]) -> Option<usize> {
+    allocations.iter().position(|allocation| {
+        allocation.protocol == params.quote_protocol && allocation.asset == params.quote_asset
+    })
+}
+
+// 一次定投的结果
+#[derive(Clone, PartialEq, Eq)]
+pub struct DcaPurchase {
+    // 实际转移的价值(lamports)，按转移的权重计算
+    pub amount_lamports: u64,
+    // 转移后的分配项
+    pub allocations: Vec<Allocation>,
+    // 报价分配项已用完
+    pub completed: bool,
+    // 留到下次执行的增量(lamports)
+    pub carried_lamports: u64,
+}
+
+// 把本次增量加上次留下的部分按策略价值换算成权重(向下取整)，不超过报价分配项的剩余权重，
+// 不足1基点的部分留到下次执行，不足1基点时本次不转移。
+// 报价分配项用完时从策略中移除，目标分配项不存在时追加到末尾
+pub fn plan_purchase(
+    params: &DcaParams,
+    carried_lamports: u64,
+    allocations: &[Allocation],
+    total_value: u64,
+) -> Result<DcaPurchase> {
+    let index = quote_index(params, allocations).ok_or(ErrorCode::DcaCompleted)?;
+    if total_value == 0 {
+        return Err(ErrorCode::InsufficientFunds.into());
+    }
+
+    let budget = params
+        .increment_lamports
+        .checked_add(carried_lamports)
+        .ok_or(ErrorCode::MathError)?;
+    let quote_weight = allocations[index].target_weight_bps;
+    let shift = (budget as u128 * BPS_DENOMINATOR as u128 / total_value as u128)
+        .min(quote_weight as u128) as u16;
+    if shift == 0 {
+        return Ok(DcaPurchase {
+            amount_lamports: 0,
+            allocations: allocations.to_vec(),
+            completed: false,
+            carried_lamports: budget,
+        });
+    }
+    let amount = (total_value as u128 * shift as u128 / BPS_DENOMINATOR as u128) as u64;
+
+    let mut next = allocations.to_vec();
+    next[index].target_weight_bps -= shift;
+    match next.iter_mut().find(|allocation| {
+        allocation.protocol == params.target_protocol && allocation.asset == params.target_asset
+    }) {
+        Some(target) => {
+            target.target_weight_bps = target
+                .target_weight_bps
+                .checked_add(shift)
+                .ok_or(ErrorCode::MathError)?;
+        }
+        None => next.push(Allocation {
+            protocol: params.target_protocol,
+            asset: params.target_asset,
+            target_weight_bps: shift,
+        }),
+    }
+    let completed = next[index].target_weight_bps == 0;
+    if completed {
+        next.remove(index);
+    }
+    validate_allocations(&next)?;
+
+    Ok(DcaPurchase {
+        amount_lamports: amount,
+        allocations: next,
+        completed,
+        // 用完时不再留存
+        carried_lamports: if completed { 0 } else { budget - amount },
+    })
+}
+
+// 资产每单位的报价价格(QUOTE_DECIMALS精度)
+pub fn unit_price(price: &GuardedPrice, decimals: u8) -> Result<u64> {
+    let unit = 10u64
+        .checked_pow(decimals as u32)
+        .ok_or(ErrorCode::MathError)?;
+    u64::try_from(price.quote_value(unit, decimals)?).map_err(|_| error!(ErrorCode::MathError))
+}
+
+// 价格高于上限时不执行，上限为0表示不限制
+pub fn check_price_limit(price_quote: u64, max_price_quote: u64) -> Result<()> {
+    if max_price_quote > 0 && price_quote > max_price_quote {
+        return Err(ErrorCode::DcaPriceAboveLimit.into());
+    }
+    Ok(())
+}
+
+// 按策略的最大滑点放宽当前价格，再以价格上限封顶。
+// 定投在链上只转移权重，实际买入由链下执行者完成，该价格随事件发出，由执行者在下单时强制执行
+pub fn max_fill_price(price_quote: u64, max_price_quote: u64, max_slippage_bps: u16) -> u64 {
+    let with_slippage = (price_quote as u128 * (BPS_DENOMINATOR as u128 + max_slippage_bps as u128)
+        / BPS_DENOMINATOR as u128)
+        .min(u64::MAX as u128) as u64;
+    if max_price_quote > 0 {
+        with_slippage.min(max_price_quote)
+    } else {
+        with_slippage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(protocol: u8, asset: u8, target_weight_bps: u16) -> Allocation {
+        Allocation {
+            protocol,
+            asset,
+            target_weight_bps,
+        }
+    }
+
+    fn params(increment_lamports: u64) -> DcaParams {
+        DcaParams {
+            quote_protocol: 1,
+            quote_asset: 2,
+            target_protocol: 0,
+            target_asset: 0,
+            increment_lamports,
+            max_price_quote: 0,
+        }
+    }
+
+    // 测试1: 定投参数校验
+    #[test]
+    fn test_validate_dca() {
+        let allocations = vec![entry(1, 2, 10000)];
+        assert!(validate_dca(&params(100), &allocations).is_ok());
+
+        let invalid = [
+            params(0),
+            DcaParams {
+                target_asset: 2,
+                ..params(100)
+            },
+            DcaParams {
+                target_protocol: 200,
+                ..params(100)
+            },
+            DcaParams {
+                quote_protocol: 0,
+                ..params(100)
+            },
+        ];
+        for params in invalid {
+            assert_eq!(
+                validate_dca(&params, &allocations).unwrap_err(),
+                ErrorCode::InvalidDcaParams.into()
+            );
+        }
+    }
+
+    // 测试2: 按增量转移权重，目标分配项不存在时追加
+    #[test]
+    fn test_plan_purchase() {
+        let purchase = plan_purchase(&params(250), 0, &[entry(1, 2, 10000)], 1000).unwrap();
+        assert_eq!(purchase.amount_lamports, 250);
+        assert!(purchase.allocations == vec![entry(1, 2, 7500), entry(0, 0, 2500)]);
+        assert!(!purchase.completed);
+
+        let purchase = plan_purchase(&params(250), 0, &purchase.allocations, 1000).unwrap();
+        assert!(purchase.allocations == vec![entry(1, 2, 5000), entry(0, 0, 5000)]);
+    }
+
+    // 测试3: 最后一次只转移剩余权重并移除报价分配项，之后的执行返回 DcaCompleted
+    #[test]
+    fn test_plan_purchase_completes() {
+        let allocations = vec![entry(1, 2, 1000), entry(0, 0, 6000), entry(3, 1, 3000)];
+        let purchase = plan_purchase(&params(500), 0, &allocations, 1000).unwrap();
+        assert_eq!(purchase.amount_lamports, 100);
+        assert!(purchase.allocations == vec![entry(0, 0, 7000), entry(3, 1, 3000)]);
+        assert!(purchase.completed);
+
+        assert_eq!(
+            plan_purchase(&params(500), 0, &purchase.allocations, 1000).err(),
+            Some(ErrorCode::DcaCompleted.into())
+        );
+        assert_eq!(
+            plan_purchase(&params(500), 0, &allocations, 0).err(),
+            Some(ErrorCode::InsufficientFunds.into())
+        );
+    }
+
+    // 测试4: 增量不足1基点时不转移，留到下次执行累计，满1基点后只转移整基点的部分
+    #[test]
+    fn test_plan_purchase_carries_remainder() {
+        let allocations = vec![entry(1, 2, 10000)];
+        let purchase = plan_purchase(&params(40), 0, &allocations, 1_000_000).unwrap();
+        assert_eq!(purchase.amount_lamports, 0);
+        assert!(purchase.allocations == allocations);
+        assert_eq!(purchase.carried_lamports, 40);
+
+        let purchase = plan_purchase(&params(40), 40, &allocations, 1_000_000).unwrap();
+        assert_eq!(purchase.amount_lamports, 0);
+        assert_eq!(purchase.carried_lamports, 80);
+
+        let purchase = plan_purchase(&params(40), 80, &allocations, 1_000_000).unwrap();
+        assert_eq!(purchase.amount_lamports, 100);
+        assert!(purchase.allocations == vec![entry(1, 2, 9999), entry(0, 0, 1)]);
+        assert_eq!(purchase.carried_lamports, 20);
+    }
+
+    // 测试5: 价格上限和按滑点放宽的成交价上限
+    #[test]
+    fn test_price_limits() {
+        assert!(check_price_limit(1_000_000, 0).is_ok());
+        assert!(check_price_limit(1_000_000, 1_000_000).is_ok());
+        assert_eq!(
+            check_price_limit(1_000_001, 1_000_000).unwrap_err(),
+            ErrorCode::DcaPriceAboveLimit.into()
+        );
+
+        assert_eq!(max_fill_price(1_000_000, 0, 100), 1_010_000);
+        assert_eq!(max_fill_price(1_000_000, 1_005_000, 100), 1_005_000);
+        assert_eq!(max_fill_price(1_000_000, 2_000_000, 0), 1_000_000);
+    }
+    // 测试6: 再平衡时间间隔为0时不能定投
+    #[test]
+    fn test_check_dca_interval() {
+        let mut condition = RebalanceCondition {
+            time_interval_seconds: 1,
+            max_deviation_bps: 0,
+            auto_rebalance: false,
+        };
+        assert!(check_dca_interval(&condition).is_ok());
+        condition.time_interval_seconds = 0;
+        assert_eq!(
+            check_dca_interval(&condition).unwrap_err(),
+            ErrorCode::DcaIntervalRequired.into()
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use crate::errors::ErrorCode;
+use crate::planner::BPS_DENOMINATOR;
+use crate::state::*;
+use crate::validation::MAX_SLIPPAGE_BPS;
+use anchor_lang::prelude::*;
+
+// 策略代理人。所有者可以授权其他地址执行、再平衡或在限定范围内更新策略；
+// 代理人不能存取资金，资金只在资金库、费用金库和所有者之间流动
+
+// 代理人可以执行的操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DelegateAction {
+    Execute,
+    Rebalance,
+    Update,
+}
+
+impl DelegateScope {
+    pub fn allows(&self, action: DelegateAction) -> bool {
+        match action {
+            DelegateAction::Execute => self.execute,
+            DelegateAction::Rebalance => self.rebalance,
+            DelegateAction::Update => self.update,
+        }
+    }
+}
+
+impl StrategyDelegate {
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+}
+
+// 校验添加代理人的参数: 代理人不是所有者，至少授予一项权限，
+// 更新范围不超过全局上限，到期时间晚于当前时间
+pub fn validate_delegate(
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    scope: &DelegateScope,
+    expires_at: i64,
+    now: i64,
+) -> Result<()> {
+    let grants_any = scope.execute || scope.rebalance || scope.update;
+    if delegate == owner
+        || !grants_any
+        || u64::from(scope.max_weight_change_bps) > BPS_DENOMINATOR
+        || scope.max_slippage_bps > MAX_SLIPPAGE_BPS
+        || expires_at <= now
+    {
+        return Err(ErrorCode::InvalidDelegate.into());
+    }
+    Ok(())
+}
+
+// 校验签名者对策略的操作权限。所有者本人签名时返回 None；
+// 其他签名者必须提供未过期且授权了该操作的代理人账户，返回其授权范围
+pub fn authorize(
+    owner: &Pubkey,
+    authority: &Pubkey,
+    delegate: Option<&StrategyDelegate>,
+    action: DelegateAction,
+    now: i64,
+) -> Result<Option<DelegateScope>> {
+    if authority == owner {
+        return Ok(None);
+    }
+    let delegate = delegate.ok_or(ErrorCode::Unauthorized)?;
+    if delegate.delegate != *authority || delegate.owner != *owner {
+        return Err(ErrorCode::Unauthorized.into());
+    }
+    if !delegate.is_active(now) {
+        return Err(ErrorCode::DelegateExpired.into());
+    }
+    if !delegate.scope.allows(action) {
+        return Err(ErrorCode::DelegatePermissionDenied.into());
+    }
+    Ok(Some(delegate.scope))
+}
+
+// 代理人更新策略时的额外限制: 分配项的协议、资产和顺序不变，
+// 每项权重相对授权时基准的变化和新的滑点不超过授权上限，多次更新也不能累积超出范围。
+// 再平衡条件决定代理人何时可以再平衡，只能由所有者修改
+pub fn check_update_bounds(
+    scope: &DelegateScope,
+    baseline: &[u16],
+    current: &[Allocation],
+    current_condition: &RebalanceCondition,
+    allocations: &[Allocation],
+    rebalance_condition: &RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    if current_condition != rebalance_condition
+        || current.len() != allocations.len()
+        || baseline.len() != allocations.len()
+        || max_slippage_bps > scope.max_slippage_bps
+    {
+        return Err(ErrorCode::DelegateBoundsExceeded.into());
+    }
+    for ((old, new), base) in current.iter().zip(allocations).zip(baseline) {
+        let change = base.abs_diff(new.target_weight_bps);
+        if old.protocol != new.protocol
+            || old.asset != new.asset
+            || change > scope.max_weight_change_bps
+        {
+            return Err(ErrorCode::DelegateBoundsExceeded.into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASELINE: [u16; 2] = [6000, 4000];
+
+    fn scope() -> DelegateScope {
+        DelegateScope {
+            execute: true,
+            rebalance: true,
+            update: false,
+            max_weight_change_bps: 500,
+            max_slippage_bps: 100,
+        }
+    }
+
+    fn delegate(owner: Pubkey, delegate: Pubkey) -> StrategyDelegate {
+        StrategyDelegate {
+            owner,
+            strategy: Pubkey::new_unique(),
+            delegate,
+            scope: scope(),
+            expires_at: 1_000,
+            baseline_weights_bps: vec![6000, 4000],
+            bump: 255,
+        }
+    }
+
+    fn condition(time_interval_seconds: u64) -> RebalanceCondition {
+        RebalanceCondition {
+            time_interval_seconds,
+            max_deviation_bps: 500,
+            auto_rebalance: true,
+        }
+    }
+
+    fn entry(protocol: u8, asset: u8, target_weight_bps: u16) -> Allocation {
+        Allocation {
+            protocol,
+            asset,
+            target_weight_bps,
+        }
+    }
+
+    // 测试1: 添加代理人的参数校验
+    #[test]
+    fn test_validate_delegate() {
+        let owner = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        assert!(validate_delegate(&owner, &other, &scope(), 1_000, 999).is_ok());
+
+        let none = DelegateScope {
+            execute: false,
+            rebalance: false,
+            ..scope()
+        };
+        let wide = DelegateScope {
+            max_slippage_bps: MAX_SLIPPAGE_BPS + 1,
+            ..scope()
+        };
+        for (delegate, scope, expires_at) in [
+            (owner, scope(), 1_000),
+            (other, none, 1_000),
+            (other, wide, 1_000),
+            (other, scope(), 999),
+        ] {
+            assert_eq!(
+                validate_delegate(&owner, &delegate, &scope, expires_at, 999).unwrap_err(),
+                ErrorCode::InvalidDelegate.into()
+            );
+        }
+    }
+
+    // 测试2: 所有者直接通过，代理人按账户、到期时间和权限校验
+    #[test]
+    fn test_authorize() {
+        let owner = Pubkey::new_unique();
+        let bot = Pubkey::new_unique();
+        let account = delegate(owner, bot);
+
+        assert!(authorize(&owner, &owner, None, DelegateAction::Update, 0)
+            .unwrap()
+            .is_none());
+        assert!(
+            authorize(&owner, &bot, Some(&account), DelegateAction::Rebalance, 999)
+                .unwrap()
+                .is_some()
+        );
+
+        let stranger = Pubkey::new_unique();
+        for (authority, delegate, action, now, err) in [
+            (
+                bot,
+                None,
+                DelegateAction::Execute,
+                0,
+                ErrorCode::Unauthorized,
+            ),
+            (
+                stranger,
+                Some(&account),
+                DelegateAction::Execute,
+                0,
+                ErrorCode::Unauthorized,
+            ),
+            (
+                bot,
+                Some(&account),
+                DelegateAction::Execute,
+                1_000,
+                ErrorCode::DelegateExpired,
+            ),
+            (
+                bot,
+                Some(&account),
+                DelegateAction::Update,
+                0,
+                ErrorCode::DelegatePermissionDenied,
+            ),
+        ] {
+            assert_eq!(
+                authorize(&owner, &authority, delegate, action, now).err(),
+                Some(err.into())
+            );
+        }
+    }
+
+    // 测试3: 代理人只能在授权范围内调整权重和滑点
+    #[test]
+    fn test_check_update_bounds() {
+        let current = [entry(0, 0, 6000), entry(1, 1, 4000)];
+        let shifted = [entry(0, 0, 5500), entry(1, 1, 4500)];
+        let cond = condition(3600);
+        assert!(
+            check_update_bounds(&scope(), &BASELINE, &current, &cond, &shifted, &cond, 100).is_ok()
+        );
+
+        for (allocations, slippage) in [
+            (vec![entry(0, 0, 5400), entry(1, 1, 4600)], 100),
+            (vec![entry(0, 0, 6000), entry(2, 1, 4000)], 100),
+            (vec![entry(1, 1, 4000), entry(0, 0, 6000)], 100),
+            (vec![entry(0, 0, 10000)], 100),
+            (shifted.to_vec(), 101),
+        ] {
+            assert_eq!(
+                check_update_bounds(
+                    &scope(),
+                    &BASELINE,
+                    &current,
+                    &cond,
+                    &allocations,
+                    &cond,
+                    slippage
+                )
+                .unwrap_err(),
+                ErrorCode::DelegateBoundsExceeded.into()
+            );
+        }
+    }
+
+    // 测试4: 代理人不能修改再平衡条件，权重不变时也不行
+    #[test]
+    fn test_check_update_bounds_rejects_condition_change() {
+        let current = [entry(0, 0, 6000), entry(1, 1, 4000)];
+        let cond = condition(3600);
+        for changed in [
+            condition(1),
+            RebalanceCondition {
+                max_deviation_bps: 1,
+                ..condition(3600)
+            },
+            RebalanceCondition {
+                auto_rebalance: false,
+                ..condition(3600)
+            },
+        ] {
+            assert_eq!(
+                check_update_bounds(
+                    &scope(),
+                    &BASELINE,
+                    &current,
+                    &cond,
+                    &current,
+                    &changed,
+                    100
+                )
+                .unwrap_err(),
+                ErrorCode::DelegateBoundsExceeded.into()
+            );
+        }
+    }
+
+    // 测试5: 多次更新的权重变化按授权时的基准累计，不能逐步超出上限
+    #[test]
+    fn test_check_update_bounds_baseline() {
+        let cond = condition(3600);
+        let current = [entry(0, 0, 5500), entry(1, 1, 4500)];
+        let back = [entry(0, 0, 6500), entry(1, 1, 3500)];
+        assert!(
+            check_update_bounds(&scope(), &BASELINE, &current, &cond, &back, &cond, 100).is_ok()
+        );
+
+        let further = [entry(0, 0, 5000), entry(1, 1, 5000)];
+        assert_eq!(
+            check_update_bounds(&scope(), &BASELINE, &current, &cond, &further, &cond, 100)
+                .unwrap_err(),
+            ErrorCode::DelegateBoundsExceeded.into()
+        );
+        // 所有者增加分配项后基准失效，需要重新授权
+        let added = [entry(0, 0, 5000), entry(1, 1, 4000), entry(2, 2, 1000)];
+        assert_eq!(
+            check_update_bounds(&scope(), &BASELINE, &added, &cond, &added, &cond, 100)
+                .unwrap_err(),
+            ErrorCode::DelegateBoundsExceeded.into()
+        );
+    }
+}
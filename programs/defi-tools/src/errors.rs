@@ -46,4 +46,178 @@ pub enum ErrorCode {
 
     #[msg("紧急模式已激活")]
     EmergencyModeActive,
+
+    #[msg("预言机价格已过期")]
+    StalePrice,
+
+    #[msg("预言机价格置信区间过宽")]
+    PriceConfidenceTooWide,
+
+    #[msg("两个价格源偏差过大")]
+    PriceSourceDivergence,
+
+    #[msg("预言机价格无效")]
+    InvalidPrice,
+
+    #[msg("无效的预言机账户")]
+    InvalidOracleAccount,
+
+    #[msg("无效的预言机校验参数")]
+    InvalidOracleGuardConfig,
+
+    #[msg("资产未注册")]
+    AssetNotRegistered,
+
+    #[msg("资产已停用")]
+    AssetDisabled,
+
+    #[msg("分配权重超过资产上限")]
+    AssetAllocationCapExceeded,
+
+    #[msg("无效的资产配置")]
+    InvalidAssetConfig,
+
+    #[msg("未知的协议类型")]
+    UnknownProtocol,
+
+    #[msg("重复的分配项(协议和资产相同)")]
+    DuplicateAllocation,
+
+    #[msg("分配项权重不能为0")]
+    ZeroWeightAllocation,
+
+    #[msg("分配项数量超过上限")]
+    TooManyAllocations,
+
+    #[msg("分配项权重超出范围(1-10000基点)")]
+    AllocationWeightOutOfRange,
+
+    #[msg("费率超出上限")]
+    InvalidFeeConfig,
+
+    #[msg("费用金库账户无效")]
+    InvalidTreasury,
+
+    #[msg("存款金额低于最小值")]
+    DepositTooSmall,
+
+    #[msg("存款后策略总价值超过上限")]
+    StrategyDepositCapExceeded,
+
+    #[msg("存款后用户总价值超过上限")]
+    UserDepositCapExceeded,
+
+    #[msg("存款后资产总存款超过上限")]
+    AssetDepositCapExceeded,
+
+    #[msg("无效的存款限制")]
+    InvalidDepositLimits,
+
+    #[msg("提款锁定时间超过上限")]
+    InvalidWithdrawalDelay,
+
+    #[msg("提款工单尚未解锁，头寸未解除且锁定时间未到")]
+    WithdrawalNotReady,
+
+    #[msg("提款工单须按先后顺序领取")]
+    WithdrawalOutOfOrder,
+
+    #[msg("无效的代理人授权")]
+    InvalidDelegate,
+
+    #[msg("代理人授权已过期")]
+    DelegateExpired,
+
+    #[msg("代理人无权执行该操作")]
+    DelegatePermissionDenied,
+
+    #[msg("策略更新超出代理人的授权范围")]
+    DelegateBoundsExceeded,
+
+    #[msg("无效的提款白名单地址")]
+    InvalidWithdrawalDestination,
+
+    #[msg("提款接收地址不在白名单中")]
+    WithdrawalDestinationNotAllowed,
+
+    #[msg("提款白名单地址尚未生效")]
+    WithdrawalDestinationPending,
+
+    #[msg("无效的提款限额")]
+    InvalidSpendingLimit,
+
+    #[msg("超出周期提款限额")]
+    SpendingLimitExceeded,
+
+    #[msg("放宽提款限额需要监护人签名")]
+    GuardianApprovalRequired,
+
+    #[msg("无效的新所有者")]
+    InvalidNewOwner,
+
+    #[msg("所有权转移尚未到可接受的时间")]
+    OwnerTransferPending,
+
+    #[msg("用户配置已转移给新所有者")]
+    ProfileMigrated,
+
+    #[msg("策略还有未领取的提款工单")]
+    WithdrawalsPending,
+
+    #[msg("模板的风险等级高于用户的风险等级")]
+    TemplateRiskTooHigh,
+
+    #[msg("无效的跟随参数")]
+    InvalidFollowParams,
+
+    #[msg("领投策略的分配项没有新的调整")]
+    FollowerInSync,
+
+    #[msg("领投策略的调整尚未到可同步的时间")]
+    FollowDelayActive,
+
+    #[msg("领投策略的分配项超出跟随者的权重上限")]
+    FollowerRiskExceeded,
+
+    #[msg("无效的定投参数")]
+    InvalidDcaParams,
+
+    #[msg("目标资产价格高于定投价格上限")]
+    DcaPriceAboveLimit,
+
+    #[msg("定投的报价分配项已用完")]
+    DcaCompleted,
+
+    #[msg("无效的保护单参数")]
+    InvalidProtectionParams,
+
+    #[msg("策略未暂停")]
+    StrategyNotPaused,
+
+    #[msg("无效的收益率轮动参数")]
+    InvalidYieldParams,
+
+    #[msg("无效的利率追踪账户")]
+    InvalidApyTracker,
+
+    #[msg("利率数据已过期")]
+    StaleApy,
+
+    #[msg("未满最短持有时间")]
+    YieldHoldActive,
+
+    #[msg("APY优势不足")]
+    YieldAdvantageTooSmall,
+
+    #[msg("策略存款涉及的资产数量超过上限")]
+    TooManyAssetDeposits,
+
+    #[msg("策略已启用其他自动调整模式")]
+    StrategyModeConflict,
+
+    #[msg("定投要求再平衡时间间隔大于0")]
+    DcaIntervalRequired,
+
+    #[msg("策略仍有定投、跟随、轮动、保护单或代理人授权")]
+    StrategyLinksActive,
 }
@@ -0,0 +1,523 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 全局配置已初始化
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub oracle_guard: OracleGuardConfig,
+    pub timestamp: i64,
+}
+
+// 预言机校验参数已更新
+#[event]
+pub struct OracleGuardUpdated {
+    pub admin: Pubkey,
+    pub oracle_guard: OracleGuardConfig,
+    pub timestamp: i64,
+}
+
+// 费率或费用金库已更新
+#[event]
+pub struct FeeConfigUpdated {
+    pub admin: Pubkey,
+    pub fees: FeeConfig,
+    pub treasury: Pubkey,
+    pub timestamp: i64,
+}
+
+// 存款限制已更新
+#[event]
+pub struct DepositLimitsUpdated {
+    pub admin: Pubkey,
+    pub limits: DepositLimits,
+    pub timestamp: i64,
+}
+
+// 提款锁定时间已更新
+#[event]
+pub struct WithdrawalDelayUpdated {
+    pub admin: Pubkey,
+    pub delay_seconds: u32,
+    pub timestamp: i64,
+}
+
+// 提款白名单的生效延迟已更新
+#[event]
+pub struct AllowlistDelayUpdated {
+    pub admin: Pubkey,
+    pub delay_seconds: u32,
+    pub timestamp: i64,
+}
+
+// 资产已注册
+#[event]
+pub struct AssetRegistered {
+    pub asset_id: u8,
+    pub params: AssetParams,
+    pub timestamp: i64,
+}
+
+// 资产配置已更新
+#[event]
+pub struct AssetUpdated {
+    pub asset_id: u8,
+    pub params: AssetParams,
+    pub timestamp: i64,
+}
+
+// 用户配置文件已初始化
+#[event]
+pub struct UserInitialized {
+    pub owner: Pubkey,
+    pub user_profile: Pubkey,
+    pub risk_level: u8,
+    pub timestamp: i64,
+}
+
+// 策略已创建
+#[event]
+pub struct StrategyCreated {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub allocations: Vec<Allocation>,
+    pub rebalance_condition: RebalanceCondition,
+    pub max_slippage_bps: u16,
+    pub timestamp: i64,
+}
+
+// 策略参数已更新
+#[event]
+pub struct StrategyUpdated {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub allocations: Vec<Allocation>,
+    pub rebalance_condition: RebalanceCondition,
+    pub max_slippage_bps: u16,
+    pub timestamp: i64,
+}
+
+// 资金已存入策略
+#[event]
+pub struct FundsDeposited {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub amount: u64,
+    // 存款后策略的总价值
+    pub strategy_value_lamports: u64,
+    // 存款后用户的总资产价值
+    pub user_value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 资金已从策略提取
+#[event]
+pub struct FundsWithdrawn {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub amount: u64,
+    // 接收提款的地址
+    pub destination: Pubkey,
+    // 提款后策略的总价值
+    pub strategy_value_lamports: u64,
+    // 提款后用户的总资产价值
+    pub user_value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 提款工单已创建，金额已从策略价值中扣除并锁定
+#[event]
+pub struct WithdrawalRequested {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub ticket_id: u64,
+    pub amount: u64,
+    pub unlocks_at: i64,
+    // 申请后策略的总价值
+    pub strategy_value_lamports: u64,
+    // 申请后用户的总资产价值
+    pub user_value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 提款工单已领取
+#[event]
+pub struct WithdrawalClaimed {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub ticket_id: u64,
+    pub amount: u64,
+    // 接收提款的地址
+    pub destination: Pubkey,
+    pub timestamp: i64,
+}
+
+// 提款工单已取消，金额退回策略
+#[event]
+pub struct WithdrawalCancelled {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub ticket_id: u64,
+    pub amount: u64,
+    // 取消后策略的总价值
+    pub strategy_value_lamports: u64,
+    // 取消后用户的总资产价值
+    pub user_value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 策略已执行
+#[event]
+pub struct StrategyExecuted {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub timestamp: i64,
+}
+
+// 再平衡中的单个分配项
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RebalanceLeg {
+    pub protocol: u8,
+    pub asset: u8,
+    pub target_weight_bps: u16,
+    // 按目标权重分配到该项的价值
+    pub target_value_lamports: u64,
+    // 再平衡时使用的已校验价格
+    pub price: u64,
+    pub expo: i32,
+}
+
+// 策略头寸已再平衡
+#[event]
+pub struct PositionsRebalanced {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub strategy_value_lamports: u64,
+    pub legs: Vec<RebalanceLeg>,
+    pub timestamp: i64,
+}
+
+// 策略费用已计提并支付给费用金库
+#[event]
+pub struct FeesCharged {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub treasury: Pubkey,
+    pub management_fee_lamports: u64,
+    // 扣费后策略的总价值
+    pub strategy_value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 策略代理人已添加
+#[event]
+pub struct DelegateAdded {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub delegate: Pubkey,
+    pub scope: DelegateScope,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+// 策略代理人已撤销
+#[event]
+pub struct DelegateRevoked {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub delegate: Pubkey,
+    pub timestamp: i64,
+}
+
+// 提款白名单地址已添加，生效时间之后才能接收提款
+#[event]
+pub struct WithdrawalDestinationAdded {
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+    pub activates_at: i64,
+    pub timestamp: i64,
+}
+
+// 提款白名单地址已移除
+#[event]
+pub struct WithdrawalDestinationRemoved {
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+    pub timestamp: i64,
+}
+
+// 提款白名单已启用或关闭；关闭在 effective_at 之后生效
+#[event]
+pub struct WithdrawalAllowlistUpdated {
+    pub owner: Pubkey,
+    pub enabled: bool,
+    pub effective_at: i64,
+    pub timestamp: i64,
+}
+
+// 周期提款限额已更新
+#[event]
+pub struct SpendingLimitUpdated {
+    pub owner: Pubkey,
+    pub max_lamports: u64,
+    pub window_seconds: u32,
+    pub guardian: Pubkey,
+    pub timestamp: i64,
+}
+
+// 所有者提名了新所有者，new_owner 为默认值表示取消
+#[event]
+pub struct OwnerProposed {
+    pub owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub accept_after: i64,
+    pub timestamp: i64,
+}
+
+// 新所有者已接受转移并创建了新的用户配置
+#[event]
+pub struct OwnerAccepted {
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub user_profile: Pubkey,
+    pub timestamp: i64,
+}
+
+// 策略及其资金已迁移到新所有者名下，策略ID不变
+#[event]
+pub struct StrategyMigrated {
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub strategy_id: u64,
+    pub previous_strategy: Pubkey,
+    pub strategy: Pubkey,
+    pub value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 策略模板已发布
+#[event]
+pub struct TemplatePublished {
+    pub publisher: Pubkey,
+    pub template: Pubkey,
+    pub template_id: u64,
+    pub allocations: Vec<Allocation>,
+    pub rebalance_condition: RebalanceCondition,
+    pub max_slippage_bps: u16,
+    pub risk_level: u8,
+    pub description_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+// 策略模板已停用并关闭，已创建的策略不受影响
+#[event]
+pub struct TemplateRetired {
+    pub publisher: Pubkey,
+    pub template: Pubkey,
+    pub template_id: u64,
+    pub strategies_created: u64,
+    pub timestamp: i64,
+}
+
+// 按模板创建了策略，与 StrategyCreated 一同发出
+#[event]
+pub struct StrategyCreatedFromTemplate {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub template: Pubkey,
+    pub publisher: Pubkey,
+    pub template_id: u64,
+    pub timestamp: i64,
+}
+
+// 跟随策略开始跟随领投策略
+#[event]
+pub struct StrategyFollowed {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub leader: Pubkey,
+    pub leader_owner: Pubkey,
+    pub max_weight_bps: u16,
+    pub delay_seconds: u32,
+    pub leader_fee_bps: u16,
+    pub timestamp: i64,
+}
+
+// 跟随策略取消跟随
+#[event]
+pub struct StrategyUnfollowed {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub leader: Pubkey,
+    pub syncs: u64,
+    pub timestamp: i64,
+}
+
+// 跟随策略已同步领投策略的分配项，领投费为0表示未收费
+#[event]
+pub struct FollowerSynced {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub leader: Pubkey,
+    pub leader_owner: Pubkey,
+    pub allocations: Vec<Allocation>,
+    pub leader_fee_lamports: u64,
+    pub strategy_value_lamports: u64,
+    pub user_value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 创建了定投计划
+#[event]
+pub struct DcaCreated {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub params: DcaParams,
+    pub timestamp: i64,
+}
+
+// 取消了定投计划
+#[event]
+pub struct DcaCancelled {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub purchased_lamports: u64,
+    pub purchases: u64,
+    pub timestamp: i64,
+}
+
+// 执行了一次定投。价格均为目标资产每单位的报价价格(QUOTE_DECIMALS精度)，
+// 执行者按新的分配项再平衡时成交价不应高于 max_fill_price_quote。
+// 程序不执行兑换，该上限只能由链下执行者强制执行
+#[event]
+pub struct DcaExecuted {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub quote_asset: u8,
+    pub target_asset: u8,
+    pub amount_lamports: u64,
+    pub price_quote: u64,
+    pub max_fill_price_quote: u64,
+    pub allocations: Vec<Allocation>,
+    pub completed: bool,
+    pub timestamp: i64,
+}
+
+// 为策略设置了保护单
+#[event]
+pub struct ProtectionSet {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub params: ProtectionParams,
+    pub value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 取消了保护单
+#[event]
+pub struct ProtectionCancelled {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub timestamp: i64,
+}
+
+// 按预言机价格重新估值，未满足触发条件
+#[event]
+pub struct ProtectionChecked {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub value_lamports: u64,
+    pub peak_value_lamports: u64,
+    pub timestamp: i64,
+}
+
+// 保护单触发：原有分配项全部转入安全头寸，策略暂停。
+// 执行者应按原有分配项解除头寸，成交价按策略的最大滑点约束
+#[event]
+pub struct ProtectionTriggered {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub trigger: ProtectionTrigger,
+    pub value_lamports: u64,
+    pub peak_value_lamports: u64,
+    pub unwound: Vec<Allocation>,
+    pub allocations: Vec<Allocation>,
+    pub timestamp: i64,
+}
+
+// 所有者恢复了被保护单暂停的策略
+#[event]
+pub struct StrategyResumed {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub timestamp: i64,
+}
+
+// 管理员报告了协议和资产的APY
+#[event]
+pub struct ApyReported {
+    pub protocol: u8,
+    pub asset: u8,
+    pub apy_bps: u32,
+    pub timestamp: i64,
+}
+
+// 策略启用了收益率轮动
+#[event]
+pub struct YieldRotationEnabled {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub params: YieldParams,
+    pub protocol: u8,
+    pub timestamp: i64,
+}
+
+// 策略停用了收益率轮动
+#[event]
+pub struct YieldRotationDisabled {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub rotations: u64,
+    pub timestamp: i64,
+}
+
+// 轮动头寸移动到APY更高的协议，执行者应在再平衡时按新的分配项调整头寸
+#[event]
+pub struct YieldRotated {
+    pub owner: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_id: u64,
+    pub asset: u8,
+    pub from_protocol: u8,
+    pub from_apy_bps: u32,
+    pub to_protocol: u8,
+    pub to_apy_bps: u32,
+    pub allocations: Vec<Allocation>,
+    pub timestamp: i64,
+}
@@ -0,0 +1,270 @@
+use crate::errors::ErrorCode;
+use crate::planner::BPS_DENOMINATOR;
+use crate::state::FeeConfig;
+use anchor_lang::prelude::*;
+
+// 管理费和业绩费计算。只依赖金额和时间，不读取账户，
+// 链上存取款、再平衡和链下回测共用同一份实现。所有费用向下取整，舍入误差归用户。
+// 链上只计提管理费: 程序不执行兑换，资金库只持有存入的SOL，策略的账面价值只随存取款和费用变化，
+// 不会产生可以计提业绩费的收益。业绩费和高水位只在回测中按模拟的头寸价值计算
+
+pub const SECONDS_PER_YEAR: u64 = 365 * 86_400;
+// 年化管理费上限 5%
+pub const MAX_MANAGEMENT_FEE_BPS: u16 = 500;
+// 业绩费上限 50%
+pub const MAX_PERFORMANCE_FEE_BPS: u16 = 5000;
+
+impl FeeConfig {
+    // 校验费率是否在上限之内
+    pub fn validate(&self) -> Result<()> {
+        if self.management_fee_bps > MAX_MANAGEMENT_FEE_BPS
+            || self.performance_fee_bps > MAX_PERFORMANCE_FEE_BPS
+        {
+            return Err(ErrorCode::InvalidFeeConfig.into());
+        }
+        Ok(())
+    }
+
+    // 链上配置的费率: 在上限之内，且不收业绩费
+    pub fn validate_onchain(&self) -> Result<()> {
+        self.validate()?;
+        if self.performance_fee_bps > 0 {
+            return Err(ErrorCode::InvalidFeeConfig.into());
+        }
+        Ok(())
+    }
+}
+
+// 管理费 = 价值 × 年费率 × 经过秒数 / (10000 × 一年秒数)，不超过价值本身
+pub fn management_fee(value: u64, fee_bps: u16, elapsed_seconds: u64) -> Result<u64> {
+    let fee = (value as u128)
+        .checked_mul(fee_bps as u128)
+        .and_then(|v| v.checked_mul(elapsed_seconds as u128))
+        .ok_or(ErrorCode::MathError)?
+        / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+    Ok(fee.min(value as u128) as u64)
+}
+
+// 业绩费 = 超过高水位的部分 × 业绩费率
+pub fn performance_fee(value: u64, high_water_mark: u64, fee_bps: u16) -> u64 {
+    let gain = value.saturating_sub(high_water_mark) as u128;
+    (gain * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// 一次计提的结果
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeAccrual {
+    pub management_fee: u64,
+    pub performance_fee: u64,
+    // 扣除费用后的价值
+    pub value: u64,
+    // 计提后的高水位
+    pub high_water_mark: u64,
+    // 下次计提管理费的起始时间
+    pub accrued_at: i64,
+}
+
+impl FeeAccrual {
+    pub fn total(&self) -> u64 {
+        self.management_fee + self.performance_fee
+    }
+}
+
+// 计提 accrued_at 到 now 的管理费，返回费用和下次计提的起始时间。
+// 管理费向下取整为0时不推进计费时间，避免频繁操作让不足1 lamport的费用一直被舍去。
+// accrued_at 为0表示尚未开始计费，只记录起始时间。创建策略时已设置计费起点，
+// 不会出现这种情况；新增费用字段前创建的策略账户没有扩容，无法反序列化，不在此处兼容
+pub fn accrue_management(
+    fees: &FeeConfig,
+    value: u64,
+    accrued_at: i64,
+    now: i64,
+) -> Result<(u64, i64)> {
+    if accrued_at == 0 {
+        return Ok((0, now));
+    }
+    let elapsed = now.saturating_sub(accrued_at).max(0) as u64;
+    let fee = management_fee(value, fees.management_fee_bps, elapsed)?;
+    let accrued_at = if fee > 0 || value == 0 || fees.management_fee_bps == 0 {
+        now
+    } else {
+        accrued_at
+    };
+    Ok((fee, accrued_at))
+}
+
+// 回测使用: 先计提管理费，再对扣除管理费后超过高水位的收益计提业绩费，
+// 高水位随后提高到扣费后的价值。尚未开始计费时只记录起始时间和高水位
+pub fn accrue(
+    fees: &FeeConfig,
+    value: u64,
+    high_water_mark: u64,
+    accrued_at: i64,
+    now: i64,
+) -> Result<FeeAccrual> {
+    if accrued_at == 0 {
+        return Ok(FeeAccrual {
+            value,
+            high_water_mark: high_water_mark.max(value),
+            accrued_at: now,
+            ..FeeAccrual::default()
+        });
+    }
+
+    let (management_fee, accrued_at) = accrue_management(fees, value, accrued_at, now)?;
+
+    let after_management = value - management_fee;
+    let performance_fee =
+        performance_fee(after_management, high_water_mark, fees.performance_fee_bps);
+    let value = after_management - performance_fee;
+
+    Ok(FeeAccrual {
+        management_fee,
+        performance_fee,
+        value,
+        high_water_mark: high_water_mark.max(value),
+        accrued_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fees(management_fee_bps: u16, performance_fee_bps: u16) -> FeeConfig {
+        FeeConfig {
+            management_fee_bps,
+            performance_fee_bps,
+        }
+    }
+
+    // 测试1: 管理费按时间线性计提
+    #[test]
+    fn test_management_fee() {
+        assert_eq!(
+            management_fee(1_000_000, 200, SECONDS_PER_YEAR).unwrap(),
+            20_000
+        );
+        assert_eq!(
+            management_fee(1_000_000, 200, SECONDS_PER_YEAR / 2).unwrap(),
+            10_000
+        );
+        assert_eq!(management_fee(1_000_000, 0, SECONDS_PER_YEAR).unwrap(), 0);
+        // 超长时间也不会超过价值本身
+        assert_eq!(
+            management_fee(1_000, 500, SECONDS_PER_YEAR * 100).unwrap(),
+            1_000
+        );
+    }
+
+    // 测试2: 管理费向下取整，为0时不推进计费时间
+    #[test]
+    fn test_management_fee_rounding() {
+        // 1_000_000 × 200 × 60 / (10000 × 31536000) = 0.038
+        assert_eq!(management_fee(1_000_000, 200, 60).unwrap(), 0);
+
+        let accrual = accrue(&fees(200, 0), 1_000_000, 1_000_000, 100, 160).unwrap();
+        assert_eq!(accrual.total(), 0);
+        assert_eq!(accrual.accrued_at, 100);
+
+        // 累积足够时间后一次性计提
+        let accrual = accrue(&fees(200, 0), 1_000_000, 1_000_000, 100, 100 + 86_400).unwrap();
+        assert_eq!(accrual.management_fee, 54);
+        assert_eq!(accrual.accrued_at, 100 + 86_400);
+    }
+
+    // 测试3: 中间结果溢出时返回错误
+    #[test]
+    fn test_management_fee_overflow() {
+        let err = management_fee(u64::MAX, 500, u64::MAX).unwrap_err();
+        assert_eq!(err, ErrorCode::MathError.into());
+    }
+
+    // 测试4: 业绩费只计提超过高水位的部分，之后高水位提高到扣费后的价值
+    #[test]
+    fn test_performance_fee() {
+        assert_eq!(performance_fee(900, 1_000, 2000), 0);
+        assert_eq!(performance_fee(1_500, 1_000, 2000), 100);
+        // 1 lamport 的 20% 向下取整为0
+        assert_eq!(performance_fee(1_001, 1_000, 2000), 0);
+
+        let accrual = accrue(&fees(0, 2000), 1_500, 1_000, 100, 200).unwrap();
+        assert_eq!(accrual.performance_fee, 100);
+        assert_eq!(accrual.value, 1_400);
+        assert_eq!(accrual.high_water_mark, 1_400);
+
+        // 回撤后没有业绩费，高水位保持不变
+        let accrual = accrue(&fees(0, 2000), 1_200, 1_400, 200, 300).unwrap();
+        assert_eq!(accrual.total(), 0);
+        assert_eq!(accrual.high_water_mark, 1_400);
+    }
+
+    // 测试5: 先扣管理费，再对剩余收益计提业绩费
+    #[test]
+    fn test_accrue_both() {
+        let value = 1_100_000;
+        let accrual = accrue(
+            &fees(500, 1000),
+            value,
+            1_000_000,
+            1,
+            1 + SECONDS_PER_YEAR as i64,
+        )
+        .unwrap();
+        assert_eq!(accrual.management_fee, 55_000);
+        // (1_045_000 - 1_000_000) × 10%
+        assert_eq!(accrual.performance_fee, 4_500);
+        assert_eq!(accrual.value, value - 59_500);
+        assert_eq!(accrual.high_water_mark, accrual.value);
+    }
+
+    // 测试6: 尚未开始计费的策略只记录起始时间和高水位
+    #[test]
+    fn test_accrue_uninitialized() {
+        let accrual = accrue(&fees(500, 5000), 2_000, 0, 0, 1_000).unwrap();
+        assert_eq!(accrual.total(), 0);
+        assert_eq!(accrual.value, 2_000);
+        assert_eq!(accrual.high_water_mark, 2_000);
+        assert_eq!(accrual.accrued_at, 1_000);
+    }
+
+    // 测试7: 链上只计提管理费，与 accrue 的管理费部分一致
+    #[test]
+    fn test_accrue_management() {
+        let year = SECONDS_PER_YEAR as i64;
+        assert_eq!(
+            accrue_management(&fees(200, 0), 1_000_000, 100, 100 + year).unwrap(),
+            (20_000, 100 + year)
+        );
+        assert_eq!(
+            accrue_management(&fees(200, 0), 1_000_000, 100, 160).unwrap(),
+            (0, 100)
+        );
+        assert_eq!(
+            accrue_management(&fees(200, 0), 1_000_000, 0, 160).unwrap(),
+            (0, 160)
+        );
+    }
+
+    // 测试8: 费率上限
+    #[test]
+    fn test_fee_config_validate() {
+        assert!(fees(MAX_MANAGEMENT_FEE_BPS, MAX_PERFORMANCE_FEE_BPS)
+            .validate()
+            .is_ok());
+        for config in [
+            fees(MAX_MANAGEMENT_FEE_BPS + 1, 0),
+            fees(0, MAX_PERFORMANCE_FEE_BPS + 1),
+        ] {
+            let err = config.validate().unwrap_err();
+            assert_eq!(err, ErrorCode::InvalidFeeConfig.into());
+        }
+
+        // 链上不收业绩费
+        assert!(fees(MAX_MANAGEMENT_FEE_BPS, 0).validate_onchain().is_ok());
+        assert_eq!(
+            fees(0, 1).validate_onchain().unwrap_err(),
+            ErrorCode::InvalidFeeConfig.into()
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::errors::ErrorCode;
+use crate::fees::management_fee;
+use crate::planner::BPS_DENOMINATOR;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 跟投。跟随策略订阅一个领投策略，领投策略调整分配项后，任何人都可以调用同步指令
+// 把新的分配项复制到跟随策略，之后由常规的再平衡调整头寸。
+// 跟随者设置权重上限、同步延迟和支付给领投者的费用，延迟期内可以取消跟随。
+// 领投费按年费率和时间计提，在同步时结算，领投者频繁调整分配项不会增加费用
+
+// 领投费年费率上限(1%)
+pub const MAX_LEADER_FEE_BPS: u16 = 100;
+// 同步延迟上限(7天)
+pub const MAX_FOLLOW_DELAY_SECONDS: u32 = 7 * 24 * 60 * 60;
+
+// 不能跟随策略自身，权重上限为1-10000基点，延迟和费用不超过上限
+pub fn validate_follow(
+    strategy: &Pubkey,
+    leader: &Pubkey,
+    max_weight_bps: u16,
+    delay_seconds: u32,
+    leader_fee_bps: u16,
+) -> Result<()> {
+    if strategy == leader
+        || max_weight_bps == 0
+        || u64::from(max_weight_bps) > BPS_DENOMINATOR
+        || delay_seconds > MAX_FOLLOW_DELAY_SECONDS
+        || leader_fee_bps > MAX_LEADER_FEE_BPS
+    {
+        return Err(ErrorCode::InvalidFollowParams.into());
+    }
+    Ok(())
+}
+
+// 领投策略在上次同步之后调整过分配项、已过同步延迟，且每个分配项都不超过跟随者的权重上限
+pub fn check_sync(follow: &StrategyFollow, leader: &StrategyConfig, now: i64) -> Result<()> {
+    if leader.allocations_updated_at <= follow.leader_synced_at {
+        return Err(ErrorCode::FollowerInSync.into());
+    }
+    let ready_at = leader
+        .allocations_updated_at
+        .checked_add(i64::from(follow.delay_seconds))
+        .ok_or(ErrorCode::MathError)?;
+    if now < ready_at {
+        return Err(ErrorCode::FollowDelayActive.into());
+    }
+    if leader
+        .allocations
+        .iter()
+        .any(|allocation| allocation.target_weight_bps > follow.max_weight_bps)
+    {
+        return Err(ErrorCode::FollowerRiskExceeded.into());
+    }
+    Ok(())
+}
+
+// 领投费从上次同步(首次同步为开始跟随)起计提，计算方式与管理费相同
+pub fn leader_fee(follow: &StrategyFollow, value: u64, now: i64) -> Result<u64> {
+    let accrued_from = follow.synced_at.max(follow.followed_at);
+    let elapsed = now.saturating_sub(accrued_from).max(0) as u64;
+    management_fee(value, follow.leader_fee_bps, elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fees::SECONDS_PER_YEAR;
+
+    fn follow(max_weight_bps: u16, delay_seconds: u32, leader_synced_at: i64) -> StrategyFollow {
+        StrategyFollow {
+            owner: Pubkey::new_unique(),
+            strategy: Pubkey::new_unique(),
+            leader: Pubkey::new_unique(),
+            max_weight_bps,
+            delay_seconds,
+            leader_fee_bps: 0,
+            followed_at: 0,
+            leader_synced_at,
+            synced_at: 0,
+            syncs: 0,
+            bump: 255,
+        }
+    }
+
+    fn leader(weights: &[u16], allocations_updated_at: i64) -> StrategyConfig {
+        StrategyConfig {
+            owner: Pubkey::new_unique(),
+            strategy_id: 0,
+            allocations: weights
+                .iter()
+                .enumerate()
+                .map(|(i, weight)| Allocation {
+                    protocol: i as u8,
+                    asset: 0,
+                    target_weight_bps: *weight,
+                })
+                .collect(),
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 3600,
+                max_deviation_bps: 500,
+                auto_rebalance: true,
+            },
+            created_at: 0,
+            last_executed_at: 0,
+            max_slippage_bps: 100,
+            total_value_lamports: 0,
+            fees_accrued_at: 0,
+            withdrawal_queue: WithdrawalQueue::default(),
+            allocations_updated_at,
+            peak_value_lamports: 0,
+            paused_at: 0,
+            asset_deposits: Vec::new(),
+            mode: StrategyMode::Manual,
+            delegates: 0,
+            origin: Pubkey::default(),
+            unwind_pending: false,
+        }
+    }
+
+    // 测试1: 跟随参数的边界
+    #[test]
+    fn test_validate_follow() {
+        let strategy = Pubkey::new_unique();
+        let leader = Pubkey::new_unique();
+        assert!(validate_follow(&strategy, &leader, 10000, 0, 0).is_ok());
+        assert!(validate_follow(
+            &strategy,
+            &leader,
+            1,
+            MAX_FOLLOW_DELAY_SECONDS,
+            MAX_LEADER_FEE_BPS
+        )
+        .is_ok());
+
+        for (leader, max_weight_bps, delay_seconds, fee_bps) in [
+            (strategy, 10000, 0, 0),
+            (leader, 0, 0, 0),
+            (leader, 10001, 0, 0),
+            (leader, 10000, MAX_FOLLOW_DELAY_SECONDS + 1, 0),
+            (leader, 10000, 0, MAX_LEADER_FEE_BPS + 1),
+        ] {
+            assert_eq!(
+                validate_follow(&strategy, &leader, max_weight_bps, delay_seconds, fee_bps)
+                    .unwrap_err(),
+                ErrorCode::InvalidFollowParams.into()
+            );
+        }
+    }
+
+    // 测试2: 只同步上次同步之后的调整，且要等待同步延迟
+    #[test]
+    fn test_check_sync_timing() {
+        let follow = follow(10000, 60, 100);
+        assert_eq!(
+            check_sync(&follow, &leader(&[10000], 100), 1000).unwrap_err(),
+            ErrorCode::FollowerInSync.into()
+        );
+        assert_eq!(
+            check_sync(&follow, &leader(&[10000], 200), 259).unwrap_err(),
+            ErrorCode::FollowDelayActive.into()
+        );
+        assert!(check_sync(&follow, &leader(&[10000], 200), 260).is_ok());
+    }
+
+    // 测试3: 领投策略的任一分配项超过权重上限时不同步
+    #[test]
+    fn test_check_sync_risk() {
+        let follow = follow(6000, 0, 0);
+        assert!(check_sync(&follow, &leader(&[6000, 4000], 10), 10).is_ok());
+        assert_eq!(
+            check_sync(&follow, &leader(&[7000, 3000], 10), 10).unwrap_err(),
+            ErrorCode::FollowerRiskExceeded.into()
+        );
+    }
+
+    // 测试4: 领投费按上次同步以来的时间计提，同一时刻重复同步不收费
+    #[test]
+    fn test_leader_fee() {
+        let year = SECONDS_PER_YEAR as i64;
+        let mut follow = follow(10000, 0, 0);
+        follow.leader_fee_bps = 100;
+        follow.followed_at = 1000;
+        assert_eq!(leader_fee(&follow, 1_000_000, 1000 + year).unwrap(), 10_000);
+        assert_eq!(
+            leader_fee(&follow, 1_000_000, 1000 + year / 2).unwrap(),
+            5_000
+        );
+        assert_eq!(leader_fee(&follow, 1_000_000, 1000).unwrap(), 0);
+
+        follow.synced_at = 1000 + year;
+        assert_eq!(leader_fee(&follow, 1_000_000, 1000 + year).unwrap(), 0);
+        assert_eq!(
+            leader_fee(&follow, 1_000_000, 1000 + 2 * year).unwrap(),
+            10_000
+        );
+
+        follow.leader_fee_bps = 0;
+        assert_eq!(leader_fee(&follow, 1_000_000, 1000 + 3 * year).unwrap(), 0);
+    }
+}
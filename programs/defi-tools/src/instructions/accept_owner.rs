@@ -0,0 +1,94 @@
+use crate::errors::ErrorCode;
+use crate::events::OwnerAccepted;
+use crate::ownership::inherit_profile;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+#[derive(Accounts)]
+#[instruction(previous_owner: Pubkey)]
+pub struct AcceptOwner<'info> {
+    // 被提名的新所有者
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", previous_owner.as_ref()],
+        bump,
+        constraint = previous_profile.pending_owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !previous_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub previous_profile: Account<'info, UserProfile>,
+
+    // 新所有者名下的用户配置，新所有者不能已有用户配置
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<UserProfile>(),
+        seeds = [b"user", owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// 接受转移：创建新配置并冻结原配置，策略和资金随后通过 migrate_strategy 迁移
+pub fn process(ctx: Context<AcceptOwner>, previous_owner: Pubkey) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if now < ctx.accounts.previous_profile.pending_owner_at {
+        return Err(ErrorCode::OwnerTransferPending.into());
+    }
+
+    // 与初始化用户相同，资金库先补足免租金余额
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let shortfall = rent_exempt.saturating_sub(ctx.accounts.vault.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    let owner = ctx.accounts.owner.key();
+    let previous_profile = &mut ctx.accounts.previous_profile;
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.owner = owner;
+    user_profile.vault_bump = ctx.bumps.vault;
+    user_profile.last_activity = now;
+    user_profile.total_value_lamports = 0;
+    user_profile.pending_owner = Pubkey::default();
+    user_profile.pending_owner_at = 0;
+    user_profile.migrated_to = Pubkey::default();
+    inherit_profile(previous_profile, user_profile);
+
+    previous_profile.migrated_to = owner;
+    previous_profile.pending_owner = Pubkey::default();
+    previous_profile.pending_owner_at = 0;
+    previous_profile.last_activity = now;
+
+    emit!(OwnerAccepted {
+        previous_owner,
+        owner,
+        user_profile: user_profile.key(),
+        timestamp: now,
+    });
+
+    msg!("所有权已从 {} 转移到 {}", previous_owner, owner);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::delegation::validate_delegate;
+use crate::errors::ErrorCode;
+use crate::events::DelegateAdded;
+use crate::state::*;
+use crate::validation::MAX_ALLOCATIONS;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64, delegate: Pubkey)]
+pub struct AddDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 同一代理人只能有一个授权，修改授权需先撤销
+    #[account(
+        init,
+        payer = owner,
+        space = 8
+            + std::mem::size_of::<StrategyDelegate>()
+            + 4
+            + std::mem::size_of::<u16>() * MAX_ALLOCATIONS,
+        seeds = [
+            b"delegate",
+            strategy_config.key().as_ref(),
+            delegate.as_ref()
+        ],
+        bump
+    )]
+    pub strategy_delegate: Account<'info, StrategyDelegate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(
+    ctx: Context<AddDelegate>,
+    strategy_id: u64,
+    delegate: Pubkey,
+    scope: DelegateScope,
+    expires_at: i64,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let clock = Clock::get()?;
+
+    // 验证授权范围和到期时间
+    validate_delegate(&owner, &delegate, &scope, expires_at, clock.unix_timestamp)?;
+
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    strategy_config.delegates = strategy_config
+        .delegates
+        .checked_add(1)
+        .ok_or(ErrorCode::MathError)?;
+
+    let strategy_delegate = &mut ctx.accounts.strategy_delegate;
+    strategy_delegate.owner = owner;
+    strategy_delegate.strategy = strategy_config.key();
+    strategy_delegate.delegate = delegate;
+    strategy_delegate.scope = scope;
+    strategy_delegate.expires_at = expires_at;
+    // 以授权时的权重为基准限制代理人的累计调整
+    strategy_delegate.baseline_weights_bps = strategy_config
+        .allocations
+        .iter()
+        .map(|allocation| allocation.target_weight_bps)
+        .collect();
+    strategy_delegate.bump = ctx.bumps.strategy_delegate;
+
+    emit!(DelegateAdded {
+        owner,
+        strategy: strategy_delegate.strategy,
+        strategy_id,
+        delegate,
+        scope,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "策略代理人已添加，策略ID: {}，代理人: {}",
+        strategy_id,
+        delegate
+    );
+
+    Ok(())
+}
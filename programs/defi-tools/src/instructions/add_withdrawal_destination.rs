@@ -0,0 +1,81 @@
+use crate::allowlist::validate_destination;
+use crate::errors::ErrorCode;
+use crate::events::WithdrawalDestinationAdded;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(destination: Pubkey)]
+pub struct AddWithdrawalDestination<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<WithdrawalDestination>(),
+        seeds = [
+            b"destination",
+            owner.key().as_ref(),
+            destination.as_ref()
+        ],
+        bump
+    )]
+    pub withdrawal_destination: Account<'info, WithdrawalDestination>,
+
+    // 用于拒绝把资金库本身加入白名单
+    #[account(
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(ctx: Context<AddWithdrawalDestination>, destination: Pubkey) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let clock = Clock::get()?;
+
+    // 验证接收地址
+    validate_destination(&owner, &ctx.accounts.vault.key(), &destination)?;
+
+    // 新地址在延迟之后才能接收提款
+    let activates_at = clock
+        .unix_timestamp
+        .checked_add(i64::from(ctx.accounts.config.allowlist_delay_seconds))
+        .ok_or(ErrorCode::MathError)?;
+
+    let entry = &mut ctx.accounts.withdrawal_destination;
+    entry.owner = owner;
+    entry.destination = destination;
+    entry.added_at = clock.unix_timestamp;
+    entry.activates_at = activates_at;
+    entry.bump = ctx.bumps.withdrawal_destination;
+
+    emit!(WithdrawalDestinationAdded {
+        owner,
+        destination,
+        activates_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "提款白名单地址已添加: {}，生效时间: {}",
+        destination,
+        activates_at
+    );
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::errors::ErrorCode;
+use crate::events::DcaCancelled;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct CancelDca<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 已转移的权重保留在策略中
+    #[account(
+        mut,
+        seeds = [b"dca", strategy_config.key().as_ref()],
+        bump = dca_schedule.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub dca_schedule: Account<'info, DcaSchedule>,
+}
+
+pub fn process(ctx: Context<CancelDca>, strategy_id: u64) -> Result<()> {
+    ctx.accounts.strategy_config.mode = StrategyMode::Manual;
+    let schedule = &ctx.accounts.dca_schedule;
+
+    emit!(DcaCancelled {
+        owner: schedule.owner,
+        strategy: schedule.strategy,
+        strategy_id,
+        purchased_lamports: schedule.purchased_lamports,
+        purchases: schedule.purchases,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("策略 {} 已取消定投计划", strategy_id);
+
+    Ok(())
+}
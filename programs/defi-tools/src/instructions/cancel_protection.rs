@@ -0,0 +1,55 @@
+use crate::errors::ErrorCode;
+use crate::events::ProtectionCancelled;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct CancelProtection<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"protect", strategy_config.key().as_ref()],
+        bump = protective_order.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub protective_order: Account<'info, ProtectiveOrder>,
+}
+
+pub fn process(ctx: Context<CancelProtection>, strategy_id: u64) -> Result<()> {
+    let order = &ctx.accounts.protective_order;
+    ctx.accounts.strategy_config.peak_value_lamports = 0;
+
+    emit!(ProtectionCancelled {
+        owner: order.owner,
+        strategy: order.strategy,
+        strategy_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("策略 {} 已取消保护单", strategy_id);
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use crate::errors::ErrorCode;
+use crate::events::WithdrawalCancelled;
+use crate::state::*;
+use crate::vault::collect_fees;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64, ticket_id: u64)]
+pub struct CancelWithdrawal<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"ticket",
+            strategy_config.key().as_ref(),
+            &ticket_id.to_le_bytes()
+        ],
+        bump = withdrawal_ticket.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub withdrawal_ticket: Account<'info, WithdrawalTicket>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(ctx: Context<CancelWithdrawal>, strategy_id: u64, ticket_id: u64) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let amount = ctx.accounts.withdrawal_ticket.amount_lamports;
+    let clock = Clock::get()?;
+
+    // 按退回前的价值结算费用
+    collect_fees(
+        &ctx.accounts.config.fees,
+        user_profile,
+        strategy_config,
+        &ctx.accounts.vault,
+        &ctx.accounts.treasury,
+        &ctx.accounts.system_program,
+        clock.unix_timestamp,
+    )?;
+
+    // 退回的金额与存款一样受策略和用户上限约束，否则可以先申请提款、再存满、最后取消来绕过上限。
+    // 资产存款记录在领取工单时才扣回，申请后仍计入资产上限，这里无需恢复
+    ctx.accounts.config.deposit_limits.check_caps(
+        amount,
+        strategy_config.total_value_lamports,
+        user_profile.total_value_lamports,
+    )?;
+
+    // 释放锁定金额并退回策略，资金一直留在资金库，无需转账
+    strategy_config.withdrawal_queue.cancel(ticket_id, amount)?;
+    user_profile.total_value_lamports = user_profile
+        .total_value_lamports
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathError)?;
+    strategy_config.total_value_lamports = strategy_config
+        .total_value_lamports
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathError)?;
+    user_profile.last_activity = clock.unix_timestamp;
+
+    emit!(WithdrawalCancelled {
+        owner: user_profile.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        ticket_id,
+        amount,
+        strategy_value_lamports: strategy_config.total_value_lamports,
+        user_value_lamports: user_profile.total_value_lamports,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "提款工单已取消，策略ID: {}，工单ID: {}，金额: {}",
+        strategy_id,
+        ticket_id,
+        amount
+    );
+
+    Ok(())
+}
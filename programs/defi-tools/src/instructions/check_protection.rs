@@ -0,0 +1,106 @@
+use crate::dca::unit_price;
+use crate::errors::ErrorCode;
+use crate::events::{ProtectionChecked, ProtectionTriggered};
+use crate::protection::{check_triggers, price_marks, revalue};
+use crate::registry::load_asset_prices;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CheckProtection<'info> {
+    // 任何人都可以重新估值，例如 keeper
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protect", strategy_config.key().as_ref()],
+        bump = protective_order.bump,
+        has_one = owner @ ErrorCode::Unauthorized
+    )]
+    pub protective_order: Account<'info, ProtectiveOrder>,
+
+    // 触发后保护单关闭，租金退还给所有者
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = strategy_config.owner == protective_order.owner @ ErrorCode::Unauthorized,
+        constraint = !strategy_config.is_paused() @ ErrorCode::StrategyPaused
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    // remaining_accounts: 按当前分配项顺序依次提供 [资产注册表, 主价格源, (备用价格源)]
+}
+
+pub fn process<'info>(ctx: Context<'_, '_, 'info, 'info, CheckProtection<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let order = &mut ctx.accounts.protective_order;
+
+    let assets = load_asset_prices(
+        &ctx.accounts.config.oracle_guard,
+        &strategy_config.allocations,
+        ctx.remaining_accounts,
+        now,
+    )?;
+    let prices = assets
+        .iter()
+        .map(|asset| unit_price(&asset.price, asset.registry.decimals))
+        .collect::<Result<Vec<_>>>()?;
+    let valuation = revalue(
+        order,
+        &strategy_config.allocations,
+        &prices,
+        strategy_config.total_value_lamports,
+        strategy_config.peak_value_lamports,
+    )?;
+    strategy_config.peak_value_lamports = valuation.peak_value_lamports;
+
+    let Some(trigger) = check_triggers(&order.params, &valuation) else {
+        order.marked_value_lamports = valuation.value_lamports;
+        order.marked_principal_lamports = strategy_config.total_value_lamports;
+        order.marks = price_marks(&strategy_config.allocations, &prices);
+        order.marked_at = now;
+
+        emit!(ProtectionChecked {
+            owner: order.owner,
+            strategy: strategy_config.key(),
+            strategy_id: strategy_config.strategy_id,
+            value_lamports: valuation.value_lamports,
+            peak_value_lamports: valuation.peak_value_lamports,
+            timestamp: now,
+        });
+        return Ok(());
+    };
+
+    let unwound = std::mem::replace(
+        &mut strategy_config.allocations,
+        vec![order.params.safe_allocation()],
+    );
+    strategy_config.allocations_updated_at = now;
+    strategy_config.paused_at = now;
+    strategy_config.unwind_pending = true;
+
+    emit!(ProtectionTriggered {
+        owner: order.owner,
+        strategy: strategy_config.key(),
+        strategy_id: strategy_config.strategy_id,
+        trigger,
+        value_lamports: valuation.value_lamports,
+        peak_value_lamports: valuation.peak_value_lamports,
+        unwound,
+        allocations: strategy_config.allocations.clone(),
+        timestamp: now,
+    });
+
+    msg!(
+        "策略 {} 的保护单已触发: {:?}",
+        strategy_config.strategy_id,
+        trigger
+    );
+
+    order.close(ctx.accounts.owner.to_account_info())
+}
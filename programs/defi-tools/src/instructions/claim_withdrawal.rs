@@ -0,0 +1,158 @@
+use crate::allowlist::check_destination;
+use crate::errors::ErrorCode;
+use crate::events::WithdrawalClaimed;
+use crate::limits::release_asset_shares;
+use crate::spending::spend;
+use crate::state::*;
+use crate::vault::transfer_from_vault;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64, ticket_id: u64)]
+pub struct ClaimWithdrawal<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"ticket",
+            strategy_config.key().as_ref(),
+            &ticket_id.to_le_bytes()
+        ],
+        bump = withdrawal_ticket.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub withdrawal_ticket: Account<'info, WithdrawalTicket>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    // 提款接收地址，启用白名单时必须是所有者本人或已生效的白名单地址
+    #[account(
+        mut,
+        constraint = destination.key() != vault.key() @ ErrorCode::WithdrawalDestinationNotAllowed
+    )]
+    pub destination: SystemAccount<'info>,
+
+    // 接收地址的白名单账户，接收地址为所有者本人或白名单未启用时不需要
+    #[account(
+        seeds = [
+            b"destination",
+            owner.key().as_ref(),
+            destination.key().as_ref()
+        ],
+        bump = withdrawal_destination.bump
+    )]
+    pub withdrawal_destination: Option<Account<'info, WithdrawalDestination>>,
+
+    // 监护人，联署的提款不受周期限额限制
+    pub guardian: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: 先按策略存款记录顺序提供可写的资产注册表，
+    // 再按ID顺序提供队首到该工单之间已取消工单的地址
+}
+
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimWithdrawal<'info>>,
+    strategy_id: u64,
+    ticket_id: u64,
+) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let ticket = &ctx.accounts.withdrawal_ticket;
+    let amount = ticket.amount_lamports;
+    let clock = Clock::get()?;
+
+    // 头寸已解除或锁定时间已到，且之前的工单都已领取或取消
+    if !ticket.is_claimable(&strategy_config.withdrawal_queue, clock.unix_timestamp) {
+        return Err(ErrorCode::WithdrawalNotReady.into());
+    }
+
+    // 验证提款接收地址
+    let destination = ctx.accounts.destination.key();
+    check_destination(
+        user_profile,
+        &destination,
+        ctx.accounts.withdrawal_destination.as_deref(),
+        clock.unix_timestamp,
+    )?;
+
+    // 计入周期提款限额
+    let guardian = ctx.accounts.guardian.as_ref().map(|signer| signer.key());
+    spend(
+        &mut user_profile.spending_limit,
+        amount,
+        guardian.as_ref(),
+        clock.unix_timestamp,
+    )?;
+    let split = ctx
+        .remaining_accounts
+        .len()
+        .min(strategy_config.asset_deposits.len());
+    let (asset_accounts, skipped) = ctx.remaining_accounts.split_at(split);
+    strategy_config
+        .withdrawal_queue
+        .check_order(&strategy_config.key(), ticket_id, skipped)?;
+    // 头寸在领取时才离开资产，按领取金额占比扣回策略计入各资产的存款
+    let released = strategy_config.release_asset_deposits(amount)?;
+    release_asset_shares(&released, asset_accounts)?;
+    strategy_config.withdrawal_queue.claim(ticket_id, amount)?;
+
+    user_profile.last_activity = clock.unix_timestamp;
+
+    // 由资金库PDA签名，将锁定的资金转入接收地址，工单租金在指令结束时退回所有者
+    transfer_from_vault(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault,
+        ctx.accounts.destination.to_account_info(),
+        user_profile,
+        amount,
+    )?;
+
+    emit!(WithdrawalClaimed {
+        owner: user_profile.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        ticket_id,
+        amount,
+        destination,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "提款工单已领取，策略ID: {}，工单ID: {}，金额: {}",
+        strategy_id,
+        ticket_id,
+        amount
+    );
+
+    Ok(())
+}
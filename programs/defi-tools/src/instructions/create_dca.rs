@@ -0,0 +1,76 @@
+use crate::dca::{check_dca_interval, validate_dca};
+use crate::errors::ErrorCode;
+use crate::events::DcaCreated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct CreateDca<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 每个策略只能有一个定投计划，修改参数需先取消
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<DcaSchedule>(),
+        seeds = [b"dca", strategy_config.key().as_ref()],
+        bump
+    )]
+    pub dca_schedule: Account<'info, DcaSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(ctx: Context<CreateDca>, strategy_id: u64, params: DcaParams) -> Result<()> {
+    validate_dca(&params, &ctx.accounts.strategy_config.allocations)?;
+    check_dca_interval(&ctx.accounts.strategy_config.rebalance_condition)?;
+    ctx.accounts.strategy_config.enter_mode(StrategyMode::Dca)?;
+
+    let owner = ctx.accounts.owner.key();
+    let strategy = ctx.accounts.strategy_config.key();
+    let clock = Clock::get()?;
+    ctx.accounts.dca_schedule.set_inner(DcaSchedule {
+        owner,
+        strategy,
+        params,
+        created_at: clock.unix_timestamp,
+        purchased_lamports: 0,
+        purchases: 0,
+        carried_lamports: 0,
+        bump: ctx.bumps.dca_schedule,
+    });
+
+    emit!(DcaCreated {
+        owner,
+        strategy,
+        strategy_id,
+        params,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("策略 {} 已创建定投计划", strategy_id);
+
+    Ok(())
+}
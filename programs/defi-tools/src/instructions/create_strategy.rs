@@ -1,5 +1,8 @@
 use crate::errors::ErrorCode;
+use crate::events::StrategyCreated;
+use crate::limits::MAX_ASSET_DEPOSITS;
 use crate::state::*;
+use crate::validation::{validate_allocation_assets, validate_strategy_params, MAX_ALLOCATIONS};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -11,7 +14,8 @@ pub struct CreateStrategy<'info> {
         mut,
         seeds = [b"user", owner.key().as_ref()],
         bump,
-        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
     )]
     pub user_profile: Account<'info, UserProfile>,
 
@@ -19,7 +23,8 @@ pub struct CreateStrategy<'info> {
         init,
         payer = owner,
         space = 8 + std::mem::size_of::<StrategyConfig>() + 
-                4 + (std::mem::size_of::<Allocation>() * 10), // 最多10个分配项
+                4 + (std::mem::size_of::<Allocation>() * MAX_ALLOCATIONS) +
+                4 + (std::mem::size_of::<AssetDeposit>() * MAX_ASSET_DEPOSITS),
         seeds = [
             b"strategy", 
             user_profile.key().as_ref(),
@@ -30,48 +35,71 @@ pub struct CreateStrategy<'info> {
     pub strategy_config: Account<'info, StrategyConfig>,
 
     pub system_program: Program<'info, System>,
+    // remaining_accounts: 每个分配项按顺序提供对应的资产注册表账户
 }
 
-pub fn process(
-    ctx: Context<CreateStrategy>,
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateStrategy<'info>>,
     allocations: Vec<Allocation>,
     rebalance_condition: RebalanceCondition,
     max_slippage_bps: u16,
 ) -> Result<()> {
-    // 验证资产分配
-    let mut total_weight = 0;
-    for allocation in allocations.iter() {
-        total_weight += allocation.target_weight_bps;
-    }
-
-    // 确保总权重为10000基点 (100%)
-    if total_weight != 10000 {
-        return Err(ErrorCode::InvalidAllocation.into());
-    }
-
-    // 验证滑点设置
-    if max_slippage_bps > 1000 {
-        // 最大10%
-        return Err(ErrorCode::InvalidSlippage.into());
-    }
+    // 验证分配项、滑点以及每个分配项对应的资产
+    validate_strategy_params(&allocations, max_slippage_bps)?;
+    validate_allocation_assets(&allocations, ctx.remaining_accounts)?;
+
+    let owner = ctx.accounts.owner.key();
+    initialize_strategy(
+        &mut ctx.accounts.user_profile,
+        &mut ctx.accounts.strategy_config,
+        owner,
+        allocations,
+        rebalance_condition,
+        max_slippage_bps,
+    )
+}
 
-    let user_profile = &mut ctx.accounts.user_profile;
-    let strategy_config = &mut ctx.accounts.strategy_config;
+// 设置新策略并递增用户的策略计数器，按模板创建策略时共用
+pub(crate) fn initialize_strategy(
+    user_profile: &mut UserProfile,
+    strategy_config: &mut Account<StrategyConfig>,
+    owner: Pubkey,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Result<()> {
     let clock = Clock::get()?;
 
     // 设置策略配置
-    strategy_config.owner = ctx.accounts.owner.key();
+    strategy_config.owner = owner;
     strategy_config.strategy_id = user_profile.strategy_counter;
     strategy_config.allocations = allocations;
     strategy_config.rebalance_condition = rebalance_condition;
     strategy_config.created_at = clock.unix_timestamp;
     strategy_config.last_executed_at = 0;
     strategy_config.max_slippage_bps = max_slippage_bps;
+    strategy_config.total_value_lamports = 0;
+    strategy_config.fees_accrued_at = clock.unix_timestamp;
+    strategy_config.withdrawal_queue = WithdrawalQueue::default();
+    strategy_config.allocations_updated_at = clock.unix_timestamp;
 
     // 增加用户的策略计数器
-    user_profile.strategy_counter += 1;
+    user_profile.strategy_counter = user_profile
+        .strategy_counter
+        .checked_add(1)
+        .ok_or(ErrorCode::MathError)?;
     user_profile.last_activity = clock.unix_timestamp;
 
+    emit!(StrategyCreated {
+        owner: strategy_config.owner,
+        strategy: strategy_config.key(),
+        strategy_id: strategy_config.strategy_id,
+        allocations: strategy_config.allocations.clone(),
+        rebalance_condition: strategy_config.rebalance_condition.clone(),
+        max_slippage_bps,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("策略已创建，ID: {}", strategy_config.strategy_id);
 
     Ok(())
@@ -0,0 +1,90 @@
+use crate::errors::ErrorCode;
+use crate::events::StrategyCreatedFromTemplate;
+use crate::instructions::create_strategy::initialize_strategy;
+use crate::limits::MAX_ASSET_DEPOSITS;
+use crate::state::*;
+use crate::templates::{check_template_risk, derive_template_risk};
+use crate::validation::MAX_ALLOCATIONS;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateStrategyFromTemplate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"template",
+            strategy_template.publisher.as_ref(),
+            &strategy_template.template_id.to_le_bytes()
+        ],
+        bump = strategy_template.bump
+    )]
+    pub strategy_template: Account<'info, StrategyTemplate>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<StrategyConfig>() +
+                4 + (std::mem::size_of::<Allocation>() * MAX_ALLOCATIONS) +
+                4 + (std::mem::size_of::<AssetDeposit>() * MAX_ASSET_DEPOSITS),
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &user_profile.strategy_counter.to_le_bytes()
+        ],
+        bump
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: 模板的每个分配项按顺序提供对应的资产注册表账户
+}
+
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateStrategyFromTemplate<'info>>,
+) -> Result<()> {
+    let template = &ctx.accounts.strategy_template;
+    // 发布后资产可能已停用或调低了权重上限，重新校验并计算风险等级
+    let risk_level = derive_template_risk(&template.allocations, ctx.remaining_accounts)?;
+    check_template_risk(template, risk_level, &ctx.accounts.user_profile)?;
+
+    let owner = ctx.accounts.owner.key();
+    initialize_strategy(
+        &mut ctx.accounts.user_profile,
+        &mut ctx.accounts.strategy_config,
+        owner,
+        template.allocations.clone(),
+        template.rebalance_condition.clone(),
+        template.max_slippage_bps,
+    )?;
+
+    let template = &mut ctx.accounts.strategy_template;
+    template.strategies_created = template
+        .strategies_created
+        .checked_add(1)
+        .ok_or(ErrorCode::MathError)?;
+
+    let strategy_config = &ctx.accounts.strategy_config;
+    emit!(StrategyCreatedFromTemplate {
+        owner,
+        strategy: strategy_config.key(),
+        strategy_id: strategy_config.strategy_id,
+        template: template.key(),
+        publisher: template.publisher,
+        template_id: template.template_id,
+        timestamp: strategy_config.created_at,
+    });
+
+    Ok(())
+}
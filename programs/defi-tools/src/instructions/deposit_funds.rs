@@ -1,6 +1,10 @@
 use crate::errors::ErrorCode;
+use crate::events::FundsDeposited;
+use crate::limits::load_asset_shares;
 use crate::state::*;
+use crate::vault::collect_fees;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 
 #[derive(Accounts)]
 #[instruction(strategy_id: u64)]
@@ -13,7 +17,8 @@ pub struct DepositFunds<'info> {
         seeds = [b"user", owner.key().as_ref()],
         bump,
         constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
-        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused
+        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
     )]
     pub user_profile: Account<'info, UserProfile>,
 
@@ -25,16 +30,36 @@ pub struct DepositFunds<'info> {
             &strategy_id.to_le_bytes()
         ],
         bump,
-        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !strategy_config.is_paused() @ ErrorCode::StrategyPaused
     )]
     pub strategy_config: Account<'info, StrategyConfig>,
 
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn process(ctx: Context<DepositFunds>, strategy_id: u64, amount: u64) -> Result<()> {
+// remaining_accounts 为按分配项顺序排列的可写资产注册表，用于检查和累计资产存款上限
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositFunds<'info>>,
+    strategy_id: u64,
+    amount: u64,
+) -> Result<()> {
     let user_profile = &mut ctx.accounts.user_profile;
     let strategy_config = &mut ctx.accounts.strategy_config;
+    let limits = ctx.accounts.config.deposit_limits;
     let clock = Clock::get()?;
 
     // 验证存款金额
@@ -45,13 +70,61 @@ pub fn process(ctx: Context<DepositFunds>, strategy_id: u64, amount: u64) -> Res
     // 更新用户活动时间
     user_profile.last_activity = clock.unix_timestamp;
 
+    // 按存款前的价值结算费用
+    collect_fees(
+        &ctx.accounts.config.fees,
+        user_profile,
+        strategy_config,
+        &ctx.accounts.vault,
+        &ctx.accounts.treasury,
+        &ctx.accounts.system_program,
+        clock.unix_timestamp,
+    )?;
+
+    // 按扣费后的价值检查策略和用户上限，再按目标权重检查并累计资产存款
+    limits.check(
+        amount,
+        strategy_config.total_value_lamports,
+        user_profile.total_value_lamports,
+    )?;
+    let assets = load_asset_shares(&strategy_config.allocations, ctx.remaining_accounts, amount)?;
+    for (mut registry, share) in assets {
+        registry.record_deposit(share)?;
+        strategy_config.record_asset_deposit(registry.asset_id, share)?;
+        registry.exit(&crate::ID)?;
+    }
+
     // 更新总资产价值
     user_profile.total_value_lamports = user_profile
         .total_value_lamports
         .checked_add(amount)
         .ok_or(ErrorCode::MathError)?;
+    strategy_config.total_value_lamports = strategy_config
+        .total_value_lamports
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathError)?;
 
-    // 在实际应用中，这里会调用资金转移和存款的逻辑
+    // 将存款从用户钱包转入资金库
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    emit!(FundsDeposited {
+        owner: user_profile.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        amount,
+        strategy_value_lamports: strategy_config.total_value_lamports,
+        user_value_lamports: user_profile.total_value_lamports,
+        timestamp: clock.unix_timestamp,
+    });
 
     msg!("向策略存入资金，ID: {}，金额: {}", strategy_id, amount);
 
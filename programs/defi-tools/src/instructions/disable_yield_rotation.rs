@@ -0,0 +1,57 @@
+use crate::errors::ErrorCode;
+use crate::events::YieldRotationDisabled;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct DisableYieldRotation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 轮动头寸保留在最后所在的协议
+    #[account(
+        mut,
+        seeds = [b"yield", strategy_config.key().as_ref()],
+        bump = yield_rotation.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub yield_rotation: Account<'info, YieldRotation>,
+}
+
+pub fn process(ctx: Context<DisableYieldRotation>, strategy_id: u64) -> Result<()> {
+    ctx.accounts.strategy_config.mode = StrategyMode::Manual;
+    let rotation = &ctx.accounts.yield_rotation;
+
+    emit!(YieldRotationDisabled {
+        owner: rotation.owner,
+        strategy: rotation.strategy,
+        strategy_id,
+        rotations: rotation.rotations,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("策略 {} 已停用收益率轮动", strategy_id);
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::apy::{validate_yield, yield_position};
+use crate::errors::ErrorCode;
+use crate::events::YieldRotationEnabled;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct EnableYieldRotation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 每个策略只能有一个轮动计划，修改参数需先停用
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<YieldRotation>(),
+        seeds = [b"yield", strategy_config.key().as_ref()],
+        bump
+    )]
+    pub yield_rotation: Account<'info, YieldRotation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(
+    ctx: Context<EnableYieldRotation>,
+    strategy_id: u64,
+    params: YieldParams,
+) -> Result<()> {
+    validate_yield(&params)?;
+    let allocations = &ctx.accounts.strategy_config.allocations;
+    let protocol = allocations[yield_position(&params, allocations)?].protocol;
+    ctx.accounts
+        .strategy_config
+        .enter_mode(StrategyMode::YieldRotation)?;
+
+    let owner = ctx.accounts.owner.key();
+    let strategy = ctx.accounts.strategy_config.key();
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.yield_rotation.set_inner(YieldRotation {
+        owner,
+        strategy,
+        params,
+        held_since: now,
+        rotations: 0,
+        created_at: now,
+        bump: ctx.bumps.yield_rotation,
+    });
+
+    emit!(YieldRotationEnabled {
+        owner,
+        strategy,
+        strategy_id,
+        params,
+        protocol,
+        timestamp: now,
+    });
+
+    msg!("策略 {} 已启用收益率轮动", strategy_id);
+
+    Ok(())
+}
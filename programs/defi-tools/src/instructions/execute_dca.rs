@@ -0,0 +1,137 @@
+use crate::dca::{
+    check_dca_interval, check_price_limit, max_fill_price, plan_purchase, unit_price,
+};
+use crate::errors::ErrorCode;
+use crate::events::DcaExecuted;
+use crate::registry::load_asset_prices;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteDca<'info> {
+    // 任何人都可以执行到期的定投，例如 keeper
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dca", strategy_config.key().as_ref()],
+        bump = dca_schedule.bump
+    )]
+    pub dca_schedule: Account<'info, DcaSchedule>,
+
+    #[account(
+        seeds = [b"user", dca_schedule.owner.as_ref()],
+        bump,
+        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        constraint = strategy_config.owner == dca_schedule.owner @ ErrorCode::Unauthorized,
+        constraint = !strategy_config.is_paused() @ ErrorCode::StrategyPaused
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    // remaining_accounts: 报价资产和目标资产依次提供 [资产注册表, 主价格源, (备用价格源)]
+}
+
+// 与执行和再平衡共用再平衡条件的时间间隔和 last_executed_at
+pub fn process<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteDca<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let schedule = &mut ctx.accounts.dca_schedule;
+    let params = schedule.params;
+
+    // 与创建时相同的校验，间隔为0时不执行
+    check_dca_interval(&strategy_config.rebalance_condition)?;
+    if !strategy_config
+        .rebalance_condition
+        .interval_elapsed(strategy_config.last_executed_at, now)
+    {
+        return Err(ErrorCode::RebalanceConditionNotMet.into());
+    }
+
+    let purchase = plan_purchase(
+        &params,
+        schedule.carried_lamports,
+        &strategy_config.allocations,
+        strategy_config.total_value_lamports,
+    )?;
+    schedule.carried_lamports = purchase.carried_lamports;
+    // 累计增量不足1基点时只消耗本次间隔，不调整分配项
+    if purchase.amount_lamports == 0 {
+        strategy_config.last_executed_at = now;
+        msg!(
+            "策略 {} 的定投增量不足1基点，累计 {} lamports 留到下次执行",
+            strategy_config.strategy_id,
+            purchase.carried_lamports
+        );
+        return Ok(());
+    }
+    let target = purchase
+        .allocations
+        .iter()
+        .find(|allocation| {
+            allocation.protocol == params.target_protocol && allocation.asset == params.target_asset
+        })
+        .cloned()
+        .ok_or(ErrorCode::MathError)?;
+    let quote = Allocation {
+        protocol: params.quote_protocol,
+        asset: params.quote_asset,
+        target_weight_bps: 0,
+    };
+    let assets = load_asset_prices(
+        &ctx.accounts.config.oracle_guard,
+        &[quote, target.clone()],
+        ctx.remaining_accounts,
+        now,
+    )?;
+    // 目标资产可能已被禁用或降低了权重上限
+    assets[1].registry.check_allocation(&target)?;
+
+    let price_quote = unit_price(&assets[1].price, assets[1].registry.decimals)?;
+    check_price_limit(price_quote, params.max_price_quote)?;
+
+    strategy_config.allocations = purchase.allocations;
+    strategy_config.allocations_updated_at = now;
+    strategy_config.last_executed_at = now;
+    schedule.purchased_lamports = schedule
+        .purchased_lamports
+        .checked_add(purchase.amount_lamports)
+        .ok_or(ErrorCode::MathError)?;
+    schedule.purchases = schedule
+        .purchases
+        .checked_add(1)
+        .ok_or(ErrorCode::MathError)?;
+
+    emit!(DcaExecuted {
+        owner: schedule.owner,
+        strategy: strategy_config.key(),
+        strategy_id: strategy_config.strategy_id,
+        quote_asset: params.quote_asset,
+        target_asset: params.target_asset,
+        amount_lamports: purchase.amount_lamports,
+        price_quote,
+        max_fill_price_quote: max_fill_price(
+            price_quote,
+            params.max_price_quote,
+            strategy_config.max_slippage_bps,
+        ),
+        allocations: strategy_config.allocations.clone(),
+        completed: purchase.completed,
+        timestamp: now,
+    });
+
+    msg!(
+        "策略 {} 已定投 {} lamports",
+        strategy_config.strategy_id,
+        purchase.amount_lamports
+    );
+
+    Ok(())
+}
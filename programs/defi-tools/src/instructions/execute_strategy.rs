@@ -1,12 +1,17 @@
+use crate::delegation::{authorize, DelegateAction};
 use crate::errors::ErrorCode;
+use crate::events::StrategyExecuted;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 #[instruction(strategy_id: u64)]
 pub struct ExecuteStrategy<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    // 策略所有者或其代理人
+    pub authority: Signer<'info>,
+
+    // 策略所有者，用于推导用户配置PDA
+    pub owner: SystemAccount<'info>,
 
     #[account(
         mut,
@@ -25,10 +30,22 @@ pub struct ExecuteStrategy<'info> {
             &strategy_id.to_le_bytes()
         ],
         bump,
-        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !strategy_config.is_paused() @ ErrorCode::StrategyPaused
     )]
     pub strategy_config: Account<'info, StrategyConfig>,
 
+    // 代理人签名时提供，所有者签名时省略
+    #[account(
+        seeds = [
+            b"delegate",
+            strategy_config.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = delegate.bump
+    )]
+    pub delegate: Option<Account<'info, StrategyDelegate>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -37,6 +54,14 @@ pub fn process(ctx: Context<ExecuteStrategy>, strategy_id: u64) -> Result<()> {
     let strategy_config = &mut ctx.accounts.strategy_config;
     let clock = Clock::get()?;
 
+    authorize(
+        &strategy_config.owner,
+        &ctx.accounts.authority.key(),
+        ctx.accounts.delegate.as_deref(),
+        DelegateAction::Execute,
+        clock.unix_timestamp,
+    )?;
+
     // 更新最后执行时间
     strategy_config.last_executed_at = clock.unix_timestamp;
     user_profile.last_activity = clock.unix_timestamp;
@@ -44,6 +69,13 @@ pub fn process(ctx: Context<ExecuteStrategy>, strategy_id: u64) -> Result<()> {
     // 这里是实际执行策略的逻辑
     // 在实际应用中，这里会调用各种DeFi协议的交互逻辑
 
+    emit!(StrategyExecuted {
+        owner: strategy_config.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("策略已执行，ID: {}", strategy_id);
 
     Ok(())
@@ -0,0 +1,103 @@
+use crate::errors::ErrorCode;
+use crate::events::StrategyFollowed;
+use crate::following::validate_follow;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct FollowStrategy<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 任意用户的策略都可以作为领投策略
+    pub leader_strategy: Account<'info, StrategyConfig>,
+
+    // 每个策略只能跟随一个领投策略，更换需先取消跟随
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<StrategyFollow>(),
+        seeds = [b"follow", strategy_config.key().as_ref()],
+        bump
+    )]
+    pub strategy_follow: Account<'info, StrategyFollow>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(
+    ctx: Context<FollowStrategy>,
+    strategy_id: u64,
+    max_weight_bps: u16,
+    delay_seconds: u32,
+    leader_fee_bps: u16,
+) -> Result<()> {
+    let strategy = ctx.accounts.strategy_config.key();
+    let leader_strategy = &ctx.accounts.leader_strategy;
+    validate_follow(
+        &strategy,
+        &leader_strategy.key(),
+        max_weight_bps,
+        delay_seconds,
+        leader_fee_bps,
+    )?;
+    // 以原始地址记录领投策略，领投策略迁移后跟投关系继续有效
+    let leader = leader_strategy.origin_or(leader_strategy.key());
+    ctx.accounts
+        .strategy_config
+        .enter_mode(StrategyMode::Following)?;
+
+    let owner = ctx.accounts.owner.key();
+    let clock = Clock::get()?;
+    // 领投策略当前的分配项也需要同步一次
+    ctx.accounts.strategy_follow.set_inner(StrategyFollow {
+        owner,
+        strategy,
+        leader,
+        max_weight_bps,
+        delay_seconds,
+        leader_fee_bps,
+        followed_at: clock.unix_timestamp,
+        leader_synced_at: 0,
+        synced_at: 0,
+        syncs: 0,
+        bump: ctx.bumps.strategy_follow,
+    });
+
+    emit!(StrategyFollowed {
+        owner,
+        strategy,
+        strategy_id,
+        leader,
+        leader_owner: ctx.accounts.leader_strategy.owner,
+        max_weight_bps,
+        delay_seconds,
+        leader_fee_bps,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("策略 {} 开始跟随领投策略: {}", strategy_id, leader);
+
+    Ok(())
+}
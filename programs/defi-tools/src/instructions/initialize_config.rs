@@ -0,0 +1,65 @@
+use crate::allowlist::DEFAULT_ALLOWLIST_DELAY_SECONDS;
+use crate::errors::ErrorCode;
+use crate::events::ConfigInitialized;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    // 只有程序的升级权限才能初始化，防止任何人抢先初始化成为管理员
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<GlobalConfig>(),
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(ctx: Context<InitializeConfig>, oracle_guard: OracleGuardConfig) -> Result<()> {
+    // 验证预言机校验参数
+    oracle_guard.validate()?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    // 初始化全局配置
+    config.admin = ctx.accounts.admin.key();
+    config.oracle_guard = oracle_guard;
+    // 默认不收费，费用金库为管理员
+    config.fees = FeeConfig::default();
+    config.treasury = config.admin;
+    // 默认不限制存款
+    config.deposit_limits = DepositLimits::default();
+    // 默认不锁定，提款工单在申请后即可领取
+    config.withdrawal_delay_seconds = 0;
+    // 新增白名单地址默认在24小时后生效
+    config.allowlist_delay_seconds = DEFAULT_ALLOWLIST_DELAY_SECONDS;
+    config.bump = ctx.bumps.config;
+
+    emit!(ConfigInitialized {
+        admin: config.admin,
+        oracle_guard,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("全局配置已初始化，管理员: {}", config.admin);
+
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use crate::errors::ErrorCode;
+use crate::events::UserInitialized;
 use crate::state::*;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 
 #[derive(Accounts)]
 pub struct InitializeUser<'info> {
@@ -16,6 +18,14 @@ pub struct InitializeUser<'info> {
     )]
     pub user_profile: Account<'info, UserProfile>,
 
+    // 托管用户存款的资金库，由系统程序持有、本程序签名
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -25,6 +35,22 @@ pub fn process(ctx: Context<InitializeUser>, risk_level: u8) -> Result<()> {
         return Err(ErrorCode::InvalidRiskLevel.into());
     }
 
+    // 向资金库转入免租金所需的最低余额，之后资金库余额 = 免租金余额 + 记账总额
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let shortfall = rent_exempt.saturating_sub(ctx.accounts.vault.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
     let user_profile = &mut ctx.accounts.user_profile;
     let clock = Clock::get()?;
 
@@ -32,10 +58,24 @@ pub fn process(ctx: Context<InitializeUser>, risk_level: u8) -> Result<()> {
     user_profile.owner = ctx.accounts.owner.key();
     user_profile.risk_level = risk_level;
     user_profile.strategy_counter = 0;
-    user_profile.vault_bump = ctx.bumps.user_profile;
+    user_profile.vault_bump = ctx.bumps.vault;
     user_profile.last_activity = clock.unix_timestamp;
     user_profile.total_value_lamports = 0;
     user_profile.is_paused = false;
+    user_profile.allowlist_enabled = false;
+    user_profile.allowlist_disable_at = 0;
+    user_profile.spending_limit = SpendingLimit::default();
+    user_profile.pending_owner = Pubkey::default();
+    user_profile.pending_owner_at = 0;
+    user_profile.migrated_to = Pubkey::default();
+    user_profile.migrated_from = Pubkey::default();
+
+    emit!(UserInitialized {
+        owner: user_profile.owner,
+        user_profile: user_profile.key(),
+        risk_level,
+        timestamp: clock.unix_timestamp,
+    });
 
     msg!("用户配置文件已初始化，风险等级: {}", risk_level);
 
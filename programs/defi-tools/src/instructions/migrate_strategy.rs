@@ -0,0 +1,142 @@
+use crate::errors::ErrorCode;
+use crate::events::StrategyMigrated;
+use crate::limits::MAX_ASSET_DEPOSITS;
+use crate::ownership::check_migratable;
+use crate::state::*;
+use crate::validation::MAX_ALLOCATIONS;
+use crate::vault::transfer_from_vault;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(previous_owner: Pubkey, strategy_id: u64)]
+pub struct MigrateStrategy<'info> {
+    // 已接受转移的新所有者
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", previous_owner.as_ref()],
+        bump,
+        constraint = previous_profile.migrated_to == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub previous_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    // 原策略账户，迁移后关闭，租金退回新所有者
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"strategy",
+            previous_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub previous_strategy: Account<'info, StrategyConfig>,
+
+    /// CHECK: 只检查原策略的保护单账户是否存在，不读取数据
+    #[account(seeds = [b"protect", previous_strategy.key().as_ref()], bump)]
+    pub previous_protection: UncheckedAccount<'info>,
+
+    // 新所有者名下同一ID的策略
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<StrategyConfig>() +
+                4 + (std::mem::size_of::<Allocation>() * MAX_ALLOCATIONS) +
+                4 + (std::mem::size_of::<AssetDeposit>() * MAX_ASSET_DEPOSITS),
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", previous_owner.as_ref()],
+        bump = previous_profile.vault_bump
+    )]
+    pub previous_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// 将一个策略及其记账价值从原资金库转入新资金库。以原策略地址为种子的账户不随策略迁移，
+// 迁移前需全部关闭。跟投关系以领投策略的原始地址标识，迁移后继续有效
+pub fn process(
+    ctx: Context<MigrateStrategy>,
+    previous_owner: Pubkey,
+    strategy_id: u64,
+) -> Result<()> {
+    let previous = &ctx.accounts.previous_strategy;
+    let protected = ctx.accounts.previous_protection.owner == &crate::ID;
+    check_migratable(previous, protected)?;
+
+    let value = previous.total_value_lamports;
+    if value > 0 {
+        transfer_from_vault(
+            &ctx.accounts.system_program,
+            &ctx.accounts.previous_vault,
+            ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.previous_profile,
+            value,
+        )?;
+    }
+
+    let owner = ctx.accounts.owner.key();
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    strategy_config.set_inner(StrategyConfig {
+        owner,
+        allocations: previous.allocations.clone(),
+        rebalance_condition: previous.rebalance_condition.clone(),
+        asset_deposits: previous.asset_deposits.clone(),
+        origin: previous.origin_or(previous.key()),
+        ..**previous
+    });
+
+    let now = Clock::get()?.unix_timestamp;
+    let previous_profile = &mut ctx.accounts.previous_profile;
+    previous_profile.total_value_lamports = previous_profile
+        .total_value_lamports
+        .checked_sub(value)
+        .ok_or(ErrorCode::MathError)?;
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.total_value_lamports = user_profile
+        .total_value_lamports
+        .checked_add(value)
+        .ok_or(ErrorCode::MathError)?;
+    user_profile.last_activity = now;
+
+    emit!(StrategyMigrated {
+        previous_owner,
+        owner,
+        strategy_id,
+        previous_strategy: ctx.accounts.previous_strategy.key(),
+        strategy: strategy_config.key(),
+        value_lamports: value,
+        timestamp: now,
+    });
+
+    msg!("策略 {} 已迁移，转移金额: {} lamports", strategy_id, value);
+
+    Ok(())
+}
@@ -1,7 +1,62 @@
 // instructions/mod.rs
+pub mod accept_owner;
+pub mod add_delegate;
+pub mod add_withdrawal_destination;
+pub mod cancel_dca;
+pub mod cancel_protection;
+pub mod cancel_withdrawal;
+pub mod check_protection;
+pub mod claim_withdrawal;
+pub mod create_dca;
 pub mod create_strategy;
+pub mod create_strategy_from_template;
 pub mod deposit_funds;
+pub mod disable_yield_rotation;
+pub mod enable_yield_rotation;
+pub mod execute_dca;
 pub mod execute_strategy;
+pub mod follow_strategy;
+pub mod initialize_config;
 pub mod initialize_user;
+pub mod migrate_strategy;
+pub mod propose_owner;
+pub mod publish_template;
 pub mod rebalance_positions;
+pub mod register_apy_tracker;
+pub mod register_asset;
+pub mod remove_withdrawal_destination;
+pub mod report_apy;
+pub mod request_withdrawal;
+pub mod resume_strategy;
+pub mod retire_template;
+pub mod revoke_delegate;
+pub mod rotate_yield;
+pub mod set_protection;
+pub mod set_spending_limit;
+pub mod set_withdrawal_allowlist;
+pub mod sync_follower;
+pub mod unfollow_strategy;
+pub mod update_allowlist_delay;
+pub mod update_asset;
+pub mod update_deposit_limits;
+pub mod update_fee_config;
+pub mod update_oracle_guard;
+pub mod update_strategy;
+pub mod update_withdrawal_delay;
 pub mod withdraw_funds;
+
+// 每个指令模块都导出 `process`，这里只需要账户结构及Anchor生成的辅助模块
+#[allow(ambiguous_glob_reexports)]
+pub use {
+    accept_owner::*, add_delegate::*, add_withdrawal_destination::*, cancel_dca::*,
+    cancel_protection::*, cancel_withdrawal::*, check_protection::*, claim_withdrawal::*,
+    create_dca::*, create_strategy::*, create_strategy_from_template::*, deposit_funds::*,
+    disable_yield_rotation::*, enable_yield_rotation::*, execute_dca::*, execute_strategy::*,
+    follow_strategy::*, initialize_config::*, initialize_user::*, migrate_strategy::*,
+    propose_owner::*, publish_template::*, rebalance_positions::*, register_apy_tracker::*,
+    register_asset::*, remove_withdrawal_destination::*, report_apy::*, request_withdrawal::*,
+    resume_strategy::*, retire_template::*, revoke_delegate::*, rotate_yield::*, set_protection::*,
+    set_spending_limit::*, set_withdrawal_allowlist::*, sync_follower::*, unfollow_strategy::*,
+    update_allowlist_delay::*, update_asset::*, update_deposit_limits::*, update_fee_config::*,
+    update_oracle_guard::*, update_strategy::*, update_withdrawal_delay::*, withdraw_funds::*,
+};
@@ -0,0 +1,68 @@
+use crate::errors::ErrorCode;
+use crate::events::OwnerProposed;
+use crate::ownership::{accept_after, validate_new_owner};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    // 监护人，设置了监护人时提名新所有者需要其签名
+    pub guardian: Option<Signer<'info>>,
+}
+
+// 提名新的所有者，重复提名会替换之前的提名；new_owner 为默认值时取消提名
+pub fn process(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    validate_new_owner(&user_profile.owner, &new_owner)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let cancelled = new_owner == Pubkey::default();
+    if !cancelled && user_profile.spending_limit.has_guardian() {
+        let guardian = ctx.accounts.guardian.as_ref().map(|signer| signer.key());
+        if guardian != Some(user_profile.spending_limit.guardian) {
+            return Err(ErrorCode::GuardianApprovalRequired.into());
+        }
+    }
+
+    let accept_at = if cancelled {
+        0
+    } else {
+        accept_after(
+            user_profile,
+            ctx.accounts.config.allowlist_delay_seconds,
+            now,
+        )?
+    };
+    user_profile.pending_owner = new_owner;
+    user_profile.pending_owner_at = accept_at;
+    user_profile.last_activity = now;
+
+    emit!(OwnerProposed {
+        owner: user_profile.owner,
+        new_owner,
+        accept_after: accept_at,
+        timestamp: now,
+    });
+
+    if cancelled {
+        msg!("已取消所有权转移");
+    } else {
+        msg!("已提名新所有者: {}，可接受时间: {}", new_owner, accept_at);
+    }
+
+    Ok(())
+}
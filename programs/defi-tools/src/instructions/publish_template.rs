@@ -0,0 +1,74 @@
+use crate::events::TemplatePublished;
+use crate::state::*;
+use crate::templates::{derive_template_risk, validate_template_risk};
+use crate::validation::{validate_strategy_params, MAX_ALLOCATIONS};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(template_id: u64)]
+pub struct PublishTemplate<'info> {
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+
+    #[account(
+        init,
+        payer = publisher,
+        space = 8 + std::mem::size_of::<StrategyTemplate>() +
+                4 + (std::mem::size_of::<Allocation>() * MAX_ALLOCATIONS),
+        seeds = [
+            b"template",
+            publisher.key().as_ref(),
+            &template_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub strategy_template: Account<'info, StrategyTemplate>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: 每个分配项按顺序提供对应的资产注册表账户
+}
+
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PublishTemplate<'info>>,
+    template_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+    risk_level: u8,
+    description_hash: [u8; 32],
+) -> Result<()> {
+    // 与创建策略相同的校验，使用模板时再按资产的当前状态校验一次
+    validate_strategy_params(&allocations, max_slippage_bps)?;
+    validate_template_risk(risk_level)?;
+    // 发布者声明的风险等级只能高于按分配项计算的等级
+    let risk_level = risk_level.max(derive_template_risk(&allocations, ctx.remaining_accounts)?);
+
+    let clock = Clock::get()?;
+    let template = &mut ctx.accounts.strategy_template;
+    template.publisher = ctx.accounts.publisher.key();
+    template.template_id = template_id;
+    template.allocations = allocations;
+    template.rebalance_condition = rebalance_condition;
+    template.max_slippage_bps = max_slippage_bps;
+    template.risk_level = risk_level;
+    template.description_hash = description_hash;
+    template.created_at = clock.unix_timestamp;
+    template.strategies_created = 0;
+    template.bump = ctx.bumps.strategy_template;
+
+    emit!(TemplatePublished {
+        publisher: template.publisher,
+        template: template.key(),
+        template_id,
+        allocations: template.allocations.clone(),
+        rebalance_condition: template.rebalance_condition.clone(),
+        max_slippage_bps,
+        risk_level,
+        description_hash,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("策略模板已发布，ID: {}", template_id);
+
+    Ok(())
+}
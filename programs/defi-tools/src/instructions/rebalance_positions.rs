@@ -1,12 +1,20 @@
+use crate::delegation::{authorize, DelegateAction};
 use crate::errors::ErrorCode;
+use crate::events::{PositionsRebalanced, RebalanceLeg};
+use crate::planner::target_values;
+use crate::registry::load_asset_prices;
 use crate::state::*;
+use crate::vault::collect_fees;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 #[instruction(strategy_id: u64)]
 pub struct RebalancePositions<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    // 策略所有者或其代理人
+    pub authority: Signer<'info>,
+
+    // 策略所有者，用于推导用户配置和资金库PDA
+    pub owner: SystemAccount<'info>,
 
     #[account(
         mut,
@@ -25,30 +33,122 @@ pub struct RebalancePositions<'info> {
             &strategy_id.to_le_bytes()
         ],
         bump,
-        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized,
+        // 保护单触发后允许暂停的策略按安全头寸再平衡一次
+        constraint = !strategy_config.is_paused() || strategy_config.unwind_pending @ ErrorCode::StrategyPaused
     )]
     pub strategy_config: Account<'info, StrategyConfig>,
 
+    // 代理人签名时提供，所有者签名时省略
+    #[account(
+        seeds = [
+            b"delegate",
+            strategy_config.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = delegate.bump
+    )]
+    pub delegate: Option<Account<'info, StrategyDelegate>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
+    // remaining_accounts: 每个分配项按顺序提供 [资产注册表, 主价格源]，
+    // 启用交叉校验时为 [资产注册表, 主价格源, 备用价格源]
 }
 
-pub fn process(ctx: Context<RebalancePositions>, strategy_id: u64) -> Result<()> {
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RebalancePositions<'info>>,
+    strategy_id: u64,
+) -> Result<()> {
     let user_profile = &mut ctx.accounts.user_profile;
     let strategy_config = &mut ctx.accounts.strategy_config;
+    let oracle_guard = ctx.accounts.config.oracle_guard;
     let clock = Clock::get()?;
 
-    // 验证再平衡条件
-    let time_since_last_execution = clock.unix_timestamp - strategy_config.last_executed_at;
-    if time_since_last_execution < strategy_config.rebalance_condition.time_interval_seconds as i64
+    // 费用只会转入全局配置中的费用金库，代理人无法转走资金
+    authorize(
+        &strategy_config.owner,
+        &ctx.accounts.authority.key(),
+        ctx.accounts.delegate.as_deref(),
+        DelegateAction::Rebalance,
+        clock.unix_timestamp,
+    )?;
+
+    // 验证再平衡条件，保护单触发后的平仓不受时间间隔限制
+    if !strategy_config.unwind_pending
+        && !strategy_config
+            .rebalance_condition
+            .interval_elapsed(strategy_config.last_executed_at, clock.unix_timestamp)
     {
         return Err(ErrorCode::RebalanceConditionNotMet.into());
     }
 
+    // 读取并校验每个分配项的价格，任何一个价格不可用都拒绝再平衡
+    let assets = load_asset_prices(
+        &oracle_guard,
+        &strategy_config.allocations,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+
+    // 按再平衡前的价值结算费用
+    collect_fees(
+        &ctx.accounts.config.fees,
+        user_profile,
+        strategy_config,
+        &ctx.accounts.vault,
+        &ctx.accounts.treasury,
+        &ctx.accounts.system_program,
+        clock.unix_timestamp,
+    )?;
+
     // 更新最后执行时间
     strategy_config.last_executed_at = clock.unix_timestamp;
+    strategy_config.unwind_pending = false;
     user_profile.last_activity = clock.unix_timestamp;
 
-    // 在实际应用中，这里会调用再平衡头寸的逻辑
+    // 策略价值已扣除排队提款的锁定金额，再平衡后这些工单的头寸视为已解除
+    strategy_config.withdrawal_queue.mark_unwound();
+
+    // 按目标权重计算每个分配项的目标价值
+    // 在实际应用中，这里会基于已校验的价格调用再平衡头寸的逻辑
+    let total_value = strategy_config.total_value_lamports;
+    let targets = target_values(total_value, &strategy_config.allocations)?;
+    let legs = strategy_config
+        .allocations
+        .iter()
+        .zip(assets.iter())
+        .zip(targets)
+        .map(|((allocation, asset), target_value)| RebalanceLeg {
+            protocol: allocation.protocol,
+            asset: allocation.asset,
+            target_weight_bps: allocation.target_weight_bps,
+            target_value_lamports: target_value,
+            price: asset.price.price(),
+            expo: asset.price.expo(),
+        })
+        .collect();
+
+    emit!(PositionsRebalanced {
+        owner: strategy_config.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        strategy_value_lamports: total_value,
+        legs,
+        timestamp: clock.unix_timestamp,
+    });
 
     msg!("策略头寸已再平衡，ID: {}", strategy_id);
 
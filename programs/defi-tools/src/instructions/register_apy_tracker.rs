@@ -0,0 +1,74 @@
+use crate::apy::{check_lending, fixed_name};
+use crate::errors::ErrorCode;
+use crate::events::ApyReported;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(protocol: u8, asset: u8)]
+pub struct RegisterApyTracker<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    // 只能为已注册的资产追踪利率
+    #[account(
+        seeds = [b"asset".as_ref(), &[asset]],
+        bump = asset_registry.bump
+    )]
+    pub asset_registry: Account<'info, AssetRegistry>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<PositionTracker>(),
+        seeds = [b"apy".as_ref(), &[protocol], &[asset]],
+        bump
+    )]
+    pub apy_tracker: Account<'info, PositionTracker>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(
+    ctx: Context<RegisterApyTracker>,
+    protocol: u8,
+    asset: u8,
+    apy_bps: u32,
+) -> Result<()> {
+    check_lending(protocol)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    // 市场利率追踪账户不属于任何策略，也不记录头寸价值
+    ctx.accounts.apy_tracker.set_inner(PositionTracker {
+        strategy_id: 0,
+        protocol: fixed_name(Protocol::try_from(protocol)?.name()),
+        asset: fixed_name(&asset.to_string()),
+        position_value_lamports: 0,
+        initial_apy_bps: apy_bps,
+        current_apy_bps: apy_bps,
+        last_updated_at: now,
+    });
+
+    emit!(ApyReported {
+        protocol,
+        asset,
+        apy_bps,
+        timestamp: now,
+    });
+
+    msg!(
+        "利率追踪已注册，协议: {}，资产: {}，APY: {} bps",
+        protocol,
+        asset,
+        apy_bps
+    );
+
+    Ok(())
+}
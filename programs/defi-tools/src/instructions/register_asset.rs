@@ -0,0 +1,49 @@
+use crate::errors::ErrorCode;
+use crate::events::AssetRegistered;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(asset_id: u8)]
+pub struct RegisterAsset<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<AssetRegistry>(),
+        seeds = [b"asset".as_ref(), &[asset_id]],
+        bump
+    )]
+    pub asset_registry: Account<'info, AssetRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(ctx: Context<RegisterAsset>, asset_id: u8, params: AssetParams) -> Result<()> {
+    // 验证资产参数
+    params.validate()?;
+
+    let asset_registry = &mut ctx.accounts.asset_registry;
+    asset_registry.asset_id = asset_id;
+    asset_registry.bump = ctx.bumps.asset_registry;
+    asset_registry.apply(&params);
+
+    emit!(AssetRegistered {
+        asset_id,
+        params,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("资产已注册，ID: {}，Mint: {}", asset_id, params.mint);
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::errors::ErrorCode;
+use crate::events::WithdrawalDestinationRemoved;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(destination: Pubkey)]
+pub struct RemoveWithdrawalDestination<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    // 移除立即生效，尚未生效的地址也可以移除
+    #[account(
+        mut,
+        seeds = [
+            b"destination",
+            owner.key().as_ref(),
+            destination.as_ref()
+        ],
+        bump = withdrawal_destination.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub withdrawal_destination: Account<'info, WithdrawalDestination>,
+}
+
+pub fn process(ctx: Context<RemoveWithdrawalDestination>, destination: Pubkey) -> Result<()> {
+    emit!(WithdrawalDestinationRemoved {
+        owner: ctx.accounts.owner.key(),
+        destination,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("提款白名单地址已移除: {}", destination);
+
+    Ok(())
+}
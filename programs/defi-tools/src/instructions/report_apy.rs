@@ -0,0 +1,47 @@
+use crate::errors::ErrorCode;
+use crate::events::ApyReported;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(protocol: u8, asset: u8)]
+pub struct ReportApy<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"apy".as_ref(), &[protocol], &[asset]],
+        bump
+    )]
+    pub apy_tracker: Account<'info, PositionTracker>,
+}
+
+pub fn process(ctx: Context<ReportApy>, protocol: u8, asset: u8, apy_bps: u32) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let apy_tracker = &mut ctx.accounts.apy_tracker;
+    apy_tracker.current_apy_bps = apy_bps;
+    apy_tracker.last_updated_at = now;
+
+    emit!(ApyReported {
+        protocol,
+        asset,
+        apy_bps,
+        timestamp: now,
+    });
+
+    msg!(
+        "APY已更新，协议: {}，资产: {}，APY: {} bps",
+        protocol,
+        asset,
+        apy_bps
+    );
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use crate::errors::ErrorCode;
+use crate::events::WithdrawalRequested;
+use crate::state::*;
+use crate::vault::collect_fees;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct RequestWithdrawal<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<WithdrawalTicket>(),
+        seeds = [
+            b"ticket",
+            strategy_config.key().as_ref(),
+            &strategy_config.withdrawal_queue.next_ticket_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub withdrawal_ticket: Account<'info, WithdrawalTicket>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn process(ctx: Context<RequestWithdrawal>, strategy_id: u64, amount: u64) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let clock = Clock::get()?;
+
+    // 先结算费用，锁定金额不能超过扣费后的价值，锁定后不再计费
+    collect_fees(
+        &ctx.accounts.config.fees,
+        user_profile,
+        strategy_config,
+        &ctx.accounts.vault,
+        &ctx.accounts.treasury,
+        &ctx.accounts.system_program,
+        clock.unix_timestamp,
+    )?;
+
+    // 验证提款金额
+    if amount == 0
+        || amount > user_profile.total_value_lamports
+        || amount > strategy_config.total_value_lamports
+    {
+        return Err(ErrorCode::InsufficientFunds.into());
+    }
+
+    user_profile.last_activity = clock.unix_timestamp;
+
+    // 与即时提款相同地降低总资产价值，资金留在资金库直到领取
+    user_profile.total_value_lamports = user_profile
+        .total_value_lamports
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathError)?;
+    strategy_config.total_value_lamports = strategy_config
+        .total_value_lamports
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathError)?;
+
+    // 创建工单
+    let ticket_id = strategy_config.withdrawal_queue.enqueue(amount)?;
+    let unlocks_at = clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.config.withdrawal_delay_seconds as i64)
+        .ok_or(ErrorCode::MathError)?;
+    let ticket = &mut ctx.accounts.withdrawal_ticket;
+    ticket.owner = user_profile.owner;
+    ticket.strategy = strategy_config.key();
+    ticket.ticket_id = ticket_id;
+    ticket.amount_lamports = amount;
+    ticket.requested_at = clock.unix_timestamp;
+    ticket.unlocks_at = unlocks_at;
+    ticket.bump = ctx.bumps.withdrawal_ticket;
+
+    emit!(WithdrawalRequested {
+        owner: user_profile.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        ticket_id,
+        amount,
+        unlocks_at,
+        strategy_value_lamports: strategy_config.total_value_lamports,
+        user_value_lamports: user_profile.total_value_lamports,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "提款工单已创建，策略ID: {}，工单ID: {}，金额: {}",
+        strategy_id,
+        ticket_id,
+        amount
+    );
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::errors::ErrorCode;
+use crate::events::StrategyResumed;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct ResumeStrategy<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = strategy_config.is_paused() @ ErrorCode::StrategyNotPaused
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+}
+
+// 保护单触发后由所有者确认恢复，恢复前可先用 update_strategy 调整分配项
+pub fn process(ctx: Context<ResumeStrategy>, strategy_id: u64) -> Result<()> {
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    strategy_config.paused_at = 0;
+    strategy_config.peak_value_lamports = 0;
+
+    emit!(StrategyResumed {
+        owner: strategy_config.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("策略 {} 已恢复", strategy_id);
+
+    Ok(())
+}
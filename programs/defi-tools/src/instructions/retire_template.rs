@@ -0,0 +1,39 @@
+use crate::errors::ErrorCode;
+use crate::events::TemplateRetired;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(template_id: u64)]
+pub struct RetireTemplate<'info> {
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+
+    // 关闭模板账户，租金退回发布者
+    #[account(
+        mut,
+        seeds = [
+            b"template",
+            publisher.key().as_ref(),
+            &template_id.to_le_bytes()
+        ],
+        bump = strategy_template.bump,
+        has_one = publisher @ ErrorCode::Unauthorized,
+        close = publisher
+    )]
+    pub strategy_template: Account<'info, StrategyTemplate>,
+}
+
+pub fn process(ctx: Context<RetireTemplate>, template_id: u64) -> Result<()> {
+    emit!(TemplateRetired {
+        publisher: ctx.accounts.publisher.key(),
+        template: ctx.accounts.strategy_template.key(),
+        template_id,
+        strategies_created: ctx.accounts.strategy_template.strategies_created,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("策略模板已停用，ID: {}", template_id);
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::errors::ErrorCode;
+use crate::events::DelegateRevoked;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64, delegate: Pubkey)]
+pub struct RevokeDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 已过期的授权也可以撤销以取回租金
+    #[account(
+        mut,
+        seeds = [
+            b"delegate",
+            strategy_config.key().as_ref(),
+            delegate.as_ref()
+        ],
+        bump = strategy_delegate.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub strategy_delegate: Account<'info, StrategyDelegate>,
+}
+
+pub fn process(ctx: Context<RevokeDelegate>, strategy_id: u64, delegate: Pubkey) -> Result<()> {
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    strategy_config.delegates = strategy_config
+        .delegates
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathError)?;
+
+    emit!(DelegateRevoked {
+        owner: ctx.accounts.owner.key(),
+        strategy: ctx.accounts.strategy_config.key(),
+        strategy_id,
+        delegate,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "策略代理人已撤销，策略ID: {}，代理人: {}",
+        strategy_id,
+        delegate
+    );
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::apy::{load_apy_reports, plan_rotation, yield_position};
+use crate::errors::ErrorCode;
+use crate::events::YieldRotated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RotateYield<'info> {
+    // 任何人都可以发起轮动，例如 keeper
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"yield", strategy_config.key().as_ref()],
+        bump = yield_rotation.bump
+    )]
+    pub yield_rotation: Account<'info, YieldRotation>,
+
+    #[account(
+        seeds = [b"user", yield_rotation.owner.as_ref()],
+        bump,
+        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        constraint = strategy_config.owner == yield_rotation.owner @ ErrorCode::Unauthorized,
+        constraint = !strategy_config.is_paused() @ ErrorCode::StrategyPaused
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+    // remaining_accounts: 按协议编号顺序提供每个候选协议的利率追踪账户
+}
+
+// 只修改轮动头寸的协议，权重不变，执行者在下次再平衡时调整实际头寸
+pub fn process<'info>(ctx: Context<'_, '_, 'info, 'info, RotateYield<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let rotation = &mut ctx.accounts.yield_rotation;
+    let params = rotation.params;
+
+    // 分配项可能在启用后被修改
+    let index = yield_position(&params, &strategy_config.allocations)?;
+    let reports = load_apy_reports(&params, ctx.remaining_accounts, now)?;
+    let planned = plan_rotation(
+        &params,
+        strategy_config.allocations[index].protocol,
+        &reports,
+        rotation.held_since,
+        now,
+    )?;
+
+    strategy_config.allocations[index].protocol = planned.to_protocol;
+    strategy_config.allocations_updated_at = now;
+    rotation.held_since = now;
+    rotation.rotations = rotation
+        .rotations
+        .checked_add(1)
+        .ok_or(ErrorCode::MathError)?;
+
+    emit!(YieldRotated {
+        owner: rotation.owner,
+        strategy: strategy_config.key(),
+        strategy_id: strategy_config.strategy_id,
+        asset: params.asset,
+        from_protocol: planned.from_protocol,
+        from_apy_bps: planned.from_apy_bps,
+        to_protocol: planned.to_protocol,
+        to_apy_bps: planned.to_apy_bps,
+        allocations: strategy_config.allocations.clone(),
+        timestamp: now,
+    });
+
+    msg!(
+        "策略 {} 的轮动头寸已从协议 {} 移至协议 {}",
+        strategy_config.strategy_id,
+        planned.from_protocol,
+        planned.to_protocol
+    );
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use crate::dca::unit_price;
+use crate::errors::ErrorCode;
+use crate::events::ProtectionSet;
+use crate::protection::{check_triggers, price_marks, validate_protection, Valuation};
+use crate::registry::load_asset_prices;
+use crate::state::*;
+use crate::validation::MAX_ALLOCATIONS;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64, params: ProtectionParams)]
+pub struct SetProtection<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !strategy_config.is_paused() @ ErrorCode::StrategyPaused
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 每个策略只能有一个保护单，修改参数需先取消
+    #[account(
+        init,
+        payer = owner,
+        space = 8
+            + std::mem::size_of::<ProtectiveOrder>()
+            + 4
+            + std::mem::size_of::<PriceMark>() * MAX_ALLOCATIONS,
+        seeds = [b"protect", strategy_config.key().as_ref()],
+        bump
+    )]
+    pub protective_order: Account<'info, ProtectiveOrder>,
+
+    // 触发后转入的安全资产
+    #[account(
+        seeds = [b"asset".as_ref(), &[params.safe_asset]],
+        bump = safe_asset.bump
+    )]
+    pub safe_asset: Account<'info, AssetRegistry>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: 按当前分配项顺序依次提供 [资产注册表, 主价格源, (备用价格源)]
+}
+
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetProtection<'info>>,
+    strategy_id: u64,
+    params: ProtectionParams,
+) -> Result<()> {
+    validate_protection(&params)?;
+    ctx.accounts
+        .safe_asset
+        .check_allocation(&params.safe_allocation())?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let value = strategy_config.total_value_lamports;
+    if value == 0 {
+        return Err(ErrorCode::InsufficientFunds.into());
+    }
+    // 设置时即满足触发条件的参数没有意义
+    let valuation = Valuation {
+        value_lamports: value,
+        peak_value_lamports: value,
+    };
+    if check_triggers(&params, &valuation).is_some() {
+        return Err(ErrorCode::InvalidProtectionParams.into());
+    }
+
+    let assets = load_asset_prices(
+        &ctx.accounts.config.oracle_guard,
+        &strategy_config.allocations,
+        ctx.remaining_accounts,
+        now,
+    )?;
+    let prices = assets
+        .iter()
+        .map(|asset| unit_price(&asset.price, asset.registry.decimals))
+        .collect::<Result<Vec<_>>>()?;
+
+    let owner = ctx.accounts.owner.key();
+    let strategy = strategy_config.key();
+    ctx.accounts.protective_order.set_inner(ProtectiveOrder {
+        owner,
+        strategy,
+        params,
+        marked_value_lamports: value,
+        marked_principal_lamports: value,
+        marks: price_marks(&strategy_config.allocations, &prices),
+        marked_at: now,
+        created_at: now,
+        bump: ctx.bumps.protective_order,
+    });
+    strategy_config.peak_value_lamports = value;
+
+    emit!(ProtectionSet {
+        owner,
+        strategy,
+        strategy_id,
+        params,
+        value_lamports: value,
+        timestamp: now,
+    });
+
+    msg!("策略 {} 已设置保护单", strategy_id);
+
+    Ok(())
+}
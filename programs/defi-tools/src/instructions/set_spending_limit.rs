@@ -0,0 +1,61 @@
+use crate::errors::ErrorCode;
+use crate::events::SpendingLimitUpdated;
+use crate::spending::{authorize_limit_change, validate_spending_limit};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetSpendingLimit<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    // 当前监护人，放宽限额或更换监护人时需要签名
+    pub guardian: Option<Signer<'info>>,
+}
+
+// 修改限额不重置当前窗口的已转出金额
+pub fn process(
+    ctx: Context<SetSpendingLimit>,
+    max_lamports: u64,
+    window_seconds: u32,
+    guardian: Pubkey,
+) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    validate_spending_limit(&user_profile.owner, max_lamports, window_seconds, &guardian)?;
+
+    let current = user_profile.spending_limit;
+    let limit = SpendingLimit {
+        max_lamports,
+        window_seconds,
+        guardian,
+        ..current
+    };
+    let signer = ctx.accounts.guardian.as_ref().map(|signer| signer.key());
+    authorize_limit_change(&current, &limit, signer.as_ref())?;
+    user_profile.spending_limit = limit;
+
+    let now = Clock::get()?.unix_timestamp;
+    emit!(SpendingLimitUpdated {
+        owner: user_profile.owner,
+        max_lamports,
+        window_seconds,
+        guardian,
+        timestamp: now,
+    });
+
+    msg!(
+        "提款限额已更新: 每 {} 秒 {} lamports",
+        window_seconds,
+        max_lamports
+    );
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::errors::ErrorCode;
+use crate::events::WithdrawalAllowlistUpdated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetWithdrawalAllowlist<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+// 启用立即生效并取消待生效的关闭；关闭在延迟之后生效，重复关闭不会推迟生效时间
+pub fn process(ctx: Context<SetWithdrawalAllowlist>, enabled: bool) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    let now = Clock::get()?.unix_timestamp;
+
+    let effective_at = if enabled {
+        user_profile.allowlist_enabled = true;
+        user_profile.allowlist_disable_at = 0;
+        now
+    } else if !user_profile.allowlist_active(now) {
+        user_profile.allowlist_enabled = false;
+        user_profile.allowlist_disable_at = 0;
+        now
+    } else if user_profile.allowlist_disable_at != 0 {
+        user_profile.allowlist_disable_at
+    } else {
+        let disable_at = now
+            .checked_add(i64::from(ctx.accounts.config.allowlist_delay_seconds))
+            .ok_or(ErrorCode::MathError)?;
+        // 没有延迟时立即关闭
+        if disable_at == now {
+            user_profile.allowlist_enabled = false;
+        } else {
+            user_profile.allowlist_disable_at = disable_at;
+        }
+        disable_at
+    };
+
+    emit!(WithdrawalAllowlistUpdated {
+        owner: user_profile.owner,
+        enabled,
+        effective_at,
+        timestamp: now,
+    });
+
+    msg!(
+        "提款白名单已{}，生效时间: {}",
+        if enabled { "启用" } else { "关闭" },
+        effective_at
+    );
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::allowlist::check_destination;
+use crate::errors::ErrorCode;
+use crate::events::FollowerSynced;
+use crate::following::{check_sync, leader_fee};
+use crate::state::*;
+use crate::validation::validate_allocation_assets;
+use crate::vault::{collect_fees, transfer_from_vault};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SyncFollower<'info> {
+    // 任何人都可以发起同步，例如 keeper
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"follow", strategy_config.key().as_ref()],
+        bump = strategy_follow.bump
+    )]
+    pub strategy_follow: Account<'info, StrategyFollow>,
+
+    #[account(
+        mut,
+        seeds = [b"user", strategy_follow.owner.as_ref()],
+        bump,
+        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        constraint = strategy_config.owner == strategy_follow.owner @ ErrorCode::Unauthorized,
+        constraint = !strategy_config.is_paused() @ ErrorCode::StrategyPaused
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 跟投关系记录领投策略的原始地址，领投策略迁移后仍然有效
+    #[account(
+        constraint = leader_strategy.origin_or(leader_strategy.key()) == strategy_follow.leader
+            @ ErrorCode::Unauthorized
+    )]
+    pub leader_strategy: Account<'info, StrategyConfig>,
+
+    // 领投费的接收地址，跟随者启用白名单时必须是已生效的白名单地址
+    #[account(mut, address = leader_strategy.owner)]
+    pub leader_owner: SystemAccount<'info>,
+
+    // 领投者在跟随者白名单中的账户，白名单未启用时不需要
+    #[account(
+        seeds = [
+            b"destination",
+            strategy_follow.owner.as_ref(),
+            leader_owner.key().as_ref()
+        ],
+        bump = withdrawal_destination.bump
+    )]
+    pub withdrawal_destination: Option<Account<'info, WithdrawalDestination>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", strategy_follow.owner.as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: 领投策略的每个分配项按顺序提供对应的资产注册表账户
+}
+
+// 把领投策略的分配项复制到跟随策略，再平衡条件和滑点保持跟随者自己的设置。
+// 领投费按上次同步以来的时间计提，从跟随者的资金库支付，
+// 与提款一样受白名单约束并计入周期提款限额
+pub fn process<'info>(ctx: Context<'_, '_, 'info, 'info, SyncFollower<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let leader = &ctx.accounts.leader_strategy;
+    check_sync(&ctx.accounts.strategy_follow, leader, now)?;
+    // 资产可能在领投策略调整之后被禁用或降低了权重上限
+    validate_allocation_assets(&leader.allocations, ctx.remaining_accounts)?;
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let follow = &mut ctx.accounts.strategy_follow;
+
+    let mut fee = 0;
+    if follow.leader_fee_bps > 0 {
+        // 先结算协议费用，领投费按扣费后的价值计算
+        collect_fees(
+            &ctx.accounts.config.fees,
+            user_profile,
+            strategy_config,
+            &ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            now,
+        )?;
+        fee = leader_fee(follow, strategy_config.total_value_lamports, now)?;
+    }
+    if fee > 0 {
+        check_destination(
+            user_profile,
+            &ctx.accounts.leader_owner.key(),
+            ctx.accounts.withdrawal_destination.as_deref(),
+            now,
+        )?;
+        user_profile.spending_limit.record(fee, now)?;
+        strategy_config.total_value_lamports = strategy_config
+            .total_value_lamports
+            .checked_sub(fee)
+            .ok_or(ErrorCode::MathError)?;
+        user_profile.total_value_lamports = user_profile
+            .total_value_lamports
+            .checked_sub(fee)
+            .ok_or(ErrorCode::MathError)?;
+        transfer_from_vault(
+            &ctx.accounts.system_program,
+            &ctx.accounts.vault,
+            ctx.accounts.leader_owner.to_account_info(),
+            user_profile,
+            fee,
+        )?;
+    }
+
+    strategy_config.allocations = leader.allocations.clone();
+    strategy_config.allocations_updated_at = now;
+    follow.leader_synced_at = leader.allocations_updated_at;
+    follow.synced_at = now;
+    follow.syncs = follow.syncs.checked_add(1).ok_or(ErrorCode::MathError)?;
+
+    emit!(FollowerSynced {
+        owner: follow.owner,
+        strategy: strategy_config.key(),
+        strategy_id: strategy_config.strategy_id,
+        leader: follow.leader,
+        leader_owner: leader.owner,
+        allocations: strategy_config.allocations.clone(),
+        leader_fee_lamports: fee,
+        strategy_value_lamports: strategy_config.total_value_lamports,
+        user_value_lamports: user_profile.total_value_lamports,
+        timestamp: now,
+    });
+
+    msg!(
+        "跟随策略 {} 已同步，领投费: {} lamports",
+        strategy_config.strategy_id,
+        fee
+    );
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::errors::ErrorCode;
+use crate::events::StrategyUnfollowed;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct UnfollowStrategy<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 已同步的分配项保留在策略中
+    #[account(
+        mut,
+        seeds = [b"follow", strategy_config.key().as_ref()],
+        bump = strategy_follow.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub strategy_follow: Account<'info, StrategyFollow>,
+}
+
+pub fn process(ctx: Context<UnfollowStrategy>, strategy_id: u64) -> Result<()> {
+    ctx.accounts.strategy_config.mode = StrategyMode::Manual;
+    let follow = &ctx.accounts.strategy_follow;
+
+    emit!(StrategyUnfollowed {
+        owner: follow.owner,
+        strategy: follow.strategy,
+        strategy_id,
+        leader: follow.leader,
+        syncs: follow.syncs,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("策略 {} 已取消跟随: {}", strategy_id, follow.leader);
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::allowlist::validate_allowlist_delay;
+use crate::errors::ErrorCode;
+use crate::events::AllowlistDelayUpdated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateAllowlistDelay<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn process(ctx: Context<UpdateAllowlistDelay>, delay_seconds: u32) -> Result<()> {
+    // 验证生效延迟
+    validate_allowlist_delay(delay_seconds)?;
+
+    // 只影响之后添加的地址和之后的关闭请求
+    ctx.accounts.config.allowlist_delay_seconds = delay_seconds;
+
+    emit!(AllowlistDelayUpdated {
+        admin: ctx.accounts.admin.key(),
+        delay_seconds,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("提款白名单生效延迟已更新: {}秒", delay_seconds);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::errors::ErrorCode;
+use crate::events::AssetUpdated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(asset_id: u8)]
+pub struct UpdateAsset<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"asset".as_ref(), &[asset_id]],
+        bump = asset_registry.bump
+    )]
+    pub asset_registry: Account<'info, AssetRegistry>,
+}
+
+pub fn process(ctx: Context<UpdateAsset>, asset_id: u8, params: AssetParams) -> Result<()> {
+    // 验证资产参数
+    params.validate()?;
+
+    ctx.accounts.asset_registry.apply(&params);
+
+    emit!(AssetUpdated {
+        asset_id,
+        params,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "资产配置已更新，ID: {}，启用: {}，权重上限: {}",
+        asset_id,
+        params.enabled,
+        params.max_allocation_bps
+    );
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::errors::ErrorCode;
+use crate::events::DepositLimitsUpdated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateDepositLimits<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn process(ctx: Context<UpdateDepositLimits>, limits: DepositLimits) -> Result<()> {
+    // 验证存款限制
+    limits.validate()?;
+
+    ctx.accounts.config.deposit_limits = limits;
+
+    emit!(DepositLimitsUpdated {
+        admin: ctx.accounts.admin.key(),
+        limits,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "存款限制已更新，最小存款: {}，策略上限: {}，用户上限: {}",
+        limits.min_deposit_lamports,
+        limits.max_strategy_value_lamports,
+        limits.max_user_value_lamports
+    );
+
+    Ok(())
+}
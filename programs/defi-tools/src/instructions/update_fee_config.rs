@@ -0,0 +1,51 @@
+use crate::errors::ErrorCode;
+use crate::events::FeeConfigUpdated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    // 新的费用金库，必须是已存在的系统账户
+    pub treasury: SystemAccount<'info>,
+}
+
+pub fn process(ctx: Context<UpdateFeeConfig>, fees: FeeConfig) -> Result<()> {
+    // 验证费率，链上不收业绩费
+    fees.validate_onchain()?;
+
+    // 余额低于免租金下限的账户无法接收小额费用
+    let treasury = &ctx.accounts.treasury;
+    if treasury.lamports() < Rent::get()?.minimum_balance(0) {
+        return Err(ErrorCode::InvalidTreasury.into());
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.fees = fees;
+    config.treasury = treasury.key();
+
+    emit!(FeeConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        fees,
+        treasury: config.treasury,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "费率已更新，管理费: {}，业绩费: {}，费用金库: {}",
+        fees.management_fee_bps,
+        fees.performance_fee_bps,
+        config.treasury
+    );
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::errors::ErrorCode;
+use crate::events::OracleGuardUpdated;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateOracleGuard<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn process(ctx: Context<UpdateOracleGuard>, oracle_guard: OracleGuardConfig) -> Result<()> {
+    // 验证预言机校验参数
+    oracle_guard.validate()?;
+
+    ctx.accounts.config.oracle_guard = oracle_guard;
+
+    emit!(OracleGuardUpdated {
+        admin: ctx.accounts.admin.key(),
+        oracle_guard,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "预言机校验参数已更新，最大年龄: {}秒，置信区间上限: {}，偏差上限: {}",
+        oracle_guard.max_price_age_seconds,
+        oracle_guard.max_confidence_bps,
+        oracle_guard.max_divergence_bps
+    );
+
+    Ok(())
+}
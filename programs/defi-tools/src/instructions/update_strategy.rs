@@ -0,0 +1,119 @@
+use crate::dca::check_dca_interval;
+use crate::delegation::{authorize, check_update_bounds, DelegateAction};
+use crate::errors::ErrorCode;
+use crate::events::StrategyUpdated;
+use crate::state::*;
+use crate::validation::{validate_allocation_assets, validate_strategy_params};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(strategy_id: u64)]
+pub struct UpdateStrategy<'info> {
+    // 策略所有者或其代理人
+    pub authority: Signer<'info>,
+
+    // 策略所有者，用于推导用户配置PDA
+    pub owner: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    // 创建时已按最多分配项数量预留空间，更新无需重新分配
+    #[account(
+        mut,
+        seeds = [
+            b"strategy",
+            user_profile.key().as_ref(),
+            &strategy_id.to_le_bytes()
+        ],
+        bump,
+        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub strategy_config: Account<'info, StrategyConfig>,
+
+    // 代理人签名时提供，所有者签名时省略
+    #[account(
+        seeds = [
+            b"delegate",
+            strategy_config.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = delegate.bump
+    )]
+    pub delegate: Option<Account<'info, StrategyDelegate>>,
+    // remaining_accounts: 每个分配项按顺序提供对应的资产注册表账户
+}
+
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateStrategy<'info>>,
+    strategy_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    // 与创建策略相同的校验
+    validate_strategy_params(&allocations, max_slippage_bps)?;
+    validate_allocation_assets(&allocations, ctx.remaining_accounts)?;
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    let strategy_config = &mut ctx.accounts.strategy_config;
+    let clock = Clock::get()?;
+
+    // 代理人只能在授权范围内调整权重和滑点，不能修改再平衡条件
+    let delegate = ctx.accounts.delegate.as_deref();
+    let scope = authorize(
+        &strategy_config.owner,
+        &ctx.accounts.authority.key(),
+        delegate,
+        DelegateAction::Update,
+        clock.unix_timestamp,
+    )?;
+    if let (Some(scope), Some(delegate)) = (scope, delegate) {
+        check_update_bounds(
+            &scope,
+            &delegate.baseline_weights_bps,
+            &strategy_config.allocations,
+            &strategy_config.rebalance_condition,
+            &allocations,
+            &rebalance_condition,
+            max_slippage_bps,
+        )?;
+    }
+
+    // 定投按再平衡时间间隔执行，启用期间间隔不能设为0
+    if strategy_config.mode == StrategyMode::Dca {
+        check_dca_interval(&rebalance_condition)?;
+    }
+
+    // 只有分配项变化时才通知跟随者
+    if strategy_config.allocations != allocations {
+        strategy_config.allocations = allocations;
+        strategy_config.allocations_updated_at = clock.unix_timestamp;
+        // 所有者已调整触发后的分配项，不再按安全头寸平仓
+        strategy_config.unwind_pending = false;
+    }
+    strategy_config.rebalance_condition = rebalance_condition;
+    strategy_config.max_slippage_bps = max_slippage_bps;
+    user_profile.last_activity = clock.unix_timestamp;
+
+    emit!(StrategyUpdated {
+        owner: strategy_config.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        allocations: strategy_config.allocations.clone(),
+        rebalance_condition: strategy_config.rebalance_condition.clone(),
+        max_slippage_bps,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("策略已更新，ID: {}", strategy_id);
+
+    Ok(())
+}
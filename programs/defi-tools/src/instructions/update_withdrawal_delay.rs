@@ -0,0 +1,36 @@
+use crate::errors::ErrorCode;
+use crate::events::WithdrawalDelayUpdated;
+use crate::state::*;
+use crate::withdrawals::validate_withdrawal_delay;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateWithdrawalDelay<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn process(ctx: Context<UpdateWithdrawalDelay>, delay_seconds: u32) -> Result<()> {
+    // 验证锁定时间
+    validate_withdrawal_delay(delay_seconds)?;
+
+    // 只影响之后申请的工单，已有工单保留申请时的到期时间
+    ctx.accounts.config.withdrawal_delay_seconds = delay_seconds;
+
+    emit!(WithdrawalDelayUpdated {
+        admin: ctx.accounts.admin.key(),
+        delay_seconds,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("提款锁定时间已更新: {}秒", delay_seconds);
+
+    Ok(())
+}
@@ -1,5 +1,10 @@
+use crate::allowlist::check_destination;
 use crate::errors::ErrorCode;
+use crate::events::FundsWithdrawn;
+use crate::limits::release_asset_shares;
+use crate::spending::spend;
 use crate::state::*;
+use crate::vault::{collect_fees, transfer_from_vault};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -12,7 +17,8 @@ pub struct WithdrawFunds<'info> {
         mut,
         seeds = [b"user", owner.key().as_ref()],
         bump,
-        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
+        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = !user_profile.is_migrated() @ ErrorCode::ProfileMigrated
     )]
     pub user_profile: Account<'info, UserProfile>,
 
@@ -28,15 +34,69 @@ pub struct WithdrawFunds<'info> {
     )]
     pub strategy_config: Account<'info, StrategyConfig>,
 
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = user_profile.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // 提款接收地址，启用白名单时必须是所有者本人或已生效的白名单地址
+    #[account(
+        mut,
+        constraint = destination.key() != vault.key() @ ErrorCode::WithdrawalDestinationNotAllowed
+    )]
+    pub destination: SystemAccount<'info>,
+
+    // 接收地址的白名单账户，接收地址为所有者本人或白名单未启用时不需要
+    #[account(
+        seeds = [
+            b"destination",
+            owner.key().as_ref(),
+            destination.key().as_ref()
+        ],
+        bump = withdrawal_destination.bump
+    )]
+    pub withdrawal_destination: Option<Account<'info, WithdrawalDestination>>,
+
+    // 监护人，联署的提款不受周期限额限制
+    pub guardian: Option<Signer<'info>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn process(ctx: Context<WithdrawFunds>, strategy_id: u64, amount: u64) -> Result<()> {
+// remaining_accounts 为按策略存款记录顺序排列的可写资产注册表，提款按比例扣回资产存款
+pub fn process<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawFunds<'info>>,
+    strategy_id: u64,
+    amount: u64,
+) -> Result<()> {
     let user_profile = &mut ctx.accounts.user_profile;
+    let strategy_config = &mut ctx.accounts.strategy_config;
     let clock = Clock::get()?;
 
+    // 先结算费用，提款金额不能超过扣费后的价值
+    collect_fees(
+        &ctx.accounts.config.fees,
+        user_profile,
+        strategy_config,
+        &ctx.accounts.vault,
+        &ctx.accounts.treasury,
+        &ctx.accounts.system_program,
+        clock.unix_timestamp,
+    )?;
+
     // 验证提款金额
-    if amount == 0 || amount > user_profile.total_value_lamports {
+    if amount == 0
+        || amount > user_profile.total_value_lamports
+        || amount > strategy_config.total_value_lamports
+    {
         return Err(ErrorCode::InsufficientFunds.into());
     }
 
@@ -45,16 +105,60 @@ pub fn process(ctx: Context<WithdrawFunds>, strategy_id: u64, amount: u64) -> Re
         return Err(ErrorCode::Unauthorized.into());
     }
 
+    // 验证提款接收地址
+    let destination = ctx.accounts.destination.key();
+    check_destination(
+        user_profile,
+        &destination,
+        ctx.accounts.withdrawal_destination.as_deref(),
+        clock.unix_timestamp,
+    )?;
+
+    // 计入周期提款限额
+    let guardian = ctx.accounts.guardian.as_ref().map(|signer| signer.key());
+    spend(
+        &mut user_profile.spending_limit,
+        amount,
+        guardian.as_ref(),
+        clock.unix_timestamp,
+    )?;
+
     // 更新用户活动时间
     user_profile.last_activity = clock.unix_timestamp;
 
+    // 按提款占比扣回策略计入各资产的存款
+    let released = strategy_config.release_asset_deposits(amount)?;
+    release_asset_shares(&released, ctx.remaining_accounts)?;
+
     // 更新总资产价值
     user_profile.total_value_lamports = user_profile
         .total_value_lamports
         .checked_sub(amount)
         .ok_or(ErrorCode::MathError)?;
+    strategy_config.total_value_lamports = strategy_config
+        .total_value_lamports
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathError)?;
 
-    // 在实际应用中，这里会调用资金提取和转移的逻辑
+    // 由资金库PDA签名，将资金转入接收地址
+    transfer_from_vault(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault,
+        ctx.accounts.destination.to_account_info(),
+        user_profile,
+        amount,
+    )?;
+
+    emit!(FundsWithdrawn {
+        owner: user_profile.owner,
+        strategy: strategy_config.key(),
+        strategy_id,
+        amount,
+        destination,
+        strategy_value_lamports: strategy_config.total_value_lamports,
+        user_value_lamports: user_profile.total_value_lamports,
+        timestamp: clock.unix_timestamp,
+    });
 
     msg!("从策略提取资金，ID: {}，金额: {}", strategy_id, amount);
 
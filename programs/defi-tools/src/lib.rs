@@ -1,460 +1,374 @@
 use anchor_lang::prelude::*;
 
+pub mod allowlist;
+pub mod apy;
+pub mod dca;
+pub mod delegation;
 pub mod errors;
+pub mod events;
+pub mod fees;
+pub mod following;
 pub mod instructions;
+pub mod limits;
+pub mod oracle;
+pub mod ownership;
+pub mod planner;
+pub mod protection;
+pub mod registry;
+pub mod spending;
 pub mod state;
+pub mod templates;
+pub mod validation;
+pub mod vault;
+pub mod withdrawals;
+
+pub use errors::ErrorCode;
+pub use instructions::*;
+pub use state::*;
 
 // 声明程序ID
 declare_id!("CdH2ymLMr7RyYcd1nyDZm59DRv6JgrtzuAxoH7STFvnm");
 
-// 状态定义
-#[account]
-pub struct UserProfile {
-    pub owner: Pubkey,
-    pub risk_level: u8,
-    pub strategy_counter: u64,
-    pub vault_bump: u8,
-    pub last_activity: i64,
-    pub total_value_lamports: u64,
-    pub is_paused: bool,
-}
-
-#[account]
-pub struct StrategyConfig {
-    pub owner: Pubkey,
-    pub strategy_id: u64,
-    pub allocations: Vec<Allocation>,
-    pub rebalance_condition: RebalanceCondition,
-    pub created_at: i64,
-    pub last_executed_at: i64,
-    pub max_slippage_bps: u16,
-}
-
-#[account]
-pub struct PositionTracker {
-    pub strategy_id: u64,
-    pub protocol: [u8; 32],
-    pub asset: [u8; 32],
-    pub position_value_lamports: u64,
-    pub initial_apy_bps: u32,
-    pub current_apy_bps: u32,
-    pub last_updated_at: i64,
-}
+// 程序定义
+#[program]
+pub mod defi_tools {
+    use super::*;
 
-#[account]
-pub struct ProtocolRegistry {
-    pub name: [u8; 32],
-    pub program_id: Pubkey,
-    pub version: [u8; 16],
-    pub min_compatible_version: [u8; 16],
-    pub active: bool,
-    pub risk_score: u8,
-}
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        msg!("DeFi工具集已初始化: {:?}", ctx.program_id);
+        Ok(())
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct Allocation {
-    pub protocol: u8,
-    pub asset: u8,
-    pub target_weight_bps: u16,
-}
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        oracle_guard: OracleGuardConfig,
+    ) -> Result<()> {
+        instructions::initialize_config::process(ctx, oracle_guard)
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct RebalanceCondition {
-    pub time_interval_seconds: u64,
-    pub max_deviation_bps: u16,
-    pub auto_rebalance: bool,
-}
+    pub fn update_oracle_guard(
+        ctx: Context<UpdateOracleGuard>,
+        oracle_guard: OracleGuardConfig,
+    ) -> Result<()> {
+        instructions::update_oracle_guard::process(ctx, oracle_guard)
+    }
 
-// 错误码定义
-#[error_code]
-pub enum ErrorCode {
-    #[msg("操作未授权")]
-    Unauthorized,
+    pub fn update_fee_config(ctx: Context<UpdateFeeConfig>, fees: FeeConfig) -> Result<()> {
+        instructions::update_fee_config::process(ctx, fees)
+    }
 
-    #[msg("无效的风险等级，必须在1-5范围内")]
-    InvalidRiskLevel,
+    pub fn update_deposit_limits(
+        ctx: Context<UpdateDepositLimits>,
+        limits: DepositLimits,
+    ) -> Result<()> {
+        instructions::update_deposit_limits::process(ctx, limits)
+    }
 
-    #[msg("无效的策略ID")]
-    InvalidStrategyId,
+    pub fn update_withdrawal_delay(
+        ctx: Context<UpdateWithdrawalDelay>,
+        delay_seconds: u32,
+    ) -> Result<()> {
+        instructions::update_withdrawal_delay::process(ctx, delay_seconds)
+    }
 
-    #[msg("无效的资产分配，总权重必须为10000基点(100%)")]
-    InvalidAllocation,
+    pub fn update_allowlist_delay(
+        ctx: Context<UpdateAllowlistDelay>,
+        delay_seconds: u32,
+    ) -> Result<()> {
+        instructions::update_allowlist_delay::process(ctx, delay_seconds)
+    }
 
-    #[msg("无效的滑点设置，必须在0-1000基点(0-10%)范围内")]
-    InvalidSlippage,
+    pub fn register_asset(
+        ctx: Context<RegisterAsset>,
+        asset_id: u8,
+        params: AssetParams,
+    ) -> Result<()> {
+        instructions::register_asset::process(ctx, asset_id, params)
+    }
 
-    #[msg("策略已暂停")]
-    StrategyPaused,
+    pub fn update_asset(ctx: Context<UpdateAsset>, asset_id: u8, params: AssetParams) -> Result<()> {
+        instructions::update_asset::process(ctx, asset_id, params)
+    }
 
-    #[msg("资金不足")]
-    InsufficientFunds,
+    pub fn initialize_user(ctx: Context<InitializeUser>, risk_level: u8) -> Result<()> {
+        instructions::initialize_user::process(ctx, risk_level)
+    }
 
-    #[msg("交易滑点超过最大允许值")]
-    SlippageExceeded,
+    pub fn create_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateStrategy<'info>>,
+        allocations: Vec<Allocation>,
+        rebalance_condition: RebalanceCondition,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        instructions::create_strategy::process(
+            ctx,
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+        )
+    }
 
-    #[msg("协议不兼容")]
-    IncompatibleProtocol,
+    pub fn update_strategy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateStrategy<'info>>,
+        strategy_id: u64,
+        allocations: Vec<Allocation>,
+        rebalance_condition: RebalanceCondition,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        instructions::update_strategy::process(
+            ctx,
+            strategy_id,
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+        )
+    }
 
-    #[msg("协议未注册")]
-    ProtocolNotRegistered,
+    pub fn execute_strategy(ctx: Context<ExecuteStrategy>, strategy_id: u64) -> Result<()> {
+        instructions::execute_strategy::process(ctx, strategy_id)
+    }
 
-    #[msg("操作超时")]
-    OperationTimeout,
+    pub fn deposit_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositFunds<'info>>,
+        strategy_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_funds::process(ctx, strategy_id, amount)
+    }
 
-    #[msg("数学错误")]
-    MathError,
+    pub fn withdraw_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawFunds<'info>>,
+        strategy_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_funds::process(ctx, strategy_id, amount)
+    }
 
-    #[msg("头寸不存在")]
-    PositionNotFound,
+    pub fn request_withdrawal(
+        ctx: Context<RequestWithdrawal>,
+        strategy_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::request_withdrawal::process(ctx, strategy_id, amount)
+    }
 
-    #[msg("再平衡条件未满足")]
-    RebalanceConditionNotMet,
+    pub fn claim_withdrawal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimWithdrawal<'info>>,
+        strategy_id: u64,
+        ticket_id: u64,
+    ) -> Result<()> {
+        instructions::claim_withdrawal::process(ctx, strategy_id, ticket_id)
+    }
 
-    #[msg("紧急模式已激活")]
-    EmergencyModeActive,
-}
+    pub fn cancel_withdrawal(
+        ctx: Context<CancelWithdrawal>,
+        strategy_id: u64,
+        ticket_id: u64,
+    ) -> Result<()> {
+        instructions::cancel_withdrawal::process(ctx, strategy_id, ticket_id)
+    }
 
-// 程序定义
-#[program]
-pub mod defi_tools {
-    use super::*;
+    pub fn set_withdrawal_allowlist(
+        ctx: Context<SetWithdrawalAllowlist>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_withdrawal_allowlist::process(ctx, enabled)
+    }
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        msg!("DeFi工具集已初始化: {:?}", ctx.program_id);
-        Ok(())
+    pub fn add_withdrawal_destination(
+        ctx: Context<AddWithdrawalDestination>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        instructions::add_withdrawal_destination::process(ctx, destination)
     }
 
-    pub fn initialize_user(ctx: Context<InitializeUser>, risk_level: u8) -> Result<()> {
-        // 验证风险等级
-        if risk_level < 1 || risk_level > 5 {
-            return Err(ErrorCode::InvalidRiskLevel.into());
-        }
+    pub fn remove_withdrawal_destination(
+        ctx: Context<RemoveWithdrawalDestination>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_withdrawal_destination::process(ctx, destination)
+    }
 
-        let user_profile = &mut ctx.accounts.user_profile;
-        let clock = Clock::get()?;
+    pub fn set_spending_limit(
+        ctx: Context<SetSpendingLimit>,
+        max_lamports: u64,
+        window_seconds: u32,
+        guardian: Pubkey,
+    ) -> Result<()> {
+        instructions::set_spending_limit::process(ctx, max_lamports, window_seconds, guardian)
+    }
 
-        // 初始化用户配置文件
-        user_profile.owner = ctx.accounts.owner.key();
-        user_profile.risk_level = risk_level;
-        user_profile.strategy_counter = 0;
-        user_profile.vault_bump = ctx.bumps.user_profile;
-        user_profile.last_activity = clock.unix_timestamp;
-        user_profile.total_value_lamports = 0;
-        user_profile.is_paused = false;
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        instructions::propose_owner::process(ctx, new_owner)
+    }
 
-        msg!("用户配置文件已初始化，风险等级: {}", risk_level);
+    pub fn accept_owner(ctx: Context<AcceptOwner>, previous_owner: Pubkey) -> Result<()> {
+        instructions::accept_owner::process(ctx, previous_owner)
+    }
 
-        Ok(())
+    pub fn migrate_strategy(
+        ctx: Context<MigrateStrategy>,
+        previous_owner: Pubkey,
+        strategy_id: u64,
+    ) -> Result<()> {
+        instructions::migrate_strategy::process(ctx, previous_owner, strategy_id)
     }
 
-    pub fn create_strategy(
-        ctx: Context<CreateStrategy>,
+    pub fn publish_template<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PublishTemplate<'info>>,
+        template_id: u64,
         allocations: Vec<Allocation>,
         rebalance_condition: RebalanceCondition,
         max_slippage_bps: u16,
+        risk_level: u8,
+        description_hash: [u8; 32],
     ) -> Result<()> {
-        // 验证资产分配
-        let mut total_weight = 0;
-        for allocation in allocations.iter() {
-            total_weight += allocation.target_weight_bps;
-        }
-
-        // 确保总权重为10000基点 (100%)
-        if total_weight != 10000 {
-            return Err(ErrorCode::InvalidAllocation.into());
-        }
-
-        // 验证滑点设置
-        if max_slippage_bps > 1000 {
-            // 最大10%
-            return Err(ErrorCode::InvalidSlippage.into());
-        }
-
-        let user_profile = &mut ctx.accounts.user_profile;
-        let strategy_config = &mut ctx.accounts.strategy_config;
-        let clock = Clock::get()?;
-
-        // 设置策略配置
-        strategy_config.owner = ctx.accounts.owner.key();
-        strategy_config.strategy_id = user_profile.strategy_counter;
-        strategy_config.allocations = allocations;
-        strategy_config.rebalance_condition = rebalance_condition;
-        strategy_config.created_at = clock.unix_timestamp;
-        strategy_config.last_executed_at = 0;
-        strategy_config.max_slippage_bps = max_slippage_bps;
-
-        // 增加用户的策略计数器
-        user_profile.strategy_counter += 1;
-        user_profile.last_activity = clock.unix_timestamp;
-
-        msg!("策略已创建，ID: {}", strategy_config.strategy_id);
-
-        Ok(())
+        instructions::publish_template::process(
+            ctx,
+            template_id,
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+            risk_level,
+            description_hash,
+        )
     }
 
-    pub fn execute_strategy(ctx: Context<ExecuteStrategy>, strategy_id: u64) -> Result<()> {
-        let user_profile = &mut ctx.accounts.user_profile;
-        let strategy_config = &mut ctx.accounts.strategy_config;
-        let clock = Clock::get()?;
-
-        // 更新最后执行时间
-        strategy_config.last_executed_at = clock.unix_timestamp;
-        user_profile.last_activity = clock.unix_timestamp;
-
-        // 这里是实际执行策略的逻辑
-        // 在实际应用中，这里会调用各种DeFi协议的交互逻辑
-
-        msg!("策略已执行，ID: {}", strategy_id);
-
-        Ok(())
+    pub fn retire_template(ctx: Context<RetireTemplate>, template_id: u64) -> Result<()> {
+        instructions::retire_template::process(ctx, template_id)
     }
 
-    pub fn deposit_funds(ctx: Context<DepositFunds>, strategy_id: u64, amount: u64) -> Result<()> {
-        let user_profile = &mut ctx.accounts.user_profile;
-        let strategy_config = &mut ctx.accounts.strategy_config;
-        let clock = Clock::get()?;
+    pub fn create_strategy_from_template<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateStrategyFromTemplate<'info>>,
+    ) -> Result<()> {
+        instructions::create_strategy_from_template::process(ctx)
+    }
 
-        // 验证存款金额
-        if amount == 0 {
-            return Err(ErrorCode::InsufficientFunds.into());
-        }
+    pub fn follow_strategy(
+        ctx: Context<FollowStrategy>,
+        strategy_id: u64,
+        max_weight_bps: u16,
+        delay_seconds: u32,
+        leader_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::follow_strategy::process(
+            ctx,
+            strategy_id,
+            max_weight_bps,
+            delay_seconds,
+            leader_fee_bps,
+        )
+    }
 
-        // 更新用户活动时间
-        user_profile.last_activity = clock.unix_timestamp;
+    pub fn unfollow_strategy(ctx: Context<UnfollowStrategy>, strategy_id: u64) -> Result<()> {
+        instructions::unfollow_strategy::process(ctx, strategy_id)
+    }
 
-        // 更新总资产价值
-        user_profile.total_value_lamports = user_profile
-            .total_value_lamports
-            .checked_add(amount)
-            .ok_or(ErrorCode::MathError)?;
+    pub fn sync_follower<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SyncFollower<'info>>,
+    ) -> Result<()> {
+        instructions::sync_follower::process(ctx)
+    }
 
-        // 在实际应用中，这里会调用资金转移和存款的逻辑
+    pub fn create_dca(ctx: Context<CreateDca>, strategy_id: u64, params: DcaParams) -> Result<()> {
+        instructions::create_dca::process(ctx, strategy_id, params)
+    }
 
-        msg!("向策略存入资金，ID: {}，金额: {}", strategy_id, amount);
+    pub fn cancel_dca(ctx: Context<CancelDca>, strategy_id: u64) -> Result<()> {
+        instructions::cancel_dca::process(ctx, strategy_id)
+    }
 
-        Ok(())
+    pub fn execute_dca<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteDca<'info>>) -> Result<()> {
+        instructions::execute_dca::process(ctx)
     }
 
-    pub fn withdraw_funds(
-        ctx: Context<WithdrawFunds>,
+    pub fn set_protection<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetProtection<'info>>,
         strategy_id: u64,
-        amount: u64,
+        params: ProtectionParams,
     ) -> Result<()> {
-        let user_profile = &mut ctx.accounts.user_profile;
-        let clock = Clock::get()?;
-
-        // 验证提款金额
-        if amount == 0 || amount > user_profile.total_value_lamports {
-            return Err(ErrorCode::InsufficientFunds.into());
-        }
+        instructions::set_protection::process(ctx, strategy_id, params)
+    }
 
-        // 更新用户活动时间
-        user_profile.last_activity = clock.unix_timestamp;
+    pub fn cancel_protection(ctx: Context<CancelProtection>, strategy_id: u64) -> Result<()> {
+        instructions::cancel_protection::process(ctx, strategy_id)
+    }
 
-        // 更新总资产价值
-        user_profile.total_value_lamports = user_profile
-            .total_value_lamports
-            .checked_sub(amount)
-            .ok_or(ErrorCode::MathError)?;
+    pub fn check_protection<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckProtection<'info>>,
+    ) -> Result<()> {
+        instructions::check_protection::process(ctx)
+    }
 
-        // 在实际应用中，这里会调用资金提取和转移的逻辑
+    pub fn resume_strategy(ctx: Context<ResumeStrategy>, strategy_id: u64) -> Result<()> {
+        instructions::resume_strategy::process(ctx, strategy_id)
+    }
 
-        msg!("从策略提取资金，ID: {}，金额: {}", strategy_id, amount);
+    pub fn register_apy_tracker(
+        ctx: Context<RegisterApyTracker>,
+        protocol: u8,
+        asset: u8,
+        apy_bps: u32,
+    ) -> Result<()> {
+        instructions::register_apy_tracker::process(ctx, protocol, asset, apy_bps)
+    }
 
-        Ok(())
+    pub fn report_apy(
+        ctx: Context<ReportApy>,
+        protocol: u8,
+        asset: u8,
+        apy_bps: u32,
+    ) -> Result<()> {
+        instructions::report_apy::process(ctx, protocol, asset, apy_bps)
     }
 
-    pub fn rebalance_positions(ctx: Context<RebalancePositions>, strategy_id: u64) -> Result<()> {
-        let user_profile = &mut ctx.accounts.user_profile;
-        let strategy_config = &mut ctx.accounts.strategy_config;
-        let clock = Clock::get()?;
+    pub fn enable_yield_rotation(
+        ctx: Context<EnableYieldRotation>,
+        strategy_id: u64,
+        params: YieldParams,
+    ) -> Result<()> {
+        instructions::enable_yield_rotation::process(ctx, strategy_id, params)
+    }
 
-        // 验证再平衡条件
-        let time_since_last_execution = clock.unix_timestamp - strategy_config.last_executed_at;
-        if time_since_last_execution
-            < strategy_config.rebalance_condition.time_interval_seconds as i64
-        {
-            return Err(ErrorCode::RebalanceConditionNotMet.into());
-        }
+    pub fn disable_yield_rotation(
+        ctx: Context<DisableYieldRotation>,
+        strategy_id: u64,
+    ) -> Result<()> {
+        instructions::disable_yield_rotation::process(ctx, strategy_id)
+    }
 
-        // 更新最后执行时间
-        strategy_config.last_executed_at = clock.unix_timestamp;
-        user_profile.last_activity = clock.unix_timestamp;
+    pub fn rotate_yield<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RotateYield<'info>>,
+    ) -> Result<()> {
+        instructions::rotate_yield::process(ctx)
+    }
 
-        // 在实际应用中，这里会调用再平衡头寸的逻辑
+    pub fn add_delegate(
+        ctx: Context<AddDelegate>,
+        strategy_id: u64,
+        delegate: Pubkey,
+        scope: DelegateScope,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::add_delegate::process(ctx, strategy_id, delegate, scope, expires_at)
+    }
 
-        msg!("策略头寸已再平衡，ID: {}", strategy_id);
+    pub fn revoke_delegate(
+        ctx: Context<RevokeDelegate>,
+        strategy_id: u64,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        instructions::revoke_delegate::process(ctx, strategy_id, delegate)
+    }
 
-        Ok(())
+    pub fn rebalance_positions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RebalancePositions<'info>>,
+        strategy_id: u64,
+    ) -> Result<()> {
+        instructions::rebalance_positions::process(ctx, strategy_id)
     }
 }
 
 // 账户验证结构
 #[derive(Accounts)]
 pub struct Initialize {}
-
-#[derive(Accounts)]
-pub struct InitializeUser<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + std::mem::size_of::<UserProfile>(),
-        seeds = [b"user", owner.key().as_ref()],
-        bump
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-#[instruction(strategy_id: u64)]
-pub struct ExecuteStrategy<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"user", owner.key().as_ref()],
-        bump,
-        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
-        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    #[account(
-        mut,
-        seeds = [
-            b"strategy", 
-            user_profile.key().as_ref(),
-            &strategy_id.to_le_bytes()
-        ],
-        bump,
-        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
-    )]
-    pub strategy_config: Account<'info, StrategyConfig>,
-
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct CreateStrategy<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"user", owner.key().as_ref()],
-        bump,
-        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + std::mem::size_of::<StrategyConfig>() + 
-                4 + (std::mem::size_of::<Allocation>() * 10), // 最多10个分配项
-        seeds = [
-            b"strategy", 
-            user_profile.key().as_ref(),
-            &user_profile.strategy_counter.to_le_bytes()
-        ],
-        bump
-    )]
-    pub strategy_config: Account<'info, StrategyConfig>,
-
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-#[instruction(strategy_id: u64)]
-pub struct DepositFunds<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"user", owner.key().as_ref()],
-        bump,
-        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
-        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    #[account(
-        mut,
-        seeds = [
-            b"strategy", 
-            user_profile.key().as_ref(),
-            &strategy_id.to_le_bytes()
-        ],
-        bump,
-        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
-    )]
-    pub strategy_config: Account<'info, StrategyConfig>,
-
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-#[instruction(strategy_id: u64)]
-pub struct WithdrawFunds<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"user", owner.key().as_ref()],
-        bump,
-        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    #[account(
-        mut,
-        seeds = [
-            b"strategy", 
-            user_profile.key().as_ref(),
-            &strategy_id.to_le_bytes()
-        ],
-        bump,
-        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
-    )]
-    pub strategy_config: Account<'info, StrategyConfig>,
-
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-#[instruction(strategy_id: u64)]
-pub struct RebalancePositions<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"user", owner.key().as_ref()],
-        bump,
-        constraint = user_profile.owner == owner.key() @ ErrorCode::Unauthorized,
-        constraint = !user_profile.is_paused @ ErrorCode::StrategyPaused
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    #[account(
-        mut,
-        seeds = [
-            b"strategy", 
-            user_profile.key().as_ref(),
-            &strategy_id.to_le_bytes()
-        ],
-        bump,
-        constraint = strategy_config.owner == owner.key() @ ErrorCode::Unauthorized
-    )]
-    pub strategy_config: Account<'info, StrategyConfig>,
-
-    pub system_program: Program<'info, System>,
-}
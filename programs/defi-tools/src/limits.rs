@@ -0,0 +1,333 @@
+use crate::errors::ErrorCode;
+use crate::planner::BPS_DENOMINATOR;
+use crate::registry::load_asset;
+use crate::state::*;
+use crate::validation::MAX_ALLOCATIONS;
+use anchor_lang::prelude::*;
+
+// 存款限制。全局限制存放在 GlobalConfig，资产上限存放在各自的 AssetRegistry，
+// 资产的累计存款按存款时的目标权重分摊。策略记录自己计入每个资产的金额，
+// 提款时按提款占比从这些记录扣回，不受之后调整目标权重的影响
+
+// 每个策略最多记录的资产数量。调整分配项后原资产的记录保留到存款全部取回
+pub const MAX_ASSET_DEPOSITS: usize = 2 * MAX_ALLOCATIONS;
+
+impl DepositLimits {
+    // 非0的上限不能低于最小存款金额
+    pub fn validate(&self) -> Result<()> {
+        let below_min = |cap: u64| cap != 0 && cap < self.min_deposit_lamports;
+        if below_min(self.max_strategy_value_lamports) || below_min(self.max_user_value_lamports) {
+            return Err(ErrorCode::InvalidDepositLimits.into());
+        }
+        Ok(())
+    }
+
+    // 检查存款金额以及存款后的策略和用户总价值
+    pub fn check(&self, amount: u64, strategy_value: u64, user_value: u64) -> Result<()> {
+        if amount < self.min_deposit_lamports {
+            return Err(ErrorCode::DepositTooSmall.into());
+        }
+        self.check_caps(amount, strategy_value, user_value)
+    }
+
+    // 只检查金额计入后的策略和用户总价值，用于取消提款工单等退回策略的金额
+    pub fn check_caps(&self, amount: u64, strategy_value: u64, user_value: u64) -> Result<()> {
+        if exceeds(self.max_strategy_value_lamports, strategy_value, amount)? {
+            return Err(ErrorCode::StrategyDepositCapExceeded.into());
+        }
+        if exceeds(self.max_user_value_lamports, user_value, amount)? {
+            return Err(ErrorCode::UserDepositCapExceeded.into());
+        }
+        Ok(())
+    }
+}
+
+// 上限为0表示不限制
+fn exceeds(cap: u64, current: u64, amount: u64) -> Result<bool> {
+    let after = current.checked_add(amount).ok_or(ErrorCode::MathError)?;
+    Ok(cap != 0 && after > cap)
+}
+
+// 按目标权重分摊到某个分配项的金额，向下取整，各分配项之和不超过总额
+pub fn allocation_share(amount: u64, target_weight_bps: u16) -> u64 {
+    (amount as u128 * target_weight_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+impl AssetRegistry {
+    // 计入一笔分摊到该资产的存款
+    pub fn record_deposit(&mut self, amount: u64) -> Result<()> {
+        if exceeds(self.deposit_cap_lamports, self.deposited_lamports, amount)? {
+            return Err(ErrorCode::AssetDepositCapExceeded.into());
+        }
+        self.deposited_lamports += amount;
+        Ok(())
+    }
+
+    // 扣回策略记录中的存款，不会减到0以下
+    pub fn record_withdraw(&mut self, amount: u64) {
+        self.deposited_lamports = self.deposited_lamports.saturating_sub(amount);
+    }
+}
+
+impl StrategyConfig {
+    // 记录计入某个资产的存款，金额为0时不记录
+    pub fn record_asset_deposit(&mut self, asset: u8, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        if let Some(deposit) = self.asset_deposits.iter_mut().find(|d| d.asset == asset) {
+            deposit.lamports = deposit
+                .lamports
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathError)?;
+            return Ok(());
+        }
+        if self.asset_deposits.len() >= MAX_ASSET_DEPOSITS {
+            return Err(ErrorCode::TooManyAssetDeposits.into());
+        }
+        self.asset_deposits.push(AssetDeposit {
+            asset,
+            lamports: amount,
+        });
+        Ok(())
+    }
+
+    // 按提款金额占策略价值与锁定金额之和的比例扣回每个资产的记录，返回每项扣回的金额。
+    // 须在金额离开策略价值或锁定金额之前调用；取回全部时清空记录
+    pub fn release_asset_deposits(&mut self, amount: u64) -> Result<Vec<(u8, u64)>> {
+        let base = self
+            .total_value_lamports
+            .checked_add(self.withdrawal_queue.locked_lamports)
+            .ok_or(ErrorCode::MathError)?;
+        let released = self
+            .asset_deposits
+            .iter_mut()
+            .map(|deposit| {
+                let share = if amount >= base {
+                    deposit.lamports
+                } else {
+                    (deposit.lamports as u128 * amount as u128 / base as u128) as u64
+                };
+                deposit.lamports -= share;
+                (deposit.asset, share)
+            })
+            .collect();
+        self.asset_deposits.retain(|deposit| deposit.lamports > 0);
+        Ok(released)
+    }
+}
+
+// 按分配项顺序读取可写的资产注册表，把金额按目标权重分摊到每个资产。
+// 多个分配项使用同一资产时合并为一项，只读取第一个对应的账户
+pub fn load_asset_shares<'info>(
+    allocations: &[Allocation],
+    accounts: &'info [AccountInfo<'info>],
+    amount: u64,
+) -> Result<Vec<(Account<'info, AssetRegistry>, u64)>> {
+    if accounts.len() != allocations.len() {
+        return Err(ErrorCode::AssetNotRegistered.into());
+    }
+
+    let mut shares: Vec<(Account<'info, AssetRegistry>, u64)> = Vec::new();
+    for (allocation, account) in allocations.iter().zip(accounts) {
+        let share = allocation_share(amount, allocation.target_weight_bps);
+        if let Some((_, total)) = shares
+            .iter_mut()
+            .find(|(registry, _)| registry.asset_id == allocation.asset)
+        {
+            *total += share;
+            continue;
+        }
+        if !account.is_writable {
+            return Err(ErrorCode::AssetNotRegistered.into());
+        }
+        shares.push((load_asset(account, allocation.asset)?, share));
+    }
+    Ok(shares)
+}
+
+// 按策略的存款记录顺序读取可写的资产注册表，扣回 release_asset_deposits 返回的金额
+pub fn release_asset_shares<'info>(
+    released: &[(u8, u64)],
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    if accounts.len() != released.len() {
+        return Err(ErrorCode::AssetNotRegistered.into());
+    }
+    for ((asset, amount), account) in released.iter().zip(accounts) {
+        if !account.is_writable {
+            return Err(ErrorCode::AssetNotRegistered.into());
+        }
+        let mut registry = load_asset(account, *asset)?;
+        registry.record_withdraw(*amount);
+        registry.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(min: u64, strategy_cap: u64, user_cap: u64) -> DepositLimits {
+        DepositLimits {
+            min_deposit_lamports: min,
+            max_strategy_value_lamports: strategy_cap,
+            max_user_value_lamports: user_cap,
+        }
+    }
+
+    fn registry(cap: u64, deposited: u64) -> AssetRegistry {
+        AssetRegistry {
+            asset_id: 0,
+            mint: Pubkey::new_unique(),
+            decimals: 9,
+            oracle: Pubkey::new_unique(),
+            secondary_oracle: Pubkey::default(),
+            enabled: true,
+            max_allocation_bps: 10000,
+            deposit_cap_lamports: cap,
+            deposited_lamports: deposited,
+            bump: 255,
+        }
+    }
+
+    // 测试1: 默认不限制
+    #[test]
+    fn test_default_limits() {
+        let limits = DepositLimits::default();
+        assert!(limits.validate().is_ok());
+        assert!(limits.check(1, u64::MAX - 1, 0).is_ok());
+        assert_eq!(
+            limits.check(2, u64::MAX - 1, 0).unwrap_err(),
+            ErrorCode::MathError.into()
+        );
+    }
+
+    // 测试2: 最小存款金额，只检查上限时不要求
+    #[test]
+    fn test_min_deposit() {
+        let limits = limits(1_000, 0, 0);
+        assert!(limits.check(1_000, 0, 0).is_ok());
+        assert_eq!(
+            limits.check(999, 0, 0).unwrap_err(),
+            ErrorCode::DepositTooSmall.into()
+        );
+        assert!(limits.check_caps(999, 0, 0).is_ok());
+    }
+
+    // 测试3: 策略上限先于用户上限检查，恰好等于上限时允许
+    #[test]
+    fn test_value_caps() {
+        let limits = limits(0, 1_000, 1_500);
+        assert!(limits.check(400, 600, 1_100).is_ok());
+        assert_eq!(
+            limits.check(401, 600, 2_000).unwrap_err(),
+            ErrorCode::StrategyDepositCapExceeded.into()
+        );
+        assert_eq!(
+            limits.check(401, 0, 1_100).unwrap_err(),
+            ErrorCode::UserDepositCapExceeded.into()
+        );
+    }
+
+    // 测试4: 上限不能低于最小存款金额
+    #[test]
+    fn test_validate() {
+        assert!(limits(100, 100, 0).validate().is_ok());
+        for invalid in [limits(100, 99, 0), limits(100, 0, 99)] {
+            assert_eq!(
+                invalid.validate().unwrap_err(),
+                ErrorCode::InvalidDepositLimits.into()
+            );
+        }
+    }
+
+    // 测试5: 按权重分摊向下取整
+    #[test]
+    fn test_allocation_share() {
+        assert_eq!(allocation_share(1_000, 6000), 600);
+        assert_eq!(allocation_share(999, 5000), 499);
+        assert_eq!(allocation_share(u64::MAX, 10000), u64::MAX);
+    }
+
+    // 测试6: 资产累计存款上限，提款扣回不会小于0
+    #[test]
+    fn test_asset_deposits() {
+        let mut asset = registry(1_000, 900);
+        assert_eq!(
+            asset.record_deposit(101).unwrap_err(),
+            ErrorCode::AssetDepositCapExceeded.into()
+        );
+        assert_eq!(asset.deposited_lamports, 900);
+        asset.record_deposit(100).unwrap();
+        assert_eq!(asset.deposited_lamports, 1_000);
+
+        asset.record_withdraw(400);
+        assert_eq!(asset.deposited_lamports, 600);
+        asset.record_withdraw(1_000);
+        assert_eq!(asset.deposited_lamports, 0);
+
+        let mut unlimited = registry(0, 0);
+        unlimited.record_deposit(u64::MAX).unwrap();
+    }
+
+    // 测试7: 存款记录按资产合并，提款按价值占比扣回，取回全部时清空
+    #[test]
+    fn test_asset_deposit_records() {
+        let mut strategy = StrategyConfig {
+            owner: Pubkey::new_unique(),
+            strategy_id: 0,
+            allocations: Vec::new(),
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 0,
+                max_deviation_bps: 0,
+                auto_rebalance: false,
+            },
+            created_at: 0,
+            last_executed_at: 0,
+            max_slippage_bps: 0,
+            total_value_lamports: 1_000,
+            fees_accrued_at: 0,
+            withdrawal_queue: WithdrawalQueue::default(),
+            allocations_updated_at: 0,
+            peak_value_lamports: 0,
+            paused_at: 0,
+            asset_deposits: Vec::new(),
+            mode: StrategyMode::Manual,
+            delegates: 0,
+            origin: Pubkey::default(),
+            unwind_pending: false,
+        };
+        strategy.record_asset_deposit(0, 600).unwrap();
+        strategy.record_asset_deposit(1, 0).unwrap();
+        strategy.record_asset_deposit(1, 400).unwrap();
+        strategy.record_asset_deposit(0, 0).unwrap();
+        assert_eq!(strategy.asset_deposits.len(), 2);
+
+        // 锁定金额计入比例的分母
+        strategy.total_value_lamports = 750;
+        strategy.withdrawal_queue.locked_lamports = 250;
+        assert_eq!(
+            strategy.release_asset_deposits(250).unwrap(),
+            vec![(0, 150), (1, 100)]
+        );
+        strategy.total_value_lamports = 500;
+        assert_eq!(
+            strategy.release_asset_deposits(800).unwrap(),
+            vec![(0, 450), (1, 300)]
+        );
+        assert!(strategy.asset_deposits.is_empty());
+
+        for asset in 0..MAX_ASSET_DEPOSITS as u8 {
+            strategy.record_asset_deposit(asset, 1).unwrap();
+        }
+        strategy.record_asset_deposit(0, 1).unwrap();
+        assert_eq!(
+            strategy
+                .record_asset_deposit(MAX_ASSET_DEPOSITS as u8, 1)
+                .unwrap_err(),
+            ErrorCode::TooManyAssetDeposits.into()
+        );
+    }
+}
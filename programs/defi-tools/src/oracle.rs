@@ -0,0 +1,379 @@
+use crate::errors::ErrorCode;
+use crate::state::OracleGuardConfig;
+use anchor_lang::prelude::*;
+use anchor_lang::pubkey;
+
+// Pyth拉取式预言机接收程序ID
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+// PriceUpdateV2账户的Anchor判别符: sha256("account:PriceUpdateV2")[..8]
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+// 估值统一使用的报价精度(6位小数)
+pub const QUOTE_DECIMALS: u8 = 6;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+// Pyth PriceUpdateV2账户布局(只用于反序列化)
+#[derive(AnchorDeserialize)]
+enum VerificationLevel {
+    Partial {
+        #[allow(dead_code)]
+        num_signatures: u8,
+    },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    _feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    _ema_price: i64,
+    _ema_conf: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    _posted_slot: u64,
+}
+
+// 从预言机账户读取的原始价格，尚未经过任何校验
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    // 读取Pyth PriceUpdateV2账户，只接受完全验证过的价格
+    pub fn load(account: &AccountInfo) -> Result<Self> {
+        let data = account.try_borrow_data()?;
+        Self::parse(account.owner, &data)
+    }
+
+    // 按账户所有者和数据解析价格，链下客户端读取预言机账户时同样使用
+    pub fn parse(owner: &Pubkey, data: &[u8]) -> Result<Self> {
+        require_keys_eq!(
+            *owner,
+            PYTH_RECEIVER_PROGRAM_ID,
+            ErrorCode::InvalidOracleAccount
+        );
+
+        if data.len() < 8 || data[..8] != PRICE_UPDATE_V2_DISCRIMINATOR {
+            return Err(ErrorCode::InvalidOracleAccount.into());
+        }
+
+        let mut payload = &data[8..];
+        let update = PriceUpdateV2::deserialize(&mut payload)
+            .map_err(|_| error!(ErrorCode::InvalidOracleAccount))?;
+
+        if !matches!(update.verification_level, VerificationLevel::Full) {
+            return Err(ErrorCode::InvalidOracleAccount.into());
+        }
+
+        let message = update.price_message;
+        Ok(Self {
+            price: message.price,
+            conf: message.conf,
+            expo: message.exponent,
+            publish_time: message.publish_time,
+        })
+    }
+}
+
+// 通过校验的价格。只能由 `OracleGuardConfig::check` 构造，
+// 估值、定投成交价上限和再平衡规划都只接受这个类型，保证不会用到未校验的数据
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GuardedPrice {
+    price: u64,
+    expo: i32,
+    publish_time: i64,
+}
+
+impl GuardedPrice {
+    pub fn price(&self) -> u64 {
+        self.price
+    }
+
+    pub fn expo(&self) -> i32 {
+        self.expo
+    }
+
+    pub fn publish_time(&self) -> i64 {
+        self.publish_time
+    }
+
+    // 计算一定数量资产的报价价值(QUOTE_DECIMALS精度)
+    pub fn quote_value(&self, amount: u64, decimals: u8) -> Result<u128> {
+        let raw = (amount as u128)
+            .checked_mul(self.price as u128)
+            .ok_or(ErrorCode::MathError)?;
+        let exponent = self.expo + QUOTE_DECIMALS as i32 - decimals as i32;
+        scale(raw, exponent)
+    }
+
+    // 计算一定报价价值可以换得的资产数量(向下取整)
+    pub fn amount_for_quote(&self, quote_value: u128, decimals: u8) -> Result<u64> {
+        let exponent = decimals as i32 - QUOTE_DECIMALS as i32 - self.expo;
+        let amount = scale(quote_value, exponent)?
+            .checked_div(self.price as u128)
+            .ok_or(ErrorCode::MathError)?;
+        u64::try_from(amount).map_err(|_| error!(ErrorCode::MathError))
+    }
+}
+
+impl OracleGuardConfig {
+    // 校验参数本身是否合理
+    pub fn validate(&self) -> Result<()> {
+        if self.max_price_age_seconds == 0
+            || self.max_confidence_bps == 0
+            || self.max_confidence_bps as u128 > BPS_DENOMINATOR
+            || self.max_divergence_bps as u128 > BPS_DENOMINATOR
+        {
+            return Err(ErrorCode::InvalidOracleGuardConfig.into());
+        }
+        Ok(())
+    }
+
+    // 配置了偏差上限时，每个价格都需要第二个价格源交叉校验
+    pub fn requires_secondary(&self) -> bool {
+        self.max_divergence_bps > 0
+    }
+
+    // 检查价格的时效性和置信区间
+    pub fn check(&self, price: &OraclePrice, now: i64) -> Result<GuardedPrice> {
+        if price.price <= 0 {
+            return Err(ErrorCode::InvalidPrice.into());
+        }
+
+        let age = now.saturating_sub(price.publish_time);
+        if age > self.max_price_age_seconds as i64 {
+            return Err(ErrorCode::StalePrice.into());
+        }
+
+        // conf / price > max_confidence_bps / 10000
+        let conf_scaled = (price.conf as u128) * BPS_DENOMINATOR;
+        let limit = (price.price as u128) * (self.max_confidence_bps as u128);
+        if conf_scaled > limit {
+            return Err(ErrorCode::PriceConfidenceTooWide.into());
+        }
+
+        Ok(GuardedPrice {
+            price: price.price as u64,
+            expo: price.expo,
+            publish_time: price.publish_time,
+        })
+    }
+
+    // 比较两个价格源，偏差超过上限时失败
+    pub fn cross_check(&self, primary: &GuardedPrice, secondary: &GuardedPrice) -> Result<()> {
+        let expo = primary.expo.min(secondary.expo);
+        let a = scale(primary.price as u128, primary.expo - expo)?;
+        let b = scale(secondary.price as u128, secondary.expo - expo)?;
+
+        let diff = a.abs_diff(b);
+        let limit = a
+            .min(b)
+            .checked_mul(self.max_divergence_bps as u128)
+            .ok_or(ErrorCode::MathError)?;
+        if diff
+            .checked_mul(BPS_DENOMINATOR)
+            .ok_or(ErrorCode::MathError)?
+            > limit
+        {
+            return Err(ErrorCode::PriceSourceDivergence.into());
+        }
+        Ok(())
+    }
+
+    // 读取并校验一个价格，配置要求时用第二个价格源交叉校验
+    pub fn load_guarded(
+        &self,
+        primary: &AccountInfo,
+        secondary: Option<&AccountInfo>,
+        now: i64,
+    ) -> Result<GuardedPrice> {
+        let guarded = self.check(&OraclePrice::load(primary)?, now)?;
+
+        if self.requires_secondary() {
+            let secondary = secondary.ok_or(ErrorCode::InvalidOracleAccount)?;
+            require_keys_neq!(
+                primary.key(),
+                secondary.key(),
+                ErrorCode::InvalidOracleAccount
+            );
+            let other = self.check(&OraclePrice::load(secondary)?, now)?;
+            self.cross_check(&guarded, &other)?;
+        }
+
+        Ok(guarded)
+    }
+
+    // 每个价格需要的预言机账户数
+    pub fn accounts_per_price(&self) -> usize {
+        if self.requires_secondary() {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+// value * 10^exponent，exponent可以为负
+fn scale(value: u128, exponent: i32) -> Result<u128> {
+    let factor = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(ErrorCode::MathError)?;
+    if exponent >= 0 {
+        value.checked_mul(factor).ok_or(ErrorCode::MathError.into())
+    } else {
+        Ok(value / factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard() -> OracleGuardConfig {
+        OracleGuardConfig {
+            max_price_age_seconds: 60,
+            max_confidence_bps: 100,
+            max_divergence_bps: 50,
+        }
+    }
+
+    // SOL = 150.00000000 USD
+    fn sol_price(publish_time: i64) -> OraclePrice {
+        OraclePrice {
+            price: 15_000_000_000,
+            conf: 10_000_000,
+            expo: -8,
+            publish_time,
+        }
+    }
+
+    // 测试1: 新鲜且置信区间窄的价格通过校验
+    #[test]
+    fn test_check_accepts_fresh_price() {
+        let guarded = guard().check(&sol_price(1_000), 1_030).unwrap();
+        assert_eq!(guarded.price(), 15_000_000_000);
+        assert_eq!(guarded.expo(), -8);
+    }
+
+    // 测试2: 超过最大年龄的价格被拒绝
+    #[test]
+    fn test_check_rejects_stale_price() {
+        let err = guard().check(&sol_price(1_000), 1_061).unwrap_err();
+        assert_eq!(err, ErrorCode::StalePrice.into());
+    }
+
+    // 测试3: 置信区间过宽的价格被拒绝
+    #[test]
+    fn test_check_rejects_wide_confidence() {
+        let mut price = sol_price(1_000);
+        // 1% 恰好等于上限
+        price.conf = 150_000_000;
+        assert!(guard().check(&price, 1_000).is_ok());
+
+        price.conf = 150_000_001;
+        let err = guard().check(&price, 1_000).unwrap_err();
+        assert_eq!(err, ErrorCode::PriceConfidenceTooWide.into());
+    }
+
+    // 测试4: 非正价格被拒绝
+    #[test]
+    fn test_check_rejects_non_positive_price() {
+        let mut price = sol_price(1_000);
+        price.price = 0;
+        let err = guard().check(&price, 1_000).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidPrice.into());
+    }
+
+    // 测试5: 不同指数的价格源交叉校验
+    #[test]
+    fn test_cross_check_normalizes_exponents() {
+        let guard = guard();
+        let a = guard.check(&sol_price(1_000), 1_000).unwrap();
+        // 150.5 USD，指数 -6，偏差约33基点
+        let b = guard
+            .check(
+                &OraclePrice {
+                    price: 150_500_000,
+                    conf: 0,
+                    expo: -6,
+                    publish_time: 1_000,
+                },
+                1_000,
+            )
+            .unwrap();
+        assert!(guard.cross_check(&a, &b).is_ok());
+
+        // 151 USD，偏差约66基点
+        let c = guard
+            .check(
+                &OraclePrice {
+                    price: 151_000_000,
+                    conf: 0,
+                    expo: -6,
+                    publish_time: 1_000,
+                },
+                1_000,
+            )
+            .unwrap();
+        let err = guard.cross_check(&a, &c).unwrap_err();
+        assert_eq!(err, ErrorCode::PriceSourceDivergence.into());
+    }
+
+    // 测试6: 估值与按报价价值换算数量
+    #[test]
+    fn test_quote_value_and_amount_for_quote() {
+        let guard = guard();
+        let sol = guard.check(&sol_price(1_000), 1_000).unwrap();
+        let usdc = guard
+            .check(
+                &OraclePrice {
+                    price: 100_000_000,
+                    conf: 0,
+                    expo: -8,
+                    publish_time: 1_000,
+                },
+                1_000,
+            )
+            .unwrap();
+
+        // 2 SOL = 300 USD
+        assert_eq!(sol.quote_value(2_000_000_000, 9).unwrap(), 300_000_000);
+        // 300 USD = 300 USDC
+        assert_eq!(usdc.amount_for_quote(300_000_000, 6).unwrap(), 300_000_000);
+    }
+
+    // 测试7: 参数校验
+    #[test]
+    fn test_validate_guard_config() {
+        assert!(guard().validate().is_ok());
+
+        let mut config = guard();
+        config.max_price_age_seconds = 0;
+        assert!(config.validate().is_err());
+
+        let mut config = guard();
+        config.max_confidence_bps = 10_001;
+        assert!(config.validate().is_err());
+
+        let mut config = guard();
+        config.max_divergence_bps = 0;
+        assert!(config.validate().is_ok());
+        assert!(!config.requires_secondary());
+        assert_eq!(config.accounts_per_price(), 1);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 所有权转移。PDA 以所有者公钥为种子，无法直接更换所有者：所有者先提名新密钥，
+// 新密钥接受后在自己名下创建用户配置并继承设置，原配置冻结，之后逐个迁移策略和资金。
+// 迁移保留策略ID、创建时间、费用状态和提款工单计数
+
+impl UserProfile {
+    // 已接受转移的配置只能迁移策略、取消工单和撤销授权
+    pub fn is_migrated(&self) -> bool {
+        self.migrated_to != Pubkey::default()
+    }
+}
+
+impl StrategyConfig {
+    // 策略的原始地址，address 为策略当前的账户地址。迁移会关闭原策略账户，原始地址保持不变
+    pub fn origin_or(&self, address: Pubkey) -> Pubkey {
+        if self.origin == Pubkey::default() {
+            address
+        } else {
+            self.origin
+        }
+    }
+}
+
+// 新所有者不能是当前所有者；默认值表示取消提名
+pub fn validate_new_owner(owner: &Pubkey, new_owner: &Pubkey) -> Result<()> {
+    if new_owner == owner {
+        return Err(ErrorCode::InvalidNewOwner.into());
+    }
+    Ok(())
+}
+
+// 新所有者可以接受的时间。白名单生效时与新增白名单地址使用相同的延迟，
+// 避免泄露的密钥通过转移所有权立即绕过白名单
+pub fn accept_after(profile: &UserProfile, delay_seconds: u32, now: i64) -> Result<i64> {
+    if !profile.allowlist_active(now) {
+        return Ok(now);
+    }
+    now.checked_add(i64::from(delay_seconds))
+        .ok_or_else(|| ErrorCode::MathError.into())
+}
+
+// 新配置继承原配置的风险等级、策略计数和提款保护设置，价值随策略迁移逐步转入。
+// 白名单地址以所有者为种子，不随转移继承
+pub fn inherit_profile(previous: &UserProfile, profile: &mut UserProfile) {
+    profile.risk_level = previous.risk_level;
+    profile.strategy_counter = previous.strategy_counter;
+    profile.is_paused = previous.is_paused;
+    profile.allowlist_enabled = previous.allowlist_enabled;
+    profile.allowlist_disable_at = previous.allowlist_disable_at;
+    profile.spending_limit = previous.spending_limit;
+    profile.migrated_from = previous.owner;
+}
+
+// 提款工单以策略地址为种子，迁移前必须全部领取或取消。工单金额都大于0，
+// 锁定金额为0即没有未关闭的工单；取消非队首工单时队首不会后移，不能按队首判断。
+// 定投、跟随、轮动、保护单和代理人授权同样以策略地址为种子，迁移后无法再关闭，
+// 需由原所有者先行取消；protected 表示原策略的保护单账户存在
+pub fn check_migratable(strategy: &StrategyConfig, protected: bool) -> Result<()> {
+    if strategy.withdrawal_queue.locked_lamports != 0 {
+        return Err(ErrorCode::WithdrawalsPending.into());
+    }
+    if strategy.mode != StrategyMode::Manual || strategy.delegates != 0 || protected {
+        return Err(ErrorCode::StrategyLinksActive.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(owner: Pubkey, allowlist_enabled: bool) -> UserProfile {
+        UserProfile {
+            owner,
+            risk_level: 4,
+            strategy_counter: 3,
+            vault_bump: 254,
+            last_activity: 10,
+            total_value_lamports: 500,
+            is_paused: false,
+            allowlist_enabled,
+            allowlist_disable_at: 0,
+            spending_limit: SpendingLimit {
+                max_lamports: 1000,
+                window_seconds: 3600,
+                window_start: 5,
+                spent_lamports: 200,
+                guardian: Pubkey::new_unique(),
+            },
+            pending_owner: Pubkey::default(),
+            pending_owner_at: 0,
+            migrated_to: Pubkey::default(),
+            migrated_from: Pubkey::default(),
+        }
+    }
+
+    // 测试1: 新所有者不能是当前所有者，默认值用于取消
+    #[test]
+    fn test_validate_new_owner() {
+        let owner = Pubkey::new_unique();
+        assert!(validate_new_owner(&owner, &Pubkey::new_unique()).is_ok());
+        assert!(validate_new_owner(&owner, &Pubkey::default()).is_ok());
+        assert_eq!(
+            validate_new_owner(&owner, &owner).unwrap_err(),
+            ErrorCode::InvalidNewOwner.into()
+        );
+    }
+
+    // 测试2: 白名单生效时接受转移需要等待延迟
+    #[test]
+    fn test_accept_after() {
+        let owner = Pubkey::new_unique();
+        assert_eq!(accept_after(&profile(owner, false), 600, 100).unwrap(), 100);
+        assert_eq!(accept_after(&profile(owner, true), 600, 100).unwrap(), 700);
+        assert!(accept_after(&profile(owner, true), 600, i64::MAX).is_err());
+    }
+
+    // 测试3: 新配置继承设置但不继承价值和资金库
+    #[test]
+    fn test_inherit_profile() {
+        let previous = profile(Pubkey::new_unique(), true);
+        let mut next = profile(Pubkey::new_unique(), false);
+        next.strategy_counter = 0;
+        next.total_value_lamports = 0;
+        next.spending_limit = SpendingLimit::default();
+        inherit_profile(&previous, &mut next);
+
+        assert_eq!(next.risk_level, 4);
+        assert_eq!(next.strategy_counter, 3);
+        assert!(next.allowlist_enabled);
+        assert_eq!(next.spending_limit.spent_lamports, 200);
+        assert_eq!(
+            next.spending_limit.guardian,
+            previous.spending_limit.guardian
+        );
+        assert_eq!(next.migrated_from, previous.owner);
+        assert_eq!(next.total_value_lamports, 0);
+        assert_eq!(next.vault_bump, 254);
+    }
+
+    // 测试4: 有未领取工单或仍有关联账户的策略不能迁移
+    #[test]
+    fn test_check_migratable() {
+        let mut strategy = StrategyConfig {
+            owner: Pubkey::new_unique(),
+            strategy_id: 0,
+            allocations: Vec::new(),
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 0,
+                max_deviation_bps: 0,
+                auto_rebalance: false,
+            },
+            created_at: 0,
+            last_executed_at: 0,
+            max_slippage_bps: 0,
+            total_value_lamports: 0,
+            fees_accrued_at: 0,
+            withdrawal_queue: WithdrawalQueue {
+                next_ticket_id: 2,
+                head_ticket_id: 2,
+                unwound_ticket_id: 2,
+                locked_lamports: 0,
+            },
+            allocations_updated_at: 0,
+            peak_value_lamports: 0,
+            paused_at: 0,
+            asset_deposits: Vec::new(),
+            mode: StrategyMode::Manual,
+            delegates: 0,
+            origin: Pubkey::default(),
+            unwind_pending: false,
+        };
+        assert!(check_migratable(&strategy, false).is_ok());
+        assert_eq!(
+            check_migratable(&strategy, true).unwrap_err(),
+            ErrorCode::StrategyLinksActive.into()
+        );
+        for (mode, delegates) in [(StrategyMode::Dca, 0), (StrategyMode::Manual, 1)] {
+            let linked = StrategyConfig {
+                mode,
+                delegates,
+                allocations: Vec::new(),
+                rebalance_condition: strategy.rebalance_condition.clone(),
+                asset_deposits: Vec::new(),
+                ..strategy
+            };
+            assert_eq!(
+                check_migratable(&linked, false).unwrap_err(),
+                ErrorCode::StrategyLinksActive.into()
+            );
+        }
+
+        strategy.withdrawal_queue.enqueue(100).unwrap();
+        assert_eq!(
+            check_migratable(&strategy, false).unwrap_err(),
+            ErrorCode::WithdrawalsPending.into()
+        );
+
+        // 申请工单2和3，先取消3再领取2，队首停在3但已没有未关闭的工单
+        let mut strategy = StrategyConfig {
+            withdrawal_queue: WithdrawalQueue {
+                next_ticket_id: 2,
+                head_ticket_id: 2,
+                unwound_ticket_id: 2,
+                locked_lamports: 0,
+            },
+            ..strategy
+        };
+        let queue = &mut strategy.withdrawal_queue;
+        let first = queue.enqueue(100).unwrap();
+        let second = queue.enqueue(200).unwrap();
+        queue.cancel(second, 200).unwrap();
+        queue.claim(first, 100).unwrap();
+        assert_eq!(queue.head_ticket_id, 3);
+        assert_eq!(queue.next_ticket_id, 4);
+        assert!(check_migratable(&strategy, false).is_ok());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::errors::ErrorCode;
+use crate::state::{Allocation, RebalanceCondition};
+use anchor_lang::prelude::*;
+
+// 再平衡规划逻辑。只依赖分配项和价值，不读取账户，
+// 链上 rebalance_positions 和链下回测共用同一份实现
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+impl RebalanceCondition {
+    // 距上次执行是否已经过了最小时间间隔
+    pub fn interval_elapsed(&self, last_executed_at: i64, now: i64) -> bool {
+        let elapsed = now.saturating_sub(last_executed_at);
+        elapsed >= i64::try_from(self.time_interval_seconds).unwrap_or(i64::MAX)
+    }
+
+    // 自动再平衡是否应当触发：时间间隔已到，且设置了偏差阈值时当前偏差达到阈值
+    pub fn is_due(&self, last_executed_at: i64, now: i64, deviation_bps: u16) -> bool {
+        self.auto_rebalance
+            && self.interval_elapsed(last_executed_at, now)
+            && (self.max_deviation_bps == 0 || deviation_bps >= self.max_deviation_bps)
+    }
+}
+
+// 按目标权重计算每个分配项的目标价值(向下取整)
+pub fn target_values(total_value: u64, allocations: &[Allocation]) -> Result<Vec<u64>> {
+    allocations
+        .iter()
+        .map(|allocation| {
+            let value = (total_value as u128)
+                .checked_mul(allocation.target_weight_bps as u128)
+                .ok_or(ErrorCode::MathError)?
+                / BPS_DENOMINATOR as u128;
+            u64::try_from(value).map_err(|_| error!(ErrorCode::MathError))
+        })
+        .collect()
+}
+
+// 各分配项当前权重与目标权重的最大偏差(基点)，总价值为0时视为没有偏差
+pub fn max_deviation_bps(allocations: &[Allocation], current_values: &[u64]) -> u16 {
+    let total: u128 = current_values.iter().map(|v| *v as u128).sum();
+    if total == 0 {
+        return 0;
+    }
+    allocations
+        .iter()
+        .zip(current_values)
+        .map(|(allocation, value)| {
+            let weight = *value as u128 * BPS_DENOMINATOR as u128 / total;
+            (weight as i128 - allocation.target_weight_bps as i128).unsigned_abs()
+        })
+        .max()
+        .unwrap_or(0)
+        .min(u16::MAX as u128) as u16
+}
+
+// 单个分配项的调整
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlannedLeg {
+    pub current_value: u64,
+    pub target_value: u64,
+}
+
+impl PlannedLeg {
+    // 需要买入(正)或卖出(负)的价值
+    pub fn delta(&self) -> i128 {
+        self.target_value as i128 - self.current_value as i128
+    }
+}
+
+// 把当前持仓调整回目标权重的计划
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebalancePlan {
+    pub total_value: u64,
+    pub legs: Vec<PlannedLeg>,
+}
+
+impl RebalancePlan {
+    // 按当前各分配项价值之和计算目标价值
+    pub fn new(allocations: &[Allocation], current_values: &[u64]) -> Result<Self> {
+        if allocations.len() != current_values.len() {
+            return Err(ErrorCode::InvalidAllocation.into());
+        }
+        let total_value = current_values
+            .iter()
+            .try_fold(0u64, |sum, v| sum.checked_add(*v))
+            .ok_or(ErrorCode::MathError)?;
+        let targets = target_values(total_value, allocations)?;
+        let legs = current_values
+            .iter()
+            .zip(targets)
+            .map(|(current_value, target_value)| PlannedLeg {
+                current_value: *current_value,
+                target_value,
+            })
+            .collect();
+        Ok(Self { total_value, legs })
+    }
+
+    // 需要买入的总价值，即本次调整的成交额
+    pub fn turnover(&self) -> u64 {
+        self.legs
+            .iter()
+            .map(|leg| leg.target_value.saturating_sub(leg.current_value))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocations(weights: &[u16]) -> Vec<Allocation> {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| Allocation {
+                protocol: 0,
+                asset: i as u8,
+                target_weight_bps: *weight,
+            })
+            .collect()
+    }
+
+    fn condition(interval: u64, max_deviation_bps: u16) -> RebalanceCondition {
+        RebalanceCondition {
+            time_interval_seconds: interval,
+            max_deviation_bps,
+            auto_rebalance: true,
+        }
+    }
+
+    // 测试1: 时间间隔检查
+    #[test]
+    fn test_interval_elapsed() {
+        let c = condition(3600, 0);
+        assert!(!c.interval_elapsed(1000, 4599));
+        assert!(c.interval_elapsed(1000, 4600));
+        assert!(!condition(u64::MAX, 0).interval_elapsed(0, i64::MAX - 1));
+    }
+
+    // 测试2: 偏差阈值与自动再平衡开关
+    #[test]
+    fn test_is_due() {
+        let c = condition(60, 500);
+        assert!(!c.is_due(0, 100, 499));
+        assert!(c.is_due(0, 100, 500));
+        assert!(!c.is_due(0, 59, 10000));
+        assert!(condition(60, 0).is_due(0, 60, 0));
+
+        let mut disabled = condition(0, 0);
+        disabled.auto_rebalance = false;
+        assert!(!disabled.is_due(0, 100, 10000));
+    }
+
+    // 测试3: 目标价值按权重向下取整
+    #[test]
+    fn test_target_values() {
+        let a = allocations(&[6000, 4000]);
+        assert_eq!(target_values(1001, &a).unwrap(), vec![600, 400]);
+        assert_eq!(
+            target_values(u64::MAX, &allocations(&[10000])).unwrap(),
+            vec![u64::MAX]
+        );
+    }
+
+    // 测试4: 最大权重偏差
+    #[test]
+    fn test_max_deviation_bps() {
+        let a = allocations(&[5000, 5000]);
+        assert_eq!(max_deviation_bps(&a, &[100, 100]), 0);
+        assert_eq!(max_deviation_bps(&a, &[150, 100]), 1000);
+        assert_eq!(max_deviation_bps(&a, &[0, 0]), 0);
+    }
+
+    // 测试5: 再平衡计划与成交额
+    #[test]
+    fn test_rebalance_plan() {
+        let a = allocations(&[5000, 5000]);
+        let plan = RebalancePlan::new(&a, &[150, 50]).unwrap();
+        assert_eq!(plan.total_value, 200);
+        assert_eq!(plan.legs[0].delta(), -50);
+        assert_eq!(plan.legs[1].delta(), 50);
+        assert_eq!(plan.turnover(), 50);
+
+        assert!(RebalancePlan::new(&a, &[100]).is_err());
+        assert!(RebalancePlan::new(&a, &[u64::MAX, 1]).is_err());
+    }
+}
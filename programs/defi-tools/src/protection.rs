@@ -0,0 +1,295 @@
+use crate::errors::ErrorCode;
+use crate::planner::BPS_DENOMINATOR;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 止损和止盈。保护单记录上次估值时各分配项的价格，本次估值为
+// 上次估值 × Σ(当前权重 × 当前价格 / 记录价格)，再按账面价值的变化(存取款)同比例调整。
+// 分配项在两次估值之间变化时按当前权重计算，新加入的分配项视为价格不变。
+// 任何人都可以重新估值，满足触发条件时全部权重转入安全头寸并暂停策略。
+// 暂停的策略仍可按安全头寸再平衡一次完成平仓，不受时间间隔限制
+
+// 价格变化比例的精度
+const GROWTH_PRECISION: u128 = 1_000_000_000;
+
+// 至少启用一个触发条件，回撤比例小于100%，止损线低于止盈线，安全头寸的协议已知
+pub fn validate_protection(params: &ProtectionParams) -> Result<()> {
+    let enabled = params.stop_loss_lamports > 0
+        || params.max_drawdown_bps > 0
+        || params.take_profit_lamports > 0;
+    if !enabled
+        || params.max_drawdown_bps as u64 >= BPS_DENOMINATOR
+        || (params.take_profit_lamports > 0
+            && params.stop_loss_lamports >= params.take_profit_lamports)
+        || params.safe_allocation().protocol().is_err()
+    {
+        return Err(ErrorCode::InvalidProtectionParams.into());
+    }
+    Ok(())
+}
+
+impl ProtectionParams {
+    // 触发后策略唯一的分配项
+    pub fn safe_allocation(&self) -> Allocation {
+        Allocation {
+            protocol: self.safe_protocol,
+            asset: self.safe_asset,
+            target_weight_bps: BPS_DENOMINATOR as u16,
+        }
+    }
+}
+
+impl StrategyConfig {
+    pub fn is_paused(&self) -> bool {
+        self.paused_at != 0
+    }
+}
+
+// 按分配项顺序记录价格
+pub fn price_marks(allocations: &[Allocation], prices: &[u64]) -> Vec<PriceMark> {
+    allocations
+        .iter()
+        .zip(prices)
+        .map(|(allocation, price)| PriceMark {
+            protocol: allocation.protocol,
+            asset: allocation.asset,
+            price_quote: *price,
+        })
+        .collect()
+}
+
+// 一次估值的结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Valuation {
+    pub value_lamports: u64,
+    pub peak_value_lamports: u64,
+}
+
+// 按当前价格和账面价值重新估值。prices 为各分配项的当前价格，
+// 峰值与估值按同一比例随存取款调整，之后取两者中较大的值
+pub fn revalue(
+    order: &ProtectiveOrder,
+    allocations: &[Allocation],
+    prices: &[u64],
+    principal: u64,
+    peak: u64,
+) -> Result<Valuation> {
+    let mut growth: u128 = 0;
+    for (allocation, price) in allocations.iter().zip(prices) {
+        let mark = order
+            .marks
+            .iter()
+            .find(|mark| mark.protocol == allocation.protocol && mark.asset == allocation.asset);
+        let ratio = match mark {
+            Some(mark) if mark.price_quote > 0 => {
+                (*price as u128)
+                    .checked_mul(GROWTH_PRECISION)
+                    .ok_or(ErrorCode::MathError)?
+                    / mark.price_quote as u128
+            }
+            _ => GROWTH_PRECISION,
+        };
+        growth = ratio
+            .checked_mul(allocation.target_weight_bps as u128)
+            .and_then(|weighted| growth.checked_add(weighted))
+            .ok_or(ErrorCode::MathError)?;
+    }
+
+    let marked = order.marked_principal_lamports;
+    // 上次估值时账面价值为0(资金已全部取出)，以当前账面价值重新开始
+    if marked == 0 {
+        return Ok(Valuation {
+            value_lamports: principal,
+            peak_value_lamports: principal.max(peak),
+        });
+    }
+    let value = (order.marked_value_lamports as u128)
+        .checked_mul(growth)
+        .ok_or(ErrorCode::MathError)?
+        / (GROWTH_PRECISION * BPS_DENOMINATOR as u128);
+    let value = scale(value, principal, marked)?;
+    let peak = scale(peak as u128, principal, marked)?;
+    Ok(Valuation {
+        value_lamports: value,
+        peak_value_lamports: peak.max(value),
+    })
+}
+
+fn scale(value: u128, numerator: u64, denominator: u64) -> Result<u64> {
+    let scaled = value
+        .checked_mul(numerator as u128)
+        .ok_or(ErrorCode::MathError)?
+        / denominator as u128;
+    u64::try_from(scaled).map_err(|_| error!(ErrorCode::MathError))
+}
+
+// 按止损、最大回撤、止盈的顺序返回第一个满足的触发条件
+pub fn check_triggers(
+    params: &ProtectionParams,
+    valuation: &Valuation,
+) -> Option<ProtectionTrigger> {
+    let value = valuation.value_lamports as u128;
+    let drawdown_floor = valuation.peak_value_lamports as u128
+        * (BPS_DENOMINATOR - params.max_drawdown_bps as u64) as u128
+        / BPS_DENOMINATOR as u128;
+    if params.stop_loss_lamports > 0 && value < params.stop_loss_lamports as u128 {
+        Some(ProtectionTrigger::StopLoss)
+    } else if params.max_drawdown_bps > 0 && value < drawdown_floor {
+        Some(ProtectionTrigger::MaxDrawdown)
+    } else if params.take_profit_lamports > 0 && value >= params.take_profit_lamports as u128 {
+        Some(ProtectionTrigger::TakeProfit)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(protocol: u8, asset: u8, target_weight_bps: u16) -> Allocation {
+        Allocation {
+            protocol,
+            asset,
+            target_weight_bps,
+        }
+    }
+
+    fn params() -> ProtectionParams {
+        ProtectionParams {
+            stop_loss_lamports: 800,
+            max_drawdown_bps: 2000,
+            take_profit_lamports: 1500,
+            safe_protocol: 1,
+            safe_asset: 1,
+        }
+    }
+
+    fn order(allocations: &[Allocation], prices: &[u64], value: u64) -> ProtectiveOrder {
+        ProtectiveOrder {
+            owner: Pubkey::new_unique(),
+            strategy: Pubkey::new_unique(),
+            params: params(),
+            marked_value_lamports: value,
+            marked_principal_lamports: value,
+            marks: price_marks(allocations, prices),
+            marked_at: 0,
+            created_at: 0,
+            bump: 255,
+        }
+    }
+
+    // 测试1: 保护单参数校验
+    #[test]
+    fn test_validate_protection() {
+        assert!(validate_protection(&params()).is_ok());
+        assert!(validate_protection(&ProtectionParams {
+            stop_loss_lamports: 0,
+            max_drawdown_bps: 0,
+            ..params()
+        })
+        .is_ok());
+
+        let invalid = [
+            ProtectionParams {
+                stop_loss_lamports: 0,
+                max_drawdown_bps: 0,
+                take_profit_lamports: 0,
+                ..params()
+            },
+            ProtectionParams {
+                max_drawdown_bps: 10000,
+                ..params()
+            },
+            ProtectionParams {
+                stop_loss_lamports: 1500,
+                ..params()
+            },
+            ProtectionParams {
+                safe_protocol: 200,
+                ..params()
+            },
+        ];
+        for params in invalid {
+            assert_eq!(
+                validate_protection(&params).unwrap_err(),
+                ErrorCode::InvalidProtectionParams.into()
+            );
+        }
+    }
+
+    // 测试2: 按当前权重和价格变化估值，峰值随估值上升
+    #[test]
+    fn test_revalue_prices() {
+        let allocations = [entry(0, 0, 6000), entry(1, 1, 4000)];
+        let order = order(&allocations, &[100, 1_000], 1000);
+
+        let valuation = revalue(&order, &allocations, &[150, 1_000], 1000, 1000).unwrap();
+        assert_eq!(valuation.value_lamports, 1300);
+        assert_eq!(valuation.peak_value_lamports, 1300);
+
+        let valuation = revalue(&order, &allocations, &[50, 1_000], 1000, 1300).unwrap();
+        assert_eq!(valuation.value_lamports, 700);
+        assert_eq!(valuation.peak_value_lamports, 1300);
+
+        // 新加入的分配项视为价格不变
+        let changed = [entry(0, 0, 5000), entry(2, 2, 5000)];
+        let valuation = revalue(&order, &changed, &[200, 7], 1000, 1000).unwrap();
+        assert_eq!(valuation.value_lamports, 1500);
+    }
+
+    // 测试3: 存取款按同一比例调整估值和峰值，不会被当成盈亏
+    #[test]
+    fn test_revalue_principal_changes() {
+        let allocations = [entry(0, 0, 10000)];
+        let order = order(&allocations, &[100], 1000);
+
+        let valuation = revalue(&order, &allocations, &[100], 500, 1200).unwrap();
+        assert_eq!(valuation.value_lamports, 500);
+        assert_eq!(valuation.peak_value_lamports, 600);
+        // 取出一半后止损线仍按绝对值比较
+        assert_eq!(
+            check_triggers(&params(), &valuation),
+            Some(ProtectionTrigger::StopLoss)
+        );
+
+        let emptied = ProtectiveOrder {
+            marked_principal_lamports: 0,
+            ..order
+        };
+        let valuation = revalue(&emptied, &allocations, &[50], 700, 0).unwrap();
+        assert_eq!(valuation.value_lamports, 700);
+        assert_eq!(valuation.peak_value_lamports, 700);
+    }
+
+    // 测试4: 触发条件的顺序和边界
+    #[test]
+    fn test_check_triggers() {
+        let valuation = |value_lamports, peak_value_lamports| Valuation {
+            value_lamports,
+            peak_value_lamports,
+        };
+        assert_eq!(check_triggers(&params(), &valuation(1000, 1000)), None);
+        assert_eq!(check_triggers(&params(), &valuation(800, 1000)), None);
+        assert_eq!(
+            check_triggers(&params(), &valuation(799, 1000)),
+            Some(ProtectionTrigger::StopLoss)
+        );
+        assert_eq!(check_triggers(&params(), &valuation(1120, 1400)), None);
+        assert_eq!(
+            check_triggers(&params(), &valuation(1119, 1400)),
+            Some(ProtectionTrigger::MaxDrawdown)
+        );
+        assert_eq!(
+            check_triggers(&params(), &valuation(1500, 1500)),
+            Some(ProtectionTrigger::TakeProfit)
+        );
+
+        let take_profit_only = ProtectionParams {
+            stop_loss_lamports: 0,
+            max_drawdown_bps: 0,
+            ..params()
+        };
+        assert_eq!(check_triggers(&take_profit_only, &valuation(1, 1400)), None);
+    }
+}
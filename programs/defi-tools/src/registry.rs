@@ -0,0 +1,200 @@
+use crate::errors::ErrorCode;
+use crate::oracle::GuardedPrice;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 已注册的资产及其通过校验的价格
+pub struct PricedAsset<'info> {
+    pub registry: Account<'info, AssetRegistry>,
+    pub price: GuardedPrice,
+}
+
+impl AssetParams {
+    // 校验注册参数
+    pub fn validate(&self) -> Result<()> {
+        if self.mint == Pubkey::default()
+            || self.oracle == Pubkey::default()
+            || self.oracle == self.secondary_oracle
+            || self.max_allocation_bps == 0
+            || self.max_allocation_bps > 10000
+        {
+            return Err(ErrorCode::InvalidAssetConfig.into());
+        }
+        Ok(())
+    }
+}
+
+impl AssetRegistry {
+    pub fn apply(&mut self, params: &AssetParams) {
+        self.mint = params.mint;
+        self.decimals = params.decimals;
+        self.oracle = params.oracle;
+        self.secondary_oracle = params.secondary_oracle;
+        self.enabled = params.enabled;
+        self.max_allocation_bps = params.max_allocation_bps;
+        self.deposit_cap_lamports = params.deposit_cap_lamports;
+    }
+
+    pub fn has_secondary_oracle(&self) -> bool {
+        self.secondary_oracle != Pubkey::default()
+    }
+
+    // 检查分配项是否可以使用该资产
+    pub fn check_allocation(&self, allocation: &Allocation) -> Result<()> {
+        if allocation.asset != self.asset_id {
+            return Err(ErrorCode::AssetNotRegistered.into());
+        }
+        if !self.enabled {
+            return Err(ErrorCode::AssetDisabled.into());
+        }
+        if allocation.target_weight_bps > self.max_allocation_bps {
+            return Err(ErrorCode::AssetAllocationCapExceeded.into());
+        }
+        Ok(())
+    }
+}
+
+// 读取资产注册表账户，并确认它对应指定的资产ID
+pub fn load_asset<'info>(
+    account: &'info AccountInfo<'info>,
+    asset_id: u8,
+) -> Result<Account<'info, AssetRegistry>> {
+    let registry = Account::<AssetRegistry>::try_from(account)
+        .map_err(|_| error!(ErrorCode::AssetNotRegistered))?;
+    if registry.asset_id != asset_id {
+        return Err(ErrorCode::AssetNotRegistered.into());
+    }
+    Ok(registry)
+}
+
+// 按分配项顺序读取 [资产注册表, 主价格源, (备用价格源)]，
+// 价格源必须是注册表中登记的账户，且全部通过预言机校验
+pub fn load_asset_prices<'info>(
+    oracle_guard: &OracleGuardConfig,
+    allocations: &[Allocation],
+    accounts: &'info [AccountInfo<'info>],
+    now: i64,
+) -> Result<Vec<PricedAsset<'info>>> {
+    let group_len = 1 + oracle_guard.accounts_per_price();
+    if accounts.len() != allocations.len() * group_len {
+        return Err(ErrorCode::InvalidOracleAccount.into());
+    }
+
+    allocations
+        .iter()
+        .zip(accounts.chunks(group_len))
+        .map(|(allocation, group)| {
+            let registry = load_asset(&group[0], allocation.asset)?;
+
+            require_keys_eq!(
+                group[1].key(),
+                registry.oracle,
+                ErrorCode::InvalidOracleAccount
+            );
+            let secondary = group.get(2);
+            if let Some(secondary) = secondary {
+                require!(
+                    registry.has_secondary_oracle(),
+                    ErrorCode::InvalidOracleAccount
+                );
+                require_keys_eq!(
+                    secondary.key(),
+                    registry.secondary_oracle,
+                    ErrorCode::InvalidOracleAccount
+                );
+            }
+
+            let price = oracle_guard.load_guarded(&group[1], secondary, now)?;
+            Ok(PricedAsset { registry, price })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> AssetRegistry {
+        AssetRegistry {
+            asset_id: 1,
+            mint: Pubkey::new_unique(),
+            decimals: 6,
+            oracle: Pubkey::new_unique(),
+            secondary_oracle: Pubkey::default(),
+            enabled: true,
+            max_allocation_bps: 6000,
+            deposit_cap_lamports: 0,
+            deposited_lamports: 0,
+            bump: 255,
+        }
+    }
+
+    fn allocation(asset: u8, target_weight_bps: u16) -> Allocation {
+        Allocation {
+            protocol: 0,
+            asset,
+            target_weight_bps,
+        }
+    }
+
+    // 测试1: 已启用且未超过上限的分配通过
+    #[test]
+    fn test_check_allocation_success() {
+        assert!(registry().check_allocation(&allocation(1, 6000)).is_ok());
+    }
+
+    // 测试2: 资产ID不匹配
+    #[test]
+    fn test_check_allocation_wrong_asset() {
+        let err = registry()
+            .check_allocation(&allocation(2, 1000))
+            .unwrap_err();
+        assert_eq!(err, ErrorCode::AssetNotRegistered.into());
+    }
+
+    // 测试3: 资产已停用
+    #[test]
+    fn test_check_allocation_disabled() {
+        let mut registry = registry();
+        registry.enabled = false;
+        let err = registry.check_allocation(&allocation(1, 1000)).unwrap_err();
+        assert_eq!(err, ErrorCode::AssetDisabled.into());
+    }
+
+    // 测试4: 超过资产的分配上限
+    #[test]
+    fn test_check_allocation_cap_exceeded() {
+        let err = registry()
+            .check_allocation(&allocation(1, 6001))
+            .unwrap_err();
+        assert_eq!(err, ErrorCode::AssetAllocationCapExceeded.into());
+    }
+
+    // 测试5: 注册参数校验
+    #[test]
+    fn test_asset_params_validate() {
+        let registry = registry();
+        let params = AssetParams {
+            mint: registry.mint,
+            decimals: registry.decimals,
+            oracle: registry.oracle,
+            secondary_oracle: Pubkey::default(),
+            enabled: true,
+            max_allocation_bps: 10000,
+            deposit_cap_lamports: 0,
+        };
+        assert!(params.validate().is_ok());
+
+        let mut invalid = params;
+        invalid.max_allocation_bps = 10001;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = params;
+        invalid.secondary_oracle = params.oracle;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = params;
+        invalid.oracle = Pubkey::default();
+        assert!(invalid.validate().is_err());
+    }
+}
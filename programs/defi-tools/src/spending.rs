@@ -0,0 +1,219 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 周期提款限额。直接提款和领取工单转出的金额计入当前窗口，窗口到期后重新计数；
+// 监护人联署的提款不受限制也不计入。启用限额时必须设置监护人，只有收紧限额可以由所有者单独完成
+
+// 窗口长度上限(30天)
+pub const MAX_SPENDING_WINDOW_SECONDS: u32 = 30 * 24 * 60 * 60;
+
+impl SpendingLimit {
+    pub fn is_enabled(&self) -> bool {
+        self.max_lamports > 0
+    }
+
+    pub fn has_guardian(&self) -> bool {
+        self.guardian != Pubkey::default()
+    }
+
+    // 新限额是否不比当前宽松: 上限不提高、窗口不缩短且监护人不变
+    pub fn is_tighter_than(&self, current: &SpendingLimit) -> bool {
+        self.is_enabled()
+            && (!current.is_enabled() || self.max_lamports <= current.max_lamports)
+            && self.window_seconds >= current.window_seconds
+            && self.guardian == current.guardian
+    }
+
+    // 计入一笔转出金额，窗口到期时从当前时间开始新窗口
+    pub fn record(&mut self, amount: u64, now: i64) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let window_end = self
+            .window_start
+            .checked_add(i64::from(self.window_seconds))
+            .ok_or(ErrorCode::MathError)?;
+        if now >= window_end {
+            self.window_start = now;
+            self.spent_lamports = 0;
+        }
+        let spent = self
+            .spent_lamports
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathError)?;
+        if spent > self.max_lamports {
+            return Err(ErrorCode::SpendingLimitExceeded.into());
+        }
+        self.spent_lamports = spent;
+        Ok(())
+    }
+
+    // 当前窗口内剩余的额度，不限制时为 None
+    pub fn remaining(&self, now: i64) -> Option<u64> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let window_end = self
+            .window_start
+            .saturating_add(i64::from(self.window_seconds));
+        if now >= window_end {
+            return Some(self.max_lamports);
+        }
+        Some(self.max_lamports.saturating_sub(self.spent_lamports))
+    }
+}
+
+// 上限为0时不限制，窗口也必须为0；否则窗口在1秒到30天之间，且必须设置监护人，
+// 否则所有者可以单独放宽或取消限额。监护人不能是所有者本人
+pub fn validate_spending_limit(
+    owner: &Pubkey,
+    max_lamports: u64,
+    window_seconds: u32,
+    guardian: &Pubkey,
+) -> Result<()> {
+    let window_valid = if max_lamports == 0 {
+        window_seconds == 0
+    } else {
+        window_seconds > 0
+            && window_seconds <= MAX_SPENDING_WINDOW_SECONDS
+            && *guardian != Pubkey::default()
+    };
+    if !window_valid || guardian == owner {
+        return Err(ErrorCode::InvalidSpendingLimit.into());
+    }
+    Ok(())
+}
+
+// 放宽限额或更换监护人需要当前监护人签名
+pub fn authorize_limit_change(
+    current: &SpendingLimit,
+    new: &SpendingLimit,
+    guardian: Option<&Pubkey>,
+) -> Result<()> {
+    if !current.has_guardian() || new.is_tighter_than(current) {
+        return Ok(());
+    }
+    match guardian {
+        Some(guardian) if *guardian == current.guardian => Ok(()),
+        _ => Err(ErrorCode::GuardianApprovalRequired.into()),
+    }
+}
+
+// 提款时校验限额。提供了监护人签名时必须是该用户的监护人，此时不计入限额
+pub fn spend(
+    limit: &mut SpendingLimit,
+    amount: u64,
+    guardian: Option<&Pubkey>,
+    now: i64,
+) -> Result<()> {
+    match guardian {
+        Some(guardian) if limit.has_guardian() && *guardian == limit.guardian => Ok(()),
+        Some(_) => Err(ErrorCode::Unauthorized.into()),
+        None => limit.record(amount, now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(max_lamports: u64, window_seconds: u32, guardian: Pubkey) -> SpendingLimit {
+        SpendingLimit {
+            max_lamports,
+            window_seconds,
+            guardian,
+            ..SpendingLimit::default()
+        }
+    }
+
+    // 测试1: 窗口内累计不超过上限，窗口到期后重新计数
+    #[test]
+    fn test_record_rolls_window() {
+        let mut limit = limit(100, 10, Pubkey::default());
+        limit.record(60, 0).unwrap();
+        limit.record(40, 5).unwrap();
+        assert_eq!(
+            limit.record(1, 9).unwrap_err(),
+            ErrorCode::SpendingLimitExceeded.into()
+        );
+        assert_eq!(limit.remaining(9), Some(0));
+        assert_eq!(limit.remaining(10), Some(100));
+
+        limit.record(100, 10).unwrap();
+        assert_eq!(limit.window_start, 10);
+        assert_eq!(limit.spent_lamports, 100);
+
+        let mut unlimited = SpendingLimit::default();
+        unlimited.record(u64::MAX, 0).unwrap();
+        assert_eq!(unlimited.remaining(0), None);
+    }
+
+    // 测试2: 只有收紧限额不需要监护人签名
+    #[test]
+    fn test_authorize_limit_change() {
+        let guardian = Pubkey::new_unique();
+        let current = limit(100, 3600, guardian);
+
+        for new in [limit(50, 3600, guardian), limit(100, 7200, guardian)] {
+            assert!(authorize_limit_change(&current, &new, None).is_ok());
+        }
+        for new in [
+            limit(200, 3600, guardian),
+            limit(100, 60, guardian),
+            limit(0, 0, guardian),
+            limit(50, 3600, Pubkey::default()),
+        ] {
+            assert_eq!(
+                authorize_limit_change(&current, &new, None).unwrap_err(),
+                ErrorCode::GuardianApprovalRequired.into()
+            );
+            assert!(authorize_limit_change(&current, &new, Some(&guardian)).is_ok());
+        }
+
+        // 未设置监护人时所有者可以任意修改
+        let unguarded = limit(0, 0, Pubkey::default());
+        assert!(authorize_limit_change(&unguarded, &limit(100, 60, guardian), None).is_ok());
+    }
+
+    // 测试3: 监护人联署的提款不计入限额，其他签名人被拒绝
+    #[test]
+    fn test_spend_with_guardian() {
+        let guardian = Pubkey::new_unique();
+        let mut limit = limit(100, 3600, guardian);
+        spend(&mut limit, 1_000, Some(&guardian), 0).unwrap();
+        assert_eq!(limit.spent_lamports, 0);
+        assert_eq!(
+            spend(&mut limit, 1, Some(&Pubkey::new_unique()), 0).unwrap_err(),
+            ErrorCode::Unauthorized.into()
+        );
+        assert_eq!(
+            spend(&mut limit, 101, None, 0).unwrap_err(),
+            ErrorCode::SpendingLimitExceeded.into()
+        );
+    }
+
+    // 测试4: 限额参数校验
+    #[test]
+    fn test_validate_spending_limit() {
+        let owner = Pubkey::new_unique();
+        let none = Pubkey::default();
+        let guardian = Pubkey::new_unique();
+        assert!(validate_spending_limit(&owner, 0, 0, &none).is_ok());
+        assert!(validate_spending_limit(&owner, 0, 0, &guardian).is_ok());
+        assert!(validate_spending_limit(&owner, 1, MAX_SPENDING_WINDOW_SECONDS, &guardian).is_ok());
+        for (max, window, guardian) in [
+            (0, 60, none),
+            (1, 0, guardian),
+            (1, MAX_SPENDING_WINDOW_SECONDS + 1, guardian),
+            (1, 60, owner),
+            // 启用限额但没有监护人
+            (1, 60, none),
+        ] {
+            assert_eq!(
+                validate_spending_limit(&owner, max, window, &guardian).unwrap_err(),
+                ErrorCode::InvalidSpendingLimit.into()
+            );
+        }
+    }
+}
@@ -17,6 +17,20 @@ pub struct UserProfile {
     pub total_value_lamports: u64,
     // 是否暂停(紧急状态)
     pub is_paused: bool,
+    // 是否启用提款地址白名单
+    pub allowlist_enabled: bool,
+    // 白名单关闭生效的时间戳，0表示没有待生效的关闭
+    pub allowlist_disable_at: i64,
+    // 周期提款限额
+    pub spending_limit: SpendingLimit,
+    // 待接受的新所有者，默认值表示没有待处理的转移
+    pub pending_owner: Pubkey,
+    // 新所有者可以接受转移的时间戳
+    pub pending_owner_at: i64,
+    // 已接受转移的新所有者。设置后配置冻结，策略只能迁移到新所有者名下
+    pub migrated_to: Pubkey,
+    // 转移前的所有者，默认值表示不是由转移创建的配置
+    pub migrated_from: Pubkey,
 }
 
 // 策略配置账户
@@ -36,9 +50,32 @@ pub struct StrategyConfig {
     pub last_executed_at: i64,
     // 最大允许滑点(基点)
     pub max_slippage_bps: u16,
+    // 策略总价值 (lamports)
+    pub total_value_lamports: u64,
+    // 上次计提管理费的时间戳
+    pub fees_accrued_at: i64,
+    // 排队提款的工单计数和锁定金额
+    pub withdrawal_queue: WithdrawalQueue,
+    // 分配项最后一次变化的时间戳，跟随者据此判断领投策略是否调整
+    pub allocations_updated_at: i64,
+    // 保护单估值的峰值(lamports)，随存取款同比例调整，没有保护单时为0
+    pub peak_value_lamports: u64,
+    // 保护单触发后暂停的时间戳，0表示未暂停
+    pub paused_at: i64,
+    // 计入各资产累计存款的金额，提款按比例扣回
+    pub asset_deposits: Vec<AssetDeposit>,
+    // 自动调整分配项的模式，同一时间只能启用一种
+    pub mode: StrategyMode,
+    // 未撤销的代理人授权数量
+    pub delegates: u16,
+    // 经过迁移的策略最初创建时的账户地址，默认值表示未迁移过。跟投关系以该地址标识领投策略
+    pub origin: Pubkey,
+    // 保护单触发后尚未按安全头寸再平衡，此时暂停的策略仍可再平衡一次
+    pub unwind_pending: bool,
 }
 
-// 头寸追踪账户
+// 头寸追踪账户。按协议和资产记录市场利率时(种子 [b"apy", 协议, 资产]，由管理员报告)
+// strategy_id 和头寸价值为0
 #[account]
 pub struct PositionTracker {
     // 关联策略ID
@@ -74,19 +111,386 @@ pub struct ProtocolRegistry {
     pub risk_score: u8,
 }
 
+// 全局配置账户(管理员维护)
+#[account]
+pub struct GlobalConfig {
+    // 管理员地址
+    pub admin: Pubkey,
+    // 预言机价格校验参数
+    pub oracle_guard: OracleGuardConfig,
+    // 管理费和业绩费费率
+    pub fees: FeeConfig,
+    // 接收费用的账户
+    pub treasury: Pubkey,
+    // 存款金额和上限
+    pub deposit_limits: DepositLimits,
+    // 提款工单的锁定时间(秒)，到期后即使头寸未解除也可领取
+    pub withdrawal_delay_seconds: u32,
+    // 新增白名单地址和关闭白名单的生效延迟(秒)
+    pub allowlist_delay_seconds: u32,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 预言机价格校验参数
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct OracleGuardConfig {
+    // 价格最大允许年龄(秒)
+    pub max_price_age_seconds: u32,
+    // 置信区间相对价格的最大比例(基点)
+    pub max_confidence_bps: u16,
+    // 两个价格源之间的最大偏差(基点)，0表示不做交叉校验
+    pub max_divergence_bps: u16,
+}
+
+// 策略费率
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct FeeConfig {
+    // 年化管理费(基点)，按时间计提
+    pub management_fee_bps: u16,
+    // 业绩费(基点)，按超过高水位的收益计提。只用于链下回测，链上必须为0
+    pub performance_fee_bps: u16,
+}
+
+// 存款限制，上限为0表示不限制
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DepositLimits {
+    // 单笔存款最小金额(lamports)
+    pub min_deposit_lamports: u64,
+    // 单个策略总价值上限(lamports)
+    pub max_strategy_value_lamports: u64,
+    // 单个用户总价值上限(lamports)
+    pub max_user_value_lamports: u64,
+}
+
+// 策略的提款队列，工单按ID先后领取
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct WithdrawalQueue {
+    // 下一个工单ID
+    pub next_ticket_id: u64,
+    // 队首工单ID，之前的工单都已领取或取消
+    pub head_ticket_id: u64,
+    // 该ID之前的工单对应的头寸已在再平衡中解除
+    pub unwound_ticket_id: u64,
+    // 已从策略价值中扣除、等待领取的金额(lamports)
+    pub locked_lamports: u64,
+}
+
+// 周期提款限额。每个窗口内转出的金额不超过上限，监护人联署的提款不受限制
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct SpendingLimit {
+    // 每个窗口内最多转出的金额(lamports)，0表示不限制
+    pub max_lamports: u64,
+    // 窗口长度(秒)
+    pub window_seconds: u32,
+    // 当前窗口开始的时间戳
+    pub window_start: i64,
+    // 当前窗口内已转出的金额(lamports)
+    pub spent_lamports: u64,
+    // 监护人地址，可批准超限提款和放宽限额；默认值表示未设置
+    pub guardian: Pubkey,
+}
+
+// 提款工单(每个工单一个PDA，领取或取消后关闭)
+#[account]
+pub struct WithdrawalTicket {
+    // 工单所有者
+    pub owner: Pubkey,
+    // 关联策略账户
+    pub strategy: Pubkey,
+    // 工单ID(策略内递增)
+    pub ticket_id: u64,
+    // 锁定的提款金额(lamports)
+    pub amount_lamports: u64,
+    // 申请时间戳
+    pub requested_at: i64,
+    // 锁定到期时间戳
+    pub unlocks_at: i64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 提款白名单地址(每个用户、地址一个PDA，移除后关闭)
+#[account]
+pub struct WithdrawalDestination {
+    // 用户钱包地址
+    pub owner: Pubkey,
+    // 允许接收提款的地址
+    pub destination: Pubkey,
+    // 添加时间戳
+    pub added_at: i64,
+    // 生效时间戳，之前不能向该地址提款
+    pub activates_at: i64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 策略代理人(每个策略、代理人一个PDA，撤销后关闭)
+#[account]
+pub struct StrategyDelegate {
+    // 策略所有者
+    pub owner: Pubkey,
+    // 关联策略账户
+    pub strategy: Pubkey,
+    // 代理人地址
+    pub delegate: Pubkey,
+    // 授权范围
+    pub scope: DelegateScope,
+    // 授权到期时间戳，到期后代理人不能再操作
+    pub expires_at: i64,
+    // 授权时各分配项的权重(基点)，代理人每次更新的权重变化都相对该基准计算
+    pub baseline_weights_bps: Vec<u16>,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 代理人的授权范围。代理人不能存取资金，也不能领取或取消提款工单
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DelegateScope {
+    // 允许执行策略
+    pub execute: bool,
+    // 允许再平衡头寸
+    pub rebalance: bool,
+    // 允许更新再平衡条件、滑点和分配项权重，不能更改分配项的协议和资产
+    pub update: bool,
+    // 更新时每个分配项权重的最大变化(基点)
+    pub max_weight_change_bps: u16,
+    // 更新时可设置的最大滑点(基点)
+    pub max_slippage_bps: u16,
+}
+
+// 策略模板(每个发布者、模板ID一个PDA，停用后关闭)
+#[account]
+pub struct StrategyTemplate {
+    // 发布者
+    pub publisher: Pubkey,
+    // 发布者自选的模板ID
+    pub template_id: u64,
+    // 资产分配(协议,资产,权重)
+    pub allocations: Vec<Allocation>,
+    // 再平衡触发条件
+    pub rebalance_condition: RebalanceCondition,
+    // 最大允许滑点(基点)
+    pub max_slippage_bps: u16,
+    // 模板风险等级(1-5)，取发布者声明的等级和按分配项计算的等级中较高者，用户的风险等级不低于该值才能使用
+    pub risk_level: u8,
+    // 链下策略说明的哈希
+    pub description_hash: [u8; 32],
+    // 发布时间戳
+    pub created_at: i64,
+    // 按模板创建的策略数量
+    pub strategies_created: u64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 跟随关系(每个跟随策略一个PDA，取消跟随后关闭)
+#[account]
+pub struct StrategyFollow {
+    // 跟随策略的所有者
+    pub owner: Pubkey,
+    // 跟随策略账户
+    pub strategy: Pubkey,
+    // 领投策略的原始地址，领投策略迁移后不变
+    pub leader: Pubkey,
+    // 同步时单个分配项的最大权重(基点)，领投策略超出时不同步
+    pub max_weight_bps: u16,
+    // 领投策略调整分配项后，需等待该时长才能同步(秒)
+    pub delay_seconds: u32,
+    // 每次同步按跟随策略价值支付给领投者的费用(基点)
+    pub leader_fee_bps: u16,
+    // 开始跟随的时间戳
+    pub followed_at: i64,
+    // 最后一次同步时领投策略的分配项变化时间戳
+    pub leader_synced_at: i64,
+    // 最后一次同步的时间戳
+    pub synced_at: i64,
+    // 已同步次数
+    pub syncs: u64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 定投计划(每个策略一个PDA，取消时关闭)
+#[account]
+pub struct DcaSchedule {
+    // 策略所有者
+    pub owner: Pubkey,
+    // 定投策略账户
+    pub strategy: Pubkey,
+    // 定投参数
+    pub params: DcaParams,
+    // 创建时间戳
+    pub created_at: i64,
+    // 累计从报价分配项转移到目标分配项的价值(lamports)
+    pub purchased_lamports: u64,
+    // 已执行次数
+    pub purchases: u64,
+    // 不足1基点而留到下次执行的增量(lamports)
+    pub carried_lamports: u64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 定投参数。报价和目标分配项以(协议, 资产)标识
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DcaParams {
+    // 出资的报价分配项
+    pub quote_protocol: u8,
+    pub quote_asset: u8,
+    // 买入的目标分配项，策略中没有时在第一次执行时添加
+    pub target_protocol: u8,
+    pub target_asset: u8,
+    // 每次从报价分配项转移到目标分配项的价值(lamports)
+    pub increment_lamports: u64,
+    // 目标资产每单位的最高价格(QUOTE_DECIMALS精度)，0表示不限制
+    pub max_price_quote: u64,
+}
+
+// 止损止盈保护单(每个策略一个PDA，取消或触发时关闭)
+#[account]
+pub struct ProtectiveOrder {
+    // 策略所有者
+    pub owner: Pubkey,
+    // 受保护的策略账户
+    pub strategy: Pubkey,
+    // 触发条件和安全资产
+    pub params: ProtectionParams,
+    // 上次估值得到的策略价值(lamports)
+    pub marked_value_lamports: u64,
+    // 上次估值时策略的账面价值，用于按存取款同比例调整估值
+    pub marked_principal_lamports: u64,
+    // 上次估值时各分配项的价格
+    pub marks: Vec<PriceMark>,
+    // 上次估值时间戳
+    pub marked_at: i64,
+    // 创建时间戳
+    pub created_at: i64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 保护单参数。三个触发条件至少启用一个
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ProtectionParams {
+    // 估值低于该值时止损(lamports)，0表示不启用
+    pub stop_loss_lamports: u64,
+    // 估值从峰值回撤超过该比例时止损(基点)，0表示不启用
+    pub max_drawdown_bps: u16,
+    // 估值达到该值时止盈(lamports)，0表示不启用
+    pub take_profit_lamports: u64,
+    // 触发后全部权重转入的安全头寸
+    pub safe_protocol: u8,
+    pub safe_asset: u8,
+}
+
+// 分配项在估值时的价格(QUOTE_DECIMALS精度)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PriceMark {
+    pub protocol: u8,
+    pub asset: u8,
+    pub price_quote: u64,
+}
+
+// 保护单的触发原因
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtectionTrigger {
+    StopLoss,
+    MaxDrawdown,
+    TakeProfit,
+}
+
+// 收益率轮动计划(每个策略一个PDA，取消时关闭)
+#[account]
+pub struct YieldRotation {
+    // 策略所有者
+    pub owner: Pubkey,
+    // 轮动的策略账户
+    pub strategy: Pubkey,
+    // 轮动参数
+    pub params: YieldParams,
+    // 轮动头寸进入当前协议的时间戳
+    pub held_since: i64,
+    // 已轮动次数
+    pub rotations: u64,
+    // 创建时间戳
+    pub created_at: i64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 收益率轮动参数
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct YieldParams {
+    // 轮动头寸的资产
+    pub asset: u8,
+    // 候选借贷协议的位掩码，第i位对应协议i，至少两个
+    pub candidate_protocols: u8,
+    // 新协议的APY至少比当前协议高出的基点数
+    pub min_apy_advantage_bps: u32,
+    // 在当前协议的最短持有时间(秒)
+    pub min_hold_seconds: u32,
+}
+
+// 资产注册表(每个资产一个PDA，由管理员维护)
+#[account]
+pub struct AssetRegistry {
+    // 资产ID(对应Allocation.asset)
+    pub asset_id: u8,
+    // 代币Mint地址
+    pub mint: Pubkey,
+    // 代币精度
+    pub decimals: u8,
+    // 主价格源账户
+    pub oracle: Pubkey,
+    // 备用价格源账户(用于交叉校验，未配置时为默认值)
+    pub secondary_oracle: Pubkey,
+    // 是否允许新的分配
+    pub enabled: bool,
+    // 单个分配项的最大权重(基点)
+    pub max_allocation_bps: u16,
+    // 全部策略按目标权重分配到该资产的存款上限(lamports)，0表示不限制
+    pub deposit_cap_lamports: u64,
+    // 按目标权重分配到该资产的累计净存款(lamports)
+    pub deposited_lamports: u64,
+    // PDA的bump种子
+    pub bump: u8,
+}
+
+// 资产注册参数
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AssetParams {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub oracle: Pubkey,
+    pub secondary_oracle: Pubkey,
+    pub enabled: bool,
+    pub max_allocation_bps: u16,
+    pub deposit_cap_lamports: u64,
+}
+
+// 策略计入某个资产累计存款的金额
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct AssetDeposit {
+    // 资产ID
+    pub asset: u8,
+    // 尚未扣回的存款(lamports)
+    pub lamports: u64,
+}
+
 // 资产分配项
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub struct Allocation {
-    // 协议类型枚举
+    // 协议类型(Protocol枚举的u8值)
     pub protocol: u8,
-    // 资产类型枚举
+    // 资产ID(须在AssetRegistry中注册)
     pub asset: u8,
     // 目标权重(基点,总和应为10000)
     pub target_weight_bps: u16,
 }
 
 // 再平衡条件
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub struct RebalanceCondition {
     // 最小时间间隔(秒)
     pub time_interval_seconds: u64,
@@ -96,7 +500,31 @@ pub struct RebalanceCondition {
     pub auto_rebalance: bool,
 }
 
-// 协议类型枚举(采用u8表示)
+// 策略的自动调整模式。跟随、定投和收益率轮动都会改写分配项，互相覆盖，
+// 启用一种前需先取消当前模式
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StrategyMode {
+    // 只由所有者和代理人调整
+    #[default]
+    Manual,
+    Following,
+    Dca,
+    YieldRotation,
+}
+
+impl StrategyConfig {
+    // 启用自动调整模式，策略必须处于手动模式
+    pub fn enter_mode(&mut self, mode: StrategyMode) -> Result<()> {
+        if self.mode != StrategyMode::Manual {
+            return Err(crate::errors::ErrorCode::StrategyModeConflict.into());
+        }
+        self.mode = mode;
+        Ok(())
+    }
+}
+
+// 协议类型枚举(Allocation中采用u8表示，通过TryFrom校验)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Protocol {
     Raydium = 0,
     Solend = 1,
@@ -104,9 +532,23 @@ pub enum Protocol {
     Mango = 3,
 }
 
-// 资产类型枚举(采用u8表示)
-pub enum Asset {
-    SOL = 0,
-    USDC = 1,
-    USDT = 2,
+impl TryFrom<u8> for Protocol {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Protocol::Raydium),
+            1 => Ok(Protocol::Solend),
+            2 => Ok(Protocol::Orca),
+            3 => Ok(Protocol::Mango),
+            _ => Err(crate::errors::ErrorCode::UnknownProtocol.into()),
+        }
+    }
+}
+
+impl Allocation {
+    // 已校验的协议类型
+    pub fn protocol(&self) -> Result<Protocol> {
+        Protocol::try_from(self.protocol)
+    }
 }
@@ -0,0 +1,192 @@
+use crate::errors::ErrorCode;
+use crate::planner::BPS_DENOMINATOR;
+use crate::registry::load_asset;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 策略模板。发布者把分配项、再平衡条件和滑点发布为链上账户，其他用户在一条指令内
+// 按模板创建自己的策略。参数在创建时复制到策略中，之后停用模板不影响已创建的策略。
+// 风险等级按分配项和资产注册表计算，发布者声明的等级只能提高，不能降低
+
+// 模板风险等级与用户风险等级的范围相同
+pub fn validate_template_risk(risk_level: u8) -> Result<()> {
+    if !(1..=5).contains(&risk_level) {
+        return Err(ErrorCode::InvalidRiskLevel.into());
+    }
+    Ok(())
+}
+
+// 单个分配项的风险等级：权重占资产权重上限的比例，每20%一级。
+// 管理员为风险较高的资产设置较低的权重上限，分配越接近上限风险越高
+pub fn allocation_risk_level(allocation: &Allocation, registry: &AssetRegistry) -> u8 {
+    if registry.max_allocation_bps == 0 {
+        return 5;
+    }
+    let usage_bps = u64::from(allocation.target_weight_bps) * BPS_DENOMINATOR
+        / u64::from(registry.max_allocation_bps);
+    (usage_bps.saturating_sub(1) / 2000 + 1).min(5) as u8
+}
+
+// 每个分配项按顺序对应一个资产注册表账户，按资产的当前状态校验分配项，
+// 返回分配项中最高的风险等级
+pub fn derive_template_risk<'info>(
+    allocations: &[Allocation],
+    registries: &'info [AccountInfo<'info>],
+) -> Result<u8> {
+    if registries.len() != allocations.len() {
+        return Err(ErrorCode::AssetNotRegistered.into());
+    }
+    let mut risk_level = 1;
+    for (allocation, account) in allocations.iter().zip(registries.iter()) {
+        let registry = load_asset(account, allocation.asset)?;
+        registry.check_allocation(allocation)?;
+        risk_level = risk_level.max(allocation_risk_level(allocation, &registry));
+    }
+    Ok(risk_level)
+}
+
+// 用户只能使用风险等级不高于自身风险等级的模板。
+// 发布后资产的权重上限可能被调低，按模板记录的等级和当前计算的等级中较高者检查
+pub fn check_template_risk(
+    template: &StrategyTemplate,
+    derived_risk_level: u8,
+    profile: &UserProfile,
+) -> Result<()> {
+    if template.risk_level.max(derived_risk_level) > profile.risk_level {
+        return Err(ErrorCode::TemplateRiskTooHigh.into());
+    }
+    Ok(())
+}
+
+// 模板PDA
+pub fn template_address(publisher: &Pubkey, template_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"template", publisher.as_ref(), &template_id.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(risk_level: u8) -> StrategyTemplate {
+        StrategyTemplate {
+            publisher: Pubkey::new_unique(),
+            template_id: 7,
+            allocations: vec![Allocation {
+                protocol: 0,
+                asset: 0,
+                target_weight_bps: 10000,
+            }],
+            rebalance_condition: RebalanceCondition {
+                time_interval_seconds: 3600,
+                max_deviation_bps: 500,
+                auto_rebalance: true,
+            },
+            max_slippage_bps: 100,
+            risk_level,
+            description_hash: [1; 32],
+            created_at: 0,
+            strategies_created: 0,
+            bump: 255,
+        }
+    }
+
+    fn profile(risk_level: u8) -> UserProfile {
+        UserProfile {
+            owner: Pubkey::new_unique(),
+            risk_level,
+            strategy_counter: 0,
+            vault_bump: 255,
+            last_activity: 0,
+            total_value_lamports: 0,
+            is_paused: false,
+            allowlist_enabled: false,
+            allowlist_disable_at: 0,
+            spending_limit: SpendingLimit::default(),
+            pending_owner: Pubkey::default(),
+            pending_owner_at: 0,
+            migrated_to: Pubkey::default(),
+            migrated_from: Pubkey::default(),
+        }
+    }
+
+    // 测试1: 模板风险等级必须在1-5范围内
+    #[test]
+    fn test_validate_template_risk() {
+        assert!(validate_template_risk(1).is_ok());
+        assert!(validate_template_risk(5).is_ok());
+        for risk_level in [0, 6] {
+            assert_eq!(
+                validate_template_risk(risk_level).unwrap_err(),
+                ErrorCode::InvalidRiskLevel.into()
+            );
+        }
+    }
+
+    // 测试2: 用户风险等级不低于模板记录的等级和当前计算的等级时才能使用
+    #[test]
+    fn test_check_template_risk() {
+        assert!(check_template_risk(&template(3), 1, &profile(3)).is_ok());
+        assert!(check_template_risk(&template(2), 2, &profile(5)).is_ok());
+        assert_eq!(
+            check_template_risk(&template(4), 1, &profile(3)).unwrap_err(),
+            ErrorCode::TemplateRiskTooHigh.into()
+        );
+        assert_eq!(
+            check_template_risk(&template(2), 4, &profile(3)).unwrap_err(),
+            ErrorCode::TemplateRiskTooHigh.into()
+        );
+    }
+
+    // 测试3: 模板地址由发布者和模板ID决定
+    #[test]
+    fn test_template_address() {
+        let publisher = Pubkey::new_unique();
+        assert_eq!(
+            template_address(&publisher, 1),
+            template_address(&publisher, 1)
+        );
+        assert_ne!(
+            template_address(&publisher, 1),
+            template_address(&publisher, 2)
+        );
+        assert_ne!(
+            template_address(&publisher, 1),
+            template_address(&Pubkey::new_unique(), 1)
+        );
+    }
+
+    // 测试4: 分配项的风险等级按权重占资产权重上限的比例每20%一级
+    #[test]
+    fn test_allocation_risk_level() {
+        let mut registry = AssetRegistry {
+            asset_id: 0,
+            mint: Pubkey::new_unique(),
+            decimals: 9,
+            oracle: Pubkey::new_unique(),
+            secondary_oracle: Pubkey::default(),
+            enabled: true,
+            max_allocation_bps: 10000,
+            deposit_cap_lamports: 0,
+            deposited_lamports: 0,
+            bump: 255,
+        };
+        let entry = |target_weight_bps| Allocation {
+            protocol: 0,
+            asset: 0,
+            target_weight_bps,
+        };
+        assert_eq!(allocation_risk_level(&entry(1000), &registry), 1);
+        assert_eq!(allocation_risk_level(&entry(2000), &registry), 1);
+        assert_eq!(allocation_risk_level(&entry(2001), &registry), 2);
+        assert_eq!(allocation_risk_level(&entry(6000), &registry), 3);
+        assert_eq!(allocation_risk_level(&entry(10000), &registry), 5);
+        // 同样的权重，资产的权重上限越低风险越高
+        registry.max_allocation_bps = 5000;
+        assert_eq!(allocation_risk_level(&entry(2000), &registry), 2);
+        assert_eq!(allocation_risk_level(&entry(5000), &registry), 5);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::errors::ErrorCode;
+use crate::planner::BPS_DENOMINATOR;
+use crate::registry::load_asset;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 策略参数校验。创建、更新策略以及其他写入分配项的路径都必须经过这里
+
+// 每个策略最多的分配项数量，StrategyConfig 按此预留账户空间
+pub const MAX_ALLOCATIONS: usize = 10;
+// 最大滑点 10%
+pub const MAX_SLIPPAGE_BPS: u16 = 1000;
+
+// 校验分配项: 数量在 1..=MAX_ALLOCATIONS 内，协议已知，每项权重在 1..=10000 内，
+// (协议, 资产)不重复，且权重在 u64 中用检查运算累加后恰好为 10000
+pub fn validate_allocations(allocations: &[Allocation]) -> Result<()> {
+    if allocations.is_empty() {
+        return Err(ErrorCode::InvalidAllocation.into());
+    }
+    if allocations.len() > MAX_ALLOCATIONS {
+        return Err(ErrorCode::TooManyAllocations.into());
+    }
+
+    let mut total_weight: u64 = 0;
+    for (index, allocation) in allocations.iter().enumerate() {
+        allocation.protocol()?;
+
+        if allocation.target_weight_bps == 0 {
+            return Err(ErrorCode::ZeroWeightAllocation.into());
+        }
+        if u64::from(allocation.target_weight_bps) > BPS_DENOMINATOR {
+            return Err(ErrorCode::AllocationWeightOutOfRange.into());
+        }
+
+        let duplicated = allocations[..index]
+            .iter()
+            .any(|other| other.protocol == allocation.protocol && other.asset == allocation.asset);
+        if duplicated {
+            return Err(ErrorCode::DuplicateAllocation.into());
+        }
+
+        total_weight = total_weight
+            .checked_add(u64::from(allocation.target_weight_bps))
+            .ok_or(ErrorCode::MathError)?;
+    }
+
+    if total_weight != BPS_DENOMINATOR {
+        return Err(ErrorCode::InvalidAllocation.into());
+    }
+    Ok(())
+}
+
+pub fn validate_slippage(max_slippage_bps: u16) -> Result<()> {
+    if max_slippage_bps > MAX_SLIPPAGE_BPS {
+        return Err(ErrorCode::InvalidSlippage.into());
+    }
+    Ok(())
+}
+
+// 不依赖链上账户的全部策略参数校验，链下工具(如回测)也使用它
+pub fn validate_strategy_params(allocations: &[Allocation], max_slippage_bps: u16) -> Result<()> {
+    validate_allocations(allocations)?;
+    validate_slippage(max_slippage_bps)
+}
+
+// 每个分配项按顺序对应一个资产注册表账户，资产必须已注册、已启用且未超过权重上限
+pub fn validate_allocation_assets<'info>(
+    allocations: &[Allocation],
+    registries: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    if registries.len() != allocations.len() {
+        return Err(ErrorCode::AssetNotRegistered.into());
+    }
+    for (allocation, account) in allocations.iter().zip(registries.iter()) {
+        load_asset(account, allocation.asset)?.check_allocation(allocation)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(protocol: u8, asset: u8, target_weight_bps: u16) -> Allocation {
+        Allocation {
+            protocol,
+            asset,
+            target_weight_bps,
+        }
+    }
+
+    // 测试1: 合法的分配项
+    #[test]
+    fn test_valid_allocations() {
+        let allocations = vec![entry(0, 0, 5000), entry(1, 0, 3000), entry(0, 1, 2000)];
+        assert!(validate_allocations(&allocations).is_ok());
+        assert!(validate_allocations(&[entry(3, 2, 10000)]).is_ok());
+    }
+
+    // 测试2: 未知协议
+    #[test]
+    fn test_unknown_protocol() {
+        let allocations = vec![entry(0, 0, 5000), entry(200, 1, 5000)];
+        let err = validate_allocations(&allocations).unwrap_err();
+        assert_eq!(err, ErrorCode::UnknownProtocol.into());
+    }
+
+    // 测试3: 重复的(协议, 资产)
+    #[test]
+    fn test_duplicate() {
+        let allocations = vec![entry(2, 1, 5000), entry(2, 1, 5000)];
+        let err = validate_allocations(&allocations).unwrap_err();
+        assert_eq!(err, ErrorCode::DuplicateAllocation.into());
+    }
+
+    // 测试4: 权重为0的分配项
+    #[test]
+    fn test_zero_weight() {
+        let allocations = vec![entry(0, 0, 10000), entry(1, 1, 0)];
+        let err = validate_allocations(&allocations).unwrap_err();
+        assert_eq!(err, ErrorCode::ZeroWeightAllocation.into());
+    }
+
+    // 测试5: 按 u16 累加会回绕到 10000 的权重组合 (65535 + 10001 = 75536)
+    #[test]
+    fn test_wrapping_sum_rejected() {
+        let allocations = vec![entry(0, 0, 65535), entry(1, 1, 10001)];
+        let err = validate_allocations(&allocations).unwrap_err();
+        assert_eq!(err, ErrorCode::AllocationWeightOutOfRange.into());
+
+        // 每项都在范围内但总和超过 10000
+        let allocations = vec![entry(0, 0, 10000), entry(1, 1, 10000)];
+        let err = validate_allocations(&allocations).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidAllocation.into());
+    }
+
+    // 测试6: 分配项为空或超过数量上限
+    #[test]
+    fn test_allocation_count() {
+        let err = validate_allocations(&[]).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidAllocation.into());
+
+        let mut allocations: Vec<Allocation> = (0..MAX_ALLOCATIONS as u8)
+            .map(|asset| entry(0, asset, 1000))
+            .collect();
+        assert!(validate_allocations(&allocations).is_ok());
+
+        allocations.push(entry(1, 0, 1));
+        let err = validate_allocations(&allocations).unwrap_err();
+        assert_eq!(err, ErrorCode::TooManyAllocations.into());
+    }
+
+    // 测试7: 滑点上限
+    #[test]
+    fn test_slippage() {
+        assert!(validate_slippage(MAX_SLIPPAGE_BPS).is_ok());
+        let err = validate_slippage(MAX_SLIPPAGE_BPS + 1).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidSlippage.into());
+
+        let err = validate_strategy_params(&[entry(0, 0, 10000)], 1001).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidSlippage.into());
+    }
+}
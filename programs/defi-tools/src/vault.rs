@@ -0,0 +1,88 @@
+use crate::errors::ErrorCode;
+use crate::events::FeesCharged;
+use crate::fees::accrue_management;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+// 资金库PDA的资金转出，提款和费用结算共用
+
+// 由资金库PDA签名，从资金库转出 lamports
+pub fn transfer_from_vault<'info>(
+    system_program: &Program<'info, System>,
+    vault: &SystemAccount<'info>,
+    to: AccountInfo<'info>,
+    user_profile: &UserProfile,
+    amount: u64,
+) -> Result<()> {
+    let vault_seeds: &[&[u8]] = &[
+        b"vault",
+        user_profile.owner.as_ref(),
+        &[user_profile.vault_bump],
+    ];
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to,
+            },
+            &[vault_seeds],
+        ),
+        amount,
+    )
+}
+
+// 计提策略到 now 为止的管理费，从策略和用户的记账中扣除，并由资金库支付给费用金库，返回费用金额。
+// 存款、提款和再平衡在改变策略价值之前都先调用它
+pub fn collect_fees<'info>(
+    fees: &FeeConfig,
+    user_profile: &mut UserProfile,
+    strategy_config: &mut Account<'info, StrategyConfig>,
+    vault: &SystemAccount<'info>,
+    treasury: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    now: i64,
+) -> Result<u64> {
+    let (fee, accrued_at) = accrue_management(
+        fees,
+        strategy_config.total_value_lamports,
+        strategy_config.fees_accrued_at,
+        now,
+    )?;
+    strategy_config.total_value_lamports -= fee;
+    strategy_config.fees_accrued_at = accrued_at;
+
+    if fee == 0 {
+        return Ok(0);
+    }
+    user_profile.total_value_lamports = user_profile
+        .total_value_lamports
+        .checked_sub(fee)
+        .ok_or(ErrorCode::MathError)?;
+    transfer_from_vault(
+        system_program,
+        vault,
+        treasury.to_account_info(),
+        user_profile,
+        fee,
+    )?;
+
+    emit!(FeesCharged {
+        owner: user_profile.owner,
+        strategy: strategy_config.key(),
+        strategy_id: strategy_config.strategy_id,
+        treasury: treasury.key(),
+        management_fee_lamports: fee,
+        strategy_value_lamports: strategy_config.total_value_lamports,
+        timestamp: now,
+    });
+
+    msg!(
+        "策略费用已计提，ID: {}，管理费: {}",
+        strategy_config.strategy_id,
+        fee
+    );
+
+    Ok(fee)
+}
@@ -0,0 +1,219 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// 排队提款。申请时金额从策略价值中扣除并锁定在工单中，资金仍留在资金库；
+// 头寸在再平衡中解除或锁定时间到期后，按工单ID先后领取
+
+// 提款锁定时间上限(30天)
+pub const MAX_WITHDRAWAL_DELAY_SECONDS: u32 = 30 * 24 * 60 * 60;
+
+pub fn validate_withdrawal_delay(delay_seconds: u32) -> Result<()> {
+    if delay_seconds > MAX_WITHDRAWAL_DELAY_SECONDS {
+        return Err(ErrorCode::InvalidWithdrawalDelay.into());
+    }
+    Ok(())
+}
+
+// 工单PDA地址
+pub fn ticket_address(strategy: &Pubkey, ticket_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"ticket", strategy.as_ref(), &ticket_id.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+impl WithdrawalQueue {
+    // 锁定提款金额并分配工单ID
+    pub fn enqueue(&mut self, amount: u64) -> Result<u64> {
+        let ticket_id = self.next_ticket_id;
+        self.next_ticket_id = ticket_id.checked_add(1).ok_or(ErrorCode::MathError)?;
+        self.locked_lamports = self
+            .locked_lamports
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathError)?;
+        Ok(ticket_id)
+    }
+
+    // 再平衡按扣除锁定金额后的价值调整头寸，此前申请的工单都视为头寸已解除
+    pub fn mark_unwound(&mut self) {
+        self.unwound_ticket_id = self.next_ticket_id;
+    }
+
+    pub fn is_unwound(&self, ticket_id: u64) -> bool {
+        ticket_id < self.unwound_ticket_id
+    }
+
+    // 领取工单，队首移到该工单之后
+    pub fn claim(&mut self, ticket_id: u64, amount: u64) -> Result<()> {
+        self.release(amount)?;
+        self.head_ticket_id = ticket_id + 1;
+        Ok(())
+    }
+
+    // 取消工单。取消的是队首时队首后移，其余的在之后领取时跳过
+    pub fn cancel(&mut self, ticket_id: u64, amount: u64) -> Result<()> {
+        self.release(amount)?;
+        if ticket_id == self.head_ticket_id {
+            self.head_ticket_id += 1;
+        }
+        Ok(())
+    }
+
+    fn release(&mut self, amount: u64) -> Result<()> {
+        self.locked_lamports = self
+            .locked_lamports
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathError)?;
+        Ok(())
+    }
+
+    // 领取 ticket_id 之前，队首到它之间的工单必须都已取消。
+    // skipped 按ID顺序提供这些工单的地址，已关闭的账户不再归本程序所有
+    pub fn check_order(
+        &self,
+        strategy: &Pubkey,
+        ticket_id: u64,
+        skipped: &[AccountInfo],
+    ) -> Result<()> {
+        let pending = ticket_id
+            .checked_sub(self.head_ticket_id)
+            .ok_or(ErrorCode::WithdrawalOutOfOrder)?;
+        if skipped.len() as u64 != pending {
+            return Err(ErrorCode::WithdrawalOutOfOrder.into());
+        }
+        for (id, account) in (self.head_ticket_id..ticket_id).zip(skipped) {
+            if account.key() != ticket_address(strategy, id) || account.owner == &crate::ID {
+                return Err(ErrorCode::WithdrawalOutOfOrder.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WithdrawalTicket {
+    // 头寸已解除或锁定时间已到
+    pub fn is_claimable(&self, queue: &WithdrawalQueue, now: i64) -> bool {
+        queue.is_unwound(self.ticket_id) || now >= self.unlocks_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket(ticket_id: u64, unlocks_at: i64) -> WithdrawalTicket {
+        WithdrawalTicket {
+            owner: Pubkey::new_unique(),
+            strategy: Pubkey::new_unique(),
+            ticket_id,
+            amount_lamports: 100,
+            requested_at: 0,
+            unlocks_at,
+            bump: 255,
+        }
+    }
+
+    // 测试1: 锁定时间上限
+    #[test]
+    fn test_validate_delay() {
+        assert!(validate_withdrawal_delay(0).is_ok());
+        assert!(validate_withdrawal_delay(MAX_WITHDRAWAL_DELAY_SECONDS).is_ok());
+        assert_eq!(
+            validate_withdrawal_delay(MAX_WITHDRAWAL_DELAY_SECONDS + 1).unwrap_err(),
+            ErrorCode::InvalidWithdrawalDelay.into()
+        );
+    }
+
+    // 测试2: 申请、领取和取消更新工单ID和锁定金额
+    #[test]
+    fn test_queue_accounting() {
+        let mut queue = WithdrawalQueue::default();
+        assert_eq!(queue.enqueue(100).unwrap(), 0);
+        assert_eq!(queue.enqueue(200).unwrap(), 1);
+        assert_eq!(queue.enqueue(300).unwrap(), 2);
+        assert_eq!(queue.locked_lamports, 600);
+
+        // 取消中间的工单不移动队首
+        queue.cancel(1, 200).unwrap();
+        assert_eq!(queue.head_ticket_id, 0);
+        queue.claim(0, 100).unwrap();
+        assert_eq!(queue.head_ticket_id, 1);
+
+        // 跳过已取消的工单领取
+        queue.claim(2, 300).unwrap();
+        assert_eq!(queue.head_ticket_id, 3);
+        assert_eq!(queue.locked_lamports, 0);
+        assert_eq!(queue.next_ticket_id, 3);
+
+        // 取消队首时队首后移
+        queue.enqueue(50).unwrap();
+        queue.cancel(3, 50).unwrap();
+        assert_eq!(queue.head_ticket_id, 4);
+
+        assert_eq!(queue.claim(4, 1).unwrap_err(), ErrorCode::MathError.into());
+    }
+
+    // 测试3: 再平衡之前申请的工单立即可领取，之后的等待锁定到期
+    #[test]
+    fn test_claimable() {
+        let mut queue = WithdrawalQueue::default();
+        queue.enqueue(100).unwrap();
+        let first = ticket(0, 1_000);
+        assert!(!first.is_claimable(&queue, 999));
+        assert!(first.is_claimable(&queue, 1_000));
+
+        queue.mark_unwound();
+        queue.enqueue(100).unwrap();
+        let second = ticket(1, 1_000);
+        assert!(first.is_claimable(&queue, 0));
+        assert!(!second.is_claimable(&queue, 999));
+        assert!(second.is_claimable(&queue, 1_000));
+    }
+
+    // 测试4: 领取顺序检查要求提供队首之后的全部已关闭工单
+    #[test]
+    fn test_check_order() {
+        let strategy = Pubkey::new_unique();
+        let queue = WithdrawalQueue {
+            next_ticket_id: 4,
+            head_ticket_id: 1,
+            unwound_ticket_id: 0,
+            locked_lamports: 0,
+        };
+        let system = Pubkey::default();
+        let program = crate::ID;
+        let keys: Vec<Pubkey> = (1..3).map(|id| ticket_address(&strategy, id)).collect();
+        let mut lamports = [0u64; 3];
+        let mut data = [[0u8; 0]; 3];
+        let [l1, l2, l3] = &mut lamports;
+        let [d1, d2, d3] = &mut data;
+        let closed = AccountInfo::new(&keys[0], false, false, l1, d1, &system, false, 0);
+        let open = AccountInfo::new(&keys[0], false, false, l2, d2, &program, false, 0);
+        let closed_next = AccountInfo::new(&keys[1], false, false, l3, d3, &system, false, 0);
+
+        assert!(queue.check_order(&strategy, 1, &[]).is_ok());
+        assert!(queue
+            .check_order(&strategy, 2, std::slice::from_ref(&closed))
+            .is_ok());
+        assert!(queue
+            .check_order(&strategy, 3, &[closed.clone(), closed_next.clone()])
+            .is_ok());
+        // 队首之前的工单、缺少或顺序错误的工单、未关闭的工单
+        for (ticket_id, skipped) in [
+            (0, vec![]),
+            (2, vec![]),
+            (2, vec![open.clone()]),
+            (3, vec![closed_next.clone(), closed.clone()]),
+            (3, vec![closed.clone()]),
+        ] {
+            assert_eq!(
+                queue
+                    .check_order(&strategy, ticket_id, &skipped)
+                    .unwrap_err(),
+                ErrorCode::WithdrawalOutOfOrder.into()
+            );
+        }
+    }
+}
@@ -0,0 +1,1640 @@
+// 集成测试公共部分: 启动 solana-program-test，构造指令并发送真实交易。
+// 设置了 SBF_OUT_DIR(如 `cargo test-sbf`)时加载编译好的程序，否则以原生模式运行同一个入口
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::{AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas};
+use defi_tools::oracle::PYTH_RECEIVER_PROGRAM_ID;
+use defi_tools::{
+    accounts, instruction, Allocation, AssetParams, DcaParams, DelegateScope, DepositLimits,
+    FeeConfig, OracleGuardConfig, ProtectionParams, RebalanceCondition, YieldParams,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::clock::Clock;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub const START_TIME: i64 = 1_700_000_000;
+// 1.0，精度 10^-8
+pub const PRICE: i64 = 100_000_000;
+pub const MAX_PRICE_AGE: u32 = 60;
+pub const INTERVAL: u64 = 3600;
+// init_registry 设置的费用金库
+pub const TREASURY: Pubkey = Pubkey::new_from_array([0xfe; 32]);
+
+// Anchor 的入口要求账户切片与 AccountInfo 具有相同的生命周期，
+// 原生模式下泄漏一份账户拷贝来满足这个约束
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    defi_tools::entry(program_id, accounts, data)
+}
+
+fn process_multisig_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    multisig_stub::entry(program_id, accounts, data)
+}
+
+// 编码 Pyth PriceUpdateV2 账户数据
+pub fn price_update(price: i64, conf: u64, publish_time: i64, full: bool) -> Vec<u8> {
+    let mut data = vec![34, 241, 35, 99, 157, 126, 244, 205];
+    data.extend([0u8; 32]);
+    if full {
+        data.push(1);
+    } else {
+        data.extend([0, 3]);
+    }
+    data.extend([7u8; 32]);
+    data.extend(price.to_le_bytes());
+    data.extend(conf.to_le_bytes());
+    data.extend((-8i32).to_le_bytes());
+    data.extend(publish_time.to_le_bytes());
+    data.extend(publish_time.to_le_bytes());
+    data.extend(price.to_le_bytes());
+    data.extend(conf.to_le_bytes());
+    data.extend(0u64.to_le_bytes());
+    data
+}
+
+pub fn oracle_account(price: i64, conf: u64, publish_time: i64) -> Account {
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: price_update(price, conf, publish_time, true),
+        owner: PYTH_RECEIVER_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn guard() -> OracleGuardConfig {
+    OracleGuardConfig {
+        max_price_age_seconds: MAX_PRICE_AGE,
+        max_confidence_bps: 200,
+        max_divergence_bps: 0,
+    }
+}
+
+pub fn condition() -> RebalanceCondition {
+    RebalanceCondition {
+        time_interval_seconds: INTERVAL,
+        max_deviation_bps: 0,
+        auto_rebalance: true,
+    }
+}
+
+pub fn allocation(protocol: u8, asset: u8, target_weight_bps: u16) -> Allocation {
+    Allocation {
+        protocol,
+        asset,
+        target_weight_bps,
+    }
+}
+
+// 默认策略: 资产0占60%，资产1占40%
+pub fn allocations() -> Vec<Allocation> {
+    vec![allocation(0, 0, 6000), allocation(1, 1, 4000)]
+}
+
+// 默认代理人授权: 可执行、再平衡和更新，权重每项最多调整10%，滑点不超过1%
+pub fn delegate_scope() -> DelegateScope {
+    DelegateScope {
+        execute: true,
+        rebalance: true,
+        update: true,
+        max_weight_change_bps: 1000,
+        max_slippage_bps: 100,
+    }
+}
+
+pub fn program_data_pda() -> Pubkey {
+    Pubkey::find_program_address(&[defi_tools::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+// 原生模式下程序没有 ProgramData 账户，按可升级加载器的布局伪造一个:
+// 枚举标签3(ProgramData)、部署slot、Option<升级权限>
+pub fn program_data_account(authority: Option<Pubkey>) -> Account {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend(0u64.to_le_bytes());
+    match authority {
+        Some(authority) => {
+            data.push(1);
+            data.extend(authority.to_bytes());
+        }
+        None => data.extend([0u8; 33]),
+    }
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &defi_tools::ID).0
+}
+
+pub fn asset_pda(asset_id: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"asset", &[asset_id]], &defi_tools::ID).0
+}
+
+pub fn user_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user", owner.as_ref()], &defi_tools::ID).0
+}
+
+pub fn vault_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", owner.as_ref()], &defi_tools::ID).0
+}
+
+pub fn strategy_pda(owner: &Pubkey, strategy_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"strategy",
+            user_pda(owner).as_ref(),
+            &strategy_id.to_le_bytes(),
+        ],
+        &defi_tools::ID,
+    )
+    .0
+}
+
+pub fn ticket_pda(owner: &Pubkey, strategy_id: u64, ticket_id: u64) -> Pubkey {
+    defi_tools::withdrawals::ticket_address(&strategy_pda(owner, strategy_id), ticket_id)
+}
+
+pub fn delegate_pda(owner: &Pubkey, strategy_id: u64, delegate: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"delegate",
+            strategy_pda(owner, strategy_id).as_ref(),
+            delegate.as_ref(),
+        ],
+        &defi_tools::ID,
+    )
+    .0
+}
+
+pub fn destination_pda(owner: &Pubkey, destination: &Pubkey) -> Pubkey {
+    defi_tools::allowlist::destination_address(owner, destination)
+}
+
+pub fn template_pda(publisher: &Pubkey, template_id: u64) -> Pubkey {
+    defi_tools::templates::template_address(publisher, template_id)
+}
+
+pub fn follow_pda(owner: &Pubkey, strategy_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"follow", strategy_pda(owner, strategy_id).as_ref()],
+        &defi_tools::ID,
+    )
+    .0
+}
+
+pub fn dca_pda(owner: &Pubkey, strategy_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"dca", strategy_pda(owner, strategy_id).as_ref()],
+        &defi_tools::ID,
+    )
+    .0
+}
+
+pub fn protect_pda(owner: &Pubkey, strategy_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"protect", strategy_pda(owner, strategy_id).as_ref()],
+        &defi_tools::ID,
+    )
+    .0
+}
+
+pub fn apy_pda(protocol: u8, asset: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"apy", &[protocol], &[asset]], &defi_tools::ID).0
+}
+
+pub fn yield_pda(owner: &Pubkey, strategy_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"yield", strategy_pda(owner, strategy_id).as_ref()],
+        &defi_tools::ID,
+    )
+    .0
+}
+
+// 签名者不是所有者时附加其代理人账户
+fn delegate_account(authority: &Pubkey, owner: &Pubkey, strategy_id: u64) -> Option<Pubkey> {
+    (authority != owner).then(|| delegate_pda(owner, strategy_id, authority))
+}
+
+pub fn build(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining: &[Pubkey],
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(
+        remaining
+            .iter()
+            .map(|key| AccountMeta::new_readonly(*key, false)),
+    );
+    Instruction::new_with_bytes(defi_tools::ID, &data.data(), metas)
+}
+
+// 存取款按分配项顺序附加可写的资产注册表
+pub fn with_assets(mut ix: Instruction, allocations: &[Allocation]) -> Instruction {
+    ix.accounts.extend(
+        allocations
+            .iter()
+            .map(|a| AccountMeta::new(asset_pda(a.asset), false)),
+    );
+    ix
+}
+
+// 提款按策略的存款记录附加资产注册表。存款记录按资产首次存入的顺序排列，
+// 策略只按 allocations 存过款时等于其中按资产去重的结果
+pub fn with_deposit_assets(mut ix: Instruction, allocations: &[Allocation]) -> Instruction {
+    let mut assets: Vec<u8> = Vec::new();
+    for allocation in allocations {
+        if !assets.contains(&allocation.asset) {
+            assets.push(allocation.asset);
+        }
+    }
+    ix.accounts.extend(
+        assets
+            .into_iter()
+            .map(|asset| AccountMeta::new(asset_pda(asset), false)),
+    );
+    ix
+}
+
+pub fn initialize_config(admin: &Pubkey, oracle_guard: OracleGuardConfig) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            admin: *admin,
+            program_data: program_data_pda(),
+            config: config_pda(),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig { oracle_guard },
+        &[],
+    )
+}
+
+pub fn update_oracle_guard(admin: &Pubkey, oracle_guard: OracleGuardConfig) -> Instruction {
+    build(
+        accounts::UpdateOracleGuard {
+            admin: *admin,
+            config: config_pda(),
+        },
+        instruction::UpdateOracleGuard { oracle_guard },
+        &[],
+    )
+}
+
+pub fn update_fee_config(admin: &Pubkey, fees: FeeConfig, treasury: &Pubkey) -> Instruction {
+    build(
+        accounts::UpdateFeeConfig {
+            admin: *admin,
+            config: config_pda(),
+            treasury: *treasury,
+        },
+        instruction::UpdateFeeConfig { fees },
+        &[],
+    )
+}
+
+pub fn update_deposit_limits(admin: &Pubkey, limits: DepositLimits) -> Instruction {
+    build(
+        accounts::UpdateDepositLimits {
+            admin: *admin,
+            config: config_pda(),
+        },
+        instruction::UpdateDepositLimits { limits },
+        &[],
+    )
+}
+
+pub fn update_withdrawal_delay(admin: &Pubkey, delay_seconds: u32) -> Instruction {
+    build(
+        accounts::UpdateWithdrawalDelay {
+            admin: *admin,
+            config: config_pda(),
+        },
+        instruction::UpdateWithdrawalDelay { delay_seconds },
+        &[],
+    )
+}
+
+pub fn update_allowlist_delay(admin: &Pubkey, delay_seconds: u32) -> Instruction {
+    build(
+        accounts::UpdateAllowlistDelay {
+            admin: *admin,
+            config: config_pda(),
+        },
+        instruction::UpdateAllowlistDelay { delay_seconds },
+        &[],
+    )
+}
+
+pub fn register_asset(admin: &Pubkey, asset_id: u8, params: AssetParams) -> Instruction {
+    build(
+        accounts::RegisterAsset {
+            admin: *admin,
+            config: config_pda(),
+            asset_registry: asset_pda(asset_id),
+            system_program: system_program::ID,
+        },
+        instruction::RegisterAsset { asset_id, params },
+        &[],
+    )
+}
+
+pub fn update_asset(admin: &Pubkey, asset_id: u8, params: AssetParams) -> Instruction {
+    build(
+        accounts::UpdateAsset {
+            admin: *admin,
+            config: config_pda(),
+            asset_registry: asset_pda(asset_id),
+        },
+        instruction::UpdateAsset { asset_id, params },
+        &[],
+    )
+}
+
+pub fn initialize_user(owner: &Pubkey, risk_level: u8) -> Instruction {
+    build(
+        accounts::InitializeUser {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            vault: vault_pda(owner),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeUser { risk_level },
+        &[],
+    )
+}
+
+// 每个分配项对应的资产注册表按顺序附加在末尾
+pub fn create_strategy(
+    owner: &Pubkey,
+    strategy_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Instruction {
+    let registries: Vec<Pubkey> = allocations.iter().map(|a| asset_pda(a.asset)).collect();
+    build(
+        accounts::CreateStrategy {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateStrategy {
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+        },
+        &registries,
+    )
+}
+
+pub fn update_strategy(
+    owner: &Pubkey,
+    strategy_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Instruction {
+    update_strategy_as(
+        owner,
+        owner,
+        strategy_id,
+        allocations,
+        rebalance_condition,
+        max_slippage_bps,
+    )
+}
+
+pub fn update_strategy_as(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    allocations: Vec<Allocation>,
+    rebalance_condition: RebalanceCondition,
+    max_slippage_bps: u16,
+) -> Instruction {
+    let registries: Vec<Pubkey> = allocations.iter().map(|a| asset_pda(a.asset)).collect();
+    build(
+        accounts::UpdateStrategy {
+            authority: *authority,
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            delegate: delegate_account(authority, owner, strategy_id),
+        },
+        instruction::UpdateStrategy {
+            strategy_id,
+            allocations,
+            rebalance_condition,
+            max_slippage_bps,
+        },
+        &registries,
+    )
+}
+
+pub fn execute_strategy(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    execute_strategy_as(owner, owner, strategy_id)
+}
+
+pub fn execute_strategy_as(authority: &Pubkey, owner: &Pubkey, strategy_id: u64) -> Instruction {
+    build(
+        accounts::ExecuteStrategy {
+            authority: *authority,
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            delegate: delegate_account(authority, owner, strategy_id),
+            system_program: system_program::ID,
+        },
+        instruction::ExecuteStrategy { strategy_id },
+        &[],
+    )
+}
+
+pub fn deposit_funds(
+    owner: &Pubkey,
+    strategy_id: u64,
+    amount: u64,
+    allocations: &[Allocation],
+) -> Instruction {
+    let ix = build(
+        accounts::DepositFunds {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            vault: vault_pda(owner),
+            config: config_pda(),
+            treasury: TREASURY,
+            system_program: system_program::ID,
+        },
+        instruction::DepositFunds {
+            strategy_id,
+            amount,
+        },
+        &[],
+    );
+    with_assets(ix, allocations)
+}
+
+pub fn withdraw_funds(
+    owner: &Pubkey,
+    strategy_id: u64,
+    amount: u64,
+    allocations: &[Allocation],
+) -> Instruction {
+    withdraw_funds_to(
+        owner,
+        strategy_id,
+        amount,
+        allocations,
+        Payout::to(owner, None),
+    )
+}
+
+// 提款的接收地址；entry 为白名单账户，guardian 为联署的监护人，None 时以程序ID占位
+#[derive(Clone, Copy)]
+pub struct Payout {
+    pub destination: Pubkey,
+    pub entry: Option<Pubkey>,
+    pub guardian: Option<Pubkey>,
+}
+
+impl Payout {
+    pub fn to(destination: &Pubkey, entry: Option<Pubkey>) -> Self {
+        Self {
+            destination: *destination,
+            entry,
+            guardian: None,
+        }
+    }
+
+    pub fn with_guardian(mut self, guardian: &Pubkey) -> Self {
+        self.guardian = Some(*guardian);
+        self
+    }
+}
+
+pub fn withdraw_funds_to(
+    owner: &Pubkey,
+    strategy_id: u64,
+    amount: u64,
+    allocations: &[Allocation],
+    payout: Payout,
+) -> Instruction {
+    let ix = build(
+        accounts::WithdrawFunds {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            vault: vault_pda(owner),
+            config: config_pda(),
+            treasury: TREASURY,
+            destination: payout.destination,
+            withdrawal_destination: payout.entry,
+            guardian: payout.guardian,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawFunds {
+            strategy_id,
+            amount,
+        },
+        &[],
+    );
+    with_deposit_assets(ix, allocations)
+}
+
+pub fn request_withdrawal(
+    owner: &Pubkey,
+    strategy_id: u64,
+    ticket_id: u64,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::RequestWithdrawal {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            withdrawal_ticket: ticket_pda(owner, strategy_id, ticket_id),
+            vault: vault_pda(owner),
+            config: config_pda(),
+            treasury: TREASURY,
+            system_program: system_program::ID,
+        },
+        instruction::RequestWithdrawal {
+            strategy_id,
+            amount,
+        },
+        &[],
+    )
+}
+
+// 资产注册表之后按ID顺序附加队首到该工单之间已取消的工单
+pub fn claim_withdrawal(
+    owner: &Pubkey,
+    strategy_id: u64,
+    ticket_id: u64,
+    allocations: &[Allocation],
+    skipped: &[u64],
+) -> Instruction {
+    claim_withdrawal_to(
+        owner,
+        strategy_id,
+        ticket_id,
+        allocations,
+        skipped,
+        Payout::to(owner, None),
+    )
+}
+
+pub fn claim_withdrawal_to(
+    owner: &Pubkey,
+    strategy_id: u64,
+    ticket_id: u64,
+    allocations: &[Allocation],
+    skipped: &[u64],
+    payout: Payout,
+) -> Instruction {
+    let ix = build(
+        accounts::ClaimWithdrawal {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            withdrawal_ticket: ticket_pda(owner, strategy_id, ticket_id),
+            vault: vault_pda(owner),
+            destination: payout.destination,
+            withdrawal_destination: payout.entry,
+            guardian: payout.guardian,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimWithdrawal {
+            strategy_id,
+            ticket_id,
+        },
+        &[],
+    );
+    let mut ix = with_deposit_assets(ix, allocations);
+    ix.accounts.extend(
+        skipped
+            .iter()
+            .map(|id| AccountMeta::new_readonly(ticket_pda(owner, strategy_id, *id), false)),
+    );
+    ix
+}
+
+pub fn cancel_withdrawal(owner: &Pubkey, strategy_id: u64, ticket_id: u64) -> Instruction {
+    build(
+        accounts::CancelWithdrawal {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            withdrawal_ticket: ticket_pda(owner, strategy_id, ticket_id),
+            vault: vault_pda(owner),
+            config: config_pda(),
+            treasury: TREASURY,
+            system_program: system_program::ID,
+        },
+        instruction::CancelWithdrawal {
+            strategy_id,
+            ticket_id,
+        },
+        &[],
+    )
+}
+
+pub fn rebalance_positions(owner: &Pubkey, strategy_id: u64, prices: &[Pubkey]) -> Instruction {
+    rebalance_positions_as(owner, owner, strategy_id, prices)
+}
+
+pub fn rebalance_positions_as(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    prices: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::RebalancePositions {
+            authority: *authority,
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            delegate: delegate_account(authority, owner, strategy_id),
+            vault: vault_pda(owner),
+            config: config_pda(),
+            treasury: TREASURY,
+            system_program: system_program::ID,
+        },
+        instruction::RebalancePositions { strategy_id },
+        prices,
+    )
+}
+
+pub fn add_delegate(
+    owner: &Pubkey,
+    strategy_id: u64,
+    delegate: &Pubkey,
+    scope: DelegateScope,
+    expires_at: i64,
+) -> Instruction {
+    build(
+        accounts::AddDelegate {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            strategy_delegate: delegate_pda(owner, strategy_id, delegate),
+            system_program: system_program::ID,
+        },
+        instruction::AddDelegate {
+            strategy_id,
+            delegate: *delegate,
+            scope,
+            expires_at,
+        },
+        &[],
+    )
+}
+
+pub fn revoke_delegate(owner: &Pubkey, strategy_id: u64, delegate: &Pubkey) -> Instruction {
+    build(
+        accounts::RevokeDelegate {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            strategy_delegate: delegate_pda(owner, strategy_id, delegate),
+        },
+        instruction::RevokeDelegate {
+            strategy_id,
+            delegate: *delegate,
+        },
+        &[],
+    )
+}
+
+pub fn set_withdrawal_allowlist(owner: &Pubkey, enabled: bool) -> Instruction {
+    build(
+        accounts::SetWithdrawalAllowlist {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            config: config_pda(),
+        },
+        instruction::SetWithdrawalAllowlist { enabled },
+        &[],
+    )
+}
+
+pub fn add_withdrawal_destination(owner: &Pubkey, destination: &Pubkey) -> Instruction {
+    build(
+        accounts::AddWithdrawalDestination {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            withdrawal_destination: destination_pda(owner, destination),
+            vault: vault_pda(owner),
+            config: config_pda(),
+            system_program: system_program::ID,
+        },
+        instruction::AddWithdrawalDestination {
+            destination: *destination,
+        },
+        &[],
+    )
+}
+
+pub fn remove_withdrawal_destination(owner: &Pubkey, destination: &Pubkey) -> Instruction {
+    build(
+        accounts::RemoveWithdrawalDestination {
+            owner: *owner,
+            withdrawal_destination: destination_pda(owner, destination),
+        },
+        instruction::RemoveWithdrawalDestination {
+            destination: *destination,
+        },
+        &[],
+    )
+}
+
+// current_guardian 为当前登记的监护人，放宽限额或更换监护人时需其联署
+pub fn set_spending_limit(
+    owner: &Pubkey,
+    max_lamports: u64,
+    window_seconds: u32,
+    guardian: &Pubkey,
+    current_guardian: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::SetSpendingLimit {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            guardian: current_guardian,
+        },
+        instruction::SetSpendingLimit {
+            max_lamports,
+            window_seconds,
+            guardian: *guardian,
+        },
+        &[],
+    )
+}
+
+pub fn propose_owner(owner: &Pubkey, new_owner: &Pubkey, guardian: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::ProposeOwner {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            config: config_pda(),
+            guardian,
+        },
+        instruction::ProposeOwner {
+            new_owner: *new_owner,
+        },
+        &[],
+    )
+}
+
+pub fn accept_owner(owner: &Pubkey, previous_owner: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptOwner {
+            owner: *owner,
+            previous_profile: user_pda(previous_owner),
+            user_profile: user_pda(owner),
+            vault: vault_pda(owner),
+            system_program: system_program::ID,
+        },
+        instruction::AcceptOwner {
+            previous_owner: *previous_owner,
+        },
+        &[],
+    )
+}
+
+pub fn migrate_strategy(owner: &Pubkey, previous_owner: &Pubkey, strategy_id: u64) -> Instruction {
+    build(
+        accounts::MigrateStrategy {
+            owner: *owner,
+            previous_profile: user_pda(previous_owner),
+            user_profile: user_pda(owner),
+            previous_strategy: strategy_pda(previous_owner, strategy_id),
+            previous_protection: protect_pda(previous_owner, strategy_id),
+            strategy_config: strategy_pda(owner, strategy_id),
+            previous_vault: vault_pda(previous_owner),
+            vault: vault_pda(owner),
+            system_program: system_program::ID,
+        },
+        instruction::MigrateStrategy {
+            previous_owner: *previous_owner,
+            strategy_id,
+        },
+        &[],
+    )
+}
+
+// 每个分配项对应的资产注册表按顺序附加在末尾
+pub fn publish_template(
+    publisher: &Pubkey,
+    template_id: u64,
+    allocations: Vec<Allocation>,
+    risk_level: u8,
+) -> Instruction {
+    let registries: Vec<Pubkey> = allocations.iter().map(|a| asset_pda(a.asset)).collect();
+    build(
+        accounts::PublishTemplate {
+            publisher: *publisher,
+            strategy_template: template_pda(publisher, template_id),
+            system_program: system_program::ID,
+        },
+        instruction::PublishTemplate {
+            template_id,
+            allocations,
+            rebalance_condition: condition(),
+            max_slippage_bps: 100,
+            risk_level,
+            description_hash: [7; 32],
+        },
+        &registries,
+    )
+}
+
+pub fn retire_template(publisher: &Pubkey, template_id: u64) -> Instruction {
+    build(
+        accounts::RetireTemplate {
+            publisher: *publisher,
+            strategy_template: template_pda(publisher, template_id),
+        },
+        instruction::RetireTemplate { template_id },
+        &[],
+    )
+}
+
+// allocations 为模板的分配项，用于附加资产注册表
+pub fn create_strategy_from_template(
+    owner: &Pubkey,
+    strategy_id: u64,
+    publisher: &Pubkey,
+    template_id: u64,
+    allocations: &[Allocation],
+) -> Instruction {
+    let registries: Vec<Pubkey> = allocations.iter().map(|a| asset_pda(a.asset)).collect();
+    build(
+        accounts::CreateStrategyFromTemplate {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_template: template_pda(publisher, template_id),
+            strategy_config: strategy_pda(owner, strategy_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateStrategyFromTemplate {},
+        &registries,
+    )
+}
+
+pub fn follow_strategy(
+    owner: &Pubkey,
+    strategy_id: u64,
+    leader_owner: &Pubkey,
+    leader_id: u64,
+    max_weight_bps: u16,
+    delay_seconds: u32,
+    leader_fee_bps: u16,
+) -> Instruction {
+    build(
+        accounts::FollowStrategy {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            leader_strategy: strategy_pda(leader_owner, leader_id),
+            strategy_follow: follow_pda(owner, strategy_id),
+            system_program: system_program::ID,
+        },
+        instruction::FollowStrategy {
+            strategy_id,
+            max_weight_bps,
+            delay_seconds,
+            leader_fee_bps,
+        },
+        &[],
+    )
+}
+
+pub fn unfollow_strategy(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    build(
+        accounts::UnfollowStrategy {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            strategy_follow: follow_pda(owner, strategy_id),
+        },
+        instruction::UnfollowStrategy { strategy_id },
+        &[],
+    )
+}
+
+// allocations 为领投策略的分配项，用于附加资产注册表
+pub fn sync_follower(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    leader_owner: &Pubkey,
+    leader_id: u64,
+    allocations: &[Allocation],
+) -> Instruction {
+    sync_follower_with(
+        authority,
+        owner,
+        strategy_id,
+        leader_owner,
+        leader_id,
+        allocations,
+        None,
+    )
+}
+
+// entry 为领投者在跟随者白名单中的账户
+pub fn sync_follower_with(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    leader_owner: &Pubkey,
+    leader_id: u64,
+    allocations: &[Allocation],
+    entry: Option<Pubkey>,
+) -> Instruction {
+    let registries: Vec<Pubkey> = allocations.iter().map(|a| asset_pda(a.asset)).collect();
+    build(
+        accounts::SyncFollower {
+            authority: *authority,
+            strategy_follow: follow_pda(owner, strategy_id),
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            leader_strategy: strategy_pda(leader_owner, leader_id),
+            leader_owner: *leader_owner,
+            withdrawal_destination: entry,
+            vault: vault_pda(owner),
+            config: config_pda(),
+            treasury: TREASURY,
+            system_program: system_program::ID,
+        },
+        instruction::SyncFollower {},
+        &registries,
+    )
+}
+
+// 以协议0的资产0为报价资产，定投协议1的资产1
+pub fn dca_params(increment_lamports: u64, max_price_quote: u64) -> DcaParams {
+    DcaParams {
+        quote_protocol: 0,
+        quote_asset: 0,
+        target_protocol: 1,
+        target_asset: 1,
+        increment_lamports,
+        max_price_quote,
+    }
+}
+
+pub fn create_dca(owner: &Pubkey, strategy_id: u64, params: DcaParams) -> Instruction {
+    build(
+        accounts::CreateDca {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            dca_schedule: dca_pda(owner, strategy_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateDca {
+            strategy_id,
+            params,
+        },
+        &[],
+    )
+}
+
+pub fn cancel_dca(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    build(
+        accounts::CancelDca {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            dca_schedule: dca_pda(owner, strategy_id),
+        },
+        instruction::CancelDca { strategy_id },
+        &[],
+    )
+}
+
+// prices 依次为报价资产和目标资产的 [资产注册表, 价格源]
+pub fn execute_dca(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    prices: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::ExecuteDca {
+            authority: *authority,
+            dca_schedule: dca_pda(owner, strategy_id),
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            config: config_pda(),
+        },
+        instruction::ExecuteDca {},
+        prices,
+    )
+}
+
+// 触发后转入协议1的资产1
+pub fn protection_params(
+    stop_loss_lamports: u64,
+    max_drawdown_bps: u16,
+    take_profit_lamports: u64,
+) -> ProtectionParams {
+    ProtectionParams {
+        stop_loss_lamports,
+        max_drawdown_bps,
+        take_profit_lamports,
+        safe_protocol: 1,
+        safe_asset: 1,
+    }
+}
+
+// prices 按当前分配项顺序提供 [资产注册表, 价格源]
+pub fn set_protection(
+    owner: &Pubkey,
+    strategy_id: u64,
+    params: ProtectionParams,
+    prices: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::SetProtection {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            protective_order: protect_pda(owner, strategy_id),
+            safe_asset: asset_pda(params.safe_asset),
+            config: config_pda(),
+            system_program: system_program::ID,
+        },
+        instruction::SetProtection {
+            strategy_id,
+            params,
+        },
+        prices,
+    )
+}
+
+pub fn cancel_protection(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    build(
+        accounts::CancelProtection {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            protective_order: protect_pda(owner, strategy_id),
+        },
+        instruction::CancelProtection { strategy_id },
+        &[],
+    )
+}
+
+pub fn check_protection(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    prices: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::CheckProtection {
+            authority: *authority,
+            protective_order: protect_pda(owner, strategy_id),
+            owner: *owner,
+            strategy_config: strategy_pda(owner, strategy_id),
+            config: config_pda(),
+        },
+        instruction::CheckProtection {},
+        prices,
+    )
+}
+
+pub fn resume_strategy(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    build(
+        accounts::ResumeStrategy {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+        },
+        instruction::ResumeStrategy { strategy_id },
+        &[],
+    )
+}
+
+pub fn register_apy_tracker(admin: &Pubkey, protocol: u8, asset: u8, apy_bps: u32) -> Instruction {
+    build(
+        accounts::RegisterApyTracker {
+            admin: *admin,
+            config: config_pda(),
+            asset_registry: asset_pda(asset),
+            apy_tracker: apy_pda(protocol, asset),
+            system_program: system_program::ID,
+        },
+        instruction::RegisterApyTracker {
+            protocol,
+            asset,
+            apy_bps,
+        },
+        &[],
+    )
+}
+
+pub fn report_apy(admin: &Pubkey, protocol: u8, asset: u8, apy_bps: u32) -> Instruction {
+    build(
+        accounts::ReportApy {
+            admin: *admin,
+            config: config_pda(),
+            apy_tracker: apy_pda(protocol, asset),
+        },
+        instruction::ReportApy {
+            protocol,
+            asset,
+            apy_bps,
+        },
+        &[],
+    )
+}
+
+// 资产1在 Solend(1) 和 Mango(3) 之间轮动
+pub fn yield_params(min_apy_advantage_bps: u32, min_hold_seconds: u32) -> YieldParams {
+    YieldParams {
+        asset: 1,
+        candidate_protocols: 0b1010,
+        min_apy_advantage_bps,
+        min_hold_seconds,
+    }
+}
+
+pub fn enable_yield_rotation(owner: &Pubkey, strategy_id: u64, params: YieldParams) -> Instruction {
+    build(
+        accounts::EnableYieldRotation {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            yield_rotation: yield_pda(owner, strategy_id),
+            system_program: system_program::ID,
+        },
+        instruction::EnableYieldRotation {
+            strategy_id,
+            params,
+        },
+        &[],
+    )
+}
+
+pub fn disable_yield_rotation(owner: &Pubkey, strategy_id: u64) -> Instruction {
+    build(
+        accounts::DisableYieldRotation {
+            owner: *owner,
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+            yield_rotation: yield_pda(owner, strategy_id),
+        },
+        instruction::DisableYieldRotation { strategy_id },
+        &[],
+    )
+}
+
+pub fn rotate_yield(
+    authority: &Pubkey,
+    owner: &Pubkey,
+    strategy_id: u64,
+    trackers: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::RotateYield {
+            authority: *authority,
+            yield_rotation: yield_pda(owner, strategy_id),
+            user_profile: user_pda(owner),
+            strategy_config: strategy_pda(owner, strategy_id),
+        },
+        instruction::RotateYield {},
+        trackers,
+    )
+}
+
+// 多签金库作为所有者时，defi-tools 的指令先提交到多签程序，达到门限后由金库 PDA 通过 CPI 签名执行
+pub fn multisig_pda(create_key: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"multisig", create_key.as_ref()], &multisig_stub::ID).0
+}
+
+pub fn multisig_vault_pda(multisig: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", multisig.as_ref()], &multisig_stub::ID).0
+}
+
+pub fn multisig_transaction_pda(multisig: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"transaction", multisig.as_ref(), &index.to_le_bytes()],
+        &multisig_stub::ID,
+    )
+    .0
+}
+
+pub fn create_multisig(
+    payer: &Pubkey,
+    create_key: &Pubkey,
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> Instruction {
+    let multisig = multisig_pda(create_key);
+    let accounts = multisig_stub::accounts::CreateMultisig {
+        payer: *payer,
+        multisig,
+        vault: multisig_vault_pda(&multisig),
+        system_program: system_program::ID,
+    };
+    let data = multisig_stub::instruction::CreateMultisig {
+        create_key: *create_key,
+        members,
+        threshold,
+    };
+    Instruction::new_with_bytes(
+        multisig_stub::ID,
+        &data.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+pub fn create_multisig_transaction(
+    member: &Pubkey,
+    multisig: &Pubkey,
+    index: u64,
+    ix: &Instruction,
+) -> Instruction {
+    let accounts = multisig_stub::accounts::CreateTransaction {
+        member: *member,
+        multisig: *multisig,
+        transaction: multisig_transaction_pda(multisig, index),
+        system_program: system_program::ID,
+    };
+    let data = multisig_stub::instruction::CreateTransaction {
+        program_id: ix.program_id,
+        accounts: ix.accounts.iter().map(Into::into).collect(),
+        data: ix.data.clone(),
+    };
+    Instruction::new_with_bytes(
+        multisig_stub::ID,
+        &data.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+pub fn approve_multisig_transaction(member: &Pubkey, multisig: &Pubkey, index: u64) -> Instruction {
+    let accounts = multisig_stub::accounts::Approve {
+        member: *member,
+        multisig: *multisig,
+        transaction: multisig_transaction_pda(multisig, index),
+    };
+    Instruction::new_with_bytes(
+        multisig_stub::ID,
+        &multisig_stub::instruction::Approve { _index: index }.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+// 被执行指令的账户和被调用的程序附加为剩余账户，金库的签名由多签程序提供
+pub fn execute_multisig_transaction(
+    member: &Pubkey,
+    multisig: &Pubkey,
+    index: u64,
+    ix: &Instruction,
+) -> Instruction {
+    let accounts = multisig_stub::accounts::Execute {
+        member: *member,
+        multisig: *multisig,
+        transaction: multisig_transaction_pda(multisig, index),
+        vault: multisig_vault_pda(multisig),
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(ix.accounts.iter().map(|meta| AccountMeta {
+        is_signer: false,
+        ..meta.clone()
+    }));
+    metas.push(AccountMeta::new_readonly(ix.program_id, false));
+    Instruction::new_with_bytes(
+        multisig_stub::ID,
+        &multisig_stub::instruction::Execute { _index: index }.data(),
+        metas,
+    )
+}
+
+// 交易失败时的指令错误码(自定义错误和Anchor框架错误)
+pub fn error_code(err: &BanksClientError) -> Option<u32> {
+    let err = match err {
+        BanksClientError::TransactionError(err) => err,
+        BanksClientError::SimulationError { err, .. } => err,
+        _ => return None,
+    };
+    match err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(*code),
+        _ => None,
+    }
+}
+
+#[track_caller]
+pub fn assert_error(result: Result<(), BanksClientError>, code: impl Into<u32>) {
+    let code = code.into();
+    match result {
+        Ok(()) => panic!("交易应以错误码 {code} 失败，实际成功"),
+        Err(err) => assert_eq!(error_code(&err), Some(code), "实际错误: {err:?}"),
+    }
+}
+
+pub struct Env {
+    pub ctx: ProgramTestContext,
+    pub admin: Keypair,
+    pub owner: Keypair,
+    // 没有任何权限的第二个用户
+    pub other: Keypair,
+    // 资产0和资产1的主价格源与备用价格源
+    pub oracles: [Pubkey; 2],
+    pub secondary_oracles: [Pubkey; 2],
+}
+
+pub async fn start() -> Env {
+    let admin = Keypair::new();
+    let owner = Keypair::new();
+    let other = Keypair::new();
+    let oracles = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let secondary_oracles = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let mut program_test = ProgramTest::new(
+        "defi_tools",
+        defi_tools::ID,
+        processor!(process_instruction),
+    );
+    program_test.add_program(
+        "multisig_stub",
+        multisig_stub::ID,
+        processor!(process_multisig_instruction),
+    );
+    for user in [&admin, &owner, &other] {
+        program_test.add_account(
+            user.pubkey(),
+            Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+    }
+    program_test.add_account(
+        program_data_pda(),
+        program_data_account(Some(admin.pubkey())),
+    );
+    program_test.add_account(
+        TREASURY,
+        Account::new(LAMPORTS_PER_SOL, 0, &system_program::ID),
+    );
+    for oracle in oracles.iter().chain(&secondary_oracles) {
+        program_test.add_account(
+            *oracle,
+            oracle_account(PRICE, PRICE as u64 / 1000, START_TIME),
+        );
+    }
+
+    let ctx = program_test.start_with_context().await;
+    let mut env = Env {
+        ctx,
+        admin,
+        owner,
+        other,
+        oracles,
+        secondary_oracles,
+    };
+    env.set_time(START_TIME).await;
+    env
+}
+
+impl Env {
+    // 每笔交易前推进一个slot，保证相同指令的交易有不同的blockhash
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let clock = self.clock().await;
+        self.ctx.warp_to_slot(clock.slot + 1).unwrap();
+        self.ctx.set_sysvar(&Clock {
+            slot: clock.slot + 1,
+            ..clock
+        });
+
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn send_as_admin(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let admin = self.admin.insecure_clone();
+        self.send(&[instruction], &[&admin]).await
+    }
+
+    pub async fn send_as_owner(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let owner = self.owner.insecure_clone();
+        self.send(&[instruction], &[&owner]).await
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.ctx.banks_client.get_sysvar().await.unwrap()
+    }
+
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let clock = self.clock().await;
+        self.ctx.set_sysvar(&Clock {
+            unix_timestamp,
+            ..clock
+        });
+    }
+
+    pub fn set_account(&mut self, address: &Pubkey, account: Account) {
+        self.ctx
+            .set_account(address, &AccountSharedData::from(account));
+    }
+
+    pub fn set_oracle(&mut self, address: &Pubkey, price: i64, conf: u64, publish_time: i64) {
+        self.set_account(address, oracle_account(price, conf, publish_time));
+    }
+
+    pub async fn raw_account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.ctx.banks_client.get_account(*address).await.unwrap()
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .raw_account(address)
+            .await
+            .unwrap_or_else(|| panic!("账户不存在: {address}"));
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    // 直接修改程序账户的数据，用于构造指令无法到达的状态
+    pub async fn modify<T: AccountSerialize + AccountDeserialize>(
+        &mut self,
+        address: &Pubkey,
+        f: impl FnOnce(&mut T),
+    ) {
+        let mut account = self.raw_account(address).await.unwrap();
+        let mut state = T::try_deserialize(&mut account.data.as_slice()).unwrap();
+        f(&mut state);
+        let mut data = Vec::new();
+        state.try_serialize(&mut data).unwrap();
+        account.data[..data.len()].copy_from_slice(&data);
+        self.set_account(address, account);
+    }
+
+    pub fn asset_params(&self, asset_id: usize) -> AssetParams {
+        AssetParams {
+            mint: Pubkey::new_from_array([asset_id as u8 + 1; 32]),
+            decimals: 9,
+            oracle: self.oracles[asset_id],
+            secondary_oracle: Pubkey::default(),
+            enabled: true,
+            max_allocation_bps: 10000,
+            deposit_cap_lamports: 0,
+        }
+    }
+
+    // 初始化全局配置(不收费，费用金库为 TREASURY)并注册资产0和资产1
+    pub async fn init_registry(&mut self) {
+        let admin = self.admin.pubkey();
+        self.send_as_admin(initialize_config(&admin, guard()))
+            .await
+            .unwrap();
+        self.send_as_admin(update_fee_config(&admin, FeeConfig::default(), &TREASURY))
+            .await
+            .unwrap();
+        for asset_id in 0..2 {
+            let params = self.asset_params(asset_id);
+            self.send_as_admin(register_asset(&admin, asset_id as u8, params))
+                .await
+                .unwrap();
+        }
+    }
+
+    // 初始化注册表、用户和默认策略0，并存入1 SOL
+    pub async fn init_strategy(&mut self) {
+        self.init_registry().await;
+        let owner = self.owner.pubkey();
+        self.send_as_owner(initialize_user(&owner, 3))
+            .await
+            .unwrap();
+        self.send_as_owner(create_strategy(&owner, 0, allocations(), condition(), 100))
+            .await
+            .unwrap();
+        self.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+            .await
+            .unwrap();
+    }
+
+    // 创建以 admin、owner 和 other 为成员的多签，返回多签账户和预存10 SOL的金库
+    pub async fn init_multisig(&mut self, threshold: u8) -> (Pubkey, Pubkey) {
+        let create_key = Pubkey::new_unique();
+        let members = vec![
+            self.admin.pubkey(),
+            self.owner.pubkey(),
+            self.other.pubkey(),
+        ];
+        self.send_as_admin(create_multisig(
+            &self.admin.pubkey(),
+            &create_key,
+            members,
+            threshold,
+        ))
+        .await
+        .unwrap();
+        let multisig = multisig_pda(&create_key);
+        let vault = multisig_vault_pda(&multisig);
+        self.set_account(
+            &vault,
+            Account::new(10 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+        (multisig, vault)
+    }
+
+    // 第一个成员提交指令，其余成员依次批准，最后由第一个成员执行
+    pub async fn send_as_multisig(
+        &mut self,
+        multisig: &Pubkey,
+        ix: Instruction,
+        members: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let state: multisig_stub::Multisig = self.account(multisig).await;
+        let index = state.transaction_index;
+        let proposer = members[0];
+        self.send(
+            &[create_multisig_transaction(
+                &proposer.pubkey(),
+                multisig,
+                index,
+                &ix,
+            )],
+            &[proposer],
+        )
+        .await?;
+        for member in &members[1..] {
+            self.send(
+                &[approve_multisig_transaction(
+                    &member.pubkey(),
+                    multisig,
+                    index,
+                )],
+                &[member],
+            )
+            .await?;
+        }
+        self.send(
+            &[execute_multisig_transaction(
+                &proposer.pubkey(),
+                multisig,
+                index,
+                &ix,
+            )],
+            &[proposer],
+        )
+        .await
+    }
+
+    // 默认策略的价格账户: [资产注册表, 主价格源, (备用价格源)]
+    pub fn price_accounts(&self, cross_check: bool) -> Vec<Pubkey> {
+        (0..2)
+            .flat_map(|asset_id| {
+                let mut accounts = vec![asset_pda(asset_id as u8), self.oracles[asset_id]];
+                if cross_check {
+                    accounts.push(self.secondary_oracles[asset_id]);
+                }
+                accounts
+            })
+            .collect()
+    }
+}
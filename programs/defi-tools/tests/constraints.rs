@@ -0,0 +1,709 @@
+// 账户约束: 错误的所有者、错误的种子、缺少签名以及暂停的用户配置文件
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use common::*;
+use defi_tools::{
+    accounts, instruction, AssetParams, DepositLimits, ErrorCode, FeeConfig, UserProfile,
+};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::system_program;
+
+// 用指定的账户构造存款指令
+fn deposit_with(
+    owner: &Pubkey,
+    user_profile: Pubkey,
+    strategy_config: Pubkey,
+    strategy_id: u64,
+) -> Instruction {
+    build(
+        accounts::DepositFunds {
+            owner: *owner,
+            user_profile,
+            strategy_config,
+            vault: vault_pda(owner),
+            config: config_pda(),
+            treasury: TREASURY,
+            system_program: system_program::ID,
+        },
+        instruction::DepositFunds {
+            strategy_id,
+            amount: 1,
+        },
+        &[],
+    )
+}
+
+// 测试1: 使用他人的用户配置文件
+#[tokio::test]
+async fn test_foreign_user_profile() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+
+    let ix = deposit_with(
+        &other.pubkey(),
+        user_pda(&owner),
+        strategy_pda(&owner, 0),
+        0,
+    );
+    assert_error(
+        env.send(&[ix], &[&other]).await,
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+// 测试2: 用自己的配置文件操作他人的策略
+#[tokio::test]
+async fn test_foreign_strategy() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+    env.send(&[initialize_user(&other.pubkey(), 3)], &[&other])
+        .await
+        .unwrap();
+
+    let ix = deposit_with(
+        &other.pubkey(),
+        user_pda(&other.pubkey()),
+        strategy_pda(&owner, 0),
+        0,
+    );
+    assert_error(
+        env.send(&[ix], &[&other]).await,
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+// 测试3: 指令参数中的策略ID与账户种子不一致
+#[tokio::test]
+async fn test_strategy_id_seed_mismatch() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+
+    let ix = deposit_with(&owner, user_pda(&owner), strategy_pda(&owner, 0), 1);
+    assert_error(env.send_as_owner(ix).await, AnchorError::ConstraintSeeds);
+
+    let mut update = update_asset(&env.admin.pubkey(), 1, env.asset_params(1));
+    update.accounts[2].pubkey = asset_pda(0);
+    assert_error(
+        env.send_as_admin(update).await,
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+// 测试4: 账户不属于本程序或类型不符
+#[tokio::test]
+async fn test_wrong_account_owner_and_type() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+
+    let ix = deposit_with(&owner, user_pda(&owner), owner, 0);
+    assert_error(
+        env.send_as_owner(ix).await,
+        AnchorError::AccountOwnedByWrongProgram,
+    );
+
+    let ix = deposit_with(&owner, user_pda(&owner), user_pda(&owner), 0);
+    assert_error(
+        env.send_as_owner(ix).await,
+        AnchorError::AccountDiscriminatorMismatch,
+    );
+}
+
+// 测试5: 所有者没有签名
+#[tokio::test]
+async fn test_owner_not_signer() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+
+    let mut ix = deposit_funds(&owner, 0, 1, &allocations());
+    ix.accounts[0].is_signer = false;
+    assert_error(
+        env.send(&[ix], &[&other]).await,
+        AnchorError::AccountNotSigner,
+    );
+}
+
+// 测试6: 非管理员不能修改全局配置和资产
+#[tokio::test]
+async fn test_admin_only() {
+    let mut env = start().await;
+    env.init_registry().await;
+    let other = env.other.insecure_clone();
+
+    let params = env.asset_params(0);
+    let ix = update_asset(&other.pubkey(), 0, params);
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::Unauthorized);
+
+    let ix = register_asset(&other.pubkey(), 2, params);
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::Unauthorized);
+
+    let ix = update_fee_config(&other.pubkey(), FeeConfig::default(), &other.pubkey());
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::Unauthorized);
+
+    let ix = update_deposit_limits(&other.pubkey(), DepositLimits::default());
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::Unauthorized);
+
+    let ix = update_withdrawal_delay(&other.pubkey(), 0);
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::Unauthorized);
+}
+
+// 测试7: 暂停的用户不能存款、更新、执行和再平衡，但仍可以提款
+#[tokio::test]
+async fn test_paused_profile() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    env.modify(&user_pda(&owner), |profile: &mut UserProfile| {
+        profile.is_paused = true
+    })
+    .await;
+
+    let prices = env.price_accounts(false);
+    for ix in [
+        deposit_funds(&owner, 0, 1, &allocations()),
+        update_strategy(&owner, 0, allocations(), condition(), 100),
+        execute_strategy(&owner, 0),
+        rebalance_positions(&owner, 0, &prices),
+    ] {
+        assert_error(env.send_as_owner(ix).await, ErrorCode::StrategyPaused);
+    }
+    env.send_as_owner(withdraw_funds(&owner, 0, 1, &allocations()))
+        .await
+        .unwrap();
+}
+
+// 测试8: 价格账户必须与资产注册表登记的一致
+#[tokio::test]
+async fn test_unregistered_price_accounts() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+
+    // 用备用价格源代替登记的主价格源
+    let mut prices = env.price_accounts(false);
+    prices[1] = env.secondary_oracles[0];
+    let ix = rebalance_positions(&owner, 0, &prices);
+    assert_error(env.send_as_owner(ix).await, ErrorCode::InvalidOracleAccount);
+
+    // 资产注册表顺序与分配项不一致
+    let mut prices = env.price_accounts(false);
+    prices.swap(0, 2);
+    let ix = rebalance_positions(&owner, 0, &prices);
+    assert_error(env.send_as_owner(ix).await, ErrorCode::AssetNotRegistered);
+
+    // 缺少价格账户
+    let ix = rebalance_positions(&owner, 0, &prices[..2]);
+    assert_error(env.send_as_owner(ix).await, ErrorCode::InvalidOracleAccount);
+
+    // 只经过部分验证的价格
+    let oracle = env.oracles[0];
+    let mut account = oracle_account(PRICE, 0, START_TIME);
+    account.data = price_update(PRICE, 0, START_TIME, false);
+    env.set_account(&oracle, account);
+    let prices = env.price_accounts(false);
+    let ix = rebalance_positions(&owner, 0, &prices);
+    assert_error(env.send_as_owner(ix).await, ErrorCode::InvalidOracleAccount);
+}
+
+// 测试9: 重复初始化用户
+#[tokio::test]
+async fn test_initialize_user_twice() {
+    let mut env = start().await;
+    let owner = env.owner.pubkey();
+    env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+
+    // 系统程序返回 AccountAlreadyInUse
+    assert_error(env.send_as_owner(initialize_user(&owner, 4)).await, 0u32);
+}
+
+// 测试10: 费用金库必须是余额不低于免租金下限的系统账户
+#[tokio::test]
+async fn test_fee_treasury() {
+    let mut env = start().await;
+    env.init_registry().await;
+    let admin = env.admin.pubkey();
+
+    let ix = update_fee_config(&admin, FeeConfig::default(), &Pubkey::new_unique());
+    assert_error(env.send_as_admin(ix).await, ErrorCode::InvalidTreasury);
+
+    let ix = update_fee_config(&admin, FeeConfig::default(), &config_pda());
+    assert_error(
+        env.send_as_admin(ix).await,
+        AnchorError::AccountNotSystemOwned,
+    );
+}
+
+// 测试11: 存取款附加的资产注册表必须与分配项一一对应且可写
+#[tokio::test]
+async fn test_deposit_asset_accounts() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+
+    // 缺少资产注册表
+    let ix = deposit_funds(&owner, 0, 1, &allocations()[..1]);
+    assert_error(env.send_as_owner(ix).await, ErrorCode::AssetNotRegistered);
+
+    // 顺序与分配项不一致
+    let mut reversed = allocations();
+    reversed.reverse();
+    let ix = withdraw_funds(&owner, 0, 1, &reversed);
+    assert_error(env.send_as_owner(ix).await, ErrorCode::AssetNotRegistered);
+
+    // 只读的资产注册表
+    let mut ix = deposit_funds(&owner, 0, 1, &allocations());
+    let last = ix.accounts.len() - 1;
+    ix.accounts[last].is_writable = false;
+    assert_error(env.send_as_owner(ix).await, ErrorCode::AssetNotRegistered);
+}
+
+// 测试12: 提款工单只能由所属策略领取或取消
+#[tokio::test]
+async fn test_foreign_withdrawal_ticket() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+    env.send_as_owner(request_withdrawal(&owner, 0, 0, 1000))
+        .await
+        .unwrap();
+
+    env.send(&[initialize_user(&other.pubkey(), 3)], &[&other])
+        .await
+        .unwrap();
+    env.send(
+        &[create_strategy(
+            &other.pubkey(),
+            0,
+            allocations(),
+            condition(),
+            100,
+        )],
+        &[&other],
+    )
+    .await
+    .unwrap();
+
+    // 用自己的策略领取或取消他人的工单
+    let mut claim = claim_withdrawal(&other.pubkey(), 0, 0, &allocations(), &[]);
+    claim.accounts[3].pubkey = ticket_pda(&owner, 0, 0);
+    let mut cancel = cancel_withdrawal(&other.pubkey(), 0, 0);
+    cancel.accounts[3].pubkey = ticket_pda(&owner, 0, 0);
+    for ix in [claim, cancel] {
+        assert_error(
+            env.send(&[ix], &[&other]).await,
+            AnchorError::ConstraintSeeds,
+        );
+    }
+}
+
+// 测试13: 代理人账户只对所属策略和代理人有效，缺少代理人账户时视为未授权
+#[tokio::test]
+async fn test_delegate_account_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let bot = env.other.insecure_clone();
+    env.send_as_owner(create_strategy(&owner, 1, allocations(), condition(), 100))
+        .await
+        .unwrap();
+    env.send_as_owner(add_delegate(
+        &owner,
+        0,
+        &bot.pubkey(),
+        delegate_scope(),
+        START_TIME + 3600,
+    ))
+    .await
+    .unwrap();
+
+    // 用策略0的授权操作策略1
+    let mut ix = execute_strategy_as(&bot.pubkey(), &owner, 1);
+    ix.accounts[4].pubkey = delegate_pda(&owner, 0, &bot.pubkey());
+    assert_error(env.send(&[ix], &[&bot]).await, AnchorError::ConstraintSeeds);
+
+    // 不提供代理人账户
+    let mut ix = execute_strategy_as(&bot.pubkey(), &owner, 0);
+    ix.accounts[4].pubkey = defi_tools::ID;
+    assert_error(env.send(&[ix], &[&bot]).await, ErrorCode::Unauthorized);
+
+    // 代理人不能存取资金
+    let mut ix = withdraw_funds(&owner, 0, 1, &allocations());
+    ix.accounts[0].is_signer = false;
+    assert_error(
+        env.send(&[ix], &[&bot]).await,
+        AnchorError::AccountNotSigner,
+    );
+}
+
+// 测试14: 白名单账户只对所属用户和接收地址有效，接收地址必须是资金库以外的系统账户
+#[tokio::test]
+async fn test_withdrawal_destination_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+    let cold = Pubkey::new_unique();
+    env.send(&[initialize_user(&other.pubkey(), 3)], &[&other])
+        .await
+        .unwrap();
+    env.send(
+        &[add_withdrawal_destination(&other.pubkey(), &cold)],
+        &[&other],
+    )
+    .await
+    .unwrap();
+    env.send_as_owner(set_withdrawal_allowlist(&owner, true))
+        .await
+        .unwrap();
+    env.set_time(START_TIME + 7 * 24 * 3600).await;
+
+    // 使用他人的白名单账户
+    let entry = Some(destination_pda(&other.pubkey(), &cold));
+    let ix = withdraw_funds_to(&owner, 0, 1000, &allocations(), Payout::to(&cold, entry));
+    assert_error(env.send_as_owner(ix).await, AnchorError::ConstraintSeeds);
+
+    let vault = vault_pda(&owner);
+    let ix = withdraw_funds_to(&owner, 0, 1000, &allocations(), Payout::to(&vault, None));
+    assert_error(
+        env.send_as_owner(ix).await,
+        ErrorCode::WithdrawalDestinationNotAllowed,
+    );
+
+    let profile = user_pda(&owner);
+    let ix = withdraw_funds_to(&owner, 0, 1000, &allocations(), Payout::to(&profile, None));
+    assert_error(
+        env.send_as_owner(ix).await,
+        AnchorError::AccountNotSystemOwned,
+    );
+}
+
+// 测试15: 提款联署的监护人必须是该用户登记的监护人
+#[tokio::test]
+async fn test_spending_guardian_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let signer = env.owner.insecure_clone();
+    let owner = signer.pubkey();
+    let other = env.other.insecure_clone();
+    let payout = Payout::to(&owner, None).with_guardian(&other.pubkey());
+
+    // 未设置监护人
+    let ix = withdraw_funds_to(&owner, 0, 1000, &allocations(), payout);
+    assert_error(
+        env.send(&[ix], &[&signer, &other]).await,
+        ErrorCode::Unauthorized,
+    );
+
+    // 联署的不是登记的监护人
+    let guardian = Pubkey::new_unique();
+    env.send_as_owner(set_spending_limit(&owner, 1000, 3600, &guardian, None))
+        .await
+        .unwrap();
+    let ix = withdraw_funds_to(&owner, 0, 1000, &allocations(), payout);
+    assert_error(
+        env.send(&[ix], &[&signer, &other]).await,
+        ErrorCode::Unauthorized,
+    );
+}
+
+// 测试16: 只有被提名的新所有者能接受转移，原配置在接受后冻结
+#[tokio::test]
+async fn test_owner_transfer_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+
+    env.send_as_owner(propose_owner(&owner, &Pubkey::new_unique(), None))
+        .await
+        .unwrap();
+    assert_error(
+        env.send(&[accept_owner(&other.pubkey(), &owner)], &[&other])
+            .await,
+        ErrorCode::Unauthorized,
+    );
+
+    env.send_as_owner(propose_owner(&owner, &other.pubkey(), None))
+        .await
+        .unwrap();
+    env.send(&[accept_owner(&other.pubkey(), &owner)], &[&other])
+        .await
+        .unwrap();
+    assert_error(
+        env.send_as_owner(withdraw_funds(&owner, 0, 1000, &allocations()))
+            .await,
+        ErrorCode::ProfileMigrated,
+    );
+    assert_error(
+        env.send_as_owner(propose_owner(&owner, &Pubkey::new_unique(), None))
+            .await,
+        ErrorCode::ProfileMigrated,
+    );
+}
+
+// 测试17: 模板只能由发布者停用，使用模板时按资产的当前状态校验
+#[tokio::test]
+async fn test_template_binding() {
+    let mut env = start().await;
+    env.init_registry().await;
+    let publisher = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+    env.send_as_owner(publish_template(&publisher, 0, allocations(), 3))
+        .await
+        .unwrap();
+
+    let ix = build(
+        accounts::RetireTemplate {
+            publisher: other.pubkey(),
+            strategy_template: template_pda(&publisher, 0),
+        },
+        instruction::RetireTemplate { template_id: 0 },
+        &[],
+    );
+    assert_error(
+        env.send(&[ix], &[&other]).await,
+        AnchorError::ConstraintSeeds,
+    );
+
+    // 资产注册表必须与模板的分配项一一对应
+    env.send(&[initialize_user(&other.pubkey(), 3)], &[&other])
+        .await
+        .unwrap();
+    let ix = create_strategy_from_template(&other.pubkey(), 0, &publisher, 0, &allocations()[..1]);
+    assert_error(
+        env.send(&[ix], &[&other]).await,
+        ErrorCode::AssetNotRegistered,
+    );
+
+    // 发布后停用的资产不能再通过模板分配
+    let admin = env.admin.pubkey();
+    let params = AssetParams {
+        enabled: false,
+        ..env.asset_params(1)
+    };
+    env.send_as_admin(update_asset(&admin, 1, params))
+        .await
+        .unwrap();
+    let ix = create_strategy_from_template(&other.pubkey(), 0, &publisher, 0, &allocations());
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::AssetDisabled);
+}
+
+// 测试18: 同步必须使用跟随关系记录的领投策略，领投费只能付给领投策略的所有者
+#[tokio::test]
+async fn test_follow_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let leader = env.owner.pubkey();
+    let other = env.other.insecure_clone();
+    let owner = other.pubkey();
+    env.send(
+        &[
+            initialize_user(&owner, 3),
+            create_strategy(&owner, 0, allocations(), condition(), 100),
+            create_strategy(&owner, 1, allocations(), condition(), 100),
+            follow_strategy(&owner, 0, &leader, 0, 10000, 0, 100),
+        ],
+        &[&other],
+    )
+    .await
+    .unwrap();
+
+    let ix = sync_follower(&owner, &owner, 0, &owner, 1, &allocations());
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::Unauthorized);
+
+    let mut ix = sync_follower(&owner, &owner, 0, &leader, 0, &allocations());
+    ix.accounts[5].pubkey = owner;
+    assert_error(
+        env.send(&[ix], &[&other]).await,
+        AnchorError::ConstraintAddress,
+    );
+
+    // 只有跟随策略的所有者可以取消跟随
+    let admin = env.admin.insecure_clone();
+    let ix = build(
+        accounts::UnfollowStrategy {
+            owner: admin.pubkey(),
+            user_profile: user_pda(&owner),
+            strategy_config: strategy_pda(&owner, 0),
+            strategy_follow: follow_pda(&owner, 0),
+        },
+        instruction::UnfollowStrategy { strategy_id: 0 },
+        &[],
+    );
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+// 测试19: 定投计划与策略绑定，只有所有者可以取消
+#[tokio::test]
+async fn test_dca_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    env.send_as_owner(create_strategy(&owner, 1, allocations(), condition(), 100))
+        .await
+        .unwrap();
+    env.send_as_owner(create_dca(&owner, 0, dca_params(LAMPORTS_PER_SOL / 10, 0)))
+        .await
+        .unwrap();
+
+    let mut ix = execute_dca(&owner, &owner, 0, &env.price_accounts(false));
+    ix.accounts[3].pubkey = strategy_pda(&owner, 1);
+    assert_error(env.send_as_owner(ix).await, AnchorError::ConstraintSeeds);
+
+    let admin = env.admin.insecure_clone();
+    let ix = build(
+        accounts::CancelDca {
+            owner: admin.pubkey(),
+            user_profile: user_pda(&owner),
+            strategy_config: strategy_pda(&owner, 0),
+            dca_schedule: dca_pda(&owner, 0),
+        },
+        instruction::CancelDca { strategy_id: 0 },
+        &[],
+    );
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+// 测试20: 保护单只能按自己的策略估值，设置时不能立即触发，触发后租金只退还给所有者
+#[tokio::test]
+async fn test_protection_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let prices = env.price_accounts(false);
+    env.send_as_owner(create_strategy(&owner, 1, allocations(), condition(), 100))
+        .await
+        .unwrap();
+
+    // 估值为 1 SOL，止盈线不能低于当前估值
+    assert_error(
+        env.send_as_owner(set_protection(
+            &owner,
+            0,
+            protection_params(0, 0, LAMPORTS_PER_SOL),
+            &prices,
+        ))
+        .await,
+        ErrorCode::InvalidProtectionParams,
+    );
+    // 没有资金的策略不能设置保护单
+    assert_error(
+        env.send_as_owner(set_protection(
+            &owner,
+            1,
+            protection_params(0, 2000, 0),
+            &prices,
+        ))
+        .await,
+        ErrorCode::InsufficientFunds,
+    );
+
+    env.send_as_owner(set_protection(
+        &owner,
+        0,
+        protection_params(LAMPORTS_PER_SOL / 2, 0, 0),
+        &prices,
+    ))
+    .await
+    .unwrap();
+    let mut ix = check_protection(&owner, &owner, 0, &prices);
+    ix.accounts[3].pubkey = strategy_pda(&owner, 1);
+    assert_error(env.send_as_owner(ix).await, AnchorError::ConstraintSeeds);
+
+    // 租金必须退还给保护单的所有者
+    let other = env.other.pubkey();
+    let mut ix = check_protection(&owner, &owner, 0, &prices);
+    ix.accounts[2].pubkey = other;
+    assert_error(env.send_as_owner(ix).await, ErrorCode::Unauthorized);
+}
+
+// 测试21: 轮动计划只能修改自己的策略，利率追踪账户必须对应轮动资产，只有管理员能报告APY
+#[tokio::test]
+async fn test_yield_rotation_binding() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+    env.send_as_owner(create_strategy(&owner, 1, allocations(), condition(), 100))
+        .await
+        .unwrap();
+    for (protocol, asset, apy_bps) in [(1, 1, 500), (3, 1, 800), (1, 0, 500), (3, 0, 800)] {
+        env.send_as_admin(register_apy_tracker(&admin, protocol, asset, apy_bps))
+            .await
+            .unwrap();
+    }
+    env.send_as_owner(enable_yield_rotation(&owner, 0, yield_params(100, 0)))
+        .await
+        .unwrap();
+
+    let trackers = [apy_pda(1, 1), apy_pda(3, 1)];
+    let mut ix = rotate_yield(&owner, &owner, 0, &trackers);
+    ix.accounts[3].pubkey = strategy_pda(&owner, 1);
+    assert_error(env.send_as_owner(ix).await, AnchorError::ConstraintSeeds);
+
+    // 其他资产的利率追踪账户
+    assert_error(
+        env.send_as_owner(rotate_yield(
+            &owner,
+            &owner,
+            0,
+            &[apy_pda(1, 0), apy_pda(3, 0)],
+        ))
+        .await,
+        ErrorCode::InvalidApyTracker,
+    );
+
+    assert_error(
+        env.send_as_owner(report_apy(&owner, 3, 1, 100)).await,
+        ErrorCode::Unauthorized,
+    );
+    env.send_as_owner(rotate_yield(&owner, &owner, 0, &trackers))
+        .await
+        .unwrap();
+}
+
+// 测试22: 只有程序的升级权限能初始化全局配置，ProgramData 账户必须属于本程序
+#[tokio::test]
+async fn test_initialize_config_requires_upgrade_authority() {
+    let mut env = start().await;
+    let other = env.other.insecure_clone();
+
+    let ix = initialize_config(&other.pubkey(), guard());
+    assert_error(env.send(&[ix], &[&other]).await, ErrorCode::Unauthorized);
+
+    // 伪造一个以自己为升级权限的 ProgramData 账户
+    let fake = Pubkey::new_unique();
+    env.set_account(&fake, program_data_account(Some(other.pubkey())));
+    let mut ix = initialize_config(&other.pubkey(), guard());
+    ix.accounts[1].pubkey = fake;
+    assert_error(
+        env.send(&[ix], &[&other]).await,
+        AnchorError::ConstraintSeeds,
+    );
+
+    // 升级权限已放弃的程序不能再初始化
+    env.set_account(&program_data_pda(), program_data_account(None));
+    let admin = env.admin.pubkey();
+    assert_error(
+        env.send_as_admin(initialize_config(&admin, guard())).await,
+        ErrorCode::Unauthorized,
+    );
+}
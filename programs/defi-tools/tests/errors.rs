@@ -0,0 +1,812 @@
+// 每个程序错误码都通过真实交易触发一次
+mod common;
+
+use common::*;
+use defi_tools::withdrawals::MAX_WITHDRAWAL_DELAY_SECONDS;
+use defi_tools::{
+    Allocation, AssetParams, DelegateScope, DepositLimits, ErrorCode, FeeConfig,
+    RebalanceCondition, UserProfile, YieldParams,
+};
+use solana_program_test::BanksClientError;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::system_program;
+
+// 按声明顺序排列的全部错误码
+const ALL: &[ErrorCode] = &[
+    ErrorCode::Unauthorized,
+    ErrorCode::InvalidRiskLevel,
+    ErrorCode::InvalidStrategyId,
+    ErrorCode::InvalidAllocation,
+    ErrorCode::InvalidSlippage,
+    ErrorCode::StrategyPaused,
+    ErrorCode::InsufficientFunds,
+    ErrorCode::SlippageExceeded,
+    ErrorCode::IncompatibleProtocol,
+    ErrorCode::ProtocolNotRegistered,
+    ErrorCode::OperationTimeout,
+    ErrorCode::MathError,
+    ErrorCode::PositionNotFound,
+    ErrorCode::RebalanceConditionNotMet,
+    ErrorCode::EmergencyModeActive,
+    ErrorCode::StalePrice,
+    ErrorCode::PriceConfidenceTooWide,
+    ErrorCode::PriceSourceDivergence,
+    ErrorCode::InvalidPrice,
+    ErrorCode::InvalidOracleAccount,
+    ErrorCode::InvalidOracleGuardConfig,
+    ErrorCode::AssetNotRegistered,
+    ErrorCode::AssetDisabled,
+    ErrorCode::AssetAllocationCapExceeded,
+    ErrorCode::InvalidAssetConfig,
+    ErrorCode::UnknownProtocol,
+    ErrorCode::DuplicateAllocation,
+    ErrorCode::ZeroWeightAllocation,
+    ErrorCode::TooManyAllocations,
+    ErrorCode::AllocationWeightOutOfRange,
+    ErrorCode::InvalidFeeConfig,
+    ErrorCode::InvalidTreasury,
+    ErrorCode::DepositTooSmall,
+    ErrorCode::StrategyDepositCapExceeded,
+    ErrorCode::UserDepositCapExceeded,
+    ErrorCode::AssetDepositCapExceeded,
+    ErrorCode::InvalidDepositLimits,
+    ErrorCode::InvalidWithdrawalDelay,
+    ErrorCode::WithdrawalNotReady,
+    ErrorCode::WithdrawalOutOfOrder,
+    ErrorCode::InvalidDelegate,
+    ErrorCode::DelegateExpired,
+    ErrorCode::DelegatePermissionDenied,
+    ErrorCode::DelegateBoundsExceeded,
+    ErrorCode::InvalidWithdrawalDestination,
+    ErrorCode::WithdrawalDestinationNotAllowed,
+    ErrorCode::WithdrawalDestinationPending,
+    ErrorCode::InvalidSpendingLimit,
+    ErrorCode::SpendingLimitExceeded,
+    ErrorCode::GuardianApprovalRequired,
+    ErrorCode::InvalidNewOwner,
+    ErrorCode::OwnerTransferPending,
+    ErrorCode::ProfileMigrated,
+    ErrorCode::WithdrawalsPending,
+    ErrorCode::TemplateRiskTooHigh,
+    ErrorCode::InvalidFollowParams,
+    ErrorCode::FollowerInSync,
+    ErrorCode::FollowDelayActive,
+    ErrorCode::FollowerRiskExceeded,
+    ErrorCode::InvalidDcaParams,
+    ErrorCode::DcaPriceAboveLimit,
+    ErrorCode::DcaCompleted,
+    ErrorCode::InvalidProtectionParams,
+    ErrorCode::StrategyNotPaused,
+    ErrorCode::InvalidYieldParams,
+    ErrorCode::InvalidApyTracker,
+    ErrorCode::StaleApy,
+    ErrorCode::YieldHoldActive,
+    ErrorCode::YieldAdvantageTooSmall,
+    ErrorCode::TooManyAssetDeposits,
+    ErrorCode::StrategyModeConflict,
+    ErrorCode::DcaIntervalRequired,
+    ErrorCode::StrategyLinksActive,
+];
+
+// 在新的测试环境中构造触发该错误的交易。
+// 返回 None 表示程序目前没有任何代码路径返回该错误
+async fn trigger(code: ErrorCode) -> Option<Result<(), BanksClientError>> {
+    let mut env = start().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+
+    let result = match code {
+        ErrorCode::Unauthorized => {
+            env.init_registry().await;
+            let other = env.other.insecure_clone();
+            env.send(&[update_oracle_guard(&other.pubkey(), guard())], &[&other])
+                .await
+        }
+        ErrorCode::InvalidRiskLevel => env.send_as_owner(initialize_user(&owner, 6)).await,
+        ErrorCode::InvalidAllocation => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            let allocations = vec![allocation(0, 0, 6000), allocation(1, 1, 3000)];
+            env.send_as_owner(create_strategy(&owner, 0, allocations, condition(), 100))
+                .await
+        }
+        ErrorCode::InvalidSlippage => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            env.send_as_owner(create_strategy(&owner, 0, allocations(), condition(), 1001))
+                .await
+        }
+        ErrorCode::StrategyPaused => {
+            env.init_strategy().await;
+            env.modify(&user_pda(&owner), |profile: &mut UserProfile| {
+                profile.is_paused = true
+            })
+            .await;
+            env.send_as_owner(deposit_funds(&owner, 0, 1, &allocations()))
+                .await
+        }
+        ErrorCode::InsufficientFunds => {
+            env.init_strategy().await;
+            env.send_as_owner(withdraw_funds(&owner, 0, u64::MAX, &allocations()))
+                .await
+        }
+        ErrorCode::MathError => {
+            env.init_strategy().await;
+            env.send_as_owner(deposit_funds(&owner, 0, u64::MAX, &allocations()))
+                .await
+        }
+        ErrorCode::RebalanceConditionNotMet => {
+            env.init_strategy().await;
+            let prices = env.price_accounts(false);
+            env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+                .await
+                .unwrap();
+            env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+                .await
+        }
+        ErrorCode::StalePrice => {
+            env.init_strategy().await;
+            env.set_time(START_TIME + MAX_PRICE_AGE as i64 + 1).await;
+            let prices = env.price_accounts(false);
+            env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+                .await
+        }
+        ErrorCode::PriceConfidenceTooWide => {
+            env.init_strategy().await;
+            // 置信区间 3% 超过 2% 的上限
+            let oracle = env.oracles[1];
+            env.set_oracle(&oracle, PRICE, PRICE as u64 * 3 / 100, START_TIME);
+            let prices = env.price_accounts(false);
+            env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+                .await
+        }
+        ErrorCode::PriceSourceDivergence => {
+            env.init_strategy().await;
+            let mut cross_checked = guard();
+            cross_checked.max_divergence_bps = 100;
+            env.send_as_admin(update_oracle_guard(&admin, cross_checked))
+                .await
+                .unwrap();
+            for asset_id in 0..2 {
+                let mut params = env.asset_params(asset_id);
+                params.secondary_oracle = env.secondary_oracles[asset_id];
+                env.send_as_admin(update_asset(&admin, asset_id as u8, params))
+                    .await
+                    .unwrap();
+            }
+            // 主备价格相差 2%
+            let secondary = env.secondary_oracles[0];
+            env.set_oracle(&secondary, PRICE * 102 / 100, 0, START_TIME);
+            let prices = env.price_accounts(true);
+            env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+                .await
+        }
+        ErrorCode::InvalidPrice => {
+            env.init_strategy().await;
+            let oracle = env.oracles[0];
+            env.set_oracle(&oracle, 0, 0, START_TIME);
+            let prices = env.price_accounts(false);
+            env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+                .await
+        }
+        ErrorCode::InvalidOracleAccount => {
+            env.init_strategy().await;
+            // 价格账户不属于 Pyth 接收程序
+            let oracle = env.oracles[0];
+            let mut account = oracle_account(PRICE, 0, START_TIME);
+            account.owner = system_program::ID;
+            env.set_account(&oracle, account);
+            let prices = env.price_accounts(false);
+            env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+                .await
+        }
+        ErrorCode::InvalidOracleGuardConfig => {
+            let mut invalid = guard();
+            invalid.max_price_age_seconds = 0;
+            env.send_as_admin(initialize_config(&admin, invalid)).await
+        }
+        ErrorCode::AssetNotRegistered => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            let allocations = vec![allocation(0, 0, 6000), allocation(1, 2, 4000)];
+            env.send_as_owner(create_strategy(&owner, 0, allocations, condition(), 100))
+                .await
+        }
+        ErrorCode::AssetDisabled => {
+            env.init_registry().await;
+            let mut params = env.asset_params(1);
+            params.enabled = false;
+            env.send_as_admin(update_asset(&admin, 1, params))
+                .await
+                .unwrap();
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            env.send_as_owner(create_strategy(&owner, 0, allocations(), condition(), 100))
+                .await
+        }
+        ErrorCode::AssetAllocationCapExceeded => {
+            env.init_registry().await;
+            let mut params = env.asset_params(0);
+            params.max_allocation_bps = 5000;
+            env.send_as_admin(update_asset(&admin, 0, params))
+                .await
+                .unwrap();
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            env.send_as_owner(create_strategy(&owner, 0, allocations(), condition(), 100))
+                .await
+        }
+        ErrorCode::InvalidAssetConfig => {
+            env.init_registry().await;
+            let mut params = env.asset_params(0);
+            params.mint = Pubkey::default();
+            env.send_as_admin(register_asset(&admin, 2, params)).await
+        }
+        ErrorCode::UnknownProtocol => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            let allocations = vec![allocation(9, 0, 6000), allocation(1, 1, 4000)];
+            env.send_as_owner(create_strategy(&owner, 0, allocations, condition(), 100))
+                .await
+        }
+        ErrorCode::DuplicateAllocation => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            let allocations = vec![allocation(0, 0, 5000), allocation(0, 0, 5000)];
+            env.send_as_owner(create_strategy(&owner, 0, allocations, condition(), 100))
+                .await
+        }
+        ErrorCode::ZeroWeightAllocation => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            let allocations = vec![allocation(0, 0, 10000), allocation(1, 1, 0)];
+            env.send_as_owner(create_strategy(&owner, 0, allocations, condition(), 100))
+                .await
+        }
+        ErrorCode::TooManyAllocations => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            let mut allocations: Vec<Allocation> =
+                (0..11u8).map(|i| allocation(i % 4, i / 4, 909)).collect();
+            allocations[0].target_weight_bps = 910;
+            env.send_as_owner(create_strategy(&owner, 0, allocations, condition(), 100))
+                .await
+        }
+        ErrorCode::AllocationWeightOutOfRange => {
+            env.init_registry().await;
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            env.send_as_owner(create_strategy(&owner, 0, allocations(), condition(), 100))
+                .await
+                .unwrap();
+            // 65535 + 10001 按 u16 累加会回绕为 10000
+            let allocations = vec![allocation(0, 0, 65535), allocation(1, 1, 10001)];
+            env.send_as_owner(update_strategy(&owner, 0, allocations, condition(), 100))
+                .await
+        }
+        ErrorCode::InvalidFeeConfig => {
+            env.init_registry().await;
+            let fees = FeeConfig {
+                management_fee_bps: 501,
+                performance_fee_bps: 0,
+            };
+            env.send_as_admin(update_fee_config(&admin, fees, &TREASURY))
+                .await
+        }
+        ErrorCode::InvalidTreasury => {
+            env.init_strategy().await;
+            // 传入的费用金库与全局配置不一致
+            let mut ix = deposit_funds(&owner, 0, 1, &allocations());
+            ix.accounts[5].pubkey = admin;
+            env.send_as_owner(ix).await
+        }
+        ErrorCode::DepositTooSmall => {
+            env.init_strategy().await;
+            let limits = DepositLimits {
+                min_deposit_lamports: 1000,
+                ..DepositLimits::default()
+            };
+            env.send_as_admin(update_deposit_limits(&admin, limits))
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, 999, &allocations()))
+                .await
+        }
+        ErrorCode::StrategyDepositCapExceeded => {
+            env.init_strategy().await;
+            let limits = DepositLimits {
+                max_strategy_value_lamports: LAMPORTS_PER_SOL,
+                ..DepositLimits::default()
+            };
+            env.send_as_admin(update_deposit_limits(&admin, limits))
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, 1, &allocations()))
+                .await
+        }
+        ErrorCode::UserDepositCapExceeded => {
+            env.init_strategy().await;
+            let limits = DepositLimits {
+                max_user_value_lamports: LAMPORTS_PER_SOL,
+                ..DepositLimits::default()
+            };
+            env.send_as_admin(update_deposit_limits(&admin, limits))
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, 1, &allocations()))
+                .await
+        }
+        ErrorCode::AssetDepositCapExceeded => {
+            env.init_strategy().await;
+            // 资产0已分摊 0.6 SOL
+            let params = AssetParams {
+                deposit_cap_lamports: LAMPORTS_PER_SOL,
+                ..env.asset_params(0)
+            };
+            env.send_as_admin(update_asset(&admin, 0, params))
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+                .await
+        }
+        ErrorCode::InvalidDepositLimits => {
+            env.init_registry().await;
+            let limits = DepositLimits {
+                min_deposit_lamports: 1000,
+                max_strategy_value_lamports: 999,
+                max_user_value_lamports: 0,
+            };
+            env.send_as_admin(update_deposit_limits(&admin, limits))
+                .await
+        }
+        ErrorCode::InvalidWithdrawalDelay => {
+            env.init_registry().await;
+            let delay = MAX_WITHDRAWAL_DELAY_SECONDS + 1;
+            env.send_as_admin(update_withdrawal_delay(&admin, delay))
+                .await
+        }
+        ErrorCode::WithdrawalNotReady => {
+            env.init_strategy().await;
+            env.send_as_admin(update_withdrawal_delay(&admin, 3600))
+                .await
+                .unwrap();
+            env.send_as_owner(request_withdrawal(&owner, 0, 0, 1000))
+                .await
+                .unwrap();
+            env.send_as_owner(claim_withdrawal(&owner, 0, 0, &allocations(), &[]))
+                .await
+        }
+        ErrorCode::WithdrawalOutOfOrder => {
+            env.init_strategy().await;
+            for ticket_id in 0..2 {
+                env.send_as_owner(request_withdrawal(&owner, 0, ticket_id, 1000))
+                    .await
+                    .unwrap();
+            }
+            // 工单0仍在队首
+            env.send_as_owner(claim_withdrawal(&owner, 0, 1, &allocations(), &[0]))
+                .await
+        }
+        ErrorCode::InvalidDelegate => {
+            env.init_strategy().await;
+            // 所有者不能授权给自己
+            let expires_at = START_TIME + 3600;
+            env.send_as_owner(add_delegate(
+                &owner,
+                0,
+                &owner,
+                delegate_scope(),
+                expires_at,
+            ))
+            .await
+        }
+        ErrorCode::DelegateExpired => {
+            env.init_strategy().await;
+            let other = env.other.insecure_clone();
+            let expires_at = START_TIME + 3600;
+            env.send_as_owner(add_delegate(
+                &owner,
+                0,
+                &other.pubkey(),
+                delegate_scope(),
+                expires_at,
+            ))
+            .await
+            .unwrap();
+            env.set_time(expires_at).await;
+            env.send(
+                &[execute_strategy_as(&other.pubkey(), &owner, 0)],
+                &[&other],
+            )
+            .await
+        }
+        ErrorCode::DelegatePermissionDenied => {
+            env.init_strategy().await;
+            let other = env.other.insecure_clone();
+            let scope = DelegateScope {
+                rebalance: false,
+                ..delegate_scope()
+            };
+            env.send_as_owner(add_delegate(
+                &owner,
+                0,
+                &other.pubkey(),
+                scope,
+                START_TIME + 3600,
+            ))
+            .await
+            .unwrap();
+            let prices = env.price_accounts(false);
+            let ix = rebalance_positions_as(&other.pubkey(), &owner, 0, &prices);
+            env.send(&[ix], &[&other]).await
+        }
+        ErrorCode::DelegateBoundsExceeded => {
+            env.init_strategy().await;
+            let other = env.other.insecure_clone();
+            env.send_as_owner(add_delegate(
+                &owner,
+                0,
+                &other.pubkey(),
+                delegate_scope(),
+                START_TIME + 3600,
+            ))
+            .await
+            .unwrap();
+            // 每项权重变化20%，超过授权的10%
+            let allocations = vec![allocation(0, 0, 8000), allocation(1, 1, 2000)];
+            let ix = update_strategy_as(&other.pubkey(), &owner, 0, allocations, condition(), 100);
+            env.send(&[ix], &[&other]).await
+        }
+        ErrorCode::InvalidWithdrawalDestination => {
+            env.init_strategy().await;
+            // 所有者本人始终允许，不能加入白名单
+            env.send_as_owner(add_withdrawal_destination(&owner, &owner))
+                .await
+        }
+        ErrorCode::WithdrawalDestinationNotAllowed => {
+            env.init_strategy().await;
+            env.send_as_owner(set_withdrawal_allowlist(&owner, true))
+                .await
+                .unwrap();
+            let cold = Pubkey::new_unique();
+            let ix = withdraw_funds_to(&owner, 0, 1000, &allocations(), Payout::to(&cold, None));
+            env.send_as_owner(ix).await
+        }
+        ErrorCode::WithdrawalDestinationPending => {
+            env.init_strategy().await;
+            let cold = Pubkey::new_unique();
+            env.send_as_owner(set_withdrawal_allowlist(&owner, true))
+                .await
+                .unwrap();
+            env.send_as_owner(add_withdrawal_destination(&owner, &cold))
+                .await
+                .unwrap();
+            let entry = Some(destination_pda(&owner, &cold));
+            let ix = withdraw_funds_to(&owner, 0, 1000, &allocations(), Payout::to(&cold, entry));
+            env.send_as_owner(ix).await
+        }
+        ErrorCode::InvalidSpendingLimit => {
+            env.init_strategy().await;
+            // 设置了额度但没有周期
+            let ix = set_spending_limit(&owner, 1000, 0, &Pubkey::default(), None);
+            env.send_as_owner(ix).await
+        }
+        ErrorCode::SpendingLimitExceeded => {
+            env.init_strategy().await;
+            let ix = set_spending_limit(&owner, 1000, 3600, &env.other.pubkey(), None);
+            env.send_as_owner(ix).await.unwrap();
+            env.send_as_owner(withdraw_funds(&owner, 0, 2000, &allocations()))
+                .await
+        }
+        ErrorCode::GuardianApprovalRequired => {
+            env.init_strategy().await;
+            let guardian = env.other.pubkey();
+            let ix = set_spending_limit(&owner, 1000, 3600, &guardian, None);
+            env.send_as_owner(ix).await.unwrap();
+            // 提高额度但没有监护人联署
+            let ix = set_spending_limit(&owner, 2000, 3600, &guardian, None);
+            env.send_as_owner(ix).await
+        }
+        ErrorCode::InvalidNewOwner => {
+            env.init_strategy().await;
+            env.send_as_owner(propose_owner(&owner, &owner, None)).await
+        }
+        ErrorCode::OwnerTransferPending => {
+            env.init_strategy().await;
+            let other = env.other.insecure_clone();
+            // 白名单生效时接受转移需要等待延迟
+            env.send_as_owner(set_withdrawal_allowlist(&owner, true))
+                .await
+                .unwrap();
+            env.send_as_owner(propose_owner(&owner, &other.pubkey(), None))
+                .await
+                .unwrap();
+            env.send(&[accept_owner(&other.pubkey(), &owner)], &[&other])
+                .await
+        }
+        ErrorCode::ProfileMigrated => {
+            env.init_strategy().await;
+            let other = env.other.insecure_clone();
+            env.send_as_owner(propose_owner(&owner, &other.pubkey(), None))
+                .await
+                .unwrap();
+            env.send(&[accept_owner(&other.pubkey(), &owner)], &[&other])
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+                .await
+        }
+        ErrorCode::WithdrawalsPending | ErrorCode::StrategyLinksActive => {
+            env.init_strategy().await;
+            let other = env.other.insecure_clone();
+            let ix = match code {
+                ErrorCode::WithdrawalsPending => request_withdrawal(&owner, 0, 0, 1000),
+                _ => create_dca(&owner, 0, dca_params(LAMPORTS_PER_SOL, 0)),
+            };
+            env.send_as_owner(ix).await.unwrap();
+            env.send_as_owner(propose_owner(&owner, &other.pubkey(), None))
+                .await
+                .unwrap();
+            env.send(&[accept_owner(&other.pubkey(), &owner)], &[&other])
+                .await
+                .unwrap();
+            let ix = migrate_strategy(&other.pubkey(), &owner, 0);
+            env.send(&[ix], &[&other]).await
+        }
+        ErrorCode::TemplateRiskTooHigh => {
+            env.init_registry().await;
+            let other = env.other.insecure_clone();
+            env.send(
+                &[publish_template(&other.pubkey(), 0, allocations(), 4)],
+                &[&other],
+            )
+            .await
+            .unwrap();
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            let ix = create_strategy_from_template(&owner, 0, &other.pubkey(), 0, &allocations());
+            env.send_as_owner(ix).await
+        }
+        ErrorCode::InvalidFollowParams => {
+            env.init_strategy().await;
+            env.send_as_owner(follow_strategy(&owner, 0, &owner, 0, 10000, 0, 0))
+                .await
+        }
+        ErrorCode::FollowerInSync
+        | ErrorCode::FollowDelayActive
+        | ErrorCode::FollowerRiskExceeded => {
+            env.init_strategy().await;
+            let other = env.other.insecure_clone();
+            let (max_weight_bps, delay_seconds) = match code {
+                ErrorCode::FollowDelayActive => (10000, 60),
+                ErrorCode::FollowerRiskExceeded => (5000, 0),
+                _ => (10000, 0),
+            };
+            env.send(
+                &[
+                    initialize_user(&other.pubkey(), 3),
+                    create_strategy(&other.pubkey(), 0, allocations(), condition(), 100),
+                    follow_strategy(
+                        &other.pubkey(),
+                        0,
+                        &owner,
+                        0,
+                        max_weight_bps,
+                        delay_seconds,
+                        0,
+                    ),
+                ],
+                &[&other],
+            )
+            .await
+            .unwrap();
+            let ix = sync_follower(&admin, &other.pubkey(), 0, &owner, 0, &allocations());
+            if matches!(code, ErrorCode::FollowerInSync) {
+                env.send_as_admin(ix.clone()).await.unwrap();
+            }
+            env.send_as_admin(ix).await
+        }
+        ErrorCode::InvalidDcaParams => {
+            env.init_strategy().await;
+            env.send_as_owner(create_dca(&owner, 0, dca_params(0, 0)))
+                .await
+        }
+        ErrorCode::DcaPriceAboveLimit | ErrorCode::DcaCompleted => {
+            env.init_strategy().await;
+            // 价格为1.0，按报价精度为 1_000_000
+            let params = match code {
+                ErrorCode::DcaPriceAboveLimit => dca_params(LAMPORTS_PER_SOL / 10, 999_999),
+                _ => dca_params(LAMPORTS_PER_SOL, 0),
+            };
+            env.send_as_owner(create_dca(&owner, 0, params))
+                .await
+                .unwrap();
+            let ix = execute_dca(&admin, &owner, 0, &env.price_accounts(false));
+            if matches!(code, ErrorCode::DcaCompleted) {
+                env.send_as_admin(ix.clone()).await.unwrap();
+                env.set_time(START_TIME + INTERVAL as i64).await;
+            }
+            env.send_as_admin(ix).await
+        }
+        ErrorCode::InvalidProtectionParams => {
+            env.init_strategy().await;
+            let prices = env.price_accounts(false);
+            env.send_as_owner(set_protection(
+                &owner,
+                0,
+                protection_params(0, 0, 0),
+                &prices,
+            ))
+            .await
+        }
+        ErrorCode::StrategyNotPaused => {
+            env.init_strategy().await;
+            env.send_as_owner(resume_strategy(&owner, 0)).await
+        }
+        ErrorCode::IncompatibleProtocol => {
+            env.init_registry().await;
+            // Raydium 不是借贷协议
+            env.send_as_admin(register_apy_tracker(&admin, 0, 1, 500))
+                .await
+        }
+        ErrorCode::InvalidYieldParams | ErrorCode::PositionNotFound => {
+            env.init_strategy().await;
+            let params = match code {
+                ErrorCode::InvalidYieldParams => YieldParams {
+                    candidate_protocols: 0b0010,
+                    ..yield_params(0, 0)
+                },
+                // 资产0的分配项在 Raydium 上
+                _ => YieldParams {
+                    asset: 0,
+                    ..yield_params(0, 0)
+                },
+            };
+            env.send_as_owner(enable_yield_rotation(&owner, 0, params))
+                .await
+        }
+        ErrorCode::InvalidApyTracker
+        | ErrorCode::StaleApy
+        | ErrorCode::YieldHoldActive
+        | ErrorCode::YieldAdvantageTooSmall => {
+            env.init_strategy().await;
+            let (mango_apy, min_hold_seconds) = match code {
+                ErrorCode::YieldAdvantageTooSmall => (500, 0),
+                ErrorCode::YieldHoldActive => (800, 3600),
+                _ => (800, 0),
+            };
+            env.send_as_admin(register_apy_tracker(&admin, 1, 1, 500))
+                .await
+                .unwrap();
+            env.send_as_admin(register_apy_tracker(&admin, 3, 1, mango_apy))
+                .await
+                .unwrap();
+            env.send_as_owner(enable_yield_rotation(
+                &owner,
+                0,
+                yield_params(100, min_hold_seconds),
+            ))
+            .await
+            .unwrap();
+            let trackers = match code {
+                ErrorCode::InvalidApyTracker => vec![apy_pda(3, 1), apy_pda(1, 1)],
+                _ => vec![apy_pda(1, 1), apy_pda(3, 1)],
+            };
+            if matches!(code, ErrorCode::StaleApy) {
+                env.set_time(START_TIME + 86_401).await;
+            }
+            env.send_as_admin(rotate_yield(&admin, &owner, 0, &trackers))
+                .await
+        }
+        ErrorCode::TooManyAssetDeposits => {
+            env.init_registry().await;
+            let signer = env.admin.insecure_clone();
+            for batch in (2..=20u8).collect::<Vec<_>>().chunks(5) {
+                let registrations: Vec<_> = batch
+                    .iter()
+                    .map(|asset_id| {
+                        let params = AssetParams {
+                            mint: Pubkey::new_unique(),
+                            ..env.asset_params(0)
+                        };
+                        register_asset(&admin, *asset_id, params)
+                    })
+                    .collect();
+                env.send(&registrations, &[&signer]).await.unwrap();
+            }
+            env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+            // 先后存入两组各10个资产，再换成第21个资产
+            let spread = |first: u8| -> Vec<Allocation> {
+                (first..first + 10)
+                    .map(|i| allocation(0, i, 1000))
+                    .collect()
+            };
+            env.send_as_owner(create_strategy(&owner, 0, spread(0), condition(), 100))
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &spread(0)))
+                .await
+                .unwrap();
+            env.send_as_owner(update_strategy(&owner, 0, spread(10), condition(), 100))
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &spread(10)))
+                .await
+                .unwrap();
+            let last = vec![allocation(0, 20, 10000)];
+            env.send_as_owner(update_strategy(&owner, 0, last.clone(), condition(), 100))
+                .await
+                .unwrap();
+            env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &last))
+                .await
+        }
+        ErrorCode::StrategyModeConflict => {
+            env.init_strategy().await;
+            env.send_as_owner(create_dca(&owner, 0, dca_params(LAMPORTS_PER_SOL, 0)))
+                .await
+                .unwrap();
+            env.send_as_owner(enable_yield_rotation(&owner, 0, yield_params(100, 0)))
+                .await
+        }
+        ErrorCode::DcaIntervalRequired => {
+            env.init_strategy().await;
+            env.send_as_owner(create_dca(&owner, 0, dca_params(LAMPORTS_PER_SOL, 0)))
+                .await
+                .unwrap();
+            let condition = RebalanceCondition {
+                time_interval_seconds: 0,
+                ..condition()
+            };
+            env.send_as_owner(update_strategy(&owner, 0, allocations(), condition, 100))
+                .await
+        }
+        ErrorCode::InvalidStrategyId
+        | ErrorCode::SlippageExceeded
+        | ErrorCode::ProtocolNotRegistered
+        | ErrorCode::OperationTimeout
+        | ErrorCode::EmergencyModeActive => return None,
+    };
+    Some(result)
+}
+
+// 测试1: 错误码列表与 IDL 一致
+#[test]
+fn test_error_list_matches_idl() {
+    let idl: serde_json::Value =
+        serde_json::from_str(include_str!("../../../sdk/src/idl/defi_tools.json")).unwrap();
+    let errors = idl["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), ALL.len());
+    for (error, code) in errors.iter().zip(ALL) {
+        assert_eq!(error["code"].as_u64().unwrap(), u32::from(*code) as u64);
+        assert_eq!(error["name"].as_str().unwrap(), code.name());
+    }
+}
+
+// 测试2: 每个有代码路径的错误码都能通过交易触发
+#[tokio::test]
+async fn test_every_error_code() {
+    let mut reserved = Vec::new();
+    for code in ALL {
+        match trigger(*code).await {
+            Some(result) => {
+                let err = result.expect_err(&code.name());
+                assert_eq!(
+                    error_code(&err),
+                    Some(u32::from(*code)),
+                    "{}: {err:?}",
+                    code.name()
+                );
+            }
+            None => reserved.push(code.name()),
+        }
+    }
+    // 预留但尚未使用的错误码
+    assert_eq!(
+        reserved,
+        [
+            "InvalidStrategyId",
+            "SlippageExceeded",
+            "ProtocolNotRegistered",
+            "OperationTimeout",
+            "EmergencyModeActive",
+        ]
+    );
+}
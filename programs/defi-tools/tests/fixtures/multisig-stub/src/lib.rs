@@ -0,0 +1,273 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+// 集成测试使用的最小多签程序，模仿 Squads 的金库交易流程:
+// 成员提交一条待执行的指令并收集批准，达到门限后由金库 PDA 通过 CPI 签名执行。
+// 金库是系统程序拥有的 PDA，可以作为 defi-tools 用户配置的所有者并支付租金。
+// 没有时间锁、成员变更和交易关闭，不能用于真实资金
+
+declare_id!("GaivT8suvyxnSBKcSBKV4j1QKVT3ctQQhKL6vWabRYot");
+
+pub const MAX_MEMBERS: usize = 10;
+
+#[program]
+pub mod multisig_stub {
+    use super::*;
+
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        create_key: Pubkey,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            !members.is_empty() && members.len() <= MAX_MEMBERS,
+            MultisigError::InvalidMembers
+        );
+        require!(
+            threshold >= 1 && usize::from(threshold) <= members.len(),
+            MultisigError::InvalidThreshold
+        );
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.create_key = create_key;
+        multisig.members = members;
+        multisig.threshold = threshold;
+        multisig.transaction_index = 0;
+        multisig.vault_bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // 提交即视为提交者的批准
+    pub fn create_transaction(
+        ctx: Context<CreateTransaction>,
+        program_id: Pubkey,
+        accounts: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let member = ctx.accounts.member.key();
+        let multisig = &mut ctx.accounts.multisig;
+        require!(multisig.is_member(&member), MultisigError::NotMember);
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.multisig = multisig.key();
+        transaction.index = multisig.transaction_index;
+        transaction.program_id = program_id;
+        transaction.accounts = accounts;
+        transaction.data = data;
+        transaction.approvals = vec![member];
+        transaction.executed = false;
+
+        multisig.transaction_index = multisig
+            .transaction_index
+            .checked_add(1)
+            .ok_or(MultisigError::InvalidTransaction)?;
+        Ok(())
+    }
+
+    pub fn approve(ctx: Context<Approve>, _index: u64) -> Result<()> {
+        let member = ctx.accounts.member.key();
+        require!(
+            ctx.accounts.multisig.is_member(&member),
+            MultisigError::NotMember
+        );
+
+        let transaction = &mut ctx.accounts.transaction;
+        require!(!transaction.executed, MultisigError::AlreadyExecuted);
+        require!(
+            !transaction.approvals.contains(&member),
+            MultisigError::AlreadyApproved
+        );
+        transaction.approvals.push(member);
+        Ok(())
+    }
+
+    // 指令涉及的账户(包括被调用的程序)按任意顺序放在剩余账户中
+    pub fn execute<'info>(
+        ctx: Context<'_, '_, '_, 'info, Execute<'info>>,
+        _index: u64,
+    ) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        require!(
+            multisig.is_member(&ctx.accounts.member.key()),
+            MultisigError::NotMember
+        );
+
+        let transaction = &mut ctx.accounts.transaction;
+        require!(!transaction.executed, MultisigError::AlreadyExecuted);
+        require!(
+            transaction.approvals.len() >= usize::from(multisig.threshold),
+            MultisigError::NotEnoughApprovals
+        );
+        transaction.executed = true;
+
+        let ix = Instruction {
+            program_id: transaction.program_id,
+            accounts: transaction
+                .accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: transaction.data.clone(),
+        };
+        let multisig_key = multisig.key();
+        let seeds: &[&[u8]] = &[b"vault", multisig_key.as_ref(), &[multisig.vault_bump]];
+        invoke_signed(&ix, ctx.remaining_accounts, &[seeds])?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(create_key: Pubkey)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Multisig::SPACE,
+        seeds = [b"multisig", create_key.as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: 只用于记录金库 PDA 的 bump
+    #[account(seeds = [b"vault", multisig.key().as_ref()], bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<TransactionAccount>, data: Vec<u8>)]
+pub struct CreateTransaction<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = member,
+        space = VaultTransaction::space(accounts.len(), data.len(), multisig.members.len()),
+        seeds = [
+            b"transaction",
+            multisig.key().as_ref(),
+            &multisig.transaction_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub transaction: Account<'info, VaultTransaction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct Approve<'info> {
+    pub member: Signer<'info>,
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"transaction", multisig.key().as_ref(), &index.to_le_bytes()],
+        bump
+    )]
+    pub transaction: Account<'info, VaultTransaction>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct Execute<'info> {
+    pub member: Signer<'info>,
+
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"transaction", multisig.key().as_ref(), &index.to_le_bytes()],
+        bump
+    )]
+    pub transaction: Account<'info, VaultTransaction>,
+
+    /// CHECK: 由种子约束校验，通过 CPI 签名
+    #[account(mut, seeds = [b"vault", multisig.key().as_ref()], bump = multisig.vault_bump)]
+    pub vault: UncheckedAccount<'info>,
+}
+
+#[account]
+pub struct Multisig {
+    pub create_key: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub transaction_index: u64,
+    pub vault_bump: u8,
+}
+
+impl Multisig {
+    pub const SPACE: usize = 8 + 32 + 4 + 32 * MAX_MEMBERS + 1 + 8 + 1;
+
+    pub fn is_member(&self, key: &Pubkey) -> bool {
+        self.members.contains(key)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl From<&AccountMeta> for TransactionAccount {
+    fn from(meta: &AccountMeta) -> Self {
+        Self {
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        }
+    }
+}
+
+#[account]
+pub struct VaultTransaction {
+    pub multisig: Pubkey,
+    pub index: u64,
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+    pub approvals: Vec<Pubkey>,
+    pub executed: bool,
+}
+
+impl VaultTransaction {
+    pub fn space(accounts: usize, data: usize, members: usize) -> usize {
+        8 + 32 + 8 + 32 + (4 + 34 * accounts) + (4 + data) + (4 + 32 * members) + 1
+    }
+}
+
+#[error_code]
+pub enum MultisigError {
+    #[msg("成员数量无效")]
+    InvalidMembers,
+    #[msg("门限无效")]
+    InvalidThreshold,
+    #[msg("签名者不是多签成员")]
+    NotMember,
+    #[msg("成员已批准")]
+    AlreadyApproved,
+    #[msg("批准数量未达到门限")]
+    NotEnoughApprovals,
+    #[msg("交易已执行")]
+    AlreadyExecuted,
+    #[msg("交易无效")]
+    InvalidTransaction,
+}
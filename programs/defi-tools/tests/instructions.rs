@@ -0,0 +1,1789 @@
+// 每条指令的成功路径
+mod common;
+
+use common::*;
+use defi_tools::allowlist::DEFAULT_ALLOWLIST_DELAY_SECONDS;
+use defi_tools::fees::SECONDS_PER_YEAR;
+use defi_tools::{
+    accounts, instruction, AssetParams, AssetRegistry, DcaSchedule, DepositLimits, ErrorCode,
+    FeeConfig, GlobalConfig, PositionTracker, ProtectiveOrder, RebalanceCondition, StrategyConfig,
+    StrategyDelegate, StrategyFollow, StrategyMode, StrategyTemplate, UserProfile,
+    WithdrawalDestination, WithdrawalTicket, YieldRotation,
+};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+// 测试1: initialize
+#[tokio::test]
+async fn test_initialize() {
+    let mut env = start().await;
+    env.send_as_admin(build(
+        accounts::Initialize {},
+        instruction::Initialize {},
+        &[],
+    ))
+    .await
+    .unwrap();
+}
+
+// 测试2: 初始化全局配置并更新预言机校验参数
+#[tokio::test]
+async fn test_initialize_config_and_update_oracle_guard() {
+    let mut env = start().await;
+    let admin = env.admin.pubkey();
+    env.send_as_admin(initialize_config(&admin, guard()))
+        .await
+        .unwrap();
+
+    let config: GlobalConfig = env.account(&config_pda()).await;
+    assert_eq!(config.admin, admin);
+    assert_eq!(config.oracle_guard.max_price_age_seconds, MAX_PRICE_AGE);
+    assert_eq!(config.treasury, admin);
+    assert_eq!(config.fees.management_fee_bps, 0);
+
+    let mut updated = guard();
+    updated.max_price_age_seconds = 120;
+    updated.max_divergence_bps = 100;
+    env.send_as_admin(update_oracle_guard(&admin, updated))
+        .await
+        .unwrap();
+
+    let config: GlobalConfig = env.account(&config_pda()).await;
+    assert_eq!(config.oracle_guard.max_price_age_seconds, 120);
+    assert_eq!(config.oracle_guard.max_divergence_bps, 100);
+}
+
+// 测试3: 注册并更新资产
+#[tokio::test]
+async fn test_register_and_update_asset() {
+    let mut env = start().await;
+    env.init_registry().await;
+
+    let registry: AssetRegistry = env.account(&asset_pda(1)).await;
+    assert_eq!(registry.asset_id, 1);
+    assert_eq!(registry.oracle, env.oracles[1]);
+    assert!(registry.enabled);
+
+    let mut params = env.asset_params(1);
+    params.enabled = false;
+    params.max_allocation_bps = 5000;
+    params.secondary_oracle = env.secondary_oracles[1];
+    let admin = env.admin.pubkey();
+    env.send_as_admin(update_asset(&admin, 1, params))
+        .await
+        .unwrap();
+
+    let registry: AssetRegistry = env.account(&asset_pda(1)).await;
+    assert!(!registry.enabled);
+    assert_eq!(registry.max_allocation_bps, 5000);
+    assert_eq!(registry.secondary_oracle, env.secondary_oracles[1]);
+}
+
+// 测试4: 初始化用户并创建策略
+#[tokio::test]
+async fn test_initialize_user_and_create_strategy() {
+    let mut env = start().await;
+    env.init_registry().await;
+    let owner = env.owner.pubkey();
+
+    env.send_as_owner(initialize_user(&owner, 5)).await.unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.owner, owner);
+    assert_eq!(profile.risk_level, 5);
+    assert_eq!(profile.strategy_counter, 0);
+    assert_eq!(profile.last_activity, START_TIME);
+
+    for strategy_id in 0..2 {
+        env.send_as_owner(create_strategy(
+            &owner,
+            strategy_id,
+            allocations(),
+            condition(),
+            100,
+        ))
+        .await
+        .unwrap();
+    }
+
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.strategy_counter, 2);
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 1)).await;
+    assert_eq!(strategy.owner, owner);
+    assert_eq!(strategy.strategy_id, 1);
+    assert_eq!(strategy.allocations.len(), 2);
+    assert_eq!(strategy.max_slippage_bps, 100);
+    assert_eq!(strategy.created_at, START_TIME);
+    assert_eq!(strategy.last_executed_at, 0);
+}
+
+// 测试5: 存入和提取资金
+#[tokio::test]
+async fn test_deposit_and_withdraw() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+
+    env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+        .await
+        .unwrap();
+    env.send_as_owner(withdraw_funds(
+        &owner,
+        0,
+        LAMPORTS_PER_SOL / 2,
+        &allocations(),
+    ))
+    .await
+    .unwrap();
+
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL * 3 / 2);
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.total_value_lamports, LAMPORTS_PER_SOL * 3 / 2);
+
+    // 资金库托管的 lamports 与记账一致
+    let rent = env.ctx.banks_client.get_rent().await.unwrap();
+    let vault = env.raw_account(&vault_pda(&owner)).await.unwrap();
+    assert_eq!(
+        vault.lamports,
+        rent.minimum_balance(0) + LAMPORTS_PER_SOL * 3 / 2
+    );
+}
+
+// 测试6: 执行策略
+#[tokio::test]
+async fn test_execute_strategy() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+
+    env.set_time(START_TIME + 10).await;
+    env.send_as_owner(execute_strategy(&owner, 0))
+        .await
+        .unwrap();
+
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.last_executed_at, START_TIME + 10);
+}
+
+// 测试7: 再平衡头寸，间隔到达后可以再次执行
+#[tokio::test]
+async fn test_rebalance_positions() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let prices = env.price_accounts(false);
+
+    env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.last_executed_at, START_TIME);
+
+    let now = START_TIME + INTERVAL as i64;
+    env.set_time(now).await;
+    for oracle in env.oracles {
+        env.set_oracle(&oracle, PRICE, PRICE as u64 / 1000, now);
+    }
+    env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.last_executed_at, now);
+}
+
+// 测试8: 启用交叉校验后使用主备两个价格源再平衡
+#[tokio::test]
+async fn test_rebalance_positions_with_secondary_oracle() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+
+    let mut cross_checked = guard();
+    cross_checked.max_divergence_bps = 100;
+    env.send_as_admin(update_oracle_guard(&admin, cross_checked))
+        .await
+        .unwrap();
+    for asset_id in 0..2 {
+        let mut params = env.asset_params(asset_id);
+        params.secondary_oracle = env.secondary_oracles[asset_id];
+        env.send_as_admin(update_asset(&admin, asset_id as u8, params))
+            .await
+            .unwrap();
+    }
+    // 0.5% 的偏差在上限之内
+    let secondary = env.secondary_oracles[0];
+    env.set_oracle(&secondary, PRICE * 1005 / 1000, 0, START_TIME);
+
+    let prices = env.price_accounts(true);
+    env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+        .await
+        .unwrap();
+}
+
+// 测试9: 更新策略的分配项和再平衡条件，资金记账保持不变
+#[tokio::test]
+async fn test_update_strategy() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+        .await
+        .unwrap();
+
+    let mut updated = condition();
+    updated.auto_rebalance = false;
+    updated.max_deviation_bps = 250;
+    let allocations = vec![
+        allocation(2, 0, 2500),
+        allocation(3, 0, 2500),
+        allocation(0, 1, 5000),
+    ];
+    env.set_time(START_TIME + 10).await;
+    env.send_as_owner(update_strategy(&owner, 0, allocations, updated, 50))
+        .await
+        .unwrap();
+
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.allocations.len(), 3);
+    assert_eq!(strategy.allocations[2].target_weight_bps, 5000);
+    assert!(!strategy.rebalance_condition.auto_rebalance);
+    assert_eq!(strategy.rebalance_condition.max_deviation_bps, 250);
+    assert_eq!(strategy.max_slippage_bps, 50);
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL * 2);
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.last_activity, START_TIME + 10);
+}
+
+// 测试10: 管理费按时间计提，在下一次存款时由资金库支付给费用金库
+#[tokio::test]
+async fn test_management_fee() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+    let fees = FeeConfig {
+        management_fee_bps: 200,
+        performance_fee_bps: 0,
+    };
+    env.send_as_admin(update_fee_config(&admin, fees, &TREASURY))
+        .await
+        .unwrap();
+
+    env.set_time(START_TIME + SECONDS_PER_YEAR as i64).await;
+    env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+        .await
+        .unwrap();
+
+    // 1 SOL 一年 2%
+    let fee = LAMPORTS_PER_SOL / 50;
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.total_value_lamports, 2 * LAMPORTS_PER_SOL - fee);
+    assert_eq!(
+        strategy.fees_accrued_at,
+        START_TIME + SECONDS_PER_YEAR as i64
+    );
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.total_value_lamports, 2 * LAMPORTS_PER_SOL - fee);
+
+    let treasury = env.raw_account(&TREASURY).await.unwrap();
+    assert_eq!(treasury.lamports, LAMPORTS_PER_SOL + fee);
+    let rent = env.ctx.banks_client.get_rent().await.unwrap();
+    let vault = env.raw_account(&vault_pda(&owner)).await.unwrap();
+    assert_eq!(
+        vault.lamports,
+        rent.minimum_balance(0) + profile.total_value_lamports
+    );
+}
+
+// 测试11: 策略的账面价值不随行情增长，链上不能设置业绩费，只计提管理费
+#[tokio::test]
+async fn test_performance_fee_rejected() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+    let fees = FeeConfig {
+        management_fee_bps: 0,
+        performance_fee_bps: 2000,
+    };
+    assert_error(
+        env.send_as_admin(update_fee_config(&admin, fees, &TREASURY))
+            .await,
+        ErrorCode::InvalidFeeConfig,
+    );
+
+    // 资产价格上涨后再平衡不产生费用
+    let now = START_TIME + INTERVAL as i64;
+    env.set_time(now).await;
+    let oracles = env.oracles;
+    env.set_oracle(&oracles[0], PRICE * 2, PRICE as u64 / 1000, now);
+    env.set_oracle(&oracles[1], PRICE, PRICE as u64 / 1000, now);
+    let prices = env.price_accounts(false);
+    env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL);
+    let treasury = env.raw_account(&TREASURY).await.unwrap();
+    assert_eq!(treasury.lamports, LAMPORTS_PER_SOL);
+}
+
+// 测试12: 存款限制可由管理员更新，资产累计存款按目标权重随存取款增减
+#[tokio::test]
+async fn test_deposit_limits() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+
+    let limits = DepositLimits {
+        min_deposit_lamports: LAMPORTS_PER_SOL / 10,
+        max_strategy_value_lamports: 3 * LAMPORTS_PER_SOL,
+        max_user_value_lamports: 5 * LAMPORTS_PER_SOL,
+    };
+    env.send_as_admin(update_deposit_limits(&admin, limits))
+        .await
+        .unwrap();
+    let config: GlobalConfig = env.account(&config_pda()).await;
+    assert_eq!(
+        config.deposit_limits.min_deposit_lamports,
+        LAMPORTS_PER_SOL / 10
+    );
+    assert_eq!(
+        config.deposit_limits.max_strategy_value_lamports,
+        3 * LAMPORTS_PER_SOL
+    );
+
+    let params = AssetParams {
+        deposit_cap_lamports: 2 * LAMPORTS_PER_SOL,
+        ..env.asset_params(0)
+    };
+    env.send_as_admin(update_asset(&admin, 0, params))
+        .await
+        .unwrap();
+
+    // 恰好达到策略上限，资产0分摊 60%
+    env.send_as_owner(deposit_funds(
+        &owner,
+        0,
+        2 * LAMPORTS_PER_SOL,
+        &allocations(),
+    ))
+    .await
+    .unwrap();
+    let asset: AssetRegistry = env.account(&asset_pda(0)).await;
+    assert_eq!(asset.deposit_cap_lamports, 2 * LAMPORTS_PER_SOL);
+    assert_eq!(asset.deposited_lamports, LAMPORTS_PER_SOL * 18 / 10);
+    let asset: AssetRegistry = env.account(&asset_pda(1)).await;
+    assert_eq!(asset.deposited_lamports, LAMPORTS_PER_SOL * 12 / 10);
+
+    // 提款按目标权重扣回
+    env.send_as_owner(withdraw_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+        .await
+        .unwrap();
+    let asset: AssetRegistry = env.account(&asset_pda(0)).await;
+    assert_eq!(asset.deposited_lamports, LAMPORTS_PER_SOL * 12 / 10);
+    let asset: AssetRegistry = env.account(&asset_pda(1)).await;
+    assert_eq!(asset.deposited_lamports, LAMPORTS_PER_SOL * 8 / 10);
+
+    env.send_as_owner(deposit_funds(
+        &owner,
+        0,
+        LAMPORTS_PER_SOL / 10,
+        &allocations(),
+    ))
+    .await
+    .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL * 21 / 10);
+}
+
+// 测试13: 排队提款锁定到期后按顺序领取，取消的工单退回策略并在领取时跳过
+#[tokio::test]
+async fn test_withdrawal_queue() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+    env.send_as_admin(update_withdrawal_delay(&admin, 3600))
+        .await
+        .unwrap();
+    let config: GlobalConfig = env.account(&config_pda()).await;
+    assert_eq!(config.withdrawal_delay_seconds, 3600);
+
+    for (ticket_id, amount) in [3, 2, 1].into_iter().enumerate() {
+        let amount = LAMPORTS_PER_SOL * amount / 10;
+        env.send_as_owner(request_withdrawal(&owner, 0, ticket_id as u64, amount))
+            .await
+            .unwrap();
+    }
+    let ticket: WithdrawalTicket = env.account(&ticket_pda(&owner, 0, 0)).await;
+    assert_eq!(ticket.owner, owner);
+    assert_eq!(ticket.strategy, strategy_pda(&owner, 0));
+    assert_eq!(ticket.amount_lamports, LAMPORTS_PER_SOL * 3 / 10);
+    assert_eq!(ticket.unlocks_at, START_TIME + 3600);
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL * 4 / 10);
+    assert_eq!(strategy.withdrawal_queue.next_ticket_id, 3);
+    assert_eq!(
+        strategy.withdrawal_queue.locked_lamports,
+        LAMPORTS_PER_SOL * 6 / 10
+    );
+
+    // 取消中间的工单，金额退回策略
+    env.send_as_owner(cancel_withdrawal(&owner, 0, 1))
+        .await
+        .unwrap();
+    assert!(env.raw_account(&ticket_pda(&owner, 0, 1)).await.is_none());
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.total_value_lamports, LAMPORTS_PER_SOL * 6 / 10);
+
+    // 锁定到期后先领取队首，再跳过已取消的工单领取最后一个
+    env.set_time(START_TIME + 3600).await;
+    env.send_as_owner(claim_withdrawal(&owner, 0, 0, &allocations(), &[]))
+        .await
+        .unwrap();
+    env.send_as_owner(claim_withdrawal(&owner, 0, 2, &allocations(), &[1]))
+        .await
+        .unwrap();
+    assert!(env.raw_account(&ticket_pda(&owner, 0, 2)).await.is_none());
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL * 6 / 10);
+    assert_eq!(strategy.withdrawal_queue.head_ticket_id, 3);
+    assert_eq!(strategy.withdrawal_queue.locked_lamports, 0);
+
+    // 领取后资产按目标权重扣回，资金库只剩策略价值
+    let asset: AssetRegistry = env.account(&asset_pda(0)).await;
+    assert_eq!(asset.deposited_lamports, LAMPORTS_PER_SOL * 36 / 100);
+    let rent = env.ctx.banks_client.get_rent().await.unwrap();
+    let vault = env.raw_account(&vault_pda(&owner)).await.unwrap();
+    assert_eq!(
+        vault.lamports,
+        rent.minimum_balance(0) + LAMPORTS_PER_SOL * 6 / 10
+    );
+}
+
+// 测试14: 再平衡之前申请的工单不必等待锁定到期
+#[tokio::test]
+async fn test_withdrawal_unwound_by_rebalance() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+    env.send_as_admin(update_withdrawal_delay(&admin, 3600))
+        .await
+        .unwrap();
+
+    env.send_as_owner(request_withdrawal(&owner, 0, 0, LAMPORTS_PER_SOL / 2))
+        .await
+        .unwrap();
+    let prices = env.price_accounts(false);
+    env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+        .await
+        .unwrap();
+    env.send_as_owner(request_withdrawal(&owner, 0, 1, LAMPORTS_PER_SOL / 4))
+        .await
+        .unwrap();
+
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.withdrawal_queue.unwound_ticket_id, 1);
+    env.send_as_owner(claim_withdrawal(&owner, 0, 0, &allocations(), &[]))
+        .await
+        .unwrap();
+    assert!(env.raw_account(&ticket_pda(&owner, 0, 0)).await.is_none());
+    assert_error(
+        env.send_as_owner(claim_withdrawal(&owner, 0, 1, &allocations(), &[]))
+            .await,
+        ErrorCode::WithdrawalNotReady,
+    );
+}
+
+// 测试15: 代理人在授权范围内执行、再平衡和更新策略，权重变化按授权时的基准累计，撤销后不能再操作
+#[tokio::test]
+async fn test_strategy_delegate() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let bot = env.other.insecure_clone();
+    let expires_at = START_TIME + 2 * INTERVAL as i64;
+
+    env.send_as_owner(add_delegate(
+        &owner,
+        0,
+        &bot.pubkey(),
+        delegate_scope(),
+        expires_at,
+    ))
+    .await
+    .unwrap();
+    let delegate: StrategyDelegate = env.account(&delegate_pda(&owner, 0, &bot.pubkey())).await;
+    assert_eq!(delegate.strategy, strategy_pda(&owner, 0));
+    assert_eq!(delegate.expires_at, expires_at);
+    assert_eq!(delegate.baseline_weights_bps, vec![6000, 4000]);
+
+    env.set_time(START_TIME + 10).await;
+    env.send(&[execute_strategy_as(&bot.pubkey(), &owner, 0)], &[&bot])
+        .await
+        .unwrap();
+    let prices = env.price_accounts(false);
+    let now = START_TIME + INTERVAL as i64 + 10;
+    env.set_time(now).await;
+    for oracle in env.oracles {
+        env.set_oracle(&oracle, PRICE, PRICE as u64 / 1000, now);
+    }
+    env.send(
+        &[rebalance_positions_as(&bot.pubkey(), &owner, 0, &prices)],
+        &[&bot],
+    )
+    .await
+    .unwrap();
+
+    // 代理人不能修改再平衡条件，否则可以随时触发再平衡
+    let shifted = vec![allocation(0, 0, 5000), allocation(1, 1, 5000)];
+    let loosened = RebalanceCondition {
+        time_interval_seconds: 1,
+        ..condition()
+    };
+    let ix = update_strategy_as(&bot.pubkey(), &owner, 0, shifted.clone(), loosened, 50);
+    assert_error(
+        env.send(&[ix], &[&bot]).await,
+        ErrorCode::DelegateBoundsExceeded,
+    );
+
+    let ix = update_strategy_as(&bot.pubkey(), &owner, 0, shifted, condition(), 50);
+    env.send(&[ix], &[&bot]).await.unwrap();
+
+    // 再次调整时按授权时的权重计算变化，不能逐步累积超出上限
+    let drifted = vec![allocation(0, 0, 4000), allocation(1, 1, 6000)];
+    let ix = update_strategy_as(&bot.pubkey(), &owner, 0, drifted, condition(), 50);
+    assert_error(
+        env.send(&[ix], &[&bot]).await,
+        ErrorCode::DelegateBoundsExceeded,
+    );
+
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.last_executed_at, now);
+    assert_eq!(strategy.allocations[0].target_weight_bps, 5000);
+    assert_eq!(strategy.max_slippage_bps, 50);
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL);
+
+    env.send_as_owner(revoke_delegate(&owner, 0, &bot.pubkey()))
+        .await
+        .unwrap();
+    assert!(env
+        .raw_account(&delegate_pda(&owner, 0, &bot.pubkey()))
+        .await
+        .is_none());
+    assert_error(
+        env.send(&[execute_strategy_as(&bot.pubkey(), &owner, 0)], &[&bot])
+            .await,
+        anchor_lang::error::ErrorCode::AccountNotInitialized,
+    );
+}
+
+// 测试16: 白名单地址在延迟后生效，关闭白名单同样需要等待延迟
+#[tokio::test]
+async fn test_withdrawal_allowlist() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let cold = Pubkey::new_unique();
+    let delay = DEFAULT_ALLOWLIST_DELAY_SECONDS as i64;
+    let amount = LAMPORTS_PER_SOL / 10;
+
+    env.send_as_owner(set_withdrawal_allowlist(&owner, true))
+        .await
+        .unwrap();
+    env.send_as_owner(add_withdrawal_destination(&owner, &cold))
+        .await
+        .unwrap();
+    let entry: WithdrawalDestination = env.account(&destination_pda(&owner, &cold)).await;
+    assert_eq!(entry.destination, cold);
+    assert_eq!(entry.activates_at, START_TIME + delay);
+
+    // 所有者本人不受白名单限制
+    env.send_as_owner(withdraw_funds(&owner, 0, amount, &allocations()))
+        .await
+        .unwrap();
+
+    let listed = Some(destination_pda(&owner, &cold));
+    env.set_time(START_TIME + delay).await;
+    env.send_as_owner(withdraw_funds_to(
+        &owner,
+        0,
+        amount,
+        &allocations(),
+        Payout::to(&cold, listed),
+    ))
+    .await
+    .unwrap();
+    assert_eq!(env.raw_account(&cold).await.unwrap().lamports, amount);
+
+    // 关闭在延迟之后生效，期间未登记的地址仍被拒绝
+    let other = Pubkey::new_unique();
+    env.send_as_owner(set_withdrawal_allowlist(&owner, false))
+        .await
+        .unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.allowlist_disable_at, START_TIME + 2 * delay);
+    assert_error(
+        env.send_as_owner(withdraw_funds_to(
+            &owner,
+            0,
+            amount,
+            &allocations(),
+            Payout::to(&other, None),
+        ))
+        .await,
+        ErrorCode::WithdrawalDestinationNotAllowed,
+    );
+    env.set_time(START_TIME + 2 * delay).await;
+    env.send_as_owner(withdraw_funds_to(
+        &owner,
+        0,
+        amount,
+        &allocations(),
+        Payout::to(&other, None),
+    ))
+    .await
+    .unwrap();
+
+    env.send_as_owner(remove_withdrawal_destination(&owner, &cold))
+        .await
+        .unwrap();
+    assert!(env
+        .raw_account(&destination_pda(&owner, &cold))
+        .await
+        .is_none());
+}
+
+// 测试17: 周期提款限额必须指定监护人，在窗口内累计，监护人联署不受限制，放宽限额需要监护人签名
+#[tokio::test]
+async fn test_spending_limit() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let signer = env.owner.insecure_clone();
+    let owner = signer.pubkey();
+    let guardian = env.other.insecure_clone();
+    let limit = LAMPORTS_PER_SOL / 10;
+    let window = 3600;
+
+    // 没有监护人时所有者可以随时放宽，启用限额必须指定监护人
+    assert_error(
+        env.send_as_owner(set_spending_limit(
+            &owner,
+            limit,
+            window,
+            &Pubkey::default(),
+            None,
+        ))
+        .await,
+        ErrorCode::InvalidSpendingLimit,
+    );
+    env.send_as_owner(set_spending_limit(
+        &owner,
+        limit,
+        window,
+        &guardian.pubkey(),
+        None,
+    ))
+    .await
+    .unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.spending_limit.max_lamports, limit);
+    assert_eq!(profile.spending_limit.guardian, guardian.pubkey());
+
+    env.send_as_owner(withdraw_funds(&owner, 0, limit / 2, &allocations()))
+        .await
+        .unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.spending_limit.window_start, START_TIME);
+    assert_eq!(profile.spending_limit.spent_lamports, limit / 2);
+    assert_error(
+        env.send_as_owner(withdraw_funds(&owner, 0, limit, &allocations()))
+            .await,
+        ErrorCode::SpendingLimitExceeded,
+    );
+
+    // 监护人联署的提款不计入限额
+    let ix = withdraw_funds_to(
+        &owner,
+        0,
+        limit,
+        &allocations(),
+        Payout::to(&owner, None).with_guardian(&guardian.pubkey()),
+    );
+    env.send(&[ix], &[&signer, &guardian]).await.unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.spending_limit.spent_lamports, limit / 2);
+
+    // 窗口到期后重新计数
+    env.set_time(START_TIME + i64::from(window)).await;
+    env.send_as_owner(withdraw_funds(&owner, 0, limit, &allocations()))
+        .await
+        .unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(
+        profile.spending_limit.window_start,
+        START_TIME + i64::from(window)
+    );
+    assert_eq!(profile.spending_limit.spent_lamports, limit);
+
+    // 收紧限额无需监护人，放宽则需要联署，已用额度保留
+    env.send_as_owner(set_spending_limit(
+        &owner,
+        limit / 2,
+        window,
+        &guardian.pubkey(),
+        None,
+    ))
+    .await
+    .unwrap();
+    assert_error(
+        env.send_as_owner(set_spending_limit(
+            &owner,
+            2 * limit,
+            window,
+            &guardian.pubkey(),
+            None,
+        ))
+        .await,
+        ErrorCode::GuardianApprovalRequired,
+    );
+    let ix = set_spending_limit(
+        &owner,
+        2 * limit,
+        window,
+        &guardian.pubkey(),
+        Some(guardian.pubkey()),
+    );
+    env.send(&[ix], &[&signer, &guardian]).await.unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.spending_limit.max_lamports, 2 * limit);
+    assert_eq!(profile.spending_limit.spent_lamports, limit);
+}
+
+// 测试18: 提名并接受新所有者后迁移策略和资金，策略ID和提款工单计数保持不变
+#[tokio::test]
+async fn test_owner_transfer() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let next = env.other.insecure_clone();
+    let new_owner = next.pubkey();
+
+    // 取消的工单不影响迁移，工单ID在新策略中继续递增
+    env.send_as_owner(request_withdrawal(&owner, 0, 0, 1000))
+        .await
+        .unwrap();
+    env.send_as_owner(cancel_withdrawal(&owner, 0, 0))
+        .await
+        .unwrap();
+    let previous: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+
+    env.send_as_owner(propose_owner(&owner, &new_owner, None))
+        .await
+        .unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.pending_owner, new_owner);
+    assert_eq!(profile.pending_owner_at, START_TIME);
+
+    env.send(&[accept_owner(&new_owner, &owner)], &[&next])
+        .await
+        .unwrap();
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.migrated_to, new_owner);
+    assert_eq!(profile.pending_owner, Pubkey::default());
+    let accepted: UserProfile = env.account(&user_pda(&new_owner)).await;
+    assert_eq!(accepted.owner, new_owner);
+    assert_eq!(accepted.migrated_from, owner);
+    assert_eq!(accepted.risk_level, 3);
+    assert_eq!(accepted.strategy_counter, 1);
+    assert_eq!(accepted.total_value_lamports, 0);
+
+    let vault_before = env.raw_account(&vault_pda(&new_owner)).await.unwrap();
+    env.send(&[migrate_strategy(&new_owner, &owner, 0)], &[&next])
+        .await
+        .unwrap();
+    assert!(env.raw_account(&strategy_pda(&owner, 0)).await.is_none());
+    let migrated: StrategyConfig = env.account(&strategy_pda(&new_owner, 0)).await;
+    assert_eq!(migrated.owner, new_owner);
+    assert_eq!(migrated.strategy_id, 0);
+    assert_eq!(migrated.created_at, previous.created_at);
+    assert_eq!(migrated.total_value_lamports, previous.total_value_lamports);
+    assert_eq!(migrated.withdrawal_queue.next_ticket_id, 1);
+    let vault = env.raw_account(&vault_pda(&new_owner)).await.unwrap();
+    assert_eq!(
+        vault.lamports,
+        vault_before.lamports + previous.total_value_lamports
+    );
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.total_value_lamports, 0);
+    let accepted: UserProfile = env.account(&user_pda(&new_owner)).await;
+    assert_eq!(accepted.total_value_lamports, previous.total_value_lamports);
+
+    // 新所有者可以直接使用迁移后的策略
+    let amount = LAMPORTS_PER_SOL / 10;
+    env.send(
+        &[withdraw_funds(&new_owner, 0, amount, &allocations())],
+        &[&next],
+    )
+    .await
+    .unwrap();
+    env.send(
+        &[create_strategy(
+            &new_owner,
+            1,
+            allocations(),
+            condition(),
+            100,
+        )],
+        &[&next],
+    )
+    .await
+    .unwrap();
+}
+
+// 测试19: 发布模板，风险等级按分配项计算，其他用户按模板创建策略，停用模板后已创建的策略不受影响
+#[tokio::test]
+async fn test_strategy_templates() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let publisher = env.owner.pubkey();
+    let user = env.other.insecure_clone();
+    let owner = user.pubkey();
+    let template_allocations = vec![allocation(0, 0, 6000), allocation(1, 1, 4000)];
+
+    // 声明的等级2低于按分配项计算的等级3(60%的资产0)，按计算结果保存
+    env.send_as_owner(publish_template(
+        &publisher,
+        5,
+        template_allocations.clone(),
+        2,
+    ))
+    .await
+    .unwrap();
+    let template: StrategyTemplate = env.account(&template_pda(&publisher, 5)).await;
+    assert_eq!(template.publisher, publisher);
+    assert_eq!(template.template_id, 5);
+    assert_eq!(template.risk_level, 3);
+    assert_eq!(template.description_hash, [7; 32]);
+    assert_eq!(template.created_at, START_TIME);
+
+    env.send(&[initialize_user(&owner, 3)], &[&user])
+        .await
+        .unwrap();
+    // 发布后调低资产0的权重上限，创建时重新计算的等级为5
+    let admin = env.admin.pubkey();
+    let mut params = env.asset_params(0);
+    params.max_allocation_bps = 6000;
+    env.send_as_admin(update_asset(&admin, 0, params))
+        .await
+        .unwrap();
+    let result = env
+        .send(
+            &[create_strategy_from_template(
+                &owner,
+                0,
+                &publisher,
+                5,
+                &template_allocations,
+            )],
+            &[&user],
+        )
+        .await;
+    assert_error(result, ErrorCode::TemplateRiskTooHigh);
+    params.max_allocation_bps = 10000;
+    env.send_as_admin(update_asset(&admin, 0, params))
+        .await
+        .unwrap();
+
+    env.send(
+        &[create_strategy_from_template(
+            &owner,
+            0,
+            &publisher,
+            5,
+            &template_allocations,
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.owner, owner);
+    assert_eq!(strategy.strategy_id, 0);
+    assert_eq!(strategy.allocations.len(), 2);
+    assert_eq!(strategy.allocations[0].target_weight_bps, 6000);
+    assert_eq!(strategy.rebalance_condition.time_interval_seconds, INTERVAL);
+    assert_eq!(strategy.max_slippage_bps, 100);
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.strategy_counter, 1);
+    let template: StrategyTemplate = env.account(&template_pda(&publisher, 5)).await;
+    assert_eq!(template.strategies_created, 1);
+
+    // 发布者也可以使用自己的模板
+    env.send_as_owner(create_strategy_from_template(
+        &publisher,
+        1,
+        &publisher,
+        5,
+        &template_allocations,
+    ))
+    .await
+    .unwrap();
+
+    let before = env.raw_account(&publisher).await.unwrap().lamports;
+    env.send_as_owner(retire_template(&publisher, 5))
+        .await
+        .unwrap();
+    assert!(env
+        .raw_account(&template_pda(&publisher, 5))
+        .await
+        .is_none());
+    assert!(env.raw_account(&publisher).await.unwrap().lamports > before);
+    // 停用不影响已创建的策略
+    env.send(
+        &[deposit_funds(
+            &owner,
+            0,
+            LAMPORTS_PER_SOL,
+            &template_allocations,
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+}
+
+// 测试20: 跟随策略在延迟后同步领投策略的分配项并支付按时间计提的领投费，未调整分配项时不能重复同步
+#[tokio::test]
+async fn test_follow_strategy() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let leader = env.owner.pubkey();
+    let keeper = env.admin.insecure_clone();
+    let user = env.other.insecure_clone();
+    let owner = user.pubkey();
+
+    env.send(&[initialize_user(&owner, 3)], &[&user])
+        .await
+        .unwrap();
+    let own_allocations = vec![allocation(0, 0, 10000)];
+    env.send(
+        &[
+            create_strategy(&owner, 0, own_allocations, condition(), 50),
+            deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &[allocation(0, 0, 10000)]),
+            follow_strategy(&owner, 0, &leader, 0, 8000, 60, 100),
+        ],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    let follow: StrategyFollow = env.account(&follow_pda(&owner, 0)).await;
+    assert_eq!(follow.leader, strategy_pda(&leader, 0));
+    assert_eq!(follow.followed_at, START_TIME);
+
+    // 领投策略创建时的分配项也需要等待同步延迟
+    let sync = sync_follower(&keeper.pubkey(), &owner, 0, &leader, 0, &allocations());
+    assert_error(
+        env.send(std::slice::from_ref(&sync), &[&keeper]).await,
+        ErrorCode::FollowDelayActive,
+    );
+    env.set_time(START_TIME + 60).await;
+    let before = env.raw_account(&leader).await.unwrap().lamports;
+    env.send(std::slice::from_ref(&sync), &[&keeper])
+        .await
+        .unwrap();
+
+    // 年费率1%，从开始跟随起计提60秒
+    let fee = LAMPORTS_PER_SOL * 100 * 60 / (10_000 * SECONDS_PER_YEAR);
+    assert!(fee > 0);
+    assert_eq!(
+        env.raw_account(&leader).await.unwrap().lamports,
+        before + fee
+    );
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == allocations());
+    assert_eq!(strategy.allocations_updated_at, START_TIME + 60);
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL - fee);
+    // 再平衡条件和滑点保持跟随者自己的设置
+    assert_eq!(strategy.max_slippage_bps, 50);
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.total_value_lamports, LAMPORTS_PER_SOL - fee);
+    let follow: StrategyFollow = env.account(&follow_pda(&owner, 0)).await;
+    assert_eq!(follow.syncs, 1);
+    assert_eq!(follow.leader_synced_at, START_TIME);
+
+    // 只修改滑点不算调整分配项
+    assert_error(
+        env.send(std::slice::from_ref(&sync), &[&keeper]).await,
+        ErrorCode::FollowerInSync,
+    );
+    env.send_as_owner(update_strategy(&leader, 0, allocations(), condition(), 80))
+        .await
+        .unwrap();
+    assert_error(
+        env.send(&[sync], &[&keeper]).await,
+        ErrorCode::FollowerInSync,
+    );
+
+    let adjusted = vec![allocation(0, 0, 7000), allocation(1, 1, 3000)];
+    env.send_as_owner(update_strategy(
+        &leader,
+        0,
+        adjusted.clone(),
+        condition(),
+        80,
+    ))
+    .await
+    .unwrap();
+    env.set_time(START_TIME + 120).await;
+    env.send(
+        &[sync_follower(
+            &keeper.pubkey(),
+            &owner,
+            0,
+            &leader,
+            0,
+            &adjusted,
+        )],
+        &[&keeper],
+    )
+    .await
+    .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == adjusted);
+    let follow: StrategyFollow = env.account(&follow_pda(&owner, 0)).await;
+    assert_eq!(follow.syncs, 2);
+    assert_eq!(follow.leader_synced_at, START_TIME + 60);
+
+    // 取消跟随后保留已同步的分配项
+    env.send(&[unfollow_strategy(&owner, 0)], &[&user])
+        .await
+        .unwrap();
+    assert!(env.raw_account(&follow_pda(&owner, 0)).await.is_none());
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == adjusted);
+}
+
+// 测试21: 定投每个间隔把固定价值从报价分配项转移到目标分配项，报价分配项用完后结束
+#[tokio::test]
+async fn test_dca() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let keeper = env.other.insecure_clone();
+    let prices = env.price_accounts(false);
+
+    env.send_as_owner(create_dca(
+        &owner,
+        0,
+        dca_params(LAMPORTS_PER_SOL / 5, 2_000_000),
+    ))
+    .await
+    .unwrap();
+    let schedule: DcaSchedule = env.account(&dca_pda(&owner, 0)).await;
+    assert_eq!(schedule.owner, owner);
+    assert_eq!(schedule.strategy, strategy_pda(&owner, 0));
+    assert_eq!(schedule.created_at, START_TIME);
+
+    let execute = execute_dca(&keeper.pubkey(), &owner, 0, &prices);
+    env.send(std::slice::from_ref(&execute), &[&keeper])
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == vec![allocation(0, 0, 4000), allocation(1, 1, 6000)]);
+    assert_eq!(strategy.last_executed_at, START_TIME);
+    assert_eq!(strategy.allocations_updated_at, START_TIME);
+
+    // 与执行和再平衡共用时间间隔
+    assert_error(
+        env.send(std::slice::from_ref(&execute), &[&keeper]).await,
+        ErrorCode::RebalanceConditionNotMet,
+    );
+
+    for round in 1..=2 {
+        let now = START_TIME + round * INTERVAL as i64;
+        env.set_time(now).await;
+        for oracle in env.oracles {
+            env.set_oracle(&oracle, PRICE, PRICE as u64 / 1000, now);
+        }
+        env.send(std::slice::from_ref(&execute), &[&keeper])
+            .await
+            .unwrap();
+    }
+    // 报价分配项用完后移除
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == vec![allocation(1, 1, 10000)]);
+    let schedule: DcaSchedule = env.account(&dca_pda(&owner, 0)).await;
+    assert_eq!(schedule.purchases, 3);
+    assert_eq!(schedule.purchased_lamports, LAMPORTS_PER_SOL * 3 / 5);
+
+    env.send_as_owner(cancel_dca(&owner, 0)).await.unwrap();
+    assert!(env.raw_account(&dca_pda(&owner, 0)).await.is_none());
+}
+
+// 测试22: 保护单按预言机价格重新估值并跟踪峰值，回撤超过上限时转入安全头寸并暂停策略，所有者确认后恢复
+#[tokio::test]
+async fn test_protection() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let keeper = env.other.insecure_clone();
+    let prices = env.price_accounts(false);
+    let oracles = env.oracles;
+
+    env.send_as_owner(set_protection(
+        &owner,
+        0,
+        protection_params(0, 2000, 2 * LAMPORTS_PER_SOL),
+        &prices,
+    ))
+    .await
+    .unwrap();
+    let order: ProtectiveOrder = env.account(&protect_pda(&owner, 0)).await;
+    assert_eq!(order.owner, owner);
+    assert_eq!(order.marked_value_lamports, LAMPORTS_PER_SOL);
+    assert_eq!(order.marks.len(), 2);
+    assert_eq!(order.marks[0].price_quote, 1_000_000);
+
+    // 资产0涨到1.5，估值 0.6 × 1.5 + 0.4 = 1.3 SOL
+    env.set_oracle(&oracles[0], PRICE * 3 / 2, PRICE as u64 / 1000, START_TIME);
+    let check = check_protection(&keeper.pubkey(), &owner, 0, &prices);
+    env.send(std::slice::from_ref(&check), &[&keeper])
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.peak_value_lamports, LAMPORTS_PER_SOL * 13 / 10);
+    let order: ProtectiveOrder = env.account(&protect_pda(&owner, 0)).await;
+    assert_eq!(order.marked_value_lamports, LAMPORTS_PER_SOL * 13 / 10);
+
+    // 资产0跌回0.75，估值 1.3 × (0.6 × 0.5 + 0.4) = 0.91 SOL，低于峰值的80%
+    let now = START_TIME + 60;
+    env.set_time(now).await;
+    env.set_oracle(&oracles[0], PRICE * 3 / 4, PRICE as u64 / 1000, now);
+    env.set_oracle(&oracles[1], PRICE, PRICE as u64 / 1000, now);
+    env.send(std::slice::from_ref(&check), &[&keeper])
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == vec![allocation(1, 1, 10000)]);
+    assert_eq!(strategy.allocations_updated_at, now);
+    assert_eq!(strategy.paused_at, now);
+    assert!(env.raw_account(&protect_pda(&owner, 0)).await.is_none());
+
+    assert_error(
+        env.send_as_owner(execute_strategy(&owner, 0)).await,
+        ErrorCode::StrategyPaused,
+    );
+
+    env.send_as_owner(resume_strategy(&owner, 0)).await.unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.paused_at, 0);
+    assert_eq!(strategy.peak_value_lamports, 0);
+    env.send_as_owner(execute_strategy(&owner, 0))
+        .await
+        .unwrap();
+
+    // 取消保护单
+    env.send_as_owner(set_protection(
+        &owner,
+        0,
+        protection_params(LAMPORTS_PER_SOL / 2, 0, 0),
+        &[asset_pda(1), oracles[1]],
+    ))
+    .await
+    .unwrap();
+    env.send_as_owner(cancel_protection(&owner, 0))
+        .await
+        .unwrap();
+    assert!(env.raw_account(&protect_pda(&owner, 0)).await.is_none());
+}
+
+// 测试23: 利率追踪由管理员报告，轮动头寸在满足最短持有时间和最小APY优势后移动到APY最高的借贷协议
+#[tokio::test]
+async fn test_yield_rotation() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+    let keeper = env.other.insecure_clone();
+    let trackers = [apy_pda(1, 1), apy_pda(3, 1)];
+
+    env.send_as_admin(register_apy_tracker(&admin, 1, 1, 500))
+        .await
+        .unwrap();
+    env.send_as_admin(register_apy_tracker(&admin, 3, 1, 800))
+        .await
+        .unwrap();
+    let tracker: PositionTracker = env.account(&trackers[1]).await;
+    assert_eq!(&tracker.protocol[..5], b"mango");
+    assert_eq!(&tracker.asset[..2], b"1\0");
+    assert_eq!(tracker.initial_apy_bps, 800);
+    assert_eq!(tracker.current_apy_bps, 800);
+    assert_eq!(tracker.last_updated_at, START_TIME);
+
+    env.send_as_owner(enable_yield_rotation(&owner, 0, yield_params(200, 3600)))
+        .await
+        .unwrap();
+    let rotation: YieldRotation = env.account(&yield_pda(&owner, 0)).await;
+    assert_eq!(rotation.owner, owner);
+    assert_eq!(rotation.held_since, START_TIME);
+
+    // 未满最短持有时间
+    let rotate = rotate_yield(&keeper.pubkey(), &owner, 0, &trackers);
+    assert_error(
+        env.send(std::slice::from_ref(&rotate), &[&keeper]).await,
+        ErrorCode::YieldHoldActive,
+    );
+
+    // Mango 高出 300 bps，轮动头寸从 Solend 移到 Mango，权重不变
+    let now = START_TIME + 3600;
+    env.set_time(now).await;
+    env.send(std::slice::from_ref(&rotate), &[&keeper])
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == vec![allocation(0, 0, 6000), allocation(3, 1, 4000)]);
+    assert_eq!(strategy.allocations_updated_at, now);
+    let rotation: YieldRotation = env.account(&yield_pda(&owner, 0)).await;
+    assert_eq!(rotation.held_since, now);
+    assert_eq!(rotation.rotations, 1);
+
+    // Solend 回升到 900，只高出 100 bps，不足最小优势
+    let now = now + 3600;
+    env.set_time(now).await;
+    env.send_as_admin(report_apy(&admin, 1, 1, 900))
+        .await
+        .unwrap();
+    let tracker: PositionTracker = env.account(&trackers[0]).await;
+    assert_eq!(tracker.initial_apy_bps, 500);
+    assert_eq!(tracker.current_apy_bps, 900);
+    assert_error(
+        env.send(std::slice::from_ref(&rotate), &[&keeper]).await,
+        ErrorCode::YieldAdvantageTooSmall,
+    );
+
+    // Solend 升到 1000 后移回
+    env.send_as_admin(report_apy(&admin, 1, 1, 1000))
+        .await
+        .unwrap();
+    env.send(std::slice::from_ref(&rotate), &[&keeper])
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == vec![allocation(0, 0, 6000), allocation(1, 1, 4000)]);
+
+    // 停用后轮动头寸保留在最后的协议
+    env.send_as_owner(disable_yield_rotation(&owner, 0))
+        .await
+        .unwrap();
+    assert!(env.raw_account(&yield_pda(&owner, 0)).await.is_none());
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == vec![allocation(0, 0, 6000), allocation(1, 1, 4000)]);
+}
+
+// 测试24: 领投者反复调整分配项触发同步，领投费只按经过的时间计提；
+// 跟随者启用白名单后，领投者必须是已生效的白名单地址才能收取领投费
+#[tokio::test]
+async fn test_leader_fee_accrual() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let leader = env.owner.pubkey();
+    let keeper = env.admin.insecure_clone();
+    let user = env.other.insecure_clone();
+    let owner = user.pubkey();
+
+    env.send(&[initialize_user(&owner, 3)], &[&user])
+        .await
+        .unwrap();
+    env.send(
+        &[
+            create_strategy(&owner, 0, vec![allocation(0, 0, 10000)], condition(), 50),
+            deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &[allocation(0, 0, 10000)]),
+            follow_strategy(&owner, 0, &leader, 0, 10000, 0, 100),
+        ],
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    let adjusted = vec![allocation(0, 0, 7000), allocation(1, 1, 3000)];
+    let rounds = 6;
+    for round in 1..=rounds {
+        let target = if round % 2 == 0 {
+            allocations()
+        } else {
+            adjusted.clone()
+        };
+        env.set_time(START_TIME + round * 60).await;
+        env.send_as_owner(update_strategy(&leader, 0, target.clone(), condition(), 50))
+            .await
+            .unwrap();
+        env.send(
+            &[sync_follower(
+                &keeper.pubkey(),
+                &owner,
+                0,
+                &leader,
+                0,
+                &target,
+            )],
+            &[&keeper],
+        )
+        .await
+        .unwrap();
+    }
+    let follow: StrategyFollow = env.account(&follow_pda(&owner, 0)).await;
+    assert_eq!(follow.syncs, rounds as u64);
+    // 总费用不超过整段时间按年费率计提的费用
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    let paid = LAMPORTS_PER_SOL - strategy.total_value_lamports;
+    let elapsed = rounds as u64 * 60;
+    assert!(paid <= LAMPORTS_PER_SOL * 100 * elapsed / (10_000 * SECONDS_PER_YEAR));
+
+    // 启用白名单后未登记或未生效的领投者不能收取领投费
+    env.send(&[set_withdrawal_allowlist(&owner, true)], &[&user])
+        .await
+        .unwrap();
+    env.set_time(START_TIME + 3600).await;
+    env.send_as_owner(update_strategy(
+        &leader,
+        0,
+        adjusted.clone(),
+        condition(),
+        50,
+    ))
+    .await
+    .unwrap();
+    let sync = sync_follower(&keeper.pubkey(), &owner, 0, &leader, 0, &adjusted);
+    assert_error(
+        env.send(&[sync], &[&keeper]).await,
+        ErrorCode::WithdrawalDestinationNotAllowed,
+    );
+    env.send(&[add_withdrawal_destination(&owner, &leader)], &[&user])
+        .await
+        .unwrap();
+    let listed = Some(destination_pda(&owner, &leader));
+    let sync = sync_follower_with(&keeper.pubkey(), &owner, 0, &leader, 0, &adjusted, listed);
+    assert_error(
+        env.send(std::slice::from_ref(&sync), &[&keeper]).await,
+        ErrorCode::WithdrawalDestinationPending,
+    );
+    env.set_time(START_TIME + 3600 + DEFAULT_ALLOWLIST_DELAY_SECONDS as i64)
+        .await;
+    env.send(&[sync], &[&keeper]).await.unwrap();
+    let follow: StrategyFollow = env.account(&follow_pda(&owner, 0)).await;
+    assert_eq!(follow.syncs, rounds as u64 + 1);
+}
+
+// 测试25: 存款后调整目标权重，提款仍按策略计入各资产的金额扣回，全部取回后资产计数归零
+#[tokio::test]
+async fn test_asset_deposits_follow_strategy_records() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+
+    // 资产0计入 0.6 SOL，资产1计入 0.4 SOL
+    let shifted = vec![allocation(0, 0, 1000), allocation(1, 1, 9000)];
+    env.send_as_owner(update_strategy(&owner, 0, shifted, condition(), 100))
+        .await
+        .unwrap();
+
+    env.send_as_owner(withdraw_funds(
+        &owner,
+        0,
+        LAMPORTS_PER_SOL / 2,
+        &allocations(),
+    ))
+    .await
+    .unwrap();
+    let asset: AssetRegistry = env.account(&asset_pda(0)).await;
+    assert_eq!(asset.deposited_lamports, LAMPORTS_PER_SOL * 3 / 10);
+    let asset: AssetRegistry = env.account(&asset_pda(1)).await;
+    assert_eq!(asset.deposited_lamports, LAMPORTS_PER_SOL * 2 / 10);
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.asset_deposits.len(), 2);
+    assert_eq!(
+        strategy.asset_deposits[0].lamports,
+        LAMPORTS_PER_SOL * 3 / 10
+    );
+
+    env.send_as_owner(withdraw_funds(
+        &owner,
+        0,
+        LAMPORTS_PER_SOL / 2,
+        &allocations(),
+    ))
+    .await
+    .unwrap();
+    for asset_id in 0..2 {
+        let asset: AssetRegistry = env.account(&asset_pda(asset_id)).await;
+        assert_eq!(asset.deposited_lamports, 0);
+    }
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.asset_deposits.is_empty());
+}
+
+// 测试26: 定投增量不足1基点时不转移权重，累计到满1基点后再转移，余数留到下次
+#[tokio::test]
+async fn test_dca_carries_small_increment() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let keeper = env.other.insecure_clone();
+    let prices = env.price_accounts(false);
+
+    // 策略价值1 SOL，1基点为 100_000 lamports
+    env.send_as_owner(create_dca(&owner, 0, dca_params(40_000, 0)))
+        .await
+        .unwrap();
+    let execute = execute_dca(&keeper.pubkey(), &owner, 0, &prices);
+    for (round, carried) in [(0, 40_000), (1, 80_000), (2, 20_000)] {
+        let now = START_TIME + round * INTERVAL as i64;
+        env.set_time(now).await;
+        for oracle in env.oracles {
+            env.set_oracle(&oracle, PRICE, PRICE as u64 / 1000, now);
+        }
+        env.send(std::slice::from_ref(&execute), &[&keeper])
+            .await
+            .unwrap();
+        let schedule: DcaSchedule = env.account(&dca_pda(&owner, 0)).await;
+        assert_eq!(schedule.carried_lamports, carried);
+        let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+        assert_eq!(strategy.last_executed_at, now);
+    }
+
+    // 前两次只消耗间隔，第三次转移1基点
+    let schedule: DcaSchedule = env.account(&dca_pda(&owner, 0)).await;
+    assert_eq!(schedule.purchases, 1);
+    assert_eq!(schedule.purchased_lamports, 100_000);
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert!(strategy.allocations == vec![allocation(0, 0, 5999), allocation(1, 1, 4001)]);
+}
+
+// 测试27: 取消工单退回的金额同样受策略上限约束，申请提款后资产存款仍计入资产上限
+#[tokio::test]
+async fn test_cancel_withdrawal_respects_caps() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let admin = env.admin.pubkey();
+    let owner = env.owner.pubkey();
+
+    let limits = DepositLimits {
+        min_deposit_lamports: 0,
+        max_strategy_value_lamports: 2 * LAMPORTS_PER_SOL,
+        max_user_value_lamports: 0,
+    };
+    env.send_as_admin(update_deposit_limits(&admin, limits))
+        .await
+        .unwrap();
+
+    // 存满上限后申请提款，再次存满
+    env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+        .await
+        .unwrap();
+    env.send_as_owner(request_withdrawal(&owner, 0, 0, LAMPORTS_PER_SOL))
+        .await
+        .unwrap();
+    env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+        .await
+        .unwrap();
+    assert_error(
+        env.send_as_owner(cancel_withdrawal(&owner, 0, 0)).await,
+        ErrorCode::StrategyDepositCapExceeded,
+    );
+
+    // 提出一部分后可以取消
+    env.send_as_owner(withdraw_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+        .await
+        .unwrap();
+    env.send_as_owner(cancel_withdrawal(&owner, 0, 0))
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.total_value_lamports, 2 * LAMPORTS_PER_SOL);
+    assert_eq!(strategy.withdrawal_queue.locked_lamports, 0);
+
+    // 资产上限: 锁定的金额在领取前仍占用资产额度
+    let params = AssetParams {
+        deposit_cap_lamports: 12 * LAMPORTS_PER_SOL / 10,
+        ..env.asset_params(0)
+    };
+    env.send_as_admin(update_asset(&admin, 0, params))
+        .await
+        .unwrap();
+    env.send_as_admin(update_deposit_limits(&admin, DepositLimits::default()))
+        .await
+        .unwrap();
+    let asset: AssetRegistry = env.account(&asset_pda(0)).await;
+    assert_eq!(asset.deposited_lamports, 12 * LAMPORTS_PER_SOL / 10);
+    env.send_as_owner(request_withdrawal(&owner, 0, 1, LAMPORTS_PER_SOL))
+        .await
+        .unwrap();
+    assert_error(
+        env.send_as_owner(deposit_funds(&owner, 0, LAMPORTS_PER_SOL, &allocations()))
+            .await,
+        ErrorCode::AssetDepositCapExceeded,
+    );
+}
+
+// 测试28: 跟随、定投和收益率轮动互斥，取消当前模式后才能启用另一种
+#[tokio::test]
+async fn test_strategy_modes_are_exclusive() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let user = env.other.insecure_clone();
+    let leader = user.pubkey();
+    env.send(
+        &[
+            initialize_user(&leader, 3),
+            create_strategy(&leader, 0, allocations(), condition(), 50),
+        ],
+        &[&user],
+    )
+    .await
+    .unwrap();
+
+    let follow = follow_strategy(&owner, 0, &leader, 0, 10000, 0, 0);
+    let dca = create_dca(&owner, 0, dca_params(LAMPORTS_PER_SOL, 0));
+    let rotation = enable_yield_rotation(&owner, 0, yield_params(100, 0));
+    let mode = |strategy: StrategyConfig| strategy.mode;
+
+    env.send_as_owner(dca.clone()).await.unwrap();
+    assert_eq!(
+        mode(env.account(&strategy_pda(&owner, 0)).await),
+        StrategyMode::Dca
+    );
+    for ix in [&follow, &rotation] {
+        assert_error(
+            env.send_as_owner(ix.clone()).await,
+            ErrorCode::StrategyModeConflict,
+        );
+    }
+
+    env.send_as_owner(cancel_dca(&owner, 0)).await.unwrap();
+    env.send_as_owner(follow.clone()).await.unwrap();
+    assert_eq!(
+        mode(env.account(&strategy_pda(&owner, 0)).await),
+        StrategyMode::Following
+    );
+    for ix in [&dca, &rotation] {
+        assert_error(
+            env.send_as_owner(ix.clone()).await,
+            ErrorCode::StrategyModeConflict,
+        );
+    }
+
+    env.send_as_owner(unfollow_strategy(&owner, 0))
+        .await
+        .unwrap();
+    env.send_as_owner(rotation).await.unwrap();
+    assert_error(
+        env.send_as_owner(dca).await,
+        ErrorCode::StrategyModeConflict,
+    );
+    env.send_as_owner(disable_yield_rotation(&owner, 0))
+        .await
+        .unwrap();
+    assert_eq!(
+        mode(env.account(&strategy_pda(&owner, 0)).await),
+        StrategyMode::Manual
+    );
+}
+
+// 测试29: 定投要求再平衡时间间隔大于0，启用期间不能把间隔改为0，取消后可以
+#[tokio::test]
+async fn test_dca_requires_interval() {
+    let mut env = start().await;
+    env.init_registry().await;
+    let owner = env.owner.pubkey();
+    let no_interval = RebalanceCondition {
+        time_interval_seconds: 0,
+        ..condition()
+    };
+    env.send_as_owner(initialize_user(&owner, 3)).await.unwrap();
+    env.send_as_owner(create_strategy(
+        &owner,
+        0,
+        allocations(),
+        no_interval.clone(),
+        100,
+    ))
+    .await
+    .unwrap();
+    let dca = create_dca(&owner, 0, dca_params(LAMPORTS_PER_SOL, 0));
+    assert_error(
+        env.send_as_owner(dca.clone()).await,
+        ErrorCode::DcaIntervalRequired,
+    );
+
+    let update =
+        |condition: RebalanceCondition| update_strategy(&owner, 0, allocations(), condition, 100);
+    env.send_as_owner(update(condition())).await.unwrap();
+    env.send_as_owner(dca).await.unwrap();
+    assert_error(
+        env.send_as_owner(update(no_interval.clone())).await,
+        ErrorCode::DcaIntervalRequired,
+    );
+
+    env.send_as_owner(cancel_dca(&owner, 0)).await.unwrap();
+    env.send_as_owner(update(no_interval)).await.unwrap();
+}
+
+// 测试30: 策略仍有代理人授权或保护单时不能迁移，原所有者取消后可以迁移；
+// 跟随者以原始地址继续同步迁移后的领投策略
+#[tokio::test]
+async fn test_migration_links() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let next = env.admin.insecure_clone();
+    let new_owner = next.pubkey();
+    let user = env.other.insecure_clone();
+    let follower = user.pubkey();
+
+    env.send(
+        &[
+            initialize_user(&follower, 3),
+            create_strategy(&follower, 0, allocations(), condition(), 50),
+            follow_strategy(&follower, 0, &owner, 0, 10000, 0, 0),
+        ],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    let delegate = Pubkey::new_unique();
+    env.send_as_owner(add_delegate(
+        &owner,
+        0,
+        &delegate,
+        delegate_scope(),
+        START_TIME + 3600,
+    ))
+    .await
+    .unwrap();
+    let prices = env.price_accounts(false);
+    env.send_as_owner(set_protection(
+        &owner,
+        0,
+        protection_params(1, 0, 0),
+        &prices,
+    ))
+    .await
+    .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.delegates, 1);
+
+    env.send_as_owner(propose_owner(&owner, &new_owner, None))
+        .await
+        .unwrap();
+    env.send(&[accept_owner(&new_owner, &owner)], &[&next])
+        .await
+        .unwrap();
+    let migrate = migrate_strategy(&new_owner, &owner, 0);
+    assert_error(
+        env.send(std::slice::from_ref(&migrate), &[&next]).await,
+        ErrorCode::StrategyLinksActive,
+    );
+
+    // 冻结的配置仍可撤销授权和取消保护单
+    env.send_as_owner(revoke_delegate(&owner, 0, &delegate))
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.delegates, 0);
+    assert_error(
+        env.send(std::slice::from_ref(&migrate), &[&next]).await,
+        ErrorCode::StrategyLinksActive,
+    );
+    env.send_as_owner(cancel_protection(&owner, 0))
+        .await
+        .unwrap();
+    env.send(&[migrate], &[&next]).await.unwrap();
+    let migrated: StrategyConfig = env.account(&strategy_pda(&new_owner, 0)).await;
+    assert_eq!(migrated.origin, strategy_pda(&owner, 0));
+
+    // 新所有者调整分配项后，跟随者传入迁移后的策略同步
+    let rebalanced = vec![allocation(0, 0, 5000), allocation(1, 1, 5000)];
+    env.send(
+        &[update_strategy(
+            &new_owner,
+            0,
+            rebalanced.clone(),
+            condition(),
+            100,
+        )],
+        &[&next],
+    )
+    .await
+    .unwrap();
+    let sync = sync_follower(&new_owner, &follower, 0, &new_owner, 0, &rebalanced);
+    env.send(&[sync], &[&next]).await.unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&follower, 0)).await;
+    assert!(strategy.allocations == rebalanced);
+    let follow: StrategyFollow = env.account(&follow_pda(&follower, 0)).await;
+    assert_eq!(follow.leader, strategy_pda(&owner, 0));
+    assert_eq!(follow.syncs, 1);
+}
+
+// 测试31: 保护单触发后暂停的策略可以立即按安全头寸再平衡一次，之后恢复前不能再平衡
+#[tokio::test]
+async fn test_protection_unwind() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let keeper = env.other.insecure_clone();
+    let prices = env.price_accounts(false);
+    let oracles = env.oracles;
+
+    env.send_as_owner(rebalance_positions(&owner, 0, &prices))
+        .await
+        .unwrap();
+    env.send_as_owner(set_protection(
+        &owner,
+        0,
+        protection_params(LAMPORTS_PER_SOL * 9 / 10, 0, 0),
+        &prices,
+    ))
+    .await
+    .unwrap();
+
+    // 资产0跌到0.5，估值 0.6 × 0.5 + 0.4 = 0.7 SOL，低于止损线
+    let now = START_TIME + 60;
+    env.set_time(now).await;
+    env.set_oracle(&oracles[0], PRICE / 2, PRICE as u64 / 1000, now);
+    env.set_oracle(&oracles[1], PRICE, PRICE as u64 / 1000, now);
+    env.send(
+        &[check_protection(&keeper.pubkey(), &owner, 0, &prices)],
+        &[&keeper],
+    )
+    .await
+    .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.paused_at, now);
+    assert!(strategy.unwind_pending);
+
+    // 距上次再平衡未满时间间隔也可以平仓
+    let safe_prices = [asset_pda(1), oracles[1]];
+    env.send_as_owner(rebalance_positions(&owner, 0, &safe_prices))
+        .await
+        .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+    assert_eq!(strategy.last_executed_at, now);
+    assert!(!strategy.unwind_pending);
+    assert_eq!(strategy.paused_at, now);
+
+    let now = now + 1;
+    env.set_time(now).await;
+    env.set_oracle(&oracles[1], PRICE, PRICE as u64 / 1000, now);
+    assert_error(
+        env.send_as_owner(rebalance_positions(&owner, 0, &safe_prices))
+            .await,
+        ErrorCode::StrategyPaused,
+    );
+}
@@ -0,0 +1,1157 @@
+// 基于属性的不变量测试: 随机生成多个用户的 初始化/创建/更新/存款/提款/执行/再平衡 操作序列，
+// 每笔交易的结果与参考模型比较，并在每步之后检查记账不变量:
+// - 资金库余额 = 免租金余额 + 用户记账总额 = 免租金余额 + 各策略记账之和
+// - 提款不能超过策略中记录的份额，也不能动用其他用户的资金库
+// - 策略计数器只在创建成功时严格递增
+// - 费用金库收到的 lamports 等于按同一费率计提的费用总额
+// - 存款不超过策略、用户和资产上限，资产注册表的累计存款与模型一致
+// - 排队提款锁定的金额留在资金库直到领取，工单按ID先后领取且不早于头寸解除或锁定到期
+// 用例数默认为32，可通过 PROPTEST_CASES 环境变量加大
+mod common;
+
+use anchor_lang::AccountDeserialize;
+use common::*;
+use defi_tools::fees::{self, FeeAccrual};
+use defi_tools::limits::allocation_share;
+use defi_tools::validation::MAX_ALLOCATIONS;
+use defi_tools::{
+    accounts, instruction, Allocation, AssetParams, AssetRegistry, DepositLimits, ErrorCode,
+    FeeConfig, StrategyConfig, UserProfile, WithdrawalTicket,
+};
+use proptest::prelude::*;
+use solana_program_test::BanksClientError;
+use solana_sdk::account::Account;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+
+const USERS: usize = 3;
+const MAX_STRATEGIES: u64 = 3;
+const WALLET_LAMPORTS: u64 = 1_000 * LAMPORTS_PER_SOL;
+// 系统程序转账余额不足时的错误码
+const SYSTEM_INSUFFICIENT_FUNDS: u32 = 1;
+// Anchor 读取未初始化账户时的错误码
+const ACCOUNT_NOT_INITIALIZED: u32 = 3012;
+// 测试期间启用的费率
+const FEES: FeeConfig = FeeConfig {
+    management_fee_bps: 200,
+    performance_fee_bps: 0,
+};
+// 测试期间启用的存款限制，随机存款金额经常触及这些上限
+const LIMITS: DepositLimits = DepositLimits {
+    min_deposit_lamports: LAMPORTS_PER_SOL / 100,
+    max_strategy_value_lamports: 20 * LAMPORTS_PER_SOL,
+    max_user_value_lamports: 40 * LAMPORTS_PER_SOL,
+};
+// 各资产的存款上限，资产1不限制
+const ASSET_CAPS: [u64; 2] = [25 * LAMPORTS_PER_SOL, 0];
+// 提款工单的锁定时间，与再平衡间隔相同
+const WITHDRAWAL_DELAY: u32 = INTERVAL as u32;
+
+// 分配项 (协议, 资产, 权重)
+type Entry = (u8, u8, u16);
+
+#[derive(Clone, Debug)]
+enum Op {
+    InitUser {
+        user: usize,
+        risk_level: u8,
+    },
+    Create {
+        user: usize,
+        entries: Vec<Entry>,
+    },
+    // 替换策略的分配项，资金记账不受影响
+    Update {
+        user: usize,
+        strategy_id: u64,
+        entries: Vec<Entry>,
+    },
+    Deposit {
+        user: usize,
+        strategy_id: u64,
+        amount: u64,
+    },
+    // 按策略记录金额的比例提款，超过 10000 基点即超额提款
+    Withdraw {
+        user: usize,
+        strategy_id: u64,
+        share_bps: u64,
+    },
+    // 用自己的签名和配置文件从其他用户的策略和资金库提款
+    Steal {
+        thief: usize,
+        victim: usize,
+        strategy_id: u64,
+    },
+    Execute {
+        user: usize,
+        strategy_id: u64,
+    },
+    Rebalance {
+        user: usize,
+        strategy_id: u64,
+    },
+    // 按策略记录金额的比例申请排队提款
+    Request {
+        user: usize,
+        strategy_id: u64,
+        share_bps: u64,
+    },
+    // 领取工单，附加队首到该工单之间的全部工单
+    Claim {
+        user: usize,
+        strategy_id: u64,
+        ticket_id: u64,
+    },
+    Cancel {
+        user: usize,
+        strategy_id: u64,
+        ticket_id: u64,
+    },
+    // 推进时间并刷新价格
+    Advance {
+        seconds: i64,
+    },
+}
+
+#[derive(Default)]
+struct StrategyModel {
+    allocations: Vec<Allocation>,
+    total_value_lamports: u64,
+    last_executed_at: i64,
+    fees_accrued_at: i64,
+    // 按ID排列的工单 (金额, 到期时间)，领取或取消后为 None
+    tickets: Vec<Option<(u64, i64)>>,
+    head_ticket_id: u64,
+    unwound_ticket_id: u64,
+    // 按首次存入顺序排列的 (资产, 计入该资产的存款)
+    asset_deposits: Vec<(u8, u64)>,
+}
+
+impl StrategyModel {
+    fn locked_lamports(&self) -> u64 {
+        self.tickets
+            .iter()
+            .flatten()
+            .map(|(amount, _)| amount)
+            .sum()
+    }
+
+    fn ticket(&self, ticket_id: u64) -> Option<(u64, i64)> {
+        self.tickets.get(ticket_id as usize).copied().flatten()
+    }
+
+    fn record_deposit(&mut self, asset: u8, amount: u64) {
+        if amount == 0 {
+            return;
+        }
+        match self.asset_deposits.iter_mut().find(|(a, _)| *a == asset) {
+            Some((_, lamports)) => *lamports += amount,
+            None => self.asset_deposits.push((asset, amount)),
+        }
+    }
+
+    // 按金额占策略价值与锁定金额之和的比例扣回存款记录，返回每个资产扣回的金额
+    fn release_deposits(&mut self, amount: u64) -> [u64; 2] {
+        let base = self.total_value_lamports + self.locked_lamports();
+        let mut released = [0; 2];
+        for (asset, lamports) in self.asset_deposits.iter_mut() {
+            let share = if amount >= base {
+                *lamports
+            } else {
+                (*lamports as u128 * amount as u128 / base as u128) as u64
+            };
+            *lamports -= share;
+            released[*asset as usize] += share;
+        }
+        self.asset_deposits.retain(|(_, lamports)| *lamports > 0);
+        released
+    }
+}
+
+#[derive(Default)]
+struct UserModel {
+    total_value_lamports: u64,
+    strategies: Vec<StrategyModel>,
+}
+
+// 参考模型: 预测每笔交易的结果。Ok(()) 表示应当成功，Err(code) 为应返回的错误码
+struct Model {
+    now: i64,
+    users: [Option<UserModel>; USERS],
+    // 已支付给费用金库的费用总额
+    fees_collected: u64,
+    // 各资产注册表记录的累计存款
+    asset_deposits: [u64; 2],
+}
+
+type Expected = Result<(), u32>;
+
+impl Model {
+    fn strategy(&self, user: usize, strategy_id: u64) -> Option<&StrategyModel> {
+        self.users[user]
+            .as_ref()
+            .and_then(|profile| profile.strategies.get(strategy_id as usize))
+    }
+
+    // 按链上相同的规则计提到当前时间的管理费，不修改模型
+    fn accrue(&self, strategy: &StrategyModel) -> FeeAccrual {
+        let (management_fee, accrued_at) = fees::accrue_management(
+            &FEES,
+            strategy.total_value_lamports,
+            strategy.fees_accrued_at,
+            self.now,
+        )
+        .unwrap();
+        FeeAccrual {
+            management_fee,
+            value: strategy.total_value_lamports - management_fee,
+            accrued_at,
+            ..FeeAccrual::default()
+        }
+    }
+
+    // 交易成功时提交计提结果，返回扣费后的策略
+    fn apply_fees(
+        &mut self,
+        user: usize,
+        strategy_id: u64,
+        accrual: FeeAccrual,
+    ) -> &mut StrategyModel {
+        self.fees_collected += accrual.total();
+        let profile = self.users[user].as_mut().unwrap();
+        profile.total_value_lamports -= accrual.total();
+        let strategy = &mut profile.strategies[strategy_id as usize];
+        strategy.total_value_lamports = accrual.value;
+        strategy.fees_accrued_at = accrual.accrued_at;
+        strategy
+    }
+
+    fn init_user(&mut self, user: usize, risk_level: u8) -> Expected {
+        if self.users[user].is_some() {
+            // 系统程序返回 AccountAlreadyInUse
+            return Err(0);
+        }
+        if !(1..=5).contains(&risk_level) {
+            return Err(ErrorCode::InvalidRiskLevel.into());
+        }
+        self.users[user] = Some(UserModel::default());
+        Ok(())
+    }
+
+    // 与 validation 模块相同的检查顺序，最后检查资产是否已注册
+    fn validate(allocations: &[Allocation]) -> Expected {
+        if allocations.is_empty() {
+            return Err(ErrorCode::InvalidAllocation.into());
+        }
+        if allocations.len() > MAX_ALLOCATIONS {
+            return Err(ErrorCode::TooManyAllocations.into());
+        }
+        for (index, a) in allocations.iter().enumerate() {
+            if a.protocol > 3 {
+                return Err(ErrorCode::UnknownProtocol.into());
+            }
+            if a.target_weight_bps == 0 {
+                return Err(ErrorCode::ZeroWeightAllocation.into());
+            }
+            if a.target_weight_bps > 10000 {
+                return Err(ErrorCode::AllocationWeightOutOfRange.into());
+            }
+            if allocations[..index]
+                .iter()
+                .any(|b| b.protocol == a.protocol && b.asset == a.asset)
+            {
+                return Err(ErrorCode::DuplicateAllocation.into());
+            }
+        }
+        let total: u32 = allocations
+            .iter()
+            .map(|a| u32::from(a.target_weight_bps))
+            .sum();
+        if total != 10000 {
+            return Err(ErrorCode::InvalidAllocation.into());
+        }
+        if allocations.iter().any(|a| a.asset > 1) {
+            return Err(ErrorCode::AssetNotRegistered.into());
+        }
+        Ok(())
+    }
+
+    fn create(&mut self, user: usize, allocations: &[Allocation]) -> Expected {
+        let Some(profile) = self.users[user].as_mut() else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        Self::validate(allocations)?;
+        profile.strategies.push(StrategyModel {
+            allocations: allocations.to_vec(),
+            fees_accrued_at: self.now,
+            ..StrategyModel::default()
+        });
+        Ok(())
+    }
+
+    fn update(&mut self, user: usize, strategy_id: u64, allocations: &[Allocation]) -> Expected {
+        let Some(strategy) = self.users[user]
+            .as_mut()
+            .and_then(|profile| profile.strategies.get_mut(strategy_id as usize))
+        else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        Self::validate(allocations)?;
+        strategy.allocations = allocations.to_vec();
+        Ok(())
+    }
+
+    fn deposit(&mut self, user: usize, strategy_id: u64, amount: u64) -> Expected {
+        let Some(strategy) = self.strategy(user, strategy_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        if amount == 0 {
+            return Err(ErrorCode::InsufficientFunds.into());
+        }
+        let accrual = self.accrue(strategy);
+        let user_value = self.users[user].as_ref().unwrap().total_value_lamports - accrual.total();
+        // 按扣费后的价值依次检查最小金额、策略上限和用户上限
+        if amount < LIMITS.min_deposit_lamports {
+            return Err(ErrorCode::DepositTooSmall.into());
+        }
+        let Some(strategy_total) = accrual.value.checked_add(amount) else {
+            return Err(ErrorCode::MathError.into());
+        };
+        if strategy_total > LIMITS.max_strategy_value_lamports {
+            return Err(ErrorCode::StrategyDepositCapExceeded.into());
+        }
+        let Some(user_total) = user_value.checked_add(amount) else {
+            return Err(ErrorCode::MathError.into());
+        };
+        if user_total > LIMITS.max_user_value_lamports {
+            return Err(ErrorCode::UserDepositCapExceeded.into());
+        }
+        let shares = asset_shares(&strategy.allocations, amount);
+        let order = asset_order(&strategy.allocations);
+        let mut asset_deposits = self.asset_deposits;
+        for (asset, share) in shares.into_iter().enumerate() {
+            asset_deposits[asset] += share;
+            if ASSET_CAPS[asset] != 0 && asset_deposits[asset] > ASSET_CAPS[asset] {
+                return Err(ErrorCode::AssetDepositCapExceeded.into());
+            }
+        }
+        if amount > WALLET_LAMPORTS {
+            return Err(SYSTEM_INSUFFICIENT_FUNDS);
+        }
+        self.asset_deposits = asset_deposits;
+        let strategy = self.apply_fees(user, strategy_id, accrual);
+        for asset in order {
+            strategy.record_deposit(asset, shares[asset as usize]);
+        }
+        strategy.total_value_lamports = strategy_total;
+        self.users[user].as_mut().unwrap().total_value_lamports = user_total;
+        Ok(())
+    }
+
+    // 先计提费用，提款金额不能超过扣费后的价值
+    fn withdraw(&mut self, user: usize, strategy_id: u64, amount: u64) -> Expected {
+        let Some(strategy) = self.strategy(user, strategy_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        let accrual = self.accrue(strategy);
+        if amount == 0 || amount > accrual.value {
+            return Err(ErrorCode::InsufficientFunds.into());
+        }
+        let strategy = self.apply_fees(user, strategy_id, accrual);
+        let released = strategy.release_deposits(amount);
+        strategy.total_value_lamports -= amount;
+        self.users[user].as_mut().unwrap().total_value_lamports -= amount;
+        for (asset, share) in released.into_iter().enumerate() {
+            self.asset_deposits[asset] -= share;
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, user: usize, strategy_id: u64) -> Expected {
+        let now = self.now;
+        let Some(strategy) = self.users[user]
+            .as_mut()
+            .and_then(|profile| profile.strategies.get_mut(strategy_id as usize))
+        else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        strategy.last_executed_at = now;
+        Ok(())
+    }
+
+    fn rebalance(&mut self, user: usize, strategy_id: u64) -> Expected {
+        let now = self.now;
+        let Some(strategy) = self.strategy(user, strategy_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        if !condition().interval_elapsed(strategy.last_executed_at, now) {
+            return Err(ErrorCode::RebalanceConditionNotMet.into());
+        }
+        let accrual = self.accrue(strategy);
+        let strategy = self.apply_fees(user, strategy_id, accrual);
+        strategy.last_executed_at = now;
+        strategy.unwound_ticket_id = strategy.tickets.len() as u64;
+        Ok(())
+    }
+
+    // 与提款相同地扣除价值，但资产存款在领取时才扣回
+    fn request(&mut self, user: usize, strategy_id: u64, amount: u64) -> Expected {
+        let now = self.now;
+        let Some(strategy) = self.strategy(user, strategy_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        let accrual = self.accrue(strategy);
+        if amount == 0 || amount > accrual.value {
+            return Err(ErrorCode::InsufficientFunds.into());
+        }
+        let strategy = self.apply_fees(user, strategy_id, accrual);
+        strategy.total_value_lamports -= amount;
+        strategy
+            .tickets
+            .push(Some((amount, now + WITHDRAWAL_DELAY as i64)));
+        self.users[user].as_mut().unwrap().total_value_lamports -= amount;
+        Ok(())
+    }
+
+    fn claim(&mut self, user: usize, strategy_id: u64, ticket_id: u64) -> Expected {
+        let now = self.now;
+        let Some(strategy) = self.strategy(user, strategy_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        let Some((amount, unlocks_at)) = strategy.ticket(ticket_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        if ticket_id >= strategy.unwound_ticket_id && now < unlocks_at {
+            return Err(ErrorCode::WithdrawalNotReady.into());
+        }
+        if (strategy.head_ticket_id..ticket_id).any(|id| strategy.ticket(id).is_some()) {
+            return Err(ErrorCode::WithdrawalOutOfOrder.into());
+        }
+        let strategy = &mut self.users[user].as_mut().unwrap().strategies[strategy_id as usize];
+        let released = strategy.release_deposits(amount);
+        for (asset, share) in released.into_iter().enumerate() {
+            self.asset_deposits[asset] -= share;
+        }
+        strategy.tickets[ticket_id as usize] = None;
+        strategy.head_ticket_id = ticket_id + 1;
+        Ok(())
+    }
+
+    // 先计提费用，取消的金额像存款一样退回策略，不检查存款限制
+    fn cancel(&mut self, user: usize, strategy_id: u64, ticket_id: u64) -> Expected {
+        let Some(strategy) = self.strategy(user, strategy_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        let Some((amount, _)) = strategy.ticket(ticket_id) else {
+            return Err(ACCOUNT_NOT_INITIALIZED);
+        };
+        let accrual = self.accrue(strategy);
+        let strategy = self.apply_fees(user, strategy_id, accrual);
+        strategy.total_value_lamports += amount;
+        strategy.tickets[ticket_id as usize] = None;
+        if ticket_id == strategy.head_ticket_id {
+            strategy.head_ticket_id += 1;
+        }
+        self.users[user].as_mut().unwrap().total_value_lamports += amount;
+        Ok(())
+    }
+}
+
+struct Harness {
+    env: Env,
+    users: Vec<Keypair>,
+    model: Model,
+    rent_exempt: u64,
+    // 费用金库的初始余额
+    treasury_lamports: u64,
+}
+
+impl Harness {
+    async fn new() -> Self {
+        let mut env = start().await;
+        env.init_registry().await;
+        let admin = env.admin.pubkey();
+        env.send_as_admin(update_fee_config(&admin, FEES, &TREASURY))
+            .await
+            .unwrap();
+        env.send_as_admin(update_deposit_limits(&admin, LIMITS))
+            .await
+            .unwrap();
+        env.send_as_admin(update_withdrawal_delay(&admin, WITHDRAWAL_DELAY))
+            .await
+            .unwrap();
+        for (asset_id, cap) in ASSET_CAPS.into_iter().enumerate() {
+            let params = AssetParams {
+                deposit_cap_lamports: cap,
+                ..env.asset_params(asset_id)
+            };
+            env.send_as_admin(update_asset(&admin, asset_id as u8, params))
+                .await
+                .unwrap();
+        }
+        let treasury_lamports = env.raw_account(&TREASURY).await.unwrap().lamports;
+        let users: Vec<Keypair> = vec![
+            env.owner.insecure_clone(),
+            env.other.insecure_clone(),
+            Keypair::new(),
+        ];
+        for user in &users {
+            env.set_account(
+                &user.pubkey(),
+                Account::new(
+                    WALLET_LAMPORTS + 10 * LAMPORTS_PER_SOL,
+                    0,
+                    &system_program::ID,
+                ),
+            );
+        }
+        let rent_exempt = env
+            .ctx
+            .banks_client
+            .get_rent()
+            .await
+            .unwrap()
+            .minimum_balance(0);
+        Harness {
+            env,
+            users,
+            model: Model {
+                now: START_TIME,
+                users: Default::default(),
+                fees_collected: 0,
+                asset_deposits: [0; 2],
+            },
+            rent_exempt,
+            treasury_lamports,
+        }
+    }
+
+    async fn send_as(&mut self, user: usize, ix: Instruction) -> Result<(), BanksClientError> {
+        let signer = self.users[user].insecure_clone();
+        self.env.send(&[ix], &[&signer]).await
+    }
+
+    // 存取款附加的资产注册表按策略的分配项顺序排列，策略不存在时为空
+    fn allocations(&self, user: usize, strategy_id: u64) -> Vec<Allocation> {
+        self.model
+            .strategy(user, strategy_id)
+            .map(|strategy| strategy.allocations.clone())
+            .unwrap_or_default()
+    }
+
+    // 提款和领取工单按存款记录顺序附加的资产注册表
+    fn deposit_registries(
+        &self,
+        mut ix: Instruction,
+        user: usize,
+        strategy_id: u64,
+    ) -> Instruction {
+        if let Some(strategy) = self.model.strategy(user, strategy_id) {
+            ix.accounts.extend(
+                strategy
+                    .asset_deposits
+                    .iter()
+                    .map(|(asset, _)| AccountMeta::new(asset_pda(*asset), false)),
+            );
+        }
+        ix
+    }
+
+    // 再平衡所需的价格账户，策略不存在时为空
+    fn price_accounts(&self, user: usize, strategy_id: u64) -> Vec<Pubkey> {
+        self.model
+            .strategy(user, strategy_id)
+            .map(|strategy| {
+                strategy
+                    .allocations
+                    .iter()
+                    .flat_map(|a| [asset_pda(a.asset), self.env.oracles[a.asset as usize]])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    async fn apply(&mut self, op: &Op) {
+        let (result, expected) = match op.clone() {
+            Op::InitUser { user, risk_level } => {
+                let owner = self.users[user].pubkey();
+                let result = self
+                    .send_as(user, initialize_user(&owner, risk_level))
+                    .await;
+                (result, self.model.init_user(user, risk_level))
+            }
+            Op::Create { user, entries } => {
+                let allocations = to_allocations(&entries);
+                let owner = self.users[user].pubkey();
+                let strategy_id = self.model.users[user]
+                    .as_ref()
+                    .map_or(0, |profile| profile.strategies.len() as u64);
+                let expected = self.model.create(user, &allocations);
+                let ix = create_strategy(&owner, strategy_id, allocations, condition(), 100);
+                (self.send_as(user, ix).await, expected)
+            }
+            Op::Update {
+                user,
+                strategy_id,
+                entries,
+            } => {
+                let allocations = to_allocations(&entries);
+                let owner = self.users[user].pubkey();
+                let expected = self.model.update(user, strategy_id, &allocations);
+                let ix = update_strategy(&owner, strategy_id, allocations, condition(), 100);
+                (self.send_as(user, ix).await, expected)
+            }
+            Op::Deposit {
+                user,
+                strategy_id,
+                amount,
+            } => {
+                let owner = self.users[user].pubkey();
+                let allocations = self.allocations(user, strategy_id);
+                let result = self
+                    .send_as(
+                        user,
+                        deposit_funds(&owner, strategy_id, amount, &allocations),
+                    )
+                    .await;
+                (result, self.model.deposit(user, strategy_id, amount))
+            }
+            Op::Withdraw {
+                user,
+                strategy_id,
+                share_bps,
+            } => {
+                let owner = self.users[user].pubkey();
+                let recorded = self
+                    .model
+                    .strategy(user, strategy_id)
+                    .map_or(0, |strategy| strategy.total_value_lamports);
+                let amount = (recorded as u128 * share_bps as u128 / 10000) as u64;
+                let ix = withdraw_funds(&owner, strategy_id, amount, &[]);
+                let ix = self.deposit_registries(ix, user, strategy_id);
+                let result = self.send_as(user, ix).await;
+                (result, self.model.withdraw(user, strategy_id, amount))
+            }
+            Op::Steal {
+                thief,
+                victim,
+                strategy_id,
+            } => {
+                let thief_key = self.users[thief].pubkey();
+                let victim_key = self.users[victim].pubkey();
+                let ix = build(
+                    accounts::WithdrawFunds {
+                        owner: thief_key,
+                        user_profile: user_pda(&thief_key),
+                        strategy_config: strategy_pda(&victim_key, strategy_id),
+                        vault: vault_pda(&victim_key),
+                        config: config_pda(),
+                        treasury: TREASURY,
+                        destination: thief_key,
+                        withdrawal_destination: None,
+                        guardian: None,
+                        system_program: system_program::ID,
+                    },
+                    instruction::WithdrawFunds {
+                        strategy_id,
+                        amount: 1,
+                    },
+                    &[],
+                );
+                let ix = self.deposit_registries(ix, victim, strategy_id);
+                let result = self.send_as(thief, ix).await;
+                // 自己的账户按原样提款，否则必须失败
+                if thief == victim {
+                    (result, self.model.withdraw(thief, strategy_id, 1))
+                } else {
+                    assert!(result.is_err(), "{op:?} 不应成功");
+                    return;
+                }
+            }
+            Op::Execute { user, strategy_id } => {
+                let owner = self.users[user].pubkey();
+                let result = self
+                    .send_as(user, execute_strategy(&owner, strategy_id))
+                    .await;
+                (result, self.model.execute(user, strategy_id))
+            }
+            Op::Rebalance { user, strategy_id } => {
+                let owner = self.users[user].pubkey();
+                let prices = self.price_accounts(user, strategy_id);
+                let result = self
+                    .send_as(user, rebalance_positions(&owner, strategy_id, &prices))
+                    .await;
+                (result, self.model.rebalance(user, strategy_id))
+            }
+            Op::Request {
+                user,
+                strategy_id,
+                share_bps,
+            } => {
+                let owner = self.users[user].pubkey();
+                let (recorded, ticket_id) = self
+                    .model
+                    .strategy(user, strategy_id)
+                    .map_or((0, 0), |strategy| {
+                        (strategy.total_value_lamports, strategy.tickets.len() as u64)
+                    });
+                let amount = (recorded as u128 * share_bps as u128 / 10000) as u64;
+                let ix = request_withdrawal(&owner, strategy_id, ticket_id, amount);
+                let result = self.send_as(user, ix).await;
+                (result, self.model.request(user, strategy_id, amount))
+            }
+            Op::Claim {
+                user,
+                strategy_id,
+                ticket_id,
+            } => {
+                let owner = self.users[user].pubkey();
+                let head = self
+                    .model
+                    .strategy(user, strategy_id)
+                    .map_or(0, |strategy| strategy.head_ticket_id);
+                let skipped: Vec<u64> = (head..ticket_id).collect();
+                let ix = claim_withdrawal(&owner, strategy_id, ticket_id, &[], &[]);
+                let mut ix = self.deposit_registries(ix, user, strategy_id);
+                ix.accounts.extend(skipped.iter().map(|id| {
+                    AccountMeta::new_readonly(ticket_pda(&owner, strategy_id, *id), false)
+                }));
+                let result = self.send_as(user, ix).await;
+                (result, self.model.claim(user, strategy_id, ticket_id))
+            }
+            Op::Cancel {
+                user,
+                strategy_id,
+                ticket_id,
+            } => {
+                let owner = self.users[user].pubkey();
+                let result = self
+                    .send_as(user, cancel_withdrawal(&owner, strategy_id, ticket_id))
+                    .await;
+                (result, self.model.cancel(user, strategy_id, ticket_id))
+            }
+            Op::Advance { seconds } => {
+                self.model.now += seconds;
+                let now = self.model.now;
+                self.env.set_time(now).await;
+                for oracle in self.env.oracles {
+                    self.env
+                        .set_oracle(&oracle, PRICE, PRICE as u64 / 1000, now);
+                }
+                return;
+            }
+        };
+
+        match (result, expected) {
+            (Ok(()), Ok(())) => {}
+            (Err(err), Err(code)) => {
+                assert_eq!(error_code(&err), Some(code), "{op:?}: {err:?}")
+            }
+            (result, expected) => panic!("{op:?}: 预期 {expected:?}，实际 {result:?}"),
+        }
+    }
+
+    // 链上各用户的策略计数器，未初始化的用户为 None
+    async fn strategy_counters(&mut self) -> Vec<Option<u64>> {
+        let mut counters = Vec::new();
+        for user in 0..USERS {
+            let profile = user_pda(&self.users[user].pubkey());
+            let counter = match self.env.raw_account(&profile).await {
+                Some(account) => Some(
+                    UserProfile::try_deserialize(&mut account.data.as_slice())
+                        .unwrap()
+                        .strategy_counter,
+                ),
+                None => None,
+            };
+            counters.push(counter);
+        }
+        counters
+    }
+
+    // 链上状态与模型一致，且资金库余额等于记账总额
+    async fn check(&mut self) {
+        for user in 0..USERS {
+            let owner = self.users[user].pubkey();
+            let Some(expected) = self.model.users[user].as_ref() else {
+                assert!(self.env.raw_account(&user_pda(&owner)).await.is_none());
+                continue;
+            };
+            let profile: UserProfile = self.env.account(&user_pda(&owner)).await;
+            assert_eq!(profile.strategy_counter, expected.strategies.len() as u64);
+            assert_eq!(profile.total_value_lamports, expected.total_value_lamports);
+
+            let mut strategy_sum = 0u64;
+            let mut locked_sum = 0u64;
+            for (strategy_id, model) in expected.strategies.iter().enumerate() {
+                let strategy: StrategyConfig = self
+                    .env
+                    .account(&strategy_pda(&owner, strategy_id as u64))
+                    .await;
+                assert_eq!(strategy.strategy_id, strategy_id as u64);
+                assert_eq!(strategy.total_value_lamports, model.total_value_lamports);
+                assert_eq!(strategy.last_executed_at, model.last_executed_at);
+                assert_eq!(strategy.fees_accrued_at, model.fees_accrued_at);
+                let weights = |allocations: &[Allocation]| -> Vec<Entry> {
+                    allocations
+                        .iter()
+                        .map(|a| (a.protocol, a.asset, a.target_weight_bps))
+                        .collect()
+                };
+                assert_eq!(weights(&strategy.allocations), weights(&model.allocations));
+                let deposits: Vec<(u8, u64)> = strategy
+                    .asset_deposits
+                    .iter()
+                    .map(|d| (d.asset, d.lamports))
+                    .collect();
+                assert_eq!(deposits, model.asset_deposits);
+                strategy_sum += strategy.total_value_lamports;
+
+                let queue = strategy.withdrawal_queue;
+                assert_eq!(queue.next_ticket_id, model.tickets.len() as u64);
+                assert_eq!(queue.head_ticket_id, model.head_ticket_id);
+                assert_eq!(queue.unwound_ticket_id, model.unwound_ticket_id);
+                assert_eq!(queue.locked_lamports, model.locked_lamports());
+                for (ticket_id, ticket) in model.tickets.iter().enumerate() {
+                    let address = ticket_pda(&owner, strategy_id as u64, ticket_id as u64);
+                    let account = self.env.raw_account(&address).await;
+                    match ticket {
+                        Some((amount, unlocks_at)) => {
+                            let ticket = WithdrawalTicket::try_deserialize(
+                                &mut account.unwrap().data.as_slice(),
+                            )
+                            .unwrap();
+                            assert_eq!(ticket.amount_lamports, *amount);
+                            assert_eq!(ticket.unlocks_at, *unlocks_at);
+                        }
+                        None => assert!(account.is_none()),
+                    }
+                }
+                locked_sum += queue.locked_lamports;
+            }
+            assert_eq!(profile.total_value_lamports, strategy_sum);
+
+            let vault = self.env.raw_account(&vault_pda(&owner)).await.unwrap();
+            assert_eq!(vault.owner, system_program::ID);
+            assert_eq!(
+                vault.lamports,
+                self.rent_exempt + profile.total_value_lamports + locked_sum
+            );
+        }
+
+        for (asset_id, expected) in self.model.asset_deposits.into_iter().enumerate() {
+            let asset: AssetRegistry = self.env.account(&asset_pda(asset_id as u8)).await;
+            assert_eq!(asset.deposited_lamports, expected);
+        }
+
+        let treasury = self.env.raw_account(&TREASURY).await.unwrap();
+        assert_eq!(
+            treasury.lamports,
+            self.treasury_lamports + self.model.fees_collected
+        );
+    }
+}
+
+// 按目标权重分摊到资产0和资产1的金额
+fn asset_shares(allocations: &[Allocation], amount: u64) -> [u64; 2] {
+    let mut shares = [0; 2];
+    for a in allocations {
+        shares[a.asset as usize] += allocation_share(amount, a.target_weight_bps);
+    }
+    shares
+}
+
+// 资产在分配项中首次出现的顺序，即存款记录新增资产的顺序
+fn asset_order(allocations: &[Allocation]) -> Vec<u8> {
+    let mut order = Vec::new();
+    for a in allocations {
+        if !order.contains(&a.asset) {
+            order.push(a.asset);
+        }
+    }
+    order
+}
+
+fn to_allocations(entries: &[Entry]) -> Vec<Allocation> {
+    entries
+        .iter()
+        .map(|&(protocol, asset, weight)| allocation(protocol, asset, weight))
+        .collect()
+}
+
+// 偏向编号小的用户和策略，让操作更多地落在已有的状态上
+fn user() -> impl Strategy<Value = usize> {
+    prop_oneof![3 => Just(0), 2 => Just(1), 1 => 2..USERS]
+}
+
+fn strategy_id() -> impl Strategy<Value = u64> {
+    prop_oneof![3 => Just(0), 2 => Just(1), 1 => 2..=MAX_STRATEGIES]
+}
+
+// 工单ID集中在前几个，领取时经常需要跳过或遇到尚未领取的工单
+fn ticket_id() -> impl Strategy<Value = u64> {
+    0..4u64
+}
+
+// 合法的分配: 单一资产，或资产0和资产1按随机比例拆分
+fn valid_entries() -> impl Strategy<Value = Vec<Entry>> {
+    prop_oneof![
+        (0..4u8, 0..2u8).prop_map(|(protocol, asset)| vec![(protocol, asset, 10000)]),
+        (0..4u8, 0..4u8, 1..10000u16).prop_map(|(first, second, weight)| {
+            vec![(first, 0, weight), (second, 1, 10000 - weight)]
+        }),
+    ]
+}
+
+// 任意分配: 大多数协议和资产合法，偶尔超过数量上限，权重一半情况下凑成 10000
+fn arbitrary_entries() -> impl Strategy<Value = Vec<Entry>> {
+    let entry = (
+        prop_oneof![4 => 0..4u8, 1 => 4..=u8::MAX],
+        prop_oneof![5 => 0..2u8, 1 => 2..=u8::MAX],
+        any::<u16>(),
+    );
+    let len = prop_oneof![5 => 0..=4usize, 1 => MAX_ALLOCATIONS..=MAX_ALLOCATIONS + 1];
+    let entries = len.prop_flat_map(move |len| prop::collection::vec(entry.clone(), len));
+    (entries, any::<bool>()).prop_map(|(mut entries, balanced)| {
+        if balanced && !entries.is_empty() {
+            let last = entries.len() - 1;
+            let cap = 10000 / entries.len() as u16;
+            for entry in &mut entries[..last] {
+                entry.2 %= cap;
+            }
+            let rest: u16 = entries[..last].iter().map(|entry| entry.2).sum();
+            entries[last].2 = 10000 - rest;
+        }
+        entries
+    })
+}
+
+fn allocation_entries() -> impl Strategy<Value = Vec<Entry>> {
+    prop_oneof![3 => valid_entries(), 1 => arbitrary_entries()]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let amount = prop_oneof![
+        1 => Just(0u64),
+        1 => 1..LIMITS.min_deposit_lamports,
+        8 => 1..=10 * LAMPORTS_PER_SOL,
+        1 => Just(u64::MAX),
+    ];
+    let share_bps = prop_oneof![
+        1 => Just(0u64),
+        6 => 1..=10000u64,
+        3 => 10001..=20000u64,
+    ];
+    let risk_level = prop_oneof![4 => 1..=5u8, 1 => any::<u8>()];
+    prop_oneof![
+        2 => (user(), risk_level).prop_map(|(user, risk_level)| Op::InitUser { user, risk_level }),
+        3 => (user(), allocation_entries()).prop_map(|(user, entries)| Op::Create { user, entries }),
+        2 => (user(), strategy_id(), allocation_entries()).prop_map(|(user, strategy_id, entries)| {
+            Op::Update {
+                user,
+                strategy_id,
+                entries,
+            }
+        }),
+        4 => (user(), strategy_id(), amount).prop_map(|(user, strategy_id, amount)| Op::Deposit {
+            user,
+            strategy_id,
+            amount
+        }),
+        4 => (user(), strategy_id(), share_bps.clone()).prop_map(|(user, strategy_id, share_bps)| {
+            Op::Withdraw {
+                user,
+                strategy_id,
+                share_bps,
+            }
+        }),
+        1 => (user(), user(), strategy_id()).prop_map(|(thief, victim, strategy_id)| Op::Steal {
+            thief,
+            victim,
+            strategy_id
+        }),
+        1 => (user(), strategy_id()).prop_map(|(user, strategy_id)| Op::Execute { user, strategy_id }),
+        2 => (user(), strategy_id()).prop_map(|(user, strategy_id)| Op::Rebalance { user, strategy_id }),
+        2 => (user(), strategy_id(), share_bps).prop_map(|(user, strategy_id, share_bps)| {
+            Op::Request {
+                user,
+                strategy_id,
+                share_bps,
+            }
+        }),
+        2 => (user(), strategy_id(), ticket_id()).prop_map(|(user, strategy_id, ticket_id)| {
+            Op::Claim {
+                user,
+                strategy_id,
+                ticket_id,
+            }
+        }),
+        1 => (user(), strategy_id(), ticket_id()).prop_map(|(user, strategy_id, ticket_id)| {
+            Op::Cancel {
+                user,
+                strategy_id,
+                ticket_id,
+            }
+        }),
+        2 => (0..=2 * INTERVAL as i64).prop_map(|seconds| Op::Advance { seconds }),
+    ]
+}
+
+// 每个用户大概率先初始化、创建一两个合法策略并存入资金，随机操作更容易落在已有的状态上
+fn bootstrap() -> impl Strategy<Value = Vec<Op>> {
+    let user_setup = (
+        prop::bool::weighted(0.8),
+        prop::collection::vec((valid_entries(), 1..=10 * LAMPORTS_PER_SOL), 0..=2),
+    );
+    prop::collection::vec(user_setup, USERS).prop_map(|setups| {
+        let mut ops = Vec::new();
+        for (user, (initialized, strategies)) in setups.into_iter().enumerate() {
+            if !initialized {
+                continue;
+            }
+            ops.push(Op::InitUser {
+                user,
+                risk_level: 3,
+            });
+            for (strategy_id, (entries, amount)) in strategies.into_iter().enumerate() {
+                ops.push(Op::Create { user, entries });
+                ops.push(Op::Deposit {
+                    user,
+                    strategy_id: strategy_id as u64,
+                    amount,
+                });
+            }
+        }
+        ops
+    })
+}
+
+fn ops() -> impl Strategy<Value = Vec<Op>> {
+    (bootstrap(), prop::collection::vec(op(), 1..40)).prop_map(|(mut ops, random)| {
+        ops.extend(random);
+        ops
+    })
+}
+
+async fn run(ops: Vec<Op>) {
+    let mut harness = Harness::new().await;
+    for op in &ops {
+        let before = harness.strategy_counters().await;
+        harness.apply(op).await;
+        harness.check().await;
+
+        // 策略计数器只在创建成功时严格加一，其他操作不改变
+        let after = harness.strategy_counters().await;
+        for (before, after) in before.into_iter().zip(after) {
+            let valid = match (op, before, after) {
+                (_, None, None) => true,
+                (Op::InitUser { .. }, None, Some(after)) => after == 0,
+                (Op::Create { .. }, Some(before), Some(after)) => {
+                    after == before || after == before + 1
+                }
+                (_, Some(before), Some(after)) => after == before,
+                _ => false,
+            };
+            assert!(valid, "{op:?}: {before:?} -> {after:?}");
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: std::env::var("PROPTEST_CASES")
+            .ok()
+            .and_then(|cases| cases.parse().ok())
+            .unwrap_or(32),
+        // 每次缩小都要重新启动测试环境，限制次数以免失败时运行过久
+        max_shrink_iters: 64,
+        ..ProptestConfig::default()
+    })]
+
+    // 测试1: 随机操作序列下记账不变量始终成立
+    #[test]
+    fn test_accounting_invariants(ops in ops()) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(run(ops));
+    }
+}
+
+// 测试2: 固定的多用户序列，覆盖超额提款和跨用户提款
+#[tokio::test]
+async fn test_cannot_withdraw_more_than_share() {
+    let mut harness = Harness::new().await;
+    let ops = [
+        Op::InitUser {
+            user: 0,
+            risk_level: 3,
+        },
+        Op::InitUser {
+            user: 1,
+            risk_level: 3,
+        },
+        Op::Create {
+            user: 0,
+            entries: vec![(0, 0, 6000), (1, 1, 4000)],
+        },
+        Op::Create {
+            user: 0,
+            entries: vec![(0, 0, 10000)],
+        },
+        Op::Create {
+            user: 1,
+            entries: vec![(2, 1, 10000)],
+        },
+        Op::Deposit {
+            user: 0,
+            strategy_id: 0,
+            amount: 2 * LAMPORTS_PER_SOL,
+        },
+        Op::Deposit {
+            user: 0,
+            strategy_id: 1,
+            amount: LAMPORTS_PER_SOL,
+        },
+        Op::Deposit {
+            user: 1,
+            strategy_id: 0,
+            amount: 5 * LAMPORTS_PER_SOL,
+        },
+        // 用户0的总额足够，但策略1只有 1 SOL
+        Op::Withdraw {
+            user: 0,
+            strategy_id: 1,
+            share_bps: 10001,
+        },
+        Op::Steal {
+            thief: 0,
+            victim: 1,
+            strategy_id: 0,
+        },
+        Op::Withdraw {
+            user: 0,
+            strategy_id: 1,
+            share_bps: 10000,
+        },
+        Op::Withdraw {
+            user: 0,
+            strategy_id: 1,
+            share_bps: 10000,
+        },
+        Op::Deposit {
+            user: 0,
+            strategy_id: 0,
+            amount: u64::MAX,
+        },
+    ];
+    for op in &ops {
+        harness.apply(op).await;
+        harness.check().await;
+    }
+}
@@ -0,0 +1,222 @@
+// 多签金库 PDA 作为所有者: 指令由多签程序通过 CPI 签名调用
+mod common;
+
+use common::*;
+use defi_tools::{StrategyConfig, UserProfile};
+use multisig_stub::MultisigError;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::Signer;
+
+// 测试1: 多签金库初始化用户、创建策略、存取款，并授权代理人执行
+#[tokio::test]
+async fn test_multisig_owned_profile() {
+    let mut env = start().await;
+    env.init_registry().await;
+    let (multisig, authority) = env.init_multisig(2).await;
+    let admin = env.admin.insecure_clone();
+    let member = env.owner.insecure_clone();
+    let keeper = env.other.insecure_clone();
+
+    env.send_as_multisig(
+        &multisig,
+        initialize_user(&authority, 3),
+        &[&admin, &member],
+    )
+    .await
+    .unwrap();
+    let profile: UserProfile = env.account(&user_pda(&authority)).await;
+    assert_eq!(profile.owner, authority);
+
+    env.send_as_multisig(
+        &multisig,
+        create_strategy(&authority, 0, allocations(), condition(), 100),
+        &[&member, &keeper],
+    )
+    .await
+    .unwrap();
+    let before = env.raw_account(&authority).await.unwrap().lamports;
+    env.send_as_multisig(
+        &multisig,
+        deposit_funds(&authority, 0, LAMPORTS_PER_SOL, &allocations()),
+        &[&admin, &keeper],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.raw_account(&authority).await.unwrap().lamports,
+        before - LAMPORTS_PER_SOL
+    );
+    let strategy: StrategyConfig = env.account(&strategy_pda(&authority, 0)).await;
+    assert_eq!(strategy.owner, authority);
+    assert_eq!(strategy.total_value_lamports, LAMPORTS_PER_SOL);
+
+    let amount = LAMPORTS_PER_SOL / 10;
+    env.send_as_multisig(
+        &multisig,
+        withdraw_funds(&authority, 0, amount, &allocations()),
+        &[&admin, &member],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.raw_account(&authority).await.unwrap().lamports,
+        before - LAMPORTS_PER_SOL + amount
+    );
+
+    // 代理人以自己的密钥直接执行多签拥有的策略，无需多签批准
+    env.send_as_multisig(
+        &multisig,
+        add_delegate(
+            &authority,
+            0,
+            &keeper.pubkey(),
+            delegate_scope(),
+            START_TIME + 86400,
+        ),
+        &[&admin, &member],
+    )
+    .await
+    .unwrap();
+    env.send(
+        &[execute_strategy_as(&keeper.pubkey(), &authority, 0)],
+        &[&keeper],
+    )
+    .await
+    .unwrap();
+    let strategy: StrategyConfig = env.account(&strategy_pda(&authority, 0)).await;
+    assert_eq!(strategy.last_executed_at, START_TIME);
+}
+
+// 测试2: 未达到门限的交易不能执行，成员不能以个人签名操作多签的用户配置
+#[tokio::test]
+async fn test_multisig_threshold() {
+    let mut env = start().await;
+    env.init_registry().await;
+    let (multisig, authority) = env.init_multisig(2).await;
+    let admin = env.admin.insecure_clone();
+    let member = env.owner.insecure_clone();
+
+    let ix = initialize_user(&authority, 3);
+    env.send(
+        &[create_multisig_transaction(
+            &admin.pubkey(),
+            &multisig,
+            0,
+            &ix,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .send(
+            &[execute_multisig_transaction(
+                &admin.pubkey(),
+                &multisig,
+                0,
+                &ix,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, MultisigError::NotEnoughApprovals);
+    assert!(env.raw_account(&user_pda(&authority)).await.is_none());
+
+    env.send(
+        &[approve_multisig_transaction(&member.pubkey(), &multisig, 0)],
+        &[&member],
+    )
+    .await
+    .unwrap();
+    env.send(
+        &[execute_multisig_transaction(
+            &admin.pubkey(),
+            &multisig,
+            0,
+            &ix,
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    // 已执行的交易不能重放
+    let result = env
+        .send(
+            &[execute_multisig_transaction(
+                &admin.pubkey(),
+                &multisig,
+                0,
+                &ix,
+            )],
+            &[&admin],
+        )
+        .await;
+    assert_error(result, MultisigError::AlreadyExecuted);
+
+    // 成员的密钥对应自己的用户配置地址，不能签署多签的用户配置
+    let mut ix = create_strategy(&authority, 0, allocations(), condition(), 100);
+    ix.accounts[0].pubkey = member.pubkey();
+    assert!(env.send(&[ix], &[&member]).await.is_err());
+    assert!(env
+        .raw_account(&strategy_pda(&authority, 0))
+        .await
+        .is_none());
+}
+
+// 测试3: 个人密钥将用户配置转移给多签，由多签接受并迁移策略
+#[tokio::test]
+async fn test_transfer_to_multisig() {
+    let mut env = start().await;
+    env.init_strategy().await;
+    let owner = env.owner.pubkey();
+    let (multisig, authority) = env.init_multisig(2).await;
+    let admin = env.admin.insecure_clone();
+    let member = env.other.insecure_clone();
+    let previous: StrategyConfig = env.account(&strategy_pda(&owner, 0)).await;
+
+    env.send_as_owner(propose_owner(&owner, &authority, None))
+        .await
+        .unwrap();
+    env.send_as_multisig(
+        &multisig,
+        accept_owner(&authority, &owner),
+        &[&admin, &member],
+    )
+    .await
+    .unwrap();
+    env.send_as_multisig(
+        &multisig,
+        migrate_strategy(&authority, &owner, 0),
+        &[&admin, &member],
+    )
+    .await
+    .unwrap();
+
+    let profile: UserProfile = env.account(&user_pda(&owner)).await;
+    assert_eq!(profile.migrated_to, authority);
+    let accepted: UserProfile = env.account(&user_pda(&authority)).await;
+    assert_eq!(accepted.migrated_from, owner);
+    let migrated: StrategyConfig = env.account(&strategy_pda(&authority, 0)).await;
+    assert_eq!(migrated.owner, authority);
+    assert_eq!(migrated.total_value_lamports, previous.total_value_lamports);
+
+    // 原所有者的密钥不再能操作
+    let result = env
+        .send_as_owner(withdraw_funds(&owner, 0, 1, &allocations()))
+        .await;
+    assert!(result.is_err());
+
+    let before = env.raw_account(&authority).await.unwrap().lamports;
+    env.send_as_multisig(
+        &multisig,
+        withdraw_funds(&authority, 0, previous.total_value_lamports, &allocations()),
+        &[&admin, &member],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.raw_account(&authority).await.unwrap().lamports,
+        before + previous.total_value_lamports
+    );
+}
@@ -12,6 +12,18 @@ import {
   import { 
     findUserProfilePDA, 
     findStrategyPDA, 
+    findConfigPDA,
+    findAssetRegistryPDA,
+    findVaultPDA,
+    findWithdrawalTicketPDA,
+    findStrategyDelegatePDA,
+    findWithdrawalDestinationPDA,
+    findStrategyTemplatePDA,
+    findStrategyFollowPDA,
+    findDcaSchedulePDA,
+    findProtectiveOrderPDA,
+    findYieldRotationPDA,
+    findApyTrackerPDA,
     PROGRAM_ID 
   } from './utils/pda';
   import { sendAndConfirmTransactionWithRetry, waitForTransaction } from './utils/transaction';
@@ -19,9 +31,29 @@ import {
     UserProfileData, 
     StrategyConfigData, 
     PositionTrackerData,
+    AssetRegistryData,
+    GlobalConfigData,
+    WithdrawalTicketData,
+    StrategyDelegateData,
+    WithdrawalDestinationData,
+    StrategyTemplateData,
+    StrategyFollowData,
+    DcaScheduleData,
+    ProtectiveOrderData,
+    YieldRotationData,
     CreateStrategyParams,
+    PublishTemplateParams,
+    UpdateStrategyParams,
     ExecuteStrategyParams,
-    FundsOperationParams
+    FundsOperationParams,
+    WithdrawalTicketParams,
+    WithdrawParams,
+    SetSpendingLimitParams,
+    AddDelegateParams,
+    FollowStrategyParams,
+    CreateDcaParams,
+    SetProtectionParams,
+    EnableYieldRotationParams
   } from './types';
   
   // 导入IDL
@@ -35,10 +67,45 @@ import {
       };
       strategyConfig: {
         fetch(address: PublicKey): Promise<StrategyConfigData>;
+        fetchNullable(address: PublicKey): Promise<StrategyConfigData | null>;
+        all(filters?: any[]): Promise<{ publicKey: PublicKey; account: StrategyConfigData }[]>;
       };
       positionTracker: {
         fetch(address: PublicKey): Promise<PositionTrackerData>;
       };
+      assetRegistry: {
+        fetch(address: PublicKey): Promise<AssetRegistryData>;
+      };
+      globalConfig: {
+        fetch(address: PublicKey): Promise<GlobalConfigData>;
+      };
+      withdrawalTicket: {
+        fetch(address: PublicKey): Promise<WithdrawalTicketData>;
+      };
+      strategyDelegate: {
+        fetch(address: PublicKey): Promise<StrategyDelegateData>;
+      };
+      withdrawalDestination: {
+        fetch(address: PublicKey): Promise<WithdrawalDestinationData>;
+        fetchNullable(address: PublicKey): Promise<WithdrawalDestinationData | null>;
+      };
+      strategyTemplate: {
+        fetch(address: PublicKey): Promise<StrategyTemplateData>;
+        all(filters?: any[]): Promise<{ publicKey: PublicKey; account: StrategyTemplateData }[]>;
+      };
+      strategyFollow: {
+        fetch(address: PublicKey): Promise<StrategyFollowData>;
+        all(filters?: any[]): Promise<{ publicKey: PublicKey; account: StrategyFollowData }[]>;
+      };
+      dcaSchedule: {
+        fetch(address: PublicKey): Promise<DcaScheduleData>;
+      };
+      protectiveOrder: {
+        fetch(address: PublicKey): Promise<ProtectiveOrderData>;
+      };
+      yieldRotation: {
+        fetch(address: PublicKey): Promise<YieldRotationData>;
+      };
     };
   }
   
@@ -87,6 +154,7 @@ import {
             .accounts({
               owner: this.wallet.publicKey,
               userProfile: userProfilePda,
+              vault: findVaultPDA(this.wallet.publicKey)[0],
               systemProgram: SystemProgram.programId
             })
             .transaction();
@@ -129,7 +197,14 @@ import {
             userProfile: userProfilePda,
             strategyConfig: strategyPda,
             systemProgram: SystemProgram.programId
-          });
+          }).remainingAccounts(
+            // 每个分配项对应的资产注册表
+            params.allocations.map((allocation) => ({
+              pubkey: findAssetRegistryPDA(allocation.asset)[0],
+              isSigner: false,
+              isWritable: false
+            }))
+          );
       
           const tx: Transaction = await accounts.transaction(); // 第二步：显式声明类型
       
@@ -147,6 +222,55 @@ import {
         }
       }
       
+/**
+ * 更新策略的分配项和再平衡条件
+ * @param params 更新参数
+ * @returns 交易签名
+ */
+async updateStrategy(params: UpdateStrategyParams): Promise<string> {
+    try {
+      const owner = params.owner ?? this.wallet.publicKey;
+      const [userProfilePda] = findUserProfilePDA(owner);
+      const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+
+      const tx: Transaction = await (this.program.methods as any)
+        .updateStrategy(
+          new BN(params.strategyId),
+          params.allocations,
+          params.rebalanceCondition,
+          params.maxSlippageBps
+        )
+        .accounts({
+          authority: this.wallet.publicKey,
+          owner,
+          userProfile: userProfilePda,
+          strategyConfig: strategyPda,
+          delegate: this.delegateAccount(owner, strategyPda)
+        })
+        .remainingAccounts(
+          // 每个分配项对应的资产注册表
+          params.allocations.map((allocation) => ({
+            pubkey: findAssetRegistryPDA(allocation.asset)[0],
+            isSigner: false,
+            isWritable: false
+          }))
+        )
+        .transaction();
+
+      const signature = await sendAndConfirmTransactionWithRetry(
+        this.connection,
+        tx,
+        [this.wallet.payer as anchor.web3.Keypair]
+      );
+
+      await waitForTransaction(this.connection, signature);
+      return signature;
+    } catch (error) {
+      console.error("更新策略失败:", error);
+      throw error;
+    }
+  }
+
 /**
  * 执行策略
  * @param params 执行参数
@@ -154,16 +278,19 @@ import {
  */
 async executeStrategy(params: ExecuteStrategyParams): Promise<string> {
     try {
-      const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+      const owner = params.owner ?? this.wallet.publicKey;
+      const [userProfilePda] = findUserProfilePDA(owner);
       const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
   
       // 强制断言链式调用
       const tx = await (this.program.methods as any)
         .executeStrategy(new BN(params.strategyId))
         .accounts({
-          owner: this.wallet.publicKey,
+          authority: this.wallet.publicKey,
+          owner,
           userProfile: userProfilePda,
           strategyConfig: strategyPda,
+          delegate: this.delegateAccount(owner, strategyPda),
           systemProgram: SystemProgram.programId
         })
         .transaction();
@@ -191,19 +318,28 @@ async executeStrategy(params: ExecuteStrategyParams): Promise<string> {
     try {
       const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
       const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+      const [configPda] = findConfigPDA();
+      const treasury = await this.getTreasury();
+      const assetAccounts = await this.getAssetAccounts(userProfilePda, params.strategyId);
   
       // 双断言策略：强制方法链类型 + 最终交易类型
       const tx = await (this.program.methods as unknown as {
         depositFunds: (...args: any[]) => {
-          accounts: (...args: any[]) => { transaction: () => Promise<Transaction> }
+          accounts: (...args: any[]) => {
+            remainingAccounts: (...args: any[]) => { transaction: () => Promise<Transaction> }
+          }
         }
       }).depositFunds(new BN(params.strategyId), new BN(params.amount))
         .accounts({
           owner: this.wallet.publicKey,
           userProfile: userProfilePda,
           strategyConfig: strategyPda,
+          vault: findVaultPDA(this.wallet.publicKey)[0],
+          config: configPda,
+          treasury,
           systemProgram: SystemProgram.programId
         })
+        .remainingAccounts(assetAccounts)
         .transaction();
   
       const signature = await sendAndConfirmTransactionWithRetry(
@@ -225,29 +361,42 @@ async executeStrategy(params: ExecuteStrategyParams): Promise<string> {
      * @param params 提款参数
      * @returns 交易签名
      */
-    async withdrawFunds(params: FundsOperationParams): Promise<string> {
+    async withdrawFunds(params: FundsOperationParams & WithdrawParams): Promise<string> {
         try {
+        const destination = params.destination ?? this.wallet.publicKey;
         const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
         const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+        const [configPda] = findConfigPDA();
+        const treasury = await this.getTreasury();
+        const assetAccounts = await this.getDepositAccounts(userProfilePda, params.strategyId);
     
         // 双断言策略：强制方法链类型 + 最终交易类型
         const tx = await (this.program.methods as unknown as {
             withdrawFunds: (...args: any[]) => {
-            accounts: (...args: any[]) => { transaction: () => Promise<Transaction> };
+            accounts: (...args: any[]) => {
+              remainingAccounts: (...args: any[]) => { transaction: () => Promise<Transaction> };
+            };
             };
         }).withdrawFunds(new BN(params.strategyId), new BN(params.amount))
             .accounts({
             owner: this.wallet.publicKey,
             userProfile: userProfilePda,
             strategyConfig: strategyPda,
+            vault: findVaultPDA(this.wallet.publicKey)[0],
+            config: configPda,
+            treasury,
+            destination,
+            withdrawalDestination: await this.destinationAccount(destination),
+            guardian: params.guardian?.publicKey ?? null,
             systemProgram: SystemProgram.programId,
             })
+            .remainingAccounts(assetAccounts)
             .transaction();
     
         const signature = await sendAndConfirmTransactionWithRetry(
             this.connection,
             tx,
-            [this.wallet.payer as anchor.web3.Keypair]
+            this.signersWith(params.guardian)
         );
     
         await waitForTransaction(this.connection, signature);
@@ -258,28 +407,195 @@ async executeStrategy(params: ExecuteStrategyParams): Promise<string> {
         }
     }
   
+    /**
+     * 申请排队提款，金额锁定在新工单中，头寸解除或锁定到期后领取
+     * @param params 提款参数
+     * @returns 交易签名
+     */
+    async requestWithdrawal(params: FundsOperationParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+        const [configPda] = findConfigPDA();
+        const treasury = await this.getTreasury();
+        const strategy = await this.getStrategyConfig(userProfilePda, params.strategyId);
+        const [ticketPda] = findWithdrawalTicketPDA(
+            strategyPda,
+            strategy.withdrawalQueue.nextTicketId.toNumber()
+        );
+
+        const tx: Transaction = await (this.program.methods as any)
+            .requestWithdrawal(new BN(params.strategyId), new BN(params.amount))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            withdrawalTicket: ticketPda,
+            vault: findVaultPDA(this.wallet.publicKey)[0],
+            config: configPda,
+            treasury,
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("申请提款失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 领取提款工单，队首到该工单之间已取消的工单地址一并提供
+     * @param params 工单参数
+     * @returns 交易签名
+     */
+    async claimWithdrawal(params: WithdrawalTicketParams & WithdrawParams): Promise<string> {
+        try {
+        const destination = params.destination ?? this.wallet.publicKey;
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+        const strategy = await this.getStrategyConfig(userProfilePda, params.strategyId);
+        const assetAccounts = await this.getDepositAccounts(userProfilePda, params.strategyId);
+        const skipped = [];
+        for (let id = strategy.withdrawalQueue.headTicketId.toNumber(); id < params.ticketId; id++) {
+          skipped.push({
+            pubkey: findWithdrawalTicketPDA(strategyPda, id)[0],
+            isSigner: false,
+            isWritable: false
+          });
+        }
+
+        const tx: Transaction = await (this.program.methods as any)
+            .claimWithdrawal(new BN(params.strategyId), new BN(params.ticketId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            withdrawalTicket: findWithdrawalTicketPDA(strategyPda, params.ticketId)[0],
+            vault: findVaultPDA(this.wallet.publicKey)[0],
+            destination,
+            withdrawalDestination: await this.destinationAccount(destination),
+            guardian: params.guardian?.publicKey ?? null,
+            systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts([...assetAccounts, ...skipped])
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            this.signersWith(params.guardian)
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("领取提款失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 取消提款工单，锁定金额退回策略
+     * @param params 工单参数
+     * @returns 交易签名
+     */
+    async cancelWithdrawal(params: WithdrawalTicketParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+        const [configPda] = findConfigPDA();
+        const treasury = await this.getTreasury();
+
+        const tx: Transaction = await (this.program.methods as any)
+            .cancelWithdrawal(new BN(params.strategyId), new BN(params.ticketId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            withdrawalTicket: findWithdrawalTicketPDA(strategyPda, params.ticketId)[0],
+            vault: findVaultPDA(this.wallet.publicKey)[0],
+            config: configPda,
+            treasury,
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("取消提款失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取提款工单数据
+     * @param userProfilePda 用户配置PDA
+     * @param strategyId 策略ID
+     * @param ticketId 工单ID
+     * @returns 工单数据
+     */
+    async getWithdrawalTicket(
+      userProfilePda: PublicKey,
+      strategyId: number,
+      ticketId: number
+    ): Promise<WithdrawalTicketData> {
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        const [ticketPda] = findWithdrawalTicketPDA(strategyPda, ticketId);
+        return this.program.account.withdrawalTicket.fetch(ticketPda);
+    }
+  
     /**
      * 再平衡头寸
      * @param strategyId 策略ID
+     * @param owner 以代理人身份操作时的策略所有者，默认为当前钱包
      * @returns 交易签名
      */
-    async rebalancePositions(strategyId: number): Promise<string> {
+    async rebalancePositions(strategyId: number, owner: PublicKey = this.wallet.publicKey): Promise<string> {
         try {
-        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [userProfilePda] = findUserProfilePDA(owner);
         const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        const [configPda] = findConfigPDA();
+        const priceAccounts = await this.getPriceAccounts(userProfilePda, strategyId);
+        const treasury = await this.getTreasury();
     
         // 双断言策略：强制方法链类型 + 最终交易类型
         const tx = await (this.program.methods as unknown as {
             rebalancePositions: (...args: any[]) => {
-            accounts: (...args: any[]) => { transaction: () => Promise<Transaction> };
+            accounts: (...args: any[]) => {
+              remainingAccounts: (...args: any[]) => { transaction: () => Promise<Transaction> };
+            };
             };
         }).rebalancePositions(new BN(strategyId))
             .accounts({
-            owner: this.wallet.publicKey,
+            authority: this.wallet.publicKey,
+            owner,
             userProfile: userProfilePda,
             strategyConfig: strategyPda,
+            delegate: this.delegateAccount(owner, strategyPda),
+            vault: findVaultPDA(owner)[0],
+            config: configPda,
+            treasury,
             systemProgram: SystemProgram.programId,
             })
+            .remainingAccounts(
+              priceAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+            )
             .transaction();
     
         const signature = await sendAndConfirmTransactionWithRetry(
@@ -296,6 +612,1216 @@ async executeStrategy(params: ExecuteStrategyParams): Promise<string> {
         }
     }
   
+    /**
+     * 授权代理人执行、再平衡或在限定范围内更新策略
+     * @param params 代理人参数
+     * @returns 交易签名
+     */
+    async addDelegate(params: AddDelegateParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .addDelegate(
+              new BN(params.strategyId),
+              params.delegate,
+              params.scope,
+              new BN(params.expiresAt)
+            )
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            strategyDelegate: findStrategyDelegatePDA(strategyPda, params.delegate)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("添加代理人失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 撤销代理人，代理人账户的租金退回所有者
+     * @param strategyId 策略ID
+     * @param delegate 代理人地址
+     * @returns 交易签名
+     */
+    async revokeDelegate(strategyId: number, delegate: PublicKey): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .revokeDelegate(new BN(strategyId), delegate)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            strategyDelegate: findStrategyDelegatePDA(strategyPda, delegate)[0],
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("撤销代理人失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取策略代理人数据
+     * @param userProfilePda 用户配置PDA
+     * @param strategyId 策略ID
+     * @param delegate 代理人地址
+     * @returns 代理人数据
+     */
+    async getStrategyDelegate(
+      userProfilePda: PublicKey,
+      strategyId: number,
+      delegate: PublicKey
+    ): Promise<StrategyDelegateData> {
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        const [delegatePda] = findStrategyDelegatePDA(strategyPda, delegate);
+        return this.program.account.strategyDelegate.fetch(delegatePda);
+    }
+
+    /**
+     * 当前钱包不是策略所有者时使用其代理人账户，否则不传代理人账户
+     * @param owner 策略所有者
+     * @param strategyPda 策略配置PDA
+     * @returns 代理人PDA或null
+     */
+    private delegateAccount(owner: PublicKey, strategyPda: PublicKey): PublicKey | null {
+        if (owner.equals(this.wallet.publicKey)) {
+          return null;
+        }
+        return findStrategyDelegatePDA(strategyPda, this.wallet.publicKey)[0];
+    }
+
+    /**
+     * 启用或关闭提款白名单。启用立即生效，关闭在生效延迟之后生效
+     * @param enabled 是否启用
+     * @returns 交易签名
+     */
+    async setWithdrawalAllowlist(enabled: boolean): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [configPda] = findConfigPDA();
+
+        const tx: Transaction = await (this.program.methods as any)
+            .setWithdrawalAllowlist(enabled)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            config: configPda,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("设置提款白名单失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 设置周期提款限额，启用限额时必须指定监护人。放宽限额或更换监护人需要当前监护人联署
+     * @param params 限额参数
+     * @returns 交易签名
+     */
+    async setSpendingLimit(params: SetSpendingLimitParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .setSpendingLimit(new BN(params.maxLamports), params.windowSeconds, params.guardian)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            guardian: params.currentGuardian?.publicKey ?? null,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            this.signersWith(params.currentGuardian)
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("设置提款限额失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 提名新所有者，传入默认公钥时取消提名。设置了监护人时需要监护人联署
+     * @param newOwner 新所有者
+     * @param guardian 联署的监护人
+     * @returns 交易签名
+     */
+    async proposeOwner(newOwner: PublicKey, guardian?: anchor.web3.Keypair): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [configPda] = findConfigPDA();
+
+        const tx: Transaction = await (this.program.methods as any)
+            .proposeOwner(newOwner)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            config: configPda,
+            guardian: guardian?.publicKey ?? null,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            this.signersWith(guardian)
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("提名新所有者失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 以当前钱包接受所有权转移，创建继承原设置的用户配置
+     * @param previousOwner 原所有者
+     * @returns 交易签名
+     */
+    async acceptOwner(previousOwner: PublicKey): Promise<string> {
+        try {
+        const tx: Transaction = await (this.program.methods as any)
+            .acceptOwner(previousOwner)
+            .accounts({
+            owner: this.wallet.publicKey,
+            previousProfile: findUserProfilePDA(previousOwner)[0],
+            userProfile: findUserProfilePDA(this.wallet.publicKey)[0],
+            vault: findVaultPDA(this.wallet.publicKey)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("接受所有权转移失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 将原所有者的策略及其资金迁移到当前钱包名下，策略ID保持不变。
+     * 未领取的提款工单需先取消
+     * @param previousOwner 原所有者
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async migrateStrategy(previousOwner: PublicKey, strategyId: number): Promise<string> {
+        try {
+        const [previousProfilePda] = findUserProfilePDA(previousOwner);
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [previousStrategyPda] = findStrategyPDA(previousProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .migrateStrategy(previousOwner, new BN(strategyId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            previousProfile: previousProfilePda,
+            userProfile: userProfilePda,
+            previousStrategy: previousStrategyPda,
+            previousProtection: findProtectiveOrderPDA(previousStrategyPda)[0],
+            strategyConfig: findStrategyPDA(userProfilePda, strategyId)[0],
+            previousVault: findVaultPDA(previousOwner)[0],
+            vault: findVaultPDA(this.wallet.publicKey)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("迁移策略失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 以当前钱包发布策略模板，模板ID不能与已发布的模板重复
+     * @param params 模板参数
+     * @returns 交易签名
+     */
+    async publishTemplate(params: PublishTemplateParams): Promise<string> {
+        try {
+        const tx: Transaction = await (this.program.methods as any)
+            .publishTemplate(
+            new BN(params.templateId),
+            params.allocations,
+            params.rebalanceCondition,
+            params.maxSlippageBps,
+            params.riskLevel,
+            params.descriptionHash
+            )
+            .accounts({
+            publisher: this.wallet.publicKey,
+            strategyTemplate: findStrategyTemplatePDA(this.wallet.publicKey, params.templateId)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(
+            params.allocations.map((allocation) => ({
+                pubkey: findAssetRegistryPDA(allocation.asset)[0],
+                isSigner: false,
+                isWritable: false
+            }))
+            )
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("发布策略模板失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 停用并关闭当前钱包发布的模板，已创建的策略不受影响
+     * @param templateId 模板ID
+     * @returns 交易签名
+     */
+    async retireTemplate(templateId: number): Promise<string> {
+        try {
+        const tx: Transaction = await (this.program.methods as any)
+            .retireTemplate(new BN(templateId))
+            .accounts({
+            publisher: this.wallet.publicKey,
+            strategyTemplate: findStrategyTemplatePDA(this.wallet.publicKey, templateId)[0],
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("停用策略模板失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 按模板创建策略，复制模板的参数。用户的风险等级不能低于模板的风险等级
+     * @param publisher 模板发布者
+     * @param templateId 模板ID
+     * @returns 交易签名
+     */
+    async createStrategyFromTemplate(publisher: PublicKey, templateId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const userProfile = await this.getUserProfile(this.wallet.publicKey);
+        const [templatePda] = findStrategyTemplatePDA(publisher, templateId);
+        const template = await this.program.account.strategyTemplate.fetch(templatePda);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .createStrategyFromTemplate()
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyTemplate: templatePda,
+            strategyConfig: findStrategyPDA(userProfilePda, userProfile.strategyCounter)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(
+            // 按模板的分配项重新校验资产
+            template.allocations.map((allocation) => ({
+                pubkey: findAssetRegistryPDA(allocation.asset)[0],
+                isSigner: false,
+                isWritable: false
+            }))
+            )
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("按模板创建策略失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取策略模板数据
+     * @param publisher 发布者地址
+     * @param templateId 模板ID
+     * @returns 模板数据
+     */
+    async getStrategyTemplate(publisher: PublicKey, templateId: number): Promise<StrategyTemplateData> {
+        const [templatePda] = findStrategyTemplatePDA(publisher, templateId);
+        return this.program.account.strategyTemplate.fetch(templatePda);
+    }
+
+    /**
+     * 列出策略模板
+     * @param publisher 只列出该发布者的模板
+     * @returns 模板地址和数据
+     */
+    async getTemplates(
+      publisher?: PublicKey
+    ): Promise<{ publicKey: PublicKey; account: StrategyTemplateData }[]> {
+        // 发布者紧随账户判别符之后
+        const filters = publisher
+          ? [{ memcmp: { offset: 8, bytes: publisher.toBase58() } }]
+          : [];
+        return this.program.account.strategyTemplate.all(filters);
+    }
+
+    /**
+     * 让当前钱包的策略跟随领投策略，之后由同步操作复制领投策略的分配项
+     * @param params 跟随参数
+     * @returns 交易签名
+     */
+    async followStrategy(params: FollowStrategyParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+        const [leaderProfilePda] = findUserProfilePDA(params.leaderOwner);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .followStrategy(
+            new BN(params.strategyId),
+            params.maxWeightBps,
+            params.delaySeconds,
+            params.leaderFeeBps
+            )
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            leaderStrategy: findStrategyPDA(leaderProfilePda, params.leaderStrategyId)[0],
+            strategyFollow: findStrategyFollowPDA(strategyPda)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("跟随策略失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 停止跟随并关闭跟投关系，租金退回当前钱包
+     * @param strategyId 跟随策略ID
+     * @returns 交易签名
+     */
+    async unfollowStrategy(strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .unfollowStrategy(new BN(strategyId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            strategyFollow: findStrategyFollowPDA(strategyPda)[0],
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("取消跟随失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 将领投策略的分配项同步到跟随策略，任何钱包都可以发起。
+     * 按时间计提的领投费从跟随者金库支付给领投策略所有者，领投者已加入跟随者白名单时附加白名单账户
+     * @param owner 跟随策略所有者
+     * @param strategyId 跟随策略ID
+     * @returns 交易签名
+     */
+    async syncFollower(owner: PublicKey, strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        const [followPda] = findStrategyFollowPDA(strategyPda);
+        const follow = await this.program.account.strategyFollow.fetch(followPda);
+        const { publicKey: leaderStrategy, account: leader } = await this.findLeaderStrategy(
+            follow.leader
+        );
+        const treasury = await this.getTreasury();
+        const [entryPda] = findWithdrawalDestinationPDA(owner, leader.owner);
+        const entry = await this.program.account.withdrawalDestination.fetchNullable(entryPda);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .syncFollower()
+            .accounts({
+            authority: this.wallet.publicKey,
+            strategyFollow: followPda,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            leaderStrategy,
+            leaderOwner: leader.owner,
+            withdrawalDestination: entry ? entryPda : null,
+            vault: findVaultPDA(owner)[0],
+            config: findConfigPDA()[0],
+            treasury,
+            systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(
+            // 按领投策略当前的分配项校验资产
+            leader.allocations.map((allocation) => ({
+                pubkey: findAssetRegistryPDA(allocation.asset)[0],
+                isSigner: false,
+                isWritable: false
+            }))
+            )
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("同步跟随策略失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取策略的跟投关系
+     * @param owner 跟随策略所有者
+     * @param strategyId 跟随策略ID
+     * @returns 跟投关系数据
+     */
+    async getStrategyFollow(owner: PublicKey, strategyId: number): Promise<StrategyFollowData> {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        return this.program.account.strategyFollow.fetch(findStrategyFollowPDA(strategyPda)[0]);
+    }
+
+    /**
+     * 列出领投策略的跟随者
+     * @param leader 领投策略的原始地址，未迁移过的策略即当前地址
+     * @returns 跟投关系地址和数据
+     */
+    async getFollowers(
+      leader: PublicKey
+    ): Promise<{ publicKey: PublicKey; account: StrategyFollowData }[]> {
+        // 领投策略位于判别符、所有者和跟随策略之后
+        return this.program.account.strategyFollow.all([
+          { memcmp: { offset: 8 + 32 + 32, bytes: leader.toBase58() } }
+        ]);
+    }
+
+    /**
+     * 为当前钱包的策略创建定投计划，每个再平衡间隔把固定价值从报价分配项转移到目标分配项
+     * @param params 定投参数
+     * @returns 交易签名
+     */
+    async createDca(params: CreateDcaParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .createDca(new BN(params.strategyId), params.params)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            dcaSchedule: findDcaSchedulePDA(strategyPda)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("创建定投计划失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 取消定投计划，已转移的分配项保持不变，租金退回当前钱包
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async cancelDca(strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .cancelDca(new BN(strategyId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            dcaSchedule: findDcaSchedulePDA(strategyPda)[0],
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("取消定投计划失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 执行一次到期的定投，任何钱包都可以发起
+     * @param owner 策略所有者
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async executeDca(owner: PublicKey, strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        const [schedulePda] = findDcaSchedulePDA(strategyPda);
+        const schedule = await this.program.account.dcaSchedule.fetch(schedulePda);
+        // 依次为报价资产和目标资产的价格账户
+        const priceAccounts = await this.getAssetPriceAccounts([
+          schedule.params.quoteAsset,
+          schedule.params.targetAsset
+        ]);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .executeDca()
+            .accounts({
+            authority: this.wallet.publicKey,
+            dcaSchedule: schedulePda,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            config: findConfigPDA()[0],
+            })
+            .remainingAccounts(
+              priceAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+            )
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("执行定投失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取策略的定投计划
+     * @param owner 策略所有者
+     * @param strategyId 策略ID
+     * @returns 定投计划数据
+     */
+    async getDcaSchedule(owner: PublicKey, strategyId: number): Promise<DcaScheduleData> {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        return this.program.account.dcaSchedule.fetch(findDcaSchedulePDA(strategyPda)[0]);
+    }
+
+    /**
+     * 为当前钱包的策略设置止损止盈保护单，按策略当前的分配项记录价格
+     * @param params 保护单参数
+     * @returns 交易签名
+     */
+    async setProtection(params: SetProtectionParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+        const priceAccounts = await this.getPriceAccounts(userProfilePda, params.strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .setProtection(new BN(params.strategyId), params.params)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            protectiveOrder: findProtectiveOrderPDA(strategyPda)[0],
+            safeAsset: findAssetRegistryPDA(params.params.safeAsset)[0],
+            config: findConfigPDA()[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(
+              priceAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+            )
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("设置保护单失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 取消保护单，租金退回当前钱包
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async cancelProtection(strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .cancelProtection(new BN(strategyId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            protectiveOrder: findProtectiveOrderPDA(strategyPda)[0],
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("取消保护单失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 按预言机价格重新估值，任何钱包都可以发起。满足触发条件时策略转入安全头寸并暂停
+     * @param owner 策略所有者
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async checkProtection(owner: PublicKey, strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        const priceAccounts = await this.getPriceAccounts(userProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .checkProtection()
+            .accounts({
+            authority: this.wallet.publicKey,
+            protectiveOrder: findProtectiveOrderPDA(strategyPda)[0],
+            owner,
+            strategyConfig: strategyPda,
+            config: findConfigPDA()[0],
+            })
+            .remainingAccounts(
+              priceAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+            )
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("检查保护单失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 恢复被保护单暂停的策略，恢复前可先更新分配项
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async resumeStrategy(strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .resumeStrategy(new BN(strategyId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("恢复策略失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取策略的保护单
+     * @param owner 策略所有者
+     * @param strategyId 策略ID
+     * @returns 保护单数据
+     */
+    async getProtectiveOrder(owner: PublicKey, strategyId: number): Promise<ProtectiveOrderData> {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        return this.program.account.protectiveOrder.fetch(findProtectiveOrderPDA(strategyPda)[0]);
+    }
+
+    /**
+     * 为当前钱包的策略启用收益率轮动，轮动头寸移动到APY最高的候选借贷协议
+     * @param params 轮动参数
+     * @returns 交易签名
+     */
+    async enableYieldRotation(params: EnableYieldRotationParams): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, params.strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .enableYieldRotation(new BN(params.strategyId), params.params)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            yieldRotation: findYieldRotationPDA(strategyPda)[0],
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("启用收益率轮动失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 停用收益率轮动，轮动头寸保留在当前协议，租金退回当前钱包
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async disableYieldRotation(strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+
+        const tx: Transaction = await (this.program.methods as any)
+            .disableYieldRotation(new BN(strategyId))
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            yieldRotation: findYieldRotationPDA(strategyPda)[0],
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("停用收益率轮动失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 将轮动头寸移到APY最高的候选协议，任何钱包都可以发起
+     * @param owner 策略所有者
+     * @param strategyId 策略ID
+     * @returns 交易签名
+     */
+    async rotateYield(owner: PublicKey, strategyId: number): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        const [yieldRotationPda] = findYieldRotationPDA(strategyPda);
+        const rotation = await this.program.account.yieldRotation.fetch(yieldRotationPda);
+        const { asset, candidateProtocols } = rotation.params;
+
+        // 按协议编号顺序提供每个候选协议的利率追踪账户
+        const trackers: PublicKey[] = [];
+        for (let protocol = 0; protocol < 8; protocol++) {
+            if (candidateProtocols & (1 << protocol)) {
+            trackers.push(findApyTrackerPDA(protocol, asset)[0]);
+            }
+        }
+
+        const tx: Transaction = await (this.program.methods as any)
+            .rotateYield()
+            .accounts({
+            authority: this.wallet.publicKey,
+            yieldRotation: yieldRotationPda,
+            userProfile: userProfilePda,
+            strategyConfig: strategyPda,
+            })
+            .remainingAccounts(
+              trackers.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+            )
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("收益率轮动失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取策略的收益率轮动计划
+     * @param owner 策略所有者
+     * @param strategyId 策略ID
+     * @returns 轮动计划数据
+     */
+    async getYieldRotation(owner: PublicKey, strategyId: number): Promise<YieldRotationData> {
+        const [userProfilePda] = findUserProfilePDA(owner);
+        const [strategyPda] = findStrategyPDA(userProfilePda, strategyId);
+        return this.program.account.yieldRotation.fetch(findYieldRotationPDA(strategyPda)[0]);
+    }
+
+    /**
+     * 获取管理员报告的协议和资产的市场利率
+     * @param protocol 协议编号
+     * @param asset 资产ID
+     * @returns 利率追踪数据
+     */
+    async getApyTracker(protocol: number, asset: number): Promise<PositionTrackerData> {
+        return this.program.account.positionTracker.fetch(findApyTrackerPDA(protocol, asset)[0]);
+    }
+
+    /**
+     * 添加提款白名单地址，在生效延迟之后才能接收提款
+     * @param destination 接收地址
+     * @returns 交易签名
+     */
+    async addWithdrawalDestination(destination: PublicKey): Promise<string> {
+        try {
+        const [userProfilePda] = findUserProfilePDA(this.wallet.publicKey);
+        const [configPda] = findConfigPDA();
+
+        const tx: Transaction = await (this.program.methods as any)
+            .addWithdrawalDestination(destination)
+            .accounts({
+            owner: this.wallet.publicKey,
+            userProfile: userProfilePda,
+            withdrawalDestination: findWithdrawalDestinationPDA(this.wallet.publicKey, destination)[0],
+            vault: findVaultPDA(this.wallet.publicKey)[0],
+            config: configPda,
+            systemProgram: SystemProgram.programId,
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("添加提款白名单地址失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 移除提款白名单地址，立即生效，账户租金退回所有者
+     * @param destination 接收地址
+     * @returns 交易签名
+     */
+    async removeWithdrawalDestination(destination: PublicKey): Promise<string> {
+        try {
+        const tx: Transaction = await (this.program.methods as any)
+            .removeWithdrawalDestination(destination)
+            .accounts({
+            owner: this.wallet.publicKey,
+            withdrawalDestination: findWithdrawalDestinationPDA(this.wallet.publicKey, destination)[0],
+            })
+            .transaction();
+
+        const signature = await sendAndConfirmTransactionWithRetry(
+            this.connection,
+            tx,
+            [this.wallet.payer as anchor.web3.Keypair]
+        );
+
+        await waitForTransaction(this.connection, signature);
+        return signature;
+        } catch (error) {
+        console.error("移除提款白名单地址失败:", error);
+        throw error;
+        }
+    }
+
+    /**
+     * 获取提款白名单地址数据
+     * @param owner 用户地址
+     * @param destination 接收地址
+     * @returns 白名单地址数据
+     */
+    async getWithdrawalDestination(
+      owner: PublicKey,
+      destination: PublicKey
+    ): Promise<WithdrawalDestinationData> {
+        const [entryPda] = findWithdrawalDestinationPDA(owner, destination);
+        return this.program.account.withdrawalDestination.fetch(entryPda);
+    }
+
+    /**
+     * 接收地址已加入当前钱包的白名单时使用其白名单账户，否则不传
+     * @param destination 接收地址
+     * @returns 白名单账户PDA或null
+     */
+    private async destinationAccount(destination: PublicKey): Promise<PublicKey | null> {
+        if (destination.equals(this.wallet.publicKey)) {
+          return null;
+        }
+        const [entryPda] = findWithdrawalDestinationPDA(this.wallet.publicKey, destination);
+        const entry = await this.program.account.withdrawalDestination.fetchNullable(entryPda);
+        return entry ? entryPda : null;
+    }
+
+    /**
+     * 按跟投关系记录的原始地址查找领投策略。领投策略迁移后原账户已关闭，
+     * 需在全部策略中查找原始地址相同的策略
+     * @param origin 领投策略的原始地址
+     * @returns 领投策略当前的地址和数据
+     */
+    private async findLeaderStrategy(
+      origin: PublicKey
+    ): Promise<{ publicKey: PublicKey; account: StrategyConfigData }> {
+        const leader = await this.program.account.strategyConfig.fetchNullable(origin);
+        if (leader) {
+          return { publicKey: origin, account: leader };
+        }
+        const strategies = await this.program.account.strategyConfig.all();
+        const migrated = strategies.find(({ account }) => account.origin.equals(origin));
+        if (!migrated) {
+          throw new Error(`领投策略不存在: ${origin.toBase58()}`);
+        }
+        return migrated;
+    }
+
+    /**
+     * 当前钱包和可选的联署密钥
+     * @param guardian 联署的监护人
+     * @returns 交易签名者
+     */
+    private signersWith(guardian?: anchor.web3.Keypair): anchor.web3.Keypair[] {
+        const signers = [this.wallet.payer as anchor.web3.Keypair];
+        return guardian ? [...signers, guardian] : signers;
+    }
+
+    /**
+     * 读取全局配置中的费用金库地址，存取款和再平衡计提的费用转入该账户
+     * @returns 费用金库地址
+     */
+    async getTreasury(): Promise<PublicKey> {
+        const [configPda] = findConfigPDA();
+        const config = await this.program.account.globalConfig.fetch(configPda);
+        return config.treasury;
+    }
+
+    /**
+     * 按分配项顺序组装存款所需的可写资产注册表账户，用于检查和累计资产存款上限
+     * @param userProfilePda 用户配置PDA
+     * @param strategyId 策略ID
+     * @returns 账户元数据列表
+     */
+    async getAssetAccounts(
+      userProfilePda: PublicKey,
+      strategyId: number
+    ): Promise<{ pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[]> {
+        const strategy = await this.getStrategyConfig(userProfilePda, strategyId);
+        return strategy.allocations.map((allocation) => ({
+          pubkey: findAssetRegistryPDA(allocation.asset)[0],
+          isSigner: false,
+          isWritable: true
+        }));
+    }
+
+    /**
+     * 按策略的存款记录顺序组装提款和领取工单所需的可写资产注册表账户
+     * @param userProfilePda 用户配置PDA
+     * @param strategyId 策略ID
+     * @returns 账户元数据列表
+     */
+    async getDepositAccounts(
+      userProfilePda: PublicKey,
+      strategyId: number
+    ): Promise<{ pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[]> {
+        const strategy = await this.getStrategyConfig(userProfilePda, strategyId);
+        return strategy.assetDeposits.map((deposit) => ({
+          pubkey: findAssetRegistryPDA(deposit.asset)[0],
+          isSigner: false,
+          isWritable: true
+        }));
+    }
+
+    /**
+     * 按分配项顺序组装再平衡所需的 [资产注册表, 主价格源, (备用价格源)] 账户
+     * @param userProfilePda 用户配置PDA
+     * @param strategyId 策略ID
+     * @returns 账户地址列表
+     */
+    async getPriceAccounts(userProfilePda: PublicKey, strategyId: number): Promise<PublicKey[]> {
+        const strategy = await this.getStrategyConfig(userProfilePda, strategyId);
+        return this.getAssetPriceAccounts(strategy.allocations.map((allocation) => allocation.asset));
+    }
+
+    private async getAssetPriceAccounts(assets: number[]): Promise<PublicKey[]> {
+        const [configPda] = findConfigPDA();
+        const config = await this.program.account.globalConfig.fetch(configPda);
+        const crossCheck = config.oracleGuard.maxDivergenceBps > 0;
+
+        const accounts: PublicKey[] = [];
+        for (const asset of assets) {
+          const [assetPda] = findAssetRegistryPDA(asset);
+          const registry = await this.program.account.assetRegistry.fetch(assetPda);
+          accounts.push(assetPda, registry.oracle);
+          if (crossCheck) {
+            accounts.push(registry.secondaryOracle);
+          }
+        }
+        return accounts;
+    }
+
     /**
      * 获取用户配置数据
      * @param ownerAddress 用户地址
@@ -313,7 +1839,9 @@ async executeStrategy(params: ExecuteStrategyParams): Promise<string> {
             vaultBump: userProfile.vaultBump,
             lastActivity: userProfile.lastActivity,
             totalValueLamports: userProfile.totalValueLamports,
-            isPaused: userProfile.isPaused
+            isPaused: userProfile.isPaused,
+            allowlistEnabled: userProfile.allowlistEnabled,
+            allowlistDisableAt: userProfile.allowlistDisableAt
         };
         } catch (error) {
         console.error("获取用户配置失败:", error);
@@ -347,7 +1875,11 @@ async executeStrategy(params: ExecuteStrategyParams): Promise<string> {
             },
             createdAt: strategyConfig.createdAt,
             lastExecutedAt: strategyConfig.lastExecutedAt,
-            maxSlippageBps: strategyConfig.maxSlippageBps
+            maxSlippageBps: strategyConfig.maxSlippageBps,
+            totalValueLamports: strategyConfig.totalValueLamports,
+            feesAccruedAt: strategyConfig.feesAccruedAt,
+            withdrawalQueue: strategyConfig.withdrawalQueue,
+            allocationsUpdatedAt: strategyConfig.allocationsUpdatedAt
         };
         } catch (error) {
         console.error("获取策略配置失败:", error);
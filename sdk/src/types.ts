@@ -1,4 +1,4 @@
-import { PublicKey } from '@solana/web3.js';
+import { Keypair, PublicKey } from '@solana/web3.js';
 import BN from 'bn.js';
 
 // 协议类型枚举
@@ -9,7 +9,7 @@ export enum ProtocolType {
   Mango = 3,
 }
 
-// 资产类型枚举
+// 默认资产ID，实际可用资产以链上资产注册表为准
 export enum AssetType {
   SOL = 0,
   USDC = 1,
@@ -39,6 +39,28 @@ export interface UserProfileData {
   lastActivity: BN;
   totalValueLamports: BN;
   isPaused: boolean;
+  // 启用后提款只能转入本人或已生效的白名单地址
+  allowlistEnabled: boolean;
+  // 白名单关闭的生效时间，0表示没有待生效的关闭
+  allowlistDisableAt: BN;
+  spendingLimit: SpendingLimitData;
+  // 已提名但尚未接受的新所有者及可接受的时间，默认公钥表示没有提名
+  pendingOwner: PublicKey;
+  pendingOwnerAt: BN;
+  // 所有权转移的去向和来源，设置 migratedTo 后该配置只能迁移策略
+  migratedTo: PublicKey;
+  migratedFrom: PublicKey;
+}
+
+// 周期提款限额，maxLamports 为0表示不限制
+export interface SpendingLimitData {
+  maxLamports: BN;
+  windowSeconds: number;
+  // 当前窗口的开始时间和已转出金额
+  windowStart: BN;
+  spentLamports: BN;
+  // 监护人，默认公钥表示未设置
+  guardian: PublicKey;
 }
 
 // 策略配置数据
@@ -50,6 +72,198 @@ export interface StrategyConfigData {
   createdAt: BN;
   lastExecutedAt: BN;
   maxSlippageBps: number;
+  totalValueLamports: BN;
+  feesAccruedAt: BN;
+  withdrawalQueue: WithdrawalQueue;
+  // 分配项最后一次变化的时间戳，跟随者按此判断是否需要同步
+  allocationsUpdatedAt: BN;
+  // 保护单估值的峰值，没有保护单时为0
+  peakValueLamports: BN;
+  // 保护单触发后暂停的时间戳，0表示未暂停
+  pausedAt: BN;
+  // 计入各资产累计存款的金额，提款按比例扣回
+  assetDeposits: AssetDeposit[];
+  // 自动调整分配项的模式，同一时间只能启用一种
+  mode: StrategyMode;
+  // 未撤销的代理人授权数量
+  delegates: number;
+  // 经过迁移的策略最初的地址，默认公钥表示未迁移过，跟投关系以该地址标识领投策略
+  origin: PublicKey;
+  // 保护单触发后尚未按安全头寸再平衡，此时暂停的策略仍可再平衡一次
+  unwindPending: boolean;
+}
+
+// 策略的自动调整模式，跟随、定投和收益率轮动互斥
+export type StrategyMode = { manual: {} } | { following: {} } | { dca: {} } | { yieldRotation: {} };
+
+// 策略计入某个资产累计存款的金额
+export interface AssetDeposit {
+  asset: number;
+  lamports: BN;
+}
+
+// 策略的提款队列，工单按ID先后领取
+export interface WithdrawalQueue {
+  nextTicketId: BN;
+  headTicketId: BN;
+  unwoundTicketId: BN;
+  lockedLamports: BN;
+}
+
+// 提款工单数据
+export interface WithdrawalTicketData {
+  owner: PublicKey;
+  strategy: PublicKey;
+  ticketId: BN;
+  amountLamports: BN;
+  requestedAt: BN;
+  unlocksAt: BN;
+  bump: number;
+}
+
+// 代理人的授权范围，代理人不能存取资金
+export interface DelegateScope {
+  execute: boolean;
+  rebalance: boolean;
+  update: boolean;
+  maxWeightChangeBps: number;
+  maxSlippageBps: number;
+}
+
+// 策略代理人数据
+export interface StrategyDelegateData {
+  owner: PublicKey;
+  strategy: PublicKey;
+  delegate: PublicKey;
+  scope: DelegateScope;
+  expiresAt: BN;
+  // 授权时各分配项的权重(基点)，代理人每次更新的权重变化都相对该基准计算
+  baselineWeightsBps: number[];
+  bump: number;
+}
+
+// 提款白名单地址数据
+export interface WithdrawalDestinationData {
+  owner: PublicKey;
+  destination: PublicKey;
+  addedAt: BN;
+  activatesAt: BN;
+  bump: number;
+}
+
+// 策略模板数据
+export interface StrategyTemplateData {
+  publisher: PublicKey;
+  templateId: BN;
+  allocations: Allocation[];
+  rebalanceCondition: RebalanceCondition;
+  maxSlippageBps: number;
+  // 用户的风险等级不低于该值才能使用
+  riskLevel: number;
+  // 链下策略说明的哈希
+  descriptionHash: number[];
+  createdAt: BN;
+  strategiesCreated: BN;
+  bump: number;
+}
+
+// 跟投关系数据
+export interface StrategyFollowData {
+  owner: PublicKey;
+  strategy: PublicKey;
+  // 领投策略的原始地址，领投策略迁移后不变
+  leader: PublicKey;
+  // 同步时单个分配项的最大权重(基点)
+  maxWeightBps: number;
+  // 领投策略调整分配项后需等待的时长(秒)
+  delaySeconds: number;
+  // 按跟随策略价值和时间计提、同步时支付给领投者的年费率(基点)
+  leaderFeeBps: number;
+  followedAt: BN;
+  leaderSyncedAt: BN;
+  syncedAt: BN;
+  syncs: BN;
+  bump: number;
+}
+
+// 定投参数。价格上限为目标资产每单位的报价价格(报价精度)，0表示不限制
+export interface DcaParams {
+  quoteProtocol: number;
+  quoteAsset: number;
+  targetProtocol: number;
+  targetAsset: number;
+  // 每次从报价分配项转移到目标分配项的价值(lamports)
+  incrementLamports: BN;
+  maxPriceQuote: BN;
+}
+
+// 定投计划数据
+export interface DcaScheduleData {
+  owner: PublicKey;
+  strategy: PublicKey;
+  params: DcaParams;
+  createdAt: BN;
+  purchasedLamports: BN;
+  purchases: BN;
+  // 不足1基点而留到下次执行的增量(lamports)
+  carriedLamports: BN;
+  bump: number;
+}
+
+// 保护单参数，三个触发条件至少启用一个，0表示不启用
+export interface ProtectionParams {
+  stopLossLamports: BN;
+  maxDrawdownBps: number;
+  takeProfitLamports: BN;
+  // 触发后全部权重转入的安全头寸
+  safeProtocol: number;
+  safeAsset: number;
+}
+
+// 保护单上次估值时分配项的价格(报价精度)
+export interface PriceMark {
+  protocol: number;
+  asset: number;
+  priceQuote: BN;
+}
+
+// 保护单数据
+export interface ProtectiveOrderData {
+  owner: PublicKey;
+  strategy: PublicKey;
+  params: ProtectionParams;
+  markedValueLamports: BN;
+  markedPrincipalLamports: BN;
+  marks: PriceMark[];
+  markedAt: BN;
+  createdAt: BN;
+  bump: number;
+}
+
+// 保护单的触发原因
+export type ProtectionTrigger = { stopLoss: {} } | { maxDrawdown: {} } | { takeProfit: {} };
+
+// 收益率轮动参数
+export interface YieldParams {
+  // 轮动头寸的资产
+  asset: number;
+  // 候选借贷协议的位掩码，第i位对应协议i，至少两个
+  candidateProtocols: number;
+  // 新协议的APY至少比当前协议高出的基点数
+  minApyAdvantageBps: number;
+  // 在当前协议的最短持有时间(秒)
+  minHoldSeconds: number;
+}
+
+// 收益率轮动计划数据
+export interface YieldRotationData {
+  owner: PublicKey;
+  strategy: PublicKey;
+  params: YieldParams;
+  heldSince: BN;
+  rotations: BN;
+  createdAt: BN;
+  bump: number;
 }
 
 // 头寸追踪数据
@@ -63,6 +277,53 @@ export interface PositionTrackerData {
   lastUpdatedAt: BN;
 }
 
+// 预言机校验参数
+export interface OracleGuardConfig {
+  maxPriceAgeSeconds: number;
+  maxConfidenceBps: number;
+  maxDivergenceBps: number;
+}
+
+// 管理费和业绩费费率
+export interface FeeConfig {
+  managementFeeBps: number;
+  // 只用于链下回测，链上必须为0
+  performanceFeeBps: number;
+}
+
+// 存款限制，上限为0表示不限制
+export interface DepositLimits {
+  minDepositLamports: BN;
+  maxStrategyValueLamports: BN;
+  maxUserValueLamports: BN;
+}
+
+// 全局配置数据
+export interface GlobalConfigData {
+  admin: PublicKey;
+  oracleGuard: OracleGuardConfig;
+  fees: FeeConfig;
+  treasury: PublicKey;
+  depositLimits: DepositLimits;
+  withdrawalDelaySeconds: number;
+  allowlistDelaySeconds: number;
+  bump: number;
+}
+
+// 资产注册表数据
+export interface AssetRegistryData {
+  assetId: number;
+  mint: PublicKey;
+  decimals: number;
+  oracle: PublicKey;
+  secondaryOracle: PublicKey;
+  enabled: boolean;
+  maxAllocationBps: number;
+  depositCapLamports: BN;
+  depositedLamports: BN;
+  bump: number;
+}
+
 // 策略创建参数
 export interface CreateStrategyParams {
   allocations: Allocation[];
@@ -70,9 +331,66 @@ export interface CreateStrategyParams {
   maxSlippageBps: number;
 }
 
+// 发布策略模板参数
+export interface PublishTemplateParams extends CreateStrategyParams {
+  // 发布者自选的模板ID
+  templateId: number;
+  // 声明的风险等级(1-5)，低于按分配项和资产权重上限计算的等级时以计算结果为准
+  riskLevel: number;
+  // 链下策略说明的32字节哈希
+  descriptionHash: number[];
+}
+
+// 策略更新参数
+export interface UpdateStrategyParams extends CreateStrategyParams {
+  strategyId: number;
+  // 以代理人身份操作时指定策略所有者，默认为当前钱包
+  owner?: PublicKey;
+}
+
 // 策略执行参数
 export interface ExecuteStrategyParams {
   strategyId: number;
+  // 以代理人身份操作时指定策略所有者，默认为当前钱包
+  owner?: PublicKey;
+}
+
+// 添加代理人参数
+export interface AddDelegateParams {
+  strategyId: number;
+  delegate: PublicKey;
+  scope: DelegateScope;
+  // 授权到期的Unix时间戳(秒)
+  expiresAt: number;
+}
+
+// 跟随领投策略参数
+export interface FollowStrategyParams {
+  strategyId: number;
+  // 领投策略所有者和策略ID
+  leaderOwner: PublicKey;
+  leaderStrategyId: number;
+  maxWeightBps: number;
+  delaySeconds: number;
+  leaderFeeBps: number;
+}
+
+// 创建定投计划参数
+export interface CreateDcaParams {
+  strategyId: number;
+  params: DcaParams;
+}
+
+// 设置保护单参数
+export interface SetProtectionParams {
+  strategyId: number;
+  params: ProtectionParams;
+}
+
+// 启用收益率轮动参数
+export interface EnableYieldRotationParams {
+  strategyId: number;
+  params: YieldParams;
 }
 
 // 资金存取参数
@@ -80,3 +398,28 @@ export interface FundsOperationParams {
   strategyId: number;
   amount: number;
 }
+
+// 提款工单操作参数
+export interface WithdrawalTicketParams {
+  strategyId: number;
+  ticketId: number;
+}
+
+// 提款和领取工单参数
+export interface WithdrawParams {
+  // 接收地址，默认为当前钱包；启用白名单时必须是已生效的白名单地址
+  destination?: PublicKey;
+  // 联署的监护人，提供时不受周期提款限额限制
+  guardian?: Keypair;
+}
+
+// 设置周期提款限额参数
+export interface SetSpendingLimitParams {
+  // 每个窗口最多转出的lamports，0表示不限制
+  maxLamports: number;
+  windowSeconds: number;
+  // 新的监护人，默认公钥表示不设置，只能在不限制时使用
+  guardian: PublicKey;
+  // 当前监护人，放宽限额或更换监护人时需要联署
+  currentGuardian?: Keypair;
+}
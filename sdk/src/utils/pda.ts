@@ -4,6 +4,26 @@ import BN from 'bn.js';
 // 程序ID：应该与您的部署ID匹配
 export const PROGRAM_ID = new PublicKey('CdH2ymLMr7RyYcd1nyDZm59DRv6JgrtzuAxoH7STFvnm');
 
+/**
+ * 查找全局配置PDA
+ * @returns [PDA, bump]
+ */
+export function findConfigPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('config')], PROGRAM_ID);
+}
+
+/**
+ * 查找资产注册表PDA
+ * @param assetId 资产ID
+ * @returns [PDA, bump]
+ */
+export function findAssetRegistryPDA(assetId: number): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('asset'), Buffer.from([assetId])],
+    PROGRAM_ID
+  );
+}
+
 /**
  * 查找用户配置PDA
  * @param owner 用户钱包地址
@@ -16,6 +36,18 @@ export function findUserProfilePDA(owner: PublicKey): [PublicKey, number] {
   );
 }
 
+/**
+ * 查找用户资金库PDA
+ * @param owner 用户钱包地址
+ * @returns [PDA, bump]
+ */
+export function findVaultPDA(owner: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('vault'), owner.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
 /**
  * 查找策略配置PDA
  * @param userProfilePda 用户配置PDA
@@ -33,6 +65,127 @@ export function findStrategyPDA(userProfilePda: PublicKey, strategyCounter: numb
   );
 }
 
+/**
+ * 查找提款工单PDA
+ * @param strategyPda 策略配置PDA
+ * @param ticketId 工单ID
+ * @returns [PDA, bump]
+ */
+export function findWithdrawalTicketPDA(strategyPda: PublicKey, ticketId: number): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('ticket'),
+      strategyPda.toBuffer(),
+      new BN(ticketId).toArrayLike(Buffer, 'le', 8)
+    ],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找策略代理人PDA
+ * @param strategyPda 策略配置PDA
+ * @param delegate 代理人地址
+ * @returns [PDA, bump]
+ */
+export function findStrategyDelegatePDA(strategyPda: PublicKey, delegate: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('delegate'), strategyPda.toBuffer(), delegate.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找提款白名单地址PDA
+ * @param owner 用户地址
+ * @param destination 接收地址
+ * @returns [PDA, bump]
+ */
+export function findWithdrawalDestinationPDA(owner: PublicKey, destination: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('destination'), owner.toBuffer(), destination.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找策略模板PDA
+ * @param publisher 发布者地址
+ * @param templateId 模板ID
+ * @returns [PDA, bump]
+ */
+export function findStrategyTemplatePDA(publisher: PublicKey, templateId: number): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('template'),
+      publisher.toBuffer(),
+      new BN(templateId).toArrayLike(Buffer, 'le', 8)
+    ],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找跟投关系PDA，每个跟随策略最多跟随一个领投策略
+ * @param strategy 跟随策略地址
+ * @returns [PDA, bump]
+ */
+export function findStrategyFollowPDA(strategy: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('follow'), strategy.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找定投计划PDA，每个策略最多一个定投计划
+ * @param strategy 策略地址
+ * @returns [PDA, bump]
+ */
+export function findDcaSchedulePDA(strategy: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('dca'), strategy.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找保护单PDA，每个策略最多一个保护单
+ * @param strategy 策略地址
+ * @returns [PDA, bump]
+ */
+export function findProtectiveOrderPDA(strategy: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('protect'), strategy.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找收益率轮动计划PDA，每个策略最多一个轮动计划
+ * @param strategy 策略地址
+ * @returns [PDA, bump]
+ */
+export function findYieldRotationPDA(strategy: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('yield'), strategy.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+/**
+ * 查找按协议和资产记录市场利率的追踪PDA，账户类型为 PositionTracker
+ * @param protocol 协议编号
+ * @param asset 资产ID
+ * @returns [PDA, bump]
+ */
+export function findApyTrackerPDA(protocol: number, asset: number): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('apy'), Buffer.from([protocol]), Buffer.from([asset])],
+    PROGRAM_ID
+  );
+}
+
 /**
  * 查找头寸追踪PDA
  * @param strategyId 策略ID